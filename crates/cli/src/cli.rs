@@ -14,13 +14,50 @@ pub enum CliRequest {
         paths: Vec<String>,
         urls: Vec<String>,
         diff_paths: Vec<[String; 2]>,
+        /// `[BASE, LOCAL, REMOTE, MERGED]` paths of a three-way merge to resolve.
+        merge_paths: Option<[String; 4]>,
         wsl: Option<String>,
+        remote_command: Option<String>,
         wait: bool,
         open_new_workspace: Option<bool>,
         reuse: bool,
         env: Option<HashMap<String, String>>,
         user_data_dir: Option<String>,
     },
+    /// Dispatches the action with the given name in the focused workspace window.
+    DispatchAction {
+        name: String,
+        /// The action's arguments, as JSON.
+        arguments: Option<String>,
+    },
+    /// Responds with a JSON description of the running instance on stdout.
+    Query { query: CliQuery },
+    /// Opens `path` in an existing workspace, preferring one that contains it,
+    /// and selects from `start` to `end` (or places a cursor at `start`).
+    SetSelection {
+        path: String,
+        start: CliPosition,
+        end: Option<CliPosition>,
+    },
+    /// Runs the task with the given label in the focused workspace window.
+    RunTask { label: String },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CliQuery {
+    /// Open workspaces, along with their root paths and open buffers.
+    Workspaces,
+    /// The selections of the active editor.
+    Selection,
+    /// The diagnostics of the focused workspace.
+    Diagnostics,
+}
+
+/// A one-based position in a file, as written in `path:row:column`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliPosition {
+    pub row: u32,
+    pub column: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
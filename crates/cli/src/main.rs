@@ -9,7 +9,7 @@
 
 use anyhow::{Context as _, Result};
 use clap::Parser;
-use cli::{CliRequest, CliResponse, IpcHandshake, ipc::IpcOneShotServer};
+use cli::{CliPosition, CliQuery, CliRequest, CliResponse, IpcHandshake, ipc::IpcOneShotServer};
 use parking_lot::Mutex;
 use std::{
     env,
@@ -45,6 +45,9 @@ trait InstalledApp {
 #[command(
     name = "zed",
     disable_version_flag = true,
+    group = clap::ArgGroup::new("control")
+        .multiple(false)
+        .conflicts_with_all(["paths_with_position", "diff", "merge", "wait"]),
     before_help = "The Zed CLI binary.
 This CLI is a separate binary that invokes Zed.
 
@@ -110,6 +113,12 @@ struct Args {
     #[cfg(target_os = "windows")]
     #[arg(long, value_name = "USER@DISTRO")]
     wsl: Option<String>,
+    /// A command that runs `{cmd}` on a remote host with its stdio bridged to Zed.
+    /// The given paths are opened on that host.
+    ///
+    /// Example: `kubectl exec -i my-pod -- {cmd}`.
+    #[arg(long, value_name = "TEMPLATE")]
+    remote_command: Option<String>,
     /// Not supported in Zed CLI, only supported on Zed binary
     /// Will attempt to give the correct command to run
     #[arg(long)]
@@ -117,6 +126,27 @@ struct Args {
     /// Pairs of file paths to diff. Can be specified multiple times.
     #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
     diff: Vec<String>,
+    /// Resolve a three-way merge, for use as git's `merge.tool`. Implies `--wait`.
+    ///
+    /// Zed exits with a non-zero status if MERGED still contains conflict markers once closed.
+    #[arg(long, num_args = 4, value_names = ["BASE", "LOCAL", "REMOTE", "MERGED"])]
+    merge: Option<Vec<String>>,
+    /// Dispatch an action in the focused Zed window, with optional JSON arguments.
+    ///
+    /// Example: `zed --action editor::SelectAll` or `zed --action workspace::SendKeystrokes '"cmd-s"'`.
+    #[arg(long, group = "control", num_args = 1..=2, value_names = ["NAME", "JSON"])]
+    action: Option<Vec<String>>,
+    /// Print information about the running Zed instance as JSON.
+    #[arg(long, group = "control", value_enum, value_name = "WHAT")]
+    query: Option<QueryArg>,
+    /// Open a file in an existing Zed window and select a range in it.
+    ///
+    /// Example: `zed --select src/main.rs:10:5` or `zed --select src/main.rs:10:5-12:1`.
+    #[arg(long, group = "control", value_name = "PATH:ROW[:COL][-ROW[:COL]]")]
+    select: Option<String>,
+    /// Run the task with the given label in the focused Zed window.
+    #[arg(long, group = "control", value_name = "LABEL")]
+    run_task: Option<String>,
     /// Uninstall Zed from user system
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
@@ -131,6 +161,80 @@ struct Args {
     askpass: Option<String>,
 }
 
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum QueryArg {
+    /// Open workspaces, along with their root paths and open buffers
+    Workspaces,
+    /// The selections of the active editor
+    Selection,
+    /// The diagnostics of the focused workspace
+    Diagnostics,
+}
+
+impl From<QueryArg> for CliQuery {
+    fn from(query: QueryArg) -> Self {
+        match query {
+            QueryArg::Workspaces => CliQuery::Workspaces,
+            QueryArg::Selection => CliQuery::Selection,
+            QueryArg::Diagnostics => CliQuery::Diagnostics,
+        }
+    }
+}
+
+/// Builds the request for the flags that control a running instance rather
+/// than open paths, if any of them was given.
+fn control_request(args: &Args) -> anyhow::Result<Option<CliRequest>> {
+    if let Some(action) = &args.action {
+        return Ok(Some(CliRequest::DispatchAction {
+            name: action[0].clone(),
+            arguments: action.get(1).cloned(),
+        }));
+    }
+    if let Some(query) = args.query {
+        return Ok(Some(CliRequest::Query {
+            query: query.into(),
+        }));
+    }
+    if let Some(selection) = &args.select {
+        return parse_selection(selection).map(Some);
+    }
+    if let Some(label) = &args.run_task {
+        return Ok(Some(CliRequest::RunTask {
+            label: label.clone(),
+        }));
+    }
+    Ok(None)
+}
+
+/// Parses a `path:row[:column][-row[:column]]` argument into a selection request.
+fn parse_selection(argument: &str) -> anyhow::Result<CliRequest> {
+    let (start, end) = argument
+        .rsplit_once('-')
+        .filter(|(start, _)| PathWithPosition::parse_str(start).row.is_some())
+        .and_then(|(start, end)| Some((start, Some(parse_position(end).ok()?))))
+        .unwrap_or((argument, None));
+    let start = PathWithPosition::parse_str(start);
+    let row = start
+        .row
+        .with_context(|| format!("expected `path:row[:column]`, got {argument:?}"))?;
+    Ok(CliRequest::SetSelection {
+        path: parse_path_with_position(&start.path.to_string_lossy())?,
+        start: CliPosition {
+            row,
+            column: start.column.unwrap_or(1),
+        },
+        end,
+    })
+}
+
+fn parse_position(position: &str) -> anyhow::Result<CliPosition> {
+    let (row, column) = position.split_once(':').unwrap_or((position, "1"));
+    Ok(CliPosition {
+        row: row.parse()?,
+        column: column.parse()?,
+    })
+}
+
 /// Parses a path containing a position (e.g. `path:line:column`)
 /// and returns its canonicalized string representation.
 ///
@@ -227,6 +331,31 @@ mod tests {
         assert_path_eq!(result, expected)
     }
 
+    #[test]
+    fn test_parse_selection() {
+        let parse = |argument: &str| match parse_selection(argument).unwrap() {
+            CliRequest::SetSelection { path, start, end } => (path, start, end),
+            request => panic!("unexpected request {request:?}"),
+        };
+        let position = |row, column| CliPosition { row, column };
+
+        let (path, start, end) = parse(path!("/non/existing/my-file.rs:10"));
+        assert_path_eq!(path, path!("/non/existing/my-file.rs"));
+        assert_eq!(start, position(10, 1));
+        assert_eq!(end, None);
+
+        let (path, start, end) = parse(path!("/non/existing/my-file.rs:10:5-12"));
+        assert_path_eq!(path, path!("/non/existing/my-file.rs"));
+        assert_eq!(start, position(10, 5));
+        assert_eq!(end, Some(position(12, 1)));
+
+        let (_, start, end) = parse(path!("/non/existing/my-file.rs:10:5-12:3"));
+        assert_eq!(start, position(10, 5));
+        assert_eq!(end, Some(position(12, 3)));
+
+        assert!(parse_selection(path!("/non/existing/my-file.rs")).is_err());
+    }
+
     #[test]
     fn test_parse_existing_path() {
         let temp_tree = TempTree::new(json!({
@@ -345,6 +474,43 @@ fn parse_path_in_wsl(source: &str, wsl: &str) -> Result<String> {
     Ok(source.to_string(|path| path.to_string_lossy().into_owned()))
 }
 
+/// Resolves a path on the host that the remote command template runs commands on,
+/// since that's where relative paths and `..` components have to be resolved.
+fn parse_path_on_remote(source: &str, template: &str) -> Result<String> {
+    let mut source = PathWithPosition::parse_str(source);
+    let path = source.path.to_string_lossy().into_owned();
+    let command = ["realpath", "-s", "--", path.as_str()];
+
+    let mut argv = Vec::new();
+    for token in
+        shlex::split(template).with_context(|| format!("invalid command template: {template:?}"))?
+    {
+        if token == "{cmd}" {
+            argv.extend(command.map(ToOwned::to_owned));
+        } else if token.contains("{cmd}") {
+            let command_line = shlex::try_join(command).context("failed to quote command")?;
+            argv.push(token.replace("{cmd}", &command_line));
+        } else {
+            argv.push(token);
+        }
+    }
+    let (program, args) = argv.split_first().context("command template is empty")?;
+
+    let output = util::command::new_std_command(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .with_context(|| format!("running {template:?}"))?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to resolve {path:?} on the remote host: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    source.path = Path::new(String::from_utf8_lossy(&output.stdout).trim()).to_owned();
+
+    Ok(source.to_string(|path| path.to_string_lossy().into_owned()))
+}
+
 fn main() -> Result<()> {
     #[cfg(unix)]
     util::prevent_root_execution();
@@ -481,6 +647,18 @@ fn main() -> Result<()> {
         ]);
     }
 
+    let control_request = control_request(&args)?;
+
+    let merge_paths = match &args.merge {
+        Some(merge) => Some([
+            parse_path_with_position(&merge[0])?,
+            parse_path_with_position(&merge[1])?,
+            parse_path_with_position(&merge[2])?,
+            parse_path_with_position(&merge[3])?,
+        ]),
+        None => None,
+    };
+
     #[cfg(target_os = "windows")]
     let wsl = args.wsl.as_ref();
     #[cfg(not(target_os = "windows"))]
@@ -499,6 +677,11 @@ fn main() -> Result<()> {
             paths.push(tmp_file.path().to_string_lossy().into_owned());
             let (tmp_file, _) = tmp_file.keep()?;
             anonymous_fd_tmp_files.push((file, tmp_file));
+        } else if let Some(remote_command) = &args.remote_command {
+            urls.push(format!(
+                "file://{}",
+                parse_path_on_remote(path, remote_command)?
+            ));
         } else if let Some(wsl) = wsl {
             urls.push(format!("file://{}", parse_path_in_wsl(path, wsl)?));
         } else {
@@ -532,17 +715,20 @@ fn main() -> Result<()> {
                 #[cfg(not(target_os = "windows"))]
                 let wsl = None;
 
-                tx.send(CliRequest::Open {
+                let request = control_request.unwrap_or_else(|| CliRequest::Open {
                     paths,
                     urls,
                     diff_paths,
+                    wait: args.wait || merge_paths.is_some(),
+                    merge_paths,
                     wsl,
-                    wait: args.wait,
+                    remote_command: args.remote_command,
                     open_new_workspace,
                     reuse: args.reuse,
                     env,
                     user_data_dir: user_data_dir_for_thread,
-                })?;
+                });
+                tx.send(request)?;
 
                 while let Ok(response) = rx.recv() {
                     match response {
@@ -0,0 +1,375 @@
+use std::time::SystemTime;
+
+use crashes::{CrashReport, symbolicate::SymbolicatedFrame};
+use gpui::{
+    App, ClipboardItem, EventEmitter, FocusHandle, Focusable, Task, WeakEntity, prelude::*,
+};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{ListItem, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Toast, Workspace,
+    item::{Item, ItemEvent},
+    notifications::NotificationId,
+};
+use zed_actions::OpenCrashReports;
+
+/// The number of log lines shown from before a crash.
+const LOG_LINE_COUNT: usize = 50;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &OpenCrashReports, window, cx| {
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items()
+                .find_map(|item| item.downcast::<CrashReportsView>());
+
+            if let Some(existing) = existing {
+                existing.update(cx, |view, cx| view.reload(cx));
+                workspace.activate_item(&existing, true, true, window, cx);
+            } else {
+                let weak_workspace = cx.entity().downgrade();
+                let view = cx.new(|cx| CrashReportsView::new(weak_workspace, cx));
+                workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// Lists the crashes recorded in the logs directory, showing the symbolicated
+/// stack and the log lines leading up to the selected one.
+pub struct CrashReportsView {
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    reports: Vec<CrashReport>,
+    selected_index: Option<usize>,
+    details: Option<CrashDetails>,
+    error: Option<SharedString>,
+    _load_task: Task<()>,
+}
+
+/// The parts of a crash report that take a while to compute.
+struct CrashDetails {
+    crash_reason: Option<String>,
+    frames: Vec<SymbolicatedFrame>,
+    minidump_error: Option<String>,
+    log_lines: Vec<String>,
+}
+
+impl CrashReportsView {
+    fn new(workspace: WeakEntity<Workspace>, cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            workspace,
+            focus_handle: cx.focus_handle(),
+            reports: Vec::new(),
+            selected_index: None,
+            details: None,
+            error: None,
+            _load_task: Task::ready(()),
+        };
+        this.reload(cx);
+        this
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self._load_task = cx.spawn(async move |this, cx| {
+            let reports = cx
+                .background_spawn(async move { CrashReport::list(paths::logs_dir()) })
+                .await;
+            this.update(cx, |this, cx| {
+                match reports {
+                    Ok(reports) => {
+                        this.reports = reports;
+                        this.error = None;
+                    }
+                    Err(error) => {
+                        this.reports.clear();
+                        this.error = Some(format!("Failed to read crash reports: {error}").into());
+                    }
+                }
+                this.selected_index = None;
+                this.details = None;
+                if !this.reports.is_empty() {
+                    this.select(0, cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn select(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(report) = self.reports.get(index).cloned() else {
+            return;
+        };
+        self.selected_index = Some(index);
+        self.details = None;
+        self._load_task = cx.spawn(async move |this, cx| {
+            let details = cx
+                .background_spawn(async move { load_details(&report) })
+                .await;
+            this.update(cx, |this, cx| {
+                if this.selected_index == Some(index) {
+                    this.details = Some(details);
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    fn copy_redacted_report(
+        &mut self,
+        _: &gpui::ClickEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(report), Some(details)) = (
+            self.selected_index
+                .and_then(|index| self.reports.get(index)),
+            self.details.as_ref(),
+        ) else {
+            return;
+        };
+        let text = crashes::format_report(
+            report,
+            details.crash_reason.as_deref(),
+            &details.frames,
+            &details.log_lines,
+        );
+        let text = crashes::redact(&text, paths::home_dir());
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+
+        struct CopiedCrashReport;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<CopiedCrashReport>(),
+                        "Copied crash report to the clipboard",
+                    )
+                    .autohide(),
+                    cx,
+                );
+            })
+            .log_err();
+    }
+
+    fn render_report_list(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        v_flex()
+            .id("crash-report-list")
+            .w_80()
+            .h_full()
+            .flex_none()
+            .overflow_y_scroll()
+            .p_1()
+            .border_r_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(self.reports.iter().enumerate().map(|(index, report)| {
+                ListItem::new(index)
+                    .toggle_state(self.selected_index == Some(index))
+                    .on_click(cx.listener(move |this, _, _, cx| this.select(index, cx)))
+                    .child(
+                        v_flex()
+                            .overflow_hidden()
+                            .child(Label::new(report.summary()).truncate())
+                            .child(
+                                Label::new(format_time(report.time, now))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+            }))
+    }
+
+    fn render_details(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = v_flex().id("crash-report-details").flex_1().h_full();
+        let Some(report) = self
+            .selected_index
+            .and_then(|index| self.reports.get(index))
+        else {
+            return content;
+        };
+
+        let mut content = content.overflow_y_scroll().p_4().gap_4().child(
+            h_flex()
+                .justify_between()
+                .child(Headline::new(report.summary()).size(HeadlineSize::Small))
+                .child(
+                    Button::new("copy-redacted-report", "Copy Redacted Report")
+                        .icon(IconName::Copy)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::Small)
+                        .disabled(self.details.is_none())
+                        .on_click(cx.listener(Self::copy_redacted_report)),
+                ),
+        );
+
+        if let Some(info) = &report.info {
+            let init = &info.init;
+            content = content.child(
+                Label::new(format!(
+                    "Zed {} ({}, {})",
+                    init.zed_version, init.release_channel, init.commit_sha
+                ))
+                .color(Color::Muted),
+            );
+            if let Some(panic) = &info.panic {
+                content = content.child(section(
+                    format!("Panic at {}", panic.span),
+                    vec![panic.message.clone()],
+                    cx,
+                ));
+            }
+        }
+
+        let Some(details) = &self.details else {
+            return content.child(Label::new("Symbolicating…").color(Color::Muted));
+        };
+
+        if let Some(reason) = &details.crash_reason {
+            content = content.child(section("Reason", vec![reason.clone()], cx));
+        }
+        if let Some(error) = &details.minidump_error {
+            content = content.child(Label::new(error.clone()).color(Color::Error));
+        }
+        if !details.frames.is_empty() {
+            let frames = details
+                .frames
+                .iter()
+                .enumerate()
+                .map(|(index, frame)| format!("{index:>3}: {frame}"))
+                .collect();
+            content = content.child(section("Stack", frames, cx));
+        }
+        if !details.log_lines.is_empty() {
+            content = content.child(section(
+                "Log Before the Crash",
+                details.log_lines.clone(),
+                cx,
+            ));
+        }
+        content
+    }
+}
+
+fn load_details(report: &CrashReport) -> CrashDetails {
+    let (crash_reason, frames, minidump_error) = match report.load_minidump() {
+        Ok(minidump) => (
+            minidump.crash_reason(),
+            crashes::symbolicate::symbolicate(&minidump),
+            None,
+        ),
+        Err(error) => (
+            None,
+            Vec::new(),
+            Some(format!("Failed to read the minidump: {error}")),
+        ),
+    };
+
+    // The log is rotated once it grows too large, so the lines before the
+    // crash may be split across both files.
+    let mut log = std::fs::read_to_string(paths::old_log_file()).unwrap_or_default();
+    log.push_str(&std::fs::read_to_string(paths::log_file()).unwrap_or_default());
+    let log_lines = crashes::log_lines_before(&log, report.time, LOG_LINE_COUNT);
+
+    CrashDetails {
+        crash_reason,
+        frames,
+        minidump_error,
+        log_lines,
+    }
+}
+
+fn section(title: impl Into<SharedString>, lines: Vec<String>, cx: &App) -> impl IntoElement {
+    v_flex()
+        .gap_1()
+        .child(
+            Label::new(title.into())
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+        )
+        .child(
+            v_flex()
+                .p_2()
+                .rounded_sm()
+                .bg(cx.theme().colors().editor_background)
+                .font_buffer(cx)
+                .text_buffer(cx)
+                .children(lines.into_iter().map(|line| div().child(line))),
+        )
+}
+
+fn format_time(time: SystemTime, now: OffsetDateTime) -> String {
+    time_format::format_local_timestamp(
+        OffsetDateTime::from(time),
+        now,
+        TimestampFormat::EnhancedAbsolute,
+    )
+}
+
+impl EventEmitter<ItemEvent> for CrashReportsView {}
+
+impl Focusable for CrashReportsView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for CrashReportsView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Crash Reports".into()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for CrashReportsView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body = if let Some(error) = &self.error {
+            h_flex()
+                .size_full()
+                .justify_center()
+                .child(Label::new(error.clone()).color(Color::Error))
+                .into_any_element()
+        } else if self.reports.is_empty() {
+            h_flex()
+                .size_full()
+                .justify_center()
+                .child(Label::new("No crashes have been recorded").color(Color::Muted))
+                .into_any_element()
+        } else {
+            h_flex()
+                .size_full()
+                .items_start()
+                .child(self.render_report_list(cx))
+                .child(self.render_details(cx))
+                .into_any_element()
+        };
+
+        div()
+            .key_context("CrashReports")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(body)
+    }
+}
@@ -0,0 +1,330 @@
+//! Reads the crash reports left in the logs directory, so that they can be
+//! viewed locally.
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Local};
+use std::{
+    ffi::OsStr,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{CrashInfo, minidump::Minidump, symbolicate::SymbolicatedFrame};
+
+/// How long before a crash log lines are considered to be related to it.
+const LOG_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// A crash that was recorded by the crash handler.
+#[derive(Clone, Debug)]
+pub struct CrashReport {
+    pub session_id: String,
+    pub time: SystemTime,
+    /// The panic message and versions recorded alongside the minidump, if
+    /// they were written.
+    pub info: Option<CrashInfo>,
+    pub minidump_path: Option<PathBuf>,
+}
+
+impl CrashReport {
+    /// Lists the crash reports in `dir`, most recent first.
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        let mut reports = Vec::<Self>::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let extension = path.extension().and_then(OsStr::to_str);
+            if !matches!(extension, Some("json" | "dmp")) {
+                continue;
+            }
+            let Some(session_id) = path.file_stem().and_then(OsStr::to_str) else {
+                continue;
+            };
+            let time = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+
+            let index = match reports
+                .iter()
+                .position(|report| report.session_id == session_id)
+            {
+                Some(index) => index,
+                None => {
+                    reports.push(Self {
+                        session_id: session_id.to_string(),
+                        time,
+                        info: None,
+                        minidump_path: None,
+                    });
+                    reports.len() - 1
+                }
+            };
+            let report = &mut reports[index];
+            report.time = report.time.max(time);
+            if extension == Some("dmp") {
+                report.minidump_path = Some(path);
+            } else {
+                // Other JSON files in the logs directory aren't crash reports.
+                match fs::read(&path)
+                    .ok()
+                    .and_then(|contents| serde_json::from_slice(&contents).ok())
+                {
+                    Some(info) => report.info = Some(info),
+                    None if report.minidump_path.is_none() => {
+                        reports.remove(index);
+                    }
+                    None => {}
+                }
+            }
+        }
+        reports.sort_by(|a, b| b.time.cmp(&a.time));
+        Ok(reports)
+    }
+
+    /// Reads the minidump, which the crash handler compresses once it's
+    /// written.
+    pub fn load_minidump(&self) -> Result<Minidump> {
+        let path = self
+            .minidump_path
+            .as_ref()
+            .context("no minidump was written for this crash")?;
+        let data = fs::read(path).with_context(|| format!("reading {path:?}"))?;
+        if data.starts_with(&ZSTD_MAGIC) {
+            let data = zstd::stream::decode_all(data.as_slice()).context("decompressing")?;
+            Minidump::parse(&data)
+        } else {
+            Minidump::parse(&data)
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match &self.info {
+            Some(CrashInfo {
+                panic: Some(panic), ..
+            }) => panic.message.lines().next().unwrap_or_default().to_string(),
+            Some(_) => "Crashed".to_string(),
+            None => "Crashed without recording details".to_string(),
+        }
+    }
+}
+
+/// Returns the log lines written in the minutes before `time`, including any
+/// lines that continue a multi-line message.
+pub fn log_lines_before(log: &str, time: SystemTime, max_lines: usize) -> Vec<String> {
+    let end = DateTime::<Local>::from(time);
+    let start = DateTime::<Local>::from(time - LOG_WINDOW);
+    let mut lines = Vec::<String>::new();
+    let mut in_window = false;
+    for line in log.lines() {
+        let timestamp = line
+            .split_once(' ')
+            .and_then(|(timestamp, _)| DateTime::parse_from_rfc3339(timestamp).ok());
+        match timestamp {
+            Some(timestamp) => {
+                if timestamp > end {
+                    break;
+                }
+                in_window = timestamp >= start;
+                if in_window {
+                    lines.push(line.to_string());
+                }
+            }
+            None if in_window => lines.push(line.to_string()),
+            None => {}
+        }
+    }
+    let skip = lines.len().saturating_sub(max_lines);
+    lines.drain(..skip);
+    lines
+}
+
+/// Removes details that identify the user from a crash report: their home
+/// directory and anything that looks like an email address.
+pub fn redact(text: &str, home_dir: &Path) -> String {
+    let home_dir = home_dir.to_string_lossy();
+    let text = if home_dir.len() > 1 {
+        text.replace(home_dir.as_ref(), "~")
+    } else {
+        text.to_string()
+    };
+
+    // Each piece ends with the whitespace that follows it, which is kept as is.
+    let mut redacted = String::with_capacity(text.len());
+    for piece in text.split_inclusive(char::is_whitespace) {
+        let word = piece.trim_end_matches(char::is_whitespace);
+        let separator = &piece[word.len()..];
+        // Punctuation around an address, such as brackets, is kept.
+        let is_punctuation = |c: char| !c.is_alphanumeric();
+        let address = word.trim_matches(is_punctuation);
+        if looks_like_email(address) {
+            let start = word.len() - word.trim_start_matches(is_punctuation).len();
+            redacted.push_str(&word[..start]);
+            redacted.push_str("<email>");
+            redacted.push_str(&word[start + address.len()..]);
+        } else {
+            redacted.push_str(word);
+        }
+        redacted.push_str(separator);
+    }
+    redacted
+}
+
+fn looks_like_email(word: &str) -> bool {
+    let Some((user, domain)) = word.split_once('@') else {
+        return false;
+    };
+    !user.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains('@')
+}
+
+/// Formats a crash report as text that can be shared.
+pub fn format_report(
+    report: &CrashReport,
+    crash_reason: Option<&str>,
+    frames: &[SymbolicatedFrame],
+    log_lines: &[String],
+) -> String {
+    let mut text = String::new();
+    if let Some(info) = &report.info {
+        let init = &info.init;
+        writeln!(
+            text,
+            "Zed {} ({}, {}) {}",
+            init.zed_version, init.release_channel, init.commit_sha, init.binary
+        )
+        .ok();
+    }
+    writeln!(
+        text,
+        "Crashed at {}",
+        DateTime::<Local>::from(report.time).to_rfc3339()
+    )
+    .ok();
+    if let Some(reason) = crash_reason {
+        writeln!(text, "Reason: {reason}").ok();
+    }
+    if let Some(panic) = report.info.as_ref().and_then(|info| info.panic.as_ref()) {
+        writeln!(text, "\nPanic at {}:\n{}", panic.span, panic.message).ok();
+    }
+    if let Some(gpu) = report
+        .info
+        .as_ref()
+        .and_then(|info| info.active_gpu.as_ref())
+    {
+        writeln!(text, "\nGPU: {} ({})", gpu.device_name, gpu.driver_name).ok();
+    }
+    if !frames.is_empty() {
+        text.push_str("\nStack:\n");
+        for (index, frame) in frames.iter().enumerate() {
+            writeln!(text, "{index:>3}: {frame}").ok();
+        }
+    }
+    if !log_lines.is_empty() {
+        text.push_str("\nLog:\n");
+        for line in log_lines {
+            writeln!(text, "{line}").ok();
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CrashPanic, InitCrashHandler};
+    use indoc::indoc;
+
+    #[test]
+    fn test_list_crash_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let info = CrashInfo {
+            init: InitCrashHandler {
+                session_id: "session-1".into(),
+                zed_version: "0.200.0".into(),
+                binary: "zed".into(),
+                release_channel: "Nightly".into(),
+                commit_sha: "abc123".into(),
+            },
+            panic: Some(CrashPanic {
+                message: "index out of bounds\nmore details".into(),
+                span: "crates/editor/src/editor.rs:1".into(),
+            }),
+            minidump_error: None,
+            gpus: Vec::new(),
+            active_gpu: None,
+        };
+        fs::write(
+            dir.path().join("session-1.json"),
+            serde_json::to_vec(&info).unwrap(),
+        )
+        .unwrap();
+        fs::write(dir.path().join("session-1.dmp"), b"").unwrap();
+        fs::write(dir.path().join("session-2.dmp"), b"").unwrap();
+        fs::write(dir.path().join("other.json"), b"{}").unwrap();
+        fs::write(dir.path().join("Zed.log"), b"").unwrap();
+
+        let mut reports = CrashReport::list(dir.path()).unwrap();
+        reports.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| (report.session_id.as_str(), report.summary()))
+                .collect::<Vec<_>>(),
+            [
+                ("session-1", "index out of bounds".to_string()),
+                ("session-2", "Crashed without recording details".to_string()),
+            ]
+        );
+        assert!(reports[1].load_minidump().is_err());
+    }
+
+    #[test]
+    fn test_log_lines_before() {
+        let log = indoc! {"
+            2024-05-01T09:00:00+00:00 INFO  [zed] too early
+            2024-05-01T09:58:00+00:00 INFO  [zed] opened workspace
+            2024-05-01T09:59:00+00:00 ERROR [editor] something failed
+            with a second line
+            2024-05-01T09:59:30+00:00 WARN  [gpui] slow frame
+            2024-05-01T10:05:00+00:00 INFO  [zed] restarted
+        "};
+        let time =
+            SystemTime::from(DateTime::parse_from_rfc3339("2024-05-01T10:00:00+00:00").unwrap());
+        assert_eq!(
+            log_lines_before(log, time, 10),
+            [
+                "2024-05-01T09:58:00+00:00 INFO  [zed] opened workspace",
+                "2024-05-01T09:59:00+00:00 ERROR [editor] something failed",
+                "with a second line",
+                "2024-05-01T09:59:30+00:00 WARN  [gpui] slow frame",
+            ]
+        );
+        assert_eq!(
+            log_lines_before(log, time, 1),
+            ["2024-05-01T09:59:30+00:00 WARN  [gpui] slow frame"]
+        );
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact(
+                "failed to open /home/alice/project/main.rs for alice@example.com (@mention)",
+                Path::new("/home/alice"),
+            ),
+            "failed to open ~/project/main.rs for <email> (@mention)"
+        );
+        assert_eq!(
+            redact(
+                "signed in as:\njohn@example.com\n\tcontact <jane.doe@example.org>, thanks",
+                Path::new("/"),
+            ),
+            "signed in as:\n<email>\n\tcontact <<email>>, thanks"
+        );
+    }
+}
@@ -24,6 +24,12 @@ use std::{
     time::Duration,
 };
 
+mod crash_report;
+pub mod minidump;
+pub mod symbolicate;
+
+pub use crash_report::{CrashReport, format_report, log_lines_before, redact};
+
 // set once the crash handler has initialized and the client has connected to it
 pub static CRASH_HANDLER: OnceLock<Arc<Client>> = OnceLock::new();
 // set when the first minidump request is made to avoid generating duplicate crash reports
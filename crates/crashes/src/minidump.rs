@@ -0,0 +1,561 @@
+//! A reader for the parts of a minidump that are needed to show a crash
+//! report locally: why the process crashed, the crashing thread's registers
+//! and stack memory, and the modules that were loaded.
+
+use anyhow::{Context as _, Result};
+use std::ops::Range;
+
+const SIGNATURE: u32 = u32::from_le_bytes(*b"MDMP");
+
+const THREAD_LIST_STREAM: u32 = 3;
+const MODULE_LIST_STREAM: u32 = 4;
+const EXCEPTION_STREAM: u32 = 6;
+const SYSTEM_INFO_STREAM: u32 = 7;
+
+const DIRECTORY_ENTRY_SIZE: usize = 12;
+const THREAD_SIZE: usize = 48;
+const MODULE_SIZE: usize = 108;
+
+/// CodeView record signatures.
+const CV_SIGNATURE_PDB70: u32 = u32::from_le_bytes(*b"RSDS");
+const CV_SIGNATURE_ELF: u32 = u32::from_le_bytes(*b"BpEL");
+
+const PROCESSOR_ARCHITECTURE_AMD64: u16 = 9;
+const PROCESSOR_ARCHITECTURE_ARM64: u16 = 12;
+const PROCESSOR_ARCHITECTURE_ARM64_OLD: u16 = 0x8003;
+
+/// The most return addresses to look for when scanning the stack.
+const MAX_SCANNED_FRAMES: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platform {
+    Windows,
+    MacOs,
+    Linux,
+    Other(u32),
+}
+
+impl Platform {
+    fn from_id(id: u32) -> Self {
+        match id {
+            2 => Self::Windows,
+            0x8101 => Self::MacOs,
+            0x8201 => Self::Linux,
+            id => Self::Other(id),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Architecture {
+    X86_64,
+    Arm64,
+    Other(u16),
+}
+
+/// Identifies the build of a module, read from its CodeView record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CodeId {
+    /// The GNU build ID of an ELF file.
+    BuildId(Vec<u8>),
+    /// The GUID of a PE file's PDB, or the UUID of a Mach-O file, as a
+    /// Windows GUID whose first three fields are little-endian.
+    Guid([u8; 16]),
+}
+
+impl CodeId {
+    /// Returns the GUID in the byte order of a UUID.
+    pub fn uuid(&self) -> Option<[u8; 16]> {
+        let Self::Guid(guid) = self else {
+            return None;
+        };
+        let mut uuid = *guid;
+        uuid[0..4].reverse();
+        uuid[4..6].reverse();
+        uuid[6..8].reverse();
+        Some(uuid)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Module {
+    pub base_address: u64,
+    pub size: u64,
+    /// The path the module was loaded from on the machine that crashed.
+    pub path: String,
+    pub code_id: Option<CodeId>,
+}
+
+impl Module {
+    pub fn address_range(&self) -> Range<u64> {
+        self.base_address..self.base_address.saturating_add(self.size)
+    }
+
+    pub fn file_name(&self) -> &str {
+        self.path
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(self.path.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Exception {
+    pub thread_id: u32,
+    pub code: u32,
+    pub address: u64,
+}
+
+/// The registers and stack memory of the thread that crashed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrashingThread {
+    pub instruction_pointer: u64,
+    pub stack_pointer: u64,
+    /// The return address register, on architectures that have one.
+    pub link_register: Option<u64>,
+    pub stack_start: u64,
+    pub stack: Vec<u8>,
+}
+
+/// How a stack frame's address was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameTrust {
+    /// Read from the crashing thread's instruction pointer.
+    InstructionPointer,
+    /// Read from the crashing thread's link register.
+    LinkRegister,
+    /// Found by scanning the stack for addresses inside a loaded module,
+    /// which can include stale return addresses.
+    Scan,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StackFrame {
+    pub address: u64,
+    pub trust: FrameTrust,
+}
+
+impl StackFrame {
+    /// The address to look up symbols for. Return addresses point at the
+    /// instruction after the call, so this points back into the call.
+    pub fn lookup_address(&self) -> u64 {
+        match self.trust {
+            FrameTrust::InstructionPointer => self.address,
+            FrameTrust::LinkRegister | FrameTrust::Scan => self.address.saturating_sub(1),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Minidump {
+    /// When the minidump was written, in seconds since the Unix epoch.
+    pub timestamp: u32,
+    pub platform: Platform,
+    pub architecture: Architecture,
+    pub exception: Option<Exception>,
+    pub crashing_thread: Option<CrashingThread>,
+    pub modules: Vec<Module>,
+}
+
+impl Minidump {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let data = Data(data);
+        anyhow::ensure!(data.u32(0)? == SIGNATURE, "not a minidump");
+        let stream_count = data.u32(8)? as usize;
+        let directory = data.u32(12)? as usize;
+        let timestamp = data.u32(20)?;
+
+        let mut minidump = Self {
+            timestamp,
+            platform: Platform::Other(0),
+            architecture: Architecture::Other(0),
+            exception: None,
+            crashing_thread: None,
+            modules: Vec::new(),
+        };
+
+        let mut thread_list = None;
+        let mut exception_context = None;
+        for index in 0..stream_count {
+            let entry = directory + index * DIRECTORY_ENTRY_SIZE;
+            let stream_type = data.u32(entry)?;
+            let rva = data.u32(entry + 8)? as usize;
+            match stream_type {
+                SYSTEM_INFO_STREAM => {
+                    minidump.architecture = match data.u16(rva)? {
+                        PROCESSOR_ARCHITECTURE_AMD64 => Architecture::X86_64,
+                        PROCESSOR_ARCHITECTURE_ARM64 | PROCESSOR_ARCHITECTURE_ARM64_OLD => {
+                            Architecture::Arm64
+                        }
+                        architecture => Architecture::Other(architecture),
+                    };
+                    minidump.platform = Platform::from_id(data.u32(rva + 20)?);
+                }
+                EXCEPTION_STREAM => {
+                    minidump.exception = Some(Exception {
+                        thread_id: data.u32(rva)?,
+                        code: data.u32(rva + 8)?,
+                        address: data.u64(rva + 24)?,
+                    });
+                    exception_context = Some(data.location(rva + 160)?);
+                }
+                MODULE_LIST_STREAM => {
+                    let count = data.u32(rva)? as usize;
+                    for index in 0..count {
+                        let module = rva + 4 + index * MODULE_SIZE;
+                        minidump.modules.push(Module {
+                            base_address: data.u64(module)?,
+                            size: data.u32(module + 8)? as u64,
+                            path: data.string(data.u32(module + 20)? as usize)?,
+                            code_id: data.code_id(data.location(module + 76)?)?,
+                        });
+                    }
+                }
+                THREAD_LIST_STREAM => thread_list = Some(rva),
+                _ => {}
+            }
+        }
+
+        if let (Some(exception), Some(context), Some(thread_list)) =
+            (&minidump.exception, exception_context, thread_list)
+        {
+            minidump.crashing_thread = data
+                .crashing_thread(
+                    thread_list,
+                    exception.thread_id,
+                    context,
+                    minidump.architecture,
+                )
+                .context("reading the crashing thread")?;
+        }
+
+        Ok(minidump)
+    }
+
+    pub fn module_for_address(&self, address: u64) -> Option<&Module> {
+        self.modules
+            .iter()
+            .find(|module| module.address_range().contains(&address))
+    }
+
+    /// Returns a readable description of why the process crashed.
+    pub fn crash_reason(&self) -> Option<String> {
+        let exception = self.exception.as_ref()?;
+        let name = match (self.platform, exception.code) {
+            (Platform::Linux, 4) => "SIGILL",
+            (Platform::Linux, 5) => "SIGTRAP",
+            (Platform::Linux, 6) => "SIGABRT",
+            (Platform::Linux, 7) => "SIGBUS",
+            (Platform::Linux, 8) => "SIGFPE",
+            (Platform::Linux, 11) => "SIGSEGV",
+            (Platform::MacOs, 1) => "EXC_BAD_ACCESS",
+            (Platform::MacOs, 2) => "EXC_BAD_INSTRUCTION",
+            (Platform::MacOs, 3) => "EXC_ARITHMETIC",
+            (Platform::MacOs, 6) => "EXC_BREAKPOINT",
+            (Platform::MacOs, 10) => "EXC_CRASH",
+            (Platform::Windows, 0xC000_0005) => "EXCEPTION_ACCESS_VIOLATION",
+            (Platform::Windows, 0xC000_001D) => "EXCEPTION_ILLEGAL_INSTRUCTION",
+            (Platform::Windows, 0xC000_00FD) => "EXCEPTION_STACK_OVERFLOW",
+            (Platform::Windows, 0xC000_0409) => "STATUS_STACK_BUFFER_OVERRUN",
+            (Platform::Windows, 0x0000_00EA) => "panic",
+            (_, code) => return Some(format!("exception {code:#x} at {:#x}", exception.address)),
+        };
+        Some(format!("{name} at {:#x}", exception.address))
+    }
+
+    /// Returns the crashing thread's stack frames, innermost first.
+    ///
+    /// Only the instruction pointer and link register are known to be frames;
+    /// the rest are found by scanning the stack for addresses inside loaded
+    /// modules.
+    pub fn stack_frames(&self) -> Vec<StackFrame> {
+        let Some(thread) = &self.crashing_thread else {
+            return Vec::new();
+        };
+
+        let mut frames = vec![StackFrame {
+            address: thread.instruction_pointer,
+            trust: FrameTrust::InstructionPointer,
+        }];
+        if let Some(link_register) = thread.link_register
+            && self.module_for_address(link_register).is_some()
+        {
+            frames.push(StackFrame {
+                address: link_register,
+                trust: FrameTrust::LinkRegister,
+            });
+        }
+
+        let start = thread
+            .stack_pointer
+            .checked_sub(thread.stack_start)
+            .map_or(0, |offset| offset as usize);
+        let Some(stack) = thread.stack.get(start..) else {
+            return frames;
+        };
+        let scanned = stack
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+            .filter(|address| self.module_for_address(*address).is_some())
+            .take(MAX_SCANNED_FRAMES)
+            .map(|address| StackFrame {
+                address,
+                trust: FrameTrust::Scan,
+            });
+        frames.extend(scanned);
+        frames
+    }
+}
+
+/// Little-endian reads from a minidump, which fail instead of panicking if
+/// the minidump is truncated.
+struct Data<'a>(&'a [u8]);
+
+impl Data<'_> {
+    fn bytes(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.0.get(offset..end))
+            .with_context(|| format!("minidump is truncated at offset {offset}"))
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.bytes(offset, 2)?.try_into()?))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(offset, 4)?.try_into()?))
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.bytes(offset, 8)?.try_into()?))
+    }
+
+    /// Reads a location descriptor, which is a size followed by an offset.
+    fn location(&self, offset: usize) -> Result<Range<usize>> {
+        let size = self.u32(offset)? as usize;
+        let rva = self.u32(offset + 4)? as usize;
+        Ok(rva..rva + size)
+    }
+
+    /// Reads a length-prefixed UTF-16 string.
+    fn string(&self, offset: usize) -> Result<String> {
+        let len = self.u32(offset)? as usize;
+        let units = self
+            .bytes(offset + 4, len)?
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect::<Vec<_>>();
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    /// Reads a module's CodeView record, if it has one in a known format.
+    fn code_id(&self, record: Range<usize>) -> Result<Option<CodeId>> {
+        if record.len() < 4 {
+            return Ok(None);
+        }
+        let code_id = match self.u32(record.start)? {
+            CV_SIGNATURE_PDB70 if record.len() >= 20 => {
+                Some(CodeId::Guid(self.bytes(record.start + 4, 16)?.try_into()?))
+            }
+            CV_SIGNATURE_ELF => Some(CodeId::BuildId(
+                self.bytes(record.start + 4, record.len() - 4)?.to_vec(),
+            )),
+            _ => None,
+        };
+        Ok(code_id)
+    }
+
+    fn crashing_thread(
+        &self,
+        thread_list: usize,
+        thread_id: u32,
+        context: Range<usize>,
+        architecture: Architecture,
+    ) -> Result<Option<CrashingThread>> {
+        let (instruction_pointer, stack_pointer, link_register) = match architecture {
+            Architecture::X86_64 => (
+                self.u64(context.start + 0xF8)?,
+                self.u64(context.start + 0x98)?,
+                None,
+            ),
+            Architecture::Arm64 => (
+                self.u64(context.start + 264)?,
+                self.u64(context.start + 256)?,
+                Some(self.u64(context.start + 248)?),
+            ),
+            Architecture::Other(_) => return Ok(None),
+        };
+
+        let count = self.u32(thread_list)? as usize;
+        for index in 0..count {
+            let thread = thread_list + 4 + index * THREAD_SIZE;
+            if self.u32(thread)? != thread_id {
+                continue;
+            }
+            let stack_start = self.u64(thread + 24)?;
+            let stack = self.location(thread + 32)?;
+            return Ok(Some(CrashingThread {
+                instruction_pointer,
+                stack_pointer,
+                link_register,
+                stack_start,
+                stack: self.bytes(stack.start, stack.len())?.to_vec(),
+            }));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Builds a minidump of an x86-64 Linux process with one module, whose
+    /// thread crashed with a SIGSEGV.
+    pub(crate) fn build_minidump() -> Vec<u8> {
+        let mut data = vec![0u8; 32];
+        let mut streams = Vec::new();
+
+        let push = |data: &mut Vec<u8>, bytes: &[u8]| {
+            let rva = data.len() as u32;
+            data.extend_from_slice(bytes);
+            rva
+        };
+
+        // System info: x86-64, Linux.
+        let mut system_info = vec![0u8; 56];
+        system_info[0..2].copy_from_slice(&9u16.to_le_bytes());
+        system_info[20..24].copy_from_slice(&0x8201u32.to_le_bytes());
+        let rva = push(&mut data, &system_info);
+        streams.push((SYSTEM_INFO_STREAM, system_info.len() as u32, rva));
+
+        // The module's name.
+        let name = "/usr/bin/zed".encode_utf16().collect::<Vec<_>>();
+        let mut name_bytes = ((name.len() * 2) as u32).to_le_bytes().to_vec();
+        name_bytes.extend(name.iter().flat_map(|unit| unit.to_le_bytes()));
+        let name_rva = push(&mut data, &name_bytes);
+
+        let mut code_view = b"BpEL".to_vec();
+        code_view.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        let code_view_rva = push(&mut data, &code_view);
+
+        let mut module_list = 1u32.to_le_bytes().to_vec();
+        let mut module = vec![0u8; MODULE_SIZE];
+        module[0..8].copy_from_slice(&0x1000u64.to_le_bytes());
+        module[8..12].copy_from_slice(&0x1000u32.to_le_bytes());
+        module[20..24].copy_from_slice(&name_rva.to_le_bytes());
+        module[76..80].copy_from_slice(&(code_view.len() as u32).to_le_bytes());
+        module[80..84].copy_from_slice(&code_view_rva.to_le_bytes());
+        module_list.extend(module);
+        let rva = push(&mut data, &module_list);
+        streams.push((MODULE_LIST_STREAM, module_list.len() as u32, rva));
+
+        // The crashing thread's registers: rsp and rip.
+        let mut context = vec![0u8; 0x100];
+        context[0x98..0xA0].copy_from_slice(&0x8008u64.to_le_bytes());
+        context[0xF8..0x100].copy_from_slice(&0x1010u64.to_le_bytes());
+        let context_rva = push(&mut data, &context);
+
+        // The stack, starting 8 bytes below the stack pointer. It contains a
+        // return address into the module and a value outside of it.
+        let mut stack = Vec::new();
+        for word in [0x1234u64, 0x1020, 0xdead_beef, 0x1abc] {
+            stack.extend_from_slice(&word.to_le_bytes());
+        }
+        let stack_rva = push(&mut data, &stack);
+
+        let mut thread_list = 1u32.to_le_bytes().to_vec();
+        let mut thread = vec![0u8; THREAD_SIZE];
+        thread[0..4].copy_from_slice(&42u32.to_le_bytes());
+        thread[24..32].copy_from_slice(&0x8000u64.to_le_bytes());
+        thread[32..36].copy_from_slice(&(stack.len() as u32).to_le_bytes());
+        thread[36..40].copy_from_slice(&stack_rva.to_le_bytes());
+        thread_list.extend(thread);
+        let rva = push(&mut data, &thread_list);
+        streams.push((THREAD_LIST_STREAM, thread_list.len() as u32, rva));
+
+        let mut exception = vec![0u8; 168];
+        exception[0..4].copy_from_slice(&42u32.to_le_bytes());
+        exception[8..12].copy_from_slice(&11u32.to_le_bytes());
+        exception[24..32].copy_from_slice(&0x10u64.to_le_bytes());
+        exception[160..164].copy_from_slice(&(context.len() as u32).to_le_bytes());
+        exception[164..168].copy_from_slice(&context_rva.to_le_bytes());
+        let rva = push(&mut data, &exception);
+        streams.push((EXCEPTION_STREAM, exception.len() as u32, rva));
+
+        let directory = data.len() as u32;
+        for (stream_type, size, rva) in &streams {
+            data.extend_from_slice(&stream_type.to_le_bytes());
+            data.extend_from_slice(&size.to_le_bytes());
+            data.extend_from_slice(&rva.to_le_bytes());
+        }
+
+        data[0..4].copy_from_slice(b"MDMP");
+        data[8..12].copy_from_slice(&(streams.len() as u32).to_le_bytes());
+        data[12..16].copy_from_slice(&directory.to_le_bytes());
+        data[20..24].copy_from_slice(&1_700_000_000u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_minidump() {
+        let minidump = Minidump::parse(&build_minidump()).unwrap();
+        assert_eq!(minidump.timestamp, 1_700_000_000);
+        assert_eq!(minidump.platform, Platform::Linux);
+        assert_eq!(minidump.architecture, Architecture::X86_64);
+        assert_eq!(
+            minidump.modules,
+            [Module {
+                base_address: 0x1000,
+                size: 0x1000,
+                path: "/usr/bin/zed".into(),
+                code_id: Some(CodeId::BuildId(vec![0xde, 0xad, 0xbe, 0xef])),
+            }]
+        );
+        assert_eq!(minidump.modules[0].file_name(), "zed");
+        assert_eq!(minidump.crash_reason().as_deref(), Some("SIGSEGV at 0x10"));
+
+        // The scan starts at the stack pointer, so the first word is skipped.
+        assert_eq!(
+            minidump.stack_frames(),
+            [
+                StackFrame {
+                    address: 0x1010,
+                    trust: FrameTrust::InstructionPointer,
+                },
+                StackFrame {
+                    address: 0x1020,
+                    trust: FrameTrust::Scan,
+                },
+                StackFrame {
+                    address: 0x1abc,
+                    trust: FrameTrust::Scan,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_code_id_uuid() {
+        let guid = CodeId::Guid([
+            0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ]);
+        assert_eq!(
+            guid.uuid(),
+            Some([
+                0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+                0xee, 0xff,
+            ])
+        );
+        assert_eq!(CodeId::BuildId(vec![1, 2, 3]).uuid(), None);
+    }
+
+    #[test]
+    fn test_parse_truncated_minidump() {
+        let data = build_minidump();
+        assert!(Minidump::parse(&data[..data.len() - 4]).is_err());
+        assert!(Minidump::parse(b"not a minidump").is_err());
+    }
+}
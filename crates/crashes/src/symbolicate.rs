@@ -0,0 +1,281 @@
+//! Resolves the addresses in a minidump's stack to function names and
+//! source locations, using debug info found next to the crashed modules.
+
+use addr2line::gimli;
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use object::{Object as _, ObjectSection as _};
+use std::{
+    borrow::Cow,
+    fmt,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use crate::minidump::{CodeId, Minidump, Module, StackFrame};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolicatedFrame {
+    pub frame: StackFrame,
+    /// The file name of the module containing the frame's address.
+    pub module: Option<String>,
+    pub module_offset: u64,
+    /// The functions at the frame's address. When functions were inlined,
+    /// the inlined function comes before the function it was inlined into.
+    pub functions: Vec<FunctionLocation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionLocation {
+    pub name: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+impl fmt::Display for SymbolicatedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(module) = &self.module else {
+            return write!(f, "{:#x}", self.frame.address);
+        };
+        if self.functions.is_empty() {
+            return write!(f, "{module} + {:#x}", self.module_offset);
+        }
+        for (index, function) in self.functions.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n    inlined into ")?;
+            }
+            f.write_str(&function.name)?;
+            if let Some(file) = &function.file {
+                write!(f, " at {file}")?;
+                if let Some(line) = function.line {
+                    write!(f, ":{line}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Symbolicates the crashing thread's stack.
+///
+/// Frames in modules without local debug info are described by their offset
+/// into the module.
+pub fn symbolicate(minidump: &Minidump) -> Vec<SymbolicatedFrame> {
+    let mut frames = minidump
+        .stack_frames()
+        .into_iter()
+        .map(|frame| {
+            let module = minidump.module_for_address(frame.address);
+            SymbolicatedFrame {
+                frame,
+                module: module.map(|module| module.file_name().to_string()),
+                module_offset: module.map_or(0, |module| frame.address - module.base_address),
+                functions: Vec::new(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut frames_by_module = HashMap::<&str, Vec<usize>>::default();
+    for (index, frame) in frames.iter().enumerate() {
+        if let Some(module) = minidump.module_for_address(frame.frame.address) {
+            frames_by_module
+                .entry(module.path.as_str())
+                .or_default()
+                .push(index);
+        }
+    }
+
+    for (module_path, frame_indices) in frames_by_module {
+        let Some(module) = minidump
+            .modules
+            .iter()
+            .find(|module| module.path == module_path)
+        else {
+            continue;
+        };
+        let addresses = frame_indices
+            .iter()
+            .map(|index| frames[*index].frame.lookup_address() - module.base_address)
+            .collect::<Vec<_>>();
+        let functions = debug_info_paths(module).into_iter().find_map(|path| {
+            symbolicate_module(&path, module.code_id.as_ref(), &addresses)
+                .map_err(|error| log::debug!("not symbolicating with {path:?}: {error:#}"))
+                .ok()
+        });
+        if let Some(functions) = functions {
+            for (index, functions) in frame_indices.into_iter().zip(functions) {
+                frames[index].functions = functions;
+            }
+        }
+    }
+
+    frames
+}
+
+/// Returns the files that may contain debug info for a module, in the order
+/// they should be tried.
+fn debug_info_paths(module: &Module) -> Vec<PathBuf> {
+    let path = Path::new(&module.path);
+    let mut paths = Vec::new();
+    if let Some(file_name) = path.file_name() {
+        let mut dsym = path.as_os_str().to_owned();
+        dsym.push(".dSYM");
+        paths.push(
+            PathBuf::from(dsym)
+                .join("Contents/Resources/DWARF")
+                .join(file_name),
+        );
+    }
+    let mut debug = path.as_os_str().to_owned();
+    debug.push(".debug");
+    paths.push(PathBuf::from(debug));
+    paths.push(path.to_path_buf());
+    paths.retain(|path| path.is_file());
+    paths
+}
+
+/// Looks up the functions at each of the given offsets into a module, using
+/// the DWARF debug info in `path`, or its symbol table if it has none.
+///
+/// Files from a different build than the crashed module are rejected, since
+/// their addresses don't line up with the module's.
+fn symbolicate_module(
+    path: &Path,
+    code_id: Option<&CodeId>,
+    offsets: &[u64],
+) -> Result<Vec<Vec<FunctionLocation>>> {
+    let file = File::open(path)?;
+    // Safety: the file is only read, and debug info files aren't expected to
+    // be modified while a crash report is being viewed.
+    let data = unsafe { memmap2::Mmap::map(&file)? };
+    let object = object::File::parse(&*data).context("parsing object file")?;
+    if let Some(code_id) = code_id {
+        anyhow::ensure!(
+            object_code_id_matches(&object, code_id)?,
+            "{path:?} is from a different build"
+        );
+    }
+    let endian = if object.is_little_endian() {
+        gimli::RunTimeEndian::Little
+    } else {
+        gimli::RunTimeEndian::Big
+    };
+    let has_debug_info = object.section_by_name(".debug_info").is_some()
+        || object.section_by_name("__debug_info").is_some();
+
+    let dwarf = gimli::Dwarf::load(|id| -> Result<Cow<[u8]>, gimli::Error> {
+        Ok(object
+            .section_by_name(id.name())
+            .and_then(|section| section.uncompressed_data().ok())
+            .unwrap_or(Cow::Borrowed(&[])))
+    })?;
+    let dwarf = dwarf.borrow(|section| gimli::EndianSlice::new(section, endian));
+    let context = addr2line::Context::from_dwarf(dwarf)?;
+    let symbols = object.symbol_map();
+    let base = object.relative_address_base();
+
+    anyhow::ensure!(
+        has_debug_info || !symbols.symbols().is_empty(),
+        "{path:?} has no debug info or symbols"
+    );
+
+    let mut results = Vec::with_capacity(offsets.len());
+    for offset in offsets {
+        let address = base + offset;
+        let mut functions = Vec::new();
+        if has_debug_info {
+            let mut frames = context.find_frames(address).skip_all_loads()?;
+            while let Some(frame) = frames.next()? {
+                let Some(function) = frame.function else {
+                    continue;
+                };
+                functions.push(FunctionLocation {
+                    name: function.demangle()?.into_owned(),
+                    file: frame
+                        .location
+                        .as_ref()
+                        .and_then(|location| location.file)
+                        .map(str::to_string),
+                    line: frame.location.and_then(|location| location.line),
+                });
+            }
+        }
+        if functions.is_empty()
+            && let Some(symbol) = symbols.get(address)
+        {
+            functions.push(FunctionLocation {
+                name: rustc_demangle::demangle(symbol.name()).to_string(),
+                file: None,
+                line: None,
+            });
+        }
+        results.push(functions);
+    }
+    Ok(results)
+}
+
+fn object_code_id_matches(object: &object::File, code_id: &CodeId) -> Result<bool> {
+    Ok(match code_id {
+        CodeId::BuildId(build_id) => object.build_id()? == Some(build_id.as_slice()),
+        CodeId::Guid(guid) => {
+            if let Some(uuid) = object.mach_uuid()? {
+                code_id.uuid() == Some(uuid)
+            } else if let Some(pdb_info) = object.pdb_info()? {
+                pdb_info.guid() == *guid
+            } else {
+                false
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minidump::{FrameTrust, tests::build_minidump};
+
+    #[test]
+    fn test_symbolicate_without_debug_info() {
+        let minidump = Minidump::parse(&build_minidump()).unwrap();
+        let frames = symbolicate(&minidump);
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| (frame.frame.trust, frame.to_string()))
+                .collect::<Vec<_>>(),
+            [
+                (FrameTrust::InstructionPointer, "zed + 0x10".to_string()),
+                (FrameTrust::Scan, "zed + 0x20".to_string()),
+                (FrameTrust::Scan, "zed + 0xabc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_frame() {
+        let frame = SymbolicatedFrame {
+            frame: StackFrame {
+                address: 0x1234,
+                trust: FrameTrust::Scan,
+            },
+            module: Some("zed".into()),
+            module_offset: 0x234,
+            functions: vec![
+                FunctionLocation {
+                    name: "editor::Editor::render".into(),
+                    file: Some("crates/editor/src/editor.rs".into()),
+                    line: Some(42),
+                },
+                FunctionLocation {
+                    name: "gpui::Window::draw".into(),
+                    file: None,
+                    line: None,
+                },
+            ],
+        };
+        assert_eq!(
+            frame.to_string(),
+            "editor::Editor::render at crates/editor/src/editor.rs:42\n    inlined into gpui::Window::draw"
+        );
+    }
+}
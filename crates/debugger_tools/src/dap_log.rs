@@ -1081,6 +1081,7 @@ impl SearchableItem for DapLogView {
             // DAP log is read-only.
             replacement: false,
             selection: false,
+            syntax_scope: false,
         }
     }
     fn active_match_index(
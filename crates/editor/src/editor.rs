@@ -1211,6 +1211,9 @@ pub struct Editor {
     accent_data: Option<AccentData>,
     fetched_tree_sitter_chunks: HashMap<ExcerptId, HashSet<Range<BufferRow>>>,
     use_base_text_line_numbers: bool,
+    /// The text reported to assistive technologies, along with the buffer's
+    /// edit count when it was built.
+    accessibility_value: Option<(usize, SharedString)>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -2406,6 +2409,7 @@ impl Editor {
             accent_data: None,
             fetched_tree_sitter_chunks: HashMap::default(),
             use_base_text_line_numbers: false,
+            accessibility_value: None,
         };
 
         if is_minimap {
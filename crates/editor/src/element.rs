@@ -42,16 +42,16 @@ use git::{
     status::FileStatus,
 };
 use gpui::{
-    Action, Along, AnyElement, App, AppContext, AvailableSpace, Axis as ScrollbarAxis, BorderStyle,
-    Bounds, ClickEvent, ClipboardItem, ContentMask, Context, Corner, Corners, CursorStyle,
-    DispatchPhase, Edges, Element, ElementInputHandler, Entity, Focusable as _, FontId,
-    GlobalElementId, Hitbox, HitboxBehavior, Hsla, InteractiveElement, IntoElement, IsZero,
-    KeybindingKeystroke, Length, Modifiers, ModifiersChangedEvent, MouseButton, MouseClickEvent,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta,
-    ScrollHandle, ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement,
-    Style, Styled, TextRun, TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill,
-    linear_color_stop, linear_gradient, outline, point, px, quad, relative, size, solid_background,
-    transparent_black,
+    AccessibilityNode, AccessibilityRole, Action, Along, AnyElement, App, AppContext,
+    AvailableSpace, Axis as ScrollbarAxis, BorderStyle, Bounds, ClickEvent, ClipboardItem,
+    ContentMask, Context, Corner, Corners, CursorStyle, DispatchPhase, Edges, Element,
+    ElementInputHandler, Entity, Focusable as _, FontId, GlobalElementId, Hitbox, HitboxBehavior,
+    Hsla, InteractiveElement, IntoElement, IsZero, KeybindingKeystroke, Length, Modifiers,
+    ModifiersChangedEvent, MouseButton, MouseClickEvent, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta, ScrollHandle, ScrollWheelEvent,
+    ShapedLine, SharedString, Size, StatefulInteractiveElement, Style, Styled, TextRun,
+    TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill, linear_color_stop,
+    linear_gradient, outline, point, px, quad, relative, size, solid_background, transparent_black,
 };
 use itertools::Itertools;
 use language::{IndentGuideSettings, language_settings::ShowWhitespaceSetting};
@@ -101,6 +101,9 @@ use workspace::{
     notifications::NotifyTaskExt,
 };
 
+/// Buffers larger than this don't expose their text to assistive technologies.
+const MAX_ACCESSIBILITY_VALUE_LEN: usize = 64 * 1024;
+
 /// Determines what kinds of highlights should be applied to a lines background.
 #[derive(Clone, Copy, Default)]
 struct LineHighlightSpec {
@@ -10254,48 +10257,94 @@ impl Element for EditorElement {
             ..Default::default()
         };
         let rem_size = self.rem_size(cx);
-        window.with_rem_size(rem_size, |window| {
-            window.with_text_style(Some(text_style), |window| {
-                window.with_content_mask(Some(ContentMask { bounds }), |window| {
-                    self.paint_mouse_listeners(layout, window, cx);
-                    self.paint_background(layout, window, cx);
-                    self.paint_indent_guides(layout, window, cx);
+        let accessibility_node = self.accessibility_node(bounds, layout, window, cx);
+        window.with_accessibility_node(None, accessibility_node, |window| {
+            window.with_rem_size(rem_size, |window| {
+                window.with_text_style(Some(text_style), |window| {
+                    window.with_content_mask(Some(ContentMask { bounds }), |window| {
+                        self.paint_mouse_listeners(layout, window, cx);
+                        self.paint_background(layout, window, cx);
+                        self.paint_indent_guides(layout, window, cx);
+
+                        if layout.gutter_hitbox.size.width > Pixels::ZERO {
+                            self.paint_blamed_display_rows(layout, window, cx);
+                            self.paint_line_numbers(layout, window, cx);
+                        }
 
-                    if layout.gutter_hitbox.size.width > Pixels::ZERO {
-                        self.paint_blamed_display_rows(layout, window, cx);
-                        self.paint_line_numbers(layout, window, cx);
-                    }
+                        self.paint_text(layout, window, cx);
 
-                    self.paint_text(layout, window, cx);
+                        if layout.gutter_hitbox.size.width > Pixels::ZERO {
+                            self.paint_gutter_highlights(layout, window, cx);
+                            self.paint_gutter_indicators(layout, window, cx);
+                        }
 
-                    if layout.gutter_hitbox.size.width > Pixels::ZERO {
-                        self.paint_gutter_highlights(layout, window, cx);
-                        self.paint_gutter_indicators(layout, window, cx);
-                    }
+                        if !layout.blocks.is_empty() {
+                            window.with_element_namespace("blocks", |window| {
+                                self.paint_blocks(layout, window, cx);
+                            });
+                        }
 
-                    if !layout.blocks.is_empty() {
                         window.with_element_namespace("blocks", |window| {
-                            self.paint_blocks(layout, window, cx);
+                            if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
+                                sticky_header.paint(window, cx)
+                            }
                         });
-                    }
 
-                    window.with_element_namespace("blocks", |window| {
-                        if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
-                            sticky_header.paint(window, cx)
-                        }
+                        self.paint_sticky_headers(layout, window, cx);
+                        self.paint_minimap(layout, window, cx);
+                        self.paint_scrollbars(layout, window, cx);
+                        self.paint_edit_prediction_popover(layout, window, cx);
+                        self.paint_mouse_context_menu(layout, window, cx);
                     });
-
-                    self.paint_sticky_headers(layout, window, cx);
-                    self.paint_minimap(layout, window, cx);
-                    self.paint_scrollbars(layout, window, cx);
-                    self.paint_edit_prediction_popover(layout, window, cx);
-                    self.paint_mouse_context_menu(layout, window, cx);
-                });
+                })
             })
         })
     }
 }
 
+impl EditorElement {
+    fn accessibility_node(
+        &self,
+        bounds: Bounds<Pixels>,
+        layout: &EditorLayout,
+        window: &Window,
+        cx: &mut App,
+    ) -> Option<AccessibilityNode> {
+        if !window.is_accessibility_enabled() || layout.mode.is_minimap() {
+            return None;
+        }
+
+        let focus_handle = self.editor.focus_handle(cx);
+        self.editor.update(cx, |editor, cx| {
+            let role = if editor.mode().is_single_line() {
+                AccessibilityRole::TextInput
+            } else {
+                AccessibilityRole::MultilineTextInput
+            };
+            let mut node = AccessibilityNode::new(role, bounds).focus_handle(&focus_handle);
+            // Building the text of a large buffer is too slow, so only small
+            // buffers report their contents, which are rebuilt after edits.
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            if snapshot.len().0 <= MAX_ACCESSIBILITY_VALUE_LEN {
+                let edit_count = snapshot.edit_count();
+                let value = match &editor.accessibility_value {
+                    Some((built_at, value)) if *built_at == edit_count => value.clone(),
+                    _ => {
+                        let value = SharedString::from(snapshot.text());
+                        editor.accessibility_value = Some((edit_count, value.clone()));
+                        value
+                    }
+                };
+                node = node.value(value);
+            }
+            if let Some(placeholder) = editor.placeholder_text(cx) {
+                node = node.name(placeholder);
+            }
+            Some(node)
+        })
+    }
+}
+
 pub(super) fn gutter_bounds(
     editor_bounds: Bounds<Pixels>,
     gutter_dimensions: GutterDimensions,
@@ -11592,6 +11641,41 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    fn test_accessibility_node(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search", window, cx);
+            editor.set_text("hello", window, cx);
+            editor
+        });
+        cx.simulate_resize(size(px(200.), px(50.)));
+        cx.enable_accessibility();
+        assert!(
+            cx.accessibility_tree()
+                .contains("text input \"Search\" value=\"hello\"\n")
+        );
+
+        cx.simulate_accessibility_action(
+            AccessibilityRole::TextInput,
+            "Search",
+            gpui::AccessibilityAction::Focus,
+        );
+        assert!(editor.update_in(cx, |editor, window, cx| {
+            editor.focus_handle(cx).is_focused(window)
+        }));
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_text("hello world", window, cx)
+        });
+        assert!(
+            cx.accessibility_tree()
+                .contains("text input \"Search\" value=\"hello world\" (focused)\n")
+        );
+    }
+
     #[gpui::test]
     fn test_shape_line_numbers(cx: &mut TestAppContext) {
         init_test(cx, |_| {});
@@ -5,6 +5,7 @@ use futures::future::{self, BoxFuture, join_all};
 use git::{
     Oid, RunHook,
     blame::Blame,
+    history_search::{HistorySearchMatch, HistorySearchQuery},
     repository::{
         AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
         GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode, Worktree,
@@ -48,6 +49,7 @@ pub struct FakeGitRepositoryState {
     pub merge_base_contents: HashMap<RepoPath, Oid>,
     pub oids: HashMap<Oid, String>,
     pub blames: HashMap<RepoPath, Blame>,
+    pub history: Vec<HistorySearchMatch>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
     /// List of remotes, keys are names and values are URLs
@@ -64,6 +66,7 @@ impl FakeGitRepositoryState {
             index_contents: Default::default(),
             unmerged_paths: Default::default(),
             blames: Default::default(),
+            history: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
@@ -465,6 +468,38 @@ impl GitRepository for FakeGitRepository {
         self.file_history_paginated(path, 0, None)
     }
 
+    fn search_history(
+        &self,
+        query: HistorySearchQuery,
+    ) -> BoxFuture<'_, Result<Vec<HistorySearchMatch>>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .history
+                .iter()
+                .filter_map(|commit| {
+                    let hunks = commit
+                        .hunks
+                        .iter()
+                        .filter(|hunk| {
+                            (query.paths.is_empty()
+                                || query.paths.iter().any(|path| hunk.path.starts_with(path)))
+                                && hunk
+                                    .lines
+                                    .iter()
+                                    .any(|line| line.contains(query.pattern.as_str()))
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    (!hunks.is_empty()).then(|| HistorySearchMatch {
+                        hunks,
+                        ..commit.clone()
+                    })
+                })
+                .take(query.limit.unwrap_or(usize::MAX))
+                .collect())
+        })
+    }
+
     fn file_history_paginated(
         &self,
         path: RepoPath,
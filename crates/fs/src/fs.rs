@@ -1945,6 +1945,18 @@ impl FakeFs {
         .unwrap();
     }
 
+    /// Sets the commits, newest first, searched by [`GitRepository::search_history`].
+    pub fn set_history_for_repo(
+        &self,
+        dot_git: &Path,
+        history: Vec<git::history_search::HistorySearchMatch>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.history = history;
+        })
+        .unwrap();
+    }
+
     /// Put the given git repository into a state with the given status,
     /// by mutating the head, index, and unmerged state.
     pub fn set_status_for_repo(&self, dot_git: &Path, statuses: &[(&str, FileStatus)]) {
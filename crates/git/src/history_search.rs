@@ -0,0 +1,564 @@
+use crate::repository::RepoPath;
+use anyhow::{Context as _, Result};
+use gpui::SharedString;
+use regex::{Regex, RegexBuilder};
+
+/// Separates commits in the output of `git log`, so that commit subjects can't be
+/// mistaken for the start of a new commit.
+pub(crate) const COMMIT_DELIMITER: &str = "<<COMMIT_START-6b1e2f7a-94c3-4d0b-a8e5-3c7f1d9b2e64>>";
+
+/// A search through the history of a repository for commits whose diffs add or
+/// remove some text, like `git log -S` (or `git log -G` for regexes).
+///
+/// Regexes use the syntax of the `regex` crate, like the rest of the project search.
+/// As git only understands POSIX regexes, it is only given a literal that every match
+/// must contain, and the regex is applied to the commits it finds. Regexes without
+/// such a literal make git print every commit's patch, so its output is parsed as it
+/// is read and git is stopped once enough matches were found.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct HistorySearchQuery {
+    pub pattern: String,
+    pub is_regex: bool,
+    pub case_sensitive: bool,
+    /// Only search commits touching these paths, or all commits when empty. Paths
+    /// may contain globs.
+    pub paths: Vec<RepoPath>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistorySearchMatch {
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+    pub author_name: SharedString,
+    /// The hunks of the commit's diff containing the pattern.
+    pub hunks: Vec<HistorySearchHunk>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistorySearchHunk {
+    pub path: RepoPath,
+    /// The first line of the hunk in the file before the commit, one-based.
+    pub old_start: u32,
+    /// The first line of the hunk in the file after the commit, one-based.
+    pub new_start: u32,
+    /// The removed and added lines of the hunk, including their `-` and `+` prefixes.
+    pub lines: Vec<String>,
+}
+
+impl HistorySearchQuery {
+    /// The arguments passed to `git log` to find the matching commits along with their patches.
+    pub(crate) fn git_log_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--no-optional-locks".to_string(),
+            "log".to_string(),
+            "--no-color".to_string(),
+            "--no-ext-diff".to_string(),
+            "--no-prefix".to_string(),
+            "--patch".to_string(),
+            "--unified=0".to_string(),
+            format!("--format={COMMIT_DELIMITER}%H%x00%s%x00%at%x00%an"),
+        ];
+        if self.is_regex {
+            if let Some(literal) = required_literal(&self.pattern) {
+                args.push(format!("-G{}", posix_regex_escape(&literal)));
+            }
+        } else {
+            args.push(format!("-S{}", self.pattern));
+        }
+        if !self.case_sensitive {
+            args.push("--regexp-ignore-case".to_string());
+        }
+        // Commits found by git may not match the regex, so the limit is applied when
+        // parsing the output instead.
+        if let Some(limit) = self.limit
+            && !self.is_regex
+        {
+            args.push(format!("--max-count={limit}"));
+        }
+        args.push("--".to_string());
+        args.extend(self.paths.iter().map(|path| {
+            let path = path.as_unix_str();
+            if path.contains(['*', '?', '[']) {
+                format!(":(glob){path}")
+            } else {
+                path.to_string()
+            }
+        }));
+        args
+    }
+
+    fn line_matcher(&self) -> Result<LineMatcher> {
+        if self.is_regex {
+            let regex = RegexBuilder::new(&self.pattern)
+                .case_insensitive(!self.case_sensitive)
+                .build()
+                .with_context(|| format!("invalid regex {:?}", self.pattern))?;
+            Ok(LineMatcher::Regex(regex))
+        } else if self.case_sensitive {
+            Ok(LineMatcher::Text(self.pattern.clone()))
+        } else {
+            Ok(LineMatcher::CaseInsensitiveText(
+                self.pattern.to_lowercase(),
+            ))
+        }
+    }
+}
+
+/// The longest run of literal text that any match of the regex contains, if any.
+fn required_literal(pattern: &str) -> Option<String> {
+    // Alternations and inline flags could make any literal optional, or change how it
+    // matches, so they aren't analyzed.
+    if pattern.contains('|') || pattern.contains("(?") {
+        return None;
+    }
+
+    let mut longest = String::new();
+    let mut current = String::new();
+    let mut end_run = |current: &mut String| {
+        if current.chars().count() > longest.chars().count() {
+            longest = current.clone();
+        }
+        current.clear();
+    };
+    let mut depth = 0;
+    let mut chars = pattern.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some(escaped) if depth == 0 && !escaped.is_alphanumeric() => current.push(escaped),
+                _ => end_run(&mut current),
+            },
+            // The preceding character is optional.
+            '*' | '?' | '{' => {
+                current.pop();
+                end_run(&mut current);
+                if ch == '{' {
+                    chars.by_ref().find(|&ch| ch == '}');
+                }
+            }
+            '+' | '.' | '^' | '$' => end_run(&mut current),
+            '[' => {
+                end_run(&mut current);
+                // Skip the class, where a leading `]` (after an optional `^`) is literal.
+                chars.next_if_eq(&'^');
+                chars.next_if_eq(&']');
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '\\' => {
+                            chars.next();
+                        }
+                        ']' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '(' => {
+                end_run(&mut current);
+                depth += 1;
+            }
+            ')' => {
+                end_run(&mut current);
+                depth -= 1;
+                // A quantified group may be skipped, but it doesn't affect the literals
+                // around it.
+                if matches!(chars.peek(), Some('*' | '?' | '{' | '+')) {
+                    chars.next();
+                }
+            }
+            _ if depth == 0 => current.push(ch),
+            _ => {}
+        }
+    }
+    end_run(&mut current);
+    (!longest.is_empty()).then_some(longest)
+}
+
+/// Escapes text to match itself in both basic and extended POSIX regexes.
+fn posix_regex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            // `^` can't be the first character of a bracket expression, so it is matched
+            // along with `]` (which has to be the first character). This is only used to
+            // narrow down commits, so the extra matches are harmless.
+            '^' | ']' => escaped.push_str("[]^]"),
+            '.' | '[' | '\\' | '*' | '$' | '+' | '?' | '(' | ')' | '{' | '}' | '|' => {
+                escaped.push('[');
+                escaped.push(ch);
+                escaped.push(']');
+            }
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+enum LineMatcher {
+    Text(String),
+    CaseInsensitiveText(String),
+    Regex(Regex),
+}
+
+impl LineMatcher {
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            LineMatcher::Text(text) => line.contains(text.as_str()),
+            LineMatcher::CaseInsensitiveText(text) => line.to_lowercase().contains(text.as_str()),
+            LineMatcher::Regex(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// Parses the output of `git log` run with [`HistorySearchQuery::git_log_args`],
+/// keeping only the hunks whose added or removed lines contain the pattern.
+#[cfg(test)]
+pub(crate) fn parse_history_search_output(
+    output: &str,
+    query: &HistorySearchQuery,
+) -> Result<Vec<HistorySearchMatch>> {
+    let mut parser = HistorySearchParser::new(query)?;
+    for line in output.split_inclusive('\n') {
+        if !parser.push_line(line) {
+            break;
+        }
+    }
+    Ok(parser.finish())
+}
+
+/// Parses the output of `git log` run with [`HistorySearchQuery::git_log_args`] as
+/// it is read, so that only one commit's patch is held in memory at a time.
+pub(crate) struct HistorySearchParser<'a> {
+    query: &'a HistorySearchQuery,
+    matcher: LineMatcher,
+    /// The lines of the commit being read, starting with its header.
+    commit: String,
+    matches: Vec<HistorySearchMatch>,
+}
+
+impl<'a> HistorySearchParser<'a> {
+    pub(crate) fn new(query: &'a HistorySearchQuery) -> Result<Self> {
+        Ok(Self {
+            query,
+            matcher: query.line_matcher()?,
+            commit: String::new(),
+            matches: Vec::new(),
+        })
+    }
+
+    /// Adds a line of output, including its line ending. Returns false once the
+    /// query's limit of matches was reached, after which git can be stopped.
+    pub(crate) fn push_line(&mut self, line: &str) -> bool {
+        if line.starts_with(COMMIT_DELIMITER) {
+            self.finish_commit();
+            if self.is_full() {
+                return false;
+            }
+        }
+        self.commit.push_str(line);
+        true
+    }
+
+    pub(crate) fn finish(mut self) -> Vec<HistorySearchMatch> {
+        if !self.is_full() {
+            self.finish_commit();
+        }
+        self.matches
+    }
+
+    fn is_full(&self) -> bool {
+        self.query
+            .limit
+            .is_some_and(|limit| self.matches.len() >= limit)
+    }
+
+    fn finish_commit(&mut self) {
+        let commit = std::mem::take(&mut self.commit);
+        let commit_block = commit.strip_prefix(COMMIT_DELIMITER).unwrap_or(&commit);
+        if commit_block.trim().is_empty() {
+            return;
+        }
+
+        let (header, patch) = commit_block.split_once('\n').unwrap_or((commit_block, ""));
+        let fields: Vec<&str> = header.split('\0').collect();
+        if fields.len() < 4 {
+            log::warn!("Unexpected commit header in git log output: {header:?}");
+            return;
+        }
+
+        let hunks = parse_patch(patch)
+            .into_iter()
+            .filter(|hunk| {
+                hunk.lines
+                    .iter()
+                    .any(|line| self.matcher.is_match(&line[1..]))
+            })
+            .collect::<Vec<_>>();
+        // Git only narrowed down the commits for regexes, so the ones without
+        // matching lines are not matches.
+        if self.query.is_regex && hunks.is_empty() {
+            return;
+        }
+        self.matches.push(HistorySearchMatch {
+            sha: fields[0].trim().to_string().into(),
+            subject: fields[1].trim().to_string().into(),
+            commit_timestamp: fields[2].trim().parse().unwrap_or(0),
+            author_name: fields[3].trim().to_string().into(),
+            hunks,
+        });
+    }
+}
+
+fn parse_patch(patch: &str) -> Vec<HistorySearchHunk> {
+    let mut hunks = Vec::new();
+    let mut old_path = None;
+    let mut new_path = None;
+    let mut current_hunk: Option<HistorySearchHunk> = None;
+
+    for line in patch.lines() {
+        if line.starts_with("diff --git ") {
+            hunks.extend(current_hunk.take());
+            old_path = None;
+            new_path = None;
+        } else if current_hunk.is_none() && old_path.is_none() && line.starts_with("--- ") {
+            old_path = parse_patch_path(&line[4..]);
+        } else if current_hunk.is_none() && new_path.is_none() && line.starts_with("+++ ") {
+            new_path = parse_patch_path(&line[4..]);
+        } else if let Some(header) = line.strip_prefix("@@ ") {
+            hunks.extend(current_hunk.take());
+            let Some(path) = new_path.clone().or_else(|| old_path.clone()) else {
+                continue;
+            };
+            let Some((old_start, new_start)) = parse_hunk_header(header) else {
+                continue;
+            };
+            current_hunk = Some(HistorySearchHunk {
+                path,
+                old_start,
+                new_start,
+                lines: Vec::new(),
+            });
+        } else if let Some(hunk) = current_hunk.as_mut()
+            && (line.starts_with('+') || line.starts_with('-'))
+        {
+            hunk.lines.push(line.to_string());
+        }
+    }
+    hunks.extend(current_hunk);
+    hunks
+}
+
+/// Parses the path of a `---` or `+++` line of a patch generated with `--no-prefix`.
+fn parse_patch_path(path: &str) -> Option<RepoPath> {
+    let path = path.trim_end_matches('\t');
+    if path == "/dev/null" {
+        return None;
+    }
+    RepoPath::new(path).ok()
+}
+
+/// Parses the line numbers from a hunk header like `-12,3 +14 @@ fn main() {`.
+fn parse_hunk_header(header: &str) -> Option<(u32, u32)> {
+    let mut ranges = header.split_whitespace();
+    let old_start = ranges.next()?.strip_prefix('-')?;
+    let new_start = ranges.next()?.strip_prefix('+')?;
+    let start = |range: &str| range.split(',').next()?.parse().ok();
+    Some((start(old_start)?, start(new_start)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::repo_path;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_history_search_output() {
+        let output = [
+            COMMIT_DELIMITER,
+            "1111111111111111111111111111111111111111\0Remove the timeout setting\01700000100\0Alice\n",
+            "\n",
+            "diff --git config.toml config.toml\n",
+            "index 3b18e51..e69de29 100644\n",
+            "--- config.toml\n",
+            "+++ config.toml\n",
+            "@@ -2 +1,0 @@ [server]\n",
+            "-request_timeout = 30\n",
+            "@@ -8 +7 @@ [client]\n",
+            "-retries = 3\n",
+            "+retries = 5\n",
+            "diff --git old.toml old.toml\n",
+            "deleted file mode 100644\n",
+            "index 9daeafb..0000000\n",
+            "--- old.toml\n",
+            "+++ /dev/null\n",
+            "@@ -1 +0,0 @@\n",
+            "-REQUEST_TIMEOUT = 10\n",
+            "\n",
+            COMMIT_DELIMITER,
+            "2222222222222222222222222222222222222222\0Add a timeout\01700000000\0Bob\n",
+            "\n",
+            "diff --git config.toml config.toml\n",
+            "new file mode 100644\n",
+            "index 0000000..3b18e51\n",
+            "--- /dev/null\n",
+            "+++ config.toml\n",
+            "@@ -0,0 +1,2 @@\n",
+            "+[server]\n",
+            "+request_timeout = 30\n",
+        ]
+        .concat();
+
+        let query = HistorySearchQuery {
+            pattern: "request_timeout".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_history_search_output(&output, &query).unwrap(),
+            vec![
+                HistorySearchMatch {
+                    sha: "1111111111111111111111111111111111111111".into(),
+                    subject: "Remove the timeout setting".into(),
+                    commit_timestamp: 1700000100,
+                    author_name: "Alice".into(),
+                    hunks: vec![
+                        HistorySearchHunk {
+                            path: repo_path("config.toml"),
+                            old_start: 2,
+                            new_start: 1,
+                            lines: vec!["-request_timeout = 30".into()],
+                        },
+                        HistorySearchHunk {
+                            path: repo_path("old.toml"),
+                            old_start: 1,
+                            new_start: 0,
+                            lines: vec!["-REQUEST_TIMEOUT = 10".into()],
+                        },
+                    ],
+                },
+                HistorySearchMatch {
+                    sha: "2222222222222222222222222222222222222222".into(),
+                    subject: "Add a timeout".into(),
+                    commit_timestamp: 1700000000,
+                    author_name: "Bob".into(),
+                    hunks: vec![HistorySearchHunk {
+                        path: repo_path("config.toml"),
+                        old_start: 0,
+                        new_start: 1,
+                        lines: vec!["+[server]".into(), "+request_timeout = 30".into()],
+                    }],
+                },
+            ]
+        );
+
+        let query = HistorySearchQuery {
+            pattern: r"^retries = \d$".into(),
+            is_regex: true,
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let matches = parse_history_search_output(&output, &query).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].hunks,
+            vec![HistorySearchHunk {
+                path: repo_path("config.toml"),
+                old_start: 8,
+                new_start: 7,
+                lines: vec!["-retries = 3".into(), "+retries = 5".into()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_history_search_parser_stops_at_limit() {
+        let commit = |sha: char, line: &str| {
+            [
+                COMMIT_DELIMITER,
+                &format!(
+                    "{}\0Subject\01700000000\0Alice\n",
+                    sha.to_string().repeat(40)
+                ),
+                "diff --git a.txt a.txt\n",
+                "--- a.txt\n",
+                "+++ a.txt\n",
+                "@@ -1 +1 @@\n",
+                &format!("+{line}\n"),
+            ]
+            .concat()
+        };
+        let output = [
+            commit('1', "no match"),
+            commit('2', "let timeout = 1;"),
+            commit('3', "let timeout = 2;"),
+        ]
+        .concat();
+        let query = HistorySearchQuery {
+            pattern: r"\w+ = \d".into(),
+            is_regex: true,
+            limit: Some(1),
+            ..Default::default()
+        };
+
+        let mut parser = HistorySearchParser::new(&query).unwrap();
+        let mut lines = output.split_inclusive('\n');
+        let read = lines
+            .by_ref()
+            .take_while(|line| parser.push_line(line))
+            .count();
+        // Reading stops at the header of the commit after the first match.
+        assert_eq!(read, 14);
+        assert!(lines.next().is_some());
+        let matches = parser.finish();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].sha.as_ref(), "2".repeat(40));
+    }
+
+    #[test]
+    fn test_required_literal() {
+        assert_eq!(required_literal(r"retries = \d"), Some("retries = ".into()));
+        assert_eq!(required_literal(r"fn \w+\(self"), Some("(self".into()));
+        assert_eq!(required_literal(r"colou?r_name"), Some("r_name".into()));
+        assert_eq!(required_literal(r"a{2}bcd[xyz]+efgh"), Some("efgh".into()));
+        assert_eq!(required_literal(r"(foo)?barbaz"), Some("barbaz".into()));
+        assert_eq!(required_literal(r"foo|bar"), None);
+        assert_eq!(required_literal(r"(?i)timeout"), None);
+        assert_eq!(required_literal(r"\d+"), None);
+
+        assert_eq!(posix_regex_escape("a.b(c)"), "a[.]b[(]c[)]");
+        assert_eq!(posix_regex_escape("x^2]"), "x[]^]2[]^]");
+    }
+
+    #[test]
+    fn test_git_log_args() {
+        let query = HistorySearchQuery {
+            pattern: "-flag".into(),
+            is_regex: false,
+            case_sensitive: true,
+            paths: vec![repo_path("src/main.rs")],
+            limit: Some(10),
+        };
+        let args = query.git_log_args();
+        assert!(args.contains(&"-S-flag".to_string()));
+        assert!(!args.contains(&"--regexp-ignore-case".to_string()));
+        assert_eq!(
+            &args[args.len() - 3..],
+            ["--max-count=10", "--", "src/main.rs"]
+        );
+
+        let query = HistorySearchQuery {
+            pattern: r"retries = \d+".into(),
+            is_regex: true,
+            case_sensitive: false,
+            paths: vec![repo_path("src/**/*.toml")],
+            limit: Some(10),
+        };
+        let args = query.git_log_args();
+        assert!(args.contains(&"-Gretries = ".to_string()));
+        assert!(args.contains(&"--regexp-ignore-case".to_string()));
+        assert!(!args.contains(&"--max-count=10".to_string()));
+        assert_eq!(&args[args.len() - 2..], ["--", ":(glob)src/**/*.toml"]);
+    }
+}
@@ -15,6 +15,29 @@ use crate::repository::RepoPath;
 pub struct PullRequest {
     pub number: u32,
     pub url: Url,
+    /// The commit at the head of the pull request, when the provider reports it.
+    pub head_sha: Option<SharedString>,
+}
+
+/// A thread of review comments on a line of a pull request's diff.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReviewThread {
+    pub id: SharedString,
+    pub path: RepoPath,
+    /// The 1-based line in the new version of the file that the thread is
+    /// attached to, or `None` if it's outdated or on a removed line.
+    pub line: Option<u32>,
+    pub is_resolved: bool,
+    pub is_outdated: bool,
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReviewComment {
+    pub id: SharedString,
+    pub author: SharedString,
+    pub body: SharedString,
+    pub url: Option<Url>,
 }
 
 #[derive(Clone)]
@@ -50,6 +73,55 @@ impl GitRemote {
             .ok()
             .flatten()
     }
+
+    pub fn host_supports_pull_request_reviews(&self) -> bool {
+        self.host.supports_pull_request_reviews()
+    }
+
+    /// Returns the open pull request into this repository from `branch`,
+    /// which is in the fork owned by `head_owner`.
+    pub async fn pull_request_for_branch(
+        &self,
+        head_owner: &str,
+        branch: &str,
+        client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        self.host
+            .pull_request_for_branch(&self.owner, &self.repo, head_owner, branch, client)
+            .await
+    }
+
+    pub async fn review_threads(
+        &self,
+        pull_request: &PullRequest,
+        client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<ReviewThread>> {
+        self.host
+            .pull_request_review_threads(&self.owner, &self.repo, pull_request.number, client)
+            .await
+    }
+
+    pub async fn reply_to_review_thread(
+        &self,
+        thread_id: &str,
+        body: &str,
+        client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        self.host
+            .reply_to_review_thread(thread_id, body, client)
+            .await
+    }
+
+    pub async fn set_review_thread_resolved(
+        &self,
+        thread_id: &str,
+        resolved: bool,
+        client: Arc<dyn HttpClient>,
+    ) -> Result<()> {
+        self.host
+            .set_review_thread_resolved(thread_id, resolved, client)
+            .await
+    }
 }
 
 pub struct BuildCommitPermalinkParams<'a> {
@@ -134,6 +206,56 @@ pub trait GitHostingProvider {
     ) -> Result<Option<Url>> {
         Ok(None)
     }
+
+    /// Returns whether this provider supports fetching and replying to
+    /// pull request review threads.
+    fn supports_pull_request_reviews(&self) -> bool {
+        false
+    }
+
+    /// Returns the open pull request whose head is the given branch of
+    /// `head_owner`'s fork, if any.
+    async fn pull_request_for_branch(
+        &self,
+        _repo_owner: &str,
+        _repo: &str,
+        _head_owner: &str,
+        _branch: &str,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        Ok(None)
+    }
+
+    /// Returns the review threads on the given pull request.
+    async fn pull_request_review_threads(
+        &self,
+        _repo_owner: &str,
+        _repo: &str,
+        _pull_request_number: u32,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<ReviewThread>> {
+        Ok(Vec::new())
+    }
+
+    /// Adds a comment to the end of a review thread.
+    async fn reply_to_review_thread(
+        &self,
+        _thread_id: &str,
+        _body: &str,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        anyhow::bail!("{} does not support pull request reviews", self.name())
+    }
+
+    /// Marks a review thread as resolved or unresolved.
+    async fn set_review_thread_resolved(
+        &self,
+        _thread_id: &str,
+        _resolved: bool,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<()> {
+        anyhow::bail!("{} does not support pull request reviews", self.name())
+    }
 }
 
 #[derive(Default, Deref, DerefMut)]
@@ -1,4 +1,5 @@
 use crate::commit::parse_git_diff_name_status;
+use crate::history_search::{HistorySearchMatch, HistorySearchParser, HistorySearchQuery};
 use crate::stash::GitStash;
 use crate::status::{DiffTreeType, GitStatus, StatusCode, TreeDiff};
 use crate::{Oid, RunHook, SHORT_SHA_LENGTH};
@@ -489,6 +490,11 @@ pub trait GitRepository: Send + Sync {
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>>;
     fn file_history(&self, path: RepoPath) -> BoxFuture<'_, Result<FileHistory>>;
+    /// Finds the commits whose diffs add or remove the query's pattern, newest first.
+    fn search_history(
+        &self,
+        query: HistorySearchQuery,
+    ) -> BoxFuture<'_, Result<Vec<HistorySearchMatch>>>;
     fn file_history_paginated(
         &self,
         path: RepoPath,
@@ -1511,6 +1517,57 @@ impl GitRepository for RealGitRepository {
         self.file_history_paginated(path, 0, None)
     }
 
+    fn search_history(
+        &self,
+        query: HistorySearchQuery,
+    ) -> BoxFuture<'_, Result<Vec<HistorySearchMatch>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let mut parser = HistorySearchParser::new(&query)?;
+                let mut child = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args(query.git_log_args())
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .kill_on_drop(true)
+                    .spawn()?;
+                let mut stdout = BufReader::new(child.stdout.take().context("no stdout")?);
+                let mut stderr = child.stderr.take().context("no stderr")?;
+
+                // The patches of the whole history can be huge, so they are parsed
+                // as they are read, and git is stopped once enough commits matched.
+                let read_stdout = async {
+                    let mut line = Vec::new();
+                    loop {
+                        line.clear();
+                        if stdout.read_until(b'\n', &mut line).await? == 0 {
+                            return anyhow::Ok(false);
+                        }
+                        if !parser.push_line(&String::from_utf8_lossy(&line)) {
+                            child.kill().ok();
+                            return Ok(true);
+                        }
+                    }
+                };
+                let mut stderr_output = Vec::new();
+                let (stopped, _) =
+                    futures::join!(read_stdout, stderr.read_to_end(&mut stderr_output));
+                let stopped = stopped?;
+                let status = child.status().await?;
+                if !stopped && !status.success() {
+                    let stderr = String::from_utf8_lossy(&stderr_output);
+                    bail!("git log failed: {stderr}");
+                }
+
+                Ok(parser.finish())
+            })
+            .boxed()
+    }
+
     fn file_history_paginated(
         &self,
         path: RepoPath,
@@ -2846,6 +2903,123 @@ mod tests {
         // );
     }
 
+    #[gpui::test]
+    async fn test_search_history(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        for (message, contents) in [
+            ("Add a timeout", "[server]\ntimeout = 30\n"),
+            ("Remove the timeout", "[server]\nretries = 3\n"),
+            ("Retry more", "[server]\nretries = 5\n"),
+        ] {
+            smol::fs::write(repo_dir.path().join("config.toml"), contents)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("config.toml")], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                Arc::new(checkpoint_author_envs()),
+            )
+            .await
+            .unwrap();
+        }
+
+        let matches = repo
+            .search_history(HistorySearchQuery {
+                pattern: "TIMEOUT".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.subject.as_ref(), m.hunks.clone()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "Remove the timeout",
+                    vec![crate::history_search::HistorySearchHunk {
+                        path: repo_path("config.toml"),
+                        old_start: 2,
+                        new_start: 2,
+                        lines: vec!["-timeout = 30".into(), "+retries = 3".into()],
+                    }]
+                ),
+                (
+                    "Add a timeout",
+                    vec![crate::history_search::HistorySearchHunk {
+                        path: repo_path("config.toml"),
+                        old_start: 0,
+                        new_start: 1,
+                        lines: vec!["+[server]".into(), "+timeout = 30".into()],
+                    }]
+                ),
+            ]
+        );
+
+        let matches = repo
+            .search_history(HistorySearchQuery {
+                pattern: r"retries = \d".into(),
+                is_regex: true,
+                case_sensitive: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| m.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Retry more", "Remove the timeout"]
+        );
+
+        let matches = repo
+            .search_history(HistorySearchQuery {
+                pattern: r"\w+ = \d+".into(),
+                is_regex: true,
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| m.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Retry more"]
+        );
+
+        let matches = repo
+            .search_history(HistorySearchQuery {
+                pattern: "timeout".into(),
+                paths: vec![repo_path("other.toml")],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(matches.is_empty());
+    }
+
     #[gpui::test]
     async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
         disable_git_global_config();
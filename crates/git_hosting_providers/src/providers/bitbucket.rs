@@ -275,7 +275,11 @@ impl GitHostingProvider for Bitbucket {
         };
         url.set_path(&path);
 
-        Some(PullRequest { number, url })
+        Some(PullRequest {
+            number,
+            url,
+            head_sha: None,
+        })
     }
 
     async fn commit_author_avatar_url(
@@ -161,7 +161,11 @@ impl GitHostingProvider for Chromium {
 
         let number = capture.get(3)?.as_str().parse::<u32>().ok()?;
 
-        Some(PullRequest { number, url })
+        Some(PullRequest {
+            number,
+            url,
+            head_sha: None,
+        })
     }
 
     async fn commit_author_avatar_url(
@@ -5,14 +5,18 @@ use anyhow::{Context as _, Result, bail};
 use async_trait::async_trait;
 use futures::AsyncReadExt;
 use gpui::SharedString;
-use http_client::{AsyncBody, HttpClient, HttpRequestExt, Request};
+use http_client::{AsyncBody, Builder, HttpClient, HttpRequestExt, Request, StatusCode};
 use regex::Regex;
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
 use url::Url;
+use util::ResultExt as _;
 
+use git::repository::RepoPath;
 use git::{
     BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    PullRequest, RemoteUrl,
+    PullRequest, RemoteUrl, ReviewComment, ReviewThread,
 };
 
 use crate::get_host_from_git_remote_url;
@@ -61,6 +65,189 @@ struct User {
     pub avatar_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct PullRequestDetails {
+    number: u32,
+    html_url: String,
+    head: PullRequestHead,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestHead {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewThreadsData {
+    repository: Option<RepositoryNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryNode {
+    pull_request: Option<PullRequestNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestNode {
+    review_threads: Connection<ReviewThreadNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewThreadCommentsData {
+    node: Option<ReviewThreadCommentsNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewThreadCommentsNode {
+    comments: Connection<ReviewCommentNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Connection<T> {
+    #[serde(default)]
+    page_info: Option<PageInfo>,
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadNode {
+    id: String,
+    path: String,
+    line: Option<u32>,
+    #[serde(default)]
+    diff_side: Option<String>,
+    is_resolved: bool,
+    is_outdated: bool,
+    comments: Connection<ReviewCommentNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewCommentNode {
+    id: String,
+    body: String,
+    url: Option<String>,
+    author: Option<Actor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Actor {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddReplyData {
+    add_pull_request_review_thread_reply: AddReplyPayload,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddReplyPayload {
+    comment: ReviewCommentNode,
+}
+
+impl From<ReviewCommentNode> for ReviewComment {
+    fn from(comment: ReviewCommentNode) -> Self {
+        Self {
+            id: comment.id.into(),
+            author: comment
+                .author
+                .map_or_else(|| "ghost".into(), |author| author.login.into()),
+            body: comment.body.into(),
+            url: comment.url.and_then(|url| Url::parse(&url).ok()),
+        }
+    }
+}
+
+fn next_cursor(page_info: Option<PageInfo>) -> Option<String> {
+    match page_info {
+        Some(PageInfo {
+            has_next_page: true,
+            end_cursor,
+        }) => end_cursor,
+        _ => None,
+    }
+}
+
+const REVIEW_THREADS_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $number: Int!, $cursor: String) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 50, after: $cursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes {
+          id
+          path
+          line
+          diffSide
+          isResolved
+          isOutdated
+          comments(first: 100) {
+            pageInfo { hasNextPage endCursor }
+            nodes { id body url author { login } }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+const REVIEW_THREAD_COMMENTS_QUERY: &str = r#"
+query($threadId: ID!, $cursor: String) {
+  node(id: $threadId) {
+    ... on PullRequestReviewThread {
+      comments(first: 100, after: $cursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes { id body url author { login } }
+      }
+    }
+  }
+}
+"#;
+
+const ADD_REPLY_MUTATION: &str = r#"
+mutation($threadId: ID!, $body: String!) {
+  addPullRequestReviewThreadReply(input: {pullRequestReviewThreadId: $threadId, body: $body}) {
+    comment { id body url author { login } }
+  }
+}
+"#;
+
+const RESOLVE_THREAD_MUTATION: &str = r#"
+mutation($threadId: ID!) {
+  resolveReviewThread(input: {threadId: $threadId}) { thread { id } }
+}
+"#;
+
+const UNRESOLVE_THREAD_MUTATION: &str = r#"
+mutation($threadId: ID!) {
+  unresolveReviewThread(input: {threadId: $threadId}) { thread { id } }
+}
+"#;
+
 #[derive(Debug)]
 pub struct Github {
     name: String,
@@ -140,6 +327,65 @@ impl Github {
             .map(|commit| commit.author)
             .context("failed to deserialize GitHub commit details")
     }
+
+    fn api_url(&self, path: &str) -> Result<String> {
+        let Some(host) = self.base_url.host_str() else {
+            bail!("failed to get host from github base url");
+        };
+        Ok(format!("https://api.{host}/{path}"))
+    }
+
+    async fn send_request<T: DeserializeOwned>(
+        &self,
+        request: Builder,
+        body: AsyncBody,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<T> {
+        let mut request = request
+            .header("Content-Type", "application/json")
+            .follow_redirects(http_client::RedirectPolicy::FollowAll);
+        if let Ok(github_token) = std::env::var("GITHUB_TOKEN") {
+            request = request.header("Authorization", format!("Bearer {}", github_token));
+        }
+
+        let mut response = client.send(request.body(body)?).await?;
+
+        let mut body = Vec::new();
+        response.body_mut().read_to_end(&mut body).await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            bail!(
+                "GitHub rejected the request. Set GITHUB_TOKEN to a token with access to the repository."
+            );
+        }
+        if !response.status().is_success() {
+            let text = String::from_utf8_lossy(body.as_slice());
+            bail!(
+                "status error {}, response: {text:?}",
+                response.status().as_u16()
+            );
+        }
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn graphql<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<T> {
+        let url = self.api_url("graphql")?;
+        let body = serde_json::to_string(&json!({ "query": query, "variables": variables }))?;
+        let response = self
+            .send_request::<GraphQlResponse<T>>(Request::post(&url), body.into(), client)
+            .await
+            .with_context(|| format!("error querying GitHub at {url:?}"))?;
+        if let Some(error) = response.errors.into_iter().next() {
+            bail!("GitHub returned an error: {}", error.message);
+        }
+        response.data.context("GitHub returned an empty response")
+    }
 }
 
 #[async_trait]
@@ -233,7 +479,11 @@ impl GitHostingProvider for Github {
         let path = format!("/{}/{}/pull/{}", remote.owner, remote.repo, number);
         url.set_path(&path);
 
-        Some(PullRequest { number, url })
+        Some(PullRequest {
+            number,
+            url,
+            head_sha: None,
+        })
     }
 
     async fn commit_author_avatar_url(
@@ -255,16 +505,163 @@ impl GitHostingProvider for Github {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    fn supports_pull_request_reviews(&self) -> bool {
+        true
+    }
+
+    async fn pull_request_for_branch(
+        &self,
+        repo_owner: &str,
+        repo: &str,
+        head_owner: &str,
+        branch: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<PullRequest>> {
+        let url = self.api_url(&format!(
+            "repos/{repo_owner}/{repo}/pulls?state=open&head={}",
+            urlencoding::encode(&format!("{head_owner}:{branch}"))
+        ))?;
+        let pull_requests = self
+            .send_request::<Vec<PullRequestDetails>>(
+                Request::get(&url),
+                AsyncBody::default(),
+                &http_client,
+            )
+            .await
+            .with_context(|| format!("error fetching GitHub pull requests at {url:?}"))?;
+        pull_requests
+            .into_iter()
+            .next()
+            .map(|pull_request| {
+                Ok(PullRequest {
+                    number: pull_request.number,
+                    url: Url::parse(&pull_request.html_url)?,
+                    head_sha: Some(pull_request.head.sha.into()),
+                })
+            })
+            .transpose()
+    }
+
+    async fn pull_request_review_threads(
+        &self,
+        repo_owner: &str,
+        repo: &str,
+        pull_request_number: u32,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Vec<ReviewThread>> {
+        let mut threads = Vec::new();
+        let mut cursor = None;
+        loop {
+            let data = self
+                .graphql::<ReviewThreadsData>(
+                    REVIEW_THREADS_QUERY,
+                    json!({
+                        "owner": repo_owner,
+                        "repo": repo,
+                        "number": pull_request_number,
+                        "cursor": cursor,
+                    }),
+                    &http_client,
+                )
+                .await?;
+            let review_threads = data
+                .repository
+                .and_then(|repository| repository.pull_request)
+                .with_context(|| format!("pull request #{pull_request_number} not found"))?
+                .review_threads;
+
+            for thread in review_threads.nodes {
+                let Some(path) = RepoPath::new(&thread.path).log_err() else {
+                    continue;
+                };
+                let mut comments = thread.comments.nodes;
+                let mut comments_cursor = next_cursor(thread.comments.page_info);
+                while let Some(cursor) = comments_cursor {
+                    let data = self
+                        .graphql::<ReviewThreadCommentsData>(
+                            REVIEW_THREAD_COMMENTS_QUERY,
+                            json!({ "threadId": thread.id, "cursor": cursor }),
+                            &http_client,
+                        )
+                        .await?;
+                    let page = data
+                        .node
+                        .with_context(|| format!("review thread {} not found", thread.id))?
+                        .comments;
+                    comments.extend(page.nodes);
+                    comments_cursor = next_cursor(page.page_info);
+                }
+                threads.push(ReviewThread {
+                    id: thread.id.into(),
+                    path,
+                    // Comments on removed lines refer to lines in the old
+                    // version of the file, so they can't be placed by line.
+                    line: thread
+                        .line
+                        .filter(|_| thread.diff_side.as_deref() != Some("LEFT")),
+                    is_resolved: thread.is_resolved,
+                    is_outdated: thread.is_outdated,
+                    comments: comments.into_iter().map(ReviewComment::from).collect(),
+                });
+            }
+
+            cursor = next_cursor(review_threads.page_info);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(threads)
+    }
+
+    async fn reply_to_review_thread(
+        &self,
+        thread_id: &str,
+        body: &str,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<ReviewComment> {
+        let data = self
+            .graphql::<AddReplyData>(
+                ADD_REPLY_MUTATION,
+                json!({ "threadId": thread_id, "body": body }),
+                &http_client,
+            )
+            .await?;
+        Ok(data.add_pull_request_review_thread_reply.comment.into())
+    }
+
+    async fn set_review_thread_resolved(
+        &self,
+        thread_id: &str,
+        resolved: bool,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<()> {
+        let mutation = if resolved {
+            RESOLVE_THREAD_MUTATION
+        } else {
+            UNRESOLVE_THREAD_MUTATION
+        };
+        self.graphql::<serde_json::Value>(mutation, json!({ "threadId": thread_id }), &http_client)
+            .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use git::repository::repo_path;
+    use http_client::{FakeHttpClient, Response};
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
     use super::*;
 
+    async fn read_json_body(request: Request<AsyncBody>) -> serde_json::Value {
+        let mut body = String::new();
+        request.into_body().read_to_string(&mut body).await.unwrap();
+        serde_json::from_str(&body).unwrap()
+    }
+
     #[test]
     fn test_remote_url_with_root_slash() {
         let remote_url = "git@github.com:/zed-industries/zed";
@@ -526,4 +923,231 @@ mod tests {
         let expected_url = "https://github.com/zed-industries/nonexistent/blob/3ef1539900037dd3601be7149b2b39ed6d0ce3db/app/blog/%5Bslug%5D/page.tsx#L8";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
+
+    #[test]
+    fn test_github_pull_request_for_branch() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            assert_eq!(
+                request.uri().to_string(),
+                "https://api.github.com/repos/zed-industries/zed/pulls?state=open&head=contributor%3Afix-blame"
+            );
+            Ok(Response::new(
+                json!([{
+                    "number": 42,
+                    "html_url": "https://github.com/zed-industries/zed/pull/42",
+                    "head": { "sha": "b7e1a0c" }
+                }])
+                .to_string()
+                .into(),
+            ))
+        });
+
+        let pull_request =
+            futures::executor::block_on(Github::public_instance().pull_request_for_branch(
+                "zed-industries",
+                "zed",
+                "contributor",
+                "fix-blame",
+                http_client,
+            ))
+            .unwrap();
+        assert_eq!(
+            pull_request,
+            Some(PullRequest {
+                number: 42,
+                url: Url::parse("https://github.com/zed-industries/zed/pull/42").unwrap(),
+                head_sha: Some("b7e1a0c".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_github_pull_request_review_threads() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.uri().to_string(), "https://api.github.com/graphql");
+            let body = read_json_body(request).await;
+            assert_eq!(body["variables"]["number"], 42);
+
+            let response = if !body["variables"]["threadId"].is_null() {
+                assert_eq!(body["variables"]["threadId"], "thread-1");
+                assert_eq!(body["variables"]["cursor"], "comments-2");
+                json!({
+                    "data": { "node": { "comments": {
+                        "pageInfo": { "hasNextPage": false, "endCursor": null },
+                        "nodes": [{
+                            "id": "comment-3",
+                            "body": "Thanks!",
+                            "url": null,
+                            "author": { "login": "reviewer" }
+                        }]
+                    }}}
+                })
+            } else if body["variables"]["cursor"].is_null() {
+                json!({
+                    "data": { "repository": { "pullRequest": { "reviewThreads": {
+                        "pageInfo": { "hasNextPage": true, "endCursor": "page-2" },
+                        "nodes": [{
+                            "id": "thread-1",
+                            "path": "src/main.rs",
+                            "line": 10,
+                            "diffSide": "RIGHT",
+                            "isResolved": false,
+                            "isOutdated": false,
+                            "comments": {
+                                "pageInfo": { "hasNextPage": true, "endCursor": "comments-2" },
+                                "nodes": [
+                                    {
+                                        "id": "comment-1",
+                                        "body": "Can this be simplified?",
+                                        "url": "https://github.com/zed-industries/zed/pull/42#discussion_r1",
+                                        "author": { "login": "reviewer" }
+                                    },
+                                    {
+                                        "id": "comment-2",
+                                        "body": "Done.",
+                                        "url": null,
+                                        "author": null
+                                    }
+                                ]
+                            }
+                        }]
+                    }}}}
+                })
+            } else {
+                assert_eq!(body["variables"]["cursor"], "page-2");
+                json!({
+                    "data": { "repository": { "pullRequest": { "reviewThreads": {
+                        "pageInfo": { "hasNextPage": false, "endCursor": null },
+                        "nodes": [{
+                            "id": "thread-2",
+                            "path": "README.md",
+                            "line": 3,
+                            "diffSide": "LEFT",
+                            "isResolved": true,
+                            "isOutdated": true,
+                            "comments": { "nodes": [] }
+                        }]
+                    }}}}
+                })
+            };
+            Ok(Response::new(response.to_string().into()))
+        });
+
+        let threads =
+            futures::executor::block_on(Github::public_instance().pull_request_review_threads(
+                "zed-industries",
+                "zed",
+                42,
+                http_client,
+            ))
+            .unwrap();
+        assert_eq!(
+            threads,
+            vec![
+                ReviewThread {
+                    id: "thread-1".into(),
+                    path: repo_path("src/main.rs"),
+                    line: Some(10),
+                    is_resolved: false,
+                    is_outdated: false,
+                    comments: vec![
+                        ReviewComment {
+                            id: "comment-1".into(),
+                            author: "reviewer".into(),
+                            body: "Can this be simplified?".into(),
+                            url: Some(
+                                Url::parse(
+                                    "https://github.com/zed-industries/zed/pull/42#discussion_r1"
+                                )
+                                .unwrap()
+                            ),
+                        },
+                        ReviewComment {
+                            id: "comment-2".into(),
+                            author: "ghost".into(),
+                            body: "Done.".into(),
+                            url: None,
+                        },
+                        ReviewComment {
+                            id: "comment-3".into(),
+                            author: "reviewer".into(),
+                            body: "Thanks!".into(),
+                            url: None,
+                        },
+                    ],
+                },
+                ReviewThread {
+                    id: "thread-2".into(),
+                    path: repo_path("README.md"),
+                    line: None,
+                    is_resolved: true,
+                    is_outdated: true,
+                    comments: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_github_reply_to_and_resolve_review_thread() {
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            let body = read_json_body(request).await;
+            let query = body["query"].as_str().unwrap();
+            assert_eq!(body["variables"]["threadId"], "thread-1");
+
+            let response = if query.contains("addPullRequestReviewThreadReply") {
+                assert_eq!(body["variables"]["body"], "Fixed, thanks!");
+                json!({ "data": { "addPullRequestReviewThreadReply": { "comment": {
+                    "id": "comment-3",
+                    "body": "Fixed, thanks!",
+                    "url": null,
+                    "author": { "login": "author" }
+                }}}})
+            } else if query.contains("unresolveReviewThread") {
+                json!({
+                    "data": null,
+                    "errors": [{ "message": "Resource not accessible by integration" }]
+                })
+            } else {
+                assert!(query.contains("resolveReviewThread"));
+                json!({ "data": { "resolveReviewThread": { "thread": { "id": "thread-1" } } } })
+            };
+            Ok(Response::new(response.to_string().into()))
+        });
+
+        let github = Github::public_instance();
+        let comment = futures::executor::block_on(github.reply_to_review_thread(
+            "thread-1",
+            "Fixed, thanks!",
+            http_client.clone(),
+        ))
+        .unwrap();
+        assert_eq!(
+            comment,
+            ReviewComment {
+                id: "comment-3".into(),
+                author: "author".into(),
+                body: "Fixed, thanks!".into(),
+                url: None,
+            }
+        );
+
+        futures::executor::block_on(github.set_review_thread_resolved(
+            "thread-1",
+            true,
+            http_client.clone(),
+        ))
+        .unwrap();
+
+        let error = futures::executor::block_on(github.set_review_thread_resolved(
+            "thread-1",
+            false,
+            http_client,
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "GitHub returned an error: Resource not accessible by integration"
+        );
+    }
 }
@@ -2,7 +2,8 @@ use anyhow::{Context as _, Result};
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::display_map::{BlockPlacement, BlockProperties, BlockStyle};
 use editor::{
-    Editor, EditorEvent, ExcerptId, ExcerptRange, MultiBuffer, multibuffer_context_lines,
+    Editor, EditorEvent, ExcerptId, ExcerptRange, MultiBuffer, SelectionEffects,
+    multibuffer_context_lines, scroll::Autoscroll,
 };
 use git::repository::{CommitDetails, CommitDiff, RepoPath};
 use git::{GitHostingProviderRegistry, GitRemote, parse_git_remote_url};
@@ -12,8 +13,8 @@ use gpui::{
     PromptLevel, Render, Styled, Task, WeakEntity, Window, actions,
 };
 use language::{
-    Anchor, Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding, OffsetRangeExt as _,
-    ReplicaId, Rope, TextBuffer,
+    Anchor, Bias, Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding,
+    OffsetRangeExt as _, Point, ReplicaId, Rope, TextBuffer,
 };
 use multi_buffer::PathKey;
 use project::{Project, WorktreeId, git_store::Repository};
@@ -81,6 +82,51 @@ impl CommitView {
         file_filter: Option<RepoPath>,
         window: &mut Window,
         cx: &mut App,
+    ) {
+        Self::open_internal(
+            commit_sha,
+            repo,
+            workspace,
+            stash,
+            file_filter,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    /// Opens the commit and scrolls to the given zero-based row of the file at `path`,
+    /// as of that commit.
+    pub fn open_at(
+        commit_sha: String,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        path: RepoPath,
+        row: u32,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        Self::open_internal(
+            commit_sha,
+            repo,
+            workspace,
+            None,
+            None,
+            Some((path, row)),
+            window,
+            cx,
+        );
+    }
+
+    fn open_internal(
+        commit_sha: String,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        stash: Option<usize>,
+        file_filter: Option<RepoPath>,
+        scroll_to: Option<(RepoPath, u32)>,
+        window: &mut Window,
+        cx: &mut App,
     ) {
         let commit_diff = repo
             .update(cx, |repo, _| repo.load_commit_diff(commit_sha.clone()))
@@ -112,6 +158,7 @@ impl CommitView {
                                 repo,
                                 project.clone(),
                                 stash,
+                                scroll_to,
                                 window,
                                 cx,
                             )
@@ -142,6 +189,7 @@ impl CommitView {
         repository: Entity<Repository>,
         project: Entity<Project>,
         stash: Option<usize>,
+        scroll_to: Option<(RepoPath, u32)>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -167,7 +215,8 @@ impl CommitView {
         let repository_clone = repository.clone();
         let commit_message = commit.message.clone();
 
-        cx.spawn(async move |this, cx| {
+        cx.spawn_in(window, async move |this, cx| {
+            let mut scroll_target = None;
             for file in commit_diff.files {
                 let is_deleted = file.new_text.is_none();
                 let new_text = file.new_text.unwrap_or_default();
@@ -189,8 +238,9 @@ impl CommitView {
                 let display_name: Arc<str> =
                     Arc::from(format!("{short_sha} - {file_name}").into_boxed_str());
 
+                let file_path = file.path.clone();
                 let file = Arc::new(GitBlob {
-                    path: file.path.clone(),
+                    path: file_path.clone(),
                     is_deleted,
                     worktree_id,
                     display_name,
@@ -199,6 +249,11 @@ impl CommitView {
                 let buffer = build_buffer(new_text, file, &language_registry, cx).await?;
                 let buffer_diff =
                     build_buffer_diff(old_text, &buffer, &language_registry, cx).await?;
+                if let Some((path, row)) = &scroll_to
+                    && *path == file_path
+                {
+                    scroll_target = Some((buffer.clone(), *row));
+                }
 
                 this.update(cx, |this, cx| {
                     this.multibuffer.update(cx, |multibuffer, cx| {
@@ -280,6 +335,28 @@ impl CommitView {
                 });
             })?;
 
+            if let Some((buffer, row)) = scroll_target {
+                this.update_in(cx, |this, window, cx| {
+                    this.editor.update(cx, |editor, cx| {
+                        let buffer_snapshot = buffer.read(cx).snapshot();
+                        let point = buffer_snapshot.clip_point(Point::new(row, 0), Bias::Left);
+                        let anchor = editor.buffer().read(cx).buffer_anchor_to_anchor(
+                            &buffer,
+                            buffer_snapshot.anchor_before(point),
+                            cx,
+                        );
+                        if let Some(anchor) = anchor {
+                            editor.change_selections(
+                                SelectionEffects::scroll(Autoscroll::center()),
+                                window,
+                                cx,
+                                |selections| selections.select_anchor_ranges([anchor..anchor]),
+                            );
+                        }
+                    });
+                })?;
+            }
+
             anyhow::Ok(())
         })
         .detach();
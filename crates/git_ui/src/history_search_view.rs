@@ -0,0 +1,586 @@
+use editor::Editor;
+use git::history_search::{HistorySearchHunk, HistorySearchMatch, HistorySearchQuery};
+use git::repository::RepoPath;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, Render,
+    Task, UniformListScrollHandle, WeakEntity, Window, uniform_list,
+};
+use project::git_store::{GitStore, Repository};
+use std::ops::Range;
+use time::OffsetDateTime;
+use ui::{Chip, ListItem, WithScrollbar, prelude::*};
+use util::ResultExt;
+use workspace::{Item, Workspace, item::ItemEvent};
+use zed_actions::git::SearchHistory;
+
+use crate::commit_view::CommitView;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(|workspace, action: &SearchHistory, window, cx| {
+            HistorySearchView::deploy(workspace, action, window, cx);
+        });
+    })
+    .detach();
+}
+
+/// The most commits a search lists, as searching the history of a large repository
+/// for a common string can take a long time.
+const MAX_COMMITS: usize = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HistorySearchRow {
+    Commit { commit_ix: usize },
+    Hunk { commit_ix: usize, hunk_ix: usize },
+}
+
+/// Lists the commits whose changes add or remove some text, like `git log -S`, along
+/// with the matching hunks.
+pub struct HistorySearchView {
+    query_editor: Entity<Editor>,
+    query: HistorySearchQuery,
+    matches: Vec<HistorySearchMatch>,
+    rows: Vec<HistorySearchRow>,
+    error: Option<SharedString>,
+    pending_search: Option<Task<()>>,
+    repository: WeakEntity<Repository>,
+    git_store: WeakEntity<GitStore>,
+    workspace: WeakEntity<Workspace>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+}
+
+impl HistorySearchView {
+    fn deploy(
+        workspace: &mut Workspace,
+        action: &SearchHistory,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = workspace.project().read(cx);
+        let git_store = project.git_store().clone();
+        let Some(repository) = project.active_repository(cx) else {
+            return;
+        };
+
+        let query = HistorySearchQuery {
+            pattern: action.query.clone(),
+            is_regex: action.regex,
+            case_sensitive: action.case_sensitive,
+            paths: action
+                .paths
+                .iter()
+                .filter_map(|path| RepoPath::new(path).log_err())
+                .collect(),
+            limit: Some(MAX_COMMITS),
+        };
+
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items()
+            .find_map(|item| item.downcast::<HistorySearchView>())
+            .filter(|view| view.read(cx).repository == repository.downgrade());
+        let view = if let Some(view) = existing {
+            workspace.activate_item(&view, true, true, window, cx);
+            view
+        } else {
+            let view = cx.new(|cx| {
+                HistorySearchView::new(
+                    repository.downgrade(),
+                    git_store.downgrade(),
+                    workspace.weak_handle(),
+                    window,
+                    cx,
+                )
+            });
+            workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, window, cx);
+            view
+        };
+
+        view.update(cx, |view, cx| {
+            view.query_editor.update(cx, |editor, cx| {
+                editor.set_text(query.pattern.as_str(), window, cx);
+            });
+            view.search(query, cx);
+            window.focus(&view.query_editor.focus_handle(cx));
+        });
+    }
+
+    fn new(
+        repository: WeakEntity<Repository>,
+        git_store: WeakEntity<GitStore>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search commits that add or remove…", window, cx);
+            editor
+        });
+        Self {
+            query_editor,
+            query: HistorySearchQuery::default(),
+            matches: Vec::new(),
+            rows: Vec::new(),
+            error: None,
+            pending_search: None,
+            repository,
+            git_store,
+            workspace,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        let query = HistorySearchQuery {
+            pattern: self.query_editor.read(cx).text(cx),
+            ..self.query.clone()
+        };
+        self.search(query, cx);
+    }
+
+    fn search(&mut self, query: HistorySearchQuery, cx: &mut Context<Self>) {
+        self.query = query.clone();
+        self.matches.clear();
+        self.rows.clear();
+        self.error = None;
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+
+        if query.pattern.is_empty() {
+            self.pending_search = None;
+            return;
+        }
+
+        let Some(repository) = self.repository.upgrade() else {
+            return;
+        };
+        let search = self
+            .git_store
+            .update(cx, |git_store, cx| {
+                git_store.search_history(&repository, query, cx)
+            })
+            .ok();
+        self.pending_search = Some(cx.spawn(async move |this, cx| {
+            let Some(search) = search else {
+                return;
+            };
+            let result = search.await;
+            this.update(cx, |this, cx| {
+                this.pending_search = None;
+                match result {
+                    Ok(matches) => this.set_matches(matches),
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn set_matches(&mut self, matches: Vec<HistorySearchMatch>) {
+        self.rows = matches
+            .iter()
+            .enumerate()
+            .flat_map(|(commit_ix, commit)| {
+                std::iter::once(HistorySearchRow::Commit { commit_ix }).chain(
+                    (0..commit.hunks.len())
+                        .map(move |hunk_ix| HistorySearchRow::Hunk { commit_ix, hunk_ix }),
+                )
+            })
+            .collect();
+        self.matches = matches;
+    }
+
+    fn open_row(&self, row: HistorySearchRow, window: &mut Window, cx: &mut App) {
+        match row {
+            HistorySearchRow::Commit { commit_ix } => {
+                let commit = &self.matches[commit_ix];
+                CommitView::open(
+                    commit.sha.to_string(),
+                    self.repository.clone(),
+                    self.workspace.clone(),
+                    None,
+                    None,
+                    window,
+                    cx,
+                );
+            }
+            HistorySearchRow::Hunk { commit_ix, hunk_ix } => {
+                let commit = &self.matches[commit_ix];
+                let hunk = &commit.hunks[hunk_ix];
+                CommitView::open_at(
+                    commit.sha.to_string(),
+                    self.repository.clone(),
+                    self.workspace.clone(),
+                    hunk.path.clone(),
+                    hunk.new_start.saturating_sub(1),
+                    window,
+                    cx,
+                );
+            }
+        }
+    }
+
+    fn render_rows(
+        &mut self,
+        range: Range<usize>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        range
+            .filter_map(|ix| {
+                let row = *self.rows.get(ix)?;
+                let item = match row {
+                    HistorySearchRow::Commit { commit_ix } => {
+                        self.render_commit(ix, &self.matches[commit_ix])
+                    }
+                    HistorySearchRow::Hunk { commit_ix, hunk_ix } => {
+                        self.render_hunk(ix, &self.matches[commit_ix].hunks[hunk_ix], cx)
+                    }
+                };
+                Some(
+                    item.on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_row(row, window, cx);
+                    }))
+                    .into_any_element(),
+                )
+            })
+            .collect()
+    }
+
+    fn render_commit(&self, ix: usize, commit: &HistorySearchMatch) -> ListItem {
+        let short_sha = commit.sha.get(..7).unwrap_or(&commit.sha).to_string();
+        let commit_time = OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::UNIX_EPOCH);
+        let relative_timestamp = time_format::format_localized_timestamp(
+            commit_time,
+            OffsetDateTime::now_utc(),
+            time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC),
+            time_format::TimestampFormat::Relative,
+        );
+
+        ListItem::new(("history-search-row", ix)).child(
+            h_flex()
+                .h_8()
+                .w_full()
+                .pl_0p5()
+                .pr_2p5()
+                .gap_2()
+                .child(Chip::new(short_sha))
+                .child(
+                    h_flex()
+                        .min_w_0()
+                        .w_full()
+                        .gap_1()
+                        .child(
+                            Label::new(commit.author_name.clone())
+                                .size(LabelSize::Small)
+                                .truncate(),
+                        )
+                        .child(
+                            Label::new(commit.subject.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                )
+                .child(
+                    Label::new(relative_timestamp)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+
+    fn render_hunk(&self, ix: usize, hunk: &HistorySearchHunk, cx: &App) -> ListItem {
+        let pattern = self.query.pattern.to_lowercase();
+        let line = hunk
+            .lines
+            .iter()
+            .find(|line| line.to_lowercase().contains(&pattern))
+            .or(hunk.lines.first())
+            .map(|line| line.trim_end().to_string())
+            .unwrap_or_default();
+        let line_color = if line.starts_with('+') {
+            Color::Created
+        } else {
+            Color::Deleted
+        };
+
+        ListItem::new(("history-search-row", ix))
+            .indent_level(1)
+            .child(
+                h_flex()
+                    .h_6()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        div().flex_none().child(
+                            Label::new(format!("{}:{}", hunk.path.as_unix_str(), hunk.new_start))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .child(
+                        Label::new(line)
+                            .size(LabelSize::Small)
+                            .color(line_color)
+                            .buffer_font(cx)
+                            .truncate(),
+                    ),
+            )
+    }
+
+    fn status_text(&self) -> SharedString {
+        if let Some(error) = &self.error {
+            error.clone()
+        } else if self.pending_search.is_some() {
+            "Searching…".into()
+        } else if self.query.pattern.is_empty() {
+            "".into()
+        } else if self.matches.len() >= MAX_COMMITS {
+            format!("First {MAX_COMMITS} commits").into()
+        } else if self.matches.len() == 1 {
+            "1 commit".into()
+        } else {
+            format!("{} commits", self.matches.len()).into()
+        }
+    }
+}
+
+impl EventEmitter<ItemEvent> for HistorySearchView {}
+
+impl Focusable for HistorySearchView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HistorySearchView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let status_color = if self.error.is_some() {
+            Color::Error
+        } else {
+            Color::Muted
+        };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .h(rems_from_px(41.))
+                    .pl_3()
+                    .pr_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        div()
+                            .flex_1()
+                            .on_action(cx.listener(Self::confirm))
+                            .child(self.query_editor.clone()),
+                    )
+                    .child(
+                        Label::new(self.status_text())
+                            .size(LabelSize::Small)
+                            .color(status_color),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .size_full()
+                    .child(
+                        uniform_list(
+                            "history-search-list",
+                            self.rows.len(),
+                            cx.processor(Self::render_rows),
+                        )
+                        .flex_1()
+                        .size_full()
+                        .track_scroll(&self.scroll_handle),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+    }
+}
+
+impl Item for HistorySearchView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        if self.query.pattern.is_empty() {
+            "History Search".into()
+        } else {
+            format!("History: {}", self.query.pattern).into()
+        }
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("history search")
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        _workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.query_editor.focus_handle(cx));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn commit(sha: &str, subject: &str, hunks: &[(&str, &str)]) -> HistorySearchMatch {
+        HistorySearchMatch {
+            sha: sha.repeat(40).into(),
+            subject: subject.to_string().into(),
+            commit_timestamp: 0,
+            author_name: "Alice".into(),
+            hunks: hunks
+                .iter()
+                .map(|(path, line)| HistorySearchHunk {
+                    path: git::repository::repo_path(path),
+                    old_start: 3,
+                    new_start: 3,
+                    lines: vec![line.to_string()],
+                })
+                .collect(),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_history_search(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "config.toml": "retries = 3\n",
+            }),
+        )
+        .await;
+        fs.set_history_for_repo(
+            path!("/project/.git").as_ref(),
+            vec![
+                commit("b", "Drop the timeout", &[("config.toml", "-timeout = 30")]),
+                commit("a", "Add retries", &[("config.toml", "+retries = 3")]),
+                commit(
+                    "c",
+                    "Add a timeout",
+                    &[("config.toml", "+timeout = 30"), ("README.md", "+Timeouts")],
+                ),
+            ],
+        );
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        workspace.update_in(cx, |_, window, cx| {
+            window.dispatch_action(
+                Box::new(SearchHistory {
+                    query: "timeout".into(),
+                    paths: vec!["config.toml".into()],
+                    ..Default::default()
+                }),
+                cx,
+            );
+        });
+        cx.run_until_parked();
+
+        let view = active_history_search_view(&workspace, cx);
+        view.read_with(cx, |view, _| {
+            assert_eq!(
+                view.matches
+                    .iter()
+                    .map(|commit| commit.subject.as_ref())
+                    .collect::<Vec<_>>(),
+                ["Drop the timeout", "Add a timeout"]
+            );
+            assert_eq!(
+                view.rows,
+                [
+                    HistorySearchRow::Commit { commit_ix: 0 },
+                    HistorySearchRow::Hunk {
+                        commit_ix: 0,
+                        hunk_ix: 0
+                    },
+                    HistorySearchRow::Commit { commit_ix: 1 },
+                    HistorySearchRow::Hunk {
+                        commit_ix: 1,
+                        hunk_ix: 0
+                    },
+                ]
+            );
+        });
+
+        // Refining the query in the view keeps the path filter.
+        view.update_in(cx, |view, window, cx| {
+            view.query_editor.update(cx, |editor, cx| {
+                editor.set_text("retries", window, cx);
+            });
+            view.confirm(&menu::Confirm, window, cx);
+        });
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            assert_eq!(
+                view.query.paths,
+                [git::repository::repo_path("config.toml")]
+            );
+            assert_eq!(
+                view.matches
+                    .iter()
+                    .map(|commit| commit.subject.as_ref())
+                    .collect::<Vec<_>>(),
+                ["Add retries"]
+            );
+        });
+    }
+
+    fn active_history_search_view(
+        workspace: &Entity<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Entity<HistorySearchView> {
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_item(cx)
+                .and_then(|item| item.downcast::<HistorySearchView>())
+                .expect("history search view should be active")
+        })
+    }
+}
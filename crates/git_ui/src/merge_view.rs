@@ -0,0 +1,846 @@
+//! MergeView provides a three-pane UI for resolving a three-way merge, as used
+//! by `zed --merge BASE LOCAL REMOTE MERGED` when Zed is configured as git's
+//! `merge.tool`.
+
+use crate::file_diff_view::build_buffer_diff;
+use anyhow::Result;
+use buffer_diff::BufferDiff;
+use editor::{
+    Editor, EditorEvent, MultiBuffer, MultiBufferOffset, SelectionEffects, scroll::Autoscroll,
+};
+use gpui::{
+    AnyElement, App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, Render, Task, Window, actions,
+};
+use language::{Anchor, Buffer, TextBufferSnapshot, ToOffset as _};
+use project::{ConflictRegion, ConflictSet, Project};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+};
+use ui::{KeyBinding, Tooltip, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, SaveOptions, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+actions!(
+    merge_view,
+    [
+        /// Resolves the conflict at the cursor using the local (ours) side.
+        AcceptOurs,
+        /// Resolves the conflict at the cursor using the remote (theirs) side.
+        AcceptTheirs,
+        /// Resolves the conflict at the cursor by keeping both sides, local first.
+        AcceptBoth,
+        /// Resolves the conflict at the cursor using the common ancestor.
+        ///
+        /// Only available when the conflict markers include a base section,
+        /// i.e. with `merge.conflictStyle` set to `diff3` or `zdiff3`.
+        AcceptBase,
+        /// Moves the cursor to the next unresolved conflict.
+        NextConflict,
+        /// Moves the cursor to the previous unresolved conflict.
+        PreviousConflict,
+    ]
+);
+
+pub struct MergeView {
+    local_editor: Entity<Editor>,
+    merged_editor: Entity<Editor>,
+    remote_editor: Entity<Editor>,
+    local_buffer: Entity<Buffer>,
+    merged_buffer: Entity<Buffer>,
+    remote_buffer: Entity<Buffer>,
+    conflicts: Arc<[ConflictRegion]>,
+}
+
+impl MergeView {
+    pub fn open(
+        base_path: PathBuf,
+        local_path: PathBuf,
+        remote_path: PathBuf,
+        merged_path: PathBuf,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace = workspace.weak_handle();
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let base_buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(&base_path, cx))?
+                .await?;
+            let local_buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(&local_path, cx))?
+                .await?;
+            let remote_buffer = project
+                .update(cx, |project, cx| {
+                    project.open_local_buffer(&remote_path, cx)
+                })?
+                .await?;
+            let merged_buffer = project
+                .update(cx, |project, cx| {
+                    project.open_local_buffer(&merged_path, cx)
+                })?
+                .await?;
+
+            let local_diff = build_buffer_diff(&base_buffer, &local_buffer, cx).await?;
+            let remote_diff = build_buffer_diff(&base_buffer, &remote_buffer, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_view = cx.new(|cx| {
+                    MergeView::new(
+                        local_buffer,
+                        local_diff,
+                        remote_buffer,
+                        remote_diff,
+                        merged_buffer,
+                        project.clone(),
+                        window,
+                        cx,
+                    )
+                });
+
+                let pane = workspace.active_pane();
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(merge_view.clone()), true, true, None, window, cx);
+                });
+
+                merge_view
+            })
+        })
+    }
+
+    pub fn new(
+        local_buffer: Entity<Buffer>,
+        local_diff: Entity<BufferDiff>,
+        remote_buffer: Entity<Buffer>,
+        remote_diff: Entity<BufferDiff>,
+        merged_buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let local_editor = side_editor(
+            local_buffer.clone(),
+            local_diff,
+            project.clone(),
+            window,
+            cx,
+        );
+        let remote_editor = side_editor(
+            remote_buffer.clone(),
+            remote_diff,
+            project.clone(),
+            window,
+            cx,
+        );
+        let merged_editor = cx.new(|cx| {
+            let mut editor = Editor::for_buffer(merged_buffer.clone(), Some(project), window, cx);
+            editor.set_should_serialize(false, cx);
+            editor
+        });
+
+        cx.subscribe(&merged_editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone())
+        })
+        .detach();
+        cx.subscribe(&merged_buffer, |this, _, event, cx| {
+            if let language::BufferEvent::Edited = event {
+                this.reparse_conflicts(cx);
+            }
+        })
+        .detach();
+
+        let mut this = Self {
+            local_editor,
+            merged_editor,
+            remote_editor,
+            local_buffer,
+            merged_buffer,
+            remote_buffer,
+            conflicts: Arc::default(),
+        };
+        this.reparse_conflicts(cx);
+        if !this.conflicts.is_empty() {
+            this.select_conflict(0, window, cx);
+        }
+        this
+    }
+
+    fn reparse_conflicts(&mut self, cx: &mut Context<Self>) {
+        let snapshot = self.merged_buffer.read(cx).text_snapshot();
+        self.conflicts = ConflictSet::parse(&snapshot).conflicts;
+        cx.notify();
+    }
+
+    fn cursor_offset(&self, cx: &App) -> usize {
+        let snapshot = self.merged_buffer.read(cx).text_snapshot();
+        self.merged_editor
+            .read(cx)
+            .selections
+            .newest_anchor()
+            .head()
+            .text_anchor
+            .to_offset(&snapshot)
+    }
+
+    fn select_conflict(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let snapshot = self.merged_buffer.read(cx).text_snapshot();
+        let offset = MultiBufferOffset(self.conflicts[ix].range.start.to_offset(&snapshot));
+        self.merged_editor.update(cx, |editor, cx| {
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_ranges([offset..offset]),
+            );
+        });
+    }
+
+    fn resolve_conflict_at_cursor(
+        &mut self,
+        ranges: impl FnOnce(&ConflictRegion) -> Option<Vec<Range<Anchor>>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.merged_buffer.read(cx).text_snapshot();
+        let Some(ix) = conflict_at_or_after(&self.conflicts, self.cursor_offset(cx), &snapshot)
+        else {
+            return;
+        };
+        let conflict = self.conflicts[ix].clone();
+        let Some(ranges) = ranges(&conflict) else {
+            return;
+        };
+        conflict.resolve(self.merged_buffer.clone(), &ranges, cx);
+
+        self.reparse_conflicts(cx);
+        let snapshot = self.merged_buffer.read(cx).text_snapshot();
+        if let Some(ix) = conflict_at_or_after(&self.conflicts, self.cursor_offset(cx), &snapshot) {
+            self.select_conflict(ix, window, cx);
+        }
+    }
+
+    fn accept_ours(&mut self, _: &AcceptOurs, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_conflict_at_cursor(|conflict| Some(vec![conflict.ours.clone()]), window, cx);
+    }
+
+    fn accept_theirs(&mut self, _: &AcceptTheirs, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_conflict_at_cursor(|conflict| Some(vec![conflict.theirs.clone()]), window, cx);
+    }
+
+    fn accept_both(&mut self, _: &AcceptBoth, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_conflict_at_cursor(
+            |conflict| Some(vec![conflict.ours.clone(), conflict.theirs.clone()]),
+            window,
+            cx,
+        );
+    }
+
+    fn accept_base(&mut self, _: &AcceptBase, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_conflict_at_cursor(|conflict| Some(vec![conflict.base.clone()?]), window, cx);
+    }
+
+    fn next_conflict(&mut self, _: &NextConflict, window: &mut Window, cx: &mut Context<Self>) {
+        if self.conflicts.is_empty() {
+            return;
+        }
+        let snapshot = self.merged_buffer.read(cx).text_snapshot();
+        let cursor = self.cursor_offset(cx);
+        let ix = self
+            .conflicts
+            .iter()
+            .position(|conflict| conflict.range.start.to_offset(&snapshot) > cursor)
+            .unwrap_or(0);
+        self.select_conflict(ix, window, cx);
+    }
+
+    fn previous_conflict(
+        &mut self,
+        _: &PreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.conflicts.is_empty() {
+            return;
+        }
+        let snapshot = self.merged_buffer.read(cx).text_snapshot();
+        let cursor = self.cursor_offset(cx);
+        let ix = self
+            .conflicts
+            .iter()
+            .rposition(|conflict| conflict.range.start.to_offset(&snapshot) < cursor)
+            .unwrap_or(self.conflicts.len() - 1);
+        self.select_conflict(ix, window, cx);
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.merged_editor.focus_handle(cx);
+        let status = match self.conflicts.len() {
+            0 => "No conflicts remaining".to_string(),
+            1 => "1 conflict remaining".to_string(),
+            count => format!("{count} conflicts remaining"),
+        };
+
+        h_flex()
+            .h_8()
+            .px_2()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(status)
+                    .size(LabelSize::Small)
+                    .color(if self.conflicts.is_empty() {
+                        Color::Success
+                    } else {
+                        Color::Warning
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("accept-ours", "Accept Local")
+                            .label_size(LabelSize::Small)
+                            .key_binding(KeyBinding::for_action_in(&AcceptOurs, &focus_handle, cx))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept_ours(&AcceptOurs, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("accept-theirs", "Accept Remote")
+                            .label_size(LabelSize::Small)
+                            .key_binding(KeyBinding::for_action_in(
+                                &AcceptTheirs,
+                                &focus_handle,
+                                cx,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept_theirs(&AcceptTheirs, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("accept-both", "Accept Both")
+                            .label_size(LabelSize::Small)
+                            .key_binding(KeyBinding::for_action_in(&AcceptBoth, &focus_handle, cx))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept_both(&AcceptBoth, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("accept-base", "Accept Base")
+                            .label_size(LabelSize::Small)
+                            .key_binding(KeyBinding::for_action_in(&AcceptBase, &focus_handle, cx))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.accept_base(&AcceptBase, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("previous-conflict", IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Previous Conflict",
+                                &PreviousConflict,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.previous_conflict(&PreviousConflict, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("next-conflict", IconName::ArrowDown)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Next Conflict",
+                                &NextConflict,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.next_conflict(&NextConflict, window, cx)
+                            })),
+                    ),
+            )
+    }
+
+    fn render_pane(
+        &self,
+        label: &'static str,
+        buffer: &Entity<Buffer>,
+        editor: &Entity<Editor>,
+        cx: &App,
+    ) -> Div {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .child(
+                h_flex()
+                    .h_7()
+                    .px_2()
+                    .gap_1p5()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(label).size(LabelSize::Small))
+                    .child(
+                        Label::new(buffer_file_name(buffer, cx))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+}
+
+/// Returns the index of the conflict containing `offset`, or of the first
+/// conflict after it, wrapping around to the first conflict in the buffer.
+fn conflict_at_or_after(
+    conflicts: &[ConflictRegion],
+    offset: usize,
+    snapshot: &TextBufferSnapshot,
+) -> Option<usize> {
+    if conflicts.is_empty() {
+        return None;
+    }
+    Some(
+        conflicts
+            .iter()
+            .position(|conflict| conflict.range.end.to_offset(snapshot) > offset)
+            .unwrap_or(0),
+    )
+}
+
+fn side_editor(
+    buffer: Entity<Buffer>,
+    diff: Entity<BufferDiff>,
+    project: Entity<Project>,
+    window: &mut Window,
+    cx: &mut Context<MergeView>,
+) -> Entity<Editor> {
+    let multibuffer = cx.new(|cx| {
+        let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+        multibuffer.add_diff(diff, cx);
+        multibuffer
+    });
+    cx.new(|cx| {
+        let mut editor = Editor::for_multibuffer(multibuffer, Some(project), window, cx);
+        editor.set_read_only(true);
+        editor.set_should_serialize(false, cx);
+        editor.start_temporary_diff_override();
+        editor.disable_diagnostics(cx);
+        editor.set_expand_all_diff_hunks(cx);
+        editor.set_render_diff_hunk_controls(
+            Arc::new(|_, _, _, _, _, _, _, _| gpui::Empty.into_any_element()),
+            cx,
+        );
+        editor
+    })
+}
+
+fn buffer_file_name(buffer: &Entity<Buffer>, cx: &App) -> String {
+    buffer
+        .read(cx)
+        .file()
+        .and_then(|file| {
+            Some(
+                file.full_path(cx)
+                    .file_name()?
+                    .to_string_lossy()
+                    .to_string(),
+            )
+        })
+        .unwrap_or_else(|| "untitled".into())
+}
+
+impl EventEmitter<EditorEvent> for MergeView {}
+
+impl Focusable for MergeView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.merged_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        format!("Merge {}", buffer_file_name(&self.merged_buffer, cx)).into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let path = |buffer: &Entity<Buffer>| {
+            buffer
+                .read(cx)
+                .file()
+                .map(|file| file.full_path(cx).compact().to_string_lossy().into_owned())
+                .unwrap_or_else(|| "untitled".into())
+        };
+        Some(
+            format!(
+                "{} ← {} + {}",
+                path(&self.merged_buffer),
+                path(&self.local_buffer),
+                path(&self.remote_buffer)
+            )
+            .into(),
+        )
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge View Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.merged_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.merged_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.merged_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.merged_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.merged_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.merged_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.merged_editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for editor in [&self.local_editor, &self.merged_editor, &self.remote_editor] {
+            editor.update(cx, |editor, cx| {
+                editor.added_to_workspace(workspace, window, cx)
+            });
+        }
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.merged_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.merged_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.merged_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        // Only the merged file is ever written; the other panes are read-only.
+        self.merged_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        v_flex()
+            .key_context("MergeView")
+            .size_full()
+            .on_action(cx.listener(Self::accept_ours))
+            .on_action(cx.listener(Self::accept_theirs))
+            .on_action(cx.listener(Self::accept_both))
+            .on_action(cx.listener(Self::accept_base))
+            .on_action(cx.listener(Self::next_conflict))
+            .on_action(cx.listener(Self::previous_conflict))
+            .child(self.render_toolbar(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        self.render_pane("Local", &self.local_buffer, &self.local_editor, cx)
+                            .border_r_1()
+                            .border_color(border_color),
+                    )
+                    .child(
+                        self.render_pane("Merged", &self.merged_buffer, &self.merged_editor, cx)
+                            .border_r_1()
+                            .border_color(border_color),
+                    )
+                    .child(self.render_pane(
+                        "Remote",
+                        &self.remote_buffer,
+                        &self.remote_editor,
+                        cx,
+                    )),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use settings::SettingsStore;
+    use unindent::unindent;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    async fn open_merge_view<'a>(
+        merged: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (Entity<MergeView>, &'a mut gpui::VisualTestContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            serde_json::json!({
+                "file_BASE.txt": "one\ntwo\nthree\nfour\n",
+                "file_LOCAL.txt": "one\ntwo (local)\nthree\nfour (local)\n",
+                "file_REMOTE.txt": "one\ntwo (remote)\nthree\nfour (remote)\n",
+                "file.txt": merged,
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let merge_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeView::open(
+                    path!("/test/file_BASE.txt").into(),
+                    path!("/test/file_LOCAL.txt").into(),
+                    path!("/test/file_REMOTE.txt").into(),
+                    path!("/test/file.txt").into(),
+                    workspace,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        (merge_view, cx)
+    }
+
+    fn merged_text(merge_view: &Entity<MergeView>, cx: &mut gpui::VisualTestContext) -> String {
+        merge_view.read_with(cx, |merge_view, cx| {
+            merge_view.merged_buffer.read(cx).text()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_accept_conflict_sides(cx: &mut TestAppContext) {
+        let (merge_view, cx) = open_merge_view(
+            &unindent(
+                "
+                one
+                <<<<<<< HEAD
+                two (local)
+                ||||||| base
+                two
+                =======
+                two (remote)
+                >>>>>>> feature
+                three
+                <<<<<<< HEAD
+                four (local)
+                ||||||| base
+                four
+                =======
+                four (remote)
+                >>>>>>> feature
+                ",
+            ),
+            cx,
+        )
+        .await;
+
+        merge_view.read_with(cx, |merge_view, _| {
+            assert_eq!(merge_view.conflicts.len(), 2);
+        });
+
+        // The cursor starts on the first conflict, and moves on to the next
+        // one after each resolution.
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.accept_theirs(&AcceptTheirs, window, cx)
+        });
+        assert_eq!(
+            merged_text(&merge_view, cx),
+            unindent(
+                "
+                one
+                two (remote)
+                three
+                <<<<<<< HEAD
+                four (local)
+                ||||||| base
+                four
+                =======
+                four (remote)
+                >>>>>>> feature
+                ",
+            )
+        );
+
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.accept_both(&AcceptBoth, window, cx)
+        });
+        assert_eq!(
+            merged_text(&merge_view, cx),
+            "one\ntwo (remote)\nthree\nfour (local)\nfour (remote)\n"
+        );
+        merge_view.read_with(cx, |merge_view, _| {
+            assert!(merge_view.conflicts.is_empty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_accept_base_requires_base_section(cx: &mut TestAppContext) {
+        let (merge_view, cx) = open_merge_view(
+            &unindent(
+                "
+                one
+                <<<<<<< HEAD
+                two (local)
+                =======
+                two (remote)
+                >>>>>>> feature
+                three
+                <<<<<<< HEAD
+                four (local)
+                ||||||| base
+                four
+                =======
+                four (remote)
+                >>>>>>> feature
+                ",
+            ),
+            cx,
+        )
+        .await;
+
+        // Without a base section, accepting the base leaves the conflict alone.
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.accept_base(&AcceptBase, window, cx)
+        });
+        merge_view.read_with(cx, |merge_view, _| {
+            assert_eq!(merge_view.conflicts.len(), 2);
+        });
+
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.next_conflict(&NextConflict, window, cx);
+            merge_view.accept_base(&AcceptBase, window, cx);
+        });
+        assert_eq!(
+            merged_text(&merge_view, cx),
+            unindent(
+                "
+                one
+                <<<<<<< HEAD
+                two (local)
+                =======
+                two (remote)
+                >>>>>>> feature
+                three
+                four
+                ",
+            )
+        );
+
+        // Navigation wraps around to the remaining conflict.
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.next_conflict(&NextConflict, window, cx);
+            merge_view.accept_ours(&AcceptOurs, window, cx);
+        });
+        assert_eq!(
+            merged_text(&merge_view, cx),
+            "one\ntwo (local)\nthree\nfour\n"
+        );
+    }
+}
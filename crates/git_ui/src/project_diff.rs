@@ -104,6 +104,15 @@ impl ProjectDiff {
         cx: &mut Context<Workspace>,
     ) {
         telemetry::event!("Git Branch Diff Opened");
+        Self::open_branch_diff(workspace, window, cx).detach_and_notify_err(window, cx);
+    }
+
+    /// Activates the diff against the default branch, opening it if needed.
+    pub(crate) fn open_branch_diff(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Entity<Self>>> {
         let project = workspace.project().clone();
 
         let existing = workspace
@@ -111,24 +120,32 @@ impl ProjectDiff {
             .find(|item| matches!(item.read(cx).diff_base(cx), DiffBase::Merge { .. }));
         if let Some(existing) = existing {
             workspace.activate_item(&existing, true, true, window, cx);
-            return;
+            return Task::ready(Ok(existing));
         }
         let workspace = cx.entity();
-        window
-            .spawn(cx, async move |cx| {
-                let this = cx
-                    .update(|window, cx| {
-                        Self::new_with_default_branch(project, workspace.clone(), window, cx)
-                    })?
-                    .await?;
-                workspace
-                    .update_in(cx, |workspace, window, cx| {
-                        workspace.add_item_to_active_pane(Box::new(this), None, true, window, cx);
-                    })
-                    .ok();
-                anyhow::Ok(())
-            })
-            .detach_and_notify_err(window, cx);
+        window.spawn(cx, async move |cx| {
+            let this = cx
+                .update(|window, cx| {
+                    Self::new_with_default_branch(project, workspace.clone(), window, cx)
+                })?
+                .await?;
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.add_item_to_active_pane(
+                        Box::new(this.clone()),
+                        None,
+                        true,
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+            anyhow::Ok(this)
+        })
+    }
+
+    pub(crate) fn primary_editor(&self, cx: &App) -> Entity<Editor> {
+        self.editor.read(cx).primary_editor().clone()
     }
 
     pub fn deploy_at(
@@ -0,0 +1,766 @@
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use editor::{
+    Editor, EditorEvent,
+    display_map::{BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+};
+use git::{GitHostingProviderRegistry, GitRemote, ReviewThread, parse_git_remote_url};
+use gpui::{
+    App, Context, Entity, FontWeight, SharedString, Subscription, Task, actions,
+    http_client::HttpClient,
+};
+use language::Point;
+use std::sync::Arc;
+use ui::{Tooltip, prelude::*};
+use workspace::{
+    Toast, Workspace,
+    notifications::{DetachAndPromptErr as _, NotificationId},
+};
+
+use crate::project_diff::ProjectDiff;
+
+actions!(
+    git,
+    [
+        /// Shows the review threads of the current branch's pull request
+        /// inline in the branch diff.
+        ShowReviewThreads,
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(show_review_threads);
+}
+
+struct ReviewThreadsNotification;
+
+fn show_review_threads(
+    workspace: &mut Workspace,
+    _: &ShowReviewThreads,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let http_client = cx.http_client();
+    let target = pull_request_target(workspace, cx);
+    let open_diff = ProjectDiff::open_branch_diff(workspace, window, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let PullRequestTarget {
+            remote,
+            head_owner,
+            branch,
+            head_sha,
+        } = target?;
+        let pull_request = remote
+            .pull_request_for_branch(&head_owner, &branch, http_client.clone())
+            .await?
+            .with_context(|| format!("There is no open pull request for {branch}"))?;
+        let threads = remote
+            .review_threads(&pull_request, http_client.clone())
+            .await?;
+        let thread_count = threads.len();
+        // Thread lines refer to the pull request's head, so they only match the
+        // diff when that commit is checked out.
+        let at_pull_request_head = head_sha.is_some() && pull_request.head_sha == head_sha;
+
+        let project_diff = open_diff.await?;
+        let editor =
+            project_diff.read_with(cx, |project_diff, cx| project_diff.primary_editor(cx))?;
+        editor.update_in(cx, |editor, window, cx| {
+            set_review_threads(
+                editor,
+                remote,
+                threads,
+                at_pull_request_head,
+                http_client,
+                window,
+                cx,
+            )
+        })?;
+
+        let mut message = match thread_count {
+            0 => format!("#{} has no review threads.", pull_request.number),
+            1 => format!("Showing 1 review thread from #{}.", pull_request.number),
+            count => format!(
+                "Showing {count} review threads from #{}.",
+                pull_request.number
+            ),
+        };
+        if thread_count > 0 && !at_pull_request_head {
+            message.push_str(
+                " The checked out commit isn't the pull request's head, \
+                so threads are shown at the top of their files.",
+            );
+        }
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<ReviewThreadsNotification>(),
+                    message,
+                )
+                .autohide(),
+                cx,
+            )
+        })
+    })
+    .detach_and_prompt_err("Failed to load review threads", window, cx, |_, _, _| None);
+}
+
+struct PullRequestTarget {
+    /// The remote that pull requests are opened against.
+    remote: GitRemote,
+    /// The owner of the repository that the current branch is pushed to.
+    head_owner: SharedString,
+    /// The name of the branch that the current branch is pushed to.
+    branch: String,
+    /// The commit that is checked out.
+    head_sha: Option<SharedString>,
+}
+
+fn pull_request_target(workspace: &Workspace, cx: &mut App) -> Result<PullRequestTarget> {
+    let repository = workspace
+        .project()
+        .read(cx)
+        .active_repository(cx)
+        .context("No active repository")?;
+    let snapshot = repository.read(cx).snapshot();
+    let branch = snapshot
+        .branch
+        .as_ref()
+        .context("No branch is checked out")?;
+
+    let provider_registry = GitHostingProviderRegistry::default_global(cx);
+    let parse_remote = |url: &String| {
+        parse_git_remote_url(provider_registry.clone(), url).map(|(host, parsed)| GitRemote {
+            host,
+            owner: parsed.owner.into(),
+            repo: parsed.repo.into(),
+        })
+    };
+    let origin = snapshot.remote_origin_url.as_ref().and_then(parse_remote);
+    let upstream = snapshot.remote_upstream_url.as_ref().and_then(parse_remote);
+
+    // Pull requests from a fork are opened against the `upstream` remote.
+    let (base, head) = match (upstream, origin) {
+        (Some(upstream), origin) => (upstream.clone(), origin.unwrap_or(upstream)),
+        (None, Some(origin)) => (origin.clone(), origin),
+        (None, None) => {
+            anyhow::bail!("The repository has no remote on a supported git hosting provider")
+        }
+    };
+    anyhow::ensure!(
+        base.host_supports_pull_request_reviews(),
+        "{} doesn't support pull request reviews",
+        base.host.name()
+    );
+
+    let branch_name = branch
+        .upstream
+        .as_ref()
+        .and_then(|upstream| upstream.stripped_ref_name())
+        .and_then(|ref_name| ref_name.split_once('/'))
+        .map_or(branch.name(), |(_, branch_name)| branch_name);
+    Ok(PullRequestTarget {
+        remote: base,
+        head_owner: head.owner,
+        branch: branch_name.to_string(),
+        head_sha: snapshot
+            .head_commit
+            .as_ref()
+            .map(|commit| commit.sha.clone()),
+    })
+}
+
+pub(crate) struct ReviewThreadsAddon {
+    threads: Vec<Entity<ReviewThreadView>>,
+    at_pull_request_head: bool,
+    block_ids: Vec<CustomBlockId>,
+    _thread_subscriptions: Vec<Subscription>,
+    _subscription: Subscription,
+}
+
+impl editor::Addon for ReviewThreadsAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+fn set_review_threads(
+    editor: &mut Editor,
+    remote: GitRemote,
+    threads: Vec<ReviewThread>,
+    at_pull_request_head: bool,
+    http_client: Arc<dyn HttpClient>,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let threads = threads
+        .into_iter()
+        .map(|thread| {
+            cx.new(|cx| {
+                ReviewThreadView::new(thread, remote.clone(), http_client.clone(), window, cx)
+            })
+        })
+        .collect::<Vec<_>>();
+    // Threads change height when they're collapsed or replied to, which the
+    // editor picks up when it lays their blocks out again.
+    let thread_subscriptions = threads
+        .iter()
+        .map(|thread| cx.observe(thread, |_, _, cx| cx.notify()))
+        .collect();
+
+    if let Some(addon) = editor.addon_mut::<ReviewThreadsAddon>() {
+        addon.threads = threads;
+        addon.at_pull_request_head = at_pull_request_head;
+        addon._thread_subscriptions = thread_subscriptions;
+    } else {
+        let subscription = cx.subscribe(&cx.entity(), |editor, _, event, cx| match event {
+            EditorEvent::ExcerptsAdded { .. }
+            | EditorEvent::ExcerptsExpanded { .. }
+            | EditorEvent::ExcerptsRemoved { .. } => refresh_blocks(editor, cx),
+            _ => {}
+        });
+        editor.register_addon(ReviewThreadsAddon {
+            threads,
+            at_pull_request_head,
+            block_ids: Vec::new(),
+            _thread_subscriptions: thread_subscriptions,
+            _subscription: subscription,
+        });
+    }
+    refresh_blocks(editor, cx);
+}
+
+/// Places a block for each thread below the line it's attached to, or above
+/// the file's first hunk if that line isn't shown in the diff or may have moved
+/// since the pull request's head.
+fn refresh_blocks(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let Some(project) = editor.project().cloned() else {
+        return;
+    };
+    let Some(addon) = editor.addon_mut::<ReviewThreadsAddon>() else {
+        return;
+    };
+    let old_block_ids = std::mem::take(&mut addon.block_ids);
+    let threads = addon.threads.clone();
+    let at_pull_request_head = addon.at_pull_request_head;
+    editor.remove_blocks(HashSet::from_iter(old_block_ids), None, cx);
+
+    let git_store = project.read(cx).git_store().read(cx);
+    let multibuffer = editor.buffer().read(cx);
+    let snapshot = multibuffer.snapshot(cx);
+    let mut blocks = Vec::new();
+    for buffer in multibuffer.all_buffers() {
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some((_, repo_path)) = git_store.repository_and_path_for_buffer_id(buffer_id, cx)
+        else {
+            continue;
+        };
+        let excerpts = multibuffer.excerpts_for_buffer(buffer_id, cx);
+        let Some((first_excerpt_id, first_excerpt_range)) = excerpts.first() else {
+            continue;
+        };
+        let Some(buffer_snapshot) = snapshot.buffer_for_excerpt(*first_excerpt_id) else {
+            continue;
+        };
+
+        for thread in &threads {
+            let review_thread = &thread.read(cx).thread;
+            if review_thread.path != repo_path {
+                continue;
+            }
+            let line = review_thread.line.filter(|_| at_pull_request_head);
+            let below_line = line.and_then(|line| {
+                let row = line.checked_sub(1)?;
+                if row > buffer_snapshot.max_point().row {
+                    return None;
+                }
+                let position = buffer_snapshot.anchor_before(Point::new(row, 0));
+                let (excerpt_id, _) = excerpts.iter().find(|(_, range)| {
+                    range.context.start.cmp(&position, buffer_snapshot).is_le()
+                        && range.context.end.cmp(&position, buffer_snapshot).is_ge()
+                })?;
+                snapshot
+                    .anchor_in_excerpt(*excerpt_id, position)
+                    .map(BlockPlacement::Below)
+            });
+            let Some(placement) = below_line.or_else(|| {
+                snapshot
+                    .anchor_in_excerpt(*first_excerpt_id, first_excerpt_range.context.start)
+                    .map(BlockPlacement::Above)
+            }) else {
+                continue;
+            };
+
+            blocks.push(BlockProperties {
+                placement,
+                // The editor measures the rendered thread and resizes the
+                // block to fit it.
+                height: Some(1),
+                style: BlockStyle::Flex,
+                render: Arc::new({
+                    let thread = thread.clone();
+                    move |cx| {
+                        div()
+                            .id(cx.block_id)
+                            .ml(cx.margins.gutter.width)
+                            .py_1()
+                            .pr_4()
+                            .child(thread.clone())
+                            .into_any_element()
+                    }
+                }),
+                priority: 0,
+            });
+        }
+    }
+
+    let block_ids = editor.insert_blocks(blocks, None, cx);
+    if let Some(addon) = editor.addon_mut::<ReviewThreadsAddon>() {
+        addon.block_ids = block_ids;
+    }
+}
+
+struct ReviewThreadView {
+    thread: ReviewThread,
+    remote: GitRemote,
+    http_client: Arc<dyn HttpClient>,
+    reply_editor: Entity<Editor>,
+    collapsed: bool,
+    pending_request: Option<Task<()>>,
+    error: Option<SharedString>,
+}
+
+impl ReviewThreadView {
+    fn new(
+        thread: ReviewThread,
+        remote: GitRemote,
+        http_client: Arc<dyn HttpClient>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let reply_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(1, 8, window, cx);
+            editor.set_placeholder_text("Reply…", window, cx);
+            editor
+        });
+        Self {
+            collapsed: thread.is_resolved,
+            thread,
+            remote,
+            http_client,
+            reply_editor,
+            pending_request: None,
+            error: None,
+        }
+    }
+
+    fn reply(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let body = self.reply_editor.read(cx).text(cx);
+        if body.trim().is_empty() || self.pending_request.is_some() {
+            return;
+        }
+        let remote = self.remote.clone();
+        let http_client = self.http_client.clone();
+        let thread_id = self.thread.id.clone();
+        self.error = None;
+        self.pending_request = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = remote
+                .reply_to_review_thread(&thread_id, &body, http_client)
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.pending_request = None;
+                match result {
+                    Ok(comment) => {
+                        this.thread.comments.push(comment);
+                        this.reply_editor
+                            .update(cx, |editor, cx| editor.clear(window, cx));
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn toggle_resolved(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_request.is_some() {
+            return;
+        }
+        let resolved = !self.thread.is_resolved;
+        let remote = self.remote.clone();
+        let http_client = self.http_client.clone();
+        let thread_id = self.thread.id.clone();
+        self.error = None;
+        self.pending_request = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = remote
+                .set_review_thread_resolved(&thread_id, resolved, http_client)
+                .await;
+            this.update(cx, |this, cx| {
+                this.pending_request = None;
+                match result {
+                    Ok(()) => {
+                        this.thread.is_resolved = resolved;
+                        this.collapsed = resolved;
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+}
+
+impl Render for ReviewThreadView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let thread = &self.thread;
+        let is_pending = self.pending_request.is_some();
+        let first_comment = thread.comments.first();
+
+        let header = h_flex()
+            .gap_1()
+            .child(
+                IconButton::new(
+                    "toggle-collapsed",
+                    if self.collapsed {
+                        IconName::ChevronRight
+                    } else {
+                        IconName::ChevronDown
+                    },
+                )
+                .icon_size(IconSize::Small)
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.collapsed = !this.collapsed;
+                    cx.notify();
+                })),
+            )
+            .child(
+                Icon::new(IconName::Chat)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(match first_comment {
+                    Some(comment) if self.collapsed => {
+                        format!("{}: {}", comment.author, first_line(&comment.body))
+                    }
+                    Some(comment) => comment.author.to_string(),
+                    None => "Review thread".to_string(),
+                })
+                .size(LabelSize::Small)
+                .truncate(),
+            )
+            .when(thread.is_outdated, |this| {
+                this.child(
+                    Label::new("Outdated")
+                        .size(LabelSize::Small)
+                        .color(Color::Warning),
+                )
+            })
+            .when(thread.is_resolved, |this| {
+                this.child(
+                    Label::new("Resolved")
+                        .size(LabelSize::Small)
+                        .color(Color::Success),
+                )
+            })
+            .child(div().flex_1())
+            .when_some(
+                first_comment.and_then(|comment| comment.url.clone()),
+                |this, url| {
+                    this.child(
+                        IconButton::new("open-in-browser", IconName::ArrowUpRight)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Open in Browser"))
+                            .on_click(move |_, _, cx| cx.open_url(url.as_str())),
+                    )
+                },
+            )
+            .child(
+                Button::new(
+                    "toggle-resolved",
+                    if thread.is_resolved {
+                        "Unresolve"
+                    } else {
+                        "Resolve"
+                    },
+                )
+                .label_size(LabelSize::Small)
+                .disabled(is_pending)
+                .on_click(cx.listener(|this, _, window, cx| this.toggle_resolved(window, cx))),
+            );
+
+        v_flex()
+            .p_2()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_background)
+            .child(header)
+            .when(!self.collapsed, |this| {
+                this.children(thread.comments.iter().map(|comment| {
+                    v_flex()
+                        .pl_6()
+                        .child(
+                            Label::new(comment.author.clone())
+                                .size(LabelSize::Small)
+                                .weight(FontWeight::BOLD),
+                        )
+                        .child(div().text_sm().child(comment.body.clone()))
+                }))
+                .child(
+                    h_flex()
+                        .pl_6()
+                        .gap_2()
+                        .items_end()
+                        .child(
+                            div()
+                                .flex_1()
+                                .px_1()
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(cx.theme().colors().border_variant)
+                                .child(self.reply_editor.clone()),
+                        )
+                        .child(
+                            Button::new("reply", "Reply")
+                                .label_size(LabelSize::Small)
+                                .disabled(is_pending)
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| this.reply(window, cx)),
+                                ),
+                        ),
+                )
+            })
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+    }
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::Bias;
+    use futures::AsyncReadExt as _;
+    use git::{ReviewComment, repository::RepoPath};
+    use git_hosting_providers::Github;
+    use gpui::{
+        TestAppContext, VisualTestContext,
+        http_client::{FakeHttpClient, Response},
+    };
+    use multi_buffer::MultiBuffer;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+        });
+    }
+
+    fn review_thread(id: &str, path: &str, line: Option<u32>, is_resolved: bool) -> ReviewThread {
+        ReviewThread {
+            id: id.to_string().into(),
+            path: RepoPath::new(path).unwrap(),
+            line,
+            is_resolved,
+            is_outdated: line.is_none(),
+            comments: vec![ReviewComment {
+                id: format!("{id}-comment").into(),
+                author: "reviewer".into(),
+                body: "Say hello to the world.".into(),
+                url: None,
+            }],
+        }
+    }
+
+    #[gpui::test]
+    async fn test_review_threads(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "src": {
+                    "main.rs": "fn main() {\n    println!(\"hello\");\n}\n",
+                    "lib.rs": "",
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            let multibuffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+            Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx)
+        });
+        cx.run_until_parked();
+
+        let http_client: Arc<dyn HttpClient> = FakeHttpClient::create(|request| async move {
+            let mut body = String::new();
+            request.into_body().read_to_string(&mut body).await?;
+            let response = if body.contains("addPullRequestReviewThreadReply") {
+                json!({ "data": { "addPullRequestReviewThreadReply": { "comment": {
+                    "id": "reply",
+                    "body": "Done.",
+                    "url": null,
+                    "author": { "login": "author" }
+                }}}})
+            } else {
+                json!({ "data": { "resolveReviewThread": { "thread": { "id": "thread-1" } } } })
+            };
+            Ok(Response::new(response.to_string().into()))
+        });
+        let remote = GitRemote {
+            host: Arc::new(Github::public_instance()),
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        // Threads on files that aren't in the diff aren't shown.
+        editor.update_in(cx, |editor, window, cx| {
+            set_review_threads(
+                editor,
+                remote,
+                vec![
+                    review_thread("thread-1", "src/main.rs", Some(2), false),
+                    review_thread("thread-2", "src/main.rs", None, true),
+                    review_thread("thread-3", "src/lib.rs", Some(1), false),
+                ],
+                true,
+                http_client,
+                window,
+                cx,
+            )
+        });
+        let thread = editor.update(cx, |editor, cx| {
+            let addon = editor.addon::<ReviewThreadsAddon>().unwrap();
+            assert_eq!(addon.block_ids.len(), 2);
+            assert!(!addon.threads[0].read(cx).collapsed);
+            assert!(addon.threads[1].read(cx).collapsed);
+            addon.threads[0].clone()
+        });
+
+        thread.update_in(cx, |thread, window, cx| {
+            thread
+                .reply_editor
+                .update(cx, |editor, cx| editor.set_text("Done.", window, cx));
+            thread.reply(window, cx);
+        });
+        cx.run_until_parked();
+        thread.update_in(cx, |thread, window, cx| {
+            assert_eq!(thread.error, None);
+            assert_eq!(
+                thread
+                    .thread
+                    .comments
+                    .iter()
+                    .map(|comment| comment.body.as_ref())
+                    .collect::<Vec<_>>(),
+                ["Say hello to the world.", "Done."]
+            );
+            assert_eq!(thread.reply_editor.read(cx).text(cx), "");
+            thread.toggle_resolved(window, cx);
+        });
+        cx.run_until_parked();
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(thread.error, None);
+            assert!(thread.thread.is_resolved);
+            assert!(thread.collapsed);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_review_thread_placement(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "src": { "main.rs": "fn main() {\n    println!(\"hello\");\n}\n" },
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            let multibuffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+            Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx)
+        });
+        cx.simulate_resize(gpui::size(px(300.), px(2000.)));
+        cx.run_until_parked();
+
+        let remote = GitRemote {
+            host: Arc::new(Github::public_instance()),
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let mut thread = review_thread("thread-1", "src/main.rs", Some(2), false);
+        thread.comments[0].body = "Say hello to the world. ".repeat(20).into();
+        let place_thread = |at_pull_request_head: bool, cx: &mut VisualTestContext| {
+            editor.update_in(cx, |editor, window, cx| {
+                set_review_threads(
+                    editor,
+                    remote.clone(),
+                    vec![thread.clone()],
+                    at_pull_request_head,
+                    FakeHttpClient::with_404_response(),
+                    window,
+                    cx,
+                )
+            });
+            cx.run_until_parked();
+            editor.update_in(cx, |editor, window, cx| {
+                let block_id = editor.addon::<ReviewThreadsAddon>().unwrap().block_ids[0];
+                let block_row = editor.row_for_block(block_id, cx).unwrap().0;
+                let last_row = editor
+                    .snapshot(window, cx)
+                    .point_to_display_point(Point::new(2, 0), Bias::Left)
+                    .row()
+                    .0;
+                (block_row, last_row)
+            })
+        };
+
+        // The block is below the thread's line, and is as tall as the wrapped comment.
+        let (block_row, last_row) = place_thread(true, cx);
+        assert_eq!(block_row, 2);
+        assert!(last_row - block_row > 6, "{block_row}..{last_row}");
+
+        // Lines may have moved when another commit is checked out.
+        let (block_row, _) = place_thread(false, cx);
+        assert_eq!(block_row, 0);
+    }
+}
@@ -0,0 +1,580 @@
+use crate::{App, Bounds, FocusHandle, FocusId, GlobalElementId, Pixels, SharedString, Window};
+use collections::FxHashMap;
+use std::{
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    rc::Rc,
+};
+
+/// The role of a node in the accessibility tree, which determines how
+/// assistive technologies such as screen readers present it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityRole {
+    /// The root of a window's tree.
+    Window,
+    /// A generic container that groups related nodes.
+    Group,
+    /// A piece of static text.
+    Label,
+    /// A heading that introduces a section of content.
+    Heading,
+    /// A control that performs an action when clicked.
+    Button,
+    /// A control that can be checked and unchecked.
+    CheckBox,
+    /// A hyperlink.
+    Link,
+    /// A single-line text field.
+    TextInput,
+    /// A multi-line text field, such as a code editor.
+    MultilineTextInput,
+    /// A list of items.
+    List,
+    /// An item within a [`AccessibilityRole::List`].
+    ListItem,
+    /// A container of tabs.
+    TabList,
+    /// A tab within a [`AccessibilityRole::TabList`].
+    Tab,
+    /// A menu of commands.
+    Menu,
+    /// A command within a [`AccessibilityRole::Menu`].
+    MenuItem,
+    /// A modal surface that interrupts the rest of the window.
+    Dialog,
+    /// A collection of controls, such as a toolbar or status bar.
+    Toolbar,
+    /// An image or icon.
+    Image,
+    /// A region that scrolls its content.
+    ScrollView,
+}
+
+impl AccessibilityRole {
+    /// Whether assistive technologies name nodes with this role after the text they contain.
+    fn is_named_from_contents(self) -> bool {
+        matches!(
+            self,
+            Self::Heading
+                | Self::Button
+                | Self::CheckBox
+                | Self::Link
+                | Self::ListItem
+                | Self::Tab
+                | Self::MenuItem
+        )
+    }
+}
+
+impl fmt::Display for AccessibilityRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Window => "window",
+            Self::Group => "group",
+            Self::Label => "label",
+            Self::Heading => "heading",
+            Self::Button => "button",
+            Self::CheckBox => "checkbox",
+            Self::Link => "link",
+            Self::TextInput => "text input",
+            Self::MultilineTextInput => "multiline text input",
+            Self::List => "list",
+            Self::ListItem => "list item",
+            Self::TabList => "tab list",
+            Self::Tab => "tab",
+            Self::Menu => "menu",
+            Self::MenuItem => "menu item",
+            Self::Dialog => "dialog",
+            Self::Toolbar => "toolbar",
+            Self::Image => "image",
+            Self::ScrollView => "scroll view",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An action that assistive technologies can request on a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityAction {
+    /// Activate the node, as if it was clicked with the mouse.
+    Click,
+    /// Move keyboard focus to the node.
+    Focus,
+}
+
+/// A request from an assistive technology to perform an action on a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessibilityActionRequest {
+    /// The node to perform the action on.
+    pub node: AccessibilityNodeId,
+    /// The action to perform.
+    pub action: AccessibilityAction,
+}
+
+/// Identifies a node in the accessibility tree.
+///
+/// Ids are derived from the element's global id when it has one, or from its
+/// position under its parent otherwise, so they stay stable across frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccessibilityNodeId(pub u64);
+
+/// A node in the accessibility tree, describing an element to assistive technologies.
+#[derive(Clone, Debug)]
+pub struct AccessibilityNode {
+    /// The role of the node.
+    pub role: AccessibilityRole,
+    /// The name announced for the node, such as the label of a button.
+    pub name: Option<SharedString>,
+    /// The current value of the node, such as the contents of a text field.
+    pub value: Option<SharedString>,
+    /// The window-relative bounds of the node.
+    pub bounds: Bounds<Pixels>,
+    /// The actions that can be performed on the node.
+    pub actions: Vec<AccessibilityAction>,
+    /// Whether the node currently has keyboard focus.
+    pub focused: bool,
+    pub(crate) focus_handle: Option<FocusHandle>,
+    pub(crate) click_handler: Option<AccessibilityClickHandler>,
+    id: AccessibilityNodeId,
+    parent: Option<AccessibilityNodeId>,
+    children: Vec<AccessibilityNodeId>,
+}
+
+impl AccessibilityNode {
+    /// Creates a node with the given role and bounds.
+    pub fn new(role: AccessibilityRole, bounds: Bounds<Pixels>) -> Self {
+        Self {
+            role,
+            name: None,
+            value: None,
+            bounds,
+            actions: Vec::new(),
+            focused: false,
+            focus_handle: None,
+            click_handler: None,
+            id: AccessibilityNodeId(0),
+            parent: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Sets the name announced for this node.
+    pub fn name(mut self, name: impl Into<SharedString>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the value of this node.
+    pub fn value(mut self, value: impl Into<SharedString>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Adds an action that can be performed on this node.
+    pub fn action(mut self, action: AccessibilityAction) -> Self {
+        if !self.actions.contains(&action) {
+            self.actions.push(action);
+        }
+        self
+    }
+
+    /// Associates this node with a focus handle, which lets assistive
+    /// technologies focus it and track whether it is focused.
+    pub fn focus_handle(mut self, focus_handle: &FocusHandle) -> Self {
+        self.focus_handle = Some(focus_handle.clone());
+        self.action(AccessibilityAction::Focus)
+    }
+
+    /// Sets the handler that runs when an assistive technology clicks this node.
+    pub fn on_click(mut self, handler: impl Fn(&mut Window, &mut App) + 'static) -> Self {
+        self.click_handler = Some(AccessibilityClickHandler(Rc::new(handler)));
+        self.action(AccessibilityAction::Click)
+    }
+
+    /// The id of this node.
+    pub fn id(&self) -> AccessibilityNodeId {
+        self.id
+    }
+
+    /// The id of this node's parent, if it isn't the root.
+    pub fn parent(&self) -> Option<AccessibilityNodeId> {
+        self.parent
+    }
+
+    /// The ids of this node's children, in paint order.
+    pub fn children(&self) -> &[AccessibilityNodeId] {
+        &self.children
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct AccessibilityClickHandler(pub(crate) Rc<dyn Fn(&mut Window, &mut App)>);
+
+impl fmt::Debug for AccessibilityClickHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AccessibilityClickHandler")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum AccessibilityOperation {
+    Push(Option<u64>, AccessibilityNode),
+    Pop,
+}
+
+/// The accessibility tree of a window, built while painting each frame.
+#[derive(Default)]
+pub struct AccessibilityTree {
+    enabled: bool,
+    nodes: Vec<AccessibilityNode>,
+    index_by_id: FxHashMap<AccessibilityNodeId, usize>,
+    stack: Vec<usize>,
+    focus: Option<AccessibilityNodeId>,
+    pub(crate) insertion_history: Vec<AccessibilityOperation>,
+}
+
+impl AccessibilityTree {
+    pub(crate) fn clear(&mut self) {
+        self.enabled = false;
+        self.nodes.clear();
+        self.index_by_id.clear();
+        self.stack.clear();
+        self.focus = None;
+        self.insertion_history.clear();
+    }
+
+    /// Starts building a new tree rooted at a window node with the given bounds.
+    pub(crate) fn begin(&mut self, bounds: Bounds<Pixels>) {
+        self.clear();
+        self.enabled = true;
+        let mut root = AccessibilityNode::new(AccessibilityRole::Window, bounds);
+        root.id = AccessibilityNodeId(0);
+        self.index_by_id.insert(root.id, 0);
+        self.nodes.push(root);
+        self.stack.push(0);
+    }
+
+    /// Whether nodes are being collected for the current frame.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn push(&mut self, element_id: Option<&GlobalElementId>, node: AccessibilityNode) {
+        let element_id = element_id.map(|element_id| {
+            let mut hasher = DefaultHasher::new();
+            element_id.hash(&mut hasher);
+            hasher.finish()
+        });
+        self.push_internal(element_id, node);
+    }
+
+    fn push_internal(&mut self, element_id: Option<u64>, mut node: AccessibilityNode) {
+        if !self.enabled {
+            return;
+        }
+        self.insertion_history
+            .push(AccessibilityOperation::Push(element_id, node.clone()));
+
+        let parent_ix = *self.stack.last().expect("accessibility tree has a root");
+        let parent = &self.nodes[parent_ix];
+        let mut hasher = DefaultHasher::new();
+        match element_id {
+            Some(element_id) => element_id.hash(&mut hasher),
+            None => (parent.id, parent.children.len()).hash(&mut hasher),
+        }
+        let mut id = AccessibilityNodeId(hasher.finish());
+        // The same element can be painted more than once, so disambiguate repeated ids.
+        while id.0 == 0 || self.index_by_id.contains_key(&id) {
+            id = AccessibilityNodeId(id.0.wrapping_add(1));
+        }
+
+        node.id = id;
+        node.parent = Some(parent.id);
+        node.children.clear();
+        let ix = self.nodes.len();
+        self.nodes[parent_ix].children.push(id);
+        self.index_by_id.insert(id, ix);
+        self.nodes.push(node);
+        self.stack.push(ix);
+    }
+
+    pub(crate) fn pop(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        self.insertion_history.push(AccessibilityOperation::Pop);
+        debug_assert!(self.stack.len() > 1, "popped the accessibility tree's root");
+        self.stack.pop();
+    }
+
+    pub(crate) fn paint_index(&self) -> usize {
+        self.insertion_history.len()
+    }
+
+    pub(crate) fn replay(&mut self, operations: &[AccessibilityOperation]) {
+        for operation in operations {
+            match operation {
+                AccessibilityOperation::Push(element_id, node) => {
+                    self.push_internal(*element_id, node.clone())
+                }
+                AccessibilityOperation::Pop => self.pop(),
+            }
+        }
+    }
+
+    /// Resolves focus and derives missing names once every node has been inserted.
+    pub(crate) fn finish(&mut self, focus: Option<FocusId>) {
+        self.focus = None;
+        for node in &mut self.nodes {
+            node.focused =
+                focus.is_some() && node.focus_handle.as_ref().map(|handle| handle.id) == focus;
+            if node.focused {
+                self.focus = Some(node.id);
+            }
+        }
+
+        for ix in (0..self.nodes.len()).rev() {
+            let node = &self.nodes[ix];
+            if node.name.is_none() && node.role.is_named_from_contents() {
+                let mut text = String::new();
+                self.collect_text(node.id, &mut text);
+                if !text.is_empty() {
+                    self.nodes[ix].name = Some(text.into());
+                }
+            }
+        }
+    }
+
+    fn collect_text(&self, id: AccessibilityNodeId, text: &mut String) {
+        let Some(node) = self.node(id) else {
+            return;
+        };
+        for child in node.children.iter().filter_map(|child| self.node(*child)) {
+            if child.role == AccessibilityRole::Label
+                && let Some(name) = &child.name
+            {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(name);
+            } else {
+                self.collect_text(child.id, text);
+            }
+        }
+    }
+
+    /// The window node at the root of the tree, if the tree was built for this frame.
+    pub fn root(&self) -> Option<&AccessibilityNode> {
+        self.nodes.first()
+    }
+
+    /// Looks up a node by id.
+    pub fn node(&self, id: AccessibilityNodeId) -> Option<&AccessibilityNode> {
+        self.nodes.get(*self.index_by_id.get(&id)?)
+    }
+
+    /// Iterates over the children of the given node.
+    pub fn children(
+        &self,
+        node: &AccessibilityNode,
+    ) -> impl Iterator<Item = &AccessibilityNode> + '_ {
+        node.children.iter().filter_map(|child| self.node(*child))
+    }
+
+    /// Iterates over every node in the tree, in depth-first order.
+    pub fn nodes(&self) -> impl Iterator<Item = &AccessibilityNode> + '_ {
+        self.nodes.iter()
+    }
+
+    /// The node that currently has keyboard focus.
+    pub fn focus(&self) -> Option<&AccessibilityNode> {
+        self.node(self.focus?)
+    }
+
+    /// Finds the first node with the given role and name.
+    pub fn find(&self, role: AccessibilityRole, name: &str) -> Option<&AccessibilityNode> {
+        self.nodes
+            .iter()
+            .find(|node| node.role == role && node.name.as_deref() == Some(name))
+    }
+
+    fn fmt_node(
+        &self,
+        node: &AccessibilityNode,
+        depth: usize,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", node.role, indent = depth * 2)?;
+        if let Some(name) = &node.name {
+            write!(f, " {name:?}")?;
+        }
+        if let Some(value) = &node.value {
+            write!(f, " value={value:?}")?;
+        }
+        if node.focused {
+            write!(f, " (focused)")?;
+        }
+        writeln!(f)?;
+        for child in self.children(node) {
+            self.fmt_node(child, depth + 1, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the tree as an indented outline, which is convenient for asserting on it in tests.
+impl fmt::Display for AccessibilityTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.root() {
+            Some(root) => self.fmt_node(root, 0, f),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Context, FocusHandle, TestAppContext, Window, div, prelude::*, px, size, uniform_list,
+    };
+
+    struct Counter {
+        count: usize,
+        focus_handle: FocusHandle,
+    }
+
+    impl Render for Counter {
+        fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .child(format!("Count: {}", self.count))
+                .child(
+                    div()
+                        .id("increment")
+                        .track_focus(&self.focus_handle)
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.count += 1;
+                            cx.notify();
+                        }))
+                        .child("Increment"),
+                )
+                .child(
+                    uniform_list("items", 2, |range, _, _| {
+                        range
+                            .map(|ix| div().h(px(10.)).child(format!("Item {ix}")))
+                            .collect()
+                    })
+                    .h(px(50.))
+                    .accessibility_name("Items"),
+                )
+        }
+    }
+
+    #[gpui::test]
+    fn test_accessibility_tree(cx: &mut TestAppContext) {
+        let (counter, cx) = cx.add_window_view(|_, cx| Counter {
+            count: 0,
+            focus_handle: cx.focus_handle(),
+        });
+        cx.simulate_resize(size(px(200.), px(200.)));
+
+        cx.run_until_parked();
+        cx.update(|window, _| assert!(window.accessibility_tree().root().is_none()));
+
+        cx.enable_accessibility();
+        assert_eq!(
+            cx.accessibility_tree(),
+            concat!(
+                "window\n",
+                "  label \"Count: 0\"\n",
+                "  button \"Increment\"\n",
+                "    label \"Increment\"\n",
+                "  list \"Items\"\n",
+                "    list item \"Item 0\"\n",
+                "      label \"Item 0\"\n",
+                "    list item \"Item 1\"\n",
+                "      label \"Item 1\"\n",
+            )
+        );
+
+        cx.simulate_accessibility_action(
+            AccessibilityRole::Button,
+            "Increment",
+            AccessibilityAction::Click,
+        );
+        assert_eq!(counter.read_with(cx, |counter, _| counter.count), 1);
+        assert!(
+            cx.accessibility_tree()
+                .starts_with("window\n  label \"Count: 1\"\n")
+        );
+
+        cx.simulate_accessibility_action(
+            AccessibilityRole::Button,
+            "Increment",
+            AccessibilityAction::Focus,
+        );
+        assert!(
+            cx.accessibility_tree()
+                .contains("  button \"Increment\" (focused)\n")
+        );
+    }
+
+    struct ScrolledList {
+        clicked: Vec<usize>,
+    }
+
+    impl Render for ScrolledList {
+        fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            div().size_full().child(
+                uniform_list(
+                    "items",
+                    10,
+                    cx.processor(|_, range: std::ops::Range<usize>, _, cx| {
+                        range
+                            .map(|ix| {
+                                div()
+                                    .id(ix)
+                                    .h(px(10.))
+                                    .on_click(
+                                        cx.listener(move |this, _, _, _| this.clicked.push(ix)),
+                                    )
+                                    .child(format!("Item {ix}"))
+                            })
+                            .collect()
+                    }),
+                )
+                // The third item is cut off above its center.
+                .h(px(22.))
+                .accessibility_name("Items"),
+            )
+        }
+    }
+
+    #[gpui::test]
+    fn test_accessibility_action_on_partly_visible_node(cx: &mut TestAppContext) {
+        let (list, cx) = cx.add_window_view(|_, _| ScrolledList {
+            clicked: Vec::new(),
+        });
+        cx.simulate_resize(size(px(200.), px(200.)));
+        cx.enable_accessibility();
+
+        let tree = cx.accessibility_tree();
+        assert!(tree.starts_with(concat!(
+            "window\n",
+            "  list \"Items\"\n",
+            "    list item \"Item 0\"\n",
+            "      button \"Item 0\"\n",
+            "        label \"Item 0\"\n",
+        )));
+        assert!(tree.contains("      button \"Item 2\"\n"));
+        assert!(!tree.contains("Item 9"));
+
+        cx.simulate_accessibility_action(
+            AccessibilityRole::Button,
+            "Item 2",
+            AccessibilityAction::Click,
+        );
+        assert_eq!(list.read_with(cx, |list, _| list.clicked.clone()), [2]);
+    }
+}
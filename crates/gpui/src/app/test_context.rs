@@ -1,7 +1,8 @@
 use crate::{
-    Action, AnyView, AnyWindowHandle, App, AppCell, AppContext, AsyncApp, AvailableSpace,
-    BackgroundExecutor, BorrowAppContext, Bounds, Capslock, ClipboardItem, DrawPhase, Drawable,
-    Element, Empty, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Modifiers,
+    AccessibilityAction, AccessibilityActionRequest, AccessibilityRole, Action, AnyView,
+    AnyWindowHandle, App, AppCell, AppContext, AsyncApp, AvailableSpace, BackgroundExecutor,
+    BorrowAppContext, Bounds, Capslock, ClipboardItem, DrawPhase, Drawable, Element, Empty,
+    EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Modifiers,
     ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels,
     Platform, Point, Render, Result, Size, Task, TestDispatcher, TestPlatform,
     TestScreenCaptureSource, TestWindow, TextSystem, VisualContext, Window, WindowBounds,
@@ -827,6 +828,55 @@ impl VisualTestContext {
         self.update(|window, _| window.rendered_frame.debug_bounds.get(selector).copied())
     }
 
+    /// Rasterizes the window's most recently rendered frame on the CPU, to
+    /// assert on its pixels. See [`Window::render_to_image`].
+    pub fn capture_frame(&mut self) -> image::RgbaImage {
+        self.run_until_parked();
+        self.update(|window, _| window.render_to_image().unwrap())
+    }
+
+    /// Builds an accessibility tree on every frame from now on, as if an
+    /// assistive technology had connected to the window.
+    pub fn enable_accessibility(&mut self) {
+        self.cx
+            .test_window(self.window)
+            .0
+            .lock()
+            .accessibility_active = true;
+        self.update(|window, _| window.refresh());
+        self.run_until_parked();
+    }
+
+    /// Returns the accessibility tree of the most recently rendered frame as an
+    /// indented outline. Requires [`Self::enable_accessibility`].
+    pub fn accessibility_tree(&mut self) -> String {
+        self.run_until_parked();
+        self.update(|window, _| window.accessibility_tree().to_string())
+    }
+
+    /// Simulates an assistive technology performing an action on the first node
+    /// with the given role and name.
+    pub fn simulate_accessibility_action(
+        &mut self,
+        role: AccessibilityRole,
+        name: &str,
+        action: AccessibilityAction,
+    ) {
+        self.run_until_parked();
+        let node = self
+            .update(|window, _| {
+                window
+                    .accessibility_tree()
+                    .find(role, name)
+                    .map(|node| node.id())
+            })
+            .unwrap_or_else(|| panic!("no {role} named {name:?} in the accessibility tree"));
+        self.cx
+            .test_window(self.window)
+            .simulate_accessibility_action(AccessibilityActionRequest { node, action });
+        self.run_until_parked();
+    }
+
     /// Draw an element to the window. Useful for simulating events or actions
     pub fn draw<E>(
         &mut self,
@@ -5,10 +5,10 @@
 //! elements with uniform height.
 
 use crate::{
-    AnyElement, App, AvailableSpace, Bounds, ContentMask, Element, ElementId, Entity,
-    GlobalElementId, Hitbox, InspectorElementId, InteractiveElement, Interactivity, IntoElement,
-    IsZero, LayoutId, ListSizingBehavior, Overflow, Pixels, Point, ScrollHandle, Size,
-    StyleRefinement, Styled, Window, point, size,
+    AccessibilityNode, AccessibilityRole, AnyElement, App, AvailableSpace, Bounds, ContentMask,
+    Element, ElementId, Entity, GlobalElementId, Hitbox, InspectorElementId, InteractiveElement,
+    Interactivity, IntoElement, IsZero, LayoutId, ListSizingBehavior, Overflow, Pixels, Point,
+    ScrollHandle, Size, StyleRefinement, Styled, Window, point, size,
 };
 use smallvec::SmallVec;
 use std::{cell::RefCell, cmp, ops::Range, rc::Rc, usize};
@@ -46,6 +46,7 @@ where
         interactivity: Interactivity {
             element_id: Some(id),
             base_style: Box::new(base_style),
+            accessibility_role: Some(AccessibilityRole::List),
             ..Interactivity::new()
         },
         scroll_handle: None,
@@ -70,7 +71,7 @@ pub struct UniformList {
 
 /// Frame state used by the [UniformList].
 pub struct UniformListFrameState {
-    items: SmallVec<[AnyElement; 32]>,
+    items: SmallVec<[(AnyElement, Bounds<Pixels>); 32]>,
     decorations: SmallVec<[AnyElement; 2]>,
 }
 
@@ -495,7 +496,9 @@ impl Element for UniformList {
                             );
                             item.layout_as_root(available_space, window, cx);
                             item.prepaint_at(item_origin, window, cx);
-                            frame_state.items.push(item);
+                            let item_bounds =
+                                Bounds::new(item_origin, size(available_width, item_height));
+                            frame_state.items.push((item, item_bounds));
                         }
 
                         let bounds =
@@ -544,8 +547,11 @@ impl Element for UniformList {
             window,
             cx,
             |_, window, cx| {
-                for item in &mut request_layout.items {
-                    item.paint(window, cx);
+                for (item, item_bounds) in &mut request_layout.items {
+                    let node = window
+                        .is_accessibility_enabled()
+                        .then(|| AccessibilityNode::new(AccessibilityRole::ListItem, *item_bounds));
+                    window.with_accessibility_node(None, node, |window| item.paint(window, cx));
                 }
                 for decoration in &mut request_layout.decorations {
                     decoration.paint(window, cx);
@@ -17,6 +17,14 @@ mod mac;
 ))]
 mod blade;
 
+#[cfg(any(
+    test,
+    feature = "test-support",
+    target_os = "linux",
+    target_os = "freebsd"
+))]
+mod software;
+
 #[cfg(any(test, feature = "test-support"))]
 mod test;
 
@@ -36,12 +44,13 @@ mod windows;
 pub(crate) mod scap_screen_capture;
 
 use crate::{
-    Action, AnyWindowHandle, App, AsyncWindowContext, BackgroundExecutor, Bounds,
-    DEFAULT_WINDOW_SIZE, DevicePixels, DispatchEventResult, Font, FontId, FontMetrics, FontRun,
-    ForegroundExecutor, GlyphId, GpuSpecs, ImageSource, Keymap, LineLayout, Pixels, PlatformInput,
-    Point, RenderGlyphParams, RenderImage, RenderImageParams, RenderSvgParams, Scene, ShapedGlyph,
-    ShapedRun, SharedString, Size, SvgRenderer, SystemWindowTab, Task, TaskLabel, TaskTiming,
-    ThreadTaskTimings, Window, WindowControlArea, hash, point, px, size,
+    AccessibilityActionRequest, AccessibilityTree, Action, AnyWindowHandle, App,
+    AsyncWindowContext, BackgroundExecutor, Bounds, DEFAULT_WINDOW_SIZE, DevicePixels,
+    DispatchEventResult, Font, FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId, GpuSpecs,
+    ImageSource, Keymap, LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams, RenderImage,
+    RenderImageParams, RenderSvgParams, Scene, ShapedGlyph, ShapedRun, SharedString, Size,
+    SvgRenderer, SystemWindowTab, Task, TaskLabel, TaskTiming, ThreadTaskTimings, Window,
+    WindowControlArea, hash, point, px, size,
 };
 use anyhow::Result;
 use async_task::Runnable;
@@ -503,6 +512,13 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     fn completed_frame(&self) {}
     fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas>;
 
+    // Accessibility, only implemented on Linux and the test platform so far
+    fn is_accessibility_active(&self) -> bool {
+        false
+    }
+    fn update_accessibility_tree(&self, _tree: &AccessibilityTree) {}
+    fn on_accessibility_action(&self, _callback: Box<dyn FnMut(AccessibilityActionRequest)>) {}
+
     // macOS specific methods
     fn get_title(&self) -> String {
         String::new()
@@ -552,6 +568,12 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
 
     fn update_ime_position(&self, _bounds: Bounds<Pixels>);
 
+    /// Rasterizes a scene drawn in this window, which is only supported by
+    /// windows drawn with the software renderer.
+    fn render_to_image(&self, _scene: &Scene) -> Result<image::RgbaImage> {
+        anyhow::bail!("rendering to an image is not supported by this window")
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     fn as_test(&mut self) -> Option<&mut TestWindow> {
         None
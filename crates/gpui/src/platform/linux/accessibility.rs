@@ -0,0 +1,203 @@
+use crate::{
+    AccessibilityAction, AccessibilityActionRequest, AccessibilityNode, AccessibilityNodeId,
+    AccessibilityRole, AccessibilityTree, Bounds, ForegroundExecutor, Pixels, Task,
+};
+use accesskit::{
+    Action, ActionHandler, ActionRequest, ActivationHandler, DeactivationHandler, Node, NodeId,
+    Rect, Role, Tree, TreeUpdate,
+};
+use futures::{StreamExt as _, channel::mpsc};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Whether an assistive technology is connected, shared by all windows.
+///
+/// Screen readers talk to the application as a whole, so once one asks for
+/// any window's tree, every window starts building one.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Incremented whenever an assistive technology connects, so that each
+/// window can tell it has to render a frame that includes its tree.
+static ACTIVATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Exposes a window's [`AccessibilityTree`] to assistive technologies over AT-SPI.
+///
+/// AT-SPI represents each top-level window as its own object, so every window
+/// has an adapter, while `accesskit_unix` shares one bus connection between
+/// them. The adapters stay dormant until a screen reader asks for a tree. Only
+/// then do windows start building one, so there is no cost when no assistive
+/// technology is running.
+pub(crate) struct LinuxAccessibility {
+    adapter: accesskit_unix::Adapter,
+    seen_activations: AtomicUsize,
+    action_requests: Option<mpsc::UnboundedReceiver<AccessibilityActionRequest>>,
+    _action_task: Option<Task<()>>,
+}
+
+impl LinuxAccessibility {
+    pub fn new() -> Self {
+        let (action_tx, action_rx) = mpsc::unbounded();
+        let adapter = accesskit_unix::Adapter::new(Activation, Actions(action_tx), Deactivation);
+        Self {
+            adapter,
+            seen_activations: AtomicUsize::new(0),
+            action_requests: Some(action_rx),
+            _action_task: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        ACTIVE.load(Ordering::SeqCst)
+    }
+
+    /// Returns true once after an assistive technology has connected, so that
+    /// the window can render a frame that includes the tree.
+    pub fn take_activation_request(&self) -> bool {
+        let activations = ACTIVATIONS.load(Ordering::SeqCst);
+        self.seen_activations.swap(activations, Ordering::SeqCst) != activations
+    }
+
+    pub fn update(&mut self, tree: &AccessibilityTree, scale_factor: f32) {
+        let Some(root) = tree.root() else {
+            return;
+        };
+        self.adapter.update_if_active(|| {
+            let nodes = tree
+                .nodes()
+                .map(|node| {
+                    (
+                        NodeId(node.id().0),
+                        to_accesskit_node(tree, node, scale_factor),
+                    )
+                })
+                .collect();
+            TreeUpdate {
+                nodes,
+                tree: Some(Tree::new(NodeId(root.id().0))),
+                focus: NodeId(tree.focus().unwrap_or(root).id().0),
+            }
+        });
+    }
+
+    pub fn set_window_bounds(&mut self, bounds: Bounds<Pixels>, scale_factor: f32) {
+        let bounds = to_rect(bounds, scale_factor);
+        self.adapter.set_root_window_bounds(bounds, bounds);
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.adapter.update_window_focus_state(focused);
+    }
+
+    pub fn on_action(
+        &mut self,
+        executor: &ForegroundExecutor,
+        mut callback: Box<dyn FnMut(AccessibilityActionRequest)>,
+    ) {
+        let Some(mut requests) = self.action_requests.take() else {
+            return;
+        };
+        self._action_task = Some(executor.spawn(async move {
+            while let Some(request) = requests.next().await {
+                callback(request);
+            }
+        }));
+    }
+}
+
+struct Activation;
+
+impl ActivationHandler for Activation {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        // The tree is only built while painting, so ask every window for a
+        // frame and push it from there.
+        if !ACTIVE.swap(true, Ordering::SeqCst) {
+            ACTIVATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        None
+    }
+}
+
+struct Deactivation;
+
+impl DeactivationHandler for Deactivation {
+    fn deactivate_accessibility(&mut self) {
+        ACTIVE.store(false, Ordering::SeqCst);
+    }
+}
+
+struct Actions(mpsc::UnboundedSender<AccessibilityActionRequest>);
+
+impl ActionHandler for Actions {
+    fn do_action(&mut self, request: ActionRequest) {
+        let action = match request.action {
+            Action::Click => AccessibilityAction::Click,
+            Action::Focus => AccessibilityAction::Focus,
+            _ => return,
+        };
+        self.0
+            .unbounded_send(AccessibilityActionRequest {
+                node: AccessibilityNodeId(request.target.0),
+                action,
+            })
+            .ok();
+    }
+}
+
+fn to_accesskit_node(
+    tree: &AccessibilityTree,
+    node: &AccessibilityNode,
+    scale_factor: f32,
+) -> Node {
+    let mut result = Node::new(to_accesskit_role(node.role));
+    result.set_bounds(to_rect(node.bounds, scale_factor));
+    if let Some(name) = &node.name {
+        result.set_label(name.to_string());
+    }
+    if let Some(value) = &node.value {
+        result.set_value(value.to_string());
+    }
+    for action in &node.actions {
+        result.add_action(match action {
+            AccessibilityAction::Click => Action::Click,
+            AccessibilityAction::Focus => Action::Focus,
+        });
+    }
+    result.set_children(
+        tree.children(node)
+            .map(|child| NodeId(child.id().0))
+            .collect::<Vec<_>>(),
+    );
+    result
+}
+
+fn to_accesskit_role(role: AccessibilityRole) -> Role {
+    match role {
+        AccessibilityRole::Window => Role::Window,
+        AccessibilityRole::Group => Role::Group,
+        AccessibilityRole::Label => Role::Label,
+        AccessibilityRole::Heading => Role::Heading,
+        AccessibilityRole::Button => Role::Button,
+        AccessibilityRole::CheckBox => Role::CheckBox,
+        AccessibilityRole::Link => Role::Link,
+        AccessibilityRole::TextInput => Role::TextInput,
+        AccessibilityRole::MultilineTextInput => Role::MultilineTextInput,
+        AccessibilityRole::List => Role::List,
+        AccessibilityRole::ListItem => Role::ListItem,
+        AccessibilityRole::TabList => Role::TabList,
+        AccessibilityRole::Tab => Role::Tab,
+        AccessibilityRole::Menu => Role::Menu,
+        AccessibilityRole::MenuItem => Role::MenuItem,
+        AccessibilityRole::Dialog => Role::Dialog,
+        AccessibilityRole::Toolbar => Role::Toolbar,
+        AccessibilityRole::Image => Role::Image,
+        AccessibilityRole::ScrollView => Role::ScrollView,
+    }
+}
+
+fn to_rect(bounds: Bounds<Pixels>, scale_factor: f32) -> Rect {
+    let bounds = bounds.scale(scale_factor);
+    Rect {
+        x0: bounds.origin.x.0 as f64,
+        y0: bounds.origin.y.0 as f64,
+        x1: (bounds.origin.x.0 + bounds.size.width.0) as f64,
+        y1: (bounds.origin.y.0 + bounds.size.height.0) as f64,
+    }
+}
@@ -1,3 +1,5 @@
 mod client;
+mod window;
 
 pub(crate) use client::*;
+pub(crate) use window::*;
@@ -1,6 +1,8 @@
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Duration;
 
+use calloop::timer::{TimeoutAction, Timer};
 use calloop::{EventLoop, LoopHandle};
 use util::ResultExt;
 
@@ -11,8 +13,13 @@ use crate::{
     PlatformKeyboardLayout, WindowParams,
 };
 
+use super::HeadlessWindow;
+
+/// How often headless windows get to draw a new frame.
+const FRAME_INTERVAL: Duration = Duration::from_micros(1_000_000 / 60);
+
 pub struct HeadlessClientState {
-    pub(crate) _loop_handle: LoopHandle<'static, HeadlessClient>,
+    pub(crate) loop_handle: LoopHandle<'static, HeadlessClient>,
     pub(crate) event_loop: Option<calloop::EventLoop<'static, HeadlessClient>>,
     pub(crate) common: LinuxCommon,
 }
@@ -41,7 +48,7 @@ impl HeadlessClient {
 
         HeadlessClient(Rc::new(RefCell::new(HeadlessClientState {
             event_loop: Some(event_loop),
-            _loop_handle: handle,
+            loop_handle: handle,
             common,
         })))
     }
@@ -97,9 +104,24 @@ impl LinuxClient for HeadlessClient {
     fn open_window(
         &self,
         _handle: AnyWindowHandle,
-        _params: WindowParams,
+        params: WindowParams,
     ) -> anyhow::Result<Box<dyn PlatformWindow>> {
-        anyhow::bail!("neither DISPLAY nor WAYLAND_DISPLAY is set. You can run in headless mode");
+        let window = HeadlessWindow::new(params);
+        let weak_window = window.downgrade();
+        self.0
+            .borrow()
+            .loop_handle
+            .insert_source(Timer::immediate(), move |_, _, _| {
+                let Some(window) = HeadlessWindow::upgrade(&weak_window) else {
+                    return TimeoutAction::Drop;
+                };
+                window.refresh();
+                TimeoutAction::ToDuration(FRAME_INTERVAL)
+            })
+            .map_err(|error| {
+                anyhow::anyhow!("failed to start the window's frame timer: {error}")
+            })?;
+        Ok(Box::new(window))
     }
 
     fn compositor_name(&self) -> &'static str {
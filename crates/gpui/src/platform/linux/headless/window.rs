@@ -0,0 +1,229 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+    sync::Arc,
+};
+
+use futures::channel::oneshot::Receiver;
+use image::RgbaImage;
+use raw_window_handle::{HandleError, HasDisplayHandle, HasWindowHandle};
+
+use crate::{
+    Bounds, Capslock, DispatchEventResult, GpuSpecs, Modifiers, Pixels, PlatformAtlas,
+    PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point, PromptButton,
+    PromptLevel, RequestFrameOptions, Scene, Size, WindowAppearance, WindowBackgroundAppearance,
+    WindowBounds, WindowControlArea, WindowParams,
+    platform::software::{SoftwareAtlas, SoftwareRenderer},
+};
+
+pub(crate) struct HeadlessWindowState {
+    bounds: Bounds<Pixels>,
+    title: String,
+    renderer: SoftwareRenderer,
+    request_frame_callback: Option<Box<dyn FnMut(RequestFrameOptions)>>,
+    resize_callback: Option<Box<dyn FnMut(Size<Pixels>, f32)>>,
+    resized: bool,
+    input_handler: Option<PlatformInputHandler>,
+}
+
+/// A window that isn't shown anywhere. Its frames are drawn on demand by the
+/// software renderer, with [`Window::render_to_image`](crate::Window::render_to_image).
+#[derive(Clone)]
+pub(crate) struct HeadlessWindow(Rc<RefCell<HeadlessWindowState>>);
+
+impl HeadlessWindow {
+    pub(crate) fn new(params: WindowParams) -> Self {
+        Self(Rc::new(RefCell::new(HeadlessWindowState {
+            bounds: params.bounds,
+            title: String::new(),
+            renderer: SoftwareRenderer::new(Arc::new(SoftwareAtlas::new())),
+            request_frame_callback: None,
+            resize_callback: None,
+            resized: false,
+            input_handler: None,
+        })))
+    }
+
+    pub(crate) fn downgrade(&self) -> Weak<RefCell<HeadlessWindowState>> {
+        Rc::downgrade(&self.0)
+    }
+
+    pub(crate) fn upgrade(state: &Weak<RefCell<HeadlessWindowState>>) -> Option<Self> {
+        state.upgrade().map(Self)
+    }
+
+    /// Reports pending resizes and lets the window draw a new frame if it was
+    /// invalidated.
+    pub(crate) fn refresh(&self) {
+        let mut state = self.0.borrow_mut();
+        if std::mem::take(&mut state.resized)
+            && let Some(mut callback) = state.resize_callback.take()
+        {
+            let size = state.bounds.size;
+            drop(state);
+            callback(size, self.scale_factor());
+            self.0.borrow_mut().resize_callback = Some(callback);
+        } else {
+            drop(state);
+        }
+
+        let Some(mut callback) = self.0.borrow_mut().request_frame_callback.take() else {
+            return;
+        };
+        callback(RequestFrameOptions::default());
+        self.0.borrow_mut().request_frame_callback = Some(callback);
+    }
+}
+
+impl HasWindowHandle for HeadlessWindow {
+    fn window_handle(&self) -> Result<raw_window_handle::WindowHandle<'_>, HandleError> {
+        Err(HandleError::NotSupported)
+    }
+}
+
+impl HasDisplayHandle for HeadlessWindow {
+    fn display_handle(&self) -> Result<raw_window_handle::DisplayHandle<'_>, HandleError> {
+        Err(HandleError::NotSupported)
+    }
+}
+
+impl PlatformWindow for HeadlessWindow {
+    fn bounds(&self) -> Bounds<Pixels> {
+        self.0.borrow().bounds
+    }
+
+    fn is_maximized(&self) -> bool {
+        false
+    }
+
+    fn window_bounds(&self) -> WindowBounds {
+        WindowBounds::Windowed(self.bounds())
+    }
+
+    fn content_size(&self) -> Size<Pixels> {
+        self.bounds().size
+    }
+
+    // The resize is reported on the next refresh, as the window is being
+    // updated when this is called.
+    fn resize(&mut self, size: Size<Pixels>) {
+        let mut state = self.0.borrow_mut();
+        state.bounds.size = size;
+        state.resized = true;
+    }
+
+    fn scale_factor(&self) -> f32 {
+        1.0
+    }
+
+    fn appearance(&self) -> WindowAppearance {
+        WindowAppearance::Light
+    }
+
+    fn display(&self) -> Option<Rc<dyn PlatformDisplay>> {
+        None
+    }
+
+    fn mouse_position(&self) -> Point<Pixels> {
+        Point::default()
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        Modifiers::default()
+    }
+
+    fn capslock(&self) -> Capslock {
+        Capslock::default()
+    }
+
+    fn set_input_handler(&mut self, input_handler: PlatformInputHandler) {
+        self.0.borrow_mut().input_handler = Some(input_handler);
+    }
+
+    fn take_input_handler(&mut self) -> Option<PlatformInputHandler> {
+        self.0.borrow_mut().input_handler.take()
+    }
+
+    fn prompt(
+        &self,
+        _level: PromptLevel,
+        _msg: &str,
+        _detail: Option<&str>,
+        _answers: &[PromptButton],
+    ) -> Option<Receiver<usize>> {
+        None
+    }
+
+    fn activate(&self) {}
+
+    fn is_active(&self) -> bool {
+        false
+    }
+
+    fn is_hovered(&self) -> bool {
+        false
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.0.borrow_mut().title = title.to_owned();
+    }
+
+    fn get_title(&self) -> String {
+        self.0.borrow().title.clone()
+    }
+
+    fn set_background_appearance(&self, _background_appearance: WindowBackgroundAppearance) {}
+
+    fn minimize(&self) {}
+
+    fn zoom(&self) {}
+
+    fn toggle_fullscreen(&self) {}
+
+    fn is_fullscreen(&self) -> bool {
+        false
+    }
+
+    fn on_request_frame(&self, callback: Box<dyn FnMut(RequestFrameOptions)>) {
+        self.0.borrow_mut().request_frame_callback = Some(callback);
+    }
+
+    fn on_input(&self, _callback: Box<dyn FnMut(PlatformInput) -> DispatchEventResult>) {}
+
+    fn on_active_status_change(&self, _callback: Box<dyn FnMut(bool)>) {}
+
+    fn on_hover_status_change(&self, _callback: Box<dyn FnMut(bool)>) {}
+
+    fn on_resize(&self, callback: Box<dyn FnMut(Size<Pixels>, f32)>) {
+        self.0.borrow_mut().resize_callback = Some(callback);
+    }
+
+    fn on_moved(&self, _callback: Box<dyn FnMut()>) {}
+
+    fn on_should_close(&self, _callback: Box<dyn FnMut() -> bool>) {}
+
+    fn on_hit_test_window_control(&self, _callback: Box<dyn FnMut() -> Option<WindowControlArea>>) {
+    }
+
+    fn on_close(&self, _callback: Box<dyn FnOnce()>) {}
+
+    fn on_appearance_changed(&self, _callback: Box<dyn FnMut()>) {}
+
+    // Frames are only rasterized when they are read back.
+    fn draw(&self, _scene: &Scene) {}
+
+    fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas> {
+        self.0.borrow().renderer.sprite_atlas().clone()
+    }
+
+    fn gpu_specs(&self) -> Option<GpuSpecs> {
+        None
+    }
+
+    fn update_ime_position(&self, _bounds: Bounds<Pixels>) {}
+
+    fn render_to_image(&self, scene: &Scene) -> anyhow::Result<RgbaImage> {
+        let size = self.content_size().to_device_pixels(self.scale_factor());
+        Ok(self.0.borrow().renderer.render(scene, size))
+    }
+}
@@ -0,0 +1,9 @@
+//! A CPU rasterizer for scenes. It draws the windows of the headless platform
+//! on Linux and test windows, whose frames can be read back with
+//! [`Window::render_to_image`](crate::Window::render_to_image).
+
+mod software_atlas;
+mod software_renderer;
+
+pub(crate) use software_atlas::*;
+pub(crate) use software_renderer::*;
@@ -0,0 +1,92 @@
+use crate::{
+    AtlasKey, AtlasTextureId, AtlasTile, Bounds, DevicePixels, PlatformAtlas, Point, Size, TileId,
+};
+use anyhow::Result;
+use collections::HashMap;
+use parking_lot::Mutex;
+use std::{borrow::Cow, sync::Arc};
+
+/// The pixels backing a single atlas tile.
+///
+/// Monochrome textures store one alpha byte per pixel, polychrome textures
+/// store four bytes per pixel in BGRA order with straight alpha, matching the
+/// layout the GPU atlases upload.
+pub(crate) struct SoftwareTexture {
+    pub size: Size<DevicePixels>,
+    pub bytes: Vec<u8>,
+}
+
+struct SoftwareAtlasState {
+    next_id: u32,
+    tiles: HashMap<AtlasKey, AtlasTile>,
+    textures: HashMap<AtlasTextureId, Arc<SoftwareTexture>>,
+}
+
+/// An atlas that keeps rasterized glyphs, SVGs and images in main memory, so
+/// that they can be sampled by the [`SoftwareRenderer`](super::SoftwareRenderer).
+///
+/// Every tile gets a texture of its own, which keeps lookups trivial at the
+/// cost of not packing anything.
+pub(crate) struct SoftwareAtlas(Mutex<SoftwareAtlasState>);
+
+impl SoftwareAtlas {
+    pub fn new() -> Self {
+        SoftwareAtlas(Mutex::new(SoftwareAtlasState {
+            next_id: 0,
+            tiles: HashMap::default(),
+            textures: HashMap::default(),
+        }))
+    }
+
+    pub fn texture(&self, id: AtlasTextureId) -> Option<Arc<SoftwareTexture>> {
+        self.0.lock().textures.get(&id).cloned()
+    }
+}
+
+impl PlatformAtlas for SoftwareAtlas {
+    fn get_or_insert_with<'a>(
+        &self,
+        key: &AtlasKey,
+        build: &mut dyn FnMut() -> Result<Option<(Size<DevicePixels>, Cow<'a, [u8]>)>>,
+    ) -> Result<Option<AtlasTile>> {
+        if let Some(tile) = self.0.lock().tiles.get(key) {
+            return Ok(Some(tile.clone()));
+        }
+
+        let Some((size, bytes)) = build()? else {
+            return Ok(None);
+        };
+
+        let mut state = self.0.lock();
+        state.next_id += 1;
+        let texture_id = AtlasTextureId {
+            index: state.next_id,
+            kind: key.texture_kind(),
+        };
+        let tile = AtlasTile {
+            texture_id,
+            tile_id: TileId(state.next_id),
+            padding: 0,
+            bounds: Bounds {
+                origin: Point::default(),
+                size,
+            },
+        };
+        state.textures.insert(
+            texture_id,
+            Arc::new(SoftwareTexture {
+                size,
+                bytes: bytes.into_owned(),
+            }),
+        );
+        state.tiles.insert(key.clone(), tile.clone());
+        Ok(Some(tile))
+    }
+
+    fn remove(&self, key: &AtlasKey) {
+        let mut state = self.0.lock();
+        if let Some(tile) = state.tiles.remove(key) {
+            state.textures.remove(&tile.texture_id);
+        }
+    }
+}
@@ -0,0 +1,985 @@
+use super::SoftwareAtlas;
+use crate::{
+    AtlasTile, Background, BackgroundTag, Bounds, ColorSpace, ContentMask, Corners, DevicePixels,
+    Hsla, MonochromeSprite, Path, PolychromeSprite, PrimitiveBatch, Quad, Rgba, ScaledPixels,
+    Scene, Shadow, Size, Underline,
+};
+use image::RgbaImage;
+use std::{f32::consts::PI, sync::Arc};
+
+const GRAYSCALE_FACTORS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// Rasterizes a [`Scene`] on the CPU.
+///
+/// The renderer mirrors the shaders in `platform/blade/shaders.wgsl` closely
+/// enough for snapshot tests and image export, but favors simplicity over
+/// speed: every primitive is evaluated once per covered pixel, sampled at the
+/// pixel center. Dashed borders are drawn solid and surfaces are skipped.
+pub(crate) struct SoftwareRenderer {
+    atlas: Arc<SoftwareAtlas>,
+}
+
+impl SoftwareRenderer {
+    pub fn new(atlas: Arc<SoftwareAtlas>) -> Self {
+        Self { atlas }
+    }
+
+    pub fn sprite_atlas(&self) -> &Arc<SoftwareAtlas> {
+        &self.atlas
+    }
+
+    /// Draws the scene onto a transparent canvas of the given size.
+    pub fn render(&self, scene: &Scene, size: Size<DevicePixels>) -> RgbaImage {
+        let mut canvas = Canvas::new(size);
+        for batch in scene.batches() {
+            match batch {
+                PrimitiveBatch::Shadows(shadows) => {
+                    for shadow in shadows {
+                        canvas.draw_shadow(shadow);
+                    }
+                }
+                PrimitiveBatch::Quads(quads) => {
+                    for quad in quads {
+                        canvas.draw_quad(quad);
+                    }
+                }
+                PrimitiveBatch::Paths(paths) => {
+                    for path in paths {
+                        canvas.draw_path(path);
+                    }
+                }
+                PrimitiveBatch::Underlines(underlines) => {
+                    for underline in underlines {
+                        canvas.draw_underline(underline);
+                    }
+                }
+                PrimitiveBatch::MonochromeSprites {
+                    texture_id,
+                    sprites,
+                } => {
+                    if let Some(texture) = self.atlas.texture(texture_id) {
+                        for sprite in sprites {
+                            canvas.draw_monochrome_sprite(sprite, &texture.bytes);
+                        }
+                    }
+                }
+                PrimitiveBatch::PolychromeSprites {
+                    texture_id,
+                    sprites,
+                } => {
+                    if let Some(texture) = self.atlas.texture(texture_id) {
+                        for sprite in sprites {
+                            canvas.draw_polychrome_sprite(sprite, &texture.bytes);
+                        }
+                    }
+                }
+                PrimitiveBatch::Surfaces(_) => {}
+            }
+        }
+        canvas.into_image()
+    }
+}
+
+/// A rectangle in device pixels, used to bound the pixels a primitive touches.
+#[derive(Clone, Copy)]
+struct Rect {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+}
+
+impl Rect {
+    fn from_bounds(bounds: &Bounds<ScaledPixels>) -> Self {
+        Self {
+            left: bounds.origin.x.0,
+            top: bounds.origin.y.0,
+            right: bounds.origin.x.0 + bounds.size.width.0,
+            bottom: bounds.origin.y.0 + bounds.size.height.0,
+        }
+    }
+
+    fn dilate(self, amount: f32) -> Self {
+        Self {
+            left: self.left - amount,
+            top: self.top - amount,
+            right: self.right + amount,
+            bottom: self.bottom + amount,
+        }
+    }
+
+    fn intersect(self, other: Self) -> Self {
+        Self {
+            left: self.left.max(other.left),
+            top: self.top.max(other.top),
+            right: self.right.min(other.right),
+            bottom: self.bottom.min(other.bottom),
+        }
+    }
+}
+
+/// A buffer of premultiplied, floating point RGBA pixels.
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    fn new(size: Size<DevicePixels>) -> Self {
+        let width = size.width.0.max(0) as usize;
+        let height = size.height.0.max(0) as usize;
+        Self {
+            width,
+            height,
+            pixels: vec![[0.; 4]; width * height],
+        }
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let mut image = RgbaImage::new(self.width as u32, self.height as u32);
+        for (pixel, [r, g, b, a]) in image.pixels_mut().zip(self.pixels) {
+            let unpremultiply = if a > 0. { 1. / a } else { 0. };
+            pixel.0 = [
+                to_byte(r * unpremultiply),
+                to_byte(g * unpremultiply),
+                to_byte(b * unpremultiply),
+                to_byte(a),
+            ];
+        }
+        image
+    }
+
+    /// Calls `f` with the center of every pixel within `area` and the content
+    /// mask, blending the color it returns over the canvas.
+    fn fill(
+        &mut self,
+        area: Rect,
+        content_mask: &ContentMask<ScaledPixels>,
+        mut f: impl FnMut(f32, f32) -> Option<[f32; 4]>,
+    ) {
+        let area = area
+            .intersect(Rect::from_bounds(&content_mask.bounds))
+            .intersect(Rect {
+                left: 0.,
+                top: 0.,
+                right: self.width as f32,
+                bottom: self.height as f32,
+            });
+        if area.left >= area.right || area.top >= area.bottom {
+            return;
+        }
+
+        let x_range = area.left.floor() as usize..(area.right.ceil() as usize).min(self.width);
+        let y_range = area.top.floor() as usize..(area.bottom.ceil() as usize).min(self.height);
+        for y in y_range {
+            let center_y = y as f32 + 0.5;
+            if center_y < area.top || center_y > area.bottom {
+                continue;
+            }
+            for x in x_range.clone() {
+                let center_x = x as f32 + 0.5;
+                if center_x < area.left || center_x > area.right {
+                    continue;
+                }
+                if let Some(color) = f(center_x, center_y) {
+                    let destination = &mut self.pixels[y * self.width + x];
+                    let alpha = color[3].clamp(0., 1.);
+                    let source = [color[0] * alpha, color[1] * alpha, color[2] * alpha, alpha];
+                    for (destination, source) in destination.iter_mut().zip(source) {
+                        *destination = source + *destination * (1. - alpha);
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_quad(&mut self, quad: &Quad) {
+        let bounds = Rect::from_bounds(&quad.bounds);
+        let border_color = hsla_to_rgba(quad.border_color);
+        let widths = &quad.border_widths;
+        let has_border =
+            widths.top.0 > 0. || widths.right.0 > 0. || widths.bottom.0 > 0. || widths.left.0 > 0.;
+        let inner_bounds = Rect {
+            left: bounds.left + widths.left.0,
+            top: bounds.top + widths.top.0,
+            right: bounds.right - widths.right.0,
+            bottom: bounds.bottom - widths.bottom.0,
+        };
+        let radii = &quad.corner_radii;
+        let inner_radii = Corners {
+            top_left: (radii.top_left.0 - widths.top.0.max(widths.left.0)).max(0.),
+            top_right: (radii.top_right.0 - widths.top.0.max(widths.right.0)).max(0.),
+            bottom_right: (radii.bottom_right.0 - widths.bottom.0.max(widths.right.0)).max(0.),
+            bottom_left: (radii.bottom_left.0 - widths.bottom.0.max(widths.left.0)).max(0.),
+        };
+        let outer_radii = Corners {
+            top_left: radii.top_left.0,
+            top_right: radii.top_right.0,
+            bottom_right: radii.bottom_right.0,
+            bottom_left: radii.bottom_left.0,
+        };
+
+        self.fill(bounds, &quad.content_mask, |x, y| {
+            let background = background_color(&quad.background, x, y, bounds);
+            let coverage = saturate(0.5 - rounded_rect_sdf(x, y, bounds, &outer_radii));
+            if coverage <= 0. {
+                return None;
+            }
+
+            let mut color = background;
+            if has_border {
+                let inside = if inner_bounds.left < inner_bounds.right
+                    && inner_bounds.top < inner_bounds.bottom
+                {
+                    saturate(0.5 - rounded_rect_sdf(x, y, inner_bounds, &inner_radii))
+                } else {
+                    0.
+                };
+                let bordered = over(background, border_color);
+                color = mix(bordered, background, inside);
+            }
+            color[3] *= coverage;
+            Some(color)
+        });
+    }
+
+    fn draw_shadow(&mut self, shadow: &Shadow) {
+        let blur_radius = shadow.blur_radius.0;
+        let bounds = Rect::from_bounds(&shadow.bounds);
+        let color = hsla_to_rgba(shadow.color);
+        let half_width = (bounds.right - bounds.left) / 2.;
+        let half_height = (bounds.bottom - bounds.top) / 2.;
+        let center_x = bounds.left + half_width;
+        let center_y = bounds.top + half_height;
+        let radii = &shadow.corner_radii;
+
+        self.fill(
+            bounds.dilate(3. * blur_radius),
+            &shadow.content_mask,
+            |x, y| {
+                let dx = x - center_x;
+                let dy = y - center_y;
+                let corner_radius = pick_corner_radius(
+                    dx,
+                    dy,
+                    &Corners {
+                        top_left: radii.top_left.0,
+                        top_right: radii.top_right.0,
+                        bottom_right: radii.bottom_right.0,
+                        bottom_left: radii.bottom_left.0,
+                    },
+                );
+
+                let alpha = if blur_radius <= 0. {
+                    let corner_to_point_x = dx.abs() - half_width + corner_radius;
+                    let corner_to_point_y = dy.abs() - half_height + corner_radius;
+                    saturate(
+                        0.5 - rounded_rect_sdf_impl(
+                            corner_to_point_x,
+                            corner_to_point_y,
+                            corner_radius,
+                        ),
+                    )
+                } else {
+                    let low = dy - half_height;
+                    let high = dy + half_height;
+                    let start = (-3. * blur_radius).clamp(low, high);
+                    let end = (3. * blur_radius).clamp(low, high);
+                    let step = (end - start) / 4.;
+                    let mut sample_y = start + step * 0.5;
+                    let mut alpha = 0.;
+                    for _ in 0..4 {
+                        let blur = blur_along_x(
+                            dx,
+                            dy - sample_y,
+                            blur_radius,
+                            corner_radius,
+                            half_width,
+                            half_height,
+                        );
+                        alpha += blur * gaussian(sample_y, blur_radius) * step;
+                        sample_y += step;
+                    }
+                    alpha
+                };
+
+                Some([color[0], color[1], color[2], color[3] * alpha])
+            },
+        );
+    }
+
+    fn draw_underline(&mut self, underline: &Underline) {
+        const WAVE_FREQUENCY: f32 = 2.0;
+        const WAVE_HEIGHT_RATIO: f32 = 0.8;
+
+        let bounds = Rect::from_bounds(&underline.bounds);
+        let color = hsla_to_rgba(underline.color);
+        let thickness = underline.thickness.0;
+        let height = bounds.bottom - bounds.top;
+
+        self.fill(bounds, &underline.content_mask, |x, y| {
+            if underline.wavy == 0 || height <= 0. {
+                return Some(color);
+            }
+
+            let half_thickness = thickness * 0.5;
+            let st_x = (x - bounds.left) / height;
+            let st_y = (y - bounds.top) / height - 0.5;
+            let frequency = PI * WAVE_FREQUENCY * thickness / height;
+            let amplitude = (thickness * WAVE_HEIGHT_RATIO) / height;
+
+            let sine = (st_x * frequency).sin() * amplitude;
+            let d_sine = (st_x * frequency).cos() * amplitude * frequency;
+            let distance = (st_y - sine) / (1. + d_sine * d_sine).sqrt();
+            let distance_in_pixels = distance * height;
+            let distance_from_top_border = distance_in_pixels - half_thickness;
+            let distance_from_bottom_border = distance_in_pixels + half_thickness;
+            let alpha =
+                saturate(0.5 - (-distance_from_bottom_border).max(distance_from_top_border));
+            Some([color[0], color[1], color[2], color[3] * alpha])
+        });
+    }
+
+    fn draw_monochrome_sprite(&mut self, sprite: &MonochromeSprite, bytes: &[u8]) {
+        let bounds = Rect::from_bounds(&sprite.bounds);
+        let color = hsla_to_rgba(sprite.color);
+        let [[a, b], [c, d]] = sprite.transformation.rotation_scale;
+        let [tx, ty] = sprite.transformation.translation;
+        let determinant = a * d - b * c;
+        if determinant == 0. {
+            return;
+        }
+
+        // The sprite is positioned by transforming its bounds, so find the
+        // device-space rectangle it covers and map each pixel back.
+        let corners = [
+            (bounds.left, bounds.top),
+            (bounds.right, bounds.top),
+            (bounds.left, bounds.bottom),
+            (bounds.right, bounds.bottom),
+        ]
+        .map(|(x, y)| (a * x + b * y + tx, c * x + d * y + ty));
+        let area = corners.iter().fold(
+            Rect {
+                left: f32::MAX,
+                top: f32::MAX,
+                right: f32::MIN,
+                bottom: f32::MIN,
+            },
+            |area, &(x, y)| Rect {
+                left: area.left.min(x),
+                top: area.top.min(y),
+                right: area.right.max(x),
+                bottom: area.bottom.max(y),
+            },
+        );
+
+        self.fill(area, &sprite.content_mask, |x, y| {
+            let (x, y) = (x - tx, y - ty);
+            let local_x = (d * x - b * y) / determinant;
+            let local_y = (a * y - c * x) / determinant;
+            let (u, v) = tile_coordinates(local_x, local_y, bounds)?;
+            let sample = sample_tile::<1>(&sprite.tile, bytes, u, v)[0];
+            Some([color[0], color[1], color[2], color[3] * sample])
+        });
+    }
+
+    fn draw_polychrome_sprite(&mut self, sprite: &PolychromeSprite, bytes: &[u8]) {
+        let bounds = Rect::from_bounds(&sprite.bounds);
+        let radii = Corners {
+            top_left: sprite.corner_radii.top_left.0,
+            top_right: sprite.corner_radii.top_right.0,
+            bottom_right: sprite.corner_radii.bottom_right.0,
+            bottom_left: sprite.corner_radii.bottom_left.0,
+        };
+
+        self.fill(bounds, &sprite.content_mask, |x, y| {
+            let (u, v) = tile_coordinates(x, y, bounds)?;
+            let [blue, green, red, alpha] = sample_tile(&sprite.tile, bytes, u, v);
+            let mut color = [red, green, blue, alpha];
+            if sprite.grayscale {
+                let gray = red * GRAYSCALE_FACTORS[0]
+                    + green * GRAYSCALE_FACTORS[1]
+                    + blue * GRAYSCALE_FACTORS[2];
+                color = [gray, gray, gray, alpha];
+            }
+            let distance = rounded_rect_sdf(x, y, bounds, &radii);
+            color[3] *= sprite.opacity * saturate(0.5 - distance);
+            Some(color)
+        });
+    }
+
+    fn draw_path(&mut self, path: &Path<ScaledPixels>) {
+        let bounds = Rect::from_bounds(&path.bounds);
+        for triangle in path.vertices.chunks_exact(3) {
+            let [p0, p1, p2] = [0, 1, 2].map(|ix| {
+                let position = triangle[ix].xy_position;
+                (position.x.0, position.y.0)
+            });
+            let [st0, st1, st2] = [0, 1, 2].map(|ix| {
+                let st = triangle[ix].st_position;
+                (st.x, st.y)
+            });
+
+            let area = (p1.0 - p0.0) * (p2.1 - p0.1) - (p2.0 - p0.0) * (p1.1 - p0.1);
+            if area == 0. {
+                continue;
+            }
+
+            // The st coordinates are affine over the triangle, so their
+            // derivatives are constant and can be computed up front.
+            let ds_dx = ((st1.0 - st0.0) * (p2.1 - p0.1) - (st2.0 - st0.0) * (p1.1 - p0.1)) / area;
+            let ds_dy = ((st2.0 - st0.0) * (p1.0 - p0.0) - (st1.0 - st0.0) * (p2.0 - p0.0)) / area;
+            let dt_dx = ((st1.1 - st0.1) * (p2.1 - p0.1) - (st2.1 - st0.1) * (p1.1 - p0.1)) / area;
+            let dt_dy = ((st2.1 - st0.1) * (p1.0 - p0.0) - (st1.1 - st0.1) * (p2.0 - p0.0)) / area;
+
+            let triangle_bounds = Rect {
+                left: p0.0.min(p1.0).min(p2.0),
+                top: p0.1.min(p1.1).min(p2.1),
+                right: p0.0.max(p1.0).max(p2.0),
+                bottom: p0.1.max(p1.1).max(p2.1),
+            };
+
+            self.fill(triangle_bounds, &path.content_mask, |x, y| {
+                let w1 = ((x - p0.0) * (p2.1 - p0.1) - (p2.0 - p0.0) * (y - p0.1)) / area;
+                let w2 = ((p1.0 - p0.0) * (y - p0.1) - (x - p0.0) * (p1.1 - p0.1)) / area;
+                let w0 = 1. - w1 - w2;
+                if w0 < 0. || w1 < 0. || w2 < 0. {
+                    return None;
+                }
+
+                let s = w0 * st0.0 + w1 * st1.0 + w2 * st2.0;
+                let t = w0 * st0.1 + w1 * st1.1 + w2 * st2.1;
+                let alpha = if ds_dx.hypot(ds_dy) < 0.001 {
+                    1.
+                } else {
+                    let gradient_x = 2. * s * ds_dx - dt_dx;
+                    let gradient_y = 2. * s * ds_dy - dt_dy;
+                    let distance = (s * s - t) / gradient_x.hypot(gradient_y);
+                    saturate(0.5 - distance)
+                };
+
+                let mut color = background_color(&path.color, x, y, bounds);
+                color[3] *= alpha;
+                Some(color)
+            });
+        }
+    }
+}
+
+fn to_byte(value: f32) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
+}
+
+fn saturate(value: f32) -> f32 {
+    value.clamp(0., 1.)
+}
+
+fn hsla_to_rgba(color: Hsla) -> [f32; 4] {
+    let Rgba { r, g, b, a } = color.to_rgb();
+    [r, g, b, a]
+}
+
+fn mix(from: [f32; 4], to: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|ix| from[ix] + (to[ix] - from[ix]) * t)
+}
+
+fn over(below: [f32; 4], above: [f32; 4]) -> [f32; 4] {
+    let alpha = above[3] + below[3] * (1. - above[3]);
+    if alpha <= 0. {
+        return [0.; 4];
+    }
+    let channel =
+        |ix: usize| (above[ix] * above[3] + below[ix] * below[3] * (1. - above[3])) / alpha;
+    [channel(0), channel(1), channel(2), alpha]
+}
+
+/// Maps a point within `bounds` to normalized tile coordinates.
+fn tile_coordinates(x: f32, y: f32, bounds: Rect) -> Option<(f32, f32)> {
+    let width = bounds.right - bounds.left;
+    let height = bounds.bottom - bounds.top;
+    if width <= 0. || height <= 0. {
+        return None;
+    }
+    let u = (x - bounds.left) / width;
+    let v = (y - bounds.top) / height;
+    ((0. ..=1.).contains(&u) && (0. ..=1.).contains(&v)).then_some((u, v))
+}
+
+/// Bilinearly samples a tile, returning each channel in the 0..=1 range.
+fn sample_tile<const CHANNELS: usize>(
+    tile: &AtlasTile,
+    bytes: &[u8],
+    u: f32,
+    v: f32,
+) -> [f32; CHANNELS] {
+    let width = tile.bounds.size.width.0.max(0) as usize;
+    let height = tile.bounds.size.height.0.max(0) as usize;
+    let origin_x = tile.bounds.origin.x.0.max(0) as usize;
+    let origin_y = tile.bounds.origin.y.0.max(0) as usize;
+    let stride = (origin_x + width) * CHANNELS;
+    let mut result = [0.; CHANNELS];
+    if width == 0 || height == 0 {
+        return result;
+    }
+
+    let x = (u * width as f32 - 0.5).clamp(0., (width - 1) as f32);
+    let y = (v * height as f32 - 0.5).clamp(0., (height - 1) as f32);
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let texel = |x: usize, y: usize, channel: usize| {
+        let offset = (origin_y + y) * stride + (origin_x + x) * CHANNELS + channel;
+        bytes.get(offset).copied().unwrap_or(0) as f32 / 255.
+    };
+    for (channel, value) in result.iter_mut().enumerate() {
+        let top = texel(x0, y0, channel) * (1. - fx) + texel(x1, y0, channel) * fx;
+        let bottom = texel(x0, y1, channel) * (1. - fx) + texel(x1, y1, channel) * fx;
+        *value = top * (1. - fy) + bottom * fy;
+    }
+    result
+}
+
+fn pick_corner_radius(dx: f32, dy: f32, radii: &Corners<f32>) -> f32 {
+    match (dx < 0., dy < 0.) {
+        (true, true) => radii.top_left,
+        (true, false) => radii.bottom_left,
+        (false, true) => radii.top_right,
+        (false, false) => radii.bottom_right,
+    }
+}
+
+/// Signed distance from the point to the edge of a rounded rectangle, which is
+/// positive outside the rectangle and negative inside.
+fn rounded_rect_sdf(x: f32, y: f32, bounds: Rect, radii: &Corners<f32>) -> f32 {
+    let half_width = (bounds.right - bounds.left) / 2.;
+    let half_height = (bounds.bottom - bounds.top) / 2.;
+    let dx = x - (bounds.left + half_width);
+    let dy = y - (bounds.top + half_height);
+    let corner_radius = pick_corner_radius(dx, dy, radii);
+    rounded_rect_sdf_impl(
+        dx.abs() - half_width + corner_radius,
+        dy.abs() - half_height + corner_radius,
+        corner_radius,
+    )
+}
+
+fn rounded_rect_sdf_impl(
+    corner_center_to_point_x: f32,
+    corner_center_to_point_y: f32,
+    corner_radius: f32,
+) -> f32 {
+    if corner_radius == 0. {
+        corner_center_to_point_x.max(corner_center_to_point_y)
+    } else {
+        let outside = corner_center_to_point_x
+            .max(0.)
+            .hypot(corner_center_to_point_y.max(0.));
+        let inside = corner_center_to_point_x
+            .max(corner_center_to_point_y)
+            .min(0.);
+        outside + inside - corner_radius
+    }
+}
+
+fn gaussian(x: f32, sigma: f32) -> f32 {
+    (-(x * x) / (2. * sigma * sigma)).exp() / ((2. * PI).sqrt() * sigma)
+}
+
+fn erf(value: f32) -> f32 {
+    let sign = value.signum();
+    let value = value.abs();
+    let r1 = 1. + (0.278393 + (0.230389 + (0.000972 + 0.078108 * value) * value) * value) * value;
+    let r2 = r1 * r1;
+    sign - sign / (r2 * r2)
+}
+
+fn blur_along_x(x: f32, y: f32, sigma: f32, corner: f32, half_width: f32, half_height: f32) -> f32 {
+    let delta = (half_height - corner - y.abs()).min(0.);
+    let curved = half_width - corner + (corner * corner - delta * delta).max(0.).sqrt();
+    let start = 0.5 + 0.5 * erf((x - curved) * (0.5f32.sqrt() / sigma));
+    let end = 0.5 + 0.5 * erf((x + curved) * (0.5f32.sqrt() / sigma));
+    end - start
+}
+
+/// Evaluates a [`Background`] at the given point, following the quad shader.
+fn background_color(background: &Background, x: f32, y: f32, bounds: Rect) -> [f32; 4] {
+    match background.tag {
+        BackgroundTag::Solid => hsla_to_rgba(background.solid),
+        BackgroundTag::LinearGradient => {
+            let width = bounds.right - bounds.left;
+            let height = bounds.bottom - bounds.top;
+            let angle = background.gradient_angle_or_pattern_height;
+            let radians = (angle % 360. - 90.).to_radians();
+            let (mut direction_x, mut direction_y) = (radians.cos(), radians.sin());
+            if width > height {
+                direction_y *= height / width;
+            } else {
+                direction_x *= width / height;
+            }
+
+            let center_to_point_x = x - (bounds.left + width / 2.);
+            let center_to_point_y = y - (bounds.top + height / 2.);
+            let mut t = (center_to_point_x * direction_x + center_to_point_y * direction_y)
+                / direction_x.hypot(direction_y);
+            if direction_x.abs() > direction_y.abs() {
+                t = (t + width / 2.) / width;
+            } else {
+                t = (t + height / 2.) / height;
+            }
+
+            let [from, to] = background.colors;
+            t = saturate((t - from.percentage) / (to.percentage - from.percentage));
+
+            let from = hsla_to_rgba(from.color);
+            let to = hsla_to_rgba(to.color);
+            match background.color_space {
+                ColorSpace::Srgb => {
+                    srgba_to_linear(mix(linear_to_srgba(from), linear_to_srgba(to), t))
+                }
+                ColorSpace::Oklab => oklab_to_linear_srgb(mix(
+                    linear_srgb_to_oklab(from),
+                    linear_srgb_to_oklab(to),
+                    t,
+                )),
+            }
+        }
+        BackgroundTag::PatternSlash => {
+            let height = background.gradient_angle_or_pattern_height;
+            let pattern_width = (height / 65535.) / 255.;
+            let pattern_interval = (height % 65535.) / 255.;
+            let pattern_height = pattern_width + pattern_interval;
+            let stripe_angle = PI / 4.;
+            let pattern_period = pattern_height * stripe_angle.sin();
+            let relative_x = x - bounds.left;
+            let relative_y = y - bounds.top;
+            // WGSL matrices are column-major, so this matches `rotation * relative_position`.
+            let rotated_x = stripe_angle.cos() * relative_x + stripe_angle.sin() * relative_y;
+            let pattern = rotated_x % pattern_period;
+            let distance = pattern.min(pattern_period - pattern)
+                - pattern_period * (pattern_width / pattern_height) / 2.;
+            let mut color = hsla_to_rgba(background.solid);
+            color[3] *= saturate(0.5 - distance);
+            color
+        }
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value < 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value < 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1. / 2.4) - 0.055
+    }
+}
+
+fn srgba_to_linear([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a]
+}
+
+fn linear_to_srgba([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a]
+}
+
+fn linear_srgb_to_oklab([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        a,
+    ]
+}
+
+fn oklab_to_linear_srgb([l, a_, b_, alpha]: [f32; 4]) -> [f32; 4] {
+    let l_ = l + 0.3963377774 * a_ + 0.2158037573 * b_;
+    let m_ = l - 0.1055613458 * a_ - 0.0638541728 * b_;
+    let s_ = l - 0.0894841775 * a_ - 1.2914855480 * b_;
+    let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+    [
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+        alpha,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AtlasKey, FontId, GlyphId, ImageId, PlatformAtlas, Point, RenderGlyphParams, RenderImage,
+        RenderImageParams, TestAppContext, TransformationMatrix, Window, div, img, point,
+        prelude::*, px, rgb, size,
+    };
+    use image::Frame;
+    use std::borrow::Cow;
+
+    struct Swatches;
+
+    impl Render for Swatches {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .flex()
+                .flex_col()
+                .size_full()
+                .bg(rgb(0xffffff))
+                .child(div().size(px(10.)).bg(rgb(0xff0000)))
+                .child(
+                    div()
+                        .size(px(10.))
+                        .border_2()
+                        .border_color(rgb(0x0000ff))
+                        .bg(rgb(0x00ff00)),
+                )
+        }
+    }
+
+    #[gpui::test]
+    fn test_render_window_frame(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| Swatches);
+        cx.simulate_resize(size(px(20.), px(30.)));
+
+        // The test window has a scale factor of 2.
+        let frame = cx.capture_frame();
+        assert_eq!(frame.dimensions(), (40, 60));
+        assert_eq!(frame.get_pixel(5, 5).0, [255, 0, 0, 255]);
+        assert_eq!(frame.get_pixel(1, 21).0, [0, 0, 255, 255]);
+        assert_eq!(frame.get_pixel(10, 30).0, [0, 255, 0, 255]);
+        assert_eq!(frame.get_pixel(30, 50).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_render_path() {
+        let mut path = Path::new(point(px(0.), px(0.)));
+        path.line_to(point(px(10.), px(0.)));
+        path.line_to(point(px(0.), px(10.)));
+        path.color = rgb(0x000000).into();
+        path.content_mask = ContentMask {
+            bounds: Bounds {
+                origin: point(px(0.), px(0.)),
+                size: size(px(10.), px(10.)),
+            },
+        };
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(path.scale(1.));
+        scene.finish();
+
+        let renderer = SoftwareRenderer::new(Arc::new(SoftwareAtlas::new()));
+        let image = renderer.render(&scene, size(DevicePixels(10), DevicePixels(10)));
+        assert_eq!(image.get_pixel(1, 1).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(8, 8).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_render_shadow() {
+        let shadow = |blur_radius| Shadow {
+            order: 0,
+            blur_radius: ScaledPixels(blur_radius),
+            bounds: scaled_bounds(10., 10., 10., 10.),
+            corner_radii: Corners::default(),
+            content_mask: content_mask(30., 30.),
+            color: rgb(0x000000).into(),
+        };
+
+        let image = render(&Arc::new(SoftwareAtlas::new()), |scene| {
+            scene.insert_primitive(shadow(0.))
+        });
+        assert_eq!(image.get_pixel(15, 15).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(21, 15).0, [0, 0, 0, 0]);
+
+        // Blurred shadows fade out across their edges.
+        let image = render(&Arc::new(SoftwareAtlas::new()), |scene| {
+            scene.insert_primitive(shadow(2.))
+        });
+        assert!(image.get_pixel(15, 15).0[3] > 200);
+        let edge = image.get_pixel(20, 15).0[3];
+        assert!(0 < edge && edge < 255, "edge alpha: {edge}");
+        assert_eq!(image.get_pixel(28, 15).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_render_underline() {
+        let underline = |wavy| Underline {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(0., 4., 20., 6.),
+            content_mask: content_mask(20., 20.),
+            color: rgb(0x00ff00).into(),
+            thickness: ScaledPixels(1.),
+            wavy,
+        };
+
+        let image = render(&Arc::new(SoftwareAtlas::new()), |scene| {
+            scene.insert_primitive(underline(0))
+        });
+        assert_eq!(image.get_pixel(5, 4).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(5, 9).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(5, 10).0, [0, 0, 0, 0]);
+
+        // Wavy underlines only cover part of their bounds.
+        let image = render(&Arc::new(SoftwareAtlas::new()), |scene| {
+            scene.insert_primitive(underline(1))
+        });
+        let alphas = (4..10)
+            .flat_map(|y| (0..20).map(move |x| (x, y)))
+            .map(|(x, y)| image.get_pixel(x, y).0[3])
+            .collect::<Vec<_>>();
+        assert!(alphas.contains(&0));
+        assert!(alphas.iter().any(|alpha| *alpha > 200));
+        assert_eq!(image.get_pixel(5, 12).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_render_glyph_sprite() {
+        // A 4x4 glyph mask whose left half is covered.
+        let atlas = Arc::new(SoftwareAtlas::new());
+        let key = AtlasKey::Glyph(RenderGlyphParams {
+            font_id: FontId(0),
+            glyph_id: GlyphId(1),
+            font_size: px(12.),
+            subpixel_variant: Point::default(),
+            scale_factor: 1.,
+            is_emoji: false,
+        });
+        let mask: Vec<u8> = [[255, 255, 0, 0]; 4].concat();
+        let tile = insert_tile(&atlas, &key, size(4, 4), &mask);
+
+        let sprite = |x, transformation| MonochromeSprite {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(x, 2., 4., 4.),
+            content_mask: content_mask(20., 20.),
+            color: rgb(0xff0000).into(),
+            tile: tile.clone(),
+            transformation,
+        };
+
+        let image = render(&atlas, |scene| {
+            scene.insert_primitive(sprite(2., TransformationMatrix::unit()))
+        });
+        assert_eq!(image.get_pixel(2, 2).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2, 5).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(5, 3).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(1, 3).0, [0, 0, 0, 0]);
+
+        // Transformed sprites are sampled through the inverse transformation.
+        let image = render(&atlas, |scene| {
+            scene.insert_primitive(sprite(
+                2.,
+                TransformationMatrix::unit().translate(point(ScaledPixels(10.), ScaledPixels(0.))),
+            ))
+        });
+        assert_eq!(image.get_pixel(2, 3).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(12, 3).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(15, 3).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_render_polychrome_sprite() {
+        // A 2x2 BGRA image with a blue top row and a white bottom row.
+        let atlas = Arc::new(SoftwareAtlas::new());
+        let key = AtlasKey::Image(RenderImageParams {
+            image_id: ImageId(usize::MAX),
+            frame_index: 0,
+        });
+        let pixels: Vec<u8> = [[255, 0, 0, 255], [255, 0, 0, 255], [255; 4], [255; 4]].concat();
+        let tile = insert_tile(&atlas, &key, size(2, 2), &pixels);
+
+        let sprite = |grayscale, opacity| PolychromeSprite {
+            order: 0,
+            pad: 0,
+            grayscale,
+            opacity,
+            bounds: scaled_bounds(0., 0., 8., 8.),
+            content_mask: content_mask(10., 10.),
+            corner_radii: Corners::default(),
+            tile: tile.clone(),
+        };
+
+        let image = render(&atlas, |scene| scene.insert_primitive(sprite(false, 1.)));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(7, 7).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(9, 9).0, [0, 0, 0, 0]);
+
+        let image = render(&atlas, |scene| scene.insert_primitive(sprite(true, 0.5)));
+        let [red, green, blue, alpha] = image.get_pixel(0, 0).0;
+        assert_eq!((red, alpha), (18, 128));
+        assert!(red == green && green == blue);
+    }
+
+    struct Picture(Arc<RenderImage>);
+
+    impl Render for Picture {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .bg(rgb(0xffffff))
+                .child(img(self.0.clone()).size(px(10.)))
+        }
+    }
+
+    #[gpui::test]
+    fn test_render_image_element(cx: &mut TestAppContext) {
+        // Render images are stored as BGRA, so this is a red image.
+        let frame = Frame::new(RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 255, 255])));
+        let picture = Arc::new(RenderImage::new(vec![frame]));
+        let (_, cx) = cx.add_window_view(|_, _| Picture(picture));
+        cx.simulate_resize(size(px(20.), px(20.)));
+
+        let frame = cx.capture_frame();
+        assert_eq!(frame.get_pixel(10, 10).0, [255, 0, 0, 255]);
+        assert_eq!(frame.get_pixel(30, 30).0, [255, 255, 255, 255]);
+    }
+
+    fn scaled_bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<ScaledPixels> {
+        Bounds {
+            origin: point(ScaledPixels(x), ScaledPixels(y)),
+            size: size(ScaledPixels(width), ScaledPixels(height)),
+        }
+    }
+
+    fn content_mask(width: f32, height: f32) -> ContentMask<ScaledPixels> {
+        ContentMask {
+            bounds: scaled_bounds(0., 0., width, height),
+        }
+    }
+
+    fn insert_tile(
+        atlas: &SoftwareAtlas,
+        key: &AtlasKey,
+        tile_size: Size<i32>,
+        bytes: &[u8],
+    ) -> AtlasTile {
+        let tile_size = size(
+            DevicePixels(tile_size.width),
+            DevicePixels(tile_size.height),
+        );
+        atlas
+            .get_or_insert_with(key, &mut || Ok(Some((tile_size, Cow::Borrowed(bytes)))))
+            .unwrap()
+            .unwrap()
+    }
+
+    /// Renders the primitives that `build` inserts onto a 30x30 canvas.
+    fn render(atlas: &Arc<SoftwareAtlas>, build: impl FnOnce(&mut Scene)) -> RgbaImage {
+        let mut scene = Scene::default();
+        build(&mut scene);
+        scene.finish();
+        SoftwareRenderer::new(atlas.clone())
+            .render(&scene, size(DevicePixels(30), DevicePixels(30)))
+    }
+}
@@ -2,13 +2,15 @@ use std::{
     cell::LazyCell,
     hash::Hasher,
     hash::{DefaultHasher, Hash},
-    sync::Arc,
+    sync::{Arc, LazyLock},
     thread::ThreadId,
     time::Instant,
 };
 
 use serde::{Deserialize, Serialize};
 
+use crate::WindowId;
+
 #[doc(hidden)]
 #[derive(Debug, Copy, Clone)]
 pub struct TaskTiming {
@@ -54,6 +56,31 @@ impl ThreadTaskTimings {
     }
 }
 
+/// The time a window spent drawing and presenting a frame.
+#[doc(hidden)]
+#[derive(Debug, Copy, Clone)]
+pub struct FrameTiming {
+    pub window_id: WindowId,
+    pub thread_id: ThreadId,
+    pub start: Instant,
+    pub end: Instant,
+}
+
+/// Returns the most recent frames drawn by any window, oldest first.
+#[doc(hidden)]
+pub fn frame_timings() -> Vec<FrameTiming> {
+    FRAME_TIMINGS.lock().iter().copied().collect()
+}
+
+pub(crate) fn record_frame(window_id: WindowId, start: Instant, end: Instant) {
+    FRAME_TIMINGS.lock().push_back(FrameTiming {
+        window_id,
+        thread_id: std::thread::current().id(),
+        start,
+        end,
+    });
+}
+
 /// Serializable variant of [`core::panic::Location`]
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct SerializedLocation<'a> {
@@ -155,6 +182,13 @@ impl<'a> SerializedThreadTaskTimings<'a> {
 const MAX_TASK_TIMINGS: usize = (20 * 1024 * 1024) / core::mem::size_of::<TaskTiming>();
 
 pub(crate) type TaskTimings = circular_buffer::CircularBuffer<MAX_TASK_TIMINGS, TaskTiming>;
+
+// Allow 1mb of frame timing entries
+const MAX_FRAME_TIMINGS: usize = (1024 * 1024) / core::mem::size_of::<FrameTiming>();
+
+static FRAME_TIMINGS: LazyLock<
+    spin::Mutex<Box<circular_buffer::CircularBuffer<MAX_FRAME_TIMINGS, FrameTiming>>>,
+> = LazyLock::new(|| spin::Mutex::new(circular_buffer::CircularBuffer::boxed()));
 pub(crate) type GuardedTaskTimings = spin::Mutex<ThreadTimings>;
 
 pub(crate) struct GlobalThreadTimings {
@@ -1,7 +1,8 @@
 #[cfg(any(feature = "inspector", debug_assertions))]
 use crate::Inspector;
 use crate::{
-    Action, AnyDrag, AnyElement, AnyImageCache, AnyTooltip, AnyView, App, AppContext, Arena, Asset,
+    AccessibilityAction, AccessibilityNode, AccessibilityNodeId, AccessibilityTree, Action,
+    AnyDrag, AnyElement, AnyImageCache, AnyTooltip, AnyView, App, AppContext, Arena, Asset,
     AsyncWindowContext, AvailableSpace, Background, BorderStyle, Bounds, BoxShadow, Capslock,
     Context, Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener,
     DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter,
@@ -692,6 +693,7 @@ pub(crate) struct Frame {
     #[cfg(any(feature = "inspector", debug_assertions))]
     pub(crate) inspector_hitboxes: FxHashMap<HitboxId, crate::InspectorElementId>,
     pub(crate) tab_stops: TabStopMap,
+    pub(crate) accessibility_tree: AccessibilityTree,
 }
 
 #[derive(Clone, Default)]
@@ -712,6 +714,7 @@ pub(crate) struct PaintIndex {
     cursor_styles_index: usize,
     accessed_element_states_index: usize,
     tab_handle_index: usize,
+    accessibility_index: usize,
     line_layout_index: LineLayoutIndex,
 }
 
@@ -741,6 +744,7 @@ impl Frame {
             #[cfg(any(feature = "inspector", debug_assertions))]
             inspector_hitboxes: FxHashMap::default(),
             tab_stops: TabStopMap::default(),
+            accessibility_tree: AccessibilityTree::default(),
         }
     }
 
@@ -757,6 +761,7 @@ impl Frame {
         self.window_control_hitboxes.clear();
         self.deferred_draws.clear();
         self.tab_stops.clear();
+        self.accessibility_tree.clear();
         self.focus = None;
 
         #[cfg(any(feature = "inspector", debug_assertions))]
@@ -1117,6 +1122,7 @@ impl Window {
 
                 if invalidator.is_dirty() || request_frame_options.force_render {
                     measure("frame duration", || {
+                        let frame_start = Instant::now();
                         handle
                             .update(&mut cx, |_, window, cx| {
                                 let arena_clear_needed = window.draw(cx);
@@ -1125,6 +1131,11 @@ impl Window {
                                 arena_clear_needed.clear();
                             })
                             .log_err();
+                        crate::profiler::record_frame(
+                            handle.window_id(),
+                            frame_start,
+                            Instant::now(),
+                        );
                     })
                 } else if needs_present {
                     handle
@@ -1163,6 +1174,16 @@ impl Window {
                     .log_err();
             }
         }));
+        platform_window.on_accessibility_action(Box::new({
+            let mut cx = cx.to_async();
+            move |request| {
+                handle
+                    .update(&mut cx, |_, window, cx| {
+                        window.perform_accessibility_action(request.node, request.action, cx)
+                    })
+                    .log_err();
+            }
+        }));
         platform_window.on_active_status_change(Box::new({
             let mut cx = cx.to_async();
             move |active| {
@@ -2007,7 +2028,18 @@ impl Window {
             self.rendered_frame.input_handlers.push(Some(input_handler));
         }
         if !cx.mode.skip_drawing() {
+            if self.platform_window.is_accessibility_active() {
+                // Views reused from the previous frame can only replay nodes
+                // that were recorded, so repaint everything when the tree is new.
+                if !self.rendered_frame.accessibility_tree.is_enabled() {
+                    self.refreshing = true;
+                }
+                self.next_frame
+                    .accessibility_tree
+                    .begin(Bounds::new(Point::default(), self.viewport_size()));
+            }
             self.draw_roots(cx);
+            self.next_frame.accessibility_tree.finish(self.focus);
         }
         self.dirty_views.clear();
         self.next_frame.window_active = self.active.get();
@@ -2092,6 +2124,10 @@ impl Window {
     #[profiling::function]
     fn present(&self) {
         self.platform_window.draw(&self.rendered_frame.scene);
+        if self.rendered_frame.accessibility_tree.is_enabled() {
+            self.platform_window
+                .update_accessibility_tree(&self.rendered_frame.accessibility_tree);
+        }
         self.needs_present.set(false);
         profiling::finish_frame!();
     }
@@ -2372,6 +2408,7 @@ impl Window {
             cursor_styles_index: self.next_frame.cursor_styles.len(),
             accessed_element_states_index: self.next_frame.accessed_element_states.len(),
             tab_handle_index: self.next_frame.tab_stops.paint_index(),
+            accessibility_index: self.next_frame.accessibility_tree.paint_index(),
             line_layout_index: self.text_system.layout_index(),
         }
     }
@@ -2405,6 +2442,10 @@ impl Window {
             &self.rendered_frame.tab_stops.insertion_history
                 [range.start.tab_handle_index..range.end.tab_handle_index],
         );
+        self.next_frame.accessibility_tree.replay(
+            &self.rendered_frame.accessibility_tree.insertion_history
+                [range.start.accessibility_index..range.end.accessibility_index],
+        );
 
         self.text_system
             .reuse_layouts(range.start.line_layout_index..range.end.line_layout_index);
@@ -2825,6 +2866,70 @@ impl Window {
         }
     }
 
+    /// Executes the given closure with the given node as the parent of any
+    /// accessibility nodes inserted by it. The node is only recorded while an
+    /// assistive technology is using the window, or in tests.
+    ///
+    /// This method should only be called as part of the paint phase of element drawing.
+    pub fn with_accessibility_node<R>(
+        &mut self,
+        global_id: Option<&GlobalElementId>,
+        node: Option<AccessibilityNode>,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        self.invalidator.debug_assert_paint();
+        match node {
+            Some(node) if self.next_frame.accessibility_tree.is_enabled() => {
+                self.next_frame.accessibility_tree.push(global_id, node);
+                let result = f(self);
+                self.next_frame.accessibility_tree.pop();
+                result
+            }
+            _ => f(self),
+        }
+    }
+
+    /// Whether elements should describe themselves with [`Window::with_accessibility_node`]
+    /// during the current frame. Elements can check this to skip computing expensive
+    /// names and values.
+    pub fn is_accessibility_enabled(&self) -> bool {
+        self.next_frame.accessibility_tree.is_enabled()
+    }
+
+    /// The accessibility tree of the most recently rendered frame.
+    pub fn accessibility_tree(&self) -> &AccessibilityTree {
+        &self.rendered_frame.accessibility_tree
+    }
+
+    /// Performs an action requested by an assistive technology on a node of the
+    /// most recently rendered accessibility tree.
+    pub fn perform_accessibility_action(
+        &mut self,
+        node: AccessibilityNodeId,
+        action: AccessibilityAction,
+        cx: &mut App,
+    ) {
+        let Some(node) = self.rendered_frame.accessibility_tree.node(node) else {
+            return;
+        };
+        if !node.actions.contains(&action) {
+            return;
+        }
+
+        match action {
+            AccessibilityAction::Focus => {
+                if let Some(focus_handle) = node.focus_handle.clone() {
+                    self.focus(&focus_handle);
+                }
+            }
+            AccessibilityAction::Click => {
+                if let Some(handler) = node.click_handler.clone() {
+                    (handler.0)(self, cx);
+                }
+            }
+        }
+    }
+
     /// Executes the given closure within the context of a tab group.
     #[inline]
     pub fn with_tab_group<R>(&mut self, index: Option<isize>, f: impl FnOnce(&mut Self) -> R) -> R {
@@ -4525,6 +4630,16 @@ impl Window {
         self.platform_window.gpu_specs()
     }
 
+    /// Rasterizes the most recently rendered frame, for example to save it as a
+    /// PNG with [`image::RgbaImage::save`].
+    ///
+    /// Only windows drawn by the software renderer support this, which are the
+    /// windows of the headless platform on Linux and test windows.
+    pub fn render_to_image(&self) -> anyhow::Result<image::RgbaImage> {
+        self.platform_window
+            .render_to_image(&self.rendered_frame.scene)
+    }
+
     /// Perform titlebar double-click action.
     /// This is macOS specific.
     pub fn titlebar_double_click(&self) {
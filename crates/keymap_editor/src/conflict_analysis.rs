@@ -0,0 +1,593 @@
+use std::rc::Rc;
+
+use anyhow::Context as _;
+use editor::{Editor, MultiBuffer, MultiBufferOffset, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    Action as _, DismissEvent, EventEmitter, FocusHandle, Focusable, KeyBindingContextPredicate,
+    KeybindingKeystroke, UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use language::Capability;
+use settings::{
+    BaseKeymap, KeybindSource, KeybindUpdateTarget, KeymapFile, Settings as _, SettingsAssets,
+};
+use ui::{Modal, ModalHeader, Section, Tooltip, prelude::*};
+use workspace::{
+    ModalView, OpenOptions, OpenVisible, Workspace, notifications::NotifyTaskExt as _,
+};
+
+use crate::{keystrokes_match_exactly, load_json_language, normalized_ctx_eq};
+
+/// A binding from the keymap, as seen by the conflict analysis.
+#[derive(Clone)]
+pub(crate) struct AnalyzedBinding {
+    keystrokes: Rc<[KeybindingKeystroke]>,
+    predicate: Option<Rc<KeyBindingContextPredicate>>,
+    action_name: &'static str,
+    action_input: Option<SharedString>,
+    source: KeybindSource,
+    is_no_action: bool,
+    /// Whether this is a `null` binding that stops the keymap from looking
+    /// for bindings with lower precedence. Only user `null` bindings do.
+    disables_lower_precedence: bool,
+}
+
+impl AnalyzedBinding {
+    pub(crate) fn new(binding: &gpui::KeyBinding) -> Self {
+        let is_no_action = gpui::is_no_action(binding.action());
+        let source = binding
+            .meta()
+            .map(KeybindSource::from_meta)
+            .unwrap_or(KeybindSource::Unknown);
+        Self {
+            keystrokes: Rc::from(binding.keystrokes()),
+            predicate: binding.predicate(),
+            action_name: binding.action().name(),
+            action_input: binding.action_input(),
+            source,
+            is_no_action,
+            disables_lower_precedence: is_no_action
+                && binding.meta().is_none_or(|_| source == KeybindSource::User),
+        }
+    }
+
+    /// Whether this binding is enabled, at the same or a deeper context,
+    /// everywhere `other` is enabled.
+    fn covers(&self, other: &Self) -> bool {
+        match (&self.predicate, &other.predicate) {
+            // Bindings without a context match at the deepest context.
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(predicate), Some(other)) => {
+                normalized_ctx_eq(predicate, other) || predicate.is_superset(other)
+            }
+        }
+    }
+
+    fn is_prefix_of(&self, other: &Self) -> bool {
+        other.keystrokes.len() > self.keystrokes.len()
+            && keystrokes_match_exactly(
+                &other.keystrokes[..self.keystrokes.len()],
+                &self.keystrokes,
+            )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConflictKind {
+    /// The binding never fires, because a later binding with the same
+    /// keystrokes is enabled wherever it is.
+    Shadowed { by: usize },
+    /// The binding never fires, because a user `null` binding disables it.
+    Disabled { by: usize },
+    /// Typing the binding's keystrokes waits for the next keystroke, because a
+    /// later multi-stroke binding starts with them.
+    SwallowedByPrefix { by: usize },
+    /// The user binding replaces a binding from the default, base or vim keymap.
+    OverridesBase { overridden: usize },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ConflictReport {
+    pub binding_ix: usize,
+    pub kind: ConflictKind,
+}
+
+impl ConflictReport {
+    fn other_binding_ix(&self) -> usize {
+        match self.kind {
+            ConflictKind::Shadowed { by }
+            | ConflictKind::Disabled { by }
+            | ConflictKind::SwallowedByPrefix { by } => by,
+            ConflictKind::OverridesBase { overridden } => overridden,
+        }
+    }
+}
+
+/// Evaluates every binding against the ones with higher precedence, following
+/// the rules of [`gpui::Keymap::bindings_for_input`]. `bindings` must be in
+/// keymap order, so that later bindings take precedence at the same depth.
+pub(crate) fn analyze_conflicts(bindings: &[AnalyzedBinding]) -> Vec<ConflictReport> {
+    let mut reports = Vec::new();
+    for (ix, binding) in bindings.iter().enumerate() {
+        if binding.is_no_action {
+            continue;
+        }
+        let later_bindings = || bindings.iter().enumerate().skip(ix + 1).rev();
+
+        let overriding = later_bindings().find(|(_, other)| {
+            (!other.is_no_action || other.disables_lower_precedence)
+                && keystrokes_match_exactly(&other.keystrokes, &binding.keystrokes)
+                && other.covers(binding)
+        });
+        if let Some((other_ix, other)) = overriding {
+            reports.push(if other.is_no_action {
+                ConflictReport {
+                    binding_ix: ix,
+                    kind: ConflictKind::Disabled { by: other_ix },
+                }
+            } else if other.source == KeybindSource::User && binding.source != KeybindSource::User {
+                ConflictReport {
+                    binding_ix: other_ix,
+                    kind: ConflictKind::OverridesBase { overridden: ix },
+                }
+            } else {
+                ConflictReport {
+                    binding_ix: ix,
+                    kind: ConflictKind::Shadowed { by: other_ix },
+                }
+            });
+            continue;
+        }
+
+        let swallowing = later_bindings().find(|&(other_ix, other)| {
+            !other.is_no_action
+                && binding.is_prefix_of(other)
+                && other.covers(binding)
+                && !bindings[other_ix + 1..].iter().any(|disabling| {
+                    disabling.is_no_action
+                        && keystrokes_match_exactly(&disabling.keystrokes, &other.keystrokes)
+                        && disabling.covers(other)
+                })
+        });
+        if let Some((other_ix, _)) = swallowing {
+            reports.push(ConflictReport {
+                binding_ix: ix,
+                kind: ConflictKind::SwallowedByPrefix { by: other_ix },
+            });
+        }
+    }
+    reports
+}
+
+pub(crate) struct KeymapConflictsModal {
+    workspace: WeakEntity<Workspace>,
+    bindings: Vec<AnalyzedBinding>,
+    reports: Vec<ConflictReport>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+}
+
+impl ModalView for KeymapConflictsModal {}
+
+impl EventEmitter<DismissEvent> for KeymapConflictsModal {}
+
+impl Focusable for KeymapConflictsModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl KeymapConflictsModal {
+    pub(crate) fn new(workspace: WeakEntity<Workspace>, cx: &mut Context<Self>) -> Self {
+        let bindings = cx
+            .key_bindings()
+            .borrow()
+            .bindings()
+            .map(AnalyzedBinding::new)
+            .collect::<Vec<_>>();
+        let mut reports = analyze_conflicts(&bindings);
+        // Conflicts the user can act on come first.
+        reports.sort_by_key(|report| {
+            bindings[report.binding_ix].source != KeybindSource::User
+                && bindings[report.other_binding_ix()].source != KeybindSource::User
+        });
+
+        Self {
+            workspace,
+            bindings,
+            reports,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn jump_to_binding(&mut self, binding_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(binding) = self.bindings.get(binding_ix).cloned() else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                open_binding_source(workspace, binding, window, cx)
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn render_report(&self, row_ix: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let report = self.reports[row_ix];
+        let (icon, icon_color, title, other_label) = match report.kind {
+            ConflictKind::Shadowed { .. } => (
+                IconName::Warning,
+                Color::Warning,
+                "Never fires, a later binding with the same keystrokes takes precedence",
+                "Overridden by",
+            ),
+            ConflictKind::Disabled { .. } => (
+                IconName::Info,
+                Color::Muted,
+                "Never fires, it is disabled by a null binding",
+                "Disabled by",
+            ),
+            ConflictKind::SwallowedByPrefix { .. } => (
+                IconName::Warning,
+                Color::Warning,
+                "Waits for the next keystroke, a later multi-stroke binding starts with it",
+                "Prefix of",
+            ),
+            ConflictKind::OverridesBase { .. } => (
+                IconName::Info,
+                Color::Muted,
+                "Replaces a built-in binding",
+                "Replaces",
+            ),
+        };
+
+        v_flex()
+            .id(("keymap-conflict", row_ix))
+            .w_full()
+            .py_1()
+            .gap_0p5()
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Icon::new(icon).size(IconSize::Small).color(icon_color))
+                    .child(Label::new(title).size(LabelSize::Small)),
+            )
+            .child(self.render_binding(row_ix * 2, report.binding_ix, "Binding", cx))
+            .child(self.render_binding(row_ix * 2 + 1, report.other_binding_ix(), other_label, cx))
+    }
+
+    fn render_binding(
+        &self,
+        id: usize,
+        binding_ix: usize,
+        label: &'static str,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let binding = &self.bindings[binding_ix];
+        let keystrokes = ui::text_for_keybinding_keystrokes(&binding.keystrokes, cx);
+        let action = if binding.is_no_action {
+            SharedString::new_static("null")
+        } else {
+            command_palette::humanize_action_name(binding.action_name).into()
+        };
+        let context = binding
+            .predicate
+            .as_ref()
+            .map(|predicate| SharedString::from(predicate.to_string()))
+            .unwrap_or(crate::KeybindContextString::GLOBAL);
+
+        h_flex()
+            .pl_5()
+            .gap_2()
+            .child(
+                div()
+                    .w_24()
+                    .flex_none()
+                    .child(Label::new(label).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(
+                Label::new(keystrokes)
+                    .size(LabelSize::Small)
+                    .buffer_font(cx),
+            )
+            .child(Label::new(action).size(LabelSize::Small).truncate())
+            .child(
+                Label::new(context)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .truncate(),
+            )
+            .child(
+                Label::new(binding.source.name())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                IconButton::new(("jump-to-binding", id), IconName::ArrowUpRight)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Go to Binding in Keymap JSON"))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.jump_to_binding(binding_ix, window, cx);
+                    })),
+            )
+    }
+}
+
+impl Render for KeymapConflictsModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let report_count = self.reports.len();
+        let summary = match report_count {
+            0 => "No conflicts found in the keymap.".to_string(),
+            1 => "Found 1 binding that conflicts with another one.".to_string(),
+            count => format!("Found {count} bindings that conflict with other ones."),
+        };
+
+        v_flex()
+            .w(rems(48.))
+            .elevation_3(cx)
+            .key_context("KeymapConflictsModal")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                Modal::new("keymap_conflicts_modal", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline("Keymap Conflicts")
+                            .description(summary)
+                            .show_dismiss_button(true),
+                    )
+                    .section(
+                        Section::new().child(
+                            uniform_list(
+                                "keymap-conflicts",
+                                report_count,
+                                cx.processor(|this, range: std::ops::Range<usize>, _window, cx| {
+                                    range.map(|row_ix| this.render_report(row_ix, cx)).collect()
+                                }),
+                            )
+                            .track_scroll(self.scroll_handle.clone())
+                            .h(rems(28.)),
+                        ),
+                    ),
+            )
+    }
+}
+
+/// Opens the keymap file that `binding` was loaded from and selects the binding's keystrokes.
+fn open_binding_source(
+    workspace: &mut Workspace,
+    binding: AnalyzedBinding,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    let keyboard_mapper = cx.keyboard_mapper().clone();
+    let base_keymap_path = BaseKeymap::get_global(cx).asset_path();
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let (contents, bundled_title) = match binding.source {
+            KeybindSource::User => (KeymapFile::load_keymap_file(&fs).await?, None),
+            KeybindSource::Default => (
+                settings::default_keymap().into_owned(),
+                Some("Default Key Bindings"),
+            ),
+            KeybindSource::Vim => (
+                settings::vim_keymap().into_owned(),
+                Some("Vim Key Bindings"),
+            ),
+            KeybindSource::Base => (
+                util::asset_str::<SettingsAssets>(
+                    base_keymap_path.context("No base keymap is selected")?,
+                )
+                .into_owned(),
+                Some("Base Key Bindings"),
+            ),
+            KeybindSource::Unknown => {
+                anyhow::bail!("This binding wasn't loaded from a keymap file")
+            }
+        };
+
+        let context = binding
+            .predicate
+            .as_ref()
+            .map(|predicate| predicate.to_string());
+        let target = KeybindUpdateTarget {
+            context: context.as_deref(),
+            keystrokes: &binding.keystrokes,
+            action_name: binding.action_name,
+            action_arguments: binding.action_input.as_deref(),
+        };
+        let range =
+            KeymapFile::binding_keystrokes_range(&contents, &target, keyboard_mapper.as_ref())
+                .context("Couldn't find the binding in its keymap file")?;
+
+        let editor = match bundled_title {
+            None => workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_abs_path(
+                        paths::keymap_file().clone(),
+                        OpenOptions {
+                            visible: Some(OpenVisible::None),
+                            ..Default::default()
+                        },
+                        window,
+                        cx,
+                    )
+                })?
+                .await?
+                .downcast::<Editor>()
+                .context("The keymap file didn't open in an editor")?,
+            Some(title) => {
+                let language = load_json_language(workspace.clone(), cx).await;
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let project = workspace.project().clone();
+                    let buffer = project.update(cx, |project, cx| {
+                        let buffer =
+                            project.create_local_buffer(&contents, Some(language), false, cx);
+                        buffer.update(cx, |buffer, cx| {
+                            buffer.set_capability(Capability::ReadOnly, cx);
+                        });
+                        buffer
+                    });
+                    let buffer =
+                        cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title.into()));
+                    let editor = cx.new(|cx| {
+                        let mut editor = Editor::for_multibuffer(buffer, Some(project), window, cx);
+                        editor.set_read_only(true);
+                        editor.set_should_serialize(false, cx);
+                        editor.set_breadcrumb_header(title.into());
+                        editor
+                    });
+                    workspace.add_item_to_active_pane(
+                        Box::new(editor.clone()),
+                        None,
+                        true,
+                        window,
+                        cx,
+                    );
+                    editor
+                })?
+            }
+        };
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| {
+                    selections.select_ranges([
+                        MultiBufferOffset(range.start)..MultiBufferOffset(range.end)
+                    ])
+                },
+            );
+        })?;
+        anyhow::Ok(())
+    })
+    .detach_and_notify_err(window, cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{KeyBinding, NoAction};
+
+    gpui::actions!(test_only, [ActionA, ActionB]);
+
+    fn binding<A: gpui::Action>(
+        keystrokes: &str,
+        action: A,
+        context: Option<&str>,
+        source: KeybindSource,
+    ) -> AnalyzedBinding {
+        AnalyzedBinding::new(&KeyBinding::new(keystrokes, action, context).with_meta(source.meta()))
+    }
+
+    #[test]
+    fn test_shadowed_bindings() {
+        use KeybindSource::*;
+        let bindings = [
+            binding("ctrl-a", ActionA, Some("Editor"), Default),
+            binding("ctrl-a", ActionB, Some("Editor && mode == full"), Default),
+            binding("ctrl-b", ActionA, Some("Editor && mode == full"), Default),
+            binding("ctrl-b", ActionB, Some("Editor"), Default),
+            binding("ctrl-c", ActionA, Some("Workspace"), Default),
+            binding("ctrl-c", ActionB, None, Default),
+            binding("ctrl-d", ActionA, None, Default),
+            binding("ctrl-d", ActionB, Some("Editor"), Default),
+        ];
+        assert_eq!(
+            analyze_conflicts(&bindings),
+            [
+                ConflictReport {
+                    binding_ix: 2,
+                    kind: ConflictKind::Shadowed { by: 3 },
+                },
+                ConflictReport {
+                    binding_ix: 4,
+                    kind: ConflictKind::Shadowed { by: 5 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disabled_bindings() {
+        use KeybindSource::*;
+        let bindings = [
+            binding("ctrl-a", ActionA, Some("Editor"), Default),
+            binding("ctrl-a", NoAction, Some("Editor"), Vim),
+            binding("ctrl-b", ActionA, Some("Editor"), Default),
+            binding("ctrl-b", NoAction, Some("Editor"), User),
+        ];
+        assert_eq!(
+            analyze_conflicts(&bindings),
+            [ConflictReport {
+                binding_ix: 2,
+                kind: ConflictKind::Disabled { by: 3 },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_prefixes_swallowing_bindings() {
+        use KeybindSource::*;
+        let bindings = [
+            binding("ctrl-k", ActionA, Some("Editor"), Default),
+            binding("ctrl-k ctrl-s", ActionB, Some("Editor"), Default),
+            binding("ctrl-j ctrl-s", ActionB, Some("Editor"), Default),
+            binding("ctrl-j", ActionA, Some("Editor"), Default),
+            binding("ctrl-l", ActionA, Some("Editor"), Default),
+            binding("ctrl-l ctrl-s", ActionB, Some("Editor"), Default),
+            binding("ctrl-l ctrl-s", NoAction, Some("Editor"), User),
+        ];
+        assert_eq!(
+            analyze_conflicts(&bindings),
+            [
+                ConflictReport {
+                    binding_ix: 0,
+                    kind: ConflictKind::SwallowedByPrefix { by: 1 },
+                },
+                ConflictReport {
+                    binding_ix: 5,
+                    kind: ConflictKind::Disabled { by: 6 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_user_overrides() {
+        use KeybindSource::*;
+        let bindings = [
+            binding("ctrl-a", ActionA, Some("Editor && mode == full"), Default),
+            binding("ctrl-b", ActionA, Some("Editor"), Base),
+            binding("ctrl-a", ActionB, Some("mode == full && Editor"), User),
+            binding("ctrl-b", ActionB, None, User),
+            binding("ctrl-c", ActionA, Some("Editor"), User),
+            binding("ctrl-c", ActionB, Some("Editor"), User),
+        ];
+        assert_eq!(
+            analyze_conflicts(&bindings),
+            [
+                ConflictReport {
+                    binding_ix: 2,
+                    kind: ConflictKind::OverridesBase { overridden: 0 },
+                },
+                ConflictReport {
+                    binding_ix: 3,
+                    kind: ConflictKind::OverridesBase { overridden: 1 },
+                },
+                ConflictReport {
+                    binding_ix: 4,
+                    kind: ConflictKind::Shadowed { by: 5 },
+                },
+            ]
+        );
+    }
+}
@@ -7,6 +7,7 @@ use std::{
     time::{Duration, Instant},
 };
 
+mod conflict_analysis;
 mod ui_components;
 
 use anyhow::{Context as _, anyhow};
@@ -45,6 +46,7 @@ pub use ui_components::*;
 use zed_actions::{ChangeKeybinding, OpenKeymap};
 
 use crate::{
+    conflict_analysis::KeymapConflictsModal,
     persistence::KEYBINDING_EDITORS,
     ui_components::keystroke_input::{
         ClearKeystrokes, KeystrokeInput, StartRecording, StopRecording,
@@ -73,7 +75,9 @@ actions!(
         /// Toggles exact matching for keystroke search
         ToggleExactKeystrokeMatching,
         /// Shows matching keystrokes for the currently selected binding
-        ShowMatchingKeybinds
+        ShowMatchingKeybinds,
+        /// Lists the bindings that can never fire or that conflict with other bindings.
+        AnalyzeConflicts
     ]
 );
 
@@ -1300,6 +1304,22 @@ impl KeymapEditor {
         self.set_filter_state(self.filter_state.invert(), cx);
     }
 
+    fn analyze_conflicts(
+        &mut self,
+        _: &AnalyzeConflicts,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let workspace_weak = cx.weak_entity();
+                workspace.toggle_modal(window, cx, |_window, cx| {
+                    KeymapConflictsModal::new(workspace_weak, cx)
+                });
+            })
+            .log_err();
+    }
+
     fn set_filter_state(&mut self, filter_state: FilterState, cx: &mut Context<Self>) {
         if self.filter_state != filter_state {
             self.filter_state = filter_state;
@@ -1646,6 +1666,7 @@ impl Render for KeymapEditor {
             .on_action(cx.listener(Self::toggle_keystroke_search))
             .on_action(cx.listener(Self::toggle_exact_keystroke_matching))
             .on_action(cx.listener(Self::show_matching_keystrokes))
+            .on_action(cx.listener(Self::analyze_conflicts))
             .on_mouse_move(cx.listener(|this, _, _window, _cx| {
                 this.show_hover_menus = true;
             }))
@@ -1751,6 +1772,31 @@ impl Render for KeymapEditor {
                                                 );
                                             }),
                                     )
+                                    .child(
+                                        IconButton::new(
+                                            "KeymapEditorAnalyzeConflictsIcon",
+                                            IconName::ListX,
+                                        )
+                                        .icon_size(IconSize::Small)
+                                        .tooltip({
+                                            let focus_handle = focus_handle.clone();
+
+                                            move |_window, cx| {
+                                                Tooltip::for_action_in(
+                                                    "Analyze Conflicts",
+                                                    &AnalyzeConflicts,
+                                                    &focus_handle.clone(),
+                                                    cx,
+                                                )
+                                            }
+                                        })
+                                        .on_click(|_, window, cx| {
+                                            window.dispatch_action(
+                                                AnalyzeConflicts.boxed_clone(),
+                                                cx,
+                                            );
+                                        }),
+                                    )
                                     .child(
                                         h_flex()
                                             .w_full()
@@ -12,8 +12,9 @@ use itertools::{Either, Itertools};
 
 pub use settings::{
     CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FormatOnSave,
-    Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LspInsertMode,
-    RewrapBehavior, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
+    Formatter, FormatterList, FormatterMode, InlayHintKind, LanguageSettingsContent, LintTrigger,
+    LinterInput, LinterOutputFormat, LspInsertMode, RewrapBehavior, ShowWhitespaceSetting,
+    SoftWrap, WordsCompletionMode,
 };
 use settings::{RegisterSetting, Settings, SettingsLocation, SettingsStore};
 use shellexpand;
@@ -52,6 +53,40 @@ pub struct AllLanguageSettings {
     pub defaults: LanguageSettings,
     languages: HashMap<LanguageName, LanguageSettings>,
     pub file_types: FxHashMap<Arc<str>, (GlobSet, Vec<String>)>,
+    /// The command-line formatters that can be referenced from the `formatter` setting, by name.
+    pub formatters: HashMap<Arc<str>, FormatterDefinition>,
+    /// The command-line linters that can be enabled in the `lint` setting, by name.
+    pub linters: HashMap<Arc<str>, LinterDefinition>,
+}
+
+/// A command-line formatter defined in the `formatters` setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatterDefinition {
+    /// The program to run.
+    pub command: Arc<str>,
+    /// The arguments to pass to the program.
+    pub arguments: Vec<String>,
+    /// How the text to format is passed to the program.
+    pub mode: FormatterMode,
+    /// Arguments added when formatting a range, if the program supports it.
+    pub range_arguments: Option<Vec<String>>,
+    /// Exit codes that mean the program succeeded.
+    pub success_exit_codes: Vec<i32>,
+    /// Exit codes that mean the text was already formatted.
+    pub unchanged_exit_codes: Vec<i32>,
+}
+
+/// A command-line linter defined in the `linters` setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinterDefinition {
+    /// The program to run.
+    pub command: Arc<str>,
+    /// The arguments to pass to the program.
+    pub arguments: Vec<String>,
+    /// How the program reads the file to lint.
+    pub input: LinterInput,
+    /// The format of the program's output.
+    pub output_format: LinterOutputFormat,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -94,6 +129,8 @@ pub struct LanguageSettings {
     pub formatter: settings::FormatterList,
     /// Zed's Prettier integration settings.
     pub prettier: PrettierSettings,
+    /// Which command-line linters to run, and when.
+    pub lint: LintSettings,
     /// Whether to automatically close JSX tags.
     pub jsx_tag_auto_close: bool,
     /// Whether to use language servers to provide code intelligence.
@@ -231,6 +268,17 @@ pub struct LanguageTaskSettings {
     pub prefer_lsp: bool,
 }
 
+/// Which command-line linters to run on files of a language, and when.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintSettings {
+    /// The names of the linters to run, from the `linters` setting.
+    pub linters: Vec<String>,
+    /// When to run the linters.
+    pub run: LintTrigger,
+    /// How long to wait after the last edit before linting, when `run` is `on_idle`.
+    pub idle_delay_ms: u64,
+}
+
 /// Allows to enable/disable formatting with Prettier
 /// and configure default Prettier, used when no project-level Prettier installation is found.
 /// Prettier formatting is disabled by default.
@@ -528,6 +576,7 @@ impl settings::Settings for AllLanguageSettings {
             let indent_guides = settings.indent_guides.unwrap();
             let tasks = settings.tasks.unwrap();
             let whitespace_map = settings.whitespace_map.unwrap();
+            let lint = settings.lint.unwrap();
 
             LanguageSettings {
                 tab_size: settings.tab_size.unwrap(),
@@ -555,6 +604,11 @@ impl settings::Settings for AllLanguageSettings {
                     plugins: prettier.plugins.unwrap_or_default(),
                     options: prettier.options.unwrap_or_default(),
                 },
+                lint: LintSettings {
+                    linters: lint.linters.unwrap(),
+                    run: lint.run.unwrap(),
+                    idle_delay_ms: lint.idle_delay_ms.unwrap(),
+                },
                 jsx_tag_auto_close: settings.jsx_tag_auto_close.unwrap().enabled.unwrap(),
                 enable_language_server: settings.enable_language_server.unwrap(),
                 language_servers: settings.language_servers.unwrap(),
@@ -671,6 +725,56 @@ impl settings::Settings for AllLanguageSettings {
             );
         }
 
+        let formatters = all_languages
+            .formatters
+            .iter()
+            .flatten()
+            .filter_map(|(name, definition)| {
+                let Some(command) = definition.command.clone() else {
+                    log::warn!("Formatter {name:?} has no command and will be ignored");
+                    return None;
+                };
+                let definition = FormatterDefinition {
+                    command,
+                    arguments: definition.arguments.clone().unwrap_or_default(),
+                    mode: definition.mode.unwrap_or_default(),
+                    range_arguments: definition.range_arguments.clone(),
+                    success_exit_codes: definition
+                        .success_exit_codes
+                        .clone()
+                        .unwrap_or_else(|| vec![0]),
+                    unchanged_exit_codes: definition
+                        .unchanged_exit_codes
+                        .clone()
+                        .unwrap_or_default(),
+                };
+                Some((name.clone(), definition))
+            })
+            .collect();
+
+        let linters = all_languages
+            .linters
+            .iter()
+            .flatten()
+            .filter_map(|(name, definition)| {
+                let (Some(command), Some(output_format)) =
+                    (definition.command.clone(), definition.output_format)
+                else {
+                    log::warn!(
+                        "Linter {name:?} needs a command and an output format and will be ignored"
+                    );
+                    return None;
+                };
+                let definition = LinterDefinition {
+                    command,
+                    arguments: definition.arguments.clone().unwrap_or_default(),
+                    input: definition.input.unwrap_or_default(),
+                    output_format,
+                };
+                Some((name.clone(), definition))
+            })
+            .collect();
+
         Self {
             edit_predictions: EditPredictionSettings {
                 provider: if let Some(provider) = edit_prediction_provider {
@@ -697,6 +801,8 @@ impl settings::Settings for AllLanguageSettings {
             defaults: default_language_settings,
             languages,
             file_types,
+            formatters,
+            linters,
         }
     }
 }
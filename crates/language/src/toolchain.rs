@@ -112,6 +112,10 @@ pub trait ToolchainLister: Send + Sync + 'static {
 
     fn activation_script(&self, toolchain: &Toolchain, shell: ShellKind, cx: &App) -> Vec<String>;
 
+    /// Updates the environment of processes spawned for this language (language servers, tasks and terminals)
+    /// so that they use the given toolchain, e.g. by prepending its `bin` directory to `PATH`.
+    fn activate_in_env(&self, _toolchain: &Toolchain, _env: &mut HashMap<String, String>) {}
+
     /// Returns various "static" bits of information about this toolchain lister. This function should be pure.
     fn meta(&self) -> ToolchainMetadata;
 }
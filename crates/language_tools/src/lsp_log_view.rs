@@ -868,6 +868,7 @@ impl SearchableItem for LspLogView {
             // LSP log is read-only.
             replacement: false,
             selection: false,
+            syntax_scope: false,
         }
     }
     fn active_match_index(
@@ -2,13 +2,15 @@ use anyhow::{Context as _, Result};
 use async_trait::async_trait;
 use collections::HashMap;
 use futures::StreamExt;
-use gpui::{App, AsyncApp, Task};
+use gpui::{App, AsyncApp, SharedString, Task};
 use http_client::github::latest_github_release;
 pub use language::*;
 use language::{LanguageToolchainStore, LspAdapterDelegate, LspInstaller};
 use lsp::{LanguageServerBinary, LanguageServerName};
 
+use project::Fs;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use smol::fs;
 use std::{
@@ -23,8 +25,12 @@ use std::{
         atomic::{AtomicBool, Ordering::SeqCst},
     },
 };
-use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
-use util::{ResultExt, fs::remove_matching, maybe};
+use task::{ShellKind, TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use util::{ResultExt, fs::remove_matching, maybe, rel_path::RelPath};
+
+use crate::version_manager::{
+    compare_versions, find_pinned_version, home_dir, prepend_to_path, subdirectories,
+};
 
 fn server_binary_arguments() -> Vec<OsString> {
     vec!["-mode=stdio".into()]
@@ -746,12 +752,271 @@ fn extract_subtest_name(input: &str) -> Option<String> {
     )
 }
 
+pub(crate) struct GoModManifestProvider;
+
+impl ManifestProvider for GoModManifestProvider {
+    fn name(&self) -> ManifestName {
+        SharedString::new_static("go.mod").into()
+    }
+
+    fn search(
+        &self,
+        ManifestQuery {
+            path,
+            depth,
+            delegate,
+        }: ManifestQuery,
+    ) -> Option<Arc<RelPath>> {
+        for path in path.ancestors().take(depth) {
+            let p = path.join(RelPath::unix("go.mod").unwrap());
+            if delegate.exists(&p, Some(false)) {
+                return Some(path.into());
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum GoSdkSource {
+    /// The SDK `GOROOT` points to.
+    Goroot,
+    /// Installed system-wide, by the official installer or a package manager.
+    System,
+    /// Installed by a `golang.org/dl/goX.Y.Z download` wrapper into `~/sdk`.
+    GolangDl,
+    /// Downloaded by the `go` command itself to satisfy a `toolchain` directive.
+    ToolchainDownload,
+    Custom,
+}
+
+impl GoSdkSource {
+    fn label(&self) -> &'static str {
+        match self {
+            GoSdkSource::Goroot => "GOROOT",
+            GoSdkSource::System => "system",
+            GoSdkSource::GolangDl => "golang.org/dl",
+            GoSdkSource::ToolchainDownload => "toolchain download",
+            GoSdkSource::Custom => "custom",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GoToolchainData {
+    version: String,
+    source: GoSdkSource,
+    goroot: PathBuf,
+}
+
+const GO_BINARY: &str = if cfg!(windows) { "go.exe" } else { "go" };
+
+const SYSTEM_GOROOTS: &[&str] = if cfg!(windows) {
+    &["C:\\Program Files\\Go"]
+} else {
+    &[
+        "/usr/local/go",
+        "/usr/lib/go",
+        "/opt/homebrew/opt/go/libexec",
+        "/usr/local/opt/go/libexec",
+    ]
+};
+
+/// Lists installed Go SDKs, preferring the one named by the `toolchain` directive of the project's
+/// `go.work` or `go.mod`.
+pub(crate) struct GoToolchainProvider;
+
+impl GoToolchainProvider {
+    fn toolchain(data: GoToolchainData) -> Option<Toolchain> {
+        Some(Toolchain {
+            name: format!("{} ({})", data.version, data.source.label()).into(),
+            path: data
+                .goroot
+                .join("bin")
+                .join(GO_BINARY)
+                .to_str()?
+                .to_owned()
+                .into(),
+            language_name: LanguageName::new_static("Go"),
+            as_json: serde_json::to_value(data).ok()?,
+        })
+    }
+}
+
+/// Returns the version of the SDK in `goroot` (e.g. `go1.22.3`), or `None` if it doesn't contain one.
+async fn go_sdk_version(fs: &dyn Fs, goroot: &Path) -> Option<String> {
+    if !fs.is_file(&goroot.join("bin").join(GO_BINARY)).await {
+        return None;
+    }
+    let version = fs.load(&goroot.join("VERSION")).await.ok();
+    version
+        .as_deref()
+        .and_then(|version| version.lines().next())
+        .map(|line| line.trim().to_owned())
+        .filter(|version| version.starts_with("go"))
+        .or_else(|| {
+            // SDKs built from source may lack a `VERSION` file.
+            let name = goroot.file_name()?.to_str()?;
+            Some(
+                name.rsplit_once('-')
+                    .map_or(name, |(_, version)| version)
+                    .to_owned(),
+            )
+        })
+}
+
+fn parse_toolchain_directive(_: &str, contents: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let line = line.split("//").next()?.trim();
+        let toolchain = line.strip_prefix("toolchain")?;
+        if !toolchain.starts_with(char::is_whitespace) {
+            return None;
+        }
+        Some(toolchain.trim().to_owned()).filter(|toolchain| toolchain != "default")
+    })
+}
+
+#[async_trait]
+impl ToolchainLister for GoToolchainProvider {
+    async fn list(
+        &self,
+        worktree_root: PathBuf,
+        subroot_relative_path: Arc<RelPath>,
+        project_env: Option<HashMap<String, String>>,
+        fs: &dyn Fs,
+    ) -> ToolchainList {
+        let env = project_env.unwrap_or_default();
+        let mut candidates = Vec::new();
+        if let Some(goroot) = env.get("GOROOT").filter(|goroot| !goroot.is_empty()) {
+            candidates.push((PathBuf::from(goroot), GoSdkSource::Goroot));
+        }
+        candidates.extend(
+            SYSTEM_GOROOTS
+                .iter()
+                .map(|goroot| (PathBuf::from(goroot), GoSdkSource::System)),
+        );
+        for sdk in subdirectories(fs, &home_dir(&env).join("sdk")).await {
+            candidates.push((sdk, GoSdkSource::GolangDl));
+        }
+        let module_cache = match env.get("GOMODCACHE").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => env
+                .get("GOPATH")
+                .and_then(|gopath| std::env::split_paths(gopath).next())
+                .unwrap_or_else(|| home_dir(&env).join("go"))
+                .join("pkg")
+                .join("mod"),
+        };
+        for dir in subdirectories(fs, &module_cache.join("golang.org")).await {
+            if dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("toolchain@"))
+            {
+                candidates.push((dir, GoSdkSource::ToolchainDownload));
+            }
+        }
+
+        let mut installed = Vec::<GoToolchainData>::new();
+        for (goroot, source) in candidates {
+            if installed.iter().any(|data| data.goroot == goroot) {
+                continue;
+            }
+            if let Some(version) = go_sdk_version(fs, &goroot).await {
+                installed.push(GoToolchainData {
+                    version,
+                    source,
+                    goroot,
+                });
+            }
+        }
+        installed.sort_by(|a, b| compare_versions(&b.version, &a.version));
+
+        // Like the `go` command, prefer the workspace's toolchain over the module's.
+        let mut pinned_version = None;
+        for manifest in ["go.work", "go.mod"] {
+            pinned_version = find_pinned_version(
+                fs,
+                &worktree_root,
+                &subroot_relative_path,
+                &[manifest],
+                parse_toolchain_directive,
+            )
+            .await;
+            if pinned_version.is_some() {
+                break;
+            }
+        }
+        let default = pinned_version.and_then(|pinned_version| {
+            installed
+                .iter()
+                .position(|data| data.version == pinned_version)
+        });
+
+        ToolchainList {
+            toolchains: installed.into_iter().filter_map(Self::toolchain).collect(),
+            default,
+            groups: Default::default(),
+        }
+    }
+
+    async fn resolve(
+        &self,
+        path: PathBuf,
+        _: Option<HashMap<String, String>>,
+        fs: &dyn Fs,
+    ) -> Result<Toolchain> {
+        let goroot = if fs.is_file(&path).await {
+            path.parent()
+                .and_then(Path::parent)
+                .context("Go executable is not in a bin directory")?
+                .to_path_buf()
+        } else {
+            path
+        };
+        let version = go_sdk_version(fs, &goroot)
+            .await
+            .with_context(|| format!("Could not find a Go SDK in {goroot:?}"))?;
+        Self::toolchain(GoToolchainData {
+            version,
+            source: GoSdkSource::Custom,
+            goroot,
+        })
+        .context("Could not convert the Go SDK into a toolchain")
+    }
+
+    fn activation_script(&self, _: &Toolchain, _: ShellKind, _: &App) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn activate_in_env(&self, toolchain: &Toolchain, env: &mut HashMap<String, String>) {
+        if let Ok(data) = serde_json::from_value::<GoToolchainData>(toolchain.as_json.clone()) {
+            prepend_to_path(env, &data.goroot.join("bin"));
+            env.insert("GOROOT".into(), data.goroot.to_string_lossy().into_owned());
+        }
+    }
+
+    fn meta(&self) -> ToolchainMetadata {
+        ToolchainMetadata {
+            term: SharedString::new_static("Go SDK"),
+            new_toolchain_placeholder: SharedString::new_static(
+                "A path to a go executable, or to the GOROOT of a Go SDK",
+            ),
+            manifest_name: ManifestName::from(SharedString::new_static("go.mod")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::language;
+    use gpui::BackgroundExecutor;
     use gpui::{AppContext, Hsla, TestAppContext};
+    use project::FakeFs;
     use theme::SyntaxTheme;
+    use util::{path, rel_path::rel_path};
 
     #[gpui::test]
     async fn test_go_label_for_completion() {
@@ -1340,4 +1605,64 @@ mod tests {
         let result = extract_subtest_name(input_with_double_quotes);
         assert_eq!(result, Some(r#"test_with_\"double_quotes\""#.to_string()));
     }
+
+    #[gpui::test]
+    async fn test_go_toolchains(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        let golang_dl_sdk = Path::new(path!("/home/user/sdk/go1.21.5"));
+        let downloaded_toolchain = Path::new(path!(
+            "/home/user/go/pkg/mod/golang.org/toolchain@v0.0.1-go1.22.3.linux-amd64"
+        ));
+        for (goroot, version) in [
+            (golang_dl_sdk, "go1.21.5\n"),
+            (
+                downloaded_toolchain,
+                "go1.22.3\ntime 2024-05-01T19:54:40Z\n",
+            ),
+        ] {
+            fs.create_dir(&goroot.join("bin")).await.unwrap();
+            fs.insert_file(goroot.join("bin").join(GO_BINARY), Vec::new())
+                .await;
+            fs.insert_file(goroot.join("VERSION"), version.into()).await;
+        }
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "go.work": "go 1.22\n\ntoolchain go1.22.3 // for the whole workspace\n\nuse ./api\n",
+                "api": {
+                    "go.mod": "module example.com/api\n\ngo 1.21\n\ntoolchain go1.21.5\n",
+                },
+            }),
+        )
+        .await;
+
+        let env = HashMap::from_iter([("HOME".to_string(), path!("/home/user").to_string())]);
+        let toolchains = GoToolchainProvider
+            .list(
+                PathBuf::from(path!("/root")),
+                rel_path("api").into(),
+                Some(env),
+                fs.as_ref(),
+            )
+            .await;
+        assert_eq!(
+            toolchains
+                .toolchains()
+                .iter()
+                .map(|toolchain| toolchain.name.as_ref())
+                .collect::<Vec<_>>(),
+            ["go1.22.3 (toolchain download)", "go1.21.5 (golang.org/dl)"]
+        );
+        // The workspace's toolchain directive takes precedence over the module's.
+        assert_eq!(toolchains.default, Some(0));
+
+        let mut env = HashMap::default();
+        GoToolchainProvider.activate_in_env(&toolchains.toolchains()[1], &mut env);
+        assert_eq!(env["GOROOT"], golang_dl_sdk.to_string_lossy());
+        assert!(!env.contains_key("GOTOOLCHAIN"));
+        assert_eq!(
+            std::env::split_paths(&env["PATH"]).collect::<Vec<_>>(),
+            [golang_dl_sdk.join("bin")]
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use anyhow::Context as _;
+use go::GoModManifestProvider;
 use gpui::{App, SharedString, UpdateGlobal};
 use node_runtime::NodeRuntime;
 use project::Fs;
@@ -8,6 +9,7 @@ use rust_embed::RustEmbed;
 use settings::SettingsStore;
 use smol::stream::StreamExt;
 use std::{str, sync::Arc};
+use typescript::PackageJsonManifestProvider;
 use util::{ResultExt, asset_str};
 
 pub use language::*;
@@ -29,6 +31,7 @@ mod python;
 mod rust;
 mod tailwind;
 mod typescript;
+mod version_manager;
 mod vtsls;
 mod yaml;
 
@@ -88,6 +91,7 @@ pub fn init(languages: Arc<LanguageRegistry>, fs: Arc<dyn Fs>, node: NodeRuntime
     let eslint_adapter = Arc::new(eslint::EsLintLspAdapter::new(node.clone()));
     let go_context_provider = Arc::new(go::GoContextProvider);
     let go_lsp_adapter = Arc::new(go::GoLspAdapter);
+    let go_toolchain_provider = Arc::new(go::GoToolchainProvider);
     let json_context_provider = Arc::new(JsonTaskProvider);
     let json_lsp_adapter = Arc::new(json::JsonLspAdapter::new(languages.clone(), node.clone()));
     let node_version_lsp_adapter = Arc::new(json::NodeVersionAdapter);
@@ -100,6 +104,7 @@ pub fn init(languages: Arc<LanguageRegistry>, fs: Arc<dyn Fs>, node: NodeRuntime
     let python_toolchain_provider = Arc::new(python::PythonToolchainProvider);
     let rust_context_provider = Arc::new(rust::RustContextProvider);
     let rust_lsp_adapter = Arc::new(rust::RustLspAdapter);
+    let rust_toolchain_provider = Arc::new(rust::RustToolchainProvider);
     let tailwind_adapter = Arc::new(tailwind::TailwindLspAdapter::new(node.clone()));
     let typescript_context = Arc::new(typescript::TypeScriptContextProvider::new(fs.clone()));
     let typescript_lsp_adapter = Arc::new(typescript::TypeScriptLspAdapter::new(
@@ -139,6 +144,7 @@ pub fn init(languages: Arc<LanguageRegistry>, fs: Arc<dyn Fs>, node: NodeRuntime
             name: "go",
             adapters: vec![go_lsp_adapter.clone()],
             context: Some(go_context_provider.clone()),
+            toolchain: Some(go_toolchain_provider),
             ..Default::default()
         },
         LanguageInfo {
@@ -186,25 +192,34 @@ pub fn init(languages: Arc<LanguageRegistry>, fs: Arc<dyn Fs>, node: NodeRuntime
             name: "rust",
             adapters: vec![rust_lsp_adapter],
             context: Some(rust_context_provider),
+            toolchain: Some(rust_toolchain_provider),
             manifest_name: Some(SharedString::new_static("Cargo.toml").into()),
-            ..Default::default()
         },
         LanguageInfo {
             name: "tsx",
             adapters: vec![typescript_lsp_adapter.clone(), vtsls_adapter.clone()],
             context: Some(typescript_context.clone()),
+            toolchain: Some(Arc::new(typescript::NodeToolchainProvider::new(
+                LanguageName::new_static("TSX"),
+            ))),
             ..Default::default()
         },
         LanguageInfo {
             name: "typescript",
             adapters: vec![typescript_lsp_adapter.clone(), vtsls_adapter.clone()],
             context: Some(typescript_context.clone()),
+            toolchain: Some(Arc::new(typescript::NodeToolchainProvider::new(
+                LanguageName::new_static("TypeScript"),
+            ))),
             ..Default::default()
         },
         LanguageInfo {
             name: "javascript",
             adapters: vec![typescript_lsp_adapter.clone(), vtsls_adapter.clone()],
             context: Some(typescript_context),
+            toolchain: Some(Arc::new(typescript::NodeToolchainProvider::new(
+                LanguageName::new_static("JavaScript"),
+            ))),
             ..Default::default()
         },
         LanguageInfo {
@@ -327,8 +342,10 @@ pub fn init(languages: Arc<LanguageRegistry>, fs: Arc<dyn Fs>, node: NodeRuntime
         anyhow::Ok(())
     })
     .detach();
-    let manifest_providers: [Arc<dyn ManifestProvider>; 2] = [
+    let manifest_providers: [Arc<dyn ManifestProvider>; 4] = [
         Arc::from(CargoManifestProvider),
+        Arc::from(GoModManifestProvider),
+        Arc::from(PackageJsonManifestProvider),
         Arc::from(PyprojectTomlManifestProvider),
     ];
     for provider in manifest_providers {
@@ -1241,9 +1241,12 @@ impl ToolchainLister for PythonToolchainProvider {
             out_toolchains.push(toolchain);
         }
         out_toolchains.dedup();
+        // The toolchains are sorted by how well they match the project, so the best match is
+        // picked when none is selected.
+        let default = (!out_toolchains.is_empty()).then_some(0);
         ToolchainList {
             toolchains: out_toolchains,
-            default: None,
+            default,
             groups: Default::default(),
         }
     }
@@ -8,14 +8,17 @@ use http_client::github::{GitHubLspBinaryVersion, latest_github_release};
 use http_client::github_download::{GithubBinaryMetadata, download_server_binary};
 pub use language::*;
 use lsp::{InitializeParams, LanguageServerBinary};
+use project::Fs;
 use project::lsp_store::rust_analyzer_ext::CARGO_DIAGNOSTICS_SOURCE_NAME;
 use project::project_settings::ProjectSettings;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use settings::Settings as _;
 use smallvec::SmallVec;
 use smol::fs::{self};
 use std::cmp::Reverse;
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::ops::Range;
 use std::{
@@ -23,13 +26,14 @@ use std::{
     path::{Path, PathBuf},
     sync::{Arc, LazyLock},
 };
-use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use task::{ShellKind, TaskTemplate, TaskTemplates, TaskVariables, VariableName};
 use util::fs::{make_file_executable, remove_matching};
 use util::merge_json_value_into;
 use util::rel_path::RelPath;
 use util::{ResultExt, maybe};
 
 use crate::language_settings::language_settings;
+use crate::version_manager::{dir_from_env, find_pinned_version, prepend_to_path, subdirectories};
 
 pub struct RustLspAdapter;
 
@@ -1176,17 +1180,211 @@ fn test_fragment(variables: &TaskVariables, path: &Path, stem: &str) -> String {
     fragment.unwrap_or_else(|| "--".to_owned())
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct RustToolchainData {
+    /// The name rustup knows the toolchain by, or `None` for toolchains it doesn't manage.
+    rustup_name: Option<String>,
+    toolchain_dir: PathBuf,
+}
+
+/// The parts of rustup's `settings.toml` that pin a toolchain for a directory.
+#[derive(Default, Deserialize)]
+struct RustupSettings {
+    #[serde(default)]
+    overrides: BTreeMap<PathBuf, String>,
+}
+
+#[derive(Deserialize)]
+struct RustToolchainFile {
+    toolchain: RustToolchainFileSection,
+}
+
+#[derive(Deserialize)]
+struct RustToolchainFileSection {
+    channel: Option<String>,
+}
+
+const RUSTC_BINARY: &str = if cfg!(windows) { "rustc.exe" } else { "rustc" };
+
+/// Lists the toolchains installed by rustup, preferring the one rustup itself would pick for the
+/// project: a directory override, then `rust-toolchain.toml`, then the default toolchain.
+pub(crate) struct RustToolchainProvider;
+
+impl RustToolchainProvider {
+    fn toolchain(data: RustToolchainData) -> Option<Toolchain> {
+        Some(Toolchain {
+            name: data
+                .rustup_name
+                .clone()
+                .or_else(|| Some(data.toolchain_dir.file_name()?.to_str()?.to_owned()))?
+                .into(),
+            path: data.toolchain_dir.to_str()?.to_owned().into(),
+            language_name: LanguageName::new_static("Rust"),
+            as_json: serde_json::to_value(data).ok()?,
+        })
+    }
+}
+
+fn parse_rust_toolchain_file(file_name: &str, contents: &str) -> Option<String> {
+    match toml::from_str::<RustToolchainFile>(contents) {
+        Ok(file) => file.toolchain.channel,
+        // The legacy `rust-toolchain` file may consist of just the channel name.
+        Err(_) if file_name == "rust-toolchain" => {
+            let channel = contents.trim();
+            (!channel.is_empty() && !channel.contains(char::is_whitespace))
+                .then(|| channel.to_owned())
+        }
+        Err(_) => None,
+    }
+}
+
+/// Whether an installed toolchain such as `stable-x86_64-unknown-linux-gnu` is the one `channel`
+/// (e.g. `stable`) refers to.
+fn toolchain_matches_channel(toolchain: &str, channel: &str) -> bool {
+    toolchain == channel
+        || toolchain
+            .strip_prefix(channel)
+            .and_then(|rest| rest.strip_prefix('-'))
+            // `nightly` shouldn't match `nightly-2024-05-01-x86_64-unknown-linux-gnu`.
+            .is_some_and(|host| !host.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+#[async_trait]
+impl ToolchainLister for RustToolchainProvider {
+    async fn list(
+        &self,
+        worktree_root: PathBuf,
+        subroot_relative_path: Arc<RelPath>,
+        project_env: Option<HashMap<String, String>>,
+        fs: &dyn Fs,
+    ) -> ToolchainList {
+        let env = project_env.unwrap_or_default();
+        let rustup_home = dir_from_env(&env, "RUSTUP_HOME", ".rustup");
+        let mut installed = Vec::new();
+        for toolchain_dir in subdirectories(fs, &rustup_home.join("toolchains")).await {
+            if fs
+                .is_file(&toolchain_dir.join("bin").join(RUSTC_BINARY))
+                .await
+                && let Some(name) = toolchain_dir.file_name().and_then(|name| name.to_str())
+            {
+                installed.push(RustToolchainData {
+                    rustup_name: Some(name.to_owned()),
+                    toolchain_dir,
+                });
+            }
+        }
+
+        let settings = fs
+            .load(&rustup_home.join("settings.toml"))
+            .await
+            .ok()
+            .and_then(|settings| toml::from_str::<RustupSettings>(&settings).log_err())
+            .unwrap_or_default();
+        let project_dir = worktree_root.join(subroot_relative_path.as_std_path());
+        let directory_override = settings
+            .overrides
+            .iter()
+            .filter(|(dir, _)| project_dir.starts_with(dir))
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map(|(_, toolchain)| toolchain.clone());
+        let toolchain_file = find_pinned_version(
+            fs,
+            &worktree_root,
+            &subroot_relative_path,
+            &["rust-toolchain.toml", "rust-toolchain"],
+            parse_rust_toolchain_file,
+        )
+        .await;
+        // Rustup's default toolchain isn't a pin, as rustup uses it without being told.
+        let default = [directory_override, toolchain_file]
+            .into_iter()
+            .flatten()
+            .find_map(|channel| {
+                installed.iter().position(|data| {
+                    data.rustup_name
+                        .as_deref()
+                        .is_some_and(|name| toolchain_matches_channel(name, &channel))
+                })
+            });
+
+        ToolchainList {
+            toolchains: installed.into_iter().filter_map(Self::toolchain).collect(),
+            default,
+            groups: Default::default(),
+        }
+    }
+
+    async fn resolve(
+        &self,
+        path: PathBuf,
+        env: Option<HashMap<String, String>>,
+        fs: &dyn Fs,
+    ) -> Result<Toolchain> {
+        let rustc = path.join("bin").join(RUSTC_BINARY);
+        anyhow::ensure!(
+            fs.is_file(&rustc).await,
+            "Could not find {rustc:?}; expected a path to a toolchain directory"
+        );
+        let rustup_toolchains =
+            dir_from_env(&env.unwrap_or_default(), "RUSTUP_HOME", ".rustup").join("toolchains");
+        let rustup_name = path
+            .parent()
+            .filter(|parent| *parent == rustup_toolchains)
+            .and(path.file_name())
+            .and_then(|name| name.to_str())
+            .map(ToOwned::to_owned);
+        Self::toolchain(RustToolchainData {
+            rustup_name,
+            toolchain_dir: path,
+        })
+        .context("Could not convert the toolchain directory into a toolchain")
+    }
+
+    fn activation_script(&self, _: &Toolchain, _: ShellKind, _: &App) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn activate_in_env(&self, toolchain: &Toolchain, env: &mut HashMap<String, String>) {
+        let Ok(data) = serde_json::from_value::<RustToolchainData>(toolchain.as_json.clone())
+        else {
+            return;
+        };
+        match data.rustup_name {
+            Some(name) => {
+                env.insert("RUSTUP_TOOLCHAIN".into(), name);
+            }
+            None => {
+                env.remove("RUSTUP_TOOLCHAIN");
+                prepend_to_path(env, &data.toolchain_dir.join("bin"));
+            }
+        }
+    }
+
+    fn meta(&self) -> ToolchainMetadata {
+        ToolchainMetadata {
+            term: SharedString::new_static("Rust Toolchain"),
+            new_toolchain_placeholder: SharedString::new_static(
+                "A path to a toolchain directory, containing bin/rustc",
+            ),
+            manifest_name: ManifestName::from(SharedString::new_static("Cargo.toml")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
 
     use super::*;
     use crate::language;
+    use gpui::BackgroundExecutor;
     use gpui::{BorrowAppContext, Hsla, TestAppContext};
     use lsp::CompletionItemLabelDetails;
+    use project::FakeFs;
     use settings::SettingsStore;
     use theme::SyntaxTheme;
     use util::path;
+    use util::rel_path::rel_path;
 
     #[gpui::test]
     async fn test_process_rust_diagnostics() {
@@ -1818,4 +2016,72 @@ mod tests {
         );
         check([], "/project/src/main.rs", "--");
     }
+
+    #[gpui::test]
+    async fn test_rust_toolchains(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        let rustup_home = Path::new(path!("/home/user/.rustup"));
+        for name in [
+            "1.80.0-x86_64-unknown-linux-gnu",
+            "nightly-2024-05-01-x86_64-unknown-linux-gnu",
+            "nightly-x86_64-unknown-linux-gnu",
+            "stable-x86_64-unknown-linux-gnu",
+        ] {
+            let bin_dir = rustup_home.join("toolchains").join(name).join("bin");
+            fs.create_dir(&bin_dir).await.unwrap();
+            fs.insert_file(bin_dir.join(RUSTC_BINARY), Vec::new()).await;
+        }
+        let settings = format!(
+            "default_toolchain = \"stable-x86_64-unknown-linux-gnu\"\n\n[overrides]\n'{}' = \"1.80.0-x86_64-unknown-linux-gnu\"\n",
+            path!("/root/overridden")
+        );
+        fs.insert_file(rustup_home.join("settings.toml"), settings.into())
+            .await;
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "Cargo.toml": "",
+                "nightly": { "rust-toolchain.toml": "[toolchain]\nchannel = \"nightly\"\n" },
+                "legacy": { "rust-toolchain": "nightly-2024-05-01\n" },
+                // Directory overrides take precedence over toolchain files.
+                "overridden": { "rust-toolchain.toml": "[toolchain]\nchannel = \"nightly\"\n" },
+            }),
+        )
+        .await;
+
+        let env = HashMap::from_iter([("HOME".to_string(), path!("/home/user").to_string())]);
+        let list = |subroot| {
+            RustToolchainProvider.list(
+                PathBuf::from(path!("/root")),
+                rel_path(subroot).into(),
+                Some(env.clone()),
+                fs.as_ref(),
+            )
+        };
+        let default_name = async |subroot| {
+            list(subroot)
+                .await
+                .default_toolchain()
+                .map(|toolchain| toolchain.name)
+        };
+
+        assert_eq!(default_name("").await, None);
+        assert_eq!(
+            default_name("nightly").await.as_deref(),
+            Some("nightly-x86_64-unknown-linux-gnu")
+        );
+        assert_eq!(
+            default_name("legacy").await.as_deref(),
+            Some("nightly-2024-05-01-x86_64-unknown-linux-gnu")
+        );
+        assert_eq!(
+            default_name("overridden").await.as_deref(),
+            Some("1.80.0-x86_64-unknown-linux-gnu")
+        );
+
+        let mut env = HashMap::default();
+        let toolchain = list("nightly").await.default_toolchain().unwrap();
+        RustToolchainProvider.activate_in_env(&toolchain, &mut env);
+        assert_eq!(env["RUSTUP_TOOLCHAIN"], "nightly-x86_64-unknown-linux-gnu");
+    }
 }
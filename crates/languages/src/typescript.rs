@@ -3,15 +3,17 @@ use async_trait::async_trait;
 use chrono::{DateTime, Local};
 use collections::HashMap;
 use futures::future::join_all;
-use gpui::{App, AppContext, AsyncApp, Task};
+use gpui::{App, AppContext, AsyncApp, SharedString, Task};
 use itertools::Itertools as _;
 use language::{
     ContextLocation, ContextProvider, File, LanguageName, LanguageToolchainStore, LspAdapter,
-    LspAdapterDelegate, LspInstaller, Toolchain,
+    LspAdapterDelegate, LspInstaller, ManifestName, ManifestProvider, ManifestQuery, Toolchain,
+    ToolchainList, ToolchainLister, ToolchainMetadata,
 };
 use lsp::{CodeActionKind, LanguageServerBinary, LanguageServerName, Uri};
 use node_runtime::{NodeRuntime, VersionStrategy};
 use project::{Fs, lsp_store::language_server_settings};
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use smol::lock::RwLock;
 use std::{
@@ -20,11 +22,17 @@ use std::{
     path::{Path, PathBuf},
     sync::{Arc, LazyLock},
 };
-use task::{TaskTemplate, TaskTemplates, VariableName};
+use task::{ShellKind, TaskTemplate, TaskTemplates, VariableName};
 use util::rel_path::RelPath;
 use util::{ResultExt, maybe};
 
-use crate::{PackageJson, PackageJsonData};
+use crate::{
+    PackageJson, PackageJsonData,
+    version_manager::{
+        compare_versions, dir_from_env, find_pinned_version, home_dir, prepend_to_path,
+        version_matches,
+    },
+};
 
 pub(crate) struct TypeScriptContextProvider {
     fs: Arc<dyn Fs>,
@@ -876,20 +884,291 @@ async fn get_cached_ts_server_binary(
     .log_err()
 }
 
+pub(crate) struct PackageJsonManifestProvider;
+
+impl ManifestProvider for PackageJsonManifestProvider {
+    fn name(&self) -> ManifestName {
+        SharedString::new_static("package.json").into()
+    }
+
+    fn search(
+        &self,
+        ManifestQuery {
+            path,
+            depth,
+            delegate,
+        }: ManifestQuery,
+    ) -> Option<Arc<RelPath>> {
+        for path in path.ancestors().take(depth) {
+            let p = path.join(RelPath::unix("package.json").unwrap());
+            if delegate.exists(&p, Some(false)) {
+                return Some(path.into());
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum NodeVersionManager {
+    Nvm,
+    Fnm,
+    Volta,
+    Custom,
+}
+
+impl NodeVersionManager {
+    fn label(&self) -> &'static str {
+        match self {
+            NodeVersionManager::Nvm => "nvm",
+            NodeVersionManager::Fnm => "fnm",
+            NodeVersionManager::Volta => "Volta",
+            NodeVersionManager::Custom => "custom",
+        }
+    }
+
+    /// The directories this version manager installs Node versions into, one subdirectory per version.
+    fn versions_dirs(&self, env: &HashMap<String, String>) -> Vec<PathBuf> {
+        match self {
+            NodeVersionManager::Nvm => {
+                vec![
+                    dir_from_env(env, "NVM_DIR", ".nvm")
+                        .join("versions")
+                        .join("node"),
+                ]
+            }
+            NodeVersionManager::Fnm => {
+                let fnm_dirs = if let Some(dir) = env.get("FNM_DIR") {
+                    vec![PathBuf::from(dir)]
+                } else {
+                    let home = home_dir(env);
+                    let data_dir = if cfg!(target_os = "macos") {
+                        home.join("Library/Application Support")
+                    } else if cfg!(target_os = "windows") {
+                        dir_from_env(env, "APPDATA", "AppData/Roaming")
+                    } else {
+                        dir_from_env(env, "XDG_DATA_HOME", ".local/share")
+                    };
+                    vec![data_dir.join("fnm"), home.join(".fnm")]
+                };
+                fnm_dirs
+                    .into_iter()
+                    .map(|dir| dir.join("node-versions"))
+                    .collect()
+            }
+            NodeVersionManager::Volta => vec![
+                dir_from_env(env, "VOLTA_HOME", ".volta")
+                    .join("tools")
+                    .join("image")
+                    .join("node"),
+            ],
+            NodeVersionManager::Custom => Vec::new(),
+        }
+    }
+
+    /// The directory with the `bin` directory of the Node version installed in `version_dir`.
+    fn installation_dir(&self, version_dir: PathBuf) -> PathBuf {
+        match self {
+            NodeVersionManager::Fnm => version_dir.join("installation"),
+            _ => version_dir,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NodeToolchainData {
+    version: String,
+    manager: NodeVersionManager,
+    bin_dir: PathBuf,
+}
+
+/// Lists the Node.js versions installed by nvm, fnm and Volta, preferring the one a project pins
+/// through `.nvmrc`, `.node-version` or the `volta` field of its `package.json`.
+///
+/// JavaScript, TypeScript and TSX each get their own lister, since active toolchains are tracked per
+/// language.
+pub(crate) struct NodeToolchainProvider {
+    language_name: LanguageName,
+}
+
+impl NodeToolchainProvider {
+    pub(crate) fn new(language_name: LanguageName) -> Self {
+        Self { language_name }
+    }
+
+    fn toolchain(&self, data: NodeToolchainData) -> Option<Toolchain> {
+        Some(Toolchain {
+            name: format!("Node.js {} ({})", data.version, data.manager.label()).into(),
+            path: data.bin_dir.join(NODE_BINARY).to_str()?.to_owned().into(),
+            language_name: self.language_name.clone(),
+            as_json: serde_json::to_value(data).ok()?,
+        })
+    }
+}
+
+const NODE_BINARY: &str = if cfg!(windows) { "node.exe" } else { "node" };
+
+fn node_bin_dir(installation_dir: PathBuf) -> PathBuf {
+    if cfg!(windows) {
+        installation_dir
+    } else {
+        installation_dir.join("bin")
+    }
+}
+
+fn parse_pinned_node_version(file_name: &str, contents: &str) -> Option<String> {
+    let version = if file_name == "package.json" {
+        let package_json: serde_json_lenient::Value =
+            serde_json_lenient::from_str(contents).ok()?;
+        package_json.get("volta")?.get("node")?.as_str()?.to_owned()
+    } else {
+        contents
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .find(|line| !line.is_empty())?
+            .to_owned()
+    };
+    Some(version)
+}
+
+#[async_trait]
+impl ToolchainLister for NodeToolchainProvider {
+    async fn list(
+        &self,
+        worktree_root: PathBuf,
+        subroot_relative_path: Arc<RelPath>,
+        project_env: Option<HashMap<String, String>>,
+        fs: &dyn Fs,
+    ) -> ToolchainList {
+        let env = project_env.unwrap_or_default();
+        let mut installed = Vec::new();
+        for manager in [
+            NodeVersionManager::Nvm,
+            NodeVersionManager::Fnm,
+            NodeVersionManager::Volta,
+        ] {
+            for versions_dir in manager.versions_dirs(&env) {
+                for version_dir in subdirectories(fs, &versions_dir).await {
+                    let Some(version) = version_dir
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .map(|name| format!("v{}", name.trim_start_matches('v')))
+                    else {
+                        continue;
+                    };
+                    let bin_dir = node_bin_dir(manager.installation_dir(version_dir));
+                    if fs.is_file(&bin_dir.join(NODE_BINARY)).await {
+                        installed.push(NodeToolchainData {
+                            version,
+                            manager,
+                            bin_dir,
+                        });
+                    }
+                }
+            }
+        }
+        // Newest first, so that the first match for a partial version like `20` is the latest `20.x`.
+        installed.sort_by(|a, b| {
+            compare_versions(&b.version, &a.version).then_with(|| a.bin_dir.cmp(&b.bin_dir))
+        });
+
+        let pinned_version = find_pinned_version(
+            fs,
+            &worktree_root,
+            &subroot_relative_path,
+            &[".nvmrc", ".node-version", "package.json"],
+            parse_pinned_node_version,
+        )
+        .await;
+        let default = pinned_version.and_then(|pinned_version| {
+            installed
+                .iter()
+                .position(|data| version_matches(&data.version, &pinned_version))
+        });
+
+        ToolchainList {
+            toolchains: installed
+                .into_iter()
+                .filter_map(|data| self.toolchain(data))
+                .collect(),
+            default,
+            groups: Default::default(),
+        }
+    }
+
+    async fn resolve(
+        &self,
+        path: PathBuf,
+        _: Option<HashMap<String, String>>,
+        fs: &dyn Fs,
+    ) -> Result<Toolchain> {
+        let bin_dir = if fs.is_file(&path).await {
+            path.parent()
+                .context("Node.js executable has no parent directory")?
+                .to_path_buf()
+        } else {
+            node_bin_dir(path)
+        };
+        let node = bin_dir.join(NODE_BINARY);
+        anyhow::ensure!(fs.is_file(&node).await, "Could not find {node:?}");
+        let output = util::command::new_smol_command(&node)
+            .arg("--version")
+            .output()
+            .await
+            .with_context(|| format!("running {node:?}"))?;
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        anyhow::ensure!(
+            !version.is_empty(),
+            "Could not determine the version of {node:?}"
+        );
+        self.toolchain(NodeToolchainData {
+            version,
+            manager: NodeVersionManager::Custom,
+            bin_dir,
+        })
+        .context("Could not convert the Node.js installation into a toolchain")
+    }
+
+    fn activation_script(&self, _: &Toolchain, _: ShellKind, _: &App) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn activate_in_env(&self, toolchain: &Toolchain, env: &mut HashMap<String, String>) {
+        if let Ok(data) = serde_json::from_value::<NodeToolchainData>(toolchain.as_json.clone()) {
+            prepend_to_path(env, &data.bin_dir);
+        }
+    }
+
+    fn meta(&self) -> ToolchainMetadata {
+        ToolchainMetadata {
+            term: SharedString::new_static("Node.js Version"),
+            new_toolchain_placeholder: SharedString::new_static(
+                "A path to a node executable, or to the directory Node.js is installed in",
+            ),
+            manifest_name: ManifestName::from(SharedString::new_static("package.json")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::Path;
 
     use gpui::{AppContext as _, BackgroundExecutor, TestAppContext};
-    use project::FakeFs;
+    use project::{FakeFs, Fs as _};
     use serde_json::json;
     use task::TaskTemplates;
     use unindent::Unindent;
     use util::{path, rel_path::rel_path};
 
+    use crate::typescript::{NODE_BINARY, NodeToolchainProvider, node_bin_dir};
     use crate::typescript::{
         PackageJsonData, TypeScriptContextProvider, replace_test_name_parameters,
     };
+    use collections::HashMap;
+    use language::{LanguageName, ToolchainLister as _};
+    use std::path::PathBuf;
 
     #[gpui::test]
     async fn test_outline(cx: &mut TestAppContext) {
@@ -1469,4 +1748,67 @@ mod tests {
             "Bun should come before Node"
         );
     }
+
+    #[gpui::test]
+    async fn test_node_toolchains(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        let nvm_versions = Path::new(path!("/home/user/.nvm/versions/node"));
+        let volta_versions = Path::new(path!("/home/user/.volta/tools/image/node"));
+        for version_dir in [
+            nvm_versions.join("v18.19.1"),
+            nvm_versions.join("v20.9.0"),
+            volta_versions.join("20.11.0"),
+        ] {
+            let bin_dir = node_bin_dir(version_dir);
+            fs.create_dir(&bin_dir).await.unwrap();
+            fs.insert_file(bin_dir.join(NODE_BINARY), Vec::new()).await;
+        }
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "package.json": r#"{ "volta": { "node": "20.11.0" } }"#,
+                "services": {
+                    "legacy": {
+                        ".nvmrc": "v18 # LTS\n",
+                        "package.json": "{}",
+                    },
+                },
+            }),
+        )
+        .await;
+
+        let provider = NodeToolchainProvider::new(LanguageName::new_static("TypeScript"));
+        let env = HashMap::from_iter([("HOME".to_string(), path!("/home/user").to_string())]);
+        let list = |subroot| {
+            provider.list(
+                PathBuf::from(path!("/root")),
+                rel_path(subroot).into(),
+                Some(env.clone()),
+                fs.as_ref(),
+            )
+        };
+
+        let toolchains = list("").await;
+        assert_eq!(
+            toolchains
+                .toolchains()
+                .iter()
+                .map(|toolchain| toolchain.name.as_ref())
+                .collect::<Vec<_>>(),
+            [
+                "Node.js v20.11.0 (Volta)",
+                "Node.js v20.9.0 (nvm)",
+                "Node.js v18.19.1 (nvm)",
+            ]
+        );
+        assert_eq!(toolchains.default, Some(0));
+        assert_eq!(list("services/legacy").await.default, Some(2));
+
+        let mut env = HashMap::default();
+        provider.activate_in_env(&toolchains.default_toolchain().unwrap(), &mut env);
+        assert_eq!(
+            std::env::split_paths(&env["PATH"]).next(),
+            Some(node_bin_dir(volta_versions.join("20.11.0")))
+        );
+    }
 }
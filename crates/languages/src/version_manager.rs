@@ -0,0 +1,149 @@
+//! Helpers shared by the toolchain listers that discover toolchains installed by version managers
+//! (nvm, fnm, Volta, rustup and Go's own SDK downloads).
+
+use collections::HashMap;
+use futures::StreamExt as _;
+use project::Fs;
+use std::{
+    cmp::Ordering,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::rel_path::RelPath;
+
+/// Returns the directory named by the `var` environment variable, falling back to `default`
+/// resolved against the home directory.
+pub(crate) fn dir_from_env(
+    env: &HashMap<String, String>,
+    var: &str,
+    default: impl AsRef<Path>,
+) -> PathBuf {
+    match env.get(var) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home_dir(env).join(default),
+    }
+}
+
+pub(crate) fn home_dir(env: &HashMap<String, String>) -> PathBuf {
+    env.get("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| util::paths::home_dir().clone())
+}
+
+/// Lists the directories directly inside `dir`, sorted by path.
+pub(crate) async fn subdirectories(fs: &dyn Fs, dir: &Path) -> Vec<PathBuf> {
+    let Ok(mut entries) = fs.read_dir(dir).await else {
+        return Vec::new();
+    };
+    let mut subdirectories = Vec::new();
+    while let Some(entry) = entries.next().await {
+        if let Ok(entry) = entry
+            && fs.is_dir(&entry).await
+        {
+            subdirectories.push(entry);
+        }
+    }
+    subdirectories.sort();
+    subdirectories
+}
+
+/// Searches the subproject and its ancestors up to the worktree root for a pinned version, the way
+/// version managers look for their pin files (`.nvmrc`, `rust-toolchain.toml`, ...) from the working
+/// directory upwards. `parse` is given the name and contents of each of `file_names` that exists, and the
+/// innermost file it finds a version in wins.
+pub(crate) async fn find_pinned_version<T>(
+    fs: &dyn Fs,
+    worktree_root: &Path,
+    subroot_relative_path: &RelPath,
+    file_names: &[&str],
+    parse: impl Fn(&str, &str) -> Option<T>,
+) -> Option<T> {
+    for ancestor in subroot_relative_path.ancestors() {
+        let dir = worktree_root.join(ancestor.as_std_path());
+        for file_name in file_names {
+            if let Ok(contents) = fs.load(&dir.join(file_name)).await
+                && let Some(version) = parse(file_name, &contents)
+            {
+                return Some(version);
+            }
+        }
+    }
+    None
+}
+
+/// Compares version numbers such as `v20.11.0` or `go1.22rc1` component by component, so that `1.10`
+/// sorts after `1.9`.
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    let components = |version: &str| {
+        version
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|component| !component.is_empty())
+            .map(|component| component.parse::<u64>().unwrap_or(u64::MAX))
+            .collect::<Vec<_>>()
+    };
+    components(a).cmp(&components(b))
+}
+
+/// Whether `version` (e.g. `20.11.1`) satisfies a partial version requirement such as `20` or `20.11`.
+pub(crate) fn version_matches(version: &str, requirement: &str) -> bool {
+    let version = version.trim_start_matches('v');
+    let requirement = requirement.trim_start_matches('v');
+    version == requirement
+        || version
+            .strip_prefix(requirement)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Puts `dir` in front of the other entries of `PATH` in `env`.
+pub(crate) fn prepend_to_path(env: &mut HashMap<String, String>, dir: &Path) {
+    let path_var = env
+        .keys()
+        .find(|key| key.eq_ignore_ascii_case("PATH"))
+        .cloned()
+        .unwrap_or_else(|| "PATH".to_string());
+    let mut paths = vec![dir.to_path_buf()];
+    if let Some(existing) = env.get(&path_var) {
+        paths.extend(std::env::split_paths(existing).filter(|path| path != dir));
+    }
+    if let Some(joined) = std::env::join_paths(paths)
+        .ok()
+        .and_then(|joined: OsString| joined.into_string().ok())
+    {
+        env.insert(path_var, joined);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        let mut versions = vec!["v20.9.0", "v18.19.1", "v20.11.0", "v20.11"];
+        versions.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(versions, ["v18.19.1", "v20.9.0", "v20.11", "v20.11.0"]);
+        assert_eq!(compare_versions("go1.9", "go1.22rc1"), Ordering::Less);
+
+        assert!(version_matches("v20.11.0", "20"));
+        assert!(version_matches("20.11.0", "v20.11"));
+        assert!(version_matches("20.11.0", "20.11.0"));
+        assert!(!version_matches("20.11.0", "20.1"));
+        assert!(!version_matches("200.0.0", "20"));
+    }
+
+    #[test]
+    fn test_prepend_to_path() {
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        let mut env = HashMap::from_iter([(
+            "PATH".to_string(),
+            ["/usr/bin", "/opt/node/bin"].join(separator),
+        )]);
+        prepend_to_path(&mut env, Path::new("/opt/node/bin"));
+        assert_eq!(env["PATH"], ["/opt/node/bin", "/usr/bin"].join(separator));
+
+        let mut env = HashMap::default();
+        prepend_to_path(&mut env, Path::new("/opt/go/bin"));
+        assert_eq!(env["PATH"], "/opt/go/bin");
+    }
+}
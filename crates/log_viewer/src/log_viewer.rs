@@ -0,0 +1,557 @@
+use std::{
+    fs,
+    io::{self, Read as _, Seek as _},
+    ops::Range,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use editor::{Editor, EditorEvent};
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, Render,
+    Task, UniformListScrollHandle, Window, uniform_list,
+};
+use regex::Regex;
+use ui::{ScrollableHandle as _, Tooltip, WithScrollbar, prelude::*};
+use workspace::{Item, Workspace, item::ItemEvent};
+use zed_actions::OpenLogViewer;
+use zlog::{JsonRecord, filter::RecordFilter};
+
+/// How often the log file is checked for new records.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// The most records kept in memory; older ones are dropped as new ones arrive.
+const MAX_RECORDS: usize = 20_000;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(|workspace, _: &OpenLogViewer, window, cx| {
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items()
+                .find_map(|item| item.downcast::<LogViewer>());
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+            } else {
+                let view = cx.new(|cx| LogViewer::new(window, cx));
+                workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// Shows the records written by zlog's JSON sink as they're logged, filtered
+/// by level and scope using the `ZED_LOG` syntax, and by a regex.
+///
+/// The JSON sink is only enabled while a log viewer is open, and each viewer
+/// starts reading at the end of the log, so records logged before it was opened
+/// aren't shown.
+pub struct LogViewer {
+    filter_editor: Entity<Editor>,
+    search_editor: Entity<Editor>,
+    filter: Option<RecordFilter>,
+    search: Option<Regex>,
+    error: Option<SharedString>,
+    records: Vec<JsonRecord>,
+    /// Indices into `records` of those that pass the filter and search.
+    matches: Vec<usize>,
+    /// Whether to keep the newest record in view as records arrive.
+    follow: bool,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    _json_output: JsonOutput,
+    _tail: Task<()>,
+}
+
+/// The number of open log viewers, which keep the JSON sink enabled.
+static OPEN_VIEWERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Enables zlog's JSON sink until the last log viewer is dropped.
+struct JsonOutput;
+
+impl JsonOutput {
+    fn new() -> Self {
+        if OPEN_VIEWERS.fetch_add(1, Ordering::SeqCst) == 0
+            && let Err(error) = zlog::init_output_json_file(
+                paths::json_log_file(),
+                Some(paths::old_json_log_file()),
+            )
+        {
+            log::error!("could not open the JSON log file: {error}");
+        }
+        Self
+    }
+}
+
+impl Drop for JsonOutput {
+    fn drop(&mut self) {
+        if OPEN_VIEWERS.fetch_sub(1, Ordering::SeqCst) == 1 {
+            zlog::close_output_json_file();
+        }
+    }
+}
+
+impl LogViewer {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter, e.g. warn,project=debug", window, cx);
+            editor
+        });
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search messages (regex)…", window, cx);
+            editor
+        });
+        for editor in [&filter_editor, &search_editor] {
+            cx.subscribe(editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_query(cx);
+                }
+            })
+            .detach();
+        }
+
+        // The end of the log is found before the sink is enabled, so that records
+        // logged in between aren't missed.
+        let tail = LogTail::from_end(paths::json_log_file().clone());
+        let json_output = JsonOutput::new();
+        Self {
+            filter_editor,
+            search_editor,
+            filter: None,
+            search: None,
+            error: None,
+            records: Vec::new(),
+            matches: Vec::new(),
+            follow: true,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _json_output: json_output,
+            _tail: Self::tail(tail, cx),
+        }
+    }
+
+    fn tail(mut tail: LogTail, cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(POLL_INTERVAL).await;
+                let records;
+                (records, tail) = cx
+                    .background_spawn(async move {
+                        let records = tail.read_new();
+                        if let Err(error) = &records {
+                            log::debug!("failed to read the JSON log: {error}");
+                        }
+                        (records.unwrap_or_default(), tail)
+                    })
+                    .await;
+                let updated = this.update(cx, |this, cx| this.push_records(records, cx));
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    fn push_records(&mut self, records: Vec<JsonRecord>, cx: &mut Context<Self>) {
+        if records.is_empty() {
+            return;
+        }
+        self.records.extend(records);
+        if self.records.len() > MAX_RECORDS {
+            self.records.drain(..self.records.len() - MAX_RECORDS);
+        }
+        self.update_matches();
+        cx.notify();
+    }
+
+    fn update_query(&mut self, cx: &mut Context<Self>) {
+        let filter = self.filter_editor.read(cx).text(cx);
+        let search = self.search_editor.read(cx).text(cx);
+        self.error = None;
+        self.filter = if filter.trim().is_empty() {
+            None
+        } else {
+            match RecordFilter::parse(&filter) {
+                Ok(filter) => Some(filter),
+                Err(error) => {
+                    self.error = Some(format!("Invalid filter: {error}").into());
+                    None
+                }
+            }
+        };
+        self.search = if search.is_empty() {
+            None
+        } else {
+            match Regex::new(&search) {
+                Ok(search) => Some(search),
+                Err(error) => {
+                    self.error = Some(format!("Invalid regex: {error}").into());
+                    None
+                }
+            }
+        };
+        self.update_matches();
+        cx.notify();
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = self
+            .records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| {
+                record_matches(record, self.filter.as_ref(), self.search.as_ref())
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+    }
+
+    fn render_rows(
+        &mut self,
+        range: Range<usize>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        range
+            .filter_map(|ix| {
+                let record = &self.records[*self.matches.get(ix)?];
+                Some(render_record(ix, record, cx))
+            })
+            .collect()
+    }
+
+    fn status_text(&self) -> SharedString {
+        if let Some(error) = &self.error {
+            error.clone()
+        } else if self.matches.len() == self.records.len() {
+            format!("{} records", self.records.len()).into()
+        } else {
+            format!("{} of {} records", self.matches.len(), self.records.len()).into()
+        }
+    }
+}
+
+fn render_record(ix: usize, record: &JsonRecord, cx: &App) -> AnyElement {
+    let (level_color, level) = match record.level {
+        log::Level::Error => (Color::Error, "ERROR"),
+        log::Level::Warn => (Color::Warning, "WARN"),
+        log::Level::Info => (Color::Info, "INFO"),
+        log::Level::Debug => (Color::Muted, "DEBUG"),
+        log::Level::Trace => (Color::Hint, "TRACE"),
+    };
+    // Only the time is shown, as the view is mostly used for the current session.
+    let time = record
+        .timestamp
+        .get(11..23)
+        .unwrap_or(&record.timestamp)
+        .to_string();
+    let mut message_lines = record.message.lines();
+    let first_line = message_lines.next().unwrap_or_default().to_string();
+    let is_multiline = message_lines.next().is_some();
+
+    h_flex()
+        .id(("log-record", ix))
+        .w_full()
+        .h_6()
+        .px_2()
+        .gap_2()
+        .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+        .child(
+            Label::new(time)
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .buffer_font(cx),
+        )
+        .child(
+            div().w_12().flex_none().child(
+                Label::new(level)
+                    .size(LabelSize::Small)
+                    .color(level_color)
+                    .buffer_font(cx),
+            ),
+        )
+        .child(
+            Label::new(format!("[{}]", record.scope.join(".")))
+                .size(LabelSize::Small)
+                .color(Color::Accent)
+                .buffer_font(cx),
+        )
+        .child(
+            Label::new(first_line)
+                .size(LabelSize::Small)
+                .buffer_font(cx)
+                .truncate(),
+        )
+        .when(is_multiline, |this| {
+            let message = record.message.clone();
+            this.tooltip(Tooltip::text(message))
+        })
+        .into_any_element()
+}
+
+fn record_matches(
+    record: &JsonRecord,
+    filter: Option<&RecordFilter>,
+    search: Option<&Regex>,
+) -> bool {
+    filter.is_none_or(|filter| {
+        filter.is_enabled(
+            &record.scope_ref(),
+            record.module_path.as_deref(),
+            record.level,
+        )
+    }) && search.is_none_or(|search| search.is_match(&record.message))
+}
+
+/// Reads the records appended to a JSON log file since it was last read.
+struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    /// The end of a record that was still being written at the last read.
+    /// Kept as bytes, since it may end within a character.
+    partial_line: Vec<u8>,
+}
+
+impl LogTail {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            partial_line: Vec::new(),
+        }
+    }
+
+    /// Creates a tail that only reads records written after this call.
+    fn from_end(path: PathBuf) -> Self {
+        let offset = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+        Self {
+            offset,
+            ..Self::new(path)
+        }
+    }
+
+    fn read_new(&mut self) -> io::Result<Vec<JsonRecord>> {
+        let mut file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            // The file was rotated, which truncates it.
+            self.offset = 0;
+            self.partial_line.clear();
+        }
+        file.seek(io::SeekFrom::Start(self.offset))?;
+        let read = file.read_to_end(&mut self.partial_line)?;
+        self.offset += read as u64;
+
+        let Some(end) = self.partial_line.iter().rposition(|byte| *byte == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let records = self.partial_line[..end]
+            .split(|byte| *byte == b'\n')
+            .filter_map(|line| serde_json::from_slice(line).ok())
+            .collect();
+        self.partial_line.drain(..=end);
+        Ok(records)
+    }
+}
+
+impl EventEmitter<ItemEvent> for LogViewer {}
+
+impl Focusable for LogViewer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LogViewer {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let scroll_offset = self.scroll_handle.offset();
+        let max_offset = self.scroll_handle.max_offset();
+        self.follow = -scroll_offset.y >= max_offset.height - px(24.);
+        if self.follow {
+            self.scroll_handle.scroll_to_bottom();
+        }
+
+        let status_color = if self.error.is_some() {
+            Color::Error
+        } else {
+            Color::Muted
+        };
+
+        v_flex()
+            .key_context("LogViewer")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .h(rems_from_px(41.))
+                    .pl_3()
+                    .pr_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(div().flex_1().child(self.filter_editor.clone()))
+                    .child(div().flex_1().child(self.search_editor.clone()))
+                    .child(
+                        Label::new(self.status_text())
+                            .size(LabelSize::Small)
+                            .color(status_color),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .size_full()
+                    .child(
+                        uniform_list(
+                            "log-records",
+                            self.matches.len(),
+                            cx.processor(Self::render_rows),
+                        )
+                        .flex_1()
+                        .size_full()
+                        .track_scroll(&self.scroll_handle),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+    }
+}
+
+impl Item for LogViewer {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Log".into()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::FileTextOutlined))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        _workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.filter_editor.focus_handle(cx));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+
+    fn record(level: log::Level, scope: &[&str], module_path: &str, message: &str) -> JsonRecord {
+        JsonRecord {
+            timestamp: "2024-05-01T10:00:00.000+00:00".into(),
+            level,
+            scope: scope.iter().map(|scope| scope.to_string()).collect(),
+            module_path: Some(module_path.into()),
+            line: None,
+            thread: "main".into(),
+            message: message.into(),
+        }
+    }
+
+    #[test]
+    fn test_record_matches() {
+        let records = [
+            record(log::Level::Info, &["workspace"], "workspace", "opened"),
+            record(
+                log::Level::Debug,
+                &["project", "git"],
+                "project::git_store",
+                "scanned 12 repositories",
+            ),
+            record(
+                log::Level::Warn,
+                &["editor"],
+                "editor::element",
+                "slow layout",
+            ),
+        ];
+        let matching = |filter: &str, search: &str| {
+            let filter = RecordFilter::parse(filter).unwrap();
+            let search = (!search.is_empty()).then(|| Regex::new(search).unwrap());
+            records
+                .iter()
+                .filter(|record| record_matches(record, Some(&filter), search.as_ref()))
+                .map(|record| record.message.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching("info", ""), ["opened", "slow layout"]);
+        assert_eq!(
+            matching("warn,project=debug", ""),
+            ["scanned 12 repositories", "slow layout"]
+        );
+        assert_eq!(matching("error,editor::element=warn", ""), ["slow layout"]);
+        assert_eq!(matching("trace", r"\d+ repos"), ["scanned 12 repositories"]);
+    }
+
+    #[test]
+    fn test_log_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Zed.log.jsonl");
+        let line = |message: &str| {
+            let record = record(log::Level::Info, &["zed"], "zed", message);
+            serde_json::to_string(&record).unwrap() + "\n"
+        };
+        let messages = |records: Vec<JsonRecord>| {
+            records
+                .into_iter()
+                .map(|record| record.message)
+                .collect::<Vec<_>>()
+        };
+
+        let mut tail = LogTail::new(path.clone());
+        assert!(tail.read_new().unwrap().is_empty());
+
+        let mut file = fs::File::create(&path).unwrap();
+        let second = line("second");
+        let (second_start, second_end) = second.split_at(10);
+        write!(file, "{}{}", line("first"), second_start).unwrap();
+        assert_eq!(messages(tail.read_new().unwrap()), ["first"]);
+
+        write!(file, "{}not json\n{}", second_end, line("third")).unwrap();
+        assert_eq!(messages(tail.read_new().unwrap()), ["second", "third"]);
+        assert!(tail.read_new().unwrap().is_empty());
+
+        // Characters split across reads are decoded once the line is complete.
+        let accented = line("café");
+        let split = accented.find('é').unwrap() + 1;
+        file.write_all(&accented.as_bytes()[..split]).unwrap();
+        assert!(tail.read_new().unwrap().is_empty());
+        file.write_all(&accented.as_bytes()[split..]).unwrap();
+        assert_eq!(messages(tail.read_new().unwrap()), ["café"]);
+
+        // Rotation truncates the file.
+        let mut file = fs::File::create(&path).unwrap();
+        write!(file, "{}", line("rotated")).unwrap();
+        assert_eq!(messages(tail.read_new().unwrap()), ["rotated"]);
+
+        // Records written before a tail is created from the end are skipped.
+        let mut tail = LogTail::from_end(path.clone());
+        assert!(tail.read_new().unwrap().is_empty());
+        write!(file, "{}", line("new")).unwrap();
+        assert_eq!(messages(tail.read_new().unwrap()), ["new"]);
+    }
+}
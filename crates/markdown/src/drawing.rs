@@ -0,0 +1,409 @@
+//! Shapes and text laid out in a coordinate space of their own, which is how
+//! math and diagrams are rendered. Layout only needs a way to measure text, so
+//! it can be tested without a window.
+
+use std::sync::Arc;
+
+use gpui::{
+    App, BorderStyle, Bounds, Element, ElementId, Font, FontStyle, FontWeight, GlobalElementId,
+    Hsla, IntoElement, LayoutId, PathBuilder, Pixels, Point, SharedString, Size, Style, TextRun,
+    Window, WindowTextSystem, point, px, quad, size,
+};
+use theme::ActiveTheme as _;
+
+/// The size and vertical metrics of a single line of text.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextMetrics {
+    pub width: f32,
+    pub ascent: f32,
+    pub descent: f32,
+}
+
+impl TextMetrics {
+    pub fn height(&self) -> f32 {
+        self.ascent + self.descent
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextVariant {
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl TextVariant {
+    pub const REGULAR: Self = Self {
+        bold: false,
+        italic: false,
+    };
+    pub const ITALIC: Self = Self {
+        bold: false,
+        italic: true,
+    };
+    pub const BOLD: Self = Self {
+        bold: true,
+        italic: false,
+    };
+}
+
+/// Measures text for layout, so that drawings can be laid out for any output.
+pub trait MeasureText {
+    fn measure(&self, text: &str, font_size: f32, variant: TextVariant) -> TextMetrics;
+}
+
+/// A role for a color, which is resolved against the theme when painting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ink {
+    Text,
+    MutedText,
+    Line,
+    Surface,
+    Note,
+    Background,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Primitive {
+    Text {
+        /// The left end of the text's baseline.
+        origin: Point<f32>,
+        text: SharedString,
+        font_size: f32,
+        variant: TextVariant,
+        ink: Ink,
+    },
+    Rect {
+        bounds: Bounds<f32>,
+        corner_radius: f32,
+        fill: Option<Ink>,
+        stroke: Option<Ink>,
+        dashed: bool,
+    },
+    Polygon {
+        points: Vec<Point<f32>>,
+        fill: Option<Ink>,
+        stroke: Option<Ink>,
+    },
+    Polyline {
+        points: Vec<Point<f32>>,
+        width: f32,
+        ink: Ink,
+        dashed: bool,
+    },
+}
+
+impl Primitive {
+    pub fn translate(&mut self, delta: Point<f32>) {
+        match self {
+            Primitive::Text { origin, .. } => *origin = *origin + delta,
+            Primitive::Rect { bounds, .. } => bounds.origin = bounds.origin + delta,
+            Primitive::Polygon { points, .. } | Primitive::Polyline { points, .. } => {
+                for point in points {
+                    *point = *point + delta;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Drawing {
+    pub size: Size<f32>,
+    pub primitives: Vec<Primitive>,
+}
+
+impl Drawing {
+    #[cfg(test)]
+    pub fn texts(&self) -> impl Iterator<Item = &str> {
+        self.primitives
+            .iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Text { text, .. } => Some(text.as_ref()),
+                _ => None,
+            })
+    }
+}
+
+/// Samples a quadratic bezier curve into a polyline, excluding its start point.
+pub(crate) fn quadratic_curve(
+    from: Point<f32>,
+    control: Point<f32>,
+    to: Point<f32>,
+    segments: usize,
+) -> impl Iterator<Item = Point<f32>> {
+    (1..=segments).map(move |segment| {
+        let t = segment as f32 / segments as f32;
+        let u = 1. - t;
+        point(
+            u * u * from.x + 2. * u * t * control.x + t * t * to.x,
+            u * u * from.y + 2. * u * t * control.y + t * t * to.y,
+        )
+    })
+}
+
+type BuildDrawing = Box<dyn FnOnce(&dyn MeasureText, f32) -> Drawing>;
+
+/// An element that lays out a [`Drawing`] using the inherited text style and
+/// paints it at its natural size.
+pub(crate) struct DrawingElement {
+    build: Option<BuildDrawing>,
+}
+
+impl DrawingElement {
+    pub fn new(build: impl FnOnce(&dyn MeasureText, f32) -> Drawing + 'static) -> Self {
+        Self {
+            build: Some(Box::new(build)),
+        }
+    }
+}
+
+struct WindowTextMeasure {
+    text_system: Arc<WindowTextSystem>,
+    font: Font,
+}
+
+impl WindowTextMeasure {
+    fn font(&self, variant: TextVariant) -> Font {
+        let mut font = self.font.clone();
+        if variant.bold {
+            font.weight = FontWeight::BOLD;
+        }
+        if variant.italic {
+            font.style = FontStyle::Italic;
+        }
+        font
+    }
+}
+
+impl MeasureText for WindowTextMeasure {
+    fn measure(&self, text: &str, font_size: f32, variant: TextVariant) -> TextMetrics {
+        let measured_text = if text.is_empty() { " " } else { text };
+        let run = TextRun {
+            len: measured_text.len(),
+            font: self.font(variant),
+            color: Hsla::default(),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        let layout = self
+            .text_system
+            .layout_line(measured_text, px(font_size), &[run], None);
+        TextMetrics {
+            width: if text.is_empty() {
+                0.
+            } else {
+                f32::from(layout.width)
+            },
+            ascent: f32::from(layout.ascent),
+            descent: f32::from(layout.descent),
+        }
+    }
+}
+
+pub(crate) struct DrawingLayout {
+    drawing: Drawing,
+    measure: WindowTextMeasure,
+    text_color: Hsla,
+}
+
+impl Element for DrawingElement {
+    type RequestLayoutState = DrawingLayout;
+    type PrepaintState = ();
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let text_style = window.text_style();
+        let font_size = text_style.font_size.to_pixels(window.rem_size());
+        let measure = WindowTextMeasure {
+            text_system: window.text_system().clone(),
+            font: text_style.font(),
+        };
+        let drawing = self
+            .build
+            .take()
+            .map(|build| build(&measure, f32::from(font_size)))
+            .unwrap_or_default();
+
+        let style = Style {
+            size: size(
+                px(drawing.size.width).into(),
+                px(drawing.size.height).into(),
+            ),
+            flex_shrink: 0.,
+            ..Style::default()
+        };
+        let layout_id = window.request_layout(style, [], cx);
+        (
+            layout_id,
+            DrawingLayout {
+                drawing,
+                measure,
+                text_color: text_style.color,
+            },
+        )
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        _bounds: Bounds<Pixels>,
+        _layout: &mut Self::RequestLayoutState,
+        _window: &mut Window,
+        _cx: &mut App,
+    ) -> Self::PrepaintState {
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        layout: &mut Self::RequestLayoutState,
+        _prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let colors = cx.theme().colors();
+        let resolve = |ink: Ink| match ink {
+            Ink::Text => layout.text_color,
+            Ink::MutedText => colors.text_muted,
+            Ink::Line => colors.text_muted,
+            Ink::Surface => colors.element_background,
+            Ink::Note => cx.theme().status().warning_background,
+            Ink::Background => colors.editor_background,
+        };
+        let to_window = |p: Point<f32>| point(bounds.origin.x + px(p.x), bounds.origin.y + px(p.y));
+
+        for primitive in &layout.drawing.primitives {
+            match primitive {
+                Primitive::Text {
+                    origin,
+                    text,
+                    font_size,
+                    variant,
+                    ink,
+                } => {
+                    let run = TextRun {
+                        len: text.len(),
+                        font: layout.measure.font(*variant),
+                        color: resolve(*ink),
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    };
+                    let line =
+                        window
+                            .text_system()
+                            .shape_line(text.clone(), px(*font_size), &[run], None);
+                    let line_height = line.ascent + line.descent;
+                    let mut origin = to_window(*origin);
+                    origin.y -= line.ascent;
+                    line.paint(origin, line_height, window, cx).ok();
+                }
+                Primitive::Rect {
+                    bounds: rect,
+                    corner_radius,
+                    fill,
+                    stroke,
+                    dashed,
+                } => {
+                    window.paint_quad(quad(
+                        Bounds::new(
+                            to_window(rect.origin),
+                            size(px(rect.size.width), px(rect.size.height)),
+                        ),
+                        px(*corner_radius),
+                        fill.map(resolve).unwrap_or_default(),
+                        px(if stroke.is_some() { 1. } else { 0. }),
+                        stroke.map(resolve).unwrap_or_default(),
+                        if *dashed {
+                            BorderStyle::Dashed
+                        } else {
+                            BorderStyle::Solid
+                        },
+                    ));
+                }
+                Primitive::Polygon {
+                    points,
+                    fill,
+                    stroke,
+                } => {
+                    let points = points.iter().copied().map(to_window).collect::<Vec<_>>();
+                    if let Some(fill) = fill {
+                        let mut builder = PathBuilder::fill();
+                        builder.add_polygon(&points, true);
+                        if let Ok(path) = builder.build() {
+                            window.paint_path(path, resolve(*fill));
+                        }
+                    }
+                    if let Some(stroke) = stroke {
+                        let mut builder = PathBuilder::stroke(px(1.));
+                        builder.add_polygon(&points, true);
+                        if let Ok(path) = builder.build() {
+                            window.paint_path(path, resolve(*stroke));
+                        }
+                    }
+                }
+                Primitive::Polyline {
+                    points,
+                    width,
+                    ink,
+                    dashed,
+                } => {
+                    let mut builder = PathBuilder::stroke(px(*width));
+                    if *dashed {
+                        builder = builder.dash_array(&[px(4.), px(3.)]);
+                    }
+                    let mut points = points.iter().copied().map(to_window);
+                    let Some(start) = points.next() else {
+                        continue;
+                    };
+                    builder.move_to(start);
+                    for point in points {
+                        builder.line_to(point);
+                    }
+                    if let Ok(path) = builder.build() {
+                        window.paint_path(path, resolve(*ink));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl IntoElement for DrawingElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+#[cfg(test)]
+pub(crate) struct FixedWidthMeasure;
+
+#[cfg(test)]
+impl MeasureText for FixedWidthMeasure {
+    fn measure(&self, text: &str, font_size: f32, _variant: TextVariant) -> TextMetrics {
+        TextMetrics {
+            width: text.chars().count() as f32 * font_size * 0.5,
+            ascent: font_size * 0.8,
+            descent: font_size * 0.2,
+        }
+    }
+}
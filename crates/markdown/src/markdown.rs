@@ -1,3 +1,6 @@
+pub mod drawing;
+pub mod math;
+pub mod mermaid;
 pub mod parser;
 mod path_range;
 
@@ -27,8 +30,13 @@ use gpui::{
     TextLayout, TextRun, TextStyle, TextStyleRefinement, actions, img, point, quad,
 };
 use language::{Language, LanguageRegistry, Rope};
+use math::MathNode;
+use mermaid::MermaidDiagram;
 use parser::CodeBlockMetadata;
-use parser::{MarkdownEvent, MarkdownTag, MarkdownTagEnd, parse_links_only, parse_markdown};
+use parser::{
+    MarkdownEvent, MarkdownTag, MarkdownTagEnd, parse_links_only, parse_markdown,
+    parse_markdown_with_math,
+};
 use pulldown_cmark::Alignment;
 use sum_tree::TreeMap;
 use theme::SyntaxTheme;
@@ -114,6 +122,7 @@ pub struct Markdown {
 
 struct Options {
     parse_links_only: bool,
+    parse_math: bool,
 }
 
 pub enum CodeBlockRenderer {
@@ -160,6 +169,44 @@ impl Markdown {
         language_registry: Option<Arc<LanguageRegistry>>,
         fallback_code_block_language: Option<LanguageName>,
         cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new_with_options(
+            source,
+            language_registry,
+            fallback_code_block_language,
+            Options {
+                parse_links_only: false,
+                parse_math: false,
+            },
+            cx,
+        )
+    }
+
+    /// Like [`Markdown::new`], but also renders `$...$` and `$$...$$` as LaTeX math.
+    pub fn new_with_math(
+        source: SharedString,
+        language_registry: Option<Arc<LanguageRegistry>>,
+        fallback_code_block_language: Option<LanguageName>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self::new_with_options(
+            source,
+            language_registry,
+            fallback_code_block_language,
+            Options {
+                parse_links_only: false,
+                parse_math: true,
+            },
+            cx,
+        )
+    }
+
+    fn new_with_options(
+        source: SharedString,
+        language_registry: Option<Arc<LanguageRegistry>>,
+        fallback_code_block_language: Option<LanguageName>,
+        options: Options,
+        cx: &mut Context<Self>,
     ) -> Self {
         let focus_handle = cx.focus_handle();
         let mut this = Self {
@@ -174,9 +221,7 @@ impl Markdown {
             focus_handle,
             language_registry,
             fallback_code_block_language,
-            options: Options {
-                parse_links_only: false,
-            },
+            options,
             copied_code_blocks: HashSet::default(),
             code_block_scroll_handles: HashMap::default(),
         };
@@ -200,6 +245,7 @@ impl Markdown {
             fallback_code_block_language: None,
             options: Options {
                 parse_links_only: true,
+                parse_math: false,
             },
             copied_code_blocks: HashSet::default(),
             code_block_scroll_handles: HashMap::default(),
@@ -319,6 +365,7 @@ impl Markdown {
     fn start_background_parse(&self, cx: &Context<Self>) -> Task<()> {
         let source = self.source.clone();
         let should_parse_links_only = self.options.parse_links_only;
+        let should_parse_math = self.options.parse_math;
         let language_registry = self.language_registry.clone();
         let fallback = self.fallback_code_block_language.clone();
 
@@ -330,12 +377,18 @@ impl Markdown {
                         source,
                         languages_by_name: TreeMap::default(),
                         languages_by_path: TreeMap::default(),
+                        math: TreeMap::default(),
+                        mermaid_diagrams: TreeMap::default(),
                     },
                     Default::default(),
                 );
             }
 
-            let (events, language_names, paths) = parse_markdown(&source);
+            let (events, language_names, paths) = if should_parse_math {
+                parse_markdown_with_math(&source)
+            } else {
+                parse_markdown(&source)
+            };
             let mut images_by_source_offset = HashMap::default();
             let mut languages_by_name = TreeMap::default();
             let mut languages_by_path = TreeMap::default();
@@ -363,7 +416,34 @@ impl Markdown {
                 }
             }
 
+            let mut math = TreeMap::default();
+            let mut mermaid_diagrams = TreeMap::default();
             for (range, event) in &events {
+                match event {
+                    MarkdownEvent::InlineMath | MarkdownEvent::DisplayMath => {
+                        math.insert(
+                            range.start,
+                            Arc::new(math::parse_math(&source[range.clone()])),
+                        );
+                    }
+                    // Diagrams are only rendered once their block is complete, so
+                    // that streamed responses don't flicker between errors.
+                    MarkdownEvent::Start(MarkdownTag::CodeBlock {
+                        kind: CodeBlockKind::FencedLang(language),
+                        metadata,
+                    }) if language.as_ref() == "mermaid"
+                        && is_closed_code_block(&source[range.clone()]) =>
+                    {
+                        if let Some(diagram) =
+                            mermaid::parse_mermaid(&source[metadata.content_range.clone()])
+                                .log_with_level(Level::Debug)
+                        {
+                            mermaid_diagrams.insert(range.start, Arc::new(diagram));
+                        }
+                    }
+                    _ => {}
+                }
+
                 if let MarkdownEvent::Start(MarkdownTag::Image { dest_url, .. }) = event
                     && let Some(data_url) = dest_url.strip_prefix("data:")
                 {
@@ -394,6 +474,8 @@ impl Markdown {
                     events: Arc::from(events),
                     languages_by_name,
                     languages_by_path,
+                    math,
+                    mermaid_diagrams,
                 },
                 images_by_source_offset,
             )
@@ -458,6 +540,10 @@ pub struct ParsedMarkdown {
     pub events: Arc<[(Range<usize>, MarkdownEvent)]>,
     pub languages_by_name: TreeMap<SharedString, Arc<Language>>,
     pub languages_by_path: TreeMap<Arc<str>, Arc<Language>>,
+    /// Parsed math expressions, keyed by the start of their source.
+    pub math: TreeMap<usize, Arc<MathNode>>,
+    /// Mermaid diagrams, keyed by the start of their code block.
+    pub mermaid_diagrams: TreeMap<usize, Arc<MermaidDiagram>>,
 }
 
 impl ParsedMarkdown {
@@ -796,6 +882,7 @@ impl Element for MarkdownElement {
         let mut code_block_ids = HashSet::default();
 
         let mut current_img_block_range: Option<Range<usize>> = None;
+        let mut current_diagram_range: Option<Range<usize>> = None;
         for (index, (range, event)) in parsed_markdown.events.iter().enumerate() {
             // Skip alt text for images that rendered
             if let Some(current_img_block_range) = &current_img_block_range
@@ -803,6 +890,12 @@ impl Element for MarkdownElement {
             {
                 continue;
             }
+            // Skip the source of diagrams that rendered
+            if let Some(current_diagram_range) = &current_diagram_range
+                && current_diagram_range.end > range.end
+            {
+                continue;
+            }
 
             match event {
                 MarkdownEvent::Start(tag) => {
@@ -856,6 +949,21 @@ impl Element for MarkdownElement {
                                 markdown_end,
                             );
                         }
+                        MarkdownTag::CodeBlock { .. }
+                            if parsed_markdown.mermaid_diagrams.get(&range.start).is_some() =>
+                        {
+                            let diagram = parsed_markdown.mermaid_diagrams.get(&range.start);
+                            current_diagram_range = Some(range.clone());
+                            builder.push_div(
+                                div()
+                                    .id(("mermaid-diagram", range.start))
+                                    .mb_2()
+                                    .overflow_x_scroll()
+                                    .children(diagram.cloned().map(mermaid::render_mermaid)),
+                                range,
+                                markdown_end,
+                            );
+                        }
                         MarkdownTag::CodeBlock { kind, .. } => {
                             let language = match kind {
                                 CodeBlockKind::Fenced => None,
@@ -1086,6 +1194,10 @@ impl Element for MarkdownElement {
                         builder.pop_text_style();
                         builder.pop_div()
                     }
+                    MarkdownTagEnd::CodeBlock if current_diagram_range.is_some() => {
+                        current_diagram_range = None;
+                        builder.pop_div();
+                    }
                     MarkdownTagEnd::CodeBlock => {
                         builder.trim_trailing_newline();
 
@@ -1246,6 +1358,32 @@ impl Element for MarkdownElement {
                 MarkdownEvent::TaskListMarker(_) => {
                     // handled inside the `MarkdownTag::Item` case
                 }
+                MarkdownEvent::InlineMath => {
+                    if let Some(node) = parsed_markdown.math.get(&range.start) {
+                        builder.push_text_style(TextStyleRefinement {
+                            font_style: Some(FontStyle::Italic),
+                            ..Default::default()
+                        });
+                        builder.push_text(&node.to_unicode(), range.clone());
+                        builder.pop_text_style();
+                    }
+                }
+                MarkdownEvent::DisplayMath => {
+                    if let Some(node) = parsed_markdown.math.get(&range.start) {
+                        let node = node.clone();
+                        builder.modify_current_div(|el| {
+                            el.child(
+                                div()
+                                    .id(("display-math", range.start))
+                                    .flex()
+                                    .justify_center()
+                                    .py_1()
+                                    .overflow_x_scroll()
+                                    .child(math::render_math(node)),
+                            )
+                        });
+                    }
+                }
                 _ => log::debug!("unsupported markdown event {:?}", event),
             }
         }
@@ -1353,6 +1491,22 @@ fn apply_heading_style(
     heading
 }
 
+/// Whether a fenced code block's source ends with its closing fence, which may use
+/// backticks or tildes.
+fn is_closed_code_block(source: &str) -> bool {
+    let source = source.trim_end();
+    let opening = source.trim_start();
+    let Some(fence_char) = opening.chars().next().filter(|ch| matches!(ch, '`' | '~')) else {
+        return false;
+    };
+    let fence_len = opening.chars().take_while(|ch| *ch == fence_char).count();
+    let Some((_, last_line)) = source.rsplit_once('\n') else {
+        return false;
+    };
+    let last_line = last_line.trim();
+    last_line.len() >= fence_len && last_line.chars().all(|ch| ch == fence_char)
+}
+
 fn render_copy_code_block_button(
     id: usize,
     code: String,
@@ -1956,6 +2110,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_is_closed_code_block() {
+        assert!(is_closed_code_block("```mermaid\ngraph TD\n```\n"));
+        assert!(is_closed_code_block("~~~mermaid\ngraph TD\n~~~~"));
+        assert!(!is_closed_code_block("~~~mermaid\ngraph TD\n```"));
+        assert!(!is_closed_code_block("````mermaid\ngraph TD\n```"));
+        assert!(!is_closed_code_block("```mermaid\ngraph TD"));
+    }
+
     #[track_caller]
     fn assert_mappings(rendered: &RenderedText, expected: Vec<Vec<(usize, usize)>>) {
         assert_eq!(rendered.lines.len(), expected.len(), "line count mismatch");
@@ -0,0 +1,977 @@
+//! Rendering of the LaTeX math subset commonly found in markdown documents.
+//!
+//! Inline math is converted to Unicode text so that it flows with the
+//! surrounding paragraph, while display math is laid out as a two-dimensional
+//! [`Drawing`] with proper fractions, radicals, scripts and matrices.
+
+mod layout;
+mod symbols;
+
+use std::sync::Arc;
+
+use gpui::IntoElement;
+
+use crate::drawing::{Drawing, DrawingElement, MeasureText};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AtomClass {
+    Ordinary,
+    /// An upright function name like `sin`.
+    Function,
+    LargeOperator,
+    Binary,
+    Relation,
+    Open,
+    Close,
+    Punctuation,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MathVariant {
+    /// Single letters are italic, everything else is upright.
+    #[default]
+    Normal,
+    Upright,
+    Bold,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Accent {
+    Hat,
+    Bar,
+    Vec,
+    Dot,
+    DoubleDot,
+    Tilde,
+    Underline,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableKind {
+    /// A matrix, whose cells are centered.
+    Matrix,
+    /// The `cases` environment, whose cells are left-aligned.
+    Cases,
+    /// The `aligned` environment, whose columns alternate between right and left alignment.
+    Aligned,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MathNode {
+    Row(Vec<MathNode>),
+    Atom {
+        text: String,
+        class: AtomClass,
+        variant: MathVariant,
+    },
+    Text(String),
+    Scripts {
+        base: Box<MathNode>,
+        sub: Option<Box<MathNode>>,
+        sup: Option<Box<MathNode>>,
+    },
+    Fraction {
+        numerator: Box<MathNode>,
+        denominator: Box<MathNode>,
+        /// Whether a fraction bar is drawn, which isn't the case for binomials.
+        bar: bool,
+    },
+    Root {
+        index: Option<Box<MathNode>>,
+        radicand: Box<MathNode>,
+    },
+    /// A body surrounded by delimiters that grow to its height. An empty delimiter is omitted.
+    Delimited {
+        open: String,
+        close: String,
+        body: Box<MathNode>,
+    },
+    Accent {
+        accent: Accent,
+        body: Box<MathNode>,
+    },
+    Table {
+        kind: TableKind,
+        rows: Vec<Vec<MathNode>>,
+    },
+    /// Horizontal space, in ems.
+    Space(f32),
+}
+
+/// Parses a LaTeX math expression. Parsing never fails: unknown commands are
+/// rendered verbatim and unbalanced groups are closed implicitly.
+pub fn parse_math(source: &str) -> MathNode {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        position: 0,
+        optional_argument_depth: 0,
+    };
+    let rows = parser.parse_table_body();
+    if let [row] = rows.as_slice()
+        && let [cell] = row.as_slice()
+    {
+        unwrap_row(cell.clone())
+    } else {
+        MathNode::Table {
+            kind: TableKind::Aligned,
+            rows,
+        }
+    }
+}
+
+/// Renders a parsed math expression as display math.
+pub fn render_math(node: Arc<MathNode>) -> impl IntoElement {
+    DrawingElement::new(move |measure, font_size| layout_math(&node, measure, font_size))
+}
+
+/// Lays out a parsed math expression as display math, for output other than
+/// the window, such as exported documents.
+pub fn layout_math(node: &MathNode, measure: &dyn MeasureText, font_size: f32) -> Drawing {
+    layout::layout_display_math(node, measure, font_size)
+}
+
+#[cfg(test)]
+fn layout_for_test(node: &MathNode) -> Drawing {
+    layout_math(node, &crate::drawing::FixedWidthMeasure, 16.)
+}
+
+#[derive(Debug, PartialEq)]
+enum Stop {
+    End,
+    CloseGroup,
+    CloseOptional,
+    NextCell,
+    NextRow,
+    Right,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    /// How many `\sqrt[...]` indices are being parsed, in which `]` ends the row.
+    optional_argument_depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Reads the name of a command after its backslash. Commands are either a
+    /// run of letters or a single other character.
+    fn read_command_name(&mut self) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.position += 1;
+        }
+        if self.position == start {
+            self.next().map(String::from).unwrap_or_default()
+        } else {
+            let name = self.chars[start..self.position].iter().collect();
+            // A star after environment-like commands (e.g. `\operatorname*`) is ignored.
+            if self.peek() == Some('*') && self.chars.get(self.position + 1) == Some(&'{') {
+                self.position += 1;
+            }
+            name
+        }
+    }
+
+    /// Reads a group's raw contents without interpreting them, as used by `\text`.
+    fn read_raw_group(&mut self) -> String {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            return self.next().map(String::from).unwrap_or_default();
+        }
+        self.position += 1;
+        let mut depth = 1;
+        let mut text = String::new();
+        while let Some(c) = self.next() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                '\\' => {
+                    if let Some(escaped) = self.next() {
+                        text.push(escaped);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            text.push(c);
+        }
+        text
+    }
+
+    fn parse_table_body(&mut self) -> Vec<Vec<MathNode>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        loop {
+            let (cell, stop) = self.parse_row();
+            row.push(cell);
+            match stop {
+                Stop::NextCell => {}
+                Stop::NextRow => rows.push(std::mem::take(&mut row)),
+                _ => break,
+            }
+        }
+        let is_trailing_empty_row =
+            matches!(row.as_slice(), [MathNode::Row(children)] if children.is_empty());
+        if !is_trailing_empty_row || rows.is_empty() {
+            rows.push(row);
+        }
+        rows
+    }
+
+    fn parse_row(&mut self) -> (MathNode, Stop) {
+        let mut children = Vec::new();
+        let stop = loop {
+            self.skip_whitespace();
+            let Some(c) = self.peek() else {
+                break Stop::End;
+            };
+            match c {
+                '}' => {
+                    self.position += 1;
+                    break Stop::CloseGroup;
+                }
+                ']' if self.optional_argument_depth > 0 => {
+                    self.position += 1;
+                    break Stop::CloseOptional;
+                }
+                '&' => {
+                    self.position += 1;
+                    break Stop::NextCell;
+                }
+                '\\' => {
+                    let start = self.position;
+                    self.position += 1;
+                    let name = self.read_command_name();
+                    match name.as_str() {
+                        "\\" | "cr" => break Stop::NextRow,
+                        "right" => break Stop::Right,
+                        "end" => {
+                            self.read_raw_group();
+                            break Stop::End;
+                        }
+                        _ => {
+                            self.position = start;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            if let Some(primary) = self.parse_primary() {
+                let node = self.parse_scripts(primary);
+                children.push(node);
+            }
+        };
+        (MathNode::Row(children), stop)
+    }
+
+    fn parse_optional_argument(&mut self) -> Option<MathNode> {
+        self.skip_whitespace();
+        if self.peek() != Some('[') {
+            return None;
+        }
+        self.position += 1;
+        self.optional_argument_depth += 1;
+        let (node, _) = self.parse_row();
+        self.optional_argument_depth -= 1;
+        Some(unwrap_row(node))
+    }
+
+    fn parse_scripts(&mut self, base: MathNode) -> MathNode {
+        let mut sub = None;
+        let mut sup: Option<MathNode> = None;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('^') => {
+                    self.position += 1;
+                    let argument = self.parse_argument();
+                    sup = Some(match sup.take() {
+                        Some(MathNode::Row(mut primes)) => {
+                            primes.push(argument);
+                            MathNode::Row(primes)
+                        }
+                        _ => argument,
+                    });
+                }
+                Some('_') => {
+                    self.position += 1;
+                    sub = Some(self.parse_argument());
+                }
+                Some('\'') => {
+                    self.position += 1;
+                    let prime = atom("′", AtomClass::Ordinary);
+                    sup = Some(match sup.take() {
+                        Some(MathNode::Row(mut primes)) => {
+                            primes.push(prime);
+                            MathNode::Row(primes)
+                        }
+                        Some(other) => MathNode::Row(vec![other, prime]),
+                        None => MathNode::Row(vec![prime]),
+                    });
+                }
+                _ => break,
+            }
+        }
+        if sub.is_none() && sup.is_none() {
+            base
+        } else {
+            MathNode::Scripts {
+                base: Box::new(base),
+                sub: sub.map(Box::new),
+                sup: sup.map(Box::new),
+            }
+        }
+    }
+
+    /// Parses the argument of a command or script: a group, a command or a single character.
+    fn parse_argument(&mut self) -> MathNode {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.position += 1;
+                let (row, _) = self.parse_row();
+                unwrap_row(row)
+            }
+            Some('\\') => self.parse_primary().unwrap_or(MathNode::Row(Vec::new())),
+            Some(c) => {
+                self.position += 1;
+                char_atom(c)
+            }
+            None => MathNode::Row(Vec::new()),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Option<MathNode> {
+        let c = self.next()?;
+        match c {
+            '{' => {
+                let (row, _) = self.parse_row();
+                Some(row)
+            }
+            '\\' => self.parse_command(),
+            '~' => Some(MathNode::Space(0.25)),
+            '0'..='9' | '.'
+                if c.is_ascii_digit() || self.peek().is_some_and(|c| c.is_ascii_digit()) =>
+            {
+                let mut number = c.to_string();
+                while let Some(next) = self.peek() {
+                    let continues_number = next.is_ascii_digit()
+                        || (next == '.'
+                            && self
+                                .chars
+                                .get(self.position + 1)
+                                .is_some_and(|c| c.is_ascii_digit()));
+                    if !continues_number {
+                        break;
+                    }
+                    number.push(next);
+                    self.position += 1;
+                }
+                Some(atom(&number, AtomClass::Ordinary))
+            }
+            _ => Some(char_atom(c)),
+        }
+    }
+
+    fn parse_command(&mut self) -> Option<MathNode> {
+        let name = self.read_command_name();
+        if let Some((text, class)) = symbols::symbol(&name) {
+            return Some(atom(text, class));
+        }
+        if symbols::is_function(&name) {
+            return Some(atom(&name, AtomClass::Function));
+        }
+        if let Some(width) = symbols::space(&name) {
+            return Some(MathNode::Space(width));
+        }
+
+        Some(match name.as_str() {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => MathNode::Fraction {
+                numerator: Box::new(self.parse_argument()),
+                denominator: Box::new(self.parse_argument()),
+                bar: true,
+            },
+            "binom" | "dbinom" | "tbinom" => MathNode::Delimited {
+                open: "(".into(),
+                close: ")".into(),
+                body: Box::new(MathNode::Fraction {
+                    numerator: Box::new(self.parse_argument()),
+                    denominator: Box::new(self.parse_argument()),
+                    bar: false,
+                }),
+            },
+            "sqrt" => {
+                let index = self.parse_optional_argument().map(Box::new);
+                MathNode::Root {
+                    index,
+                    radicand: Box::new(self.parse_argument()),
+                }
+            }
+            "left" => {
+                let open = self.read_delimiter();
+                let (body, stop) = self.parse_row();
+                let close = if stop == Stop::Right {
+                    self.read_delimiter()
+                } else {
+                    String::new()
+                };
+                MathNode::Delimited {
+                    open,
+                    close,
+                    body: Box::new(body),
+                }
+            }
+            "big" | "Big" | "bigg" | "Bigg" | "bigl" | "Bigl" | "biggl" | "Biggl" | "bigr"
+            | "Bigr" | "biggr" | "Biggr" | "bigm" | "Bigm" => {
+                let delimiter = self.read_delimiter();
+                let class = if name.ends_with('l') {
+                    AtomClass::Open
+                } else if name.ends_with('r') {
+                    AtomClass::Close
+                } else {
+                    char_class(delimiter.chars().next().unwrap_or(' '))
+                };
+                MathNode::Atom {
+                    text: delimiter,
+                    class,
+                    variant: MathVariant::Upright,
+                }
+            }
+            "text" | "textrm" | "textnormal" | "textit" | "textbf" | "textsf" | "texttt"
+            | "mbox" | "hbox" => MathNode::Text(self.read_raw_group()),
+            "operatorname" => MathNode::Atom {
+                text: self.read_raw_group().trim().to_string(),
+                class: AtomClass::Function,
+                variant: MathVariant::Upright,
+            },
+            "mathrm" | "mathsf" | "mathtt" | "mathit" | "rm" => {
+                with_variant(self.parse_argument(), MathVariant::Upright)
+            }
+            "mathbf" | "boldsymbol" | "bm" | "bf" => {
+                with_variant(self.parse_argument(), MathVariant::Bold)
+            }
+            "mathbb" => map_chars(self.parse_argument(), symbols::double_struck),
+            "mathcal" | "mathscr" => map_chars(self.parse_argument(), symbols::calligraphic),
+            "mathfrak" => map_chars(self.parse_argument(), symbols::fraktur),
+            "hat" | "widehat" => self.accent(Accent::Hat),
+            "bar" | "overline" => self.accent(Accent::Bar),
+            "vec" | "overrightarrow" => self.accent(Accent::Vec),
+            "dot" => self.accent(Accent::Dot),
+            "ddot" => self.accent(Accent::DoubleDot),
+            "tilde" | "widetilde" => self.accent(Accent::Tilde),
+            "underline" => self.accent(Accent::Underline),
+            "not" => {
+                let negated = self.parse_argument();
+                match negated {
+                    MathNode::Atom {
+                        mut text,
+                        class,
+                        variant,
+                    } => {
+                        text.push('\u{338}');
+                        MathNode::Atom {
+                            text,
+                            class,
+                            variant,
+                        }
+                    }
+                    other => other,
+                }
+            }
+            "pmod" => MathNode::Row(vec![
+                MathNode::Space(0.5),
+                atom("(", AtomClass::Open),
+                atom("mod", AtomClass::Function),
+                MathNode::Space(1. / 3.),
+                self.parse_argument(),
+                atom(")", AtomClass::Close),
+            ]),
+            "color" => {
+                self.read_raw_group();
+                MathNode::Row(Vec::new())
+            }
+            "textcolor" | "colorbox" => {
+                self.read_raw_group();
+                self.parse_argument()
+            }
+            "label" | "tag" => {
+                self.read_raw_group();
+                MathNode::Row(Vec::new())
+            }
+            "limits" | "nolimits" | "displaystyle" | "textstyle" | "scriptstyle"
+            | "scriptscriptstyle" | "nonumber" | "notag" | "mathstrut" | "strut" | "middle" => {
+                MathNode::Row(Vec::new())
+            }
+            "begin" => self.parse_environment(),
+            _ => MathNode::Atom {
+                text: format!("\\{name}"),
+                class: AtomClass::Ordinary,
+                variant: MathVariant::Upright,
+            },
+        })
+    }
+
+    fn accent(&mut self, accent: Accent) -> MathNode {
+        MathNode::Accent {
+            accent,
+            body: Box::new(self.parse_argument()),
+        }
+    }
+
+    fn read_delimiter(&mut self) -> String {
+        self.skip_whitespace();
+        match self.next() {
+            Some('.') | None => String::new(),
+            Some('\\') => {
+                let name = self.read_command_name();
+                symbols::symbol(&name)
+                    .map(|(text, _)| text.to_string())
+                    .unwrap_or_default()
+            }
+            Some(c) => c.to_string(),
+        }
+    }
+
+    fn parse_environment(&mut self) -> MathNode {
+        let name = self.read_raw_group();
+        let name = name.trim_end_matches('*');
+        if name == "array" {
+            // Column specifications don't affect rendering.
+            self.read_raw_group();
+        }
+        let rows = self.parse_table_body();
+        let (kind, open, close) = match name {
+            "matrix" | "smallmatrix" => (TableKind::Matrix, "", ""),
+            "pmatrix" => (TableKind::Matrix, "(", ")"),
+            "bmatrix" => (TableKind::Matrix, "[", "]"),
+            "Bmatrix" => (TableKind::Matrix, "{", "}"),
+            "vmatrix" => (TableKind::Matrix, "|", "|"),
+            "Vmatrix" => (TableKind::Matrix, "‖", "‖"),
+            "cases" => (TableKind::Cases, "{", ""),
+            "array" => (TableKind::Matrix, "", ""),
+            _ => (TableKind::Aligned, "", ""),
+        };
+        let table = MathNode::Table { kind, rows };
+        if open.is_empty() && close.is_empty() {
+            table
+        } else {
+            MathNode::Delimited {
+                open: open.into(),
+                close: close.into(),
+                body: Box::new(table),
+            }
+        }
+    }
+}
+
+fn atom(text: &str, class: AtomClass) -> MathNode {
+    MathNode::Atom {
+        text: text.to_string(),
+        class,
+        variant: MathVariant::Normal,
+    }
+}
+
+fn char_class(c: char) -> AtomClass {
+    match c {
+        '+' | '-' | '*' | '±' | '×' | '÷' | '⋅' => AtomClass::Binary,
+        '=' | '<' | '>' | ':' | '≤' | '≥' | '≠' | '→' => AtomClass::Relation,
+        '(' | '[' => AtomClass::Open,
+        ')' | ']' => AtomClass::Close,
+        ',' | ';' => AtomClass::Punctuation,
+        _ => AtomClass::Ordinary,
+    }
+}
+
+fn char_atom(c: char) -> MathNode {
+    let text = match c {
+        '-' => '−',
+        '*' => '∗',
+        c => c,
+    };
+    atom(&text.to_string(), char_class(c))
+}
+
+fn unwrap_row(node: MathNode) -> MathNode {
+    match node {
+        MathNode::Row(mut children) if children.len() == 1 => children.pop().unwrap(),
+        other => other,
+    }
+}
+
+fn with_variant(node: MathNode, variant: MathVariant) -> MathNode {
+    map_atoms(node, &mut |text, class, _| MathNode::Atom {
+        text,
+        class,
+        variant,
+    })
+}
+
+fn map_chars(node: MathNode, map: fn(char) -> char) -> MathNode {
+    map_atoms(node, &mut |text, class, variant| MathNode::Atom {
+        text: text.chars().map(map).collect(),
+        class,
+        variant,
+    })
+}
+
+fn map_atoms(
+    node: MathNode,
+    f: &mut dyn FnMut(String, AtomClass, MathVariant) -> MathNode,
+) -> MathNode {
+    match node {
+        MathNode::Atom {
+            text,
+            class,
+            variant,
+        } => f(text, class, variant),
+        MathNode::Row(children) => MathNode::Row(
+            children
+                .into_iter()
+                .map(|child| map_atoms(child, f))
+                .collect(),
+        ),
+        MathNode::Scripts { base, sub, sup } => MathNode::Scripts {
+            base: Box::new(map_atoms(*base, f)),
+            sub,
+            sup,
+        },
+        other => other,
+    }
+}
+
+impl MathNode {
+    /// Converts the expression to a single line of Unicode text, for inline math.
+    pub fn to_unicode(&self) -> String {
+        let mut text = String::new();
+        self.write_unicode(&mut text);
+        text.trim().to_string()
+    }
+
+    fn write_unicode(&self, out: &mut String) {
+        match self {
+            MathNode::Row(children) => {
+                let mut previous_class = None;
+                for child in children {
+                    let class = child.class();
+                    let follows_function = matches!(
+                        previous_class,
+                        Some(AtomClass::Function | AtomClass::LargeOperator)
+                    ) && matches!(
+                        class,
+                        Some(AtomClass::Ordinary | AtomClass::Function | AtomClass::LargeOperator)
+                    );
+                    if follows_function && !out.ends_with(' ') {
+                        out.push(' ');
+                    }
+                    let spaced = matches!(class, Some(AtomClass::Relation))
+                        || (matches!(class, Some(AtomClass::Binary))
+                            && previous_class.is_some_and(|previous| {
+                                !matches!(
+                                    previous,
+                                    AtomClass::Binary
+                                        | AtomClass::Relation
+                                        | AtomClass::Open
+                                        | AtomClass::Punctuation
+                                )
+                            }));
+                    if spaced {
+                        out.push(' ');
+                        child.write_unicode(out);
+                        out.push(' ');
+                    } else {
+                        if matches!(class, Some(AtomClass::Function | AtomClass::LargeOperator))
+                            && out.ends_with(|c: char| c.is_alphanumeric())
+                        {
+                            out.push(' ');
+                        }
+                        child.write_unicode(out);
+                        if matches!(class, Some(AtomClass::Punctuation)) {
+                            out.push(' ');
+                        }
+                    }
+                    if class.is_some() {
+                        previous_class = class;
+                    }
+                }
+            }
+            MathNode::Atom { text, .. } => out.push_str(text),
+            MathNode::Text(text) => out.push_str(text),
+            MathNode::Scripts { base, sub, sup } => {
+                base.write_unicode(out);
+                if let Some(sub) = sub {
+                    write_script(out, sub, '_', symbols::subscript);
+                }
+                if let Some(sup) = sup {
+                    write_script(out, sup, '^', symbols::superscript);
+                }
+            }
+            MathNode::Fraction {
+                numerator,
+                denominator,
+                bar,
+            } => {
+                write_operand(out, numerator);
+                out.push_str(if *bar { "/" } else { " over " });
+                write_operand(out, denominator);
+            }
+            MathNode::Root { index, radicand } => {
+                match index.as_ref().map(|index| index.to_unicode()).as_deref() {
+                    None => out.push('√'),
+                    Some("3") => out.push('∛'),
+                    Some("4") => out.push('∜'),
+                    Some(index) => {
+                        write_script(
+                            out,
+                            &MathNode::Text(index.to_string()),
+                            '^',
+                            symbols::superscript,
+                        );
+                        out.push('√');
+                    }
+                }
+                write_operand(out, radicand);
+            }
+            MathNode::Delimited { open, close, body } => {
+                out.push_str(open);
+                body.write_unicode(out);
+                out.push_str(close);
+            }
+            MathNode::Accent { accent, body } => {
+                let mark = match accent {
+                    Accent::Hat => '\u{302}',
+                    Accent::Bar => '\u{305}',
+                    Accent::Vec => '\u{20d7}',
+                    Accent::Dot => '\u{307}',
+                    Accent::DoubleDot => '\u{308}',
+                    Accent::Tilde => '\u{303}',
+                    Accent::Underline => '\u{332}',
+                };
+                let body = body.to_unicode();
+                if matches!(accent, Accent::Bar | Accent::Underline) {
+                    for c in body.chars() {
+                        out.push(c);
+                        out.push(mark);
+                    }
+                } else {
+                    out.push_str(&body);
+                    out.push(mark);
+                }
+            }
+            MathNode::Table { rows, .. } => {
+                let rows = rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|cell| cell.to_unicode())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<_>>();
+                out.push_str(&rows.join("; "));
+            }
+            MathNode::Space(width) => {
+                if *width > 0. {
+                    out.push(' ');
+                }
+            }
+        }
+    }
+
+    /// The class used for spacing this node relative to its neighbors, or
+    /// `None` for nodes that don't take part in spacing.
+    fn class(&self) -> Option<AtomClass> {
+        match self {
+            MathNode::Atom { class, .. } => Some(*class),
+            MathNode::Scripts { base, .. } => base.class(),
+            MathNode::Space(_) => None,
+            MathNode::Row(children) if children.is_empty() => None,
+            _ => Some(AtomClass::Ordinary),
+        }
+    }
+}
+
+fn write_script(out: &mut String, script: &MathNode, marker: char, map: fn(char) -> Option<char>) {
+    let text = script.to_unicode();
+    let mapped = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(map)
+        .collect::<Option<String>>();
+    if let Some(mapped) = mapped {
+        out.push_str(&mapped);
+    } else if text.chars().count() == 1 {
+        out.push(marker);
+        out.push_str(&text);
+    } else {
+        out.push(marker);
+        out.push('(');
+        out.push_str(&text);
+        out.push(')');
+    }
+}
+
+fn write_operand(out: &mut String, operand: &MathNode) {
+    let text = operand.to_unicode();
+    let is_simple = !text.contains(' ')
+        && (text.chars().count() == 1
+            || text.chars().all(|c| c.is_alphanumeric() || c == '.')
+            || matches!(operand, MathNode::Delimited { .. }));
+    if is_simple {
+        out.push_str(&text);
+    } else {
+        out.push('(');
+        out.push_str(&text);
+        out.push(')');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::Primitive;
+
+    #[test]
+    fn test_parse_math() {
+        assert_eq!(
+            parse_math(r"\frac{a}{b}^2"),
+            MathNode::Scripts {
+                base: Box::new(MathNode::Fraction {
+                    numerator: Box::new(atom("a", AtomClass::Ordinary)),
+                    denominator: Box::new(atom("b", AtomClass::Ordinary)),
+                    bar: true,
+                }),
+                sub: None,
+                sup: Some(Box::new(atom("2", AtomClass::Ordinary))),
+            }
+        );
+
+        assert_eq!(
+            parse_math(r"\sqrt[3]{x}"),
+            MathNode::Root {
+                index: Some(Box::new(atom("3", AtomClass::Ordinary))),
+                radicand: Box::new(atom("x", AtomClass::Ordinary)),
+            }
+        );
+
+        let MathNode::Delimited { open, close, body } =
+            parse_math(r"\begin{pmatrix} a & b \\ c & d \end{pmatrix}")
+        else {
+            panic!("expected a delimited matrix");
+        };
+        assert_eq!((open.as_str(), close.as_str()), ("(", ")"));
+        let MathNode::Table { kind, rows } = *body else {
+            panic!("expected a table");
+        };
+        assert_eq!(kind, TableKind::Matrix);
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == 2));
+
+        assert_eq!(
+            parse_math(r"\left( x \right]"),
+            MathNode::Delimited {
+                open: "(".into(),
+                close: "]".into(),
+                body: Box::new(MathNode::Row(vec![atom("x", AtomClass::Ordinary)])),
+            }
+        );
+
+        // Unknown commands and unbalanced groups don't prevent rendering.
+        assert_eq!(parse_math(r"\foo{x").to_unicode(), r"\foox");
+    }
+
+    #[test]
+    fn test_to_unicode() {
+        let cases = [
+            (r"E = mc^2", "E = mc²"),
+            (r"x_{i+1} \leq \alpha", "xᵢ₊₁ ≤ α"),
+            (r"\frac{a+b}{2}", "(a + b)/2"),
+            (r"\sqrt{x^2 + y^2}", "√(x² + y²)"),
+            (r"\sum_{i=1}^{n} i", "∑ᵢ₌₁ⁿ i"),
+            (r"f'(x) = \sin x", "f′(x) = sin x"),
+            (r"e^{i\pi} + 1 = 0", "e^(iπ) + 1 = 0"),
+            (r"\mathbb{R}^n \to \mathbb{R}", "ℝⁿ → ℝ"),
+            (r"-x", "−x"),
+            (r"\begin{cases} 1 & x > 0 \\ 0 \end{cases}", "{1 x > 0; 0"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(parse_math(source).to_unicode(), expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn test_layout_math() {
+        let drawing = layout_for_test(&parse_math(r"\frac{1}{x}"));
+        assert_eq!(drawing.texts().collect::<Vec<_>>(), ["1", "x"]);
+
+        let glyph_origin = |text: &str| {
+            drawing
+                .primitives
+                .iter()
+                .find_map(|primitive| match primitive {
+                    Primitive::Text {
+                        origin, text: t, ..
+                    } if t.as_ref() == text => Some(*origin),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let bar = drawing
+            .primitives
+            .iter()
+            .find_map(|primitive| match primitive {
+                Primitive::Rect { bounds, .. } => Some(*bounds),
+                _ => None,
+            })
+            .expect("fraction bar");
+        assert!(glyph_origin("1").y < bar.origin.y);
+        assert!(glyph_origin("x").y > bar.origin.y + bar.size.height);
+        assert!(bar.origin.x >= 0. && bar.size.width <= drawing.size.width);
+
+        // Superscripts are smaller and raised.
+        let drawing = layout_for_test(&parse_math("x^2"));
+        let sizes = drawing
+            .primitives
+            .iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Text {
+                    origin, font_size, ..
+                } => Some((origin.y, *font_size)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(sizes.len(), 2);
+        assert!(sizes[1].0 < sizes[0].0);
+        assert!(sizes[1].1 < sizes[0].1);
+
+        // Tall bodies get drawn delimiters instead of glyphs.
+        let drawing = layout_for_test(&parse_math(r"\left(\frac{a}{b}\right)"));
+        let curves = drawing
+            .primitives
+            .iter()
+            .filter(|primitive| matches!(primitive, Primitive::Polyline { .. }))
+            .count();
+        assert_eq!(curves, 2);
+    }
+}
@@ -0,0 +1,738 @@
+use gpui::{Bounds, Point, point, size};
+
+use super::{Accent, AtomClass, MathNode, MathVariant, TableKind, symbols};
+use crate::drawing::{Drawing, Ink, MeasureText, Primitive, TextVariant, quadratic_curve};
+
+/// Space around display math, so that tall delimiters aren't clipped.
+const PADDING: f32 = 4.;
+
+pub(super) fn layout_display_math(
+    node: &MathNode,
+    measure: &dyn MeasureText,
+    font_size: f32,
+) -> Drawing {
+    let layout = MathLayout {
+        measure,
+        base_size: font_size,
+    };
+    let math_box = layout.node(
+        node,
+        Style {
+            size: font_size,
+            display: true,
+        },
+    );
+    let mut primitives = math_box.primitives;
+    for primitive in &mut primitives {
+        primitive.translate(point(PADDING, PADDING + math_box.ascent));
+    }
+    Drawing {
+        size: size(
+            math_box.width + 2. * PADDING,
+            math_box.ascent + math_box.descent + 2. * PADDING,
+        ),
+        primitives,
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Style {
+    size: f32,
+    /// Whether this is the top level of display math, where large operators
+    /// get limits and fractions keep their full size.
+    display: bool,
+}
+
+impl Style {
+    fn script(self, base_size: f32) -> Self {
+        Self {
+            size: (self.size * 0.7).max(base_size * 0.5),
+            display: false,
+        }
+    }
+
+    fn fraction(self, base_size: f32) -> Self {
+        if self.display {
+            Self {
+                size: self.size,
+                display: false,
+            }
+        } else {
+            self.script(base_size)
+        }
+    }
+
+    /// The height of the math axis above the baseline, on which fraction bars
+    /// and operators are centered.
+    fn axis(self) -> f32 {
+        self.size * 0.25
+    }
+
+    fn rule(self) -> f32 {
+        (self.size * 0.05).max(1.)
+    }
+}
+
+/// A laid out expression, whose primitives are positioned relative to the
+/// left end of its baseline.
+#[derive(Default)]
+struct MathBox {
+    width: f32,
+    ascent: f32,
+    descent: f32,
+    primitives: Vec<Primitive>,
+}
+
+impl MathBox {
+    /// Places another box with its baseline origin at `(x, shift)`, where a
+    /// positive shift moves it down.
+    fn append(&mut self, other: MathBox, x: f32, shift: f32) {
+        self.width = self.width.max(x + other.width);
+        self.ascent = self.ascent.max(other.ascent - shift);
+        self.descent = self.descent.max(other.descent + shift);
+        self.primitives
+            .extend(other.primitives.into_iter().map(|mut primitive| {
+                primitive.translate(point(x, shift));
+                primitive
+            }));
+    }
+
+    /// Returns this box moved to the center of a box of the given width.
+    fn with_x_centered(self, width: f32) -> MathBox {
+        let mut centered = MathBox::default();
+        let x = (width - self.width) / 2.;
+        centered.append(self, x, 0.);
+        centered
+    }
+
+    fn polyline(&mut self, points: Vec<Point<f32>>, width: f32) {
+        self.primitives.push(Primitive::Polyline {
+            points,
+            width,
+            ink: Ink::Text,
+            dashed: false,
+        });
+    }
+
+    fn rule(&mut self, origin: Point<f32>, width: f32, thickness: f32) {
+        self.primitives.push(Primitive::Rect {
+            bounds: Bounds::new(origin, size(width, thickness)),
+            corner_radius: 0.,
+            fill: Some(Ink::Text),
+            stroke: None,
+            dashed: false,
+        });
+    }
+}
+
+struct MathLayout<'a> {
+    measure: &'a dyn MeasureText,
+    base_size: f32,
+}
+
+impl MathLayout<'_> {
+    fn node(&self, node: &MathNode, style: Style) -> MathBox {
+        match node {
+            MathNode::Row(children) => self.row(children, style),
+            MathNode::Atom {
+                text,
+                class,
+                variant,
+            } => self.atom(text, *class, *variant, style),
+            MathNode::Text(text) => self.glyph(text, style.size, TextVariant::REGULAR),
+            MathNode::Scripts { base, sub, sup } => {
+                self.scripts(base, sub.as_deref(), sup.as_deref(), style)
+            }
+            MathNode::Fraction {
+                numerator,
+                denominator,
+                bar,
+            } => self.fraction(numerator, denominator, *bar, style),
+            MathNode::Root { index, radicand } => self.root(index.as_deref(), radicand, style),
+            MathNode::Delimited { open, close, body } => self.delimited(open, close, body, style),
+            MathNode::Accent { accent, body } => self.accent(*accent, body, style),
+            MathNode::Table { kind, rows } => self.table(*kind, rows, style),
+            MathNode::Space(width) => MathBox {
+                width: width * style.size,
+                ..MathBox::default()
+            },
+        }
+    }
+
+    fn glyph(&self, text: &str, font_size: f32, variant: TextVariant) -> MathBox {
+        let metrics = self.measure.measure(text, font_size, variant);
+        MathBox {
+            width: metrics.width,
+            ascent: metrics.ascent,
+            descent: metrics.descent,
+            primitives: vec![Primitive::Text {
+                origin: point(0., 0.),
+                text: text.to_string().into(),
+                font_size,
+                variant,
+                ink: Ink::Text,
+            }],
+        }
+    }
+
+    fn atom(&self, text: &str, class: AtomClass, variant: MathVariant, style: Style) -> MathBox {
+        let text_variant = match variant {
+            MathVariant::Bold => TextVariant::BOLD,
+            MathVariant::Upright => TextVariant::REGULAR,
+            MathVariant::Normal if class == AtomClass::Ordinary && is_letter(text) => {
+                TextVariant::ITALIC
+            }
+            MathVariant::Normal => TextVariant::REGULAR,
+        };
+        if class != AtomClass::LargeOperator {
+            return self.glyph(text, style.size, text_variant);
+        }
+
+        let font_size = style.size * if style.display { 1.6 } else { 1.2 };
+        let glyph = self.glyph(text, font_size, text_variant);
+        // Center the operator on the math axis.
+        let shift = (glyph.ascent - glyph.descent) / 2. - style.axis();
+        let mut operator = MathBox::default();
+        operator.append(glyph, 0., shift);
+        operator
+    }
+
+    fn row(&self, children: &[MathNode], style: Style) -> MathBox {
+        let mut row = MathBox::default();
+        let mut x = 0.;
+        let mut previous_class = None;
+        for child in children {
+            let class = match child.class() {
+                // A binary operator at the start of an expression is unary, like `-x`.
+                Some(AtomClass::Binary)
+                    if !previous_class.is_some_and(|previous| {
+                        !matches!(
+                            previous,
+                            AtomClass::Binary
+                                | AtomClass::Relation
+                                | AtomClass::Open
+                                | AtomClass::Punctuation
+                        )
+                    }) =>
+                {
+                    Some(AtomClass::Ordinary)
+                }
+                class => class,
+            };
+            if let (Some(previous), Some(class)) = (previous_class, class) {
+                x += self.spacing(previous, class, style);
+            }
+            let child = self.node(child, style);
+            let width = child.width;
+            row.append(child, x, 0.);
+            x += width;
+            if class.is_some() {
+                previous_class = class;
+            }
+        }
+        row.width = x.max(0.);
+        row
+    }
+
+    /// The space between adjacent atoms, following TeX's spacing table.
+    fn spacing(&self, previous: AtomClass, next: AtomClass, style: Style) -> f32 {
+        use AtomClass::*;
+        const THIN: f32 = 3. / 18.;
+        const MEDIUM: f32 = 4. / 18.;
+        const THICK: f32 = 5. / 18.;
+
+        if previous == Open || next == Close || next == Punctuation {
+            return 0.;
+        }
+        let space = if previous == Relation || next == Relation {
+            if previous == next { 0. } else { THICK }
+        } else if previous == Binary || next == Binary {
+            MEDIUM
+        } else if previous == Punctuation
+            || (matches!(previous, Function | LargeOperator)
+                && matches!(next, Ordinary | Function | LargeOperator))
+            || (previous == Ordinary && matches!(next, Function | LargeOperator))
+        {
+            THIN
+        } else {
+            0.
+        };
+        // Scripts only keep thin spaces.
+        let is_script = style.size < self.base_size * 0.95;
+        if is_script && space != THIN {
+            0.
+        } else {
+            space * style.size
+        }
+    }
+
+    fn scripts(
+        &self,
+        base: &MathNode,
+        sub: Option<&MathNode>,
+        sup: Option<&MathNode>,
+        style: Style,
+    ) -> MathBox {
+        let script_style = style.script(self.base_size);
+        let base_box = self.node(base, style);
+        let sub_box = sub.map(|sub| self.node(sub, script_style));
+        let sup_box = sup.map(|sup| self.node(sup, script_style));
+        let mut result = MathBox::default();
+
+        if style.display && has_limits(base) {
+            let gap = style.size * 0.1;
+            let width = [
+                Some(base_box.width),
+                sub_box.as_ref().map(|sub| sub.width),
+                sup_box.as_ref().map(|sup| sup.width),
+            ]
+            .into_iter()
+            .flatten()
+            .fold(0., f32::max);
+            let (base_ascent, base_descent) = (base_box.ascent, base_box.descent);
+            result.append(base_box.with_x_centered(width), 0., 0.);
+            if let Some(sup) = sup_box {
+                let shift = -(base_ascent + gap + sup.descent);
+                let x = (width - sup.width) / 2.;
+                result.append(sup, x, shift);
+            }
+            if let Some(sub) = sub_box {
+                let shift = base_descent + gap + sub.ascent;
+                let x = (width - sub.width) / 2.;
+                result.append(sub, x, shift);
+            }
+            result.width = width;
+            return result;
+        }
+
+        let x = base_box.width + style.size * 0.05;
+        let (base_ascent, base_descent) = (base_box.ascent, base_box.descent);
+        result.append(base_box, 0., 0.);
+
+        let sup_shift = sup_box
+            .as_ref()
+            .map(|sup| (style.size * 0.4).max(base_ascent - sup.ascent * 0.6));
+        let mut sub_shift = sub_box
+            .as_ref()
+            .map(|_| (style.size * 0.25).max(base_descent - style.size * 0.1));
+        if let (Some(sup), Some(sub), Some(sup_shift), Some(sub_shift)) =
+            (&sup_box, &sub_box, sup_shift, sub_shift.as_mut())
+        {
+            let overlap = (sup.descent - sup_shift) - (*sub_shift - sub.ascent) + style.size * 0.1;
+            if overlap > 0. {
+                *sub_shift += overlap;
+            }
+        }
+        if let (Some(sup), Some(shift)) = (sup_box, sup_shift) {
+            result.append(sup, x, -shift);
+        }
+        if let (Some(sub), Some(shift)) = (sub_box, sub_shift) {
+            result.append(sub, x, shift);
+        }
+        result
+    }
+
+    fn fraction(
+        &self,
+        numerator: &MathNode,
+        denominator: &MathNode,
+        bar: bool,
+        style: Style,
+    ) -> MathBox {
+        let inner_style = style.fraction(self.base_size);
+        let numerator = self.node(numerator, inner_style);
+        let denominator = self.node(denominator, inner_style);
+        let padding = style.size * 0.12;
+        let width = numerator.width.max(denominator.width) + 2. * padding;
+        let rule = style.rule();
+        let gap = style.size * 0.15;
+        let axis = style.axis();
+
+        let mut fraction = MathBox::default();
+        let numerator_shift = -(axis + rule / 2. + gap + numerator.descent);
+        let denominator_shift = -axis + rule / 2. + gap + denominator.ascent;
+        let numerator_x = (width - numerator.width) / 2.;
+        let denominator_x = (width - denominator.width) / 2.;
+        fraction.append(numerator, numerator_x, numerator_shift);
+        fraction.append(denominator, denominator_x, denominator_shift);
+        if bar {
+            fraction.rule(
+                point(padding / 2., -axis - rule / 2.),
+                width - padding,
+                rule,
+            );
+        }
+        fraction.width = width;
+        fraction
+    }
+
+    fn root(&self, index: Option<&MathNode>, radicand: &MathNode, style: Style) -> MathBox {
+        let radicand = self.node(radicand, style);
+        let rule = style.rule();
+        let gap = style.size * 0.12;
+        let top = radicand.ascent + gap + rule / 2.;
+        let bottom = radicand.descent;
+        let sign_width = style.size * 0.6;
+        let middle = -top + (top + bottom) * 0.55;
+
+        let index = index.map(|index| {
+            let index_style = style.script(self.base_size).script(self.base_size);
+            self.node(index, index_style)
+        });
+        let offset = index
+            .as_ref()
+            .map_or(0., |index| (index.width - sign_width * 0.45).max(0.));
+
+        let mut root = MathBox::default();
+        let radicand_x = offset + sign_width + style.size * 0.05;
+        let end = radicand_x + radicand.width + style.size * 0.05;
+        root.append(radicand, radicand_x, 0.);
+        root.polyline(
+            vec![
+                point(offset, middle),
+                point(offset + sign_width * 0.2, middle - style.size * 0.06),
+                point(offset + sign_width * 0.5, bottom),
+                point(offset + sign_width, -top),
+                point(end, -top),
+            ],
+            rule,
+        );
+        if let Some(index) = index {
+            let x = offset + sign_width * 0.45 - index.width;
+            let shift = middle - index.descent - style.size * 0.05;
+            root.append(index, x, shift);
+        }
+        root.width = end;
+        root.ascent = root.ascent.max(top + rule / 2.);
+        root.descent = root.descent.max(bottom + rule);
+        root
+    }
+
+    fn delimited(&self, open: &str, close: &str, body: &MathNode, style: Style) -> MathBox {
+        let body = self.node(body, style);
+        let axis = style.axis();
+        let half_height = if body.ascent + body.descent > style.size * 1.25 {
+            Some((body.ascent - axis).max(body.descent + axis) + style.size * 0.1)
+        } else {
+            None
+        };
+
+        let mut delimited = MathBox::default();
+        let open = self.delimiter(open, false, half_height, style);
+        let mut x = open.width;
+        delimited.append(open, 0., 0.);
+        let body_width = body.width;
+        delimited.append(body, x, 0.);
+        x += body_width;
+        let close = self.delimiter(close, true, half_height, style);
+        let close_width = close.width;
+        delimited.append(close, x, 0.);
+        delimited.width = x + close_width;
+        delimited
+    }
+
+    /// Lays out a delimiter, drawing it as a path when it needs to extend
+    /// `half_height` above and below the math axis.
+    fn delimiter(
+        &self,
+        delimiter: &str,
+        is_closing: bool,
+        half_height: Option<f32>,
+        style: Style,
+    ) -> MathBox {
+        if delimiter.is_empty() {
+            return MathBox {
+                width: style.size * 0.1,
+                ..MathBox::default()
+            };
+        }
+        let Some(half_height) = half_height else {
+            return self.glyph(delimiter, style.size, TextVariant::REGULAR);
+        };
+
+        let width = style.size
+            * if delimiter == "{" || delimiter == "}" {
+                0.5
+            } else {
+                0.4
+            };
+        let top = -style.axis() - half_height;
+        let bottom = -style.axis() + half_height;
+        let middle = -style.axis();
+        let height = bottom - top;
+        let p = |x: f32, y: f32| point(x * width, y);
+
+        let shapes: Vec<Vec<Point<f32>>> = match delimiter {
+            "(" | ")" => {
+                let start = p(0.85, top);
+                let mut points = vec![start];
+                points.extend(quadratic_curve(start, p(-0.3, middle), p(0.85, bottom), 16));
+                vec![points]
+            }
+            "[" | "]" => vec![vec![
+                p(0.8, top),
+                p(0.3, top),
+                p(0.3, bottom),
+                p(0.8, bottom),
+            ]],
+            "{" | "}" => {
+                let end = height * 0.12;
+                let mut points = vec![p(0.9, top)];
+                points.extend(quadratic_curve(
+                    p(0.9, top),
+                    p(0.5, top),
+                    p(0.5, top + end),
+                    6,
+                ));
+                points.push(p(0.5, middle - end));
+                points.extend(quadratic_curve(
+                    p(0.5, middle - end),
+                    p(0.5, middle),
+                    p(0.1, middle),
+                    6,
+                ));
+                points.extend(quadratic_curve(
+                    p(0.1, middle),
+                    p(0.5, middle),
+                    p(0.5, middle + end),
+                    6,
+                ));
+                points.push(p(0.5, bottom - end));
+                points.extend(quadratic_curve(
+                    p(0.5, bottom - end),
+                    p(0.5, bottom),
+                    p(0.9, bottom),
+                    6,
+                ));
+                vec![points]
+            }
+            "|" => vec![vec![p(0.5, top), p(0.5, bottom)]],
+            "‖" => vec![
+                vec![p(0.35, top), p(0.35, bottom)],
+                vec![p(0.65, top), p(0.65, bottom)],
+            ],
+            "⟨" | "⟩" => vec![vec![p(0.8, top), p(0.2, middle), p(0.8, bottom)]],
+            "⌊" | "⌋" => vec![vec![p(0.3, top), p(0.3, bottom), p(0.8, bottom)]],
+            "⌈" | "⌉" => vec![vec![p(0.8, top), p(0.3, top), p(0.3, bottom)]],
+            _ => return self.glyph(delimiter, style.size, TextVariant::REGULAR),
+        };
+
+        let mut result = MathBox {
+            width,
+            ascent: -top,
+            descent: bottom,
+            primitives: Vec::new(),
+        };
+        for mut points in shapes {
+            if is_closing {
+                for point in &mut points {
+                    point.x = width - point.x;
+                }
+            }
+            result.polyline(points, style.rule() * 1.2);
+        }
+        result
+    }
+
+    fn accent(&self, accent: Accent, body: &MathNode, style: Style) -> MathBox {
+        let mark = match accent {
+            Accent::Hat => Some('\u{302}'),
+            Accent::Bar => Some('\u{304}'),
+            Accent::Vec => Some('\u{20d7}'),
+            Accent::Dot => Some('\u{307}'),
+            Accent::DoubleDot => Some('\u{308}'),
+            Accent::Tilde => Some('\u{303}'),
+            Accent::Underline => None,
+        };
+        // Accents on single characters are left to the font, using combining marks.
+        if let (
+            Some(mark),
+            MathNode::Atom {
+                text,
+                class,
+                variant,
+            },
+        ) = (mark, body)
+            && text.chars().count() == 1
+        {
+            let mut text = text.clone();
+            text.push(mark);
+            return self.atom(&text, *class, *variant, style);
+        }
+
+        let mut result = self.node(body, style);
+        let rule = style.rule();
+        let gap = style.size * 0.1;
+        let width = result.width;
+        let top = -(result.ascent + gap);
+        match accent {
+            Accent::Underline => {
+                let y = result.descent + gap;
+                result.rule(point(0., y), width, rule);
+                result.descent = y + rule;
+            }
+            Accent::Bar => {
+                result.rule(point(0., top - rule), width, rule);
+                result.ascent = -(top - rule);
+            }
+            Accent::Hat => {
+                let height = style.size * 0.2;
+                result.polyline(
+                    vec![
+                        point(0., top),
+                        point(width / 2., top - height),
+                        point(width, top),
+                    ],
+                    rule,
+                );
+                result.ascent = -(top - height - rule);
+            }
+            Accent::Tilde => {
+                let amplitude = style.size * 0.07;
+                let y = top - amplitude - rule;
+                let points = (0..=16)
+                    .map(|step| {
+                        let t = step as f32 / 16.;
+                        point(t * width, y - (t * std::f32::consts::TAU).sin() * amplitude)
+                    })
+                    .collect();
+                result.polyline(points, rule);
+                result.ascent = -(y - amplitude - rule);
+            }
+            Accent::Vec => {
+                let head = style.size * 0.15;
+                let y = top - head;
+                result.polyline(vec![point(0., y), point(width, y)], rule);
+                result.polyline(
+                    vec![
+                        point(width - head, y - head),
+                        point(width, y),
+                        point(width - head, y + head),
+                    ],
+                    rule,
+                );
+                result.ascent = -(y - head - rule);
+            }
+            Accent::Dot | Accent::DoubleDot => {
+                let diameter = style.size * 0.12;
+                let y = top - diameter;
+                let centers: &[f32] = if accent == Accent::Dot {
+                    &[0.5]
+                } else {
+                    &[0.35, 0.65]
+                };
+                for center in centers {
+                    result.primitives.push(Primitive::Rect {
+                        bounds: Bounds::new(
+                            point(center * width - diameter / 2., y),
+                            size(diameter, diameter),
+                        ),
+                        corner_radius: diameter / 2.,
+                        fill: Some(Ink::Text),
+                        stroke: None,
+                        dashed: false,
+                    });
+                }
+                result.ascent = -y;
+            }
+        }
+        result
+    }
+
+    fn table(&self, kind: TableKind, rows: &[Vec<MathNode>], style: Style) -> MathBox {
+        let cell_style = Style {
+            size: style.size,
+            display: style.display && kind == TableKind::Aligned,
+        };
+        let cells = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| self.node(cell, cell_style))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let column_count = cells.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut column_widths = vec![0f32; column_count];
+        for row in &cells {
+            for (column, cell) in row.iter().enumerate() {
+                column_widths[column] = column_widths[column].max(cell.width);
+            }
+        }
+        let column_gap = |column: usize| match kind {
+            TableKind::Matrix | TableKind::Cases => style.size,
+            TableKind::Aligned if column.is_multiple_of(2) => style.size * 5. / 18.,
+            TableKind::Aligned => style.size * 1.5,
+        };
+        let mut column_x = Vec::with_capacity(column_count);
+        let mut x = 0.;
+        for (column, width) in column_widths.iter().enumerate() {
+            column_x.push(x);
+            x += width;
+            if column + 1 < column_count {
+                x += column_gap(column);
+            }
+        }
+        let width = x;
+
+        let strut = self.measure.measure("(", style.size, TextVariant::REGULAR);
+        let row_gap = style.size * 0.3;
+        let mut baselines = Vec::with_capacity(cells.len());
+        let mut y = 0.;
+        for row in &cells {
+            let ascent = row
+                .iter()
+                .map(|cell| cell.ascent)
+                .fold(strut.ascent, f32::max);
+            let descent = row
+                .iter()
+                .map(|cell| cell.descent)
+                .fold(strut.descent, f32::max);
+            baselines.push(y + ascent);
+            y += ascent + descent + row_gap;
+        }
+        let height = (y - row_gap).max(0.);
+        let top = -style.axis() - height / 2.;
+
+        let mut table = MathBox::default();
+        for (row, baseline) in cells.into_iter().zip(baselines) {
+            for (column, cell) in row.into_iter().enumerate() {
+                let free_space = column_widths[column] - cell.width;
+                let offset = match kind {
+                    TableKind::Matrix => free_space / 2.,
+                    TableKind::Cases => 0.,
+                    TableKind::Aligned if column.is_multiple_of(2) => free_space,
+                    TableKind::Aligned => 0.,
+                };
+                table.append(cell, column_x[column] + offset, top + baseline);
+            }
+        }
+        table.width = width;
+        table.ascent = table.ascent.max(-top);
+        table.descent = table.descent.max(top + height);
+        table
+    }
+}
+
+fn has_limits(node: &MathNode) -> bool {
+    matches!(
+        node,
+        MathNode::Atom {
+            text,
+            class: AtomClass::LargeOperator | AtomClass::Function,
+            ..
+        } if symbols::has_limits(text)
+    )
+}
+
+/// Whether the text is a single letter, ignoring combining marks, which is set in italics.
+fn is_letter(text: &str) -> bool {
+    let mut chars = text
+        .chars()
+        .filter(|c| !matches!(c, '\u{300}'..='\u{36f}' | '\u{20d0}'..='\u{20ff}'));
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => c.is_ascii_alphabetic() || (c.is_alphabetic() && c.is_lowercase()),
+        _ => false,
+    }
+}
@@ -0,0 +1,395 @@
+use super::AtomClass;
+
+/// Looks up a command that stands for a single symbol, like `\alpha` or `\leq`.
+pub(super) fn symbol(command: &str) -> Option<(&'static str, AtomClass)> {
+    use AtomClass::*;
+    Some(match command {
+        // Lowercase Greek
+        "alpha" => ("α", Ordinary),
+        "beta" => ("β", Ordinary),
+        "gamma" => ("γ", Ordinary),
+        "delta" => ("δ", Ordinary),
+        "epsilon" => ("ϵ", Ordinary),
+        "varepsilon" => ("ε", Ordinary),
+        "zeta" => ("ζ", Ordinary),
+        "eta" => ("η", Ordinary),
+        "theta" => ("θ", Ordinary),
+        "vartheta" => ("ϑ", Ordinary),
+        "iota" => ("ι", Ordinary),
+        "kappa" => ("κ", Ordinary),
+        "lambda" => ("λ", Ordinary),
+        "mu" => ("μ", Ordinary),
+        "nu" => ("ν", Ordinary),
+        "xi" => ("ξ", Ordinary),
+        "omicron" => ("ο", Ordinary),
+        "pi" => ("π", Ordinary),
+        "varpi" => ("ϖ", Ordinary),
+        "rho" => ("ρ", Ordinary),
+        "varrho" => ("ϱ", Ordinary),
+        "sigma" => ("σ", Ordinary),
+        "varsigma" => ("ς", Ordinary),
+        "tau" => ("τ", Ordinary),
+        "upsilon" => ("υ", Ordinary),
+        "phi" => ("ϕ", Ordinary),
+        "varphi" => ("φ", Ordinary),
+        "chi" => ("χ", Ordinary),
+        "psi" => ("ψ", Ordinary),
+        "omega" => ("ω", Ordinary),
+        // Uppercase Greek
+        "Gamma" => ("Γ", Ordinary),
+        "Delta" => ("Δ", Ordinary),
+        "Theta" => ("Θ", Ordinary),
+        "Lambda" => ("Λ", Ordinary),
+        "Xi" => ("Ξ", Ordinary),
+        "Pi" => ("Π", Ordinary),
+        "Sigma" => ("Σ", Ordinary),
+        "Upsilon" => ("Υ", Ordinary),
+        "Phi" => ("Φ", Ordinary),
+        "Psi" => ("Ψ", Ordinary),
+        "Omega" => ("Ω", Ordinary),
+        // Letter-like symbols
+        "infty" => ("∞", Ordinary),
+        "partial" => ("∂", Ordinary),
+        "nabla" => ("∇", Ordinary),
+        "forall" => ("∀", Ordinary),
+        "exists" => ("∃", Ordinary),
+        "nexists" => ("∄", Ordinary),
+        "emptyset" => ("∅", Ordinary),
+        "varnothing" => ("∅", Ordinary),
+        "hbar" => ("ℏ", Ordinary),
+        "ell" => ("ℓ", Ordinary),
+        "Re" => ("ℜ", Ordinary),
+        "Im" => ("ℑ", Ordinary),
+        "aleph" => ("ℵ", Ordinary),
+        "wp" => ("℘", Ordinary),
+        "top" => ("⊤", Ordinary),
+        "bot" => ("⊥", Ordinary),
+        "angle" => ("∠", Ordinary),
+        "triangle" => ("△", Ordinary),
+        "square" => ("□", Ordinary),
+        "prime" => ("′", Ordinary),
+        "degree" => ("°", Ordinary),
+        "neg" => ("¬", Ordinary),
+        "lnot" => ("¬", Ordinary),
+        "ldots" => ("…", Ordinary),
+        "dots" => ("…", Ordinary),
+        "cdots" => ("⋯", Ordinary),
+        "vdots" => ("⋮", Ordinary),
+        "ddots" => ("⋱", Ordinary),
+        "|" => ("‖", Ordinary),
+        "{" => ("{", Open),
+        "}" => ("}", Close),
+        "%" => ("%", Ordinary),
+        "$" => ("$", Ordinary),
+        "#" => ("#", Ordinary),
+        "&" => ("&", Ordinary),
+        "_" => ("_", Ordinary),
+        // Binary operators
+        "pm" => ("±", Binary),
+        "mp" => ("∓", Binary),
+        "times" => ("×", Binary),
+        "div" => ("÷", Binary),
+        "cdot" => ("⋅", Binary),
+        "ast" => ("∗", Binary),
+        "star" => ("⋆", Binary),
+        "circ" => ("∘", Binary),
+        "bullet" => ("∙", Binary),
+        "oplus" => ("⊕", Binary),
+        "ominus" => ("⊖", Binary),
+        "otimes" => ("⊗", Binary),
+        "odot" => ("⊙", Binary),
+        "cup" => ("∪", Binary),
+        "cap" => ("∩", Binary),
+        "setminus" => ("∖", Binary),
+        "wedge" => ("∧", Binary),
+        "land" => ("∧", Binary),
+        "vee" => ("∨", Binary),
+        "lor" => ("∨", Binary),
+        "bmod" => ("mod", Binary),
+        // Relations
+        "le" => ("≤", Relation),
+        "leq" => ("≤", Relation),
+        "ge" => ("≥", Relation),
+        "geq" => ("≥", Relation),
+        "ne" => ("≠", Relation),
+        "neq" => ("≠", Relation),
+        "ll" => ("≪", Relation),
+        "gg" => ("≫", Relation),
+        "approx" => ("≈", Relation),
+        "equiv" => ("≡", Relation),
+        "sim" => ("∼", Relation),
+        "simeq" => ("≃", Relation),
+        "cong" => ("≅", Relation),
+        "propto" => ("∝", Relation),
+        "in" => ("∈", Relation),
+        "notin" => ("∉", Relation),
+        "ni" => ("∋", Relation),
+        "subset" => ("⊂", Relation),
+        "subseteq" => ("⊆", Relation),
+        "supset" => ("⊃", Relation),
+        "supseteq" => ("⊇", Relation),
+        "perp" => ("⊥", Relation),
+        "parallel" => ("∥", Relation),
+        "mid" => ("∣", Relation),
+        "vdash" => ("⊢", Relation),
+        "models" => ("⊨", Relation),
+        "to" => ("→", Relation),
+        "rightarrow" => ("→", Relation),
+        "gets" => ("←", Relation),
+        "leftarrow" => ("←", Relation),
+        "leftrightarrow" => ("↔", Relation),
+        "Rightarrow" => ("⇒", Relation),
+        "Leftarrow" => ("⇐", Relation),
+        "Leftrightarrow" => ("⇔", Relation),
+        "implies" => ("⟹", Relation),
+        "impliedby" => ("⟸", Relation),
+        "iff" => ("⟺", Relation),
+        "mapsto" => ("↦", Relation),
+        "longrightarrow" => ("⟶", Relation),
+        "longleftarrow" => ("⟵", Relation),
+        "uparrow" => ("↑", Relation),
+        "downarrow" => ("↓", Relation),
+        "coloneqq" => ("≔", Relation),
+        // Delimiters
+        "langle" => ("⟨", Open),
+        "rangle" => ("⟩", Close),
+        "lfloor" => ("⌊", Open),
+        "rfloor" => ("⌋", Close),
+        "lceil" => ("⌈", Open),
+        "rceil" => ("⌉", Close),
+        "lvert" => ("|", Open),
+        "rvert" => ("|", Close),
+        "lVert" => ("‖", Open),
+        "rVert" => ("‖", Close),
+        "vert" => ("|", Ordinary),
+        "Vert" => ("‖", Ordinary),
+        "lbrace" => ("{", Open),
+        "rbrace" => ("}", Close),
+        "lbrack" => ("[", Open),
+        "rbrack" => ("]", Close),
+        // Large operators
+        "sum" => ("∑", LargeOperator),
+        "prod" => ("∏", LargeOperator),
+        "coprod" => ("∐", LargeOperator),
+        "int" => ("∫", LargeOperator),
+        "iint" => ("∬", LargeOperator),
+        "iiint" => ("∭", LargeOperator),
+        "oint" => ("∮", LargeOperator),
+        "bigcup" => ("⋃", LargeOperator),
+        "bigcap" => ("⋂", LargeOperator),
+        "bigoplus" => ("⨁", LargeOperator),
+        "bigotimes" => ("⨂", LargeOperator),
+        "bigvee" => ("⋁", LargeOperator),
+        "bigwedge" => ("⋀", LargeOperator),
+        _ => return None,
+    })
+}
+
+/// Function names that are set upright, like `\sin`.
+pub(super) fn is_function(command: &str) -> bool {
+    matches!(
+        command,
+        "sin"
+            | "cos"
+            | "tan"
+            | "cot"
+            | "sec"
+            | "csc"
+            | "sinh"
+            | "cosh"
+            | "tanh"
+            | "coth"
+            | "arcsin"
+            | "arccos"
+            | "arctan"
+            | "log"
+            | "ln"
+            | "lg"
+            | "exp"
+            | "lim"
+            | "limsup"
+            | "liminf"
+            | "max"
+            | "min"
+            | "sup"
+            | "inf"
+            | "det"
+            | "dim"
+            | "ker"
+            | "deg"
+            | "gcd"
+            | "arg"
+            | "hom"
+            | "Pr"
+            | "mod"
+    )
+}
+
+/// Whether scripts on this operator are placed above and below it in display math.
+pub(super) fn has_limits(text: &str) -> bool {
+    matches!(
+        text,
+        "∑" | "∏"
+            | "∐"
+            | "⋃"
+            | "⋂"
+            | "⨁"
+            | "⨂"
+            | "⋁"
+            | "⋀"
+            | "lim"
+            | "limsup"
+            | "liminf"
+            | "max"
+            | "min"
+            | "sup"
+            | "inf"
+            | "det"
+            | "gcd"
+            | "Pr"
+    )
+}
+
+/// The width of spacing commands like `\,` and `\quad`, in ems.
+pub(super) fn space(command: &str) -> Option<f32> {
+    Some(match command {
+        "," | "thinspace" => 3. / 18.,
+        ":" | ">" | "medspace" => 4. / 18.,
+        ";" | "thickspace" => 5. / 18.,
+        "!" | "negthinspace" => -3. / 18.,
+        " " => 0.25,
+        "quad" => 1.,
+        "qquad" => 2.,
+        _ => return None,
+    })
+}
+
+pub(super) fn double_struck(c: char) -> char {
+    match c {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        'A'..='Z' => char::from_u32(0x1D538 + (c as u32 - 'A' as u32)).unwrap_or(c),
+        'a'..='z' => char::from_u32(0x1D552 + (c as u32 - 'a' as u32)).unwrap_or(c),
+        '0'..='9' => char::from_u32(0x1D7D8 + (c as u32 - '0' as u32)).unwrap_or(c),
+        _ => c,
+    }
+}
+
+pub(super) fn calligraphic(c: char) -> char {
+    match c {
+        'B' => 'ℬ',
+        'E' => 'ℰ',
+        'F' => 'ℱ',
+        'H' => 'ℋ',
+        'I' => 'ℐ',
+        'L' => 'ℒ',
+        'M' => 'ℳ',
+        'R' => 'ℛ',
+        'A'..='Z' => char::from_u32(0x1D49C + (c as u32 - 'A' as u32)).unwrap_or(c),
+        _ => c,
+    }
+}
+
+pub(super) fn fraktur(c: char) -> char {
+    match c {
+        'C' => 'ℭ',
+        'H' => 'ℌ',
+        'I' => 'ℑ',
+        'R' => 'ℜ',
+        'Z' => 'ℨ',
+        'A'..='Z' => char::from_u32(0x1D504 + (c as u32 - 'A' as u32)).unwrap_or(c),
+        'a'..='z' => char::from_u32(0x1D51E + (c as u32 - 'a' as u32)).unwrap_or(c),
+        _ => c,
+    }
+}
+
+pub(super) fn superscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '−' | '-' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        'T' => 'ᵀ',
+        '′' => '′',
+        _ => return None,
+    })
+}
+
+pub(super) fn subscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '−' | '-' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
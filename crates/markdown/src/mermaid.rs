@@ -0,0 +1,386 @@
+//! Native rendering of the most common [Mermaid](https://mermaid.js.org)
+//! diagrams: flowcharts, sequence diagrams and class diagrams.
+//!
+//! Diagrams are parsed into a model and laid out as a [`Drawing`], so no
+//! browser is involved and layout can be tested without a window.
+
+mod class_diagram;
+mod flowchart;
+mod layered_layout;
+mod sequence;
+
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{Bounds, IntoElement, Point, SharedString, point, size};
+
+use crate::drawing::{
+    Drawing, DrawingElement, Ink, MeasureText, Primitive, TextMetrics, TextVariant,
+};
+
+pub use class_diagram::{Class, ClassDiagram, Relation, RelationEnd};
+pub use flowchart::{EdgeEnd, EdgeLine, FlowEdge, FlowNode, Flowchart, NodeShape};
+pub use layered_layout::Direction;
+pub use sequence::{
+    ArrowHead, Message, NotePlacement, Participant, ParticipantKind, SequenceDiagram, SequenceItem,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MermaidDiagram {
+    Flowchart(Flowchart),
+    Sequence(SequenceDiagram),
+    Class(ClassDiagram),
+}
+
+/// Parses the source of a fenced `mermaid` block.
+pub fn parse_mermaid(source: &str) -> Result<MermaidDiagram> {
+    let mut lines = statements(source);
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| anyhow!("the diagram is empty"))?;
+    let mut words = header.split_whitespace();
+    let kind = words.next().unwrap_or_default();
+    match kind {
+        "graph" | "flowchart" => {
+            let direction = match words.next() {
+                Some(direction) => Direction::from_mermaid(direction)
+                    .ok_or_else(|| anyhow!("unknown flowchart direction {direction:?}"))?,
+                None => Direction::TopToBottom,
+            };
+            Ok(MermaidDiagram::Flowchart(flowchart::parse(
+                direction, lines,
+            )?))
+        }
+        "sequenceDiagram" => Ok(MermaidDiagram::Sequence(sequence::parse(lines)?)),
+        "classDiagram" | "classDiagram-v2" => {
+            Ok(MermaidDiagram::Class(class_diagram::parse(lines)?))
+        }
+        _ => Err(anyhow!("unsupported diagram type {kind:?}")),
+    }
+}
+
+/// Renders a parsed diagram at its natural size.
+pub fn render_mermaid(diagram: Arc<MermaidDiagram>) -> impl IntoElement {
+    DrawingElement::new(move |measure, font_size| layout_mermaid(&diagram, measure, font_size))
+}
+
+/// Lays out a parsed diagram, for output other than the window, such as
+/// exported documents.
+pub fn layout_mermaid(
+    diagram: &MermaidDiagram,
+    measure: &dyn MeasureText,
+    font_size: f32,
+) -> Drawing {
+    match diagram {
+        MermaidDiagram::Flowchart(flowchart) => flowchart::layout(flowchart, measure, font_size),
+        MermaidDiagram::Sequence(sequence) => sequence::layout(sequence, measure, font_size),
+        MermaidDiagram::Class(class_diagram) => {
+            class_diagram::layout(class_diagram, measure, font_size)
+        }
+    }
+}
+
+/// Yields the non-empty statements of a diagram along with their line numbers,
+/// skipping front matter, directives and comments.
+fn statements(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut in_front_matter = false;
+    source.lines().enumerate().filter_map(move |(index, line)| {
+        let line = line.trim();
+        if index == 0 && line == "---" {
+            in_front_matter = true;
+            return None;
+        }
+        if in_front_matter {
+            in_front_matter = line != "---";
+            return None;
+        }
+        if line.starts_with("%%") {
+            return None;
+        }
+        let line = line.trim_end_matches(';').trim_end();
+        (!line.is_empty()).then_some((index + 1, line))
+    })
+}
+
+fn with_line<T>(line_number: usize, result: Result<T>) -> Result<T> {
+    result.with_context(|| format!("line {line_number}"))
+}
+
+/// Strips quotes from a label and turns `<br>` tags into line breaks.
+fn normalize_label(label: &str) -> String {
+    let label = label.trim();
+    let label = label
+        .strip_prefix('"')
+        .and_then(|label| label.strip_suffix('"'))
+        .unwrap_or(label);
+    let label = label
+        .strip_prefix('`')
+        .and_then(|label| label.strip_suffix('`'))
+        .unwrap_or(label);
+    let mut normalized = String::with_capacity(label.len());
+    let mut rest = label;
+    while let Some(start) = rest.find('<') {
+        normalized.push_str(&rest[..start]);
+        let tag = &rest[start..];
+        let end = tag.find('>').map(|end| end + 1).unwrap_or(tag.len());
+        let name = tag[1..end.saturating_sub(1).max(1)]
+            .trim()
+            .trim_end_matches('/')
+            .trim();
+        if name.eq_ignore_ascii_case("br") {
+            normalized.push('\n');
+            rest = &tag[end..];
+        } else {
+            normalized.push('<');
+            rest = &tag[1..];
+        }
+    }
+    normalized.push_str(rest);
+    normalized.replace("\\n", "\n")
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+}
+
+/// Measured lines of text that are drawn together.
+struct TextBlock {
+    lines: Vec<(SharedString, TextMetrics)>,
+    font_size: f32,
+    variant: TextVariant,
+    line_height: f32,
+    width: f32,
+}
+
+impl TextBlock {
+    fn new(text: &str, measure: &dyn MeasureText, font_size: f32, variant: TextVariant) -> Self {
+        let lines = text
+            .split('\n')
+            .map(|line| {
+                let line = line.trim();
+                (
+                    SharedString::from(line.to_string()),
+                    measure.measure(line, font_size, variant),
+                )
+            })
+            .collect::<Vec<_>>();
+        let line_height = lines
+            .iter()
+            .map(|(_, metrics)| metrics.height())
+            .fold(0., f32::max)
+            * 1.2;
+        let width = lines
+            .iter()
+            .map(|(_, metrics)| metrics.width)
+            .fold(0., f32::max);
+        Self {
+            lines,
+            font_size,
+            variant,
+            line_height,
+            width,
+        }
+    }
+
+    fn height(&self) -> f32 {
+        self.line_height * self.lines.len() as f32
+    }
+
+    /// Draws the block below `top`, either starting at or centered on `x`.
+    fn draw(&self, primitives: &mut Vec<Primitive>, x: f32, top: f32, align: Align, ink: Ink) {
+        for (index, (text, metrics)) in self.lines.iter().enumerate() {
+            if text.is_empty() {
+                continue;
+            }
+            let line_top = top + index as f32 * self.line_height;
+            let baseline = line_top + (self.line_height - metrics.height()) / 2. + metrics.ascent;
+            let left = match align {
+                Align::Left => x,
+                Align::Center => x - metrics.width / 2.,
+            };
+            primitives.push(Primitive::Text {
+                origin: point(left, baseline),
+                text: text.clone(),
+                font_size: self.font_size,
+                variant: self.variant,
+                ink,
+            });
+        }
+    }
+
+    fn draw_centered(&self, primitives: &mut Vec<Primitive>, center: Point<f32>, ink: Ink) {
+        self.draw(
+            primitives,
+            center.x,
+            center.y - self.height() / 2.,
+            Align::Center,
+            ink,
+        );
+    }
+}
+
+/// Returns the unit vector from `from` to `to`, along with the distance between them.
+fn direction(from: Point<f32>, to: Point<f32>) -> (Point<f32>, f32) {
+    let delta = point(to.x - from.x, to.y - from.y);
+    let length = (delta.x * delta.x + delta.y * delta.y).sqrt();
+    if length < f32::EPSILON {
+        (point(0., 1.), 0.)
+    } else {
+        (point(delta.x / length, delta.y / length), length)
+    }
+}
+
+/// The point where a line from the center of a rectangle toward `target`
+/// leaves the rectangle.
+fn clip_to_rect(
+    center: Point<f32>,
+    half_width: f32,
+    half_height: f32,
+    target: Point<f32>,
+) -> Point<f32> {
+    let (unit, _) = direction(center, target);
+    let distance = (half_width / unit.x.abs().max(f32::EPSILON))
+        .min(half_height / unit.y.abs().max(f32::EPSILON));
+    offset(center, unit, distance)
+}
+
+fn offset(origin: Point<f32>, direction: Point<f32>, distance: f32) -> Point<f32> {
+    point(
+        origin.x + direction.x * distance,
+        origin.y + direction.y * distance,
+    )
+}
+
+/// The points of a marker whose tip is at `tip`, pointing away from `from`.
+/// The marker is `length` long and `2 * half_width` wide. Diamonds have an
+/// additional point at their back.
+fn marker_points(
+    tip: Point<f32>,
+    from: Point<f32>,
+    length: f32,
+    half_width: f32,
+    diamond: bool,
+) -> Vec<Point<f32>> {
+    let (unit, _) = direction(from, tip);
+    let normal = point(-unit.y, unit.x);
+    let side_distance = if diamond { length / 2. } else { length };
+    let side_center = offset(tip, unit, -side_distance);
+    let left = offset(side_center, normal, half_width);
+    let right = offset(side_center, normal, -half_width);
+    if diamond {
+        vec![tip, left, offset(tip, unit, -length), right]
+    } else {
+        vec![tip, left, right]
+    }
+}
+
+fn filled_arrow(tip: Point<f32>, from: Point<f32>, font_size: f32) -> Primitive {
+    Primitive::Polygon {
+        points: marker_points(tip, from, font_size * 0.6, font_size * 0.3, false),
+        fill: Some(Ink::Line),
+        stroke: Some(Ink::Line),
+    }
+}
+
+fn open_arrow(tip: Point<f32>, from: Point<f32>, font_size: f32) -> Primitive {
+    let points = marker_points(tip, from, font_size * 0.6, font_size * 0.3, false);
+    Primitive::Polyline {
+        points: vec![points[1], points[0], points[2]],
+        width: 1.,
+        ink: Ink::Line,
+        dashed: false,
+    }
+}
+
+fn cross(center: Point<f32>, font_size: f32) -> [Primitive; 2] {
+    let half = font_size * 0.25;
+    let stroke = |from: Point<f32>, to: Point<f32>| Primitive::Polyline {
+        points: vec![from, to],
+        width: 1.5,
+        ink: Ink::Line,
+        dashed: false,
+    };
+    [
+        stroke(
+            point(center.x - half, center.y - half),
+            point(center.x + half, center.y + half),
+        ),
+        stroke(
+            point(center.x - half, center.y + half),
+            point(center.x + half, center.y - half),
+        ),
+    ]
+}
+
+fn line(points: Vec<Point<f32>>, dashed: bool) -> Primitive {
+    Primitive::Polyline {
+        points,
+        width: 1.,
+        ink: Ink::Line,
+        dashed,
+    }
+}
+
+/// Draws a label on an opaque background so that lines passing under it don't
+/// obscure it.
+fn draw_label(primitives: &mut Vec<Primitive>, label: &TextBlock, center: Point<f32>) {
+    let padding = label.font_size * 0.2;
+    primitives.push(Primitive::Rect {
+        bounds: Bounds {
+            origin: point(
+                center.x - label.width / 2. - padding,
+                center.y - label.height() / 2.,
+            ),
+            size: size(label.width + padding * 2., label.height()),
+        },
+        corner_radius: 2.,
+        fill: Some(Ink::Background),
+        stroke: None,
+        dashed: false,
+    });
+    label.draw_centered(primitives, center, Ink::Text);
+}
+
+#[cfg(test)]
+fn layout_for_test(source: &str) -> Drawing {
+    let diagram = parse_mermaid(source).unwrap();
+    layout_mermaid(&diagram, &crate::drawing::FixedWidthMeasure, 16.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mermaid() {
+        assert!(matches!(
+            parse_mermaid("%% a comment\nflowchart LR\nA --> B"),
+            Ok(MermaidDiagram::Flowchart(Flowchart {
+                direction: Direction::LeftToRight,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse_mermaid("---\ntitle: Example\n---\nsequenceDiagram\nA->>B: hi"),
+            Ok(MermaidDiagram::Sequence(_))
+        ));
+        assert!(matches!(
+            parse_mermaid("classDiagram\nA <|-- B"),
+            Ok(MermaidDiagram::Class(_))
+        ));
+        assert_eq!(
+            parse_mermaid("pie\n\"a\": 1").unwrap_err().to_string(),
+            "unsupported diagram type \"pie\""
+        );
+        assert!(parse_mermaid("  \n%% nothing\n").is_err());
+    }
+
+    #[test]
+    fn test_normalize_label() {
+        assert_eq!(normalize_label("\"Hello<br/>world\""), "Hello\nworld");
+        assert_eq!(normalize_label(" a < b <BR> c "), "a < b \n c");
+        assert_eq!(normalize_label("plain"), "plain");
+    }
+}
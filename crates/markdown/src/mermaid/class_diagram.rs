@@ -0,0 +1,581 @@
+use anyhow::{Result, anyhow, bail};
+use gpui::{Bounds, Point, point, size};
+
+use super::{
+    Align, Direction, TextBlock, clip_to_rect, direction, draw_label, line, marker_points,
+    normalize_label, offset, open_arrow, with_line,
+};
+use crate::drawing::{Drawing, Ink, MeasureText, Primitive, TextVariant};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClassDiagram {
+    pub direction: Direction,
+    pub classes: Vec<Class>,
+    pub relations: Vec<Relation>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Class {
+    pub id: String,
+    /// The name shown in the diagram, including generic parameters.
+    pub name: String,
+    pub annotation: Option<String>,
+    pub attributes: Vec<String>,
+    pub methods: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relation {
+    pub from: usize,
+    pub to: usize,
+    pub from_end: RelationEnd,
+    pub to_end: RelationEnd,
+    pub dashed: bool,
+    pub label: Option<String>,
+    pub from_cardinality: Option<String>,
+    pub to_cardinality: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelationEnd {
+    None,
+    /// A hollow triangle, as in `<|--`.
+    Inheritance,
+    /// A filled diamond, as in `*--`.
+    Composition,
+    /// A hollow diamond, as in `o--`.
+    Aggregation,
+    /// An open arrow, as in `<--`.
+    Association,
+}
+
+impl RelationEnd {
+    /// Whether the class at this end is drawn above the other one.
+    fn is_parent(self) -> bool {
+        matches!(
+            self,
+            Self::Inheritance | Self::Composition | Self::Aggregation
+        )
+    }
+}
+
+const IGNORED_STATEMENTS: &[&str] = &[
+    "note",
+    "classDef",
+    "cssClass",
+    "style",
+    "click",
+    "callback",
+    "link",
+    "namespace",
+    "}",
+];
+
+pub(super) fn parse<'a>(
+    statements: impl Iterator<Item = (usize, &'a str)>,
+) -> Result<ClassDiagram> {
+    let mut diagram = ClassDiagram::default();
+    let mut open_class = None;
+    for (line_number, statement) in statements {
+        if let Some(class) = open_class {
+            if statement == "}" {
+                open_class = None;
+            } else {
+                diagram.add_member(class, statement);
+            }
+            continue;
+        }
+
+        let keyword = statement.split_whitespace().next().unwrap_or_default();
+        let result = if keyword == "direction" {
+            let direction = statement[keyword.len()..].trim();
+            Direction::from_mermaid(direction)
+                .map(|direction| diagram.direction = direction)
+                .ok_or_else(|| anyhow!("unknown direction {direction:?}"))
+        } else if keyword == "class" {
+            let declaration = statement[keyword.len()..].trim();
+            let (declaration, has_body) = match declaration.strip_suffix('{') {
+                Some(declaration) => (declaration.trim(), true),
+                None => (declaration, false),
+            };
+            let class = diagram.class(declaration);
+            if has_body {
+                open_class = Some(class);
+            }
+            Ok(())
+        } else if IGNORED_STATEMENTS.contains(&keyword) {
+            Ok(())
+        } else if let Some(annotation) = statement.strip_prefix("<<") {
+            match annotation.split_once(">>") {
+                Some((annotation, class)) => {
+                    let class = diagram.class(class.trim());
+                    diagram.classes[class].annotation = Some(annotation.trim().to_string());
+                    Ok(())
+                }
+                None => Err(anyhow!("expected '>>' after the annotation")),
+            }
+        } else {
+            diagram.parse_relation_or_member(statement)
+        };
+        with_line(line_number, result)?;
+    }
+    Ok(diagram)
+}
+
+impl ClassDiagram {
+    /// Returns the index of the class declared as `declaration`, which may
+    /// include generic parameters like `List~T~`.
+    fn class(&mut self, declaration: &str) -> usize {
+        let (id, generics) = match declaration.split_once('~') {
+            Some((id, generics)) => (id.trim(), Some(generics.trim_end_matches('~'))),
+            None => (declaration.trim(), None),
+        };
+        let index = match self.classes.iter().position(|class| class.id == id) {
+            Some(index) => index,
+            None => {
+                self.classes.push(Class {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    ..Class::default()
+                });
+                self.classes.len() - 1
+            }
+        };
+        if let Some(generics) = generics {
+            self.classes[index].name = format!("{id}<{}>", generics.replace('~', ""));
+        }
+        index
+    }
+
+    fn add_member(&mut self, class: usize, member: &str) {
+        let member = member.trim();
+        let class = &mut self.classes[class];
+        if let Some(annotation) = member
+            .strip_prefix("<<")
+            .and_then(|annotation| annotation.strip_suffix(">>"))
+        {
+            class.annotation = Some(annotation.trim().to_string());
+        } else if member.contains('(') {
+            class.methods.push(member.replace('~', ""));
+        } else if !member.is_empty() {
+            class.attributes.push(member.replace('~', ""));
+        }
+    }
+
+    fn parse_relation_or_member(&mut self, statement: &str) -> Result<()> {
+        let tokens = tokenize(statement);
+        let Some(operator_index) = tokens.iter().position(|token| is_relation(token)) else {
+            let Some((class, member)) = statement.split_once(':') else {
+                bail!("expected a relation or a member, like \"A <|-- B\" or \"A : +int x\"");
+            };
+            let class = self.class(class);
+            self.add_member(class, member);
+            return Ok(());
+        };
+
+        let operator = tokens[operator_index];
+        let (left, right) = (&tokens[..operator_index], &tokens[operator_index + 1..]);
+        let (from, from_cardinality) = match left {
+            [from] => (*from, None),
+            [from, cardinality] => (*from, Some(normalize_label(cardinality))),
+            _ => bail!("expected a class before {operator:?}"),
+        };
+        let label_start = right.iter().position(|token| token.starts_with(':'));
+        let (right, label) = match label_start {
+            Some(label_start) => {
+                let label = normalize_label(&right[label_start][1..]);
+                (
+                    &right[..label_start],
+                    Some(label).filter(|label| !label.is_empty()),
+                )
+            }
+            None => (right, None),
+        };
+        let (to_cardinality, to) = match right {
+            [to] => (None, *to),
+            [cardinality, to] => (Some(normalize_label(cardinality)), *to),
+            _ => bail!("expected a class after {operator:?}"),
+        };
+
+        let from = self.class(from);
+        let to = self.class(to);
+        self.relations.push(Relation {
+            from,
+            to,
+            from_end: relation_end(operator, true),
+            to_end: relation_end(operator, false),
+            dashed: operator.contains(".."),
+            label,
+            from_cardinality,
+            to_cardinality,
+        });
+        Ok(())
+    }
+}
+
+/// Splits a statement on whitespace, keeping quoted strings together and
+/// separating a trailing `:label` from the token it's attached to.
+fn tokenize(statement: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = statement.trim_start();
+    while !rest.is_empty() {
+        let len = if let Some(quoted) = rest.strip_prefix('"') {
+            quoted.find('"').map_or(rest.len(), |end| end + 2)
+        } else if rest.starts_with(':') {
+            rest.len()
+        } else {
+            rest.find(|c: char| c.is_whitespace() || c == ':')
+                .unwrap_or(rest.len())
+        };
+        tokens.push(&rest[..len]);
+        rest = rest[len..].trim_start();
+    }
+    tokens
+}
+
+fn is_relation(token: &str) -> bool {
+    (token.contains("--") || token.contains(".."))
+        && token
+            .chars()
+            .all(|c| matches!(c, '<' | '>' | '|' | '*' | 'o' | '-' | '.'))
+}
+
+fn relation_end(operator: &str, is_from: bool) -> RelationEnd {
+    // Markers sit at the outer ends of the operator, around its line.
+    let marker = if is_from {
+        &operator[..operator.find(['-', '.']).unwrap_or(0)]
+    } else {
+        &operator[operator
+            .rfind(['-', '.'])
+            .map_or(operator.len(), |end| end + 1)..]
+    };
+    match marker {
+        "<|" | "|>" => RelationEnd::Inheritance,
+        "*" => RelationEnd::Composition,
+        "o" => RelationEnd::Aggregation,
+        "<" | ">" => RelationEnd::Association,
+        _ => RelationEnd::None,
+    }
+}
+
+struct ClassBox {
+    header: Vec<(TextBlock, Ink)>,
+    sections: [Vec<TextBlock>; 2],
+    width: f32,
+    height: f32,
+}
+
+pub(super) fn layout(diagram: &ClassDiagram, measure: &dyn MeasureText, font_size: f32) -> Drawing {
+    let padding = font_size * 0.5;
+    let member_size = font_size * 0.9;
+    let class_boxes = diagram
+        .classes
+        .iter()
+        .map(|class| {
+            let mut header = Vec::new();
+            if let Some(annotation) = &class.annotation {
+                header.push((
+                    TextBlock::new(
+                        &format!("«{annotation}»"),
+                        measure,
+                        member_size,
+                        TextVariant::ITALIC,
+                    ),
+                    Ink::MutedText,
+                ));
+            }
+            header.push((
+                TextBlock::new(&class.name, measure, font_size, TextVariant::BOLD),
+                Ink::Text,
+            ));
+            let members = |members: &[String]| {
+                members
+                    .iter()
+                    .map(|member| {
+                        TextBlock::new(member, measure, member_size, TextVariant::REGULAR)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let sections = [members(&class.attributes), members(&class.methods)];
+            let width = header
+                .iter()
+                .map(|(text, _)| text.width)
+                .chain(sections.iter().flatten().map(|text| text.width))
+                .fold(font_size * 3., f32::max)
+                + padding * 2.;
+            let height = header.iter().map(|(text, _)| text.height()).sum::<f32>()
+                + sections
+                    .iter()
+                    .map(|section| {
+                        section.iter().map(TextBlock::height).sum::<f32>() + padding * 1.5
+                    })
+                    .sum::<f32>()
+                + padding * 2.;
+            ClassBox {
+                header,
+                sections,
+                width,
+                height,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let edges = diagram
+        .relations
+        .iter()
+        .map(|relation| {
+            if !relation.from_end.is_parent() && relation.to_end.is_parent() {
+                (relation.to, relation.from)
+            } else {
+                (relation.from, relation.to)
+            }
+        })
+        .collect::<Vec<_>>();
+    let graph = super::layered_layout::layered_layout(
+        &class_boxes
+            .iter()
+            .map(|class_box| size(class_box.width, class_box.height))
+            .collect::<Vec<_>>(),
+        &edges,
+        diagram.direction,
+        font_size * 4.,
+        font_size * 2.5,
+    );
+    let margin = font_size;
+    let centers = graph
+        .centers
+        .iter()
+        .map(|center| point(center.x + margin, center.y + margin))
+        .collect::<Vec<_>>();
+
+    let mut primitives = Vec::new();
+    let mut markers = Vec::new();
+    let mut labels = Vec::new();
+    let mut cardinalities = Vec::new();
+    for relation in &diagram.relations {
+        if relation.from == relation.to {
+            continue;
+        }
+        let (from_box, to_box) = (&class_boxes[relation.from], &class_boxes[relation.to]);
+        let (from_center, to_center) = (centers[relation.from], centers[relation.to]);
+        let start = clip_to_rect(
+            from_center,
+            from_box.width / 2.,
+            from_box.height / 2.,
+            to_center,
+        );
+        let end = clip_to_rect(
+            to_center,
+            to_box.width / 2.,
+            to_box.height / 2.,
+            from_center,
+        );
+        primitives.push(line(vec![start, end], relation.dashed));
+        push_relation_end(&mut markers, relation.from_end, start, end, font_size);
+        push_relation_end(&mut markers, relation.to_end, end, start, font_size);
+
+        if let Some(label) = &relation.label {
+            let label = TextBlock::new(label, measure, member_size, TextVariant::REGULAR);
+            labels.push((label, point((start.x + end.x) / 2., (start.y + end.y) / 2.)));
+        }
+        for (cardinality, tip, other) in [
+            (&relation.from_cardinality, start, end),
+            (&relation.to_cardinality, end, start),
+        ] {
+            let Some(cardinality) = cardinality else {
+                continue;
+            };
+            let text = TextBlock::new(cardinality, measure, member_size, TextVariant::REGULAR);
+            let (unit, _) = direction(tip, other);
+            let normal = point(-unit.y, unit.x);
+            let along = offset(tip, unit, font_size * 1.4);
+            let center = offset(along, normal, text.width / 2. + font_size * 0.5);
+            text.draw_centered(&mut cardinalities, center, Ink::MutedText);
+        }
+    }
+    primitives.extend(markers);
+
+    for (class_box, center) in class_boxes.iter().zip(&centers) {
+        let left = center.x - class_box.width / 2.;
+        let right = left + class_box.width;
+        let mut y = center.y - class_box.height / 2.;
+        primitives.push(Primitive::Rect {
+            bounds: Bounds {
+                origin: point(left, y),
+                size: size(class_box.width, class_box.height),
+            },
+            corner_radius: 0.,
+            fill: Some(Ink::Surface),
+            stroke: Some(Ink::Line),
+            dashed: false,
+        });
+        y += padding;
+        for (text, ink) in &class_box.header {
+            text.draw(&mut primitives, center.x, y, Align::Center, *ink);
+            y += text.height();
+        }
+        y += padding;
+        for section in &class_box.sections {
+            primitives.push(line(vec![point(left, y), point(right, y)], false));
+            y += padding * 0.75;
+            for text in section {
+                text.draw(&mut primitives, left + padding, y, Align::Left, Ink::Text);
+                y += text.height();
+            }
+            y += padding * 0.75;
+        }
+    }
+    primitives.extend(cardinalities);
+    for (label, center) in &labels {
+        draw_label(&mut primitives, label, *center);
+    }
+
+    Drawing {
+        size: size(
+            graph.size.width + margin * 2.,
+            graph.size.height + margin * 2.,
+        ),
+        primitives,
+    }
+}
+
+fn push_relation_end(
+    primitives: &mut Vec<Primitive>,
+    end: RelationEnd,
+    tip: Point<f32>,
+    from: Point<f32>,
+    font_size: f32,
+) {
+    let (length, half_width) = (font_size * 0.9, font_size * 0.45);
+    match end {
+        RelationEnd::None => {}
+        RelationEnd::Inheritance => primitives.push(Primitive::Polygon {
+            points: marker_points(tip, from, length, half_width, false),
+            fill: Some(Ink::Background),
+            stroke: Some(Ink::Line),
+        }),
+        RelationEnd::Composition | RelationEnd::Aggregation => {
+            primitives.push(Primitive::Polygon {
+                points: marker_points(tip, from, length * 1.2, half_width * 0.8, true),
+                fill: Some(if end == RelationEnd::Composition {
+                    Ink::Line
+                } else {
+                    Ink::Background
+                }),
+                stroke: Some(Ink::Line),
+            })
+        }
+        RelationEnd::Association => primitives.push(open_arrow(tip, from, font_size)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mermaid::{MermaidDiagram, layout_for_test, parse_mermaid};
+
+    #[test]
+    fn test_parse_class_diagram() {
+        let MermaidDiagram::Class(diagram) = parse_mermaid(
+            "classDiagram\n\
+             class Animal {\n\
+             <<abstract>>\n\
+             +String name\n\
+             +speak() String\n\
+             }\n\
+             class List~T~\n\
+             Animal <|-- Dog\n\
+             Dog : +fetch()\n\
+             Owner \"1\" *-- \"many\" Dog : owns\n\
+             List~T~ o-- Animal\n\
+             Dog ..> Owner\n\
+             Dog ..|> Pet",
+        )
+        .unwrap() else {
+            panic!("expected a class diagram");
+        };
+        let classes = diagram
+            .classes
+            .iter()
+            .map(|class| {
+                (
+                    class.name.as_str(),
+                    class.annotation.as_deref(),
+                    class.attributes.len(),
+                    class.methods.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            classes,
+            [
+                ("Animal", Some("abstract"), 1, 1),
+                ("List<T>", None, 0, 0),
+                ("Dog", None, 0, 1),
+                ("Owner", None, 0, 0),
+                ("Pet", None, 0, 0),
+            ]
+        );
+
+        use RelationEnd::{Aggregation, Association, Composition, Inheritance};
+        let relations = diagram
+            .relations
+            .iter()
+            .map(|relation| {
+                (
+                    relation.from,
+                    relation.to,
+                    relation.from_end,
+                    relation.to_end,
+                    relation.dashed,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            relations,
+            [
+                (0, 2, Inheritance, RelationEnd::None, false),
+                (3, 2, Composition, RelationEnd::None, false),
+                (1, 0, Aggregation, RelationEnd::None, false),
+                (2, 3, RelationEnd::None, Association, true),
+                (2, 4, RelationEnd::None, Inheritance, true),
+            ]
+        );
+        let owns = &diagram.relations[1];
+        assert_eq!(owns.label.as_deref(), Some("owns"));
+        assert_eq!(owns.from_cardinality.as_deref(), Some("1"));
+        assert_eq!(owns.to_cardinality.as_deref(), Some("many"));
+
+        assert!(parse_mermaid("classDiagram\nA <|--").is_err());
+        assert!(parse_mermaid("classDiagram\njust words").is_err());
+    }
+
+    #[test]
+    fn test_layout_class_diagram() {
+        let drawing =
+            layout_for_test("classDiagram\nAnimal <|-- Dog\nAnimal <|-- Cat\nDog : +bark()");
+        let text_y = |text: &str| {
+            drawing
+                .primitives
+                .iter()
+                .find_map(|primitive| match primitive {
+                    Primitive::Text {
+                        text: t, origin, ..
+                    } if t == text => Some(origin.y),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        // Parents are drawn above the classes that inherit from them.
+        assert!(text_y("Animal") < text_y("Dog"));
+        assert!(text_y("Animal") < text_y("Cat"));
+        assert!(text_y("+bark()") > text_y("Dog"));
+
+        let triangles = drawing
+            .primitives
+            .iter()
+            .filter(|primitive| matches!(primitive, Primitive::Polygon { points, .. } if points.len() == 3))
+            .count();
+        assert_eq!(triangles, 2);
+    }
+}
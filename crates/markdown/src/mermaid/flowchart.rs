@@ -0,0 +1,771 @@
+use anyhow::{Result, anyhow, bail};
+use collections::HashMap;
+use gpui::{Bounds, Point, point, size};
+
+use super::{
+    Align, Direction, TextBlock, clip_to_rect, cross, direction, draw_label, filled_arrow, line,
+    normalize_label, offset, with_line,
+};
+use crate::drawing::{Drawing, Ink, MeasureText, Primitive, TextVariant, quadratic_curve};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Flowchart {
+    pub direction: Direction,
+    pub nodes: Vec<FlowNode>,
+    pub edges: Vec<FlowEdge>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowNode {
+    pub id: String,
+    pub label: String,
+    pub shape: NodeShape,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeShape {
+    /// `A[label]`
+    Rectangle,
+    /// `A(label)`
+    Rounded,
+    /// `A([label])`
+    Stadium,
+    /// `A[[label]]`
+    Subroutine,
+    /// `A[(label)]`
+    Cylinder,
+    /// `A((label))`
+    Circle,
+    /// `A{label}`
+    Diamond,
+    /// `A{{label}}`
+    Hexagon,
+    /// `A[/label/]`
+    Parallelogram,
+    /// `A>label]`
+    Asymmetric,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowEdge {
+    pub from: usize,
+    pub to: usize,
+    pub label: Option<String>,
+    pub line: EdgeLine,
+    pub start: EdgeEnd,
+    pub end: EdgeEnd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeLine {
+    Solid,
+    Dotted,
+    Thick,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeEnd {
+    None,
+    Arrow,
+    Circle,
+    Cross,
+}
+
+const IGNORED_STATEMENTS: &[&str] = &[
+    "classDef",
+    "class",
+    "style",
+    "linkStyle",
+    "click",
+    "subgraph",
+    "end",
+    "direction",
+];
+
+pub(super) fn parse<'a>(
+    direction: Direction,
+    statements: impl Iterator<Item = (usize, &'a str)>,
+) -> Result<Flowchart> {
+    let mut parser = FlowchartParser {
+        flowchart: Flowchart {
+            direction,
+            ..Flowchart::default()
+        },
+        node_ids: HashMap::default(),
+    };
+    for (line_number, statement) in statements {
+        let keyword = statement.split_whitespace().next().unwrap_or_default();
+        if IGNORED_STATEMENTS.contains(&keyword) {
+            continue;
+        }
+        with_line(line_number, parser.parse_chain(statement))?;
+    }
+    Ok(parser.flowchart)
+}
+
+struct FlowchartParser {
+    flowchart: Flowchart,
+    node_ids: HashMap<String, usize>,
+}
+
+struct Cursor<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.position += prefix.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Consumes text up to and including `terminator`, returning the text before it.
+    fn take_until(&mut self, terminator: &str) -> Option<&'a str> {
+        let rest = self.rest();
+        // Quoted text may contain the terminator.
+        let search_start = rest
+            .strip_prefix('"')
+            .and_then(|quoted| quoted.find('"'))
+            .map_or(0, |end| end + 2);
+        let end = search_start + rest[search_start..].find(terminator)?;
+        self.position += end + terminator.len();
+        Some(&rest[..end])
+    }
+}
+
+/// Openings of node shapes and their closings, with longer openings first so
+/// that they take precedence.
+const SHAPES: &[(&str, &str, NodeShape)] = &[
+    ("([", "])", NodeShape::Stadium),
+    ("((", "))", NodeShape::Circle),
+    ("[[", "]]", NodeShape::Subroutine),
+    ("[(", ")]", NodeShape::Cylinder),
+    ("[/", "/]", NodeShape::Parallelogram),
+    ("[\\", "\\]", NodeShape::Parallelogram),
+    ("{{", "}}", NodeShape::Hexagon),
+    ("[", "]", NodeShape::Rectangle),
+    ("(", ")", NodeShape::Rounded),
+    ("{", "}", NodeShape::Diamond),
+    (">", "]", NodeShape::Asymmetric),
+];
+
+impl FlowchartParser {
+    fn parse_chain(&mut self, statement: &str) -> Result<()> {
+        let mut cursor = Cursor {
+            text: statement,
+            position: 0,
+        };
+        let mut previous = self.parse_node_group(&mut cursor)?;
+        loop {
+            cursor.skip_whitespace();
+            if cursor.rest().is_empty() {
+                return Ok(());
+            }
+            let link = parse_link(&mut cursor)?;
+            let next = self.parse_node_group(&mut cursor)?;
+            for &from in &previous {
+                for &to in &next {
+                    self.flowchart.edges.push(FlowEdge {
+                        from,
+                        to,
+                        ..link.clone()
+                    });
+                }
+            }
+            previous = next;
+        }
+    }
+
+    fn parse_node_group(&mut self, cursor: &mut Cursor) -> Result<Vec<usize>> {
+        let mut nodes = vec![self.parse_node(cursor)?];
+        loop {
+            cursor.skip_whitespace();
+            if !cursor.eat("&") {
+                return Ok(nodes);
+            }
+            nodes.push(self.parse_node(cursor)?);
+        }
+    }
+
+    fn parse_node(&mut self, cursor: &mut Cursor) -> Result<usize> {
+        cursor.skip_whitespace();
+        let rest = cursor.rest();
+        let id_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        // Dashes are allowed in ids, but not when they start a link.
+        let id_len = rest[..id_len]
+            .find("--")
+            .unwrap_or(id_len)
+            .min(rest[..id_len].trim_end_matches('-').len());
+        if id_len == 0 {
+            bail!("expected a node at {:?}", rest);
+        }
+        let id = &rest[..id_len];
+        cursor.position += id_len;
+
+        let mut shape = None;
+        for &(open, close, node_shape) in SHAPES {
+            if cursor.eat(open) {
+                let label = cursor
+                    .take_until(close)
+                    .ok_or_else(|| anyhow!("expected {close:?} after the label of {id:?}"))?;
+                shape = Some((node_shape, normalize_label(label)));
+                break;
+            }
+        }
+        if cursor.eat(":::") {
+            let rest = cursor.rest();
+            cursor.position += rest
+                .find(|c: char| c.is_whitespace() || c == '&')
+                .unwrap_or(rest.len());
+        }
+
+        let index = *self.node_ids.entry(id.to_string()).or_insert_with(|| {
+            self.flowchart.nodes.push(FlowNode {
+                id: id.to_string(),
+                label: id.to_string(),
+                shape: NodeShape::Rectangle,
+            });
+            self.flowchart.nodes.len() - 1
+        });
+        if let Some((shape, label)) = shape {
+            let node = &mut self.flowchart.nodes[index];
+            node.shape = shape;
+            node.label = label;
+        }
+        Ok(index)
+    }
+}
+
+fn parse_link(cursor: &mut Cursor) -> Result<FlowEdge> {
+    let start = parse_link_end(cursor, true);
+    let mut stroke = take_stroke(cursor);
+    if stroke.is_empty() {
+        bail!("expected a link at {:?}", cursor.rest());
+    }
+    let mut end = parse_link_end(cursor, false);
+    let mut label = None;
+
+    // `A -- text --> B` places the label inside the link.
+    if end == EdgeEnd::None && stroke.len() == 2 {
+        let closings: &[&str] = match stroke.as_str() {
+            "--" => &["-->", "---", "--x", "--o"],
+            "-." => &[".->", ".-"],
+            _ => &["==>", "==="],
+        };
+        let rest = cursor.rest();
+        if let Some((label_end, _)) = closings
+            .iter()
+            .filter_map(|closing| rest.find(closing).map(|index| (index, closing)))
+            .min()
+        {
+            label = Some(normalize_label(&rest[..label_end]));
+            cursor.position += label_end;
+            stroke.push_str(&take_stroke(cursor));
+            end = parse_link_end(cursor, false);
+        }
+    }
+
+    cursor.skip_whitespace();
+    if cursor.eat("|") {
+        let text = cursor
+            .take_until("|")
+            .ok_or_else(|| anyhow!("expected a closing '|' after the link label"))?;
+        label = Some(normalize_label(text));
+    }
+
+    let line = if stroke.contains('.') {
+        EdgeLine::Dotted
+    } else if stroke.contains('=') {
+        EdgeLine::Thick
+    } else {
+        EdgeLine::Solid
+    };
+    Ok(FlowEdge {
+        from: 0,
+        to: 0,
+        label,
+        line,
+        start,
+        end,
+    })
+}
+
+fn take_stroke(cursor: &mut Cursor) -> String {
+    let rest = cursor.rest();
+    let len = rest
+        .find(|c| !matches!(c, '-' | '.' | '='))
+        .unwrap_or(rest.len());
+    cursor.position += len;
+    rest[..len].to_string()
+}
+
+/// Parses an arrowhead. Letters are only markers when they touch the stroke on
+/// the inside and whitespace on the outside, as they could otherwise be node ids.
+fn parse_link_end(cursor: &mut Cursor, is_start: bool) -> EdgeEnd {
+    let rest = cursor.rest();
+    let Some(c) = cursor.peek() else {
+        return EdgeEnd::None;
+    };
+    let next = rest[c.len_utf8()..].chars().next();
+    let end = match c {
+        '<' if is_start => EdgeEnd::Arrow,
+        '>' if !is_start => EdgeEnd::Arrow,
+        'o' | 'x' => {
+            let is_marker = if is_start {
+                matches!(next, Some('-' | '=' | '.'))
+            } else {
+                next.is_none_or(|next| next.is_whitespace())
+            };
+            if !is_marker {
+                return EdgeEnd::None;
+            }
+            if c == 'o' {
+                EdgeEnd::Circle
+            } else {
+                EdgeEnd::Cross
+            }
+        }
+        _ => return EdgeEnd::None,
+    };
+    cursor.position += 1;
+    end
+}
+
+#[derive(Clone, Copy)]
+struct NodeBox {
+    center: Point<f32>,
+    half_width: f32,
+    half_height: f32,
+    shape: NodeShape,
+}
+
+impl NodeBox {
+    fn bounds(&self) -> Bounds<f32> {
+        Bounds {
+            origin: point(
+                self.center.x - self.half_width,
+                self.center.y - self.half_height,
+            ),
+            size: size(self.half_width * 2., self.half_height * 2.),
+        }
+    }
+
+    /// The point where a line from the center toward `target` leaves the node.
+    fn boundary_toward(&self, target: Point<f32>) -> Point<f32> {
+        let (unit, _) = direction(self.center, target);
+        match self.shape {
+            NodeShape::Circle => offset(self.center, unit, self.half_width),
+            NodeShape::Diamond => offset(
+                self.center,
+                unit,
+                1. / (unit.x.abs() / self.half_width + unit.y.abs() / self.half_height),
+            ),
+            _ => clip_to_rect(self.center, self.half_width, self.half_height, target),
+        }
+    }
+}
+
+pub(super) fn layout(flowchart: &Flowchart, measure: &dyn MeasureText, font_size: f32) -> Drawing {
+    let margin = font_size * 0.5;
+    let labels = flowchart
+        .nodes
+        .iter()
+        .map(|node| TextBlock::new(&node.label, measure, font_size, TextVariant::REGULAR))
+        .collect::<Vec<_>>();
+    let node_sizes = flowchart
+        .nodes
+        .iter()
+        .zip(&labels)
+        .map(|(node, label)| {
+            let width = label.width + font_size * 1.8;
+            let height = label.height() + font_size * 1.2;
+            match node.shape {
+                NodeShape::Circle => {
+                    let diameter = width.max(height);
+                    size(diameter, diameter)
+                }
+                NodeShape::Diamond => size(width + height, height * 1.6),
+                NodeShape::Hexagon
+                | NodeShape::Parallelogram
+                | NodeShape::Asymmetric
+                | NodeShape::Stadium => size(width + height * 0.5, height),
+                NodeShape::Cylinder => size(width, height + font_size * 0.6),
+                NodeShape::Rectangle | NodeShape::Rounded | NodeShape::Subroutine => {
+                    size(width, height)
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let edge_pairs = flowchart
+        .edges
+        .iter()
+        .map(|edge| (edge.from, edge.to))
+        .collect::<Vec<_>>();
+    let has_labels = flowchart.edges.iter().any(|edge| edge.label.is_some());
+    let rank_gap = font_size * if has_labels { 3.5 } else { 2.5 };
+    let graph = super::layered_layout::layered_layout(
+        &node_sizes,
+        &edge_pairs,
+        flowchart.direction,
+        rank_gap,
+        font_size * 2.,
+    );
+
+    let boxes = flowchart
+        .nodes
+        .iter()
+        .zip(&node_sizes)
+        .zip(&graph.centers)
+        .map(|((node, node_size), center)| NodeBox {
+            center: point(center.x + margin, center.y + margin),
+            half_width: node_size.width / 2.,
+            half_height: node_size.height / 2.,
+            shape: node.shape,
+        })
+        .collect::<Vec<_>>();
+
+    let mut primitives = Vec::new();
+    let mut edge_labels = Vec::new();
+    let mut extra_width: f32 = 0.;
+    for edge in &flowchart.edges {
+        let from = boxes[edge.from];
+        let to = boxes[edge.to];
+        let label = edge
+            .label
+            .as_ref()
+            .map(|label| TextBlock::new(label, measure, font_size * 0.9, TextVariant::REGULAR));
+        let width = match edge.line {
+            EdgeLine::Thick => 2.5,
+            EdgeLine::Solid | EdgeLine::Dotted => 1.,
+        };
+        let dashed = edge.line == EdgeLine::Dotted;
+
+        if edge.from == edge.to {
+            // Loop around the node's right side.
+            let bounds = from.bounds();
+            let right = bounds.origin.x + bounds.size.width;
+            let start = point(right, from.center.y - from.half_height * 0.5);
+            let end = point(right, from.center.y + from.half_height * 0.5);
+            let reach = font_size * 1.5;
+            let control = point(right + reach * 1.5, from.center.y);
+            let mut points = vec![start];
+            points.extend(quadratic_curve(start, control, end, 12));
+            primitives.push(Primitive::Polyline {
+                points,
+                width,
+                ink: Ink::Line,
+                dashed,
+            });
+            push_edge_end(&mut primitives, edge.end, end, control, font_size);
+            extra_width = extra_width.max(reach);
+            if let Some(label) = label {
+                let center = point(right + reach + label.width / 2. + 4., from.center.y);
+                extra_width = extra_width.max(reach + label.width + 8.);
+                edge_labels.push((label, center));
+            }
+            continue;
+        }
+
+        let start = from.boundary_toward(to.center);
+        let end = to.boundary_toward(from.center);
+        let (unit, length) = direction(start, end);
+        let inset = |edge_end: EdgeEnd| match edge_end {
+            EdgeEnd::Arrow => font_size * 0.5,
+            EdgeEnd::Circle => font_size * 0.35,
+            EdgeEnd::None | EdgeEnd::Cross => 0.,
+        };
+        primitives.push(Primitive::Polyline {
+            points: vec![
+                offset(start, unit, inset(edge.start).min(length / 2.)),
+                offset(end, unit, -inset(edge.end).min(length / 2.)),
+            ],
+            width,
+            ink: Ink::Line,
+            dashed,
+        });
+        push_edge_end(&mut primitives, edge.start, start, end, font_size);
+        push_edge_end(&mut primitives, edge.end, end, start, font_size);
+        if let Some(label) = label {
+            let center = point((start.x + end.x) / 2., (start.y + end.y) / 2.);
+            edge_labels.push((label, center));
+        }
+    }
+
+    for (node, (label, node_box)) in flowchart.nodes.iter().zip(labels.iter().zip(&boxes)) {
+        push_node_shape(&mut primitives, node.shape, node_box, font_size);
+        label.draw(
+            &mut primitives,
+            node_box.center.x,
+            node_box.center.y - label.height() / 2.
+                + if node.shape == NodeShape::Cylinder {
+                    font_size * 0.3
+                } else {
+                    0.
+                },
+            Align::Center,
+            Ink::Text,
+        );
+    }
+    for (label, center) in &edge_labels {
+        draw_label(&mut primitives, label, *center);
+    }
+
+    Drawing {
+        size: size(
+            graph.size.width + margin * 2. + extra_width,
+            graph.size.height + margin * 2.,
+        ),
+        primitives,
+    }
+}
+
+fn push_edge_end(
+    primitives: &mut Vec<Primitive>,
+    edge_end: EdgeEnd,
+    tip: Point<f32>,
+    from: Point<f32>,
+    font_size: f32,
+) {
+    match edge_end {
+        EdgeEnd::None => {}
+        EdgeEnd::Arrow => primitives.push(filled_arrow(tip, from, font_size)),
+        EdgeEnd::Circle => {
+            let (unit, _) = direction(from, tip);
+            let radius = font_size * 0.18;
+            let center = offset(tip, unit, -radius);
+            primitives.push(Primitive::Rect {
+                bounds: Bounds {
+                    origin: point(center.x - radius, center.y - radius),
+                    size: size(radius * 2., radius * 2.),
+                },
+                corner_radius: radius,
+                fill: Some(Ink::Line),
+                stroke: None,
+                dashed: false,
+            });
+        }
+        EdgeEnd::Cross => {
+            let (unit, _) = direction(from, tip);
+            primitives.extend(cross(offset(tip, unit, -font_size * 0.3), font_size));
+        }
+    }
+}
+
+fn push_node_shape(
+    primitives: &mut Vec<Primitive>,
+    shape: NodeShape,
+    node: &NodeBox,
+    font_size: f32,
+) {
+    let bounds = node.bounds();
+    let (left, top) = (bounds.origin.x, bounds.origin.y);
+    let (right, bottom) = (left + bounds.size.width, top + bounds.size.height);
+    let (cx, cy) = (node.center.x, node.center.y);
+    let rect = |corner_radius: f32| Primitive::Rect {
+        bounds,
+        corner_radius,
+        fill: Some(Ink::Surface),
+        stroke: Some(Ink::Line),
+        dashed: false,
+    };
+    let polygon = |points: Vec<Point<f32>>| Primitive::Polygon {
+        points,
+        fill: Some(Ink::Surface),
+        stroke: Some(Ink::Line),
+    };
+    let slant = bounds.size.height * 0.25;
+    match shape {
+        NodeShape::Rectangle => primitives.push(rect(0.)),
+        NodeShape::Rounded => primitives.push(rect(font_size * 0.4)),
+        NodeShape::Stadium | NodeShape::Circle => primitives.push(rect(node.half_height)),
+        NodeShape::Subroutine => {
+            primitives.push(rect(0.));
+            let inset = font_size * 0.4;
+            for x in [left + inset, right - inset] {
+                primitives.push(line(vec![point(x, top), point(x, bottom)], false));
+            }
+        }
+        NodeShape::Cylinder => {
+            let rim = font_size * 0.3;
+            primitives.push(rect(rim));
+            let mut rim_line = vec![point(left, top + rim)];
+            rim_line.extend(quadratic_curve(
+                point(left, top + rim),
+                point(cx, top + rim * 3.),
+                point(right, top + rim),
+                12,
+            ));
+            primitives.push(line(rim_line, false));
+        }
+        NodeShape::Diamond => primitives.push(polygon(vec![
+            point(cx, top),
+            point(right, cy),
+            point(cx, bottom),
+            point(left, cy),
+        ])),
+        NodeShape::Hexagon => primitives.push(polygon(vec![
+            point(left + slant, top),
+            point(right - slant, top),
+            point(right, cy),
+            point(right - slant, bottom),
+            point(left + slant, bottom),
+            point(left, cy),
+        ])),
+        NodeShape::Parallelogram => primitives.push(polygon(vec![
+            point(left + slant, top),
+            point(right, top),
+            point(right - slant, bottom),
+            point(left, bottom),
+        ])),
+        NodeShape::Asymmetric => primitives.push(polygon(vec![
+            point(left, top),
+            point(right, top),
+            point(right, bottom),
+            point(left, bottom),
+            point(left + slant, cy),
+        ])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mermaid::{MermaidDiagram, layout_for_test, parse_mermaid};
+
+    fn parse_flowchart(source: &str) -> Flowchart {
+        match parse_mermaid(source).unwrap() {
+            MermaidDiagram::Flowchart(flowchart) => flowchart,
+            diagram => panic!("expected a flowchart, got {diagram:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_flowchart() {
+        let flowchart = parse_flowchart(
+            "flowchart TD\n\
+             A[\"Start<br>here\"] --> B{Is it?}\n\
+             B -- Yes --> C([Done]) & D((Retry))\n\
+             B -.->|No| A\n\
+             C ==> E[(Store)]\n\
+             D --- E\n\
+             E <--> F>Flag] --x G{{Hex}}\n\
+             style A fill:#f9f\n\
+             subgraph one\n\
+             my-node[/Lean/] --o A\n\
+             end",
+        );
+        let nodes = flowchart
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node.label.as_str(), node.shape))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nodes,
+            [
+                ("A", "Start\nhere", NodeShape::Rectangle),
+                ("B", "Is it?", NodeShape::Diamond),
+                ("C", "Done", NodeShape::Stadium),
+                ("D", "Retry", NodeShape::Circle),
+                ("E", "Store", NodeShape::Cylinder),
+                ("F", "Flag", NodeShape::Asymmetric),
+                ("G", "Hex", NodeShape::Hexagon),
+                ("my-node", "Lean", NodeShape::Parallelogram),
+            ]
+        );
+
+        let edges = flowchart
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    edge.from,
+                    edge.to,
+                    edge.label.as_deref(),
+                    edge.line,
+                    edge.start,
+                    edge.end,
+                )
+            })
+            .collect::<Vec<_>>();
+        use EdgeEnd::{Arrow, Circle, Cross};
+        use EdgeLine::{Dotted, Solid, Thick};
+        assert_eq!(
+            edges,
+            [
+                (0, 1, None, Solid, EdgeEnd::None, Arrow),
+                (1, 2, Some("Yes"), Solid, EdgeEnd::None, Arrow),
+                (1, 3, Some("Yes"), Solid, EdgeEnd::None, Arrow),
+                (1, 0, Some("No"), Dotted, EdgeEnd::None, Arrow),
+                (2, 4, None, Thick, EdgeEnd::None, Arrow),
+                (3, 4, None, Solid, EdgeEnd::None, EdgeEnd::None),
+                (4, 5, None, Solid, Arrow, Arrow),
+                (5, 6, None, Solid, EdgeEnd::None, Cross),
+                (7, 0, None, Solid, EdgeEnd::None, Circle),
+            ]
+        );
+
+        assert_eq!(
+            parse_mermaid("graph LR\nA --> [oops]")
+                .unwrap_err()
+                .chain()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>(),
+            ["line 2", "expected a node at \"[oops]\""]
+        );
+        assert!(parse_mermaid("graph LR\nA[unclosed --> B").is_err());
+    }
+
+    #[test]
+    fn test_layout_flowchart() {
+        let drawing = layout_for_test("graph LR\nA[Start] -->|go| B(End)\nB --> B");
+        let texts = drawing.texts().collect::<Vec<_>>();
+        assert_eq!(texts, ["Start", "End", "go"]);
+
+        let text_x = |text: &str| {
+            drawing
+                .primitives
+                .iter()
+                .find_map(|primitive| match primitive {
+                    Primitive::Text {
+                        text: t, origin, ..
+                    } if t == text => Some(origin.x),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        // Left to right: the label sits between the two nodes.
+        assert!(text_x("Start") < text_x("go"));
+        assert!(text_x("go") < text_x("End"));
+
+        // Every primitive fits in the drawing.
+        for primitive in &drawing.primitives {
+            if let Primitive::Polyline { points, .. } | Primitive::Polygon { points, .. } =
+                primitive
+            {
+                for point in points {
+                    assert!(point.x >= 0. && point.x <= drawing.size.width, "{point:?}");
+                    assert!(point.y >= 0. && point.y <= drawing.size.height, "{point:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,375 @@
+//! A simple layered graph layout in the spirit of Sugiyama's method, shared by
+//! flowcharts and class diagrams.
+
+use gpui::{Point, Size, point, size};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    TopToBottom,
+    BottomToTop,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Direction {
+    pub(super) fn from_mermaid(direction: &str) -> Option<Self> {
+        match direction {
+            "TB" | "TD" => Some(Self::TopToBottom),
+            "BT" => Some(Self::BottomToTop),
+            "LR" => Some(Self::LeftToRight),
+            "RL" => Some(Self::RightToLeft),
+            _ => None,
+        }
+    }
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, Self::LeftToRight | Self::RightToLeft)
+    }
+}
+
+pub(super) struct LayeredLayout {
+    /// The center of each node.
+    pub centers: Vec<Point<f32>>,
+    pub size: Size<f32>,
+}
+
+/// Assigns nodes to ranks along `direction` and positions them so that edges
+/// mostly point forward and rarely cross.
+pub(super) fn layered_layout(
+    node_sizes: &[Size<f32>],
+    edges: &[(usize, usize)],
+    direction: Direction,
+    rank_gap: f32,
+    node_gap: f32,
+) -> LayeredLayout {
+    let node_count = node_sizes.len();
+    if node_count == 0 {
+        return LayeredLayout {
+            centers: Vec::new(),
+            size: Size::default(),
+        };
+    }
+
+    let edges = acyclic_edges(node_count, edges);
+    let mut predecessors = vec![Vec::new(); node_count];
+    let mut successors = vec![Vec::new(); node_count];
+    for &(from, to) in &edges {
+        predecessors[to].push(from);
+        successors[from].push(to);
+    }
+
+    let ranks = assign_ranks(&predecessors, &successors);
+    let rank_count = ranks.iter().max().map_or(0, |rank| rank + 1);
+    let mut layers = vec![Vec::new(); rank_count];
+    for (node, &rank) in ranks.iter().enumerate() {
+        layers[rank].push(node);
+    }
+    order_layers(&mut layers, &predecessors, &successors);
+
+    // Lay out as if the graph flowed from top to bottom, with `cross` being the
+    // horizontal extent of each node and `main` the vertical one.
+    let (cross, main): (Vec<f32>, Vec<f32>) = node_sizes
+        .iter()
+        .map(|size| {
+            if direction.is_horizontal() {
+                (size.height, size.width)
+            } else {
+                (size.width, size.height)
+            }
+        })
+        .unzip();
+
+    let mut main_positions = vec![0.; node_count];
+    let mut main_extent = 0.;
+    for layer in &layers {
+        let thickness = layer.iter().map(|&node| main[node]).fold(0., f32::max);
+        for &node in layer {
+            main_positions[node] = main_extent + thickness / 2.;
+        }
+        main_extent += thickness + rank_gap;
+    }
+    main_extent -= rank_gap;
+
+    let cross_positions = position_layers(&layers, &cross, &predecessors, &successors, node_gap);
+    let cross_extent = (0..node_count)
+        .map(|node| cross_positions[node] + cross[node] / 2.)
+        .fold(0., f32::max);
+
+    let centers = (0..node_count)
+        .map(|node| {
+            let (cross_position, main_position) = (cross_positions[node], main_positions[node]);
+            match direction {
+                Direction::TopToBottom => point(cross_position, main_position),
+                Direction::BottomToTop => point(cross_position, main_extent - main_position),
+                Direction::LeftToRight => point(main_position, cross_position),
+                Direction::RightToLeft => point(main_extent - main_position, cross_position),
+            }
+        })
+        .collect();
+    let size = if direction.is_horizontal() {
+        size(main_extent, cross_extent)
+    } else {
+        size(cross_extent, main_extent)
+    };
+    LayeredLayout { centers, size }
+}
+
+/// Drops self-loops and reverses the edges that close a cycle, found through a
+/// depth-first search, so that every remaining edge can point forward.
+fn acyclic_edges(node_count: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        OnStack,
+        Done,
+    }
+
+    let mut adjacency = vec![Vec::new(); node_count];
+    for &(from, to) in edges {
+        if from != to {
+            adjacency[from].push(to);
+        }
+    }
+
+    let mut state = vec![State::Unvisited; node_count];
+    let mut result = Vec::new();
+    for root in 0..node_count {
+        if state[root] != State::Unvisited {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        state[root] = State::OnStack;
+        while let Some((node, next_edge)) = stack.last_mut() {
+            let node = *node;
+            if let Some(&target) = adjacency[node].get(*next_edge) {
+                *next_edge += 1;
+                match state[target] {
+                    State::Unvisited => {
+                        result.push((node, target));
+                        state[target] = State::OnStack;
+                        stack.push((target, 0));
+                    }
+                    State::OnStack => result.push((target, node)),
+                    State::Done => result.push((node, target)),
+                }
+            } else {
+                state[node] = State::Done;
+                stack.pop();
+            }
+        }
+    }
+    result
+}
+
+/// Ranks nodes by their longest path from a source, then pulls sources down so
+/// that they sit right above their closest successor.
+fn assign_ranks(predecessors: &[Vec<usize>], successors: &[Vec<usize>]) -> Vec<usize> {
+    let node_count = predecessors.len();
+    let mut remaining_predecessors = predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+    let mut queue = (0..node_count)
+        .filter(|&node| remaining_predecessors[node] == 0)
+        .collect::<std::collections::VecDeque<_>>();
+    let mut ranks = vec![0_usize; node_count];
+    while let Some(node) = queue.pop_front() {
+        for &successor in &successors[node] {
+            ranks[successor] = ranks[successor].max(ranks[node] + 1);
+            remaining_predecessors[successor] -= 1;
+            if remaining_predecessors[successor] == 0 {
+                queue.push_back(successor);
+            }
+        }
+    }
+
+    for node in 0..node_count {
+        if predecessors[node].is_empty()
+            && let Some(closest) = successors[node].iter().map(|&node| ranks[node]).min()
+        {
+            ranks[node] = closest.saturating_sub(1);
+        }
+    }
+    ranks
+}
+
+/// Reduces crossings by repeatedly sorting each layer by the average position
+/// of its neighbors in the previous layers, alternating sweep directions.
+fn order_layers(layers: &mut [Vec<usize>], predecessors: &[Vec<usize>], successors: &[Vec<usize>]) {
+    let node_count = predecessors.len();
+    let mut positions = vec![0.; node_count];
+    for layer in layers.iter() {
+        for (index, &node) in layer.iter().enumerate() {
+            positions[node] = index as f32;
+        }
+    }
+
+    for iteration in 0..4_usize {
+        let downward = iteration.is_multiple_of(2);
+        let layer_indices = if downward {
+            (1..layers.len()).collect::<Vec<_>>()
+        } else {
+            (0..layers.len().saturating_sub(1)).rev().collect()
+        };
+        let neighbors = if downward { predecessors } else { successors };
+        for layer_index in layer_indices {
+            let mut keyed = layers[layer_index]
+                .iter()
+                .map(|&node| {
+                    let barycenter = if neighbors[node].is_empty() {
+                        positions[node]
+                    } else {
+                        neighbors[node]
+                            .iter()
+                            .map(|&neighbor| positions[neighbor])
+                            .sum::<f32>()
+                            / neighbors[node].len() as f32
+                    };
+                    (barycenter, node)
+                })
+                .collect::<Vec<_>>();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[layer_index] = keyed.into_iter().map(|(_, node)| node).collect();
+            for (index, &node) in layers[layer_index].iter().enumerate() {
+                positions[node] = index as f32;
+            }
+        }
+    }
+}
+
+/// Computes the center of each node across the flow, keeping the order within
+/// each layer while moving nodes toward the nodes they're connected to.
+fn position_layers(
+    layers: &[Vec<usize>],
+    extents: &[f32],
+    predecessors: &[Vec<usize>],
+    successors: &[Vec<usize>],
+    gap: f32,
+) -> Vec<f32> {
+    let mut positions = vec![0.; extents.len()];
+    for layer in layers {
+        let mut x = 0.;
+        for &node in layer {
+            positions[node] = x + extents[node] / 2.;
+            x += extents[node] + gap;
+        }
+    }
+
+    let widest = layers
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| {
+            let width =
+                |layer: &Vec<usize>| -> f32 { layer.iter().map(|&node| extents[node] + gap).sum() };
+            width(a).total_cmp(&width(b))
+        })
+        .map_or(0, |(index, _)| index);
+
+    let align = |layer: &[usize], neighbors: &[Vec<usize>], positions: &mut Vec<f32>| {
+        let desired = layer
+            .iter()
+            .map(|&node| {
+                if neighbors[node].is_empty() {
+                    None
+                } else {
+                    Some(
+                        neighbors[node]
+                            .iter()
+                            .map(|&neighbor| positions[neighbor])
+                            .sum::<f32>()
+                            / neighbors[node].len() as f32,
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+        if desired.iter().all(Option::is_none) {
+            return;
+        }
+        let mut placed = layer
+            .iter()
+            .zip(&desired)
+            .map(|(&node, desired)| desired.unwrap_or(positions[node]))
+            .collect::<Vec<_>>();
+        for index in 1..layer.len() {
+            let min =
+                placed[index - 1] + (extents[layer[index - 1]] + extents[layer[index]]) / 2. + gap;
+            placed[index] = placed[index].max(min);
+        }
+        // Pushing nodes apart shifts the whole layer to one side, so recenter
+        // it on the positions it wanted.
+        let (shift, count) = placed
+            .iter()
+            .zip(&desired)
+            .filter_map(|(placed, desired)| desired.map(|desired| desired - placed))
+            .fold((0., 0.), |(sum, count), shift| (sum + shift, count + 1.));
+        let shift = shift / count;
+        for (&node, placed) in layer.iter().zip(placed) {
+            positions[node] = placed + shift;
+        }
+    };
+
+    for layer in &layers[widest + 1..] {
+        align(layer, predecessors, &mut positions);
+    }
+    for layer in layers[..widest].iter().rev() {
+        align(layer, successors, &mut positions);
+    }
+
+    let min = positions
+        .iter()
+        .zip(extents)
+        .map(|(position, extent)| position - extent / 2.)
+        .fold(f32::INFINITY, f32::min);
+    for position in &mut positions {
+        *position -= min;
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(edges: &[(usize, usize)], node_count: usize, direction: Direction) -> LayeredLayout {
+        layered_layout(
+            &vec![size(20., 10.); node_count],
+            edges,
+            direction,
+            30.,
+            10.,
+        )
+    }
+
+    #[test]
+    fn test_layered_layout() {
+        // A diamond: 0 -> 1, 0 -> 2, 1 -> 3, 2 -> 3.
+        let edges = [(0, 1), (0, 2), (1, 3), (2, 3)];
+        let result = layout(&edges, 4, Direction::TopToBottom);
+        let centers = &result.centers;
+        assert_eq!(centers[0].y, 5.);
+        assert_eq!(centers[1].y, 45.);
+        assert_eq!(centers[2].y, 45.);
+        assert_eq!(centers[3].y, 85.);
+        assert_eq!(centers[1].x, 10.);
+        assert_eq!(centers[2].x, 40.);
+        assert_eq!(centers[0].x, 25.);
+        assert_eq!(centers[3].x, 25.);
+        assert_eq!(result.size, size(50., 90.));
+
+        let result = layout(&edges, 4, Direction::RightToLeft);
+        // Nodes are wider than they're tall, so the ranks are further apart.
+        assert_eq!(result.centers[0].x, 110.);
+        assert_eq!(result.centers[3].x, 10.);
+        assert_eq!(result.centers[1].y, 5.);
+        assert_eq!(result.size, size(120., 30.));
+    }
+
+    #[test]
+    fn test_layered_layout_cycles() {
+        let result = layout(&[(0, 1), (1, 2), (2, 0), (2, 2)], 3, Direction::TopToBottom);
+        let ys = result
+            .centers
+            .iter()
+            .map(|center| center.y)
+            .collect::<Vec<_>>();
+        assert_eq!(ys, [5., 45., 85.]);
+    }
+}
@@ -0,0 +1,850 @@
+use anyhow::{Result, anyhow, bail};
+use gpui::{Bounds, Point, point, size};
+
+use super::{Align, TextBlock, cross, filled_arrow, line, normalize_label, open_arrow, with_line};
+use crate::drawing::{Drawing, Ink, MeasureText, Primitive, TextVariant};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SequenceDiagram {
+    pub participants: Vec<Participant>,
+    pub items: Vec<SequenceItem>,
+    pub autonumber: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Participant {
+    pub id: String,
+    pub label: String,
+    pub kind: ParticipantKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticipantKind {
+    Participant,
+    Actor,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SequenceItem {
+    Message(Message),
+    Note {
+        placement: NotePlacement,
+        text: String,
+    },
+    /// The start of a `loop`, `alt`, `opt`, `par`, `critical`, `break` or `rect` block.
+    BlockStart {
+        kind: String,
+        label: String,
+    },
+    /// An `else`, `and` or `option` section within a block.
+    BlockSection {
+        kind: String,
+        label: String,
+    },
+    BlockEnd,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub from: usize,
+    pub to: usize,
+    pub text: String,
+    pub dotted: bool,
+    pub arrow: ArrowHead,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArrowHead {
+    /// `->`
+    None,
+    /// `->>`
+    Filled,
+    /// `-)`, an asynchronous message.
+    Open,
+    /// `-x`
+    Cross,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotePlacement {
+    LeftOf(usize),
+    RightOf(usize),
+    Over(usize, usize),
+}
+
+/// Message arrows, with longer arrows first so that they take precedence.
+const ARROWS: &[(&str, bool, ArrowHead)] = &[
+    ("-->>", true, ArrowHead::Filled),
+    ("->>", false, ArrowHead::Filled),
+    ("--x", true, ArrowHead::Cross),
+    ("-x", false, ArrowHead::Cross),
+    ("--)", true, ArrowHead::Open),
+    ("-)", false, ArrowHead::Open),
+    ("-->", true, ArrowHead::None),
+    ("->", false, ArrowHead::None),
+];
+
+const BLOCK_STARTS: &[&str] = &["loop", "alt", "opt", "par", "critical", "break", "rect"];
+const BLOCK_SECTIONS: &[&str] = &["else", "and", "option"];
+const IGNORED_STATEMENTS: &[&str] = &["title", "activate", "deactivate", "destroy", "box"];
+
+pub(super) fn parse<'a>(
+    statements: impl Iterator<Item = (usize, &'a str)>,
+) -> Result<SequenceDiagram> {
+    let mut diagram = SequenceDiagram::default();
+    let mut depth = 0_usize;
+    for (line_number, statement) in statements {
+        let statement = statement.strip_prefix("create ").unwrap_or(statement);
+        let (keyword, rest) = statement
+            .split_once(char::is_whitespace)
+            .map_or((statement, ""), |(keyword, rest)| (keyword, rest.trim()));
+        let result = if keyword == "participant" || keyword == "actor" {
+            let kind = if keyword == "actor" {
+                ParticipantKind::Actor
+            } else {
+                ParticipantKind::Participant
+            };
+            let (id, label) = rest
+                .split_once(" as ")
+                .map_or((rest, rest), |(id, label)| (id.trim(), label.trim()));
+            let index = diagram.participant(id);
+            let participant = &mut diagram.participants[index];
+            participant.label = normalize_label(label);
+            participant.kind = kind;
+            Ok(())
+        } else if keyword == "autonumber" {
+            diagram.autonumber = true;
+            Ok(())
+        } else if IGNORED_STATEMENTS.contains(&keyword) {
+            Ok(())
+        } else if keyword.eq_ignore_ascii_case("note") {
+            diagram.parse_note(rest)
+        } else if BLOCK_STARTS.contains(&keyword) {
+            depth += 1;
+            diagram.items.push(SequenceItem::BlockStart {
+                kind: keyword.to_string(),
+                label: normalize_label(rest),
+            });
+            Ok(())
+        } else if BLOCK_SECTIONS.contains(&keyword) {
+            if depth == 0 {
+                Err(anyhow!("{keyword:?} outside of a block"))
+            } else {
+                diagram.items.push(SequenceItem::BlockSection {
+                    kind: keyword.to_string(),
+                    label: normalize_label(rest),
+                });
+                Ok(())
+            }
+        } else if statement == "end" {
+            if depth == 0 {
+                Err(anyhow!("\"end\" without a matching block"))
+            } else {
+                depth -= 1;
+                diagram.items.push(SequenceItem::BlockEnd);
+                Ok(())
+            }
+        } else {
+            diagram.parse_message(statement)
+        };
+        with_line(line_number, result)?;
+    }
+    for _ in 0..depth {
+        diagram.items.push(SequenceItem::BlockEnd);
+    }
+    Ok(diagram)
+}
+
+impl SequenceDiagram {
+    fn participant(&mut self, id: &str) -> usize {
+        if let Some(index) = self.participants.iter().position(|p| p.id == id) {
+            index
+        } else {
+            self.participants.push(Participant {
+                id: id.to_string(),
+                label: id.to_string(),
+                kind: ParticipantKind::Participant,
+            });
+            self.participants.len() - 1
+        }
+    }
+
+    fn parse_note(&mut self, note: &str) -> Result<()> {
+        let (position, text) = note
+            .split_once(':')
+            .ok_or_else(|| anyhow!("expected ':' before the text of the note"))?;
+        let position = position.trim();
+        let placement = if let Some(id) = position.strip_prefix("left of ") {
+            NotePlacement::LeftOf(self.participant(id.trim()))
+        } else if let Some(id) = position.strip_prefix("right of ") {
+            NotePlacement::RightOf(self.participant(id.trim()))
+        } else if let Some(ids) = position.strip_prefix("over ") {
+            match ids.split_once(',') {
+                Some((first, second)) => {
+                    let first = self.participant(first.trim());
+                    let second = self.participant(second.trim());
+                    NotePlacement::Over(first.min(second), first.max(second))
+                }
+                None => {
+                    let index = self.participant(ids.trim());
+                    NotePlacement::Over(index, index)
+                }
+            }
+        } else {
+            bail!("unknown note position {position:?}");
+        };
+        self.items.push(SequenceItem::Note {
+            placement,
+            text: normalize_label(text),
+        });
+        Ok(())
+    }
+
+    fn parse_message(&mut self, statement: &str) -> Result<()> {
+        let (arrow_start, arrow, dotted, arrow_head) = statement
+            .match_indices('-')
+            .find_map(|(index, _)| {
+                ARROWS.iter().find_map(|&(arrow, dotted, head)| {
+                    statement[index..]
+                        .starts_with(arrow)
+                        .then_some((index, arrow, dotted, head))
+                })
+            })
+            .ok_or_else(|| anyhow!("expected a message, like \"A->>B: text\""))?;
+        let from = statement[..arrow_start].trim();
+        let rest = &statement[arrow_start + arrow.len()..];
+        let (to, text) = rest.split_once(':').unwrap_or((rest, ""));
+        let to = to.trim().trim_start_matches(['+', '-']).trim();
+        if from.is_empty() || to.is_empty() {
+            bail!("expected a participant on both sides of the message");
+        }
+        let from = self.participant(from);
+        let to = self.participant(to);
+        self.items.push(SequenceItem::Message(Message {
+            from,
+            to,
+            text: normalize_label(text),
+            dotted,
+            arrow: arrow_head,
+        }));
+        Ok(())
+    }
+}
+
+struct Frame {
+    kind: String,
+    label: TextBlock,
+    top: f32,
+    left: f32,
+    right: f32,
+    sections: Vec<(f32, TextBlock)>,
+}
+
+pub(super) fn layout(
+    diagram: &SequenceDiagram,
+    measure: &dyn MeasureText,
+    font_size: f32,
+) -> Drawing {
+    let padding = font_size * 0.6;
+    let gap = font_size * 2.;
+    let participant_count = diagram.participants.len();
+    let names = diagram
+        .participants
+        .iter()
+        .map(|participant| {
+            TextBlock::new(&participant.label, measure, font_size, TextVariant::REGULAR)
+        })
+        .collect::<Vec<_>>();
+    let box_widths = names
+        .iter()
+        .map(|name| (name.width + padding * 3.).max(font_size * 4.))
+        .collect::<Vec<_>>();
+    let name_height = names.iter().map(TextBlock::height).fold(0., f32::max);
+    let has_actors = diagram
+        .participants
+        .iter()
+        .any(|participant| participant.kind == ParticipantKind::Actor);
+    let figure_height = if has_actors { font_size * 2.5 } else { 0. };
+    let header_height = name_height + padding * 2. + figure_height;
+
+    let mut sequence_number = 0;
+    let message_texts = diagram
+        .items
+        .iter()
+        .map(|item| match item {
+            SequenceItem::Message(message) => {
+                let text = if diagram.autonumber {
+                    sequence_number += 1;
+                    format!("{sequence_number}. {}", message.text)
+                } else {
+                    message.text.clone()
+                };
+                Some(TextBlock::new(
+                    &text,
+                    measure,
+                    font_size * 0.9,
+                    TextVariant::REGULAR,
+                ))
+            }
+            SequenceItem::Note { text, .. } => Some(TextBlock::new(
+                text,
+                measure,
+                font_size * 0.9,
+                TextVariant::REGULAR,
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Distances between neighboring lifelines, widened until every message and
+    // note fits between the lifelines it spans.
+    let mut gaps = (1..participant_count)
+        .map(|index| (box_widths[index - 1] + box_widths[index]) / 2. + gap)
+        .collect::<Vec<_>>();
+    let mut left_overhang: f32 = box_widths.first().map_or(0., |width| width / 2.);
+    let mut right_overhang: f32 = box_widths.last().map_or(0., |width| width / 2.);
+    let require = |gaps: &mut Vec<f32>, start: usize, end: usize, distance: f32| {
+        let current: f32 = gaps[start..end].iter().sum();
+        if current < distance {
+            gaps[end - 1] += distance - current;
+        }
+    };
+    for (item, text) in diagram.items.iter().zip(&message_texts) {
+        let Some(text) = text else {
+            continue;
+        };
+        let width = text.width + padding * 2.;
+        match item {
+            SequenceItem::Message(message) if message.from == message.to => {
+                let needed = font_size * 1.5 + width;
+                if message.from + 1 < participant_count {
+                    require(&mut gaps, message.from, message.from + 1, needed);
+                } else {
+                    right_overhang = right_overhang.max(needed);
+                }
+            }
+            SequenceItem::Message(message) => {
+                let (start, end) = (message.from.min(message.to), message.from.max(message.to));
+                require(&mut gaps, start, end, width);
+            }
+            SequenceItem::Note { placement, .. } => match *placement {
+                NotePlacement::LeftOf(index) => {
+                    if index > 0 {
+                        require(&mut gaps, index - 1, index, width + padding);
+                    } else {
+                        left_overhang = left_overhang.max(width + padding);
+                    }
+                }
+                NotePlacement::RightOf(index) => {
+                    if index + 1 < participant_count {
+                        require(&mut gaps, index, index + 1, width + padding);
+                    } else {
+                        right_overhang = right_overhang.max(width + padding);
+                    }
+                }
+                NotePlacement::Over(start, end) if start < end => {
+                    require(&mut gaps, start, end, width - padding * 2.);
+                }
+                NotePlacement::Over(index, _) => {
+                    if index > 0 {
+                        let needed = (width + box_widths[index - 1]) / 2. + padding;
+                        require(&mut gaps, index - 1, index, needed);
+                    } else {
+                        left_overhang = left_overhang.max(width / 2.);
+                    }
+                    if index + 1 < participant_count {
+                        let needed = (width + box_widths[index + 1]) / 2. + padding;
+                        require(&mut gaps, index, index + 1, needed);
+                    } else {
+                        right_overhang = right_overhang.max(width / 2.);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    let margin = font_size * 0.5;
+    let mut centers = Vec::with_capacity(participant_count);
+    let mut x = margin + left_overhang;
+    for index in 0..participant_count {
+        centers.push(x);
+        if let Some(gap) = gaps.get(index) {
+            x += gap;
+        }
+    }
+    let mut width = x + right_overhang + margin;
+
+    let mut lifelines = Vec::new();
+    let mut frames_primitives = Vec::new();
+    let mut messages = Vec::new();
+    let mut open_frames: Vec<Frame> = Vec::new();
+    let row_gap = font_size * 0.8;
+    let mut y = margin + header_height + row_gap;
+    let extend_frames = |open_frames: &mut Vec<Frame>, left: f32, right: f32| {
+        for frame in open_frames.iter_mut() {
+            frame.left = frame.left.min(left);
+            frame.right = frame.right.max(right);
+        }
+    };
+
+    for (item, text) in diagram.items.iter().zip(message_texts) {
+        match item {
+            SequenceItem::Message(message) => {
+                let Some(text) = text else {
+                    continue;
+                };
+                let from_x = centers[message.from];
+                let to_x = centers[message.to];
+                if message.from == message.to {
+                    let loop_width = font_size * 1.5;
+                    let top = y + text.height();
+                    let bottom = top + font_size;
+                    text.draw(&mut messages, from_x + padding, y, Align::Left, Ink::Text);
+                    messages.push(line(
+                        vec![
+                            point(from_x, top),
+                            point(from_x + loop_width, top),
+                            point(from_x + loop_width, bottom),
+                            point(from_x + 1., bottom),
+                        ],
+                        message.dotted,
+                    ));
+                    push_arrow_head(
+                        &mut messages,
+                        message.arrow,
+                        point(from_x, bottom),
+                        point(from_x + loop_width, bottom),
+                        font_size,
+                    );
+                    extend_frames(
+                        &mut open_frames,
+                        from_x,
+                        from_x + loop_width.max(text.width + padding),
+                    );
+                    y = bottom + row_gap;
+                } else {
+                    let line_y = y + text.height() + font_size * 0.2;
+                    text.draw(
+                        &mut messages,
+                        (from_x + to_x) / 2.,
+                        y,
+                        Align::Center,
+                        Ink::Text,
+                    );
+                    let direction = if to_x > from_x { 1. } else { -1. };
+                    let inset = match message.arrow {
+                        ArrowHead::Filled => font_size * 0.5,
+                        _ => 0.,
+                    };
+                    messages.push(line(
+                        vec![
+                            point(from_x, line_y),
+                            point(to_x - direction * inset, line_y),
+                        ],
+                        message.dotted,
+                    ));
+                    push_arrow_head(
+                        &mut messages,
+                        message.arrow,
+                        point(to_x, line_y),
+                        point(from_x, line_y),
+                        font_size,
+                    );
+                    extend_frames(&mut open_frames, from_x.min(to_x), from_x.max(to_x));
+                    y = line_y + row_gap;
+                }
+            }
+            SequenceItem::Note { placement, .. } => {
+                let Some(text) = text else {
+                    continue;
+                };
+                let note_width = text.width + padding * 2.;
+                let (left, right) = match *placement {
+                    NotePlacement::LeftOf(index) => {
+                        let right = centers[index] - padding / 2.;
+                        (right - note_width, right)
+                    }
+                    NotePlacement::RightOf(index) => {
+                        let left = centers[index] + padding / 2.;
+                        (left, left + note_width)
+                    }
+                    NotePlacement::Over(start, end) => {
+                        let center = (centers[start] + centers[end]) / 2.;
+                        let half_width =
+                            (note_width / 2.).max((centers[end] - centers[start]) / 2. + padding);
+                        (center - half_width, center + half_width)
+                    }
+                };
+                let height = text.height() + padding;
+                messages.push(Primitive::Rect {
+                    bounds: Bounds {
+                        origin: point(left, y),
+                        size: size(right - left, height),
+                    },
+                    corner_radius: 0.,
+                    fill: Some(Ink::Note),
+                    stroke: Some(Ink::Line),
+                    dashed: false,
+                });
+                text.draw(
+                    &mut messages,
+                    (left + right) / 2.,
+                    y + padding / 2.,
+                    Align::Center,
+                    Ink::Text,
+                );
+                extend_frames(&mut open_frames, left, right);
+                y += height + row_gap;
+            }
+            SequenceItem::BlockStart { kind, label } => {
+                let label = if label.is_empty() {
+                    String::new()
+                } else {
+                    format!("[{label}]")
+                };
+                open_frames.push(Frame {
+                    kind: kind.clone(),
+                    label: TextBlock::new(&label, measure, font_size * 0.9, TextVariant::REGULAR),
+                    top: y,
+                    left: f32::INFINITY,
+                    right: f32::NEG_INFINITY,
+                    sections: Vec::new(),
+                });
+                y += font_size * 1.6;
+            }
+            SequenceItem::BlockSection { label, .. } => {
+                let label = if label.is_empty() {
+                    String::new()
+                } else {
+                    format!("[{label}]")
+                };
+                if let Some(frame) = open_frames.last_mut() {
+                    frame.sections.push((
+                        y,
+                        TextBlock::new(&label, measure, font_size * 0.9, TextVariant::REGULAR),
+                    ));
+                }
+                y += font_size * 1.6;
+            }
+            SequenceItem::BlockEnd => {
+                let Some(frame) = open_frames.pop() else {
+                    continue;
+                };
+                let bottom = y;
+                let tab = TextBlock::new(&frame.kind, measure, font_size * 0.8, TextVariant::BOLD);
+                let tab_width = tab.width + padding * 2.;
+                let tab_height = tab.height() + padding * 0.5;
+                let (mut left, mut right) = if frame.left <= frame.right {
+                    (frame.left - padding * 2., frame.right + padding * 2.)
+                } else {
+                    (
+                        centers.first().copied().unwrap_or(margin) - padding * 2.,
+                        centers.last().copied().unwrap_or(margin) + padding * 2.,
+                    )
+                };
+                right = right.max(left + tab_width + frame.label.width + padding * 2.);
+                left = left.max(0.);
+                width = width.max(right + margin);
+                // Enclosing frames grow to fit this one.
+                extend_frames(&mut open_frames, left - padding, right + padding);
+
+                if frame.kind == "rect" {
+                    frames_primitives.push(Primitive::Rect {
+                        bounds: Bounds {
+                            origin: point(left, frame.top),
+                            size: size(right - left, bottom - frame.top),
+                        },
+                        corner_radius: 0.,
+                        fill: Some(Ink::Surface),
+                        stroke: None,
+                        dashed: false,
+                    });
+                } else {
+                    frames_primitives.push(Primitive::Rect {
+                        bounds: Bounds {
+                            origin: point(left, frame.top),
+                            size: size(right - left, bottom - frame.top),
+                        },
+                        corner_radius: 0.,
+                        fill: None,
+                        stroke: Some(Ink::Line),
+                        dashed: false,
+                    });
+                    frames_primitives.push(Primitive::Polygon {
+                        points: vec![
+                            point(left, frame.top),
+                            point(left + tab_width, frame.top),
+                            point(left + tab_width, frame.top + tab_height * 0.7),
+                            point(left + tab_width - padding * 0.5, frame.top + tab_height),
+                            point(left, frame.top + tab_height),
+                        ],
+                        fill: Some(Ink::Surface),
+                        stroke: Some(Ink::Line),
+                    });
+                    tab.draw(
+                        &mut frames_primitives,
+                        left + padding,
+                        frame.top + padding * 0.25,
+                        Align::Left,
+                        Ink::Text,
+                    );
+                    frame.label.draw(
+                        &mut frames_primitives,
+                        left + tab_width + padding,
+                        frame.top + padding * 0.25,
+                        Align::Left,
+                        Ink::MutedText,
+                    );
+                    for (section_top, label) in &frame.sections {
+                        frames_primitives.push(line(
+                            vec![point(left, *section_top), point(right, *section_top)],
+                            true,
+                        ));
+                        label.draw(
+                            &mut frames_primitives,
+                            (left + right) / 2.,
+                            section_top + padding * 0.25,
+                            Align::Center,
+                            Ink::MutedText,
+                        );
+                    }
+                }
+                y += padding;
+            }
+        }
+    }
+
+    let footer_top = y;
+    let height = footer_top + header_height + margin;
+    let mut participants = Vec::new();
+    for (index, (participant, name)) in diagram.participants.iter().zip(&names).enumerate() {
+        let center = centers[index];
+        lifelines.push(line(
+            vec![
+                point(center, margin + header_height),
+                point(center, footer_top),
+            ],
+            true,
+        ));
+        for top in [margin, footer_top] {
+            let box_width = box_widths[index];
+            match participant.kind {
+                ParticipantKind::Participant => {
+                    let box_top = top + figure_height;
+                    participants.push(Primitive::Rect {
+                        bounds: Bounds {
+                            origin: point(center - box_width / 2., box_top),
+                            size: size(box_width, header_height - figure_height),
+                        },
+                        corner_radius: 3.,
+                        fill: Some(Ink::Surface),
+                        stroke: Some(Ink::Line),
+                        dashed: false,
+                    });
+                    name.draw(
+                        &mut participants,
+                        center,
+                        box_top + padding,
+                        Align::Center,
+                        Ink::Text,
+                    );
+                }
+                ParticipantKind::Actor => {
+                    push_actor_figure(&mut participants, center, top, figure_height);
+                    name.draw(
+                        &mut participants,
+                        center,
+                        top + figure_height + padding,
+                        Align::Center,
+                        Ink::Text,
+                    );
+                }
+            }
+        }
+    }
+
+    let mut primitives = frames_primitives;
+    primitives.extend(lifelines);
+    primitives.extend(messages);
+    primitives.extend(participants);
+    Drawing {
+        size: size(width, height),
+        primitives,
+    }
+}
+
+fn push_arrow_head(
+    primitives: &mut Vec<Primitive>,
+    arrow: ArrowHead,
+    tip: Point<f32>,
+    from: Point<f32>,
+    font_size: f32,
+) {
+    match arrow {
+        ArrowHead::None => {}
+        ArrowHead::Filled => primitives.push(filled_arrow(tip, from, font_size)),
+        ArrowHead::Open => primitives.push(open_arrow(tip, from, font_size)),
+        ArrowHead::Cross => {
+            let direction = if from.x > tip.x { 1. } else { -1. };
+            primitives.extend(cross(
+                point(tip.x + direction * font_size * 0.3, tip.y),
+                font_size,
+            ));
+        }
+    }
+}
+
+/// Draws a stick figure whose head touches `top`.
+fn push_actor_figure(primitives: &mut Vec<Primitive>, center: f32, top: f32, height: f32) {
+    let unit = height / 5.;
+    let head_radius = unit * 0.8;
+    primitives.push(Primitive::Rect {
+        bounds: Bounds {
+            origin: point(center - head_radius, top),
+            size: size(head_radius * 2., head_radius * 2.),
+        },
+        corner_radius: head_radius,
+        fill: None,
+        stroke: Some(Ink::Line),
+        dashed: false,
+    });
+    let neck = top + head_radius * 2.;
+    let hip = neck + unit * 1.6;
+    primitives.push(line(vec![point(center, neck), point(center, hip)], false));
+    primitives.push(line(
+        vec![
+            point(center - unit * 1.2, neck + unit * 0.6),
+            point(center + unit * 1.2, neck + unit * 0.6),
+        ],
+        false,
+    ));
+    primitives.push(line(
+        vec![
+            point(center - unit, top + height - unit * 0.4),
+            point(center, hip),
+            point(center + unit, top + height - unit * 0.4),
+        ],
+        false,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mermaid::{MermaidDiagram, layout_for_test, parse_mermaid};
+
+    #[test]
+    fn test_parse_sequence_diagram() {
+        let MermaidDiagram::Sequence(diagram) = parse_mermaid(
+            "sequenceDiagram\n\
+             autonumber\n\
+             actor U as User\n\
+             participant S as Server\n\
+             U->>+S: Request<br>data\n\
+             loop Every minute\n\
+             S--)U: Ping\n\
+             end\n\
+             alt success\n\
+             S-->>-U: Data\n\
+             else failure\n\
+             S-xU: Error\n\
+             end\n\
+             Note over U,S: Done\n\
+             Note right of S: Idle\n\
+             S->S: think",
+        )
+        .unwrap() else {
+            panic!("expected a sequence diagram");
+        };
+        assert!(diagram.autonumber);
+        assert_eq!(
+            diagram.participants,
+            [
+                Participant {
+                    id: "U".into(),
+                    label: "User".into(),
+                    kind: ParticipantKind::Actor,
+                },
+                Participant {
+                    id: "S".into(),
+                    label: "Server".into(),
+                    kind: ParticipantKind::Participant,
+                },
+            ]
+        );
+        let message = |from, to, text: &str, dotted, arrow| {
+            SequenceItem::Message(Message {
+                from,
+                to,
+                text: text.into(),
+                dotted,
+                arrow,
+            })
+        };
+        assert_eq!(
+            diagram.items,
+            [
+                message(0, 1, "Request\ndata", false, ArrowHead::Filled),
+                SequenceItem::BlockStart {
+                    kind: "loop".into(),
+                    label: "Every minute".into(),
+                },
+                message(1, 0, "Ping", true, ArrowHead::Open),
+                SequenceItem::BlockEnd,
+                SequenceItem::BlockStart {
+                    kind: "alt".into(),
+                    label: "success".into(),
+                },
+                message(1, 0, "Data", true, ArrowHead::Filled),
+                SequenceItem::BlockSection {
+                    kind: "else".into(),
+                    label: "failure".into(),
+                },
+                message(1, 0, "Error", false, ArrowHead::Cross),
+                SequenceItem::BlockEnd,
+                SequenceItem::Note {
+                    placement: NotePlacement::Over(0, 1),
+                    text: "Done".into(),
+                },
+                SequenceItem::Note {
+                    placement: NotePlacement::RightOf(1),
+                    text: "Idle".into(),
+                },
+                message(1, 1, "think", false, ArrowHead::None),
+            ]
+        );
+
+        assert!(parse_mermaid("sequenceDiagram\nend").is_err());
+        assert!(parse_mermaid("sequenceDiagram\nA talks to B").is_err());
+    }
+
+    #[test]
+    fn test_layout_sequence_diagram() {
+        let drawing = layout_for_test(
+            "sequenceDiagram\n\
+             Alice->>Bob: a rather long message that needs room\n\
+             Bob-->>Alice: ok",
+        );
+        let text_x = |text: &str| {
+            drawing
+                .primitives
+                .iter()
+                .filter_map(|primitive| match primitive {
+                    Primitive::Text {
+                        text: t, origin, ..
+                    } if t == text => Some(origin.x),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        // Participants are drawn both above and below the lifelines.
+        assert_eq!(text_x("Alice").len(), 2);
+        let alice = text_x("Alice")[0] + 20.;
+        let bob = text_x("Bob")[0] + 12.;
+        // The lifelines are spread apart so that the long message fits between them.
+        let message_width = "a rather long message that needs room".len() as f32 * 7.2;
+        assert!(bob - alice >= message_width, "{alice} {bob}");
+        assert!(drawing.size.width >= bob);
+    }
+}
@@ -26,15 +26,35 @@ pub fn parse_markdown(
     Vec<(Range<usize>, MarkdownEvent)>,
     HashSet<SharedString>,
     HashSet<Arc<str>>,
+) {
+    parse_markdown_with_options(text, PARSE_OPTIONS)
+}
+
+/// Like [`parse_markdown`], but also parses `$...$` and `$$...$$` as LaTeX math.
+pub fn parse_markdown_with_math(
+    text: &str,
+) -> (
+    Vec<(Range<usize>, MarkdownEvent)>,
+    HashSet<SharedString>,
+    HashSet<Arc<str>>,
+) {
+    parse_markdown_with_options(text, PARSE_OPTIONS.union(Options::ENABLE_MATH))
+}
+
+fn parse_markdown_with_options(
+    text: &str,
+    options: Options,
+) -> (
+    Vec<(Range<usize>, MarkdownEvent)>,
+    HashSet<SharedString>,
+    HashSet<Arc<str>>,
 ) {
     let mut events = Vec::new();
     let mut language_names = HashSet::default();
     let mut language_paths = HashSet::default();
     let mut within_link = false;
     let mut within_metadata = false;
-    let mut parser = Parser::new_ext(text, PARSE_OPTIONS)
-        .into_offset_iter()
-        .peekable();
+    let mut parser = Parser::new_ext(text, options).into_offset_iter().peekable();
     while let Some((pulldown_event, range)) = parser.next() {
         if within_metadata {
             if let pulldown_cmark::Event::End(pulldown_cmark::TagEnd::MetadataBlock { .. }) =
@@ -321,7 +341,12 @@ pub fn parse_markdown(
             pulldown_cmark::Event::TaskListMarker(checked) => {
                 events.push((range, MarkdownEvent::TaskListMarker(checked)))
             }
-            pulldown_cmark::Event::InlineMath(_) | pulldown_cmark::Event::DisplayMath(_) => {}
+            pulldown_cmark::Event::InlineMath(_) => {
+                events.push((range.start + 1..range.end - 1, MarkdownEvent::InlineMath))
+            }
+            pulldown_cmark::Event::DisplayMath(_) => {
+                events.push((range.start + 2..range.end - 2, MarkdownEvent::DisplayMath))
+            }
         }
     }
     (events, language_names, language_paths)
@@ -397,6 +422,10 @@ pub enum MarkdownEvent {
     Rule,
     /// A task list marker, rendered as a checkbox in HTML. Contains a true when it is checked.
     TaskListMarker(bool),
+    /// Inline LaTeX math. The range excludes the surrounding `$`.
+    InlineMath,
+    /// Display LaTeX math. The range excludes the surrounding `$$`.
+    DisplayMath,
 }
 
 /// Tags for elements that can contain other elements.
@@ -523,7 +552,12 @@ fn extract_code_content_range(text: &str) -> Range<usize> {
 
 pub(crate) fn extract_code_block_content_range(text: &str) -> Range<usize> {
     let mut range = 0..text.len();
-    if text.starts_with("```") {
+    let fence = if text.starts_with("~~~") {
+        "~~~"
+    } else {
+        "```"
+    };
+    if text.starts_with(fence) {
         range.start += 3;
 
         if let Some(newline_ix) = text[range.clone()].find('\n') {
@@ -531,7 +565,7 @@ pub(crate) fn extract_code_block_content_range(text: &str) -> Range<usize> {
         }
     }
 
-    if !range.is_empty() && text.ends_with("```") {
+    if !range.is_empty() && text.ends_with(fence) {
         range.end -= 3;
     }
     if range.start > range.end {
@@ -678,6 +712,32 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_math() {
+        let source = "Euler: $e^{i\\pi} + 1 = 0$ and\n$$\\frac{a}{b}$$";
+        assert!(
+            !parse_markdown(source)
+                .0
+                .iter()
+                .any(|(_, event)| matches!(event, InlineMath | DisplayMath))
+        );
+
+        let events = parse_markdown_with_math(source).0;
+        assert_eq!(
+            events,
+            vec![
+                (0..45, Start(Paragraph)),
+                (0..7, Text),
+                (8..24, InlineMath),
+                (25..29, Text),
+                (29..30, SoftBreak),
+                (32..43, DisplayMath),
+                (0..45, End(MarkdownTagEnd::Paragraph)),
+            ]
+        );
+        assert_eq!(&source[8..24], "e^{i\\pi} + 1 = 0");
+    }
+
     #[test]
     fn test_code_block_metadata() {
         assert_eq!(
@@ -757,6 +817,9 @@ mod tests {
         let input = "```python\nprint('hello')\nprint('world')\n```";
         assert_eq!(extract_code_block_content_range(input), 10..40);
 
+        let input = "~~~mermaid\ngraph TD\n~~~";
+        assert_eq!(extract_code_block_content_range(input), 11..20);
+
         // Malformed input
         let input = "`````";
         assert_eq!(extract_code_block_content_range(input), 3..3);
@@ -3,7 +3,8 @@ use gpui::{
     UnderlineStyle, px,
 };
 use language::HighlightId;
-use std::{fmt::Display, ops::Range, path::PathBuf};
+use markdown::{math::MathNode, mermaid::MermaidDiagram};
+use std::{fmt::Display, ops::Range, path::PathBuf, sync::Arc};
 use urlencoding;
 
 #[derive(Debug)]
@@ -18,6 +19,10 @@ pub enum ParsedMarkdownElement {
     Paragraph(MarkdownParagraph),
     HorizontalRule(Range<usize>),
     Image(Image),
+    /// A display math expression.
+    Math(ParsedMarkdownMath),
+    /// A fenced `mermaid` code block.
+    Mermaid(ParsedMarkdownMermaid),
 }
 
 impl ParsedMarkdownElement {
@@ -34,6 +39,8 @@ impl ParsedMarkdownElement {
             },
             Self::HorizontalRule(range) => range.clone(),
             Self::Image(image) => image.source_range.clone(),
+            Self::Math(math) => math.source_range.clone(),
+            Self::Mermaid(mermaid) => mermaid.source_range.clone(),
         })
     }
 
@@ -86,6 +93,23 @@ pub struct ParsedMarkdownCodeBlock {
     pub highlights: Option<Vec<(Range<usize>, HighlightId)>>,
 }
 
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMath {
+    pub source_range: Range<usize>,
+    pub contents: SharedString,
+    pub node: Arc<MathNode>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMermaid {
+    pub source_range: Range<usize>,
+    pub contents: SharedString,
+    /// The parsed diagram, or why it couldn't be parsed.
+    pub diagram: Result<Arc<MermaidDiagram>, SharedString>,
+}
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ParsedMarkdownHeading {
@@ -260,8 +284,7 @@ pub struct ParsedRegion {
 }
 
 /// A Markdown link.
-#[derive(Debug, Clone)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Link {
     /// A link to a webpage.
     Web {
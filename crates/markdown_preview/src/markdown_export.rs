@@ -0,0 +1,395 @@
+//! Exports markdown documents as standalone HTML or PDF files, styled after the
+//! active theme, for sharing with people who don't use the editor.
+
+mod html;
+mod pdf;
+mod pdf_writer;
+
+use crate::markdown_elements::{
+    Link, MarkdownHighlight, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
+    ParsedMarkdownElement, ParsedMarkdownText,
+};
+use crate::markdown_parser::parse_markdown;
+use anyhow::Result;
+use collections::HashMap;
+use fs::Fs;
+use futures::AsyncReadExt as _;
+use gpui::{
+    App, FontStyle, FontWeight, Hsla, SharedString,
+    http_client::{AsyncBody, HttpClient},
+};
+use language::LanguageRegistry;
+use markdown::drawing::{Ink, MeasureText, TextMetrics, TextVariant};
+use settings::Settings as _;
+use std::{ops::Range, path::PathBuf, sync::Arc};
+use theme::{ActiveTheme as _, SyntaxTheme, ThemeSettings};
+
+pub use html::export_html;
+pub use pdf::export_pdf;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Html,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// The colors and fonts of an exported document.
+#[derive(Clone, Debug)]
+pub struct ExportStyle {
+    pub ui_font_family: SharedString,
+    pub buffer_font_family: SharedString,
+    /// The size of body text in HTML documents, in pixels.
+    pub font_size: f32,
+    pub background: Hsla,
+    pub text: Hsla,
+    pub muted_text: Hsla,
+    pub link: Hsla,
+    pub border: Hsla,
+    pub surface: Hsla,
+    pub note: Hsla,
+    pub code_block_background: Hsla,
+    pub code_span_background: Hsla,
+    pub syntax_theme: Arc<SyntaxTheme>,
+}
+
+impl ExportStyle {
+    /// Uses the same colors as the markdown preview.
+    pub fn from_theme(cx: &App) -> Self {
+        let settings = ThemeSettings::get_global(cx);
+        let theme = cx.theme();
+        let colors = theme.colors();
+        Self {
+            ui_font_family: settings.ui_font.family.clone(),
+            buffer_font_family: settings.buffer_font.family.clone(),
+            font_size: f32::from(settings.buffer_font_size(cx)),
+            background: colors.editor_background,
+            text: colors.text,
+            muted_text: colors.text_muted,
+            link: colors.text_accent,
+            border: colors.border,
+            surface: colors.element_background,
+            note: theme.status().warning_background,
+            code_block_background: colors.surface_background,
+            code_span_background: colors.editor_document_highlight_read_background,
+            syntax_theme: theme.syntax().clone(),
+        }
+    }
+
+    /// Resolves the colors of math and diagrams the same way the preview does.
+    fn ink(&self, ink: Ink) -> Hsla {
+        match ink {
+            Ink::Text => self.text,
+            Ink::MutedText | Ink::Line => self.muted_text,
+            Ink::Surface => self.surface,
+            Ink::Note => self.note,
+            Ink::Background => self.background,
+        }
+    }
+}
+
+/// The contents of the images a document refers to, by their link.
+pub type ExportImages = HashMap<Link, Vec<u8>>;
+
+/// Parses `source` and writes it to `path` in the given format, embedding the
+/// images it refers to, including remote ones, so that the file can be viewed
+/// offline.
+pub async fn export_markdown(
+    source: String,
+    file_location: Option<PathBuf>,
+    language_registry: Arc<LanguageRegistry>,
+    title: String,
+    format: ExportFormat,
+    style: ExportStyle,
+    fs: Arc<dyn Fs>,
+    http_client: Arc<dyn HttpClient>,
+    path: PathBuf,
+) -> Result<()> {
+    let markdown = parse_markdown(&source, file_location, Some(language_registry)).await;
+
+    let mut images = ExportImages::default();
+    for link in image_links(&markdown) {
+        let bytes = match &link {
+            Link::Path { path, .. } => fs.load_bytes(path).await,
+            Link::Web { url } => fetch_image(url, &http_client).await,
+        };
+        match bytes {
+            Ok(bytes) => {
+                images.insert(link, bytes);
+            }
+            Err(error) => {
+                log::warn!("failed to load {link} for export: {error:#}");
+            }
+        }
+    }
+
+    let contents = match format {
+        ExportFormat::Html => export_html(&markdown, &title, &style, &images).into_bytes(),
+        ExportFormat::Pdf => export_pdf(&markdown, &title, &style, &images)?,
+    };
+    fs.write(&path, &contents).await
+}
+
+async fn fetch_image(url: &str, http_client: &Arc<dyn HttpClient>) -> Result<Vec<u8>> {
+    let mut response = http_client.get(url, AsyncBody::default(), true).await?;
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "status error {}",
+        response.status().as_u16()
+    );
+    Ok(body)
+}
+
+/// The links of the images a document refers to.
+pub fn image_links(markdown: &ParsedMarkdown) -> Vec<Link> {
+    fn visit_paragraph(paragraph: &MarkdownParagraph, links: &mut Vec<Link>) {
+        for chunk in paragraph {
+            if let MarkdownParagraphChunk::Image(image) = chunk
+                && !links.contains(&image.link)
+            {
+                links.push(image.link.clone());
+            }
+        }
+    }
+
+    fn visit(elements: &[ParsedMarkdownElement], links: &mut Vec<Link>) {
+        for element in elements {
+            match element {
+                ParsedMarkdownElement::Paragraph(paragraph) => visit_paragraph(paragraph, links),
+                ParsedMarkdownElement::Heading(heading) => {
+                    visit_paragraph(&heading.contents, links)
+                }
+                ParsedMarkdownElement::ListItem(item) => visit(&item.content, links),
+                ParsedMarkdownElement::BlockQuote(quote) => visit(&quote.children, links),
+                ParsedMarkdownElement::Table(table) => {
+                    for row in table.header.iter().chain(&table.body) {
+                        for column in &row.columns {
+                            visit_paragraph(&column.children, links);
+                        }
+                    }
+                    if let Some(caption) = &table.caption {
+                        visit_paragraph(caption, links);
+                    }
+                }
+                ParsedMarkdownElement::Image(image) => {
+                    if !links.contains(&image.link) {
+                        links.push(image.link.clone());
+                    }
+                }
+                ParsedMarkdownElement::CodeBlock(_)
+                | ParsedMarkdownElement::HorizontalRule(_)
+                | ParsedMarkdownElement::Math(_)
+                | ParsedMarkdownElement::Mermaid(_) => {}
+            }
+        }
+    }
+
+    let mut links = Vec::new();
+    visit(&markdown.children, &mut links);
+    links
+}
+
+/// How a run of inline text is styled.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct SegmentStyle<'a> {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+    code: bool,
+    /// The color of syntax highlighted text.
+    color: Option<Hsla>,
+    link: Option<&'a Link>,
+}
+
+/// A run of inline text with a single style.
+#[derive(Debug, PartialEq)]
+struct TextSegment<'a> {
+    text: &'a str,
+    style: SegmentStyle<'a>,
+}
+
+/// Splits text into runs wherever its highlights or regions start or end.
+fn text_segments<'a>(
+    text: &'a ParsedMarkdownText,
+    syntax_theme: &SyntaxTheme,
+) -> Vec<TextSegment<'a>> {
+    let contents = text.contents.as_ref();
+    let mut boundaries = vec![0, contents.len()];
+    for range in text
+        .highlights
+        .iter()
+        .map(|(range, _)| range)
+        .chain(text.regions.iter().map(|(range, _)| range))
+    {
+        boundaries.push(range.start.min(contents.len()));
+        boundaries.push(range.end.min(contents.len()));
+    }
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut runs: Vec<(Range<usize>, SegmentStyle)> = Vec::new();
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let mut style = SegmentStyle::default();
+        for (range, highlight) in &text.highlights {
+            if range.start > start || range.end < end {
+                continue;
+            }
+            match highlight {
+                MarkdownHighlight::Style(highlight) => {
+                    style.bold |= highlight.weight > FontWeight::NORMAL;
+                    style.italic |= highlight.italic || highlight.oblique;
+                    style.underline |= highlight.underline;
+                    style.strikethrough |= highlight.strikethrough;
+                }
+                MarkdownHighlight::Code(id) => {
+                    if let Some(highlight) = id.style(syntax_theme) {
+                        style.color = highlight.color.or(style.color);
+                        style.bold |= highlight
+                            .font_weight
+                            .is_some_and(|weight| weight > FontWeight::NORMAL);
+                        style.italic |= highlight.font_style == Some(FontStyle::Italic);
+                    }
+                }
+            }
+        }
+        for (range, region) in &text.regions {
+            if range.start <= start && range.end >= end {
+                style.code |= region.code;
+                style.link = region.link.as_ref().or(style.link);
+            }
+        }
+
+        if let Some((last_range, last_style)) = runs.last_mut()
+            && *last_style == style
+        {
+            last_range.end = end;
+        } else {
+            runs.push((start..end, style));
+        }
+    }
+
+    runs.into_iter()
+        .filter_map(|(range, style)| {
+            Some(TextSegment {
+                text: contents.get(range)?,
+                style,
+            })
+        })
+        .collect()
+}
+
+/// Measures text as set in the standard PDF fonts, which exported math and
+/// diagrams are laid out with so that they look the same in every viewer.
+struct StandardFontMeasure;
+
+impl MeasureText for StandardFontMeasure {
+    fn measure(&self, text: &str, font_size: f32, variant: TextVariant) -> TextMetrics {
+        TextMetrics {
+            width: pdf_writer::text_width(text, pdf_writer::Font::Sans(variant), font_size),
+            ascent: font_size * pdf_writer::ASCENT,
+            descent: font_size * pdf_writer::DESCENT,
+        }
+    }
+}
+
+#[cfg(test)]
+impl ExportStyle {
+    fn test() -> Self {
+        Self {
+            ui_font_family: "Zed Plex Sans".into(),
+            buffer_font_family: "Zed Plex Mono".into(),
+            font_size: 16.,
+            background: gpui::white(),
+            text: gpui::black(),
+            muted_text: gpui::hsla(0., 0., 0.4, 1.),
+            link: gpui::blue(),
+            border: gpui::hsla(0., 0., 0.8, 1.),
+            surface: gpui::hsla(0., 0., 0.95, 1.),
+            note: gpui::yellow(),
+            code_block_background: gpui::hsla(0., 0., 0.97, 1.),
+            code_span_background: gpui::hsla(0., 0., 0.9, 1.),
+            syntax_theme: Arc::new(SyntaxTheme::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown_elements::{MarkdownHighlightStyle, ParsedRegion};
+
+    #[test]
+    fn test_text_segments() {
+        let link = Link::Web {
+            url: "https://zed.dev".into(),
+        };
+        let text = ParsedMarkdownText {
+            source_range: 0..20,
+            contents: "see the docs, bold".into(),
+            highlights: vec![
+                (
+                    4..12,
+                    MarkdownHighlight::Style(MarkdownHighlightStyle {
+                        link: true,
+                        ..Default::default()
+                    }),
+                ),
+                (
+                    14..18,
+                    MarkdownHighlight::Style(MarkdownHighlightStyle {
+                        weight: FontWeight::BOLD,
+                        ..Default::default()
+                    }),
+                ),
+            ],
+            regions: vec![(
+                4..12,
+                ParsedRegion {
+                    code: false,
+                    link: Some(link.clone()),
+                },
+            )],
+        };
+
+        let segments = text_segments(&text, &SyntaxTheme::default());
+        assert_eq!(
+            segments,
+            vec![
+                TextSegment {
+                    text: "see ",
+                    style: SegmentStyle::default(),
+                },
+                TextSegment {
+                    text: "the docs",
+                    style: SegmentStyle {
+                        link: Some(&link),
+                        ..Default::default()
+                    },
+                },
+                TextSegment {
+                    text: ", ",
+                    style: SegmentStyle::default(),
+                },
+                TextSegment {
+                    text: "bold",
+                    style: SegmentStyle {
+                        bold: true,
+                        ..Default::default()
+                    },
+                },
+            ]
+        );
+    }
+}
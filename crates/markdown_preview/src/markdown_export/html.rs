@@ -0,0 +1,713 @@
+use super::{ExportImages, ExportStyle, StandardFontMeasure, TextSegment, text_segments};
+use crate::markdown_elements::{
+    HeadingLevel, Image, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
+    ParsedMarkdownCodeBlock, ParsedMarkdownElement, ParsedMarkdownListItem,
+    ParsedMarkdownListItemType, ParsedMarkdownMath, ParsedMarkdownMermaid, ParsedMarkdownTable,
+    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+};
+use crate::markdown_renderer::list_item_prefix;
+use base64::Engine as _;
+use gpui::{AbsoluteLength, DefiniteLength, FontStyle, FontWeight, Hsla, Rgba};
+use markdown::{
+    drawing::{Drawing, MeasureText as _, Primitive},
+    math::layout_math,
+    mermaid::layout_mermaid,
+};
+use std::{borrow::Cow, fmt::Write as _, path::Path};
+
+/// Writes a document as a single HTML file, with its styles and local images
+/// inlined and its math and diagrams drawn as SVG.
+pub fn export_html(
+    markdown: &ParsedMarkdown,
+    title: &str,
+    style: &ExportStyle,
+    images: &ExportImages,
+) -> String {
+    let mut writer = HtmlWriter {
+        html: String::new(),
+        style,
+        images,
+    };
+    writer
+        .html
+        .push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    writer
+        .html
+        .push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    writeln!(writer.html, "<title>{}</title>", escape(title)).ok();
+    writeln!(writer.html, "<style>\n{}</style>", stylesheet(style)).ok();
+    writer.html.push_str("</head>\n<body>\n<main>\n");
+    for block in &markdown.children {
+        writer.block(block);
+    }
+    writer.html.push_str("</main>\n</body>\n</html>\n");
+    writer.html
+}
+
+fn stylesheet(style: &ExportStyle) -> String {
+    let background = css_color(style.background);
+    let text = css_color(style.text);
+    let muted_text = css_color(style.muted_text);
+    let link = css_color(style.link);
+    let border = css_color(style.border);
+    let surface = css_color(style.surface);
+    let code_block_background = css_color(style.code_block_background);
+    let code_span_background = css_color(style.code_span_background);
+    let ui_font = css_string(&style.ui_font_family);
+    let buffer_font = css_string(&style.buffer_font_family);
+    let font_size = style.font_size;
+    format!(
+        "\
+body {{ margin: 0; background: {background}; color: {text}; font-family: {ui_font}, system-ui, sans-serif; font-size: {font_size}px; line-height: 1.5; }}
+main {{ max-width: 48rem; margin: 0 auto; padding: 2rem 1.5rem; }}
+h1, h2, h3, h4, h5, h6 {{ line-height: 1.25; margin: 1.25em 0 0.5em; }}
+h1 {{ font-size: 2em; }}
+h2 {{ font-size: 1.5em; }}
+h3 {{ font-size: 1.25em; }}
+h4 {{ font-size: 1em; }}
+h5 {{ font-size: 0.875em; }}
+h6 {{ font-size: 0.85em; color: {muted_text}; }}
+p {{ margin: 0 0 1em; }}
+a {{ color: {link}; }}
+code, pre {{ font-family: {buffer_font}, ui-monospace, monospace; }}
+code {{ background: {code_span_background}; border-radius: 3px; padding: 0 0.2em; }}
+pre {{ background: {code_block_background}; border-radius: 4px; padding: 0.75rem; overflow-x: auto; margin: 0 0 1em; }}
+pre code {{ background: none; padding: 0; }}
+blockquote {{ margin: 0 0 1em; padding-left: 0.75rem; border-left: 4px solid {border}; }}
+hr {{ border: none; height: 2px; background: {border}; margin: 1em 0; }}
+table {{ border-collapse: collapse; margin: 0 0 1em; }}
+caption {{ color: {muted_text}; padding-bottom: 0.25em; }}
+th, td {{ border: 1px solid {border}; padding: 0.25rem 0.5rem; }}
+th {{ background: {surface}; }}
+img {{ max-width: 100%; }}
+.list-item {{ display: flex; gap: 0.5em; margin: 0 0 0.25em; }}
+.list-item > .list-content > :last-child {{ margin-bottom: 0; }}
+.math, .diagram {{ display: flex; justify-content: center; overflow-x: auto; margin: 0 0 1em; }}
+.diagram-error {{ color: {muted_text}; }}
+@media print {{ main {{ max-width: none; padding: 0; }} pre {{ white-space: pre-wrap; }} }}
+"
+    )
+}
+
+struct HtmlWriter<'a> {
+    html: String,
+    style: &'a ExportStyle,
+    images: &'a ExportImages,
+}
+
+impl HtmlWriter<'_> {
+    fn block(&mut self, block: &ParsedMarkdownElement) {
+        match block {
+            ParsedMarkdownElement::Heading(heading) => {
+                let level = match heading.level {
+                    HeadingLevel::H1 => 1,
+                    HeadingLevel::H2 => 2,
+                    HeadingLevel::H3 => 3,
+                    HeadingLevel::H4 => 4,
+                    HeadingLevel::H5 => 5,
+                    HeadingLevel::H6 => 6,
+                };
+                write!(self.html, "<h{level}>").ok();
+                self.paragraph(&heading.contents);
+                writeln!(self.html, "</h{level}>").ok();
+            }
+            ParsedMarkdownElement::Paragraph(paragraph) => {
+                self.html.push_str("<p>");
+                self.paragraph(paragraph);
+                self.html.push_str("</p>\n");
+            }
+            ParsedMarkdownElement::ListItem(item) => self.list_item(item),
+            ParsedMarkdownElement::Table(table) => self.table(table),
+            ParsedMarkdownElement::BlockQuote(quote) => {
+                self.html.push_str("<blockquote>\n");
+                for child in &quote.children {
+                    self.block(child);
+                }
+                self.html.push_str("</blockquote>\n");
+            }
+            ParsedMarkdownElement::CodeBlock(code_block) => self.code_block(code_block),
+            ParsedMarkdownElement::HorizontalRule(_) => self.html.push_str("<hr>\n"),
+            ParsedMarkdownElement::Image(image) => {
+                self.html.push_str("<p>");
+                self.image(image);
+                self.html.push_str("</p>\n");
+            }
+            ParsedMarkdownElement::Math(math) => self.math(math),
+            ParsedMarkdownElement::Mermaid(mermaid) => self.mermaid(mermaid),
+        }
+    }
+
+    fn paragraph(&mut self, paragraph: &MarkdownParagraph) {
+        for chunk in paragraph {
+            match chunk {
+                MarkdownParagraphChunk::Text(text) => {
+                    let mut current_link = None;
+                    for segment in text_segments(text, &self.style.syntax_theme) {
+                        if segment.style.link != current_link {
+                            if current_link.is_some() {
+                                self.html.push_str("</a>");
+                            }
+                            if let Some(link) = segment.style.link {
+                                write!(self.html, "<a href=\"{}\">", escape(&link_href(link))).ok();
+                            }
+                            current_link = segment.style.link;
+                        }
+                        self.segment(&segment);
+                    }
+                    if current_link.is_some() {
+                        self.html.push_str("</a>");
+                    }
+                }
+                MarkdownParagraphChunk::Image(image) => self.image(image),
+            }
+        }
+    }
+
+    fn segment(&mut self, segment: &TextSegment) {
+        let style = &segment.style;
+        let mut closing = Vec::new();
+        for (enabled, open, close) in [
+            (style.code, "<code>", "</code>"),
+            (style.bold, "<strong>", "</strong>"),
+            (style.italic, "<em>", "</em>"),
+            (style.strikethrough, "<del>", "</del>"),
+            (style.underline && style.link.is_none(), "<u>", "</u>"),
+        ] {
+            if enabled {
+                self.html.push_str(open);
+                closing.push(close);
+            }
+        }
+        if let Some(color) = style.color {
+            write!(self.html, "<span style=\"color: {}\">", css_color(color)).ok();
+            closing.push("</span>");
+        }
+        self.html
+            .push_str(&escape(segment.text).replace('\n', "<br>\n"));
+        for close in closing.into_iter().rev() {
+            self.html.push_str(close);
+        }
+    }
+
+    fn image(&mut self, image: &Image) {
+        let path = match &image.link {
+            // Query strings and fragments aren't part of the file name.
+            Link::Web { url } => Path::new(url.split(['?', '#']).next().unwrap_or_default()),
+            Link::Path { path, .. } => path.as_path(),
+        };
+        let source = match (self.images.get(&image.link), &image.link) {
+            (Some(bytes), _) => Some(format!(
+                "data:{};base64,{}",
+                image_mime_type(path),
+                base64::prelude::BASE64_STANDARD.encode(bytes)
+            )),
+            // Remote images that couldn't be fetched are still shown when online.
+            (None, Link::Web { url }) => Some(url.clone()),
+            (None, Link::Path { .. }) => None,
+        };
+        let alt_text = image.alt_text.as_deref().unwrap_or_default();
+        let Some(source) = source else {
+            self.html.push_str(&escape(alt_text));
+            return;
+        };
+
+        let mut size = String::new();
+        if let Some(width) = image.width {
+            write!(size, "width: {};", css_length(width)).ok();
+        }
+        if let Some(height) = image.height {
+            write!(size, "height: {};", css_length(height)).ok();
+        }
+        write!(
+            self.html,
+            "<img src=\"{}\" alt=\"{}\"",
+            escape(&source),
+            escape(alt_text)
+        )
+        .ok();
+        if !size.is_empty() {
+            write!(self.html, " style=\"{size}\"").ok();
+        }
+        self.html.push('>');
+    }
+
+    fn list_item(&mut self, item: &ParsedMarkdownListItem) {
+        let depth = item.depth.saturating_sub(1) as usize;
+        write!(
+            self.html,
+            "<div class=\"list-item\" style=\"margin-left: {}em\">",
+            if item.nested { 0. } else { depth as f32 * 1.5 }
+        )
+        .ok();
+        match &item.item_type {
+            ParsedMarkdownListItemType::Ordered(order) => {
+                write!(
+                    self.html,
+                    "<span>{}</span>",
+                    escape(list_item_prefix(*order as usize, true, depth).trim_end())
+                )
+                .ok();
+            }
+            ParsedMarkdownListItemType::Unordered => {
+                write!(
+                    self.html,
+                    "<span>{}</span>",
+                    escape(list_item_prefix(1, false, depth).trim_end())
+                )
+                .ok();
+            }
+            ParsedMarkdownListItemType::Task(checked, _) => {
+                write!(
+                    self.html,
+                    "<input type=\"checkbox\" disabled{}>",
+                    if *checked { " checked" } else { "" }
+                )
+                .ok();
+            }
+        }
+        self.html.push_str("<div class=\"list-content\">\n");
+        for child in &item.content {
+            self.block(child);
+        }
+        self.html.push_str("</div></div>\n");
+    }
+
+    fn table(&mut self, table: &ParsedMarkdownTable) {
+        self.html.push_str("<table>\n");
+        if let Some(caption) = &table.caption {
+            self.html.push_str("<caption>");
+            self.paragraph(caption);
+            self.html.push_str("</caption>\n");
+        }
+        if !table.header.is_empty() {
+            self.html.push_str("<thead>\n");
+            for row in &table.header {
+                self.table_row(row);
+            }
+            self.html.push_str("</thead>\n");
+        }
+        self.html.push_str("<tbody>\n");
+        for row in &table.body {
+            self.table_row(row);
+        }
+        self.html.push_str("</tbody>\n</table>\n");
+    }
+
+    fn table_row(&mut self, row: &ParsedMarkdownTableRow) {
+        self.html.push_str("<tr>");
+        for column in &row.columns {
+            let tag = if column.is_header { "th" } else { "td" };
+            write!(self.html, "<{tag}").ok();
+            if column.col_span > 1 {
+                write!(self.html, " colspan=\"{}\"", column.col_span).ok();
+            }
+            if column.row_span > 1 {
+                write!(self.html, " rowspan=\"{}\"", column.row_span).ok();
+            }
+            let alignment = match column.alignment {
+                ParsedMarkdownTableAlignment::None => None,
+                ParsedMarkdownTableAlignment::Left => Some("left"),
+                ParsedMarkdownTableAlignment::Center => Some("center"),
+                ParsedMarkdownTableAlignment::Right => Some("right"),
+            };
+            if let Some(alignment) = alignment {
+                write!(self.html, " style=\"text-align: {alignment}\"").ok();
+            }
+            self.html.push('>');
+            self.paragraph(&column.children);
+            write!(self.html, "</{tag}>").ok();
+        }
+        self.html.push_str("</tr>\n");
+    }
+
+    fn code_block(&mut self, code_block: &ParsedMarkdownCodeBlock) {
+        self.html.push_str("<pre><code");
+        if let Some(language) = &code_block.language {
+            write!(self.html, " class=\"language-{}\"", escape(language)).ok();
+        }
+        self.html.push('>');
+
+        let contents = code_block.contents.as_ref();
+        let mut offset = 0;
+        for (range, highlight_id) in code_block.highlights.iter().flatten() {
+            let Some(highlight) = highlight_id.style(&self.style.syntax_theme) else {
+                continue;
+            };
+            if range.start < offset {
+                continue;
+            }
+            let (Some(before), Some(text)) = (
+                contents.get(offset..range.start),
+                contents.get(range.clone()),
+            ) else {
+                continue;
+            };
+            self.html.push_str(&escape(before));
+
+            let mut css = String::new();
+            if let Some(color) = highlight.color {
+                write!(css, "color: {};", css_color(color)).ok();
+            }
+            if let Some(weight) = highlight.font_weight
+                && weight != FontWeight::NORMAL
+            {
+                write!(css, "font-weight: {};", weight.0).ok();
+            }
+            if highlight.font_style == Some(FontStyle::Italic) {
+                css.push_str("font-style: italic;");
+            }
+            if css.is_empty() {
+                self.html.push_str(&escape(text));
+            } else {
+                write!(self.html, "<span style=\"{css}\">{}</span>", escape(text)).ok();
+            }
+            offset = range.end;
+        }
+        self.html
+            .push_str(&escape(contents.get(offset..).unwrap_or_default()));
+        self.html.push_str("</code></pre>\n");
+    }
+
+    fn math(&mut self, math: &ParsedMarkdownMath) {
+        let drawing = layout_math(&math.node, &StandardFontMeasure, self.style.font_size);
+        self.html.push_str("<div class=\"math\">");
+        self.html
+            .push_str(&drawing_to_svg(&drawing, &math.contents, self.style));
+        self.html.push_str("</div>\n");
+    }
+
+    fn mermaid(&mut self, mermaid: &ParsedMarkdownMermaid) {
+        match &mermaid.diagram {
+            Ok(diagram) => {
+                let drawing = layout_mermaid(diagram, &StandardFontMeasure, self.style.font_size);
+                self.html.push_str("<figure class=\"diagram\">");
+                self.html
+                    .push_str(&drawing_to_svg(&drawing, &mermaid.contents, self.style));
+                self.html.push_str("</figure>\n");
+            }
+            Err(error) => {
+                write!(
+                    self.html,
+                    "<pre><code class=\"language-mermaid\">{}</code></pre>\n<p class=\"diagram-error\">Couldn't render diagram: {}</p>\n",
+                    escape(&mermaid.contents),
+                    escape(error)
+                )
+                .ok();
+            }
+        }
+    }
+}
+
+/// Draws math or a diagram as SVG. Text is stretched to the width it was laid
+/// out with, so that it fits its boxes whatever font the browser picks.
+fn drawing_to_svg(drawing: &Drawing, label: &str, style: &ExportStyle) -> String {
+    let width = number(drawing.size.width);
+    let height = number(drawing.size.height);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" role=\"img\" aria-label=\"{}\" font-family=\"Helvetica, Arial, sans-serif\" style=\"white-space: pre\">",
+        escape(label)
+    );
+    let points = |points: &[gpui::Point<f32>]| {
+        points
+            .iter()
+            .map(|point| format!("{},{}", number(point.x), number(point.y)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let paint = |ink: Option<markdown::drawing::Ink>| {
+        ink.map_or("none".to_string(), |ink| css_color(style.ink(ink)))
+    };
+
+    for primitive in &drawing.primitives {
+        match primitive {
+            Primitive::Text {
+                origin,
+                text,
+                font_size,
+                variant,
+                ink,
+            } => {
+                let text_width = StandardFontMeasure
+                    .measure(text, *font_size, *variant)
+                    .width;
+                write!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\"",
+                    number(origin.x),
+                    number(origin.y),
+                    number(*font_size),
+                    css_color(style.ink(*ink)),
+                )
+                .ok();
+                if variant.bold {
+                    svg.push_str(" font-weight=\"bold\"");
+                }
+                if variant.italic {
+                    svg.push_str(" font-style=\"italic\"");
+                }
+                if text_width > 0. {
+                    write!(
+                        svg,
+                        " textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\"",
+                        number(text_width)
+                    )
+                    .ok();
+                }
+                write!(svg, ">{}</text>", escape(text)).ok();
+            }
+            Primitive::Rect {
+                bounds,
+                corner_radius,
+                fill,
+                stroke,
+                dashed,
+            } => {
+                write!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"{}\" stroke=\"{}\"{}/>",
+                    number(bounds.origin.x),
+                    number(bounds.origin.y),
+                    number(bounds.size.width),
+                    number(bounds.size.height),
+                    number(*corner_radius),
+                    paint(*fill),
+                    paint(*stroke),
+                    dash(*dashed),
+                )
+                .ok();
+            }
+            Primitive::Polygon {
+                points: polygon,
+                fill,
+                stroke,
+            } => {
+                write!(
+                    svg,
+                    "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\"/>",
+                    points(polygon),
+                    paint(*fill),
+                    paint(*stroke),
+                )
+                .ok();
+            }
+            Primitive::Polyline {
+                points: polyline,
+                width,
+                ink,
+                dashed,
+            } => {
+                write!(
+                    svg,
+                    "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linejoin=\"round\"{}/>",
+                    points(polyline),
+                    css_color(style.ink(*ink)),
+                    number(*width),
+                    dash(*dashed),
+                )
+                .ok();
+            }
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn dash(dashed: bool) -> &'static str {
+    if dashed {
+        " stroke-dasharray=\"4 3\""
+    } else {
+        ""
+    }
+}
+
+fn number(value: f32) -> String {
+    let value = (value * 100.).round() / 100.;
+    format!("{value}")
+}
+
+fn link_href(link: &Link) -> String {
+    match link {
+        Link::Web { url } => url.clone(),
+        // Keep links relative, since the export is usually read elsewhere.
+        Link::Path { display_path, .. } => display_path.to_string_lossy().into_owned(),
+    }
+}
+
+fn image_mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "tif" | "tiff" => "image/tiff",
+        _ => "image/png",
+    }
+}
+
+fn css_color(color: Hsla) -> String {
+    let color = Rgba::from(color);
+    let channel = |value: f32| (value * 255.).round() as u8;
+    if color.a >= 1. {
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(color.r),
+            channel(color.g),
+            channel(color.b)
+        )
+    } else {
+        format!(
+            "rgba({}, {}, {}, {})",
+            channel(color.r),
+            channel(color.g),
+            channel(color.b),
+            number(color.a)
+        )
+    }
+}
+
+fn css_length(length: DefiniteLength) -> String {
+    match length {
+        DefiniteLength::Absolute(AbsoluteLength::Pixels(pixels)) => {
+            format!("{}px", number(f32::from(pixels)))
+        }
+        DefiniteLength::Absolute(AbsoluteLength::Rems(rems)) => format!("{}rem", number(rems.0)),
+        DefiniteLength::Fraction(fraction) => format!("{}%", number(fraction * 100.)),
+    }
+}
+
+fn css_string(value: &str) -> String {
+    format!("\"{}\"", value.replace(['"', '\\', '<'], ""))
+}
+
+fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown_parser::parse_markdown;
+    use std::path::PathBuf;
+
+    #[gpui::test]
+    async fn test_export_html() {
+        let markdown = parse_markdown(
+            "\
+# Notes & drafts
+
+Some **bold** text and a [link](https://zed.dev).
+
+- [x] Done
+- Pending
+
+> Quoted
+
+```
+let x = 1 < 2;
+```
+
+$$x^2$$
+
+```mermaid
+graph LR
+  A --> B
+```
+",
+            None,
+            None,
+        )
+        .await;
+        let html = export_html(
+            &markdown,
+            "Notes & more",
+            &ExportStyle::test(),
+            &ExportImages::default(),
+        );
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Notes &amp; more</title>"));
+        assert!(html.contains("background: #ffffff; color: #000000;"));
+        assert!(html.contains("<h1>Notes &amp; drafts</h1>"));
+        assert!(html.contains(
+            "<p>Some <strong>bold</strong> text and a <a href=\"https://zed.dev\">link</a>.</p>"
+        ));
+        assert!(html.contains("<input type=\"checkbox\" disabled checked>"));
+        assert!(html.contains("<span>•</span>"));
+        assert!(html.contains("<blockquote>\n<p>Quoted</p>\n</blockquote>"));
+        assert!(html.contains("<pre><code>let x = 1 &lt; 2;</code></pre>"));
+        assert!(html.contains("<div class=\"math\"><svg"));
+        assert!(html.contains("aria-label=\"x^2\""));
+        assert!(html.contains("<figure class=\"diagram\"><svg"));
+        assert!(html.contains(">A</text>"));
+    }
+
+    #[gpui::test]
+    async fn test_export_html_images() {
+        let markdown = parse_markdown("![remote](https://zed.dev/logo.png)", None, None).await;
+        let html = export_html(
+            &markdown,
+            "Images",
+            &ExportStyle::test(),
+            &ExportImages::default(),
+        );
+        assert!(html.contains("<img src=\"https://zed.dev/logo.png\" alt=\"remote\">"));
+
+        let local = Link::Path {
+            display_path: PathBuf::from("logo.png"),
+            path: PathBuf::from("/docs/logo.png"),
+        };
+        let remote = Link::Web {
+            url: "https://zed.dev/photo.jpg?size=2".into(),
+        };
+        let mut images = ExportImages::default();
+        images.insert(local.clone(), b"png".to_vec());
+        images.insert(remote.clone(), b"jpg".to_vec());
+        let style = ExportStyle::test();
+        let mut writer = HtmlWriter {
+            html: String::new(),
+            style: &style,
+            images: &images,
+        };
+        for (link, alt_text) in [(local, "logo"), (remote, "photo")] {
+            writer.image(&Image {
+                link,
+                source_range: 0..0,
+                alt_text: Some(alt_text.into()),
+                width: None,
+                height: None,
+            });
+        }
+        assert_eq!(
+            writer.html,
+            "<img src=\"data:image/png;base64,cG5n\" alt=\"logo\">\
+             <img src=\"data:image/jpeg;base64,anBn\" alt=\"photo\">"
+        );
+    }
+
+    #[test]
+    fn test_css_color() {
+        assert_eq!(css_color(gpui::red()), "#ff0000");
+        assert_eq!(css_color(gpui::red().opacity(0.5)), "rgba(255, 0, 0, 0.5)");
+    }
+}
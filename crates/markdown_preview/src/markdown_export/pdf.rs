@@ -0,0 +1,1041 @@
+use super::{
+    ExportImages, ExportStyle, StandardFontMeasure,
+    pdf_writer::{ASCENT, DESCENT, Font, Page, PdfDocument, PdfImage, text_width},
+    text_segments,
+};
+use crate::markdown_elements::{
+    HeadingLevel, Image, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
+    ParsedMarkdownBlockQuote, ParsedMarkdownElement, ParsedMarkdownListItem,
+    ParsedMarkdownListItemType, ParsedMarkdownTable, ParsedMarkdownTableAlignment,
+    ParsedMarkdownText,
+};
+use crate::markdown_renderer::list_item_prefix;
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{
+    AbsoluteLength, Bounds, DefiniteLength, FontStyle, FontWeight, Hsla, Point, Rgba, Size, point,
+    size,
+};
+use language::HighlightId;
+use markdown::{
+    drawing::{Drawing, Primitive, TextVariant},
+    math::layout_math,
+    mermaid::layout_mermaid,
+};
+use std::ops::Range;
+
+/// A4, in points.
+const PAGE_SIZE: Size<f32> = Size {
+    width: 595.,
+    height: 842.,
+};
+const MARGIN: f32 = 56.;
+const FONT_SIZE: f32 = 11.;
+const CODE_FONT_SIZE: f32 = 9.;
+const LINE_HEIGHT: f32 = 1.45;
+const BLOCK_GAP: f32 = 9.;
+const LIST_ITEM_GAP: f32 = 3.;
+const CODE_BLOCK_PADDING: f32 = 8.;
+const TABLE_CELL_PADDING: f32 = 4.;
+const BLOCK_QUOTE_INDENT: f32 = 14.;
+const LIST_INDENT: f32 = 18.;
+/// Images are sized like the preview sizes them on a standard density screen.
+const POINTS_PER_PIXEL: f32 = 0.75;
+
+/// Writes a document as a paginated PDF file. Text is set in the standard PDF
+/// fonts, so it stays selectable and searchable without embedding any fonts,
+/// unless it has characters those fonts lack.
+pub fn export_pdf(
+    markdown: &ParsedMarkdown,
+    title: &str,
+    style: &ExportStyle,
+    images: &ExportImages,
+) -> Result<Vec<u8>> {
+    let mut layout = PdfLayout {
+        style,
+        images,
+        document: PdfDocument::new(title.to_string()),
+        embedded_images: HashMap::default(),
+        pages: vec![Page::new(PAGE_SIZE, style.background)],
+        y: MARGIN,
+        left: MARGIN,
+        right: PAGE_SIZE.width - MARGIN,
+    };
+    layout.blocks(&markdown.children);
+
+    let mut document = layout.document;
+    for page in layout.pages {
+        document.push_page(page);
+    }
+    document.finish()
+}
+
+/// A run of text with a single style.
+#[derive(Clone, Debug)]
+struct Span {
+    text: String,
+    font: Font,
+    font_size: f32,
+    color: Hsla,
+    background: Option<Hsla>,
+    underline: bool,
+    strikethrough: bool,
+    url: Option<String>,
+}
+
+/// A wrapped line of text, made of parts of one or more spans.
+#[derive(Debug, Default)]
+struct Line {
+    pieces: Vec<Piece>,
+    width: f32,
+    /// The size of the largest text on the line.
+    font_size: f32,
+}
+
+#[derive(Debug)]
+struct Piece {
+    span: usize,
+    text: String,
+    x: f32,
+    width: f32,
+}
+
+impl Line {
+    fn push(&mut self, span_index: usize, span: &Span, text: &str, width: f32) {
+        self.font_size = self.font_size.max(span.font_size);
+        match self.pieces.last_mut() {
+            Some(piece) if piece.span == span_index => {
+                piece.text.push_str(text);
+                piece.width += width;
+            }
+            _ => self.pieces.push(Piece {
+                span: span_index,
+                text: text.to_string(),
+                x: self.width,
+                width,
+            }),
+        }
+        self.width += width;
+    }
+
+    fn trim_end(&mut self, spans: &[Span]) {
+        while let Some(piece) = self.pieces.last_mut() {
+            let trimmed = piece.text.trim_end_matches(' ');
+            let removed = piece.text.len() - trimmed.len();
+            let span = &spans[piece.span];
+            let removed_width = text_width(" ", span.font, span.font_size) * removed as f32;
+            piece.text.truncate(trimmed.len());
+            piece.width -= removed_width;
+            self.width -= removed_width;
+            if piece.text.is_empty() {
+                self.pieces.pop();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn height(&self) -> f32 {
+        self.font_size * LINE_HEIGHT
+    }
+}
+
+/// Splits text into words, runs of spaces and line breaks.
+fn tokens(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = if first == '\n' {
+            1
+        } else {
+            let is_space = first.is_whitespace();
+            rest.char_indices()
+                .find(|(_, c)| *c == '\n' || c.is_whitespace() != is_space)
+                .map_or(rest.len(), |(index, _)| index)
+        };
+        let (token, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(token)
+    })
+}
+
+/// Breaks spans into lines no wider than `width`, collapsing runs of spaces.
+fn wrap(spans: &[Span], width: f32, font_size: f32) -> Vec<Line> {
+    let new_line = || Line {
+        font_size,
+        ..Line::default()
+    };
+    let mut lines = Vec::new();
+    let mut line = new_line();
+    for (span_index, span) in spans.iter().enumerate() {
+        for token in tokens(&span.text) {
+            if token == "\n" {
+                line.trim_end(spans);
+                lines.push(std::mem::replace(&mut line, new_line()));
+            } else if token.starts_with(char::is_whitespace) {
+                if !line.pieces.is_empty() {
+                    let space_width = text_width(" ", span.font, span.font_size);
+                    line.push(span_index, span, " ", space_width);
+                }
+            } else {
+                let token_width = text_width(token, span.font, span.font_size);
+                if line.width + token_width > width && !line.pieces.is_empty() {
+                    line.trim_end(spans);
+                    lines.push(std::mem::replace(&mut line, new_line()));
+                }
+                if token_width <= width {
+                    line.push(span_index, span, token, token_width);
+                    continue;
+                }
+                // Words wider than a line are broken wherever they overflow.
+                for c in token.chars() {
+                    let mut buffer = [0; 4];
+                    let c = c.encode_utf8(&mut buffer);
+                    let char_width = text_width(c, span.font, span.font_size);
+                    if line.width + char_width > width && !line.pieces.is_empty() {
+                        lines.push(std::mem::replace(&mut line, new_line()));
+                    }
+                    line.push(span_index, span, c, char_width);
+                }
+            }
+        }
+    }
+    line.trim_end(spans);
+    if !line.pieces.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+struct PdfLayout<'a> {
+    style: &'a ExportStyle,
+    images: &'a ExportImages,
+    document: PdfDocument,
+    /// Images already added to the document, with their size in pixels.
+    embedded_images: HashMap<Link, Option<(usize, Size<f32>)>>,
+    pages: Vec<Page>,
+    /// The distance from the top of the page to where the next block goes.
+    y: f32,
+    /// The horizontal extent of the current block, which narrows inside lists
+    /// and block quotes.
+    left: f32,
+    right: f32,
+}
+
+impl PdfLayout<'_> {
+    fn page(&mut self) -> &mut Page {
+        let index = self.pages.len() - 1;
+        &mut self.pages[index]
+    }
+
+    fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    fn bottom(&self) -> f32 {
+        PAGE_SIZE.height - MARGIN
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Page::new(PAGE_SIZE, self.style.background));
+        self.y = MARGIN;
+    }
+
+    /// Moves to a new page unless something `height` tall fits on this one.
+    /// Things taller than a page are started at the top of a new one.
+    fn reserve(&mut self, height: f32) {
+        if self.y + height > self.bottom() && self.y > MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn blocks(&mut self, blocks: &[ParsedMarkdownElement]) {
+        for (index, block) in blocks.iter().enumerate() {
+            self.block(block);
+            if block.is_list_item()
+                && blocks
+                    .get(index + 1)
+                    .is_some_and(|next| next.is_list_item())
+            {
+                self.y -= BLOCK_GAP - LIST_ITEM_GAP;
+            }
+        }
+    }
+
+    fn block(&mut self, block: &ParsedMarkdownElement) {
+        match block {
+            ParsedMarkdownElement::Heading(heading) => {
+                let scale = match heading.level {
+                    HeadingLevel::H1 => 2.,
+                    HeadingLevel::H2 => 1.5,
+                    HeadingLevel::H3 => 1.25,
+                    HeadingLevel::H4 => 1.,
+                    HeadingLevel::H5 => 0.875,
+                    HeadingLevel::H6 => 0.85,
+                };
+                let color = match heading.level {
+                    HeadingLevel::H6 => self.style.muted_text,
+                    _ => self.style.text,
+                };
+                if self.y > MARGIN {
+                    self.y += FONT_SIZE * 0.5;
+                }
+                self.paragraph(&heading.contents, FONT_SIZE * scale, true, color);
+            }
+            ParsedMarkdownElement::Paragraph(paragraph) => {
+                self.paragraph(paragraph, FONT_SIZE, false, self.style.text)
+            }
+            ParsedMarkdownElement::ListItem(item) => self.list_item(item),
+            ParsedMarkdownElement::Table(table) => self.table(table),
+            ParsedMarkdownElement::BlockQuote(quote) => self.block_quote(quote),
+            ParsedMarkdownElement::CodeBlock(code_block) => {
+                self.code_block(&code_block.contents, code_block.highlights.as_deref())
+            }
+            ParsedMarkdownElement::HorizontalRule(_) => {
+                let height = FONT_SIZE;
+                self.reserve(height);
+                let bounds = Bounds::new(
+                    point(self.left, self.y + height / 2. - 1.),
+                    size(self.width(), 2.),
+                );
+                let color = self.style.border;
+                self.page().fill_rect(bounds, color);
+                self.y += height + BLOCK_GAP;
+            }
+            ParsedMarkdownElement::Image(image) => self.image(image),
+            ParsedMarkdownElement::Math(math) => {
+                self.drawing(&layout_math(&math.node, &StandardFontMeasure, FONT_SIZE))
+            }
+            ParsedMarkdownElement::Mermaid(mermaid) => match &mermaid.diagram {
+                Ok(diagram) => {
+                    self.drawing(&layout_mermaid(diagram, &StandardFontMeasure, FONT_SIZE))
+                }
+                Err(error) => {
+                    self.code_block(&mermaid.contents, None);
+                    let span = self.plain_span(
+                        format!("Couldn't render diagram: {error}"),
+                        self.style.muted_text,
+                    );
+                    self.text_block(vec![span], FONT_SIZE);
+                }
+            },
+        }
+    }
+
+    fn plain_span(&self, text: String, color: Hsla) -> Span {
+        Span {
+            text,
+            font: Font::Sans(TextVariant::REGULAR),
+            font_size: FONT_SIZE,
+            color,
+            background: None,
+            underline: false,
+            strikethrough: false,
+            url: None,
+        }
+    }
+
+    /// Lays out a paragraph's text, placing its images between lines of text.
+    fn paragraph(
+        &mut self,
+        paragraph: &MarkdownParagraph,
+        font_size: f32,
+        bold: bool,
+        color: Hsla,
+    ) {
+        let mut spans = Vec::new();
+        for chunk in paragraph {
+            match chunk {
+                MarkdownParagraphChunk::Text(text) => {
+                    spans.extend(self.text_spans(text, font_size, bold, color));
+                }
+                MarkdownParagraphChunk::Image(image) => {
+                    if !spans.is_empty() {
+                        self.text_block(std::mem::take(&mut spans), font_size);
+                    }
+                    self.image(image);
+                }
+            }
+        }
+        if !spans.is_empty() || paragraph.is_empty() {
+            self.text_block(spans, font_size);
+        }
+    }
+
+    fn text_spans(
+        &self,
+        text: &ParsedMarkdownText,
+        font_size: f32,
+        bold: bool,
+        color: Hsla,
+    ) -> Vec<Span> {
+        text_segments(text, &self.style.syntax_theme)
+            .into_iter()
+            .map(|segment| {
+                let style = segment.style;
+                let variant = TextVariant {
+                    bold: bold || style.bold,
+                    italic: style.italic,
+                };
+                Span {
+                    text: segment.text.to_string(),
+                    font: if style.code {
+                        Font::Mono(variant)
+                    } else {
+                        Font::Sans(variant)
+                    },
+                    font_size,
+                    color: if style.link.is_some() {
+                        self.style.link
+                    } else {
+                        style.color.unwrap_or(color)
+                    },
+                    background: style.code.then_some(self.style.code_span_background),
+                    underline: style.underline || style.link.is_some(),
+                    strikethrough: style.strikethrough,
+                    url: match style.link {
+                        Some(Link::Web { url }) => Some(url.clone()),
+                        _ => None,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn text_block(&mut self, spans: Vec<Span>, font_size: f32) {
+        for line in wrap(&spans, self.width(), font_size) {
+            let height = line.height();
+            self.reserve(height);
+            let (x, y) = (self.left, self.y);
+            self.draw_line(&spans, &line, point(x, y));
+            self.y += height;
+        }
+        self.y += BLOCK_GAP;
+    }
+
+    fn draw_line(&mut self, spans: &[Span], line: &Line, origin: Point<f32>) {
+        let height = line.height();
+        let baseline = origin.y
+            + (height - (ASCENT + DESCENT) * line.font_size) / 2.
+            + ASCENT * line.font_size;
+        let page = self.page();
+        for piece in &line.pieces {
+            let span = &spans[piece.span];
+            let x = origin.x + piece.x;
+            if let Some(background) = span.background {
+                page.fill_rect(
+                    Bounds::new(
+                        point(x - 1., baseline - ASCENT * span.font_size - 1.),
+                        size(piece.width + 2., (ASCENT + DESCENT) * span.font_size + 2.),
+                    ),
+                    background,
+                );
+            }
+            page.text(
+                point(x, baseline),
+                &piece.text,
+                span.font,
+                span.font_size,
+                span.color,
+            );
+            let mut decorate = |y: f32| {
+                page.path(
+                    &[point(x, y), point(x + piece.width, y)],
+                    false,
+                    None,
+                    Some((span.color, span.font_size / 16.)),
+                    false,
+                );
+            };
+            if span.underline {
+                decorate(baseline + span.font_size * 0.12);
+            }
+            if span.strikethrough {
+                decorate(baseline - span.font_size * 0.3);
+            }
+            if let Some(url) = &span.url {
+                page.link(
+                    Bounds::new(point(x, origin.y), size(piece.width, height)),
+                    url.clone(),
+                );
+            }
+        }
+    }
+
+    fn list_item(&mut self, item: &ParsedMarkdownListItem) {
+        let depth = item.depth.saturating_sub(1) as usize;
+        let marker_x = self.left
+            + if item.nested {
+                0.
+            } else {
+                depth as f32 * LIST_INDENT
+            };
+        let line_height = FONT_SIZE * LINE_HEIGHT;
+        self.reserve(line_height);
+        let baseline =
+            self.y + (line_height - (ASCENT + DESCENT) * FONT_SIZE) / 2. + ASCENT * FONT_SIZE;
+
+        let marker = match &item.item_type {
+            ParsedMarkdownListItemType::Ordered(order) => {
+                Some(list_item_prefix(*order as usize, true, depth))
+            }
+            // Not every bullet the preview uses is in the standard fonts.
+            ParsedMarkdownListItemType::Unordered => Some(["•", "–", "·"][depth % 3].to_string()),
+            ParsedMarkdownListItemType::Task(..) => None,
+        };
+        let color = self.style.text;
+        let marker_width = if let Some(marker) = marker {
+            let font = Font::Sans(TextVariant::REGULAR);
+            self.page().text(
+                point(marker_x, baseline),
+                marker.trim_end(),
+                font,
+                FONT_SIZE,
+                color,
+            );
+            text_width(&marker, font, FONT_SIZE).max(LIST_INDENT * 0.75)
+        } else {
+            let box_size = FONT_SIZE * 0.8;
+            let bounds = Bounds::new(
+                point(marker_x, baseline - box_size),
+                size(box_size, box_size),
+            );
+            let border = self.style.border;
+            self.page().stroke_rect(bounds, border, 0.75, false);
+            if let ParsedMarkdownListItemType::Task(true, _) = item.item_type {
+                let at = |x: f32, y: f32| {
+                    point(
+                        bounds.origin.x + x * box_size,
+                        bounds.origin.y + y * box_size,
+                    )
+                };
+                self.page().path(
+                    &[at(0.2, 0.5), at(0.42, 0.75), at(0.8, 0.25)],
+                    false,
+                    None,
+                    Some((color, 1.2)),
+                    false,
+                );
+            }
+            box_size + FONT_SIZE * 0.5
+        };
+
+        let left = self.left;
+        self.left = marker_x + marker_width;
+        if item.content.is_empty() {
+            self.y += line_height + BLOCK_GAP;
+        } else {
+            self.blocks(&item.content);
+        }
+        self.left = left;
+    }
+
+    fn block_quote(&mut self, quote: &ParsedMarkdownBlockQuote) {
+        let start_page = self.pages.len() - 1;
+        let start_y = self.y;
+        let bar_x = self.left;
+        self.left += BLOCK_QUOTE_INDENT;
+        self.blocks(&quote.children);
+        self.left -= BLOCK_QUOTE_INDENT;
+
+        // The bar is drawn once the quote's extent is known, on every page the
+        // quote spans.
+        let end_page = self.pages.len() - 1;
+        let end_y = self.y - BLOCK_GAP;
+        let color = self.style.border;
+        for (page_index, page) in self.pages.iter_mut().enumerate().skip(start_page) {
+            let top = if page_index == start_page {
+                start_y
+            } else {
+                MARGIN
+            };
+            let bottom = if page_index == end_page {
+                end_y
+            } else {
+                PAGE_SIZE.height - MARGIN
+            };
+            if bottom > top {
+                page.fill_rect(
+                    Bounds::new(point(bar_x, top), size(3., bottom - top)),
+                    color,
+                );
+            }
+        }
+    }
+
+    fn code_block(&mut self, contents: &str, highlights: Option<&[(Range<usize>, HighlightId)]>) {
+        // The highlight covering each byte, if any.
+        let mut byte_highlights = vec![None; contents.len()];
+        for (range, highlight_id) in highlights.unwrap_or_default() {
+            if let Some(highlight) = highlight_id.style(&self.style.syntax_theme) {
+                let color = highlight.color.unwrap_or(self.style.text);
+                let variant = TextVariant {
+                    bold: highlight
+                        .font_weight
+                        .is_some_and(|weight| weight > FontWeight::NORMAL),
+                    italic: highlight.font_style == Some(FontStyle::Italic),
+                };
+                for byte in byte_highlights.iter_mut().take(range.end).skip(range.start) {
+                    *byte = Some((color, variant));
+                }
+            }
+        }
+
+        // Code isn't reflowed, but lines that don't fit are continued below.
+        let char_width = text_width(" ", Font::Mono(TextVariant::REGULAR), CODE_FONT_SIZE);
+        let columns = (((self.width() - 2. * CODE_BLOCK_PADDING) / char_width) as usize).max(1);
+        let mut lines: Vec<Vec<Span>> = Vec::new();
+        let mut offset = 0;
+        for source_line in contents.split('\n') {
+            let mut line: Vec<Span> = Vec::new();
+            let mut column = 0;
+            for (index, c) in source_line.char_indices() {
+                if column == columns {
+                    lines.push(std::mem::take(&mut line));
+                    column = 0;
+                }
+                let (color, variant) = byte_highlights[offset + index]
+                    .unwrap_or((self.style.text, TextVariant::REGULAR));
+                match line.last_mut() {
+                    Some(span) if span.color == color && span.font == Font::Mono(variant) => {
+                        span.text.push(c);
+                    }
+                    _ => line.push(Span {
+                        text: c.to_string(),
+                        font: Font::Mono(variant),
+                        font_size: CODE_FONT_SIZE,
+                        color,
+                        background: None,
+                        underline: false,
+                        strikethrough: false,
+                        url: None,
+                    }),
+                }
+                column += if c == '\t' { 4 } else { 1 };
+            }
+            lines.push(line);
+            offset += source_line.len() + 1;
+        }
+
+        let line_height = CODE_FONT_SIZE * 1.4;
+        let mut remaining = lines.as_slice();
+        while !remaining.is_empty() {
+            self.reserve(line_height + 2. * CODE_BLOCK_PADDING);
+            let available = self.bottom() - self.y - 2. * CODE_BLOCK_PADDING;
+            let count = ((available / line_height) as usize).clamp(1, remaining.len());
+            let (chunk, rest) = remaining.split_at(count);
+            remaining = rest;
+
+            let bounds = Bounds::new(
+                point(self.left, self.y),
+                size(
+                    self.width(),
+                    count as f32 * line_height + 2. * CODE_BLOCK_PADDING,
+                ),
+            );
+            let (background, left) = (self.style.code_block_background, self.left);
+            let mut baseline = self.y
+                + CODE_BLOCK_PADDING
+                + (line_height - (ASCENT + DESCENT) * CODE_FONT_SIZE) / 2.
+                + ASCENT * CODE_FONT_SIZE;
+            let page = self.page();
+            page.fill_rect(bounds, background);
+            for line in chunk {
+                let mut x = left + CODE_BLOCK_PADDING;
+                for span in line {
+                    let text = span.text.replace('\t', "    ");
+                    page.text(
+                        point(x, baseline),
+                        &text,
+                        span.font,
+                        span.font_size,
+                        span.color,
+                    );
+                    x += text_width(&text, span.font, span.font_size);
+                }
+                baseline += line_height;
+            }
+            self.y += bounds.size.height;
+            if !remaining.is_empty() {
+                self.new_page();
+            }
+        }
+        self.y += BLOCK_GAP;
+    }
+
+    fn table(&mut self, table: &ParsedMarkdownTable) {
+        if let Some(caption) = &table.caption {
+            self.paragraph(caption, FONT_SIZE * 0.9, false, self.style.muted_text);
+        }
+        let rows = table
+            .header
+            .iter()
+            .map(|row| (row, true))
+            .chain(table.body.iter().map(|row| (row, false)))
+            .collect::<Vec<_>>();
+        let column_count = rows
+            .iter()
+            .map(|(row, _)| {
+                row.columns
+                    .iter()
+                    .map(|column| column.col_span.max(1))
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or_default();
+        if column_count == 0 {
+            return;
+        }
+        let column_width = self.width() / column_count as f32;
+
+        for (row, is_header_row) in rows {
+            let mut cells = Vec::new();
+            let mut column_index = 0;
+            for column in &row.columns {
+                let span_count = column.col_span.max(1);
+                let x = self.left + column_index as f32 * column_width;
+                let width = span_count as f32 * column_width;
+                column_index += span_count;
+
+                let is_header = column.is_header || is_header_row;
+                let spans = column
+                    .children
+                    .iter()
+                    .filter_map(|chunk| match chunk {
+                        MarkdownParagraphChunk::Text(text) => Some(text),
+                        MarkdownParagraphChunk::Image(_) => None,
+                    })
+                    .flat_map(|text| self.text_spans(text, FONT_SIZE, is_header, self.style.text))
+                    .collect::<Vec<_>>();
+                let lines = wrap(&spans, width - 2. * TABLE_CELL_PADDING, FONT_SIZE);
+                cells.push((x, width, is_header, &column.alignment, spans, lines));
+            }
+            let row_height = cells
+                .iter()
+                .map(|(.., lines)| lines.iter().map(Line::height).sum::<f32>())
+                .fold(FONT_SIZE * LINE_HEIGHT, f32::max)
+                + 2. * TABLE_CELL_PADDING;
+
+            self.reserve(row_height);
+            let top = self.y;
+            for (x, width, is_header, alignment, spans, lines) in cells {
+                let bounds = Bounds::new(point(x, top), size(width, row_height));
+                if is_header {
+                    let surface = self.style.surface;
+                    self.page().fill_rect(bounds, surface);
+                }
+                let border = self.style.border;
+                self.page().stroke_rect(bounds, border, 0.75, false);
+
+                let mut y = top + TABLE_CELL_PADDING;
+                for line in &lines {
+                    let free_space = width - 2. * TABLE_CELL_PADDING - line.width;
+                    let offset = match alignment {
+                        ParsedMarkdownTableAlignment::Center => free_space / 2.,
+                        ParsedMarkdownTableAlignment::Right => free_space,
+                        ParsedMarkdownTableAlignment::None | ParsedMarkdownTableAlignment::Left => {
+                            0.
+                        }
+                    };
+                    self.draw_line(&spans, line, point(x + TABLE_CELL_PADDING + offset, y));
+                    y += line.height();
+                }
+            }
+            self.y += row_height;
+        }
+        self.y += BLOCK_GAP;
+    }
+
+    fn image(&mut self, image: &Image) {
+        let Some((index, natural_size)) = self.embed_image(&image.link) else {
+            // Show the images that couldn't be loaded as links to them.
+            let mut span = self.plain_span(
+                image
+                    .alt_text
+                    .as_ref()
+                    .map_or_else(|| image.link.to_string(), |alt_text| alt_text.to_string()),
+                self.style.muted_text,
+            );
+            span.font = Font::Sans(TextVariant::ITALIC);
+            if let Link::Web { url } = &image.link {
+                span.url = Some(url.clone());
+                span.color = self.style.link;
+                span.underline = true;
+            }
+            self.text_block(vec![span], FONT_SIZE);
+            return;
+        };
+
+        let natural_width = natural_size.width * POINTS_PER_PIXEL;
+        let natural_height = natural_size.height * POINTS_PER_PIXEL;
+        let to_points = |length: DefiniteLength, base: f32| match length {
+            DefiniteLength::Absolute(AbsoluteLength::Pixels(pixels)) => {
+                f32::from(pixels) * POINTS_PER_PIXEL
+            }
+            DefiniteLength::Absolute(AbsoluteLength::Rems(rems)) => rems.0 * FONT_SIZE,
+            DefiniteLength::Fraction(fraction) => base * fraction,
+        };
+        let (mut width, mut height) = match (image.width, image.height) {
+            (Some(width), Some(height)) => (
+                to_points(width, self.width()),
+                to_points(height, natural_height),
+            ),
+            (Some(width), None) => {
+                let width = to_points(width, self.width());
+                (width, natural_height * width / natural_width)
+            }
+            (None, Some(height)) => {
+                let height = to_points(height, natural_height);
+                (natural_width * height / natural_height, height)
+            }
+            (None, None) => (natural_width, natural_height),
+        };
+        let scale = (self.width() / width)
+            .min((PAGE_SIZE.height - 2. * MARGIN) / height)
+            .min(1.);
+        width *= scale;
+        height *= scale;
+
+        self.reserve(height);
+        let bounds = Bounds::new(point(self.left, self.y), size(width, height));
+        self.page().image(index, bounds);
+        self.y += height + BLOCK_GAP;
+    }
+
+    /// Adds an image to the document once, returning its index and size in
+    /// pixels, or `None` if it couldn't be loaded.
+    fn embed_image(&mut self, link: &Link) -> Option<(usize, Size<f32>)> {
+        if let Some(embedded) = self.embedded_images.get(link) {
+            return *embedded;
+        }
+        let embedded = self.images.get(link).and_then(|bytes| {
+            match encode_jpeg(bytes, self.style.background) {
+                Ok(image) => {
+                    let size = size(image.width as f32, image.height as f32);
+                    Some((self.document.add_image(image), size))
+                }
+                Err(error) => {
+                    log::warn!("failed to export image {link}: {error:#}");
+                    None
+                }
+            }
+        });
+        self.embedded_images.insert(link.clone(), embedded);
+        embedded
+    }
+
+    /// Draws math or a diagram centered, scaled down if it's too wide.
+    fn drawing(&mut self, drawing: &Drawing) {
+        if drawing.size.width <= 0. || drawing.size.height <= 0. {
+            return;
+        }
+        let scale = (self.width() / drawing.size.width).min(1.);
+        let height = drawing.size.height * scale;
+        self.reserve(height);
+        let origin = point(
+            self.left + (self.width() - drawing.size.width * scale) / 2.,
+            self.y,
+        );
+        let to_page = |p: Point<f32>| point(origin.x + p.x * scale, origin.y + p.y * scale);
+        let style = self.style;
+        let page = self.page();
+        for primitive in &drawing.primitives {
+            match primitive {
+                Primitive::Text {
+                    origin,
+                    text,
+                    font_size,
+                    variant,
+                    ink,
+                } => page.text(
+                    to_page(*origin),
+                    text,
+                    Font::Sans(*variant),
+                    font_size * scale,
+                    style.ink(*ink),
+                ),
+                Primitive::Rect {
+                    bounds,
+                    fill,
+                    stroke,
+                    dashed,
+                    ..
+                } => {
+                    let bounds = Bounds::new(to_page(bounds.origin), bounds.size * scale);
+                    if let Some(fill) = fill {
+                        page.fill_rect(bounds, style.ink(*fill));
+                    }
+                    if let Some(stroke) = stroke {
+                        page.stroke_rect(bounds, style.ink(*stroke), scale, *dashed);
+                    }
+                }
+                Primitive::Polygon {
+                    points,
+                    fill,
+                    stroke,
+                } => page.path(
+                    &points.iter().copied().map(to_page).collect::<Vec<_>>(),
+                    true,
+                    fill.map(|fill| style.ink(fill)),
+                    stroke.map(|stroke| (style.ink(stroke), scale)),
+                    false,
+                ),
+                Primitive::Polyline {
+                    points,
+                    width,
+                    ink,
+                    dashed,
+                } => page.path(
+                    &points.iter().copied().map(to_page).collect::<Vec<_>>(),
+                    false,
+                    None,
+                    Some((style.ink(*ink), width * scale)),
+                    *dashed,
+                ),
+            }
+        }
+        self.y += height + BLOCK_GAP;
+    }
+}
+
+/// Decodes an image and re-encodes it as a JPEG, which PDF readers can draw
+/// directly. JPEG has no transparency, so the image is flattened onto the page
+/// background.
+fn encode_jpeg(bytes: &[u8], background: Hsla) -> Result<PdfImage> {
+    let image = image::load_from_memory(bytes)?.into_rgba8();
+    let background = Rgba::from(background);
+    let background = [background.r, background.g, background.b];
+    let flattened = image::RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = image.get_pixel(x, y).0;
+        let alpha = pixel[3] as f32 / 255.;
+        image::Rgb(std::array::from_fn(|channel| {
+            (pixel[channel] as f32 * alpha + background[channel] * 255. * (1. - alpha)).round()
+                as u8
+        }))
+    });
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 90).encode_image(&flattened)?;
+    Ok(PdfImage {
+        width: flattened.width(),
+        height: flattened.height(),
+        jpeg,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown_parser::parse_markdown;
+
+    fn span(text: &str) -> Span {
+        Span {
+            text: text.to_string(),
+            font: Font::Mono(TextVariant::REGULAR),
+            font_size: 10.,
+            color: gpui::black(),
+            background: None,
+            underline: false,
+            strikethrough: false,
+            url: None,
+        }
+    }
+
+    fn line_texts(lines: &[Line]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| {
+                line.pieces
+                    .iter()
+                    .map(|piece| piece.text.as_str())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap() {
+        // Each character is 6 points wide, so 10 fit on a line.
+        let spans = [span("one two  three"), span(" four\nfive abcdefghijklmn")];
+        let lines = wrap(&spans, 60., 10.);
+        assert_eq!(
+            line_texts(&lines),
+            ["one two", "three four", "five", "abcdefghij", "klmn"]
+        );
+        assert_eq!(lines[1].pieces.len(), 2);
+        assert_eq!(lines[1].pieces[1].x, 30.);
+        assert_eq!(lines[1].width, 60.);
+
+        assert_eq!(line_texts(&wrap(&[], 60., 10.)), [""]);
+    }
+
+    #[gpui::test]
+    async fn test_export_pdf() {
+        let markdown = parse_markdown(
+            "\
+# Report
+
+Some *text* with a [link](https://zed.dev).
+
+1. First
+2. Second
+
+| A | B |
+|---|---|
+| 1 | 2 |
+
+```
+fn main() {}
+```
+
+$$\\alpha + \\beta$$
+",
+            None,
+            None,
+        )
+        .await;
+        let pdf = export_pdf(
+            &markdown,
+            "Report",
+            &ExportStyle::test(),
+            &ExportImages::default(),
+        )
+        .unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Count 1"));
+        assert!(text.contains("/Title (Report)"));
+        // Headings are bold, emphasis is oblique and code is monospaced.
+        assert!(text.contains("/F1 22 Tf (Report) Tj"));
+        assert!(text.contains("/F2 11 Tf (text) Tj"));
+        assert!(text.contains("/F4 9 Tf (fn main\\(\\) {}) Tj"));
+        assert!(text.contains("/URI (https://zed.dev)"));
+        assert!(text.contains("(1.) Tj"));
+        // Greek letters in math come from the symbol font.
+        assert!(text.contains("/F8 11 Tf (a) Tj"));
+    }
+
+    #[gpui::test]
+    async fn test_export_pdf_page_breaks() {
+        let source = (0..200)
+            .map(|index| format!("Paragraph {index}\n\n"))
+            .collect::<String>();
+        let markdown = parse_markdown(&source, None, None).await;
+        let pdf = export_pdf(
+            &markdown,
+            "Long",
+            &ExportStyle::test(),
+            &ExportImages::default(),
+        )
+        .unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        let page_count = text
+            .split("/Count ")
+            .nth(1)
+            .and_then(|rest| rest.split(' ').next())
+            .and_then(|count| count.parse::<usize>().ok())
+            .unwrap();
+        assert!(page_count > 1);
+        assert!(text.contains("(Paragraph 199) Tj"));
+    }
+}
@@ -0,0 +1,1083 @@
+//! A minimal PDF writer, supporting only what exported documents need: text in
+//! the standard fonts, filled and stroked shapes, JPEG images and web links.
+//!
+//! The standard fonts only cover Latin scripts and common symbols, so other
+//! characters are set in a bundled font, which is embedded in the documents
+//! that use it.
+//!
+//! Coordinates passed to a [`Page`] have their origin at the top left corner
+//! of the page, with `y` growing downwards, and are measured in points.
+
+use anyhow::Result;
+use assets::Assets;
+use gpui::{AssetSource as _, Bounds, Hsla, Point, Rgba, Size};
+use markdown::drawing::TextVariant;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    sync::LazyLock,
+};
+use ttf_parser::{Face, GlyphId};
+use util::ResultExt as _;
+
+/// The font characters that the standard fonts lack are set in.
+const FALLBACK_FONT_PATH: &str = "fonts/ibm-plex-sans/IBMPlexSans-Regular.ttf";
+const FALLBACK_FONT_NAME: &str = "IBMPlexSans";
+
+struct FallbackFont {
+    data: &'static [u8],
+    face: Face<'static>,
+}
+
+static FALLBACK_FONT: LazyLock<Option<FallbackFont>> = LazyLock::new(|| {
+    let data = Assets.load(FALLBACK_FONT_PATH).log_err().flatten()?;
+    let data: &'static [u8] = match data {
+        Cow::Borrowed(data) => data,
+        // Loaded once, and needed for as long as documents can be exported.
+        Cow::Owned(data) => data.leak(),
+    };
+    let face = Face::parse(data, 0).log_err()?;
+    Some(FallbackFont { data, face })
+});
+
+/// A font text is set in: one of the fonts every PDF reader provides, which
+/// never need embedding, or the fallback font.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Font {
+    Sans(TextVariant),
+    Mono(TextVariant),
+    Symbol,
+    Fallback,
+}
+
+impl Font {
+    const ALL: [Font; 10] = [
+        Font::Sans(TextVariant::REGULAR),
+        Font::Sans(TextVariant::BOLD),
+        Font::Sans(TextVariant::ITALIC),
+        Font::Sans(TextVariant {
+            bold: true,
+            italic: true,
+        }),
+        Font::Mono(TextVariant::REGULAR),
+        Font::Mono(TextVariant::BOLD),
+        Font::Mono(TextVariant::ITALIC),
+        Font::Mono(TextVariant {
+            bold: true,
+            italic: true,
+        }),
+        Font::Symbol,
+        Font::Fallback,
+    ];
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Sans(variant) => match (variant.bold, variant.italic) {
+                (false, false) => "Helvetica",
+                (true, false) => "Helvetica-Bold",
+                (false, true) => "Helvetica-Oblique",
+                (true, true) => "Helvetica-BoldOblique",
+            },
+            Font::Mono(variant) => match (variant.bold, variant.italic) {
+                (false, false) => "Courier",
+                (true, false) => "Courier-Bold",
+                (false, true) => "Courier-Oblique",
+                (true, true) => "Courier-BoldOblique",
+            },
+            Font::Symbol => "Symbol",
+            Font::Fallback => FALLBACK_FONT_NAME,
+        }
+    }
+
+    fn resource_name(self) -> String {
+        let index = Self::ALL
+            .iter()
+            .position(|font| *font == self)
+            .unwrap_or_default();
+        format!("F{index}")
+    }
+
+    /// How `c` is set when using this font. Characters this font lacks come
+    /// from the symbol font if it has them, and from the fallback font
+    /// otherwise. This is `None` only if the fallback font failed to load.
+    pub fn glyph(self, c: char) -> Option<Glyph> {
+        let c = if c == '\t' { ' ' } else { c };
+        let font = match self {
+            Font::Symbol | Font::Fallback => Font::Sans(TextVariant::REGULAR),
+            font => font,
+        };
+        if let Some(code) = win_ansi_code(c) {
+            let width = match font {
+                Font::Mono(_) => 600,
+                Font::Sans(variant) => helvetica_width(code, variant.bold),
+                Font::Symbol | Font::Fallback => unreachable!(),
+            };
+            Some(Glyph {
+                font,
+                code: code.into(),
+                width,
+            })
+        } else if let Some((code, width)) = symbol_glyph(c) {
+            Some(Glyph {
+                font: Font::Symbol,
+                code: code.into(),
+                width,
+            })
+        } else {
+            fallback_glyph(c)
+        }
+    }
+}
+
+/// Characters the fallback font lacks too are shown as its missing glyph box.
+fn fallback_glyph(c: char) -> Option<Glyph> {
+    let font = FALLBACK_FONT.as_ref()?;
+    let id = font.face.glyph_index(c).unwrap_or(GlyphId(0));
+    let advance = font.face.glyph_hor_advance(id).unwrap_or_default();
+    Some(Glyph {
+        font: Font::Fallback,
+        code: id.0,
+        width: (u32::from(advance) * 1000 / u32::from(font.face.units_per_em())) as u16,
+    })
+}
+
+/// A character as set in a particular font.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Glyph {
+    pub font: Font,
+    /// The character's code in the font's encoding, which is its glyph ID in
+    /// the fallback font.
+    pub code: u16,
+    /// The advance, in thousandths of the font size.
+    pub width: u16,
+}
+
+/// The width of `text` when set in `font` at `font_size`. Characters that
+/// can't be set take no space.
+pub(super) fn text_width(text: &str, font: Font, font_size: f32) -> f32 {
+    text.chars()
+        .filter_map(|c| font.glyph(c))
+        .map(|glyph| glyph.width as f32)
+        .sum::<f32>()
+        * font_size
+        / 1000.
+}
+
+/// The height of the standard fonts' ascenders and descenders, as a fraction of
+/// the font size.
+pub(super) const ASCENT: f32 = 0.718;
+pub(super) const DESCENT: f32 = 0.207;
+
+fn win_ansi_code(c: char) -> Option<u8> {
+    match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => Some(c as u8),
+        '€' => Some(0x80),
+        '‚' => Some(0x82),
+        'ƒ' => Some(0x83),
+        '„' => Some(0x84),
+        '…' => Some(0x85),
+        '†' => Some(0x86),
+        '‡' => Some(0x87),
+        'ˆ' => Some(0x88),
+        '‰' => Some(0x89),
+        'Š' => Some(0x8a),
+        '‹' => Some(0x8b),
+        'Œ' => Some(0x8c),
+        'Ž' => Some(0x8e),
+        '‘' => Some(0x91),
+        '’' => Some(0x92),
+        '“' => Some(0x93),
+        '”' => Some(0x94),
+        '•' => Some(0x95),
+        '–' => Some(0x96),
+        '—' => Some(0x97),
+        '˜' => Some(0x98),
+        '™' => Some(0x99),
+        'š' => Some(0x9a),
+        '›' => Some(0x9b),
+        'œ' => Some(0x9c),
+        'ž' => Some(0x9e),
+        'Ÿ' => Some(0x9f),
+        _ => None,
+    }
+}
+
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+fn helvetica_width(code: u8, bold: bool) -> u16 {
+    match code {
+        0x20..=0x7e => {
+            let widths = if bold {
+                &HELVETICA_BOLD_WIDTHS
+            } else {
+                &HELVETICA_WIDTHS
+            };
+            widths[(code - 0x20) as usize]
+        }
+        0x85 | 0x89 | 0x97 | 0x99 => 1000,
+        0x91 | 0x92 | 0x82 => 222,
+        0x93 | 0x94 | 0x84 => 333,
+        0x95 => 350,
+        0xa0 => 278,
+        // The remaining characters are mostly accented letters, which are about
+        // as wide as a digit.
+        _ => 556,
+    }
+}
+
+/// The code and width of the characters the symbol font has that the text
+/// fonts lack, which covers Greek letters and most common math symbols.
+fn symbol_glyph(c: char) -> Option<(u8, u16)> {
+    Some(match c {
+        'Α' => (0x41, 722),
+        'Β' => (0x42, 667),
+        'Γ' => (0x47, 603),
+        'Δ' => (0x44, 612),
+        'Ε' => (0x45, 611),
+        'Ζ' => (0x5a, 611),
+        'Η' => (0x48, 722),
+        'Θ' => (0x51, 741),
+        'Ι' => (0x49, 333),
+        'Κ' => (0x4b, 722),
+        'Λ' => (0x4c, 686),
+        'Μ' => (0x4d, 889),
+        'Ν' => (0x4e, 722),
+        'Ξ' => (0x58, 645),
+        'Ο' => (0x4f, 722),
+        'Π' => (0x50, 768),
+        'Ρ' => (0x52, 556),
+        'Σ' => (0x53, 592),
+        'Τ' => (0x54, 611),
+        'Υ' => (0xa1, 620),
+        'Φ' => (0x46, 763),
+        'Χ' => (0x43, 722),
+        'Ψ' => (0x59, 795),
+        'Ω' => (0x57, 768),
+        'α' => (0x61, 631),
+        'β' => (0x62, 549),
+        'γ' => (0x67, 411),
+        'δ' => (0x64, 494),
+        'ε' | 'ϵ' => (0x65, 439),
+        'ζ' => (0x7a, 494),
+        'η' => (0x68, 603),
+        'θ' => (0x71, 521),
+        'ϑ' => (0x4a, 631),
+        'ι' => (0x69, 329),
+        'κ' => (0x6b, 549),
+        'λ' => (0x6c, 549),
+        'μ' => (0x6d, 576),
+        'ν' => (0x6e, 521),
+        'ξ' => (0x78, 493),
+        'ο' => (0x6f, 549),
+        'π' => (0x70, 549),
+        'ϖ' => (0x76, 713),
+        'ρ' => (0x72, 549),
+        'σ' => (0x73, 603),
+        'ς' => (0x56, 439),
+        'τ' => (0x74, 439),
+        'υ' => (0x75, 576),
+        'φ' => (0x6a, 603),
+        'ϕ' => (0x66, 521),
+        'χ' => (0x63, 549),
+        'ψ' => (0x79, 686),
+        'ω' => (0x77, 686),
+        '∀' => (0x22, 713),
+        '∃' => (0x24, 549),
+        '∋' => (0x27, 439),
+        '∗' => (0x2a, 500),
+        '−' => (0x2d, 549),
+        '≅' => (0x40, 549),
+        '∴' => (0x5c, 863),
+        '⊥' => (0x5e, 658),
+        '∼' => (0x7e, 549),
+        '′' => (0xa2, 247),
+        '≤' => (0xa3, 549),
+        '⁄' => (0xa4, 167),
+        '∞' => (0xa5, 713),
+        '↔' => (0xab, 1042),
+        '←' => (0xac, 987),
+        '↑' => (0xad, 603),
+        '→' | '⟶' => (0xae, 987),
+        '↓' => (0xaf, 603),
+        '″' => (0xb2, 411),
+        '≥' => (0xb3, 549),
+        '∝' => (0xb5, 713),
+        '∂' => (0xb6, 494),
+        '≠' => (0xb9, 549),
+        '≡' => (0xba, 549),
+        '≈' => (0xbb, 549),
+        'ℵ' => (0xc0, 823),
+        'ℑ' => (0xc1, 686),
+        'ℜ' => (0xc2, 795),
+        '℘' => (0xc3, 987),
+        '⊗' => (0xc4, 768),
+        '⊕' => (0xc5, 768),
+        '∅' => (0xc6, 823),
+        '∩' => (0xc7, 768),
+        '∪' => (0xc8, 768),
+        '⊃' => (0xc9, 713),
+        '⊇' => (0xca, 713),
+        '⊄' => (0xcb, 713),
+        '⊂' => (0xcc, 713),
+        '⊆' => (0xcd, 713),
+        '∈' => (0xce, 713),
+        '∉' => (0xcf, 713),
+        '∠' => (0xd0, 768),
+        '∇' => (0xd1, 713),
+        '∏' => (0xd5, 823),
+        '√' => (0xd6, 549),
+        '⋅' | '∙' => (0xd7, 250),
+        '∧' => (0xd9, 603),
+        '∨' => (0xda, 603),
+        '⇔' => (0xdb, 1042),
+        '⇐' => (0xdc, 987),
+        '⇑' => (0xdd, 603),
+        '⇒' | '⟹' => (0xde, 987),
+        '⇓' => (0xdf, 603),
+        '◊' => (0xe0, 494),
+        '⟨' | '〈' => (0xe1, 329),
+        '∑' => (0xe5, 713),
+        '⟩' | '〉' => (0xf1, 329),
+        '∫' => (0xf2, 274),
+        _ => return None,
+    })
+}
+
+/// A JPEG image drawn on one or more pages.
+pub(super) struct PdfImage {
+    pub width: u32,
+    pub height: u32,
+    pub jpeg: Vec<u8>,
+}
+
+/// The contents of a single page.
+pub(super) struct Page {
+    size: Size<f32>,
+    background: Hsla,
+    content: String,
+    links: Vec<(Bounds<f32>, String)>,
+    /// The glyphs of the fallback font the page uses, with the character and
+    /// width of each.
+    fallback_glyphs: BTreeMap<u16, (char, u16)>,
+    /// Characters that were left out of the page's text, since the fallback
+    /// font couldn't be loaded.
+    missing_chars: BTreeSet<char>,
+}
+
+impl Page {
+    pub fn new(size: Size<f32>, background: Hsla) -> Self {
+        let mut page = Self {
+            size,
+            background,
+            content: String::new(),
+            links: Vec::new(),
+            fallback_glyphs: BTreeMap::new(),
+            missing_chars: BTreeSet::new(),
+        };
+        page.fill_rect(Bounds::new(Point::default(), size), background);
+        page
+    }
+
+    pub fn fill_rect(&mut self, bounds: Bounds<f32>, color: Hsla) {
+        let color = self.color(color);
+        let (x, y) = self.to_pdf(bounds.bottom_left());
+        writeln!(
+            self.content,
+            "{color} rg {} {} {} {} re f",
+            number(x),
+            number(y),
+            number(bounds.size.width),
+            number(bounds.size.height),
+        )
+        .ok();
+    }
+
+    pub fn stroke_rect(&mut self, bounds: Bounds<f32>, color: Hsla, width: f32, dashed: bool) {
+        let color = self.color(color);
+        let (x, y) = self.to_pdf(bounds.bottom_left());
+        writeln!(
+            self.content,
+            "{color} RG {} w {} {} {} {} {} re S{}",
+            number(width),
+            dash(dashed),
+            number(x),
+            number(y),
+            number(bounds.size.width),
+            number(bounds.size.height),
+            undash(dashed),
+        )
+        .ok();
+    }
+
+    /// Draws a line through `points`, or a polygon if `closed` is true.
+    pub fn path(
+        &mut self,
+        points: &[Point<f32>],
+        closed: bool,
+        fill: Option<Hsla>,
+        stroke: Option<(Hsla, f32)>,
+        dashed: bool,
+    ) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+        if let Some(fill) = fill {
+            let color = self.color(fill);
+            write!(self.content, "{color} rg ").ok();
+        }
+        if let Some((stroke, width)) = stroke {
+            let color = self.color(stroke);
+            write!(
+                self.content,
+                "{color} RG {} w 1 J 1 j {}",
+                number(width),
+                dash(dashed)
+            )
+            .ok();
+        }
+        let (x, y) = self.to_pdf(*first);
+        write!(self.content, "{} {} m", number(x), number(y)).ok();
+        for point in rest {
+            let (x, y) = self.to_pdf(*point);
+            write!(self.content, " {} {} l", number(x), number(y)).ok();
+        }
+        if closed {
+            self.content.push_str(" h");
+        }
+        let operator = match (fill.is_some(), stroke.is_some()) {
+            (true, true) => "B",
+            (true, false) => "f",
+            _ => "S",
+        };
+        writeln!(
+            self.content,
+            " {operator}{}",
+            undash(dashed && stroke.is_some())
+        )
+        .ok();
+    }
+
+    /// Sets `text` on a single line, starting at the left end of its baseline.
+    pub fn text(
+        &mut self,
+        origin: Point<f32>,
+        text: &str,
+        font: Font,
+        font_size: f32,
+        color: Hsla,
+    ) {
+        if text.is_empty() {
+            return;
+        }
+        let color = self.color(color);
+        let (x, y) = self.to_pdf(origin);
+        write!(self.content, "BT {color} rg {} {} Td", number(x), number(y)).ok();
+        let mut current_font = None;
+        let mut codes = Vec::new();
+        for c in text.chars() {
+            let Some(glyph) = font.glyph(c) else {
+                self.missing_chars.insert(c);
+                continue;
+            };
+            if current_font != Some(glyph.font) {
+                self.show_text(current_font, &codes);
+                codes.clear();
+                write!(
+                    self.content,
+                    " /{} {} Tf",
+                    glyph.font.resource_name(),
+                    number(font_size)
+                )
+                .ok();
+                current_font = Some(glyph.font);
+            }
+            if glyph.font == Font::Fallback {
+                self.fallback_glyphs
+                    .entry(glyph.code)
+                    .or_insert((c, glyph.width));
+            }
+            codes.push(glyph.code);
+        }
+        self.show_text(current_font, &codes);
+        self.content.push_str(" ET\n");
+    }
+
+    fn show_text(&mut self, font: Option<Font>, codes: &[u16]) {
+        if codes.is_empty() {
+            return;
+        }
+        let text = if font == Some(Font::Fallback) {
+            // The fallback font is encoded with two byte glyph IDs.
+            let mut text = String::from("<");
+            for code in codes {
+                write!(text, "{code:04X}").ok();
+            }
+            text.push('>');
+            text
+        } else {
+            literal_string(&codes.iter().map(|code| *code as u8).collect::<Vec<_>>())
+        };
+        write!(self.content, " {text} Tj").ok();
+    }
+
+    /// Draws the image that [`PdfDocument::add_image`] returned `index` for.
+    pub fn image(&mut self, index: usize, bounds: Bounds<f32>) {
+        let (x, y) = self.to_pdf(bounds.bottom_left());
+        writeln!(
+            self.content,
+            "q {} 0 0 {} {} {} cm /Im{index} Do Q",
+            number(bounds.size.width),
+            number(bounds.size.height),
+            number(x),
+            number(y),
+        )
+        .ok();
+    }
+
+    /// Makes the given area of the page open `url` when clicked.
+    pub fn link(&mut self, bounds: Bounds<f32>, url: String) {
+        self.links.push((bounds, url));
+    }
+
+    fn to_pdf(&self, point: Point<f32>) -> (f32, f32) {
+        (point.x, self.size.height - point.y)
+    }
+
+    /// PDF has no transparency without extra machinery, so translucent colors
+    /// are blended with the page background up front.
+    fn color(&self, color: Hsla) -> String {
+        let color = Rgba::from(color);
+        let background = Rgba::from(self.background);
+        let blend = |channel: f32, background: f32| channel * color.a + background * (1. - color.a);
+        format!(
+            "{} {} {}",
+            number(blend(color.r, background.r)),
+            number(blend(color.g, background.g)),
+            number(blend(color.b, background.b)),
+        )
+    }
+}
+
+fn dash(dashed: bool) -> &'static str {
+    if dashed { "[4 3] 0 d " } else { "" }
+}
+
+fn undash(dashed: bool) -> &'static str {
+    if dashed { " [] 0 d" } else { "" }
+}
+
+/// Formats a number with at most two decimals, which is plenty for points.
+fn number(value: f32) -> String {
+    let value = (value * 100.).round() / 100.;
+    if value == value.trunc() {
+        format!("{}", value as i64)
+    } else {
+        format!("{value}")
+    }
+}
+
+fn literal_string(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() + 2);
+    result.push('(');
+    for &byte in bytes {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                result.push('\\');
+                result.push(byte as char);
+            }
+            0x20..=0x7e => result.push(byte as char),
+            _ => {
+                write!(result, "\\{byte:03o}").ok();
+            }
+        }
+    }
+    result.push(')');
+    result
+}
+
+/// Encodes text for the document information dictionary, which unlike page
+/// content can hold any Unicode text.
+fn text_string(text: &str) -> String {
+    if text.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+        literal_string(text.as_bytes())
+    } else {
+        let mut result = String::from("<FEFF");
+        for unit in text.encode_utf16() {
+            write!(result, "{unit:04X}").ok();
+        }
+        result.push('>');
+        result
+    }
+}
+
+/// A document being assembled from pages and the images they draw.
+pub(super) struct PdfDocument {
+    title: String,
+    pages: Vec<Page>,
+    images: Vec<PdfImage>,
+}
+
+impl PdfDocument {
+    pub fn new(title: String) -> Self {
+        Self {
+            title,
+            pages: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    pub fn add_image(&mut self, image: PdfImage) -> usize {
+        self.images.push(image);
+        self.images.len() - 1
+    }
+
+    pub fn push_page(&mut self, page: Page) {
+        self.pages.push(page);
+    }
+
+    /// Writes the document, failing if any of its text couldn't be set.
+    pub fn finish(self) -> Result<Vec<u8>> {
+        let missing_chars = self
+            .pages
+            .iter()
+            .flat_map(|page| &page.missing_chars)
+            .collect::<BTreeSet<_>>();
+        if !missing_chars.is_empty() {
+            anyhow::bail!(
+                "failed to load the font for {} in the document. Export it as HTML instead.",
+                missing_chars
+                    .iter()
+                    .take(8)
+                    .map(|c| format!("\"{c}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        let mut writer = ObjectWriter::default();
+        let catalog = writer.reserve();
+        let page_tree = writer.reserve();
+        let info = writer.reserve();
+
+        let fallback_glyphs = self
+            .pages
+            .iter()
+            .flat_map(|page| &page.fallback_glyphs)
+            .map(|(id, glyph)| (*id, *glyph))
+            .collect::<BTreeMap<_, _>>();
+
+        let fonts = Font::ALL
+            .iter()
+            .filter_map(|font| {
+                if *font == Font::Fallback {
+                    let fallback_font = FALLBACK_FONT.as_ref()?;
+                    if fallback_glyphs.is_empty() {
+                        return None;
+                    }
+                    let id = write_fallback_font(&mut writer, fallback_font, &fallback_glyphs);
+                    return Some(format!("/{} {id} 0 R", font.resource_name()));
+                }
+
+                let encoding = if *font == Font::Symbol {
+                    ""
+                } else {
+                    " /Encoding /WinAnsiEncoding"
+                };
+                let id = writer.reserve();
+                writer.write_object(
+                    id,
+                    format!(
+                        "<< /Type /Font /Subtype /Type1 /BaseFont /{}{encoding} >>",
+                        font.base_font()
+                    )
+                    .as_bytes(),
+                );
+                Some(format!("/{} {id} 0 R", font.resource_name()))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let images = self
+            .images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| {
+                let id = writer.reserve();
+                writer.write_stream(
+                    id,
+                    &format!(
+                        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+                        image.width, image.height
+                    ),
+                    &image.jpeg,
+                );
+                format!("/Im{index} {id} 0 R")
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let resources = format!("<< /Font << {fonts} >> /XObject << {images} >> >>");
+
+        let mut page_ids = Vec::new();
+        for page in &self.pages {
+            let content = writer.reserve();
+            writer.write_stream(content, "", page.content.as_bytes());
+
+            let annotations = page
+                .links
+                .iter()
+                .map(|(bounds, url)| {
+                    let id = writer.reserve();
+                    let (left, bottom) = page.to_pdf(bounds.bottom_left());
+                    let (right, top) = page.to_pdf(bounds.top_right());
+                    writer.write_object(
+                        id,
+                        format!(
+                            "<< /Type /Annot /Subtype /Link /Rect [{} {} {} {}] /Border [0 0 0] /A << /S /URI /URI {} >> >>",
+                            number(left),
+                            number(bottom),
+                            number(right),
+                            number(top),
+                            literal_string(url.as_bytes()),
+                        )
+                        .as_bytes(),
+                    );
+                    format!("{id} 0 R")
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let id = writer.reserve();
+            writer.write_object(
+                id,
+                format!(
+                    "<< /Type /Page /Parent {page_tree} 0 R /MediaBox [0 0 {} {}] /Resources {resources} /Contents {content} 0 R /Annots [{annotations}] >>",
+                    number(page.size.width),
+                    number(page.size.height),
+                )
+                .as_bytes(),
+            );
+            page_ids.push(format!("{id} 0 R"));
+        }
+
+        writer.write_object(
+            page_tree,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids.join(" "),
+                page_ids.len()
+            )
+            .as_bytes(),
+        );
+        writer.write_object(
+            catalog,
+            format!("<< /Type /Catalog /Pages {page_tree} 0 R >>").as_bytes(),
+        );
+        writer.write_object(
+            info,
+            format!("<< /Title {} /Producer (Zed) >>", text_string(&self.title)).as_bytes(),
+        );
+        Ok(writer.finish(catalog, info))
+    }
+}
+
+/// Embeds the fallback font as a composite font whose character codes are
+/// glyph IDs, with a map from the glyphs the document uses back to Unicode so
+/// that its text can be selected and searched.
+fn write_fallback_font(
+    writer: &mut ObjectWriter,
+    font: &FallbackFont,
+    glyphs: &BTreeMap<u16, (char, u16)>,
+) -> usize {
+    let face = &font.face;
+    let scale = |value: i16| i32::from(value) * 1000 / i32::from(face.units_per_em());
+
+    let font_file = writer.reserve();
+    writer.write_stream(
+        font_file,
+        &format!("/Length1 {}", font.data.len()),
+        font.data,
+    );
+
+    let descriptor = writer.reserve();
+    let bounding_box = face.global_bounding_box();
+    writer.write_object(
+        descriptor,
+        format!(
+            "<< /Type /FontDescriptor /FontName /{FALLBACK_FONT_NAME} /Flags 32 /FontBBox [{} {} {} {}] /ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /FontFile2 {font_file} 0 R >>",
+            scale(bounding_box.x_min),
+            scale(bounding_box.y_min),
+            scale(bounding_box.x_max),
+            scale(bounding_box.y_max),
+            scale(face.ascender()),
+            scale(face.descender()),
+            scale(face.capital_height().unwrap_or(face.ascender())),
+        )
+        .as_bytes(),
+    );
+
+    let widths = glyphs
+        .iter()
+        .map(|(id, (_, width))| format!("{id} [{width}]"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let descendant = writer.reserve();
+    writer.write_object(
+        descendant,
+        format!(
+            "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{FALLBACK_FONT_NAME} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {descriptor} 0 R /CIDToGIDMap /Identity /W [{widths}] >>"
+        )
+        .as_bytes(),
+    );
+
+    let to_unicode = writer.reserve();
+    writer.write_stream(to_unicode, "", to_unicode_cmap(glyphs).as_bytes());
+
+    let id = writer.reserve();
+    writer.write_object(
+        id,
+        format!(
+            "<< /Type /Font /Subtype /Type0 /BaseFont /{FALLBACK_FONT_NAME} /Encoding /Identity-H /DescendantFonts [{descendant} 0 R] /ToUnicode {to_unicode} 0 R >>"
+        )
+        .as_bytes(),
+    );
+    id
+}
+
+/// A CMap from two byte glyph IDs to the characters they show.
+fn to_unicode_cmap(glyphs: &BTreeMap<u16, (char, u16)>) -> String {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    // Each block can map at most 100 codes.
+    for block in glyphs.iter().collect::<Vec<_>>().chunks(100) {
+        writeln!(cmap, "{} beginbfchar", block.len()).ok();
+        for (id, (c, _)) in block {
+            write!(cmap, "<{id:04X}> <").ok();
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                write!(cmap, "{unit:04X}").ok();
+            }
+            cmap.push_str(">\n");
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap
+}
+
+/// Writes numbered objects in any order, keeping track of their offsets for
+/// the cross-reference table.
+struct ObjectWriter {
+    output: Vec<u8>,
+    offsets: Vec<Option<usize>>,
+}
+
+impl Default for ObjectWriter {
+    fn default() -> Self {
+        Self {
+            // The second line has bytes above 127 so that tools treat the file
+            // as binary.
+            output: b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec(),
+            offsets: Vec::new(),
+        }
+    }
+}
+
+impl ObjectWriter {
+    fn reserve(&mut self) -> usize {
+        self.offsets.push(None);
+        self.offsets.len()
+    }
+
+    fn write_object(&mut self, id: usize, body: &[u8]) {
+        self.offsets[id - 1] = Some(self.output.len());
+        self.output
+            .extend_from_slice(format!("{id} 0 obj\n").as_bytes());
+        self.output.extend_from_slice(body);
+        self.output.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn write_stream(&mut self, id: usize, dictionary: &str, data: &[u8]) {
+        let mut body = format!("<< {dictionary} /Length {} >>\nstream\n", data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.write_object(id, &body);
+    }
+
+    fn finish(mut self, catalog: usize, info: usize) -> Vec<u8> {
+        let xref_offset = self.output.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+        for offset in &self.offsets {
+            writeln!(xref, "{:010} 00000 n ", offset.unwrap_or_default()).ok();
+        }
+        write!(
+            xref,
+            "trailer\n<< /Size {} /Root {catalog} 0 R /Info {info} 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            self.offsets.len() + 1
+        )
+        .ok();
+        self.output.extend_from_slice(xref.as_bytes());
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{point, size};
+
+    #[test]
+    fn test_glyphs() {
+        let regular = Font::Sans(TextVariant::REGULAR);
+        assert_eq!(
+            regular.glyph('A'),
+            Some(Glyph {
+                font: regular,
+                code: b'A'.into(),
+                width: 667
+            })
+        );
+        assert_eq!(regular.glyph('—').unwrap().code, 0x97);
+        assert_eq!(
+            regular.glyph('α'),
+            Some(Glyph {
+                font: Font::Symbol,
+                code: 0x61,
+                width: 631
+            })
+        );
+        let cyrillic = regular.glyph('Ж').unwrap();
+        assert_eq!(cyrillic.font, Font::Fallback);
+        assert_ne!(cyrillic.code, 0);
+        assert!(cyrillic.width > 0);
+        // The fallback font has no CJK glyphs.
+        assert_eq!(regular.glyph('中').unwrap().code, 0);
+        assert_eq!(
+            Font::Mono(TextVariant::BOLD).glyph('i').unwrap().width,
+            Font::Mono(TextVariant::BOLD).glyph('W').unwrap().width
+        );
+        assert_eq!(text_width("ab", regular, 10.), 11.12);
+    }
+
+    #[test]
+    fn test_literal_strings() {
+        assert_eq!(literal_string(b"a(b)\\c"), r"(a\(b\)\\c)");
+        assert_eq!(literal_string(&[0x97, b'x']), r"(\227x)");
+        assert_eq!(text_string("Notes"), "(Notes)");
+        assert_eq!(text_string("Café"), "<FEFF00430061006600E9>");
+    }
+
+    #[test]
+    fn test_document_structure() {
+        let page_size = size(200., 100.);
+        let mut document = PdfDocument::new("Test".into());
+        let mut page = Page::new(page_size, gpui::white());
+        page.text(
+            point(10., 20.),
+            "x ≤ y",
+            Font::Sans(TextVariant::REGULAR),
+            12.,
+            gpui::black(),
+        );
+        page.link(
+            Bounds::new(point(10., 10.), size(30., 12.)),
+            "https://zed.dev".into(),
+        );
+        document.push_page(page);
+        let output = document.finish().unwrap();
+        let text = String::from_utf8_lossy(&output);
+
+        assert!(output.starts_with(b"%PDF-1.4\n"));
+        assert!(output.ends_with(b"%%EOF\n"));
+        // The page's content switches to the symbol font for the `≤`, and its
+        // y coordinates grow upwards.
+        assert!(
+            text.contains("10 80 Td /F0 12 Tf (x ) Tj /F8 12 Tf (\\243) Tj /F0 12 Tf ( y) Tj ET")
+        );
+        assert!(text.contains("/Rect [10 78 40 90]"));
+        assert!(text.contains("/URI (https://zed.dev)"));
+        assert!(!text.contains("/FontFile2"));
+
+        // Every entry in the cross-reference table points at its object.
+        let position = |needle: &[u8]| {
+            output
+                .windows(needle.len())
+                .rposition(|window| window == needle)
+                .unwrap()
+        };
+        let xref_offset = std::str::from_utf8(&output[position(b"startxref\n") + 10..])
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        assert_eq!(xref_offset, position(b"xref\n0 "));
+        let entries = std::str::from_utf8(&output[xref_offset..])
+            .unwrap()
+            .lines()
+            .skip(3)
+            .take_while(|line| !line.starts_with("trailer"))
+            .map(|line| line[..10].parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+        assert!(!entries.is_empty());
+        for (index, offset) in entries.into_iter().enumerate() {
+            assert!(
+                output[offset..].starts_with(format!("{} 0 obj\n", index + 1).as_bytes()),
+                "entry {} points at the wrong offset",
+                index + 1
+            );
+        }
+    }
+
+    #[test]
+    fn test_fallback_font() {
+        let mut document = PdfDocument::new("Test".into());
+        let mut page = Page::new(size(200., 100.), gpui::white());
+        page.text(
+            point(10., 20.),
+            "a Жж",
+            Font::Sans(TextVariant::REGULAR),
+            12.,
+            gpui::black(),
+        );
+        document.push_page(page);
+        let output = document.finish().unwrap();
+        let text = String::from_utf8_lossy(&output);
+
+        let capital = Font::Fallback.glyph('Ж').unwrap().code;
+        let small = Font::Fallback.glyph('ж').unwrap().code;
+        assert!(text.contains(&format!(
+            "/F0 12 Tf (a ) Tj /F9 12 Tf <{capital:04X}{small:04X}> Tj ET"
+        )));
+        assert!(text.contains("/Subtype /Type0 /BaseFont /IBMPlexSans /Encoding /Identity-H"));
+        assert!(text.contains("/FontFile2"));
+        // Copying the text gives back the characters.
+        assert!(text.contains(&format!("<{capital:04X}> <0416>\n")));
+        assert!(text.contains(&format!("<{small:04X}> <0436>\n")));
+    }
+}
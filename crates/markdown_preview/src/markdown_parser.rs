@@ -7,6 +7,7 @@ use collections::FxHashMap;
 use gpui::{DefiniteLength, FontWeight, px, relative};
 use html5ever::{ParseOpts, local_name, parse_document, tendril::TendrilSink};
 use language::LanguageRegistry;
+use markdown::{math::parse_math, mermaid::parse_mermaid};
 use markup5ever_rcdom::RcDom;
 use pulldown_cmark::{Alignment, Event, Options, Parser, Tag, TagEnd};
 use std::{
@@ -139,6 +140,7 @@ impl<'a> MarkdownParser<'a> {
             | Event::Html(_)
             | Event::InlineHtml(_)
             | Event::FootnoteReference(_)
+            | Event::InlineMath(_)
             | Event::Start(Tag::Link { .. })
             | Event::Start(Tag::Emphasis)
             | Event::Start(Tag::Strong)
@@ -169,8 +171,30 @@ impl<'a> MarkdownParser<'a> {
             Event::Start(tag) => match tag {
                 Tag::Paragraph => {
                     self.cursor += 1;
-                    let text = self.parse_text(false, Some(source_range));
-                    Some(vec![ParsedMarkdownElement::Paragraph(text)])
+                    let mut elements = Vec::new();
+                    loop {
+                        let text = self.parse_text(false, Some(source_range.clone()));
+                        if !text.is_empty() {
+                            elements.push(ParsedMarkdownElement::Paragraph(text));
+                        }
+                        // Display math is part of a paragraph, but is rendered as its own block.
+                        let Some((Event::DisplayMath(contents), math_range)) = self.current()
+                        else {
+                            break;
+                        };
+                        let contents = SharedString::from(contents.to_string());
+                        let math_range = math_range.clone();
+                        self.cursor += 1;
+                        elements.push(ParsedMarkdownElement::Math(ParsedMarkdownMath {
+                            source_range: math_range,
+                            node: Arc::new(parse_math(&contents)),
+                            contents,
+                        }));
+                    }
+                    if elements.is_empty() {
+                        elements.push(ParsedMarkdownElement::Paragraph(Vec::new()));
+                    }
+                    Some(elements)
                 }
                 Tag::Heading { level, .. } => {
                     let level = *level;
@@ -210,6 +234,18 @@ impl<'a> MarkdownParser<'a> {
                     self.cursor += 1;
 
                     let code_block = self.parse_code_block(language).await?;
+                    if code_block.language.as_deref() == Some("mermaid") {
+                        let diagram = parse_mermaid(&code_block.contents)
+                            .map(Arc::new)
+                            .map_err(|error| SharedString::from(format!("{error:#}")));
+                        return Some(vec![ParsedMarkdownElement::Mermaid(
+                            ParsedMarkdownMermaid {
+                                source_range: code_block.source_range,
+                                contents: code_block.contents,
+                                diagram,
+                            },
+                        )]);
+                    }
                     Some(vec![ParsedMarkdownElement::CodeBlock(code_block)])
                 }
                 Tag::HtmlBlock => {
@@ -358,6 +394,17 @@ impl<'a> MarkdownParser<'a> {
                         }
                     }
                 }
+                Event::InlineMath(source) => {
+                    text.push_str(&parse_math(source).to_unicode());
+                    highlights.push((
+                        prev_len..text.len(),
+                        MarkdownHighlight::Style(MarkdownHighlightStyle {
+                            italic: true,
+                            link: link.is_some(),
+                            ..Default::default()
+                        }),
+                    ));
+                }
                 Event::Code(t) => {
                     text.push_str(t.as_ref());
                     let range = prev_len..text.len();
@@ -3048,6 +3095,40 @@ fn main() {
         );
     }
 
+    #[gpui::test]
+    async fn test_display_math() {
+        let parsed = parse("Before\n$$x^2$$\nafter").await;
+
+        assert_eq!(parsed.children.len(), 3);
+        assert_eq!(
+            parsed.children[1],
+            ParsedMarkdownElement::Math(ParsedMarkdownMath {
+                source_range: 7..14,
+                contents: "x^2".into(),
+                node: Arc::new(parse_math("x^2")),
+            })
+        );
+    }
+
+    #[gpui::test]
+    async fn test_mermaid_block() {
+        let parsed = parse("```mermaid\ngraph TD\n  A --> B\n```\n").await;
+        let [ParsedMarkdownElement::Mermaid(mermaid)] = parsed.children.as_slice() else {
+            panic!("expected a single mermaid block, got {:?}", parsed.children);
+        };
+        assert_eq!(mermaid.contents, "graph TD\n  A --> B");
+        assert!(mermaid.diagram.is_ok());
+
+        let parsed = parse("```mermaid\npie\n  \"A\" : 1\n```\n").await;
+        let [ParsedMarkdownElement::Mermaid(mermaid)] = parsed.children.as_slice() else {
+            panic!("expected a single mermaid block, got {:?}", parsed.children);
+        };
+        assert_eq!(
+            mermaid.diagram,
+            Err("unsupported diagram type \"pie\"".into())
+        );
+    }
+
     #[gpui::test]
     async fn test_code_block_with_language(executor: BackgroundExecutor) {
         let language_registry = Arc::new(LanguageRegistry::test(executor.clone()));
@@ -2,6 +2,7 @@ use gpui::{App, actions};
 use workspace::Workspace;
 
 pub mod markdown_elements;
+pub mod markdown_export;
 mod markdown_minifier;
 pub mod markdown_parser;
 pub mod markdown_preview_view;
@@ -19,7 +20,11 @@ actions!(
         /// Opens a markdown preview in a split pane.
         OpenPreviewToTheSide,
         /// Opens a following markdown preview that syncs with the editor.
-        OpenFollowingPreview
+        OpenFollowingPreview,
+        /// Exports the current markdown file as a standalone HTML page.
+        ExportAsHtml,
+        /// Exports the current markdown file as a PDF document.
+        ExportAsPdf
     ]
 );
 
@@ -15,13 +15,16 @@ use settings::Settings;
 use theme::ThemeSettings;
 use ui::{WithScrollbar, prelude::*};
 use workspace::item::{Item, ItemHandle};
+use workspace::notifications::DetachAndPromptErr as _;
 use workspace::{Pane, Workspace};
 
 use crate::markdown_elements::ParsedMarkdownElement;
 use crate::markdown_renderer::CheckboxClickedEvent;
 use crate::{
-    MovePageDown, MovePageUp, OpenFollowingPreview, OpenPreview, OpenPreviewToTheSide,
+    ExportAsHtml, ExportAsPdf, MovePageDown, MovePageUp, OpenFollowingPreview, OpenPreview,
+    OpenPreviewToTheSide,
     markdown_elements::ParsedMarkdown,
+    markdown_export::{ExportFormat, ExportStyle, export_markdown},
     markdown_parser::parse_markdown,
     markdown_renderer::{RenderContext, render_markdown_block},
 };
@@ -123,6 +126,77 @@ impl MarkdownPreviewView {
                 cx.notify();
             }
         });
+
+        workspace.register_action(move |workspace, _: &ExportAsHtml, window, cx| {
+            Self::export(workspace, ExportFormat::Html, window, cx);
+        });
+
+        workspace.register_action(move |workspace, _: &ExportAsPdf, window, cx| {
+            Self::export(workspace, ExportFormat::Pdf, window, cx);
+        });
+    }
+
+    /// Exports the markdown file shown by the active preview, or open in the
+    /// active editor, to a location the user picks.
+    fn export(
+        workspace: &mut Workspace,
+        format: ExportFormat,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let editor = workspace
+            .active_item(cx)
+            .and_then(|item| item.downcast::<MarkdownPreviewView>())
+            .and_then(|view| {
+                let view = view.read(cx);
+                view.active_editor
+                    .as_ref()
+                    .map(|state| state.editor.clone())
+            })
+            .or_else(|| Self::resolve_active_item_as_markdown_editor(workspace, cx));
+        let Some(editor) = editor else {
+            return;
+        };
+
+        let editor = editor.read(cx);
+        let source = editor.buffer().read(cx).snapshot(cx).text();
+        let file_location = Self::get_folder_for_active_editor(editor, cx);
+        let title = editor
+            .file_at(MultiBufferOffset(0), cx)
+            .and_then(|file| {
+                let file_name = file.path().file_stem()?;
+                Some(file_name.to_string())
+            })
+            .unwrap_or_else(|| "Untitled".to_string());
+        let language_registry = workspace.project().read(cx).languages().clone();
+        let fs = workspace.app_state().fs.clone();
+        let http_client = cx.http_client();
+        let style = ExportStyle::from_theme(cx);
+
+        let directory = file_location
+            .clone()
+            .or_else(std::env::home_dir)
+            .unwrap_or_default();
+        let suggested_name = format!("{title}.{}", format.extension());
+        let path = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+        cx.spawn_in(window, async move |_, _| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            export_markdown(
+                source,
+                file_location,
+                language_registry,
+                title,
+                format,
+                style,
+                fs,
+                http_client,
+                path,
+            )
+            .await
+        })
+        .detach_and_prompt_err("Failed to export markdown", window, cx, |_, _, _| None);
     }
 
     fn find_existing_independent_preview_item_idx(
@@ -1,8 +1,9 @@
 use crate::markdown_elements::{
     HeadingLevel, Image, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
     ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
-    ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownTable,
-    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+    ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownMath,
+    ParsedMarkdownMermaid, ParsedMarkdownTable, ParsedMarkdownTableAlignment,
+    ParsedMarkdownTableRow,
 };
 use fs::normalize_path;
 use gpui::{
@@ -186,6 +187,8 @@ pub fn render_markdown_block(block: &ParsedMarkdownElement, cx: &mut RenderConte
         CodeBlock(code_block) => render_markdown_code_block(code_block, cx),
         HorizontalRule(_) => render_markdown_rule(cx),
         Image(image) => render_markdown_image(image, cx),
+        Math(math) => render_markdown_math(math, cx),
+        Mermaid(mermaid) => render_markdown_mermaid(mermaid, cx),
     }
 }
 
@@ -649,6 +652,51 @@ fn render_markdown_code_block(
         .into_any()
 }
 
+fn render_markdown_math(parsed: &ParsedMarkdownMath, cx: &mut RenderContext) -> AnyElement {
+    let element_id = cx.next_id(&parsed.source_range);
+    cx.with_common_p(div())
+        .id(element_id)
+        .flex()
+        .justify_center()
+        .py(cx.scaled_rems(0.25))
+        .overflow_x_scroll()
+        .child(markdown::math::render_math(parsed.node.clone()))
+        .into_any()
+}
+
+fn render_markdown_mermaid(parsed: &ParsedMarkdownMermaid, cx: &mut RenderContext) -> AnyElement {
+    let element_id = cx.next_id(&parsed.source_range);
+    match &parsed.diagram {
+        Ok(diagram) => cx
+            .with_common_p(div())
+            .id(element_id)
+            .overflow_x_scroll()
+            .child(markdown::mermaid::render_mermaid(diagram.clone()))
+            .into_any(),
+        // Show the source so that the block is still readable, along with why
+        // it couldn't be drawn.
+        Err(error) => cx
+            .with_common_p(div())
+            .id(element_id)
+            .px_3()
+            .py_3()
+            .bg(cx.code_block_background_color)
+            .rounded_sm()
+            .child(
+                div()
+                    .font_family(cx.buffer_font_family.clone())
+                    .child(parsed.contents.clone()),
+            )
+            .child(
+                div()
+                    .pt_2()
+                    .text_color(cx.text_muted_color)
+                    .child(format!("Couldn't render diagram: {error}")),
+            )
+            .into_any(),
+    }
+}
+
 fn render_markdown_paragraph(parsed: &MarkdownParagraph, cx: &mut RenderContext) -> AnyElement {
     cx.with_common_p(div())
         .children(render_markdown_text(parsed, cx))
@@ -881,7 +929,7 @@ impl Render for InteractiveMarkdownElementTooltip {
 }
 
 /// Returns the prefix for a list item.
-fn list_item_prefix(order: usize, ordered: bool, depth: usize) -> String {
+pub(crate) fn list_item_prefix(order: usize, ordered: bool, depth: usize) -> String {
     let ix = order.saturating_sub(1);
     const NUMBERED_PREFIXES_1: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
     const NUMBERED_PREFIXES_2: &str = "abcdefghijklmnopqrstuvwxyz";
@@ -21,7 +21,12 @@ use workspace::{
 };
 use zed_actions::OpenPerformanceProfiler;
 
+mod trace_recording;
+
+pub use trace_recording::{StartTraceRecording, StopTraceRecording};
+
 pub fn init(startup_time: Instant, cx: &mut App) {
+    trace_recording::init(cx);
     cx.observe_new(move |workspace: &mut workspace::Workspace, _, _| {
         workspace.register_action(move |workspace, _: &OpenPerformanceProfiler, window, cx| {
             let window_handle = window
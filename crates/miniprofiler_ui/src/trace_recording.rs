@@ -0,0 +1,221 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write as _},
+    path::{Path, PathBuf},
+    thread::ThreadId,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context as _, Result};
+use gpui::{App, Context, Global, Task, Window, actions};
+use workspace::{
+    Toast, Workspace,
+    notifications::{DetachAndPromptErr, NotificationId},
+};
+use ztracing::recording::{self, Recording};
+
+actions!(
+    profiler,
+    [
+        /// Starts recording a trace of spans, frames and executor task polls.
+        StartTraceRecording,
+        /// Stops recording the trace and saves it in the Chrome Trace Event
+        /// format, which Perfetto can open.
+        StopTraceRecording,
+    ]
+);
+
+/// When set to a path, a trace is recorded from startup and written there
+/// when Zed quits.
+const TRACE_PATH_ENV_VAR: &str = "ZED_RECORD_TRACE";
+
+/// How often frames and task polls are copied into the recording. gpui only
+/// keeps the most recent ones, so this has to happen well before they are
+/// overwritten.
+const COPY_TIMINGS_INTERVAL: Duration = Duration::from_secs(1);
+
+pub(crate) fn init(cx: &mut App) {
+    if let Some(path) = std::env::var_os(TRACE_PATH_ENV_VAR).map(PathBuf::from) {
+        start_recording(cx);
+        cx.on_app_quit(move |cx| {
+            if let Some(recording) = finish_recording(cx)
+                && let Err(error) = write_trace(&recording, &path)
+            {
+                log::error!("failed to write trace to {path:?}: {error:#}");
+            }
+            async {}
+        })
+        .detach();
+    }
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &StartTraceRecording, _, cx| {
+            struct TraceRecordingStarted;
+            let message = if start_recording(cx) {
+                "Recording a trace. Run \"profiler: stop trace recording\" to save it."
+            } else {
+                "A trace is already being recorded."
+            };
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<TraceRecordingStarted>(), message).autohide(),
+                cx,
+            );
+        });
+        workspace.register_action(|workspace, _: &StopTraceRecording, window, cx| {
+            stop_trace_recording(workspace, window, cx);
+        });
+    })
+    .detach();
+}
+
+fn stop_trace_recording(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    struct TraceRecordingStopped;
+
+    let Some(recording) = finish_recording(cx) else {
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<TraceRecordingStopped>(),
+                "No trace is being recorded.",
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    };
+
+    let directory = workspace
+        .most_recent_active_path(cx)
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| util::paths::home_dir().clone());
+    let path = cx.prompt_for_new_path(&directory, Some("zed-trace.json"));
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(path) = path.await?? else {
+            return Ok(());
+        };
+        let message = format!("Saved the trace to {}", path.display());
+        cx.background_spawn(async move { write_trace(&recording, &path) })
+            .await?;
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<TraceRecordingStopped>(), message).autohide(),
+                cx,
+            );
+        })
+    })
+    .detach_and_prompt_err("Failed to save the trace", window, cx, |_, _, _| None);
+}
+
+/// A recording in progress, which periodically copies frames and task polls
+/// into itself.
+struct ActiveRecording {
+    timings: CopiedTimings,
+    _copy_timings: Task<()>,
+}
+
+impl Global for ActiveRecording {}
+
+/// Tracks which of gpui's frames and task polls were already added to the
+/// recording, by the start of the last one copied from each thread.
+#[derive(Default)]
+struct CopiedTimings {
+    task_polls: HashMap<ThreadId, Instant>,
+    frames: HashMap<ThreadId, Instant>,
+}
+
+impl CopiedTimings {
+    /// Adds the frames and task polls that weren't copied yet. Polls that are
+    /// still running are left for the next time, unless `finish` is true.
+    fn copy(&mut self, recording: &mut Recording, cx: &App, finish: bool) {
+        let now = Instant::now();
+        for thread in cx.foreground_executor().dispatcher.get_all_timings() {
+            if let Some(name) = &thread.thread_name {
+                recording.set_thread_name(thread.thread_id, name);
+            }
+            for timing in thread.timings {
+                if self
+                    .task_polls
+                    .get(&thread.thread_id)
+                    .is_some_and(|copied| timing.start <= *copied)
+                {
+                    continue;
+                }
+                let end = match timing.end {
+                    Some(end) => end,
+                    None if finish => now,
+                    None => break,
+                };
+                let location = timing.location;
+                recording.add_event(
+                    format!("{}:{}", location.file(), location.line()),
+                    "task",
+                    thread.thread_id,
+                    timing.start,
+                    end,
+                );
+                self.task_polls.insert(thread.thread_id, timing.start);
+            }
+        }
+        for frame in gpui::frame_timings() {
+            if self
+                .frames
+                .get(&frame.thread_id)
+                .is_some_and(|copied| frame.start <= *copied)
+            {
+                continue;
+            }
+            recording.add_event("frame", "frame", frame.thread_id, frame.start, frame.end);
+            self.frames.insert(frame.thread_id, frame.start);
+        }
+    }
+}
+
+/// Starts recording, returning false if a recording was already in progress.
+fn start_recording(cx: &mut App) -> bool {
+    if !recording::start_recording() {
+        return false;
+    }
+    let copy_timings = cx.spawn(async move |cx| {
+        loop {
+            cx.background_executor().timer(COPY_TIMINGS_INTERVAL).await;
+            let copied = cx.update(|cx| {
+                cx.update_global(|active: &mut ActiveRecording, cx| {
+                    recording::with_recording(|recording| {
+                        active.timings.copy(recording, cx, false)
+                    });
+                })
+            });
+            if copied.is_err() {
+                break;
+            }
+        }
+    });
+    cx.set_global(ActiveRecording {
+        timings: CopiedTimings::default(),
+        _copy_timings: copy_timings,
+    });
+    true
+}
+
+/// Stops recording spans and adds the frames and task polls that happened
+/// since they were last copied.
+fn finish_recording(cx: &mut App) -> Option<Recording> {
+    let mut recording = recording::stop_recording()?;
+    if cx.has_global::<ActiveRecording>() {
+        let mut active = cx.remove_global::<ActiveRecording>();
+        active.timings.copy(&mut recording, cx, true);
+    }
+    Some(recording)
+}
+
+fn write_trace(recording: &Recording, path: &Path) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("creating {path:?}"))?;
+    let mut writer = BufWriter::new(file);
+    recording.write_chrome_trace(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
@@ -16,7 +16,8 @@ use ui::{
 use vim_mode_setting::VimModeSetting;
 
 use crate::{
-    ImportCursorSettings, ImportVsCodeSettings, SettingsImportState,
+    ImportCursorKeymap, ImportCursorSettings, ImportVsCodeKeymap, ImportVsCodeSettings,
+    SettingsImportState,
     theme_preview::{ThemePreviewStyle, ThemePreviewTile},
 };
 
@@ -472,6 +473,44 @@ fn render_import_settings_section(tab_index: &mut isize, cx: &mut App) -> impl I
         .child(h_flex().gap_1().child(vscode).child(cursor))
 }
 
+fn render_import_keymap_section(tab_index: &mut isize, cx: &mut App) -> impl IntoElement {
+    let import_state = SettingsImportState::global(cx);
+    let imports: [(SharedString, &dyn Action, bool); 2] = [
+        (
+            "VS Code".into(),
+            &ImportVsCodeKeymap,
+            import_state.vscode_keymap,
+        ),
+        (
+            "Cursor".into(),
+            &ImportCursorKeymap,
+            import_state.cursor_keymap,
+        ),
+    ];
+
+    let [vscode, cursor] = imports.map(|(label, action, imported)| {
+        *tab_index += 1;
+        render_setting_import_button(*tab_index - 1, label, action, imported)
+    });
+
+    h_flex()
+        .id("import-keybindings")
+        .gap_2()
+        .flex_wrap()
+        .justify_between()
+        .child(
+            v_flex()
+                .gap_0p5()
+                .max_w_5_6()
+                .child(Label::new("Import Keybindings"))
+                .child(
+                    Label::new("Review and add your custom keybindings from other editors")
+                        .color(Color::Muted),
+                ),
+        )
+        .child(h_flex().gap_1().child(vscode).child(cursor))
+}
+
 pub(crate) fn render_basics_page(cx: &mut App) -> impl IntoElement {
     let mut tab_index = 0;
     v_flex()
@@ -480,6 +519,7 @@ pub(crate) fn render_basics_page(cx: &mut App) -> impl IntoElement {
         .child(render_theme_section(&mut tab_index, cx))
         .child(render_base_keymap_section(&mut tab_index, cx))
         .child(render_import_settings_section(&mut tab_index, cx))
+        .child(render_import_keymap_section(&mut tab_index, cx))
         .child(render_vim_mode_switch(&mut tab_index, cx))
         .child(Divider::horizontal().color(ui::DividerColor::BorderVariant))
         .child(render_telemetry_section(&mut tab_index, cx))
@@ -0,0 +1,259 @@
+use crate::SettingsImportState;
+use fs::Fs;
+use gpui::{
+    Action, AsyncWindowContext, DismissEvent, EventEmitter, FocusHandle, Focusable, ScrollHandle,
+    WeakEntity,
+};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use settings::{VsCodeKeymap, VsCodeSettingsSource};
+use std::sync::Arc;
+use ui::{Modal, ModalFooter, ModalHeader, Section, SectionHeader, prelude::*};
+use util::truncate_and_remove_front;
+use workspace::{ModalView, Workspace};
+
+/// Loads the user's VS Code or Cursor keybindings and shows what they translate
+/// to, so that the user can review them before they're added to the keymap.
+pub async fn handle_import_vscode_keymap(
+    workspace: WeakEntity<Workspace>,
+    source: VsCodeSettingsSource,
+    fs: Arc<dyn Fs>,
+    cx: &mut AsyncWindowContext,
+) {
+    let keymap = match VsCodeKeymap::load_user_keymap(source, fs.clone()).await {
+        Ok(keymap) => keymap,
+        Err(err) => {
+            zlog::error!("{err:?}");
+            let _ = cx.prompt(
+                gpui::PromptLevel::Info,
+                &format!("Could not find or load a {source} keybindings file"),
+                None,
+                &["Ok"],
+            );
+            return;
+        }
+    };
+
+    workspace
+        .update_in(cx, |workspace, window, cx| {
+            let workspace_handle = workspace.weak_handle();
+            workspace.toggle_modal(window, cx, |_, cx| {
+                KeymapImportModal::new(keymap, fs, workspace_handle, cx)
+            });
+        })
+        .ok();
+}
+
+pub struct KeymapImportModal {
+    keymap: Arc<VsCodeKeymap>,
+    fs: Arc<dyn Fs>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+}
+
+impl EventEmitter<DismissEvent> for KeymapImportModal {}
+
+impl Focusable for KeymapImportModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ModalView for KeymapImportModal {}
+
+impl KeymapImportModal {
+    fn new(
+        keymap: VsCodeKeymap,
+        fs: Arc<dyn Fs>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            keymap: Arc::new(keymap),
+            fs,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.keymap.imported.is_empty() {
+            return;
+        }
+
+        let keymap = self.keymap.clone();
+        let fs = self.fs.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let result = keymap.write_to_user_keymap(&fs).await;
+            workspace
+                .update(cx, |workspace, cx| {
+                    let toast = match result {
+                        Ok(()) => {
+                            zlog::info!(
+                                "Imported {} keybindings from {}",
+                                keymap.source,
+                                keymap.path.display()
+                            );
+                            SettingsImportState::update(cx, |state, _| match keymap.source {
+                                VsCodeSettingsSource::VsCode => state.vscode_keymap = true,
+                                VsCodeSettingsSource::Cursor => state.cursor_keymap = true,
+                            });
+                            StatusToast::new(
+                                format!("Your {} keybindings were imported.", keymap.source),
+                                cx,
+                                |this, _| {
+                                    this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+                                        .dismiss_button(true)
+                                },
+                            )
+                        }
+                        Err(err) => {
+                            zlog::error!("Failed to import keybindings: {err:?}");
+                            StatusToast::new(
+                                "Failed to import keybindings. See log for details",
+                                cx,
+                                |this, _| {
+                                    this.icon(ToastIcon::new(IconName::Close).color(Color::Error))
+                                        .action("Open Log", |window, cx| {
+                                            window.dispatch_action(
+                                                workspace::OpenLog.boxed_clone(),
+                                                cx,
+                                            )
+                                        })
+                                        .dismiss_button(true)
+                                },
+                            )
+                        }
+                    };
+                    workspace.toggle_status_toast(toast, cx);
+                })
+                .ok();
+        })
+        .detach();
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for KeymapImportModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let keymap = &self.keymap;
+        let import_count = keymap.imported.len();
+
+        let imported = keymap.imported.iter().map(|binding| {
+            h_flex()
+                .w_full()
+                .gap_2()
+                .justify_between()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(binding.keystrokes.clone()).buffer_font(cx))
+                        .child(
+                            Label::new(binding.action_name().to_string())
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                )
+                .children(binding.context.clone().map(|context| {
+                    Label::new(context)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .buffer_font(cx)
+                }))
+        });
+
+        let skipped = keymap.skipped.iter().map(|binding| {
+            v_flex()
+                .w_full()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(binding.original.key.clone()).buffer_font(cx))
+                        .child(
+                            Label::new(binding.original.command.clone())
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                )
+                .child(
+                    Label::new(binding.reason.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Warning),
+                )
+        });
+
+        let import_label = match import_count {
+            1 => "Import 1 Keybinding".to_string(),
+            count => format!("Import {count} Keybindings"),
+        };
+
+        v_flex()
+            .key_context("KeymapImportModal")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(36.))
+            .h(rems(32.))
+            .child(
+                Modal::new("keymap-import", Some(self.scroll_handle.clone()))
+                    .header(
+                        ModalHeader::new()
+                            .headline(format!("Import {} Keybindings", keymap.source))
+                            .description(truncate_and_remove_front(
+                                &keymap.path.to_string_lossy(),
+                                64,
+                            ))
+                            .show_dismiss_button(true),
+                    )
+                    .section(
+                        Section::new()
+                            .header(SectionHeader::new(format!(
+                                "Added to your keymap ({import_count})"
+                            )))
+                            .when(import_count == 0, |section| {
+                                section.child(
+                                    Label::new("None of these keybindings have an equivalent.")
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .children(imported),
+                    )
+                    .when(!keymap.skipped.is_empty(), |modal| {
+                        modal.section(
+                            Section::new()
+                                .header(SectionHeader::new(format!(
+                                    "Can't be imported ({})",
+                                    keymap.skipped.len()
+                                )))
+                                .children(skipped),
+                        )
+                    })
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(Button::new("cancel", "Cancel").on_click(cx.listener(
+                                    |_, _, _, cx| {
+                                        cx.emit(DismissEvent);
+                                    },
+                                )))
+                                .child(
+                                    Button::new("import", import_label)
+                                        .style(ButtonStyle::Filled)
+                                        .disabled(import_count == 0)
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx);
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
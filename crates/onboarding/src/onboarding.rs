@@ -27,6 +27,7 @@ use workspace::{
 
 mod base_keymap_picker;
 mod basics_page;
+mod keymap_import;
 pub mod multibuffer_hint;
 mod theme_preview;
 mod welcome;
@@ -56,7 +57,11 @@ actions!(
     zed,
     [
         /// Opens the onboarding view.
-        OpenOnboarding
+        OpenOnboarding,
+        /// Imports keybindings from Visual Studio Code, after reviewing them.
+        ImportVsCodeKeymap,
+        /// Imports keybindings from Cursor editor, after reviewing them.
+        ImportCursorKeymap
     ]
 );
 
@@ -175,6 +180,40 @@ pub fn init(cx: &mut App) {
                 })
                 .detach();
         });
+
+        workspace.register_action(|_workspace, _: &ImportVsCodeKeymap, window, cx| {
+            let fs = <dyn Fs>::global(cx);
+            let workspace = cx.weak_entity();
+
+            window
+                .spawn(cx, async move |cx: &mut AsyncWindowContext| {
+                    keymap_import::handle_import_vscode_keymap(
+                        workspace,
+                        VsCodeSettingsSource::VsCode,
+                        fs,
+                        cx,
+                    )
+                    .await
+                })
+                .detach();
+        });
+
+        workspace.register_action(|_workspace, _: &ImportCursorKeymap, window, cx| {
+            let fs = <dyn Fs>::global(cx);
+            let workspace = cx.weak_entity();
+
+            window
+                .spawn(cx, async move |cx: &mut AsyncWindowContext| {
+                    keymap_import::handle_import_vscode_keymap(
+                        workspace,
+                        VsCodeSettingsSource::Cursor,
+                        fs,
+                        cx,
+                    )
+                    .await
+                })
+                .detach();
+        });
     })
     .detach();
 
@@ -534,6 +573,8 @@ pub async fn handle_import_vscode_settings(
 pub struct SettingsImportState {
     pub cursor: bool,
     pub vscode: bool,
+    pub cursor_keymap: bool,
+    pub vscode_keymap: bool,
 }
 
 impl Global for SettingsImportState {}
@@ -191,6 +191,19 @@ pub fn old_log_file() -> &'static PathBuf {
     OLD_LOG_FILE.get_or_init(|| logs_dir().join("Zed.log.old"))
 }
 
+/// Returns the path to the `Zed.log.jsonl` file, which holds the same records
+/// as `Zed.log` in a structured form.
+pub fn json_log_file() -> &'static PathBuf {
+    static JSON_LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
+    JSON_LOG_FILE.get_or_init(|| logs_dir().join("Zed.log.jsonl"))
+}
+
+/// Returns the path to the `Zed.log.jsonl.old` file.
+pub fn old_json_log_file() -> &'static PathBuf {
+    static OLD_JSON_LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
+    OLD_JSON_LOG_FILE.get_or_init(|| logs_dir().join("Zed.log.jsonl.old"))
+}
+
 /// Returns the path to the database directory.
 pub fn database_dir() -> &'static PathBuf {
     static DATABASE_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -235,6 +248,12 @@ pub fn keymap_file() -> &'static PathBuf {
     KEYMAP_FILE.get_or_init(|| config_dir().join("keymap.json"))
 }
 
+/// Returns the path to the `vimrc` file, which is loaded when vim mode is enabled.
+pub fn vimrc_file() -> &'static PathBuf {
+    static VIMRC_FILE: OnceLock<PathBuf> = OnceLock::new();
+    VIMRC_FILE.get_or_init(|| config_dir().join("vimrc"))
+}
+
 /// Returns the path to the `keymap_backup.json` file.
 pub fn keymap_backup_file() -> &'static PathBuf {
     static KEYMAP_FILE: OnceLock<PathBuf> = OnceLock::new();
@@ -291,6 +310,14 @@ pub fn snippets_dir() -> &'static PathBuf {
     SNIPPETS_DIR.get_or_init(|| config_dir().join("snippets"))
 }
 
+/// Returns the path to the settings sync directory.
+///
+/// This is where the state of the last sync is kept, so that it stays local to this machine.
+pub fn settings_sync_dir() -> &'static PathBuf {
+    static SETTINGS_SYNC_DIR: OnceLock<PathBuf> = OnceLock::new();
+    SETTINGS_SYNC_DIR.get_or_init(|| data_dir().join("settings_sync"))
+}
+
 /// Returns the path to the contexts directory.
 ///
 /// This is where the saved contexts from the Assistant are stored.
@@ -492,6 +519,24 @@ pub fn cursor_settings_file_paths() -> Vec<PathBuf> {
     paths
 }
 
+/// Returns candidate paths for the vscode user keybindings file
+pub fn vscode_keybindings_file_paths() -> Vec<PathBuf> {
+    let mut paths = vscode_user_data_paths();
+    for path in paths.iter_mut() {
+        path.push("User/keybindings.json");
+    }
+    paths
+}
+
+/// Returns candidate paths for the cursor user keybindings file
+pub fn cursor_keybindings_file_paths() -> Vec<PathBuf> {
+    let mut paths = cursor_user_data_paths();
+    for path in paths.iter_mut() {
+        path.push("User/keybindings.json");
+    }
+    paths
+}
+
 fn vscode_user_data_paths() -> Vec<PathBuf> {
     // https://github.com/microsoft/vscode/blob/23e7148cdb6d8a27f0109ff77e5b1e019f8da051/src/vs/platform/environment/node/userDataPath.ts#L45
     const VSCODE_PRODUCT_NAMES: &[&str] = &[
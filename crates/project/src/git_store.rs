@@ -27,6 +27,7 @@ use futures::{
 use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, Oid, RunHook,
     blame::Blame,
+    history_search::{HistorySearchHunk, HistorySearchMatch, HistorySearchQuery},
     parse_git_remote_url,
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
@@ -491,6 +492,7 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_file_history);
+        client.add_entity_request_handler(Self::handle_search_history);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
@@ -1084,6 +1086,17 @@ impl GitStore {
         cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
     }
 
+    pub fn search_history(
+        &self,
+        repo: &Entity<Repository>,
+        query: HistorySearchQuery,
+        cx: &mut App,
+    ) -> Task<Result<Vec<HistorySearchMatch>>> {
+        let rx = repo.update(cx, |repo, _| repo.search_history(query));
+
+        cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
+    }
+
     pub fn get_permalink_to_line(
         &self,
         buffer: &Entity<Buffer>,
@@ -2413,6 +2426,37 @@ impl GitStore {
         })
     }
 
+    async fn handle_search_history(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSearchHistory>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSearchHistoryResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let query = HistorySearchQuery {
+            pattern: envelope.payload.pattern,
+            is_regex: envelope.payload.is_regex,
+            case_sensitive: envelope.payload.case_sensitive,
+            paths: envelope
+                .payload
+                .paths
+                .iter()
+                .map(|path| RepoPath::from_proto(path))
+                .collect::<Result<_>>()?,
+            limit: envelope.payload.limit.map(|limit| limit as usize),
+        };
+
+        let matches = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.search_history(query)
+            })?
+            .await??;
+
+        Ok(proto::GitSearchHistoryResponse {
+            matches: matches.iter().map(history_search_match_to_proto).collect(),
+        })
+    }
+
     async fn handle_reset(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitReset>,
@@ -4126,6 +4170,38 @@ impl Repository {
         self.file_history_paginated(path, 0, None)
     }
 
+    pub fn search_history(
+        &mut self,
+        query: HistorySearchQuery,
+    ) -> oneshot::Receiver<Result<Vec<HistorySearchMatch>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.search_history(query).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::GitSearchHistory {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            pattern: query.pattern,
+                            is_regex: query.is_regex,
+                            case_sensitive: query.case_sensitive,
+                            paths: query.paths.iter().map(|path| path.to_proto()).collect(),
+                            limit: query.limit.map(|limit| limit as u64),
+                        })
+                        .await?;
+                    response
+                        .matches
+                        .iter()
+                        .map(proto_to_history_search_match)
+                        .collect()
+                }
+            }
+        })
+    }
+
     pub fn file_history_paginated(
         &mut self,
         path: RepoPath,
@@ -6098,6 +6174,46 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn history_search_match_to_proto(commit: &HistorySearchMatch) -> proto::HistorySearchMatch {
+    proto::HistorySearchMatch {
+        sha: commit.sha.to_string(),
+        subject: commit.subject.to_string(),
+        commit_timestamp: commit.commit_timestamp,
+        author_name: commit.author_name.to_string(),
+        hunks: commit
+            .hunks
+            .iter()
+            .map(|hunk| proto::HistorySearchHunk {
+                path: hunk.path.to_proto(),
+                old_start: hunk.old_start,
+                new_start: hunk.new_start,
+                lines: hunk.lines.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn proto_to_history_search_match(proto: &proto::HistorySearchMatch) -> Result<HistorySearchMatch> {
+    Ok(HistorySearchMatch {
+        sha: proto.sha.clone().into(),
+        subject: proto.subject.clone().into(),
+        commit_timestamp: proto.commit_timestamp,
+        author_name: proto.author_name.clone().into(),
+        hunks: proto
+            .hunks
+            .iter()
+            .map(|hunk| {
+                Ok(HistorySearchHunk {
+                    path: RepoPath::from_proto(&hunk.path)?,
+                    old_start: hunk.old_start,
+                    new_start: hunk.new_start,
+                    lines: hunk.lines.clone(),
+                })
+            })
+            .collect::<Result<_>>()?,
+    })
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
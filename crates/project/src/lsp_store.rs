@@ -10,6 +10,8 @@
 //!
 //! Most of the interesting work happens at the local layer, as bulk of the complexity is with managing the lifecycle of language servers. The actual implementation of the LSP protocol is handled by [`lsp`] crate.
 pub mod clangd_ext;
+mod cli_formatter;
+mod cli_linter;
 pub mod json_language_server_ext;
 pub mod log_store;
 pub mod lsp_ext_command;
@@ -18,7 +20,7 @@ pub mod vue_language_server_ext;
 
 mod inlay_hint_cache;
 
-use self::inlay_hint_cache::BufferInlayHints;
+use self::{cli_linter::CliLinters, inlay_hint_cache::BufferInlayHints};
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
     CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, InlayId, LocationLink,
@@ -37,7 +39,7 @@ use crate::{
     },
     prettier_store::{self, PrettierStore, PrettierStoreEvent},
     project_settings::{LspSettings, ProjectSettings},
-    toolchain_store::{LocalToolchainStore, ToolchainStoreEvent},
+    toolchain_store::{LocalToolchainStore, ToolchainStoreEvent, activate_toolchain_in_env},
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
     yarn::YarnPathStore,
 };
@@ -47,7 +49,7 @@ use client::{TypedEnvelope, proto};
 use clock::Global;
 use collections::{BTreeMap, BTreeSet, HashMap, HashSet, btree_map};
 use futures::{
-    AsyncWriteExt, Future, FutureExt, StreamExt,
+    Future, FutureExt, StreamExt,
     future::{Either, Shared, join_all, pending, select},
     select, select_biased,
     stream::FuturesUnordered,
@@ -61,11 +63,13 @@ use http_client::HttpClient;
 use itertools::Itertools as _;
 use language::{
     Bias, BinaryStatus, Buffer, BufferRow, BufferSnapshot, CachedLspAdapter, CodeLabel, Diagnostic,
-    DiagnosticEntry, DiagnosticSet, DiagnosticSourceKind, Diff, File as _, Language, LanguageName,
+    DiagnosticEntry, DiagnosticSet, DiagnosticSourceKind, File as _, Language, LanguageName,
     LanguageRegistry, LocalFile, LspAdapter, LspAdapterDelegate, LspInstaller, ManifestDelegate,
     ManifestName, Patch, PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Toolchain,
     Transaction, Unclipped,
-    language_settings::{FormatOnSave, Formatter, LanguageSettings, language_settings},
+    language_settings::{
+        FormatOnSave, Formatter, LanguageSettings, all_language_settings, language_settings,
+    },
     point_to_lsp,
     proto::{
         deserialize_anchor, deserialize_lsp_edit, deserialize_version, serialize_anchor,
@@ -268,6 +272,7 @@ pub struct LocalLspStore {
     language_server_dynamic_registrations: HashMap<LanguageServerId, DynamicRegistrations>,
     supplementary_language_servers:
         HashMap<LanguageServerId, (LanguageServerName, Arc<LanguageServer>)>,
+    cli_linters: CliLinters,
     prettier_store: Entity<PrettierStore>,
     next_diagnostic_group_id: usize,
     diagnostics: HashMap<
@@ -575,6 +580,7 @@ impl LocalLspStore {
         allow_binary_download: bool,
         cx: &mut App,
     ) -> Task<Result<LanguageServerBinary>> {
+        let languages = self.languages.clone();
         if let Some(settings) = &settings.binary
             && let Some(path) = settings.path.as_ref().map(PathBuf::from)
         {
@@ -582,6 +588,9 @@ impl LocalLspStore {
 
             return cx.background_spawn(async move {
                 let mut env = delegate.shell_env().await;
+                if let Some(toolchain) = &toolchain {
+                    activate_toolchain_in_env(&languages, toolchain, &mut env).await;
+                }
                 env.extend(settings.env.unwrap_or_default());
 
                 Ok(LanguageServerBinary {
@@ -613,7 +622,12 @@ impl LocalLspStore {
         cx.spawn(async move |cx| {
             let (existing_binary, maybe_download_binary) = adapter
                 .clone()
-                .get_language_server_command(delegate.clone(), toolchain, lsp_binary_options, cx)
+                .get_language_server_command(
+                    delegate.clone(),
+                    toolchain.clone(),
+                    lsp_binary_options,
+                    cx,
+                )
                 .await
                 .await;
 
@@ -644,6 +658,9 @@ impl LocalLspStore {
             let mut shell_env = delegate.shell_env().await;
 
             shell_env.extend(binary.env.unwrap_or_default());
+            if let Some(toolchain) = &toolchain {
+                activate_toolchain_in_env(&languages, toolchain, &mut shell_env).await;
+            }
 
             if let Some(settings) = settings.binary.as_ref() {
                 if let Some(arguments) = &settings.arguments {
@@ -1542,29 +1559,63 @@ impl LocalLspStore {
                     zlog::trace!(logger => "formatting");
                     let _timer = zlog::time!(logger => "Formatting buffer via external command");
 
-                    let diff = Self::format_via_external_command(
-                        buffer,
-                        command.as_ref(),
-                        arguments.as_deref(),
-                        cx,
-                    )
-                    .await
-                    .with_context(|| {
-                        format!("Failed to format buffer via external command: {}", command)
-                    })?;
-                    let Some(diff) = diff else {
+                    let definition =
+                        cli_formatter::external_command_definition(command, arguments.as_deref());
+                    let diffs = cli_formatter::format_buffer(buffer, &definition, cx)
+                        .await
+                        .with_context(|| {
+                            format!("Failed to format buffer via external command: {}", command)
+                        })?;
+                    if diffs.is_empty() {
                         zlog::trace!(logger => "No changes");
                         continue;
-                    };
+                    }
 
-                    extend_formatting_transaction(
-                        buffer,
-                        formatting_transaction_id,
-                        cx,
-                        |buffer, cx| {
-                            buffer.apply_diff(diff, cx);
-                        },
-                    )?;
+                    for diff in diffs {
+                        extend_formatting_transaction(
+                            buffer,
+                            formatting_transaction_id,
+                            cx,
+                            |buffer, cx| {
+                                buffer.apply_diff(diff, cx);
+                            },
+                        )?;
+                    }
+                }
+                Formatter::Named(name) => {
+                    let logger = zlog::scoped!(logger => "command");
+                    zlog::trace!(logger => "formatting with {name}");
+                    let _timer = zlog::time!(logger => "Formatting buffer via named formatter");
+
+                    let definition = buffer
+                        .handle
+                        .read_with(cx, |buffer, cx| {
+                            all_language_settings(buffer.file(), cx)
+                                .formatters
+                                .get(name.as_str())
+                                .cloned()
+                        })?
+                        .with_context(|| {
+                            format!("No formatter named {name:?} in the `formatters` setting")
+                        })?;
+                    let diffs = cli_formatter::format_buffer(buffer, &definition, cx)
+                        .await
+                        .with_context(|| format!("Failed to format buffer via {name}"))?;
+                    if diffs.is_empty() {
+                        zlog::trace!(logger => "No changes");
+                        continue;
+                    }
+
+                    for diff in diffs {
+                        extend_formatting_transaction(
+                            buffer,
+                            formatting_transaction_id,
+                            cx,
+                            |buffer, cx| {
+                                buffer.apply_diff(diff, cx);
+                            },
+                        )?;
+                    }
                 }
                 Formatter::LanguageServer(specifier) => {
                     let logger = zlog::scoped!(logger => "language-server");
@@ -2139,75 +2190,6 @@ impl LocalLspStore {
         }
     }
 
-    async fn format_via_external_command(
-        buffer: &FormattableBuffer,
-        command: &str,
-        arguments: Option<&[String]>,
-        cx: &mut AsyncApp,
-    ) -> Result<Option<Diff>> {
-        let working_dir_path = buffer.handle.update(cx, |buffer, cx| {
-            let file = File::from_dyn(buffer.file())?;
-            let worktree = file.worktree.read(cx);
-            let mut worktree_path = worktree.abs_path().to_path_buf();
-            if worktree.root_entry()?.is_file() {
-                worktree_path.pop();
-            }
-            Some(worktree_path)
-        })?;
-
-        let mut child = util::command::new_smol_command(command);
-
-        if let Some(buffer_env) = buffer.env.as_ref() {
-            child.envs(buffer_env);
-        }
-
-        if let Some(working_dir_path) = working_dir_path {
-            child.current_dir(working_dir_path);
-        }
-
-        if let Some(arguments) = arguments {
-            child.args(arguments.iter().map(|arg| {
-                if let Some(buffer_abs_path) = buffer.abs_path.as_ref() {
-                    arg.replace("{buffer_path}", &buffer_abs_path.to_string_lossy())
-                } else {
-                    arg.replace("{buffer_path}", "Untitled")
-                }
-            }));
-        }
-
-        let mut child = child
-            .stdin(smol::process::Stdio::piped())
-            .stdout(smol::process::Stdio::piped())
-            .stderr(smol::process::Stdio::piped())
-            .spawn()?;
-
-        let stdin = child.stdin.as_mut().context("failed to acquire stdin")?;
-        let text = buffer
-            .handle
-            .read_with(cx, |buffer, _| buffer.as_rope().clone())?;
-        for chunk in text.chunks() {
-            stdin.write_all(chunk.as_bytes()).await?;
-        }
-        stdin.flush().await?;
-
-        let output = child.output().await?;
-        anyhow::ensure!(
-            output.status.success(),
-            "command failed with exit code {:?}:\nstdout: {}\nstderr: {}",
-            output.status.code(),
-            String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr),
-        );
-
-        let stdout = String::from_utf8(output.stdout)?;
-        Ok(Some(
-            buffer
-                .handle
-                .update(cx, |buffer, cx| buffer.diff(stdout, cx))?
-                .await,
-        ))
-    }
-
     async fn try_resolve_code_action(
         lang_server: &LanguageServer,
         action: &mut CodeAction,
@@ -3940,6 +3922,7 @@ impl LspStore {
                 worktree_store: worktree_store.clone(),
 
                 supplementary_language_servers: Default::default(),
+                cli_linters: Default::default(),
                 languages: languages.clone(),
                 language_server_ids: Default::default(),
                 language_servers: Default::default(),
@@ -4170,10 +4153,12 @@ impl LspStore {
     ) {
         match event {
             language::BufferEvent::Edited => {
+                self.lint_edited_buffer(&buffer, cx);
                 self.on_buffer_edited(buffer, cx);
             }
 
             language::BufferEvent::Saved => {
+                self.lint_saved_buffer(&buffer, cx);
                 self.on_buffer_saved(buffer, cx);
             }
 
@@ -4246,6 +4231,7 @@ impl LspStore {
                         local.registered_buffers.remove(&buffer_id);
 
                         local.buffers_opened_in_servers.remove(&buffer_id);
+                        local.cli_linters.forget_buffer(buffer_id);
                         if let Some(file) = File::from_dyn(buffer.read(cx).file()).cloned() {
                             local.unregister_old_buffer_from_language_servers(buffer, &file, cx);
 
@@ -4955,6 +4941,11 @@ impl LspStore {
         push_to_history: bool,
         cx: &mut Context<Self>,
     ) -> Task<Result<ProjectTransaction>> {
+        if let Some(transaction) =
+            cli_linter::apply_lint_fix(&buffer_handle, &action, push_to_history, cx)
+        {
+            return Task::ready(Ok(transaction));
+        }
         if let Some((upstream_client, project_id)) = self.upstream_client() {
             let request = proto::ApplyCodeAction {
                 project_id,
@@ -5831,13 +5822,23 @@ impl LspStore {
         kinds: Option<Vec<CodeActionKind>>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Option<Vec<CodeAction>>>> {
+        let lint_fix_actions = if kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.contains(&CodeActionKind::QUICKFIX))
+        {
+            cli_linter::lint_fix_actions(&buffer.read(cx).snapshot(), range.clone())
+        } else {
+            Vec::new()
+        };
         if let Some((upstream_client, project_id)) = self.upstream_client() {
             let request = GetCodeActions {
                 range: range.clone(),
                 kinds: kinds.clone(),
             };
             if !self.is_capable_for_proto_request(buffer, &request, cx) {
-                return Task::ready(Ok(None));
+                return Task::ready(Ok(
+                    (!lint_fix_actions.is_empty()).then_some(lint_fix_actions)
+                ));
             }
             let request_task = upstream_client.request_lsp(
                 project_id,
@@ -5852,7 +5853,7 @@ impl LspStore {
                     return Ok(None);
                 };
                 let Some(responses) = request_task.await? else {
-                    return Ok(None);
+                    return Ok((!lint_fix_actions.is_empty()).then_some(lint_fix_actions));
                 };
                 let actions = join_all(responses.payload.into_iter().map(|response| {
                     GetCodeActions {
@@ -5874,6 +5875,7 @@ impl LspStore {
                         .collect::<Result<Vec<Vec<_>>>>()?
                         .into_iter()
                         .flatten()
+                        .chain(lint_fix_actions)
                         .collect(),
                 ))
             })
@@ -5890,6 +5892,7 @@ impl LspStore {
                         .await
                         .into_iter()
                         .flat_map(|(_, actions)| actions)
+                        .chain(lint_fix_actions)
                         .collect(),
                 ))
             })
@@ -0,0 +1,479 @@
+//! Runs the command-line formatters defined in the `formatters` setting, as well as `external`
+//! formatters, which are treated as formatters reading from stdin and writing to stdout.
+
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use futures::AsyncWriteExt as _;
+use gpui::AsyncApp;
+use language::{
+    BufferSnapshot, Diff,
+    language_settings::{FormatterDefinition, FormatterMode},
+};
+use text::{Anchor, Point, ToOffset as _, ToPoint as _};
+use worktree::File;
+
+use super::FormattableBuffer;
+
+/// The definition of an `external` formatter.
+pub(super) fn external_command_definition(
+    command: &Arc<str>,
+    arguments: Option<&[String]>,
+) -> FormatterDefinition {
+    FormatterDefinition {
+        command: command.clone(),
+        arguments: arguments.map(<[String]>::to_vec).unwrap_or_default(),
+        mode: FormatterMode::Stdio,
+        range_arguments: None,
+        success_exit_codes: vec![0],
+        unchanged_exit_codes: Vec::new(),
+    }
+}
+
+/// Formats the buffer with the given formatter, returning the diffs to apply, in order.
+///
+/// When only some ranges of the buffer should be formatted, formatters that support range
+/// formatting are run once per range. Other formatters format the whole buffer, and only the
+/// changes touching the lines of those ranges are kept.
+pub(super) async fn format_buffer(
+    buffer: &FormattableBuffer,
+    definition: &FormatterDefinition,
+    cx: &mut AsyncApp,
+) -> Result<Vec<Diff>> {
+    let mut diffs = Vec::new();
+    match (buffer.ranges.as_deref(), &definition.range_arguments) {
+        (Some(ranges), Some(_)) => {
+            for range in ranges {
+                if let Some(diff) = run_formatter(buffer, definition, Some(range), cx).await? {
+                    diffs.push(diff);
+                }
+            }
+        }
+        (Some(ranges), None) => {
+            if let Some(mut diff) = run_formatter(buffer, definition, None, cx).await? {
+                let snapshot = buffer.handle.read_with(cx, |buffer, _| buffer.snapshot())?;
+                retain_edits_touching_ranges(&mut diff, ranges, &snapshot);
+                diffs.push(diff);
+            }
+        }
+        (None, _) => {
+            diffs.extend(run_formatter(buffer, definition, None, cx).await?);
+        }
+    }
+    Ok(diffs)
+}
+
+/// Runs the formatter once, returning `None` if it reports that the text is already formatted.
+async fn run_formatter(
+    buffer: &FormattableBuffer,
+    definition: &FormatterDefinition,
+    range: Option<&Range<Anchor>>,
+    cx: &mut AsyncApp,
+) -> Result<Option<Diff>> {
+    let (snapshot, worktree_root) = buffer.handle.read_with(cx, |buffer, cx| {
+        let worktree_root = File::from_dyn(buffer.file()).and_then(|file| {
+            let worktree = file.worktree.read(cx);
+            let mut worktree_path = worktree.abs_path().to_path_buf();
+            if worktree.root_entry()?.is_file() {
+                worktree_path.pop();
+            }
+            Some(worktree_path)
+        });
+        (buffer.snapshot(), worktree_root)
+    })?;
+
+    let buffer_path = buffer.abs_path.as_ref().map_or_else(
+        || "Untitled".to_string(),
+        |path| path.to_string_lossy().into_owned(),
+    );
+    let text = snapshot.text();
+
+    // Formatters that rewrite files in place are given a copy of the buffer, named like the
+    // buffer so that they can tell which language it is written in.
+    let temp_dir = match definition.mode {
+        FormatterMode::Stdio => None,
+        FormatterMode::InPlace => Some(
+            tempfile::tempdir().context("failed to create a directory for the file to format")?,
+        ),
+    };
+    let input_path = match &temp_dir {
+        Some(temp_dir) => {
+            let file_name = buffer
+                .abs_path
+                .as_deref()
+                .and_then(Path::file_name)
+                .map_or_else(|| "Untitled".into(), |name| name.to_owned());
+            let input_path = temp_dir.path().join(file_name);
+            smol::fs::write(&input_path, &text)
+                .await
+                .context("failed to write the file to format")?;
+            Some(input_path)
+        }
+        None => None,
+    };
+
+    let mut variables = vec![
+        (
+            "input_path",
+            input_path.as_ref().map_or_else(
+                || buffer_path.clone(),
+                |path| path.to_string_lossy().into_owned(),
+            ),
+        ),
+        ("buffer_path", buffer_path),
+        (
+            "worktree_root",
+            worktree_root
+                .as_ref()
+                .map(|root| root.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        ),
+    ];
+    let mut arguments = definition.arguments.clone();
+    if let Some(range) = range {
+        variables.extend(range_variables(range, &snapshot));
+        arguments.extend(definition.range_arguments.iter().flatten().cloned());
+    }
+
+    let mut command = util::command::new_smol_command(definition.command.as_ref());
+    if let Some(buffer_env) = buffer.env.as_ref() {
+        command.envs(buffer_env);
+    }
+    if let Some(worktree_root) = worktree_root {
+        command.current_dir(worktree_root);
+    }
+    command
+        .args(expand_arguments(&arguments, &variables))
+        .stdout(smol::process::Stdio::piped())
+        .stderr(smol::process::Stdio::piped())
+        .stdin(match definition.mode {
+            FormatterMode::Stdio => smol::process::Stdio::piped(),
+            FormatterMode::InPlace => smol::process::Stdio::null(),
+        });
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("failed to run {}", definition.command))?;
+
+    // Write the input while reading the output, so that formatters that start writing before they
+    // have read all of their input don't block on a full pipe.
+    let stdin = child.stdin.take();
+    let write_input = async {
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(text.as_bytes()).await?;
+            stdin.flush().await?;
+        }
+        anyhow::Ok(())
+    };
+    let (write_result, output) = futures::join!(write_input, child.output());
+    let output = output?;
+
+    let exit_code = output.status.code();
+    if exit_code.is_some_and(|code| definition.unchanged_exit_codes.contains(&code)) {
+        return Ok(None);
+    }
+    if !exit_code.is_some_and(|code| definition.success_exit_codes.contains(&code)) {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::error!(
+            "{} failed with exit code {exit_code:?}:\nstdout: {stdout}\nstderr: {stderr}",
+            definition.command,
+        );
+        let status = match exit_code {
+            Some(code) => format!("exited with code {code}"),
+            None => "was terminated by a signal".to_string(),
+        };
+        match first_line(&stderr).or_else(|| first_line(&stdout)) {
+            Some(line) => anyhow::bail!("{status}: {line}"),
+            None => anyhow::bail!("{status}"),
+        }
+    }
+    write_result.context("failed to write the text to format")?;
+
+    let formatted_text = match &input_path {
+        Some(input_path) => smol::fs::read_to_string(input_path)
+            .await
+            .context("failed to read the formatted file")?,
+        None => String::from_utf8(output.stdout).context("the output is not valid UTF-8")?,
+    };
+    // A formatter that succeeds without printing anything most likely didn't format anything,
+    // rather than meaning that the whole buffer should be deleted.
+    anyhow::ensure!(
+        !formatted_text.trim().is_empty() || text.trim().is_empty(),
+        "the formatter produced no output"
+    );
+
+    Ok(Some(
+        buffer
+            .handle
+            .update(cx, |buffer, cx| buffer.diff(formatted_text, cx))?
+            .await,
+    ))
+}
+
+pub(super) fn first_line(output: &str) -> Option<&str> {
+    output.lines().map(str::trim).find(|line| !line.is_empty())
+}
+
+/// Substitutes `{name}` in the arguments with the value of each of the variables.
+pub(super) fn expand_arguments(arguments: &[String], variables: &[(&str, String)]) -> Vec<String> {
+    arguments
+        .iter()
+        .map(|argument| {
+            variables
+                .iter()
+                .fold(argument.clone(), |argument, (name, value)| {
+                    argument.replace(&format!("{{{name}}}"), value)
+                })
+        })
+        .collect()
+}
+
+/// The variables describing a range, for use in `range_arguments`.
+fn range_variables(
+    range: &Range<Anchor>,
+    snapshot: &BufferSnapshot,
+) -> [(&'static str, String); 6] {
+    let start = range.start.to_point(snapshot);
+    let mut end = range.end.to_point(snapshot);
+    // Selections of whole lines end at the start of the following line, which isn't selected.
+    if end.column == 0 && end.row > start.row {
+        end = Point::new(end.row - 1, snapshot.line_len(end.row - 1));
+    }
+    let column = |point: Point| {
+        snapshot
+            .text_for_range(Point::new(point.row, 0)..point)
+            .map(|chunk| chunk.chars().count())
+            .sum::<usize>()
+            + 1
+    };
+    [
+        ("range_start_line", (start.row + 1).to_string()),
+        ("range_start_column", column(start).to_string()),
+        ("range_end_line", (end.row + 1).to_string()),
+        ("range_end_column", column(end).to_string()),
+        ("range_start_offset", start.to_offset(snapshot).to_string()),
+        ("range_end_offset", end.to_offset(snapshot).to_string()),
+    ]
+}
+
+/// Drops the edits of a diff of the whole buffer that don't touch any of the lines spanned by `ranges`.
+fn retain_edits_touching_ranges(
+    diff: &mut Diff,
+    ranges: &[Range<Anchor>],
+    snapshot: &BufferSnapshot,
+) {
+    let line_ranges = ranges
+        .iter()
+        .map(|range| {
+            let start = range.start.to_point(snapshot);
+            let end = range.end.to_point(snapshot);
+            let start = Point::new(start.row, 0).to_offset(snapshot);
+            let end = Point::new(end.row, snapshot.line_len(end.row)).to_offset(snapshot);
+            start..end
+        })
+        .collect::<Vec<_>>();
+    diff.edits.retain(|(edit_range, _)| {
+        line_ranges.iter().any(|line_range| {
+            edit_range.start <= line_range.end && edit_range.end >= line_range.start
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::Buffer;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_expand_arguments() {
+        let variables = [
+            ("buffer_path", "/project/src/main.py".to_string()),
+            ("range_start_line", "3".to_string()),
+            ("range_end_line", "5".to_string()),
+        ];
+        assert_eq!(
+            expand_arguments(
+                &[
+                    "--stdin-filename".to_string(),
+                    "{buffer_path}".to_string(),
+                    "--line-ranges={range_start_line}-{range_end_line}".to_string(),
+                    "{unknown}".to_string(),
+                ],
+                &variables
+            ),
+            [
+                "--stdin-filename",
+                "/project/src/main.py",
+                "--line-ranges=3-5",
+                "{unknown}"
+            ]
+        );
+    }
+
+    #[gpui::test]
+    fn test_range_variables(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("fn a() {}\nfn bé() {\n    1\n}\n", cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let variables = |range: Range<Point>| {
+            let range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+            range_variables(&range, &snapshot).map(|(_, value)| value)
+        };
+
+        assert_eq!(
+            variables(Point::new(1, 3)..Point::new(2, 5)),
+            ["2", "4", "3", "6", "13", "26"]
+        );
+        // Selecting whole lines doesn't select the line after them.
+        assert_eq!(
+            variables(Point::new(1, 0)..Point::new(3, 0)),
+            ["2", "1", "3", "6", "10", "26"]
+        );
+        // Columns count characters rather than bytes.
+        assert_eq!(
+            variables(Point::new(1, 0)..Point::new(1, 6)),
+            ["2", "1", "2", "6", "10", "16"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_retain_edits_touching_ranges(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a  =  1\nb  =  2\nc  =  3\n", cx));
+        let mut diff = buffer
+            .update(cx, |buffer, cx| {
+                buffer.diff("a = 1\nb = 2\nc = 3\n".into(), cx)
+            })
+            .await;
+        buffer.update(cx, |buffer, cx| {
+            let snapshot = buffer.snapshot();
+            let range =
+                snapshot.anchor_before(Point::new(1, 2))..snapshot.anchor_after(Point::new(1, 2));
+            retain_edits_touching_ranges(&mut diff, &[range], &snapshot);
+            buffer.apply_diff(diff, cx);
+            assert_eq!(buffer.text(), "a  =  1\nb = 2\nc  =  3\n");
+        });
+    }
+
+    #[cfg(not(windows))]
+    #[gpui::test]
+    async fn test_format_buffer(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        /// Formats `text` with the definition, returning the number of diffs and the formatted text.
+        async fn format(
+            text: &str,
+            ranges: Option<Vec<Range<Point>>>,
+            definition: &FormatterDefinition,
+            cx: &mut TestAppContext,
+        ) -> Result<(usize, String)> {
+            let handle = cx.new(|cx| Buffer::local(text, cx));
+            let ranges = ranges.map(|ranges| {
+                handle.read_with(cx, |buffer, _| {
+                    ranges
+                        .into_iter()
+                        .map(|range| {
+                            buffer.anchor_before(range.start)..buffer.anchor_after(range.end)
+                        })
+                        .collect()
+                })
+            });
+            let buffer = FormattableBuffer {
+                handle: handle.clone(),
+                abs_path: Some(PathBuf::from("/project/file.txt")),
+                env: None,
+                ranges,
+            };
+            let diffs = format_buffer(&buffer, definition, &mut cx.to_async()).await?;
+            let diff_count = diffs.len();
+            let text = handle.update(cx, |buffer, cx| {
+                for diff in diffs {
+                    buffer.apply_diff(diff, cx);
+                }
+                buffer.text()
+            });
+            Ok((diff_count, text))
+        }
+
+        let script = |script: &str| FormatterDefinition {
+            command: "sh".into(),
+            arguments: vec!["-c".into(), script.into(), "sh".into()],
+            mode: FormatterMode::Stdio,
+            range_arguments: None,
+            success_exit_codes: vec![0],
+            unchanged_exit_codes: Vec::new(),
+        };
+        let text = "one\ntwo\nthree\n";
+
+        let uppercase = script("tr a-z A-Z");
+        assert_eq!(
+            format(text, None, &uppercase, cx).await.unwrap(),
+            (1, "ONE\nTWO\nTHREE\n".to_string())
+        );
+        // Without range arguments, the whole buffer is formatted and the other lines are kept.
+        assert_eq!(
+            format(
+                text,
+                Some(vec![Point::new(1, 0)..Point::new(1, 1)]),
+                &uppercase,
+                cx
+            )
+            .await
+            .unwrap(),
+            (1, "one\nTWO\nthree\n".to_string())
+        );
+
+        let in_place = FormatterDefinition {
+            arguments: vec![
+                "-c".into(),
+                r#"tr a-z A-Z < "$1" > "$1.tmp" && mv "$1.tmp" "$1""#.into(),
+                "sh".into(),
+                "{input_path}".into(),
+            ],
+            mode: FormatterMode::InPlace,
+            ..script("")
+        };
+        assert_eq!(
+            format(text, None, &in_place, cx).await.unwrap(),
+            (1, "ONE\nTWO\nTHREE\n".to_string())
+        );
+
+        // Formatters supporting ranges are run once per range.
+        let uppercase_line = FormatterDefinition {
+            range_arguments: Some(vec!["{range_start_line}".into()]),
+            ..script(r#"awk -v line="$1" 'NR == line { $0 = toupper($0) } { print }'"#)
+        };
+        assert_eq!(
+            format(
+                text,
+                Some(vec![
+                    Point::new(0, 0)..Point::new(0, 1),
+                    Point::new(2, 0)..Point::new(2, 1),
+                ]),
+                &uppercase_line,
+                cx
+            )
+            .await
+            .unwrap(),
+            (2, "ONE\ntwo\nTHREE\n".to_string())
+        );
+
+        let unchanged = FormatterDefinition {
+            unchanged_exit_codes: vec![3],
+            ..script("cat > /dev/null; exit 3")
+        };
+        assert_eq!(
+            format(text, None, &unchanged, cx).await.unwrap(),
+            (0, text.to_string())
+        );
+
+        let failing = script("cat > /dev/null; echo >&2; echo 'error: expected item' >&2; exit 2");
+        assert_eq!(
+            format(text, None, &failing, cx)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "exited with code 2: error: expected item"
+        );
+    }
+}
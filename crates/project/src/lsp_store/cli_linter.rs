@@ -0,0 +1,1617 @@
+//! Runs the command-line linters from the `linters` setting, reporting their findings as
+//! diagnostics and their suggested fixes as code actions.
+
+use std::{borrow::Cow, ops::Range, path::Path, str::FromStr as _, sync::Arc, time::Duration};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use futures::{AsyncWriteExt as _, future::join_all};
+use gpui::{App, Context, Entity, Task};
+use language::{
+    Buffer, BufferSnapshot, Diagnostic, DiagnosticEntry, DiagnosticSourceKind, File as _,
+    LocalFile as _, PointUtf16, Unclipped,
+    language_settings::{
+        LintTrigger, LinterDefinition, LinterInput, LinterOutputFormat, all_language_settings,
+        language_settings,
+    },
+    point_to_lsp, range_from_lsp,
+};
+use lsp::{DiagnosticSeverity, LanguageServerId, NumberOrString};
+use serde::{Deserialize, Serialize};
+use text::{Anchor, Bias, BufferId, Point, ToPointUtf16 as _};
+use util::{ResultExt as _, post_inc};
+use worktree::File;
+
+use super::{
+    DocumentDiagnostics, DocumentDiagnosticsUpdate, LspStore,
+    cli_formatter::{expand_arguments, first_line},
+};
+use crate::{CodeAction, LspAction, ProjectTransaction};
+
+/// The key of a lint fix code action's data that holds the edits to apply.
+const LINT_FIX_EDITS_KEY: &str = "lint_fix_edits";
+
+/// The linters' state in a local project.
+///
+/// Each linter publishes its diagnostics under a language server id of its own, so that they can be
+/// replaced without touching the diagnostics of language servers or of the other linters.
+#[derive(Default)]
+pub(super) struct CliLinters {
+    server_ids: HashMap<Arc<str>, LanguageServerId>,
+    /// The scheduled or running lint of each buffer. Linters that read the file on disk are tracked
+    /// separately, so that editing the buffer after saving doesn't cancel them.
+    pending_lints: HashMap<(BufferId, LinterInput), Task<()>>,
+}
+
+impl CliLinters {
+    pub(super) fn forget_buffer(&mut self, buffer_id: BufferId) {
+        self.pending_lints
+            .retain(|(pending_buffer_id, _), _| *pending_buffer_id != buffer_id);
+    }
+}
+
+impl LspStore {
+    /// Lints a buffer that was just saved with all of its linters.
+    pub(super) fn lint_saved_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        for input in [LinterInput::Stdin, LinterInput::File] {
+            self.schedule_lint(buffer, input, Duration::ZERO, cx);
+        }
+    }
+
+    /// Lints an edited buffer with the linters that can read unsaved changes, once the buffer
+    /// hasn't been edited for a while.
+    pub(super) fn lint_edited_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let lint_settings = {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+                .lint
+                .clone()
+        };
+        if lint_settings.run == LintTrigger::OnIdle && !lint_settings.linters.is_empty() {
+            self.schedule_lint(
+                buffer,
+                LinterInput::Stdin,
+                Duration::from_millis(lint_settings.idle_delay_ms),
+                cx,
+            );
+        }
+    }
+
+    fn schedule_lint(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        input: LinterInput,
+        delay: Duration,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode.is_local() {
+            return;
+        }
+        let buffer_id = buffer.read(cx).remote_id();
+        let weak_buffer = buffer.downgrade();
+        let task = cx.spawn(async move |lsp_store, cx| {
+            if !delay.is_zero() {
+                cx.background_executor().timer(delay).await;
+            }
+            let Some(buffer) = weak_buffer.upgrade() else {
+                return;
+            };
+            let Ok(lint) = lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store.lint_buffer(&buffer, input, cx)
+            }) else {
+                return;
+            };
+            lint.await;
+        });
+        if let Some(local) = self.as_local_mut() {
+            local
+                .cli_linters
+                .pending_lints
+                .insert((buffer_id, input), task);
+        }
+    }
+
+    /// Runs the buffer's linters that read their input in the given way, and replaces their
+    /// diagnostics for the buffer with the ones they report.
+    fn lint_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        input: LinterInput,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        let languages = self.languages.clone();
+        let Some(local) = self.as_local_mut() else {
+            return Task::ready(());
+        };
+        let buffer_ref = buffer.read(cx);
+        let Some(file) = File::from_dyn(buffer_ref.file()) else {
+            return Task::ready(());
+        };
+        let Some(abs_path) = file.as_local().map(|file| file.abs_path(cx)) else {
+            return Task::ready(());
+        };
+        // Linters that read the file on disk would report outdated findings for unsaved changes.
+        if input == LinterInput::File && buffer_ref.is_dirty() {
+            return Task::ready(());
+        }
+        let worktree_root = file.worktree.read(cx).abs_path();
+        let settings = language_settings(
+            buffer_ref.language().map(|l| l.name()),
+            buffer_ref.file(),
+            cx,
+        );
+        let definitions = &all_language_settings(buffer_ref.file(), cx).linters;
+
+        // Clear the diagnostics of the linters that are no longer enabled for this buffer.
+        let disabled_linters = local
+            .cli_linters
+            .server_ids
+            .iter()
+            .filter(|(name, _)| !settings.lint.linters.iter().any(|n| **n == ***name))
+            .map(|(_, server_id)| *server_id)
+            .filter(|server_id| !buffer_ref.buffer_diagnostics(Some(*server_id)).is_empty())
+            .collect::<Vec<_>>();
+
+        let mut linters = Vec::new();
+        for name in &settings.lint.linters {
+            let Some((name, definition)) = definitions.get_key_value(name.as_str()) else {
+                log::warn!("No linter named {name:?} in the `linters` setting");
+                continue;
+            };
+            if definition.input != input {
+                continue;
+            }
+            let server_id = *local
+                .cli_linters
+                .server_ids
+                .entry(name.clone())
+                .or_insert_with(|| languages.next_language_server_id());
+            linters.push((name.clone(), server_id, definition.clone()));
+        }
+        if linters.is_empty() && disabled_linters.is_empty() {
+            return Task::ready(());
+        }
+
+        let snapshot = buffer_ref.snapshot();
+        let text = (input == LinterInput::Stdin).then(|| snapshot.text());
+        let env = self.environment_for_buffer(buffer, cx);
+        let buffer = buffer.clone();
+        cx.spawn(async move |lsp_store, cx| {
+            let env = env.await;
+            let results = join_all(linters.into_iter().map(|(name, server_id, definition)| {
+                let text = text.as_deref();
+                let abs_path = abs_path.as_path();
+                let worktree_root = worktree_root.as_ref();
+                let env = env.as_ref();
+                async move {
+                    let result = run_linter(&definition, text, abs_path, worktree_root, env).await;
+                    (name, server_id, definition.output_format, result)
+                }
+            }))
+            .await;
+
+            lsp_store
+                .update(cx, |lsp_store, cx| {
+                    let current_snapshot = buffer.read(cx).snapshot();
+                    let Some(local) = lsp_store.as_local_mut() else {
+                        return;
+                    };
+                    let mut updates = disabled_linters
+                        .into_iter()
+                        .map(|server_id| (server_id, Vec::new()))
+                        .collect::<Vec<_>>();
+                    for (name, server_id, output_format, result) in results {
+                        match result {
+                            Ok(issues) => updates.push((
+                                server_id,
+                                diagnostic_entries(
+                                    &name,
+                                    issues,
+                                    column_unit(output_format),
+                                    input == LinterInput::File,
+                                    &snapshot,
+                                    &current_snapshot,
+                                    &mut local.next_diagnostic_group_id,
+                                ),
+                            )),
+                            Err(error) => {
+                                log::error!("Failed to lint {abs_path:?} with {name}: {error:#}")
+                            }
+                        }
+                    }
+                    let updates = updates
+                        .into_iter()
+                        .map(|(server_id, diagnostics)| DocumentDiagnosticsUpdate {
+                            diagnostics: DocumentDiagnostics {
+                                diagnostics,
+                                document_abs_path: abs_path.clone(),
+                                version: None,
+                            },
+                            result_id: None,
+                            registration_id: None,
+                            server_id,
+                            disk_based_sources: Cow::Borrowed(&[]),
+                        })
+                        .collect();
+                    lsp_store
+                        .merge_diagnostic_entries(updates, |_, _, _| false, cx)
+                        .log_err();
+                })
+                .ok();
+        })
+    }
+}
+
+/// The quick fixes that linters suggested for the diagnostics in the given range.
+pub(super) fn lint_fix_actions(buffer: &BufferSnapshot, range: Range<Anchor>) -> Vec<CodeAction> {
+    let mut actions = Vec::new();
+    for (server_id, diagnostic_set) in buffer.diagnostic_sets() {
+        for entry in diagnostic_set.range::<_, PointUtf16>(range.clone(), buffer, true, false) {
+            if entry.diagnostic.source_kind != DiagnosticSourceKind::Other {
+                continue;
+            }
+            let Some(fix_data) = entry
+                .diagnostic
+                .data
+                .as_ref()
+                .and_then(|data| serde_json::from_value::<LintFixData>(data.clone()).ok())
+            else {
+                continue;
+            };
+            let start = entry.range.start;
+            for fix in fix_data.lint_fixes {
+                let edits = fix
+                    .edits
+                    .into_iter()
+                    .map(|edit| lsp::TextEdit {
+                        range: lsp::Range {
+                            start: point_to_lsp(edit.start.resolve(start)),
+                            end: point_to_lsp(edit.end.resolve(start)),
+                        },
+                        new_text: edit.new_text,
+                    })
+                    .collect::<Vec<_>>();
+                actions.push(CodeAction {
+                    server_id: *server_id,
+                    range: buffer.anchor_before(entry.range.start)
+                        ..buffer.anchor_after(entry.range.end),
+                    lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                        title: fix.title,
+                        kind: Some(lsp::CodeActionKind::QUICKFIX),
+                        data: Some(serde_json::json!({ LINT_FIX_EDITS_KEY: edits })),
+                        ..lsp::CodeAction::default()
+                    })),
+                    resolved: true,
+                });
+            }
+        }
+    }
+    actions
+}
+
+/// Applies the code action if it is a fix suggested by a linter, which carries its edits in its
+/// data rather than being resolved by a language server.
+pub(super) fn apply_lint_fix(
+    buffer: &Entity<Buffer>,
+    action: &CodeAction,
+    push_to_history: bool,
+    cx: &mut App,
+) -> Option<ProjectTransaction> {
+    let LspAction::Action(lsp_action) = &action.lsp_action else {
+        return None;
+    };
+    let edits = lsp_action.data.as_ref()?.get(LINT_FIX_EDITS_KEY)?.clone();
+    let edits = serde_json::from_value::<Vec<lsp::TextEdit>>(edits).log_err()?;
+
+    let transaction = buffer.update(cx, |buffer, cx| {
+        let snapshot = buffer.snapshot();
+        let edits = edits
+            .into_iter()
+            .map(|edit| {
+                let range = range_from_lsp(edit.range);
+                let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(range.end, Bias::Left);
+                (start..end, edit.new_text)
+            })
+            .collect::<Vec<_>>();
+
+        buffer.finalize_last_transaction();
+        buffer.start_transaction();
+        buffer.edit(edits, None, cx);
+        if buffer.end_transaction(cx).is_some() {
+            let transaction = buffer.finalize_last_transaction().unwrap().clone();
+            if !push_to_history {
+                buffer.forget_transaction(transaction.id);
+            }
+            Some(transaction)
+        } else {
+            None
+        }
+    });
+
+    let mut project_transaction = ProjectTransaction::default();
+    if let Some(transaction) = transaction {
+        project_transaction.0.insert(buffer.clone(), transaction);
+    }
+    Some(project_transaction)
+}
+
+async fn run_linter(
+    definition: &LinterDefinition,
+    text: Option<&str>,
+    buffer_path: &Path,
+    worktree_root: &Path,
+    env: Option<&HashMap<String, String>>,
+) -> Result<Vec<LintIssue>> {
+    let variables = [
+        ("buffer_path", buffer_path.to_string_lossy().into_owned()),
+        (
+            "buffer_dir",
+            buffer_path
+                .parent()
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        ),
+        (
+            "worktree_root",
+            worktree_root.to_string_lossy().into_owned(),
+        ),
+    ];
+
+    let mut command = util::command::new_smol_command(definition.command.as_ref());
+    if let Some(env) = env {
+        command.envs(env);
+    }
+    command
+        .current_dir(worktree_root)
+        .args(expand_arguments(&definition.arguments, &variables))
+        .stdout(smol::process::Stdio::piped())
+        .stderr(smol::process::Stdio::piped())
+        .stdin(match definition.input {
+            LinterInput::Stdin => smol::process::Stdio::piped(),
+            LinterInput::File => smol::process::Stdio::null(),
+        });
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("failed to run {}", definition.command))?;
+
+    let stdin = child.stdin.take();
+    let write_input = async {
+        if let Some((mut stdin, text)) = stdin.zip(text) {
+            stdin.write_all(text.as_bytes()).await?;
+            stdin.flush().await?;
+        }
+        anyhow::Ok(())
+    };
+    let (write_result, output) = futures::join!(write_input, child.output());
+    let output = output?;
+
+    // Linters exit with an error code when they find problems, so the exit code doesn't tell
+    // whether they ran successfully. Instead, they're considered to have failed when their
+    // report can't be parsed.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let report = if stdout.trim().is_empty() {
+        &stderr
+    } else {
+        &stdout
+    };
+    let issues = if report.trim().is_empty() {
+        Ok(Vec::new())
+    } else {
+        parse_report(definition.output_format, report, &|path| {
+            is_linted_file(path, buffer_path, worktree_root)
+        })
+    };
+    match issues {
+        Ok(issues) => {
+            write_result.context("failed to write the text to lint")?;
+            Ok(issues)
+        }
+        Err(error) => {
+            log::error!(
+                "{} failed with exit code {:?}:\nstdout: {stdout}\nstderr: {stderr}",
+                definition.command,
+                output.status.code(),
+            );
+            match first_line(&stderr) {
+                Some(line) => Err(anyhow!("{line}")),
+                None => Err(error.context("failed to parse the linter's output")),
+            }
+        }
+    }
+}
+
+/// Whether a path reported by a linter refers to the linted buffer. Linters reading their standard
+/// input report no path or a placeholder for it.
+fn is_linted_file(reported_path: Option<&str>, buffer_path: &Path, working_dir: &Path) -> bool {
+    let Some(reported_path) =
+        reported_path.filter(|path| !matches!(*path, "" | "-" | "<stdin>" | "stdin"))
+    else {
+        return true;
+    };
+    if reported_path.starts_with("file:") {
+        return url::Url::parse(reported_path)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .is_some_and(|path| path == buffer_path);
+    }
+    let reported_path = Path::new(reported_path);
+    if reported_path.is_absolute() {
+        reported_path == buffer_path
+    } else {
+        working_dir.join(reported_path) == buffer_path
+    }
+}
+
+/// A position reported by a linter, with a zero-based row and a zero-based column counted in the
+/// linter's [`ColumnUnit`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct LintPosition {
+    row: u32,
+    column: u32,
+}
+
+impl LintPosition {
+    /// Converts the one-based line and column that most linters report.
+    fn one_based(line: u32, column: u32) -> Self {
+        Self {
+            row: line.saturating_sub(1),
+            column: column.saturating_sub(1),
+        }
+    }
+
+    fn line_start(line: u32) -> Self {
+        Self::one_based(line, 1)
+    }
+
+    fn line_end(line: u32) -> Self {
+        Self {
+            row: line.saturating_sub(1),
+            column: u32::MAX,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ColumnUnit {
+    Chars,
+    Bytes,
+    Utf16,
+}
+
+fn column_unit(output_format: LinterOutputFormat) -> ColumnUnit {
+    match output_format {
+        LinterOutputFormat::Shellcheck | LinterOutputFormat::Ruff => ColumnUnit::Chars,
+        LinterOutputFormat::GolangciLint => ColumnUnit::Bytes,
+        LinterOutputFormat::Markdownlint | LinterOutputFormat::Sarif => ColumnUnit::Utf16,
+    }
+}
+
+/// Converts a position reported by a linter to a position in the buffer, clipping it to the
+/// buffer's lines.
+fn point_utf16(position: LintPosition, unit: ColumnUnit, snapshot: &BufferSnapshot) -> PointUtf16 {
+    if position.row > snapshot.max_point().row {
+        return snapshot.max_point_utf16();
+    }
+    let mut remaining = position.column;
+    let mut column = 0;
+    for ch in snapshot.chars_at(Point::new(position.row, 0)) {
+        let len = match unit {
+            ColumnUnit::Chars => 1,
+            ColumnUnit::Bytes => ch.len_utf8() as u32,
+            ColumnUnit::Utf16 => ch.len_utf16() as u32,
+        };
+        if ch == '\n' || remaining < len {
+            break;
+        }
+        remaining -= len;
+        column += ch.len_utf16() as u32;
+    }
+    PointUtf16::new(position.row, column)
+}
+
+#[derive(Debug, PartialEq)]
+struct LintIssue {
+    range: Range<LintPosition>,
+    severity: DiagnosticSeverity,
+    code: Option<String>,
+    code_url: Option<String>,
+    message: String,
+    fixes: Vec<LintIssueFix>,
+}
+
+#[derive(Debug, PartialEq)]
+struct LintIssueFix {
+    title: String,
+    edits: Vec<(Range<LintPosition>, String)>,
+}
+
+/// The fixes of a linter diagnostic, stored in the diagnostic's data so that they are available
+/// wherever the diagnostic is.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct LintFixData {
+    lint_fixes: Vec<LintFix>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct LintFix {
+    title: String,
+    edits: Vec<LintFixEdit>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct LintFixEdit {
+    start: RelativePoint,
+    end: RelativePoint,
+    new_text: String,
+}
+
+/// A position whose row is relative to the start of the diagnostic, so that the fix follows the
+/// diagnostic as lines are inserted or removed above it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+struct RelativePoint {
+    row_delta: i64,
+    column: u32,
+}
+
+impl RelativePoint {
+    fn new(point: PointUtf16, origin: PointUtf16) -> Self {
+        Self {
+            row_delta: point.row as i64 - origin.row as i64,
+            column: point.column,
+        }
+    }
+
+    fn resolve(self, origin: PointUtf16) -> PointUtf16 {
+        let row = (origin.row as i64 + self.row_delta).clamp(0, u32::MAX as i64);
+        PointUtf16::new(row as u32, self.column)
+    }
+}
+
+/// Converts the issues reported for a snapshot of the buffer into diagnostics for its current
+/// contents.
+///
+/// Issues found in the file on disk are left as they are, as they are mapped through the edits
+/// made since the buffer was saved when they're published.
+fn diagnostic_entries(
+    linter: &str,
+    issues: Vec<LintIssue>,
+    unit: ColumnUnit,
+    is_disk_based: bool,
+    linted: &BufferSnapshot,
+    current: &BufferSnapshot,
+    next_group_id: &mut usize,
+) -> Vec<DiagnosticEntry<Unclipped<PointUtf16>>> {
+    let point = |position: LintPosition, bias: Bias| {
+        let point = point_utf16(position, unit, linted);
+        if is_disk_based {
+            point
+        } else {
+            linted.anchor_at(point, bias).to_point_utf16(current)
+        }
+    };
+    let range = |range: Range<LintPosition>| {
+        let start = point(range.start, Bias::Left);
+        let end = point(range.end, Bias::Right);
+        start..end.max(start)
+    };
+
+    issues
+        .into_iter()
+        .map(|issue| {
+            let diagnostic_range = range(issue.range);
+            let origin = diagnostic_range.start;
+            let lint_fixes = issue
+                .fixes
+                .into_iter()
+                .map(|fix| LintFix {
+                    title: fix.title,
+                    edits: fix
+                        .edits
+                        .into_iter()
+                        .map(|(edit_range, new_text)| {
+                            let edit_range = range(edit_range);
+                            LintFixEdit {
+                                start: RelativePoint::new(edit_range.start, origin),
+                                end: RelativePoint::new(edit_range.end, origin),
+                                new_text,
+                            }
+                        })
+                        .collect(),
+                })
+                .collect::<Vec<_>>();
+            DiagnosticEntry {
+                range: Unclipped(diagnostic_range.start)..Unclipped(diagnostic_range.end),
+                diagnostic: Diagnostic {
+                    source: Some(linter.to_string()),
+                    source_kind: DiagnosticSourceKind::Other,
+                    code: issue.code.map(NumberOrString::String),
+                    code_description: issue.code_url.and_then(|url| lsp::Uri::from_str(&url).ok()),
+                    severity: issue.severity,
+                    message: issue.message.trim().to_string(),
+                    group_id: post_inc(next_group_id),
+                    is_primary: true,
+                    is_disk_based,
+                    data: (!lint_fixes.is_empty()).then(|| {
+                        serde_json::to_value(LintFixData { lint_fixes })
+                            .expect("lint fixes are serializable")
+                    }),
+                    ..Diagnostic::default()
+                },
+            }
+        })
+        .collect()
+}
+
+/// Parses a linter's report, keeping the issues whose path `is_linted_file` accepts.
+fn parse_report(
+    output_format: LinterOutputFormat,
+    report: &str,
+    is_linted_file: &dyn Fn(Option<&str>) -> bool,
+) -> Result<Vec<LintIssue>> {
+    match output_format {
+        LinterOutputFormat::Sarif => parse_sarif(report, is_linted_file),
+        LinterOutputFormat::Shellcheck => parse_shellcheck(report, is_linted_file),
+        LinterOutputFormat::Ruff => parse_ruff(report, is_linted_file),
+        LinterOutputFormat::GolangciLint => parse_golangci_lint(report, is_linted_file),
+        LinterOutputFormat::Markdownlint => parse_markdownlint(report, is_linted_file),
+    }
+}
+
+#[derive(Deserialize)]
+struct ShellcheckReport {
+    comments: Vec<ShellcheckComment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShellcheckComment {
+    file: Option<String>,
+    line: u32,
+    end_line: u32,
+    column: u32,
+    end_column: u32,
+    level: String,
+    code: u32,
+    message: String,
+    fix: Option<ShellcheckFix>,
+}
+
+#[derive(Deserialize)]
+struct ShellcheckFix {
+    replacements: Vec<ShellcheckReplacement>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShellcheckReplacement {
+    line: u32,
+    end_line: u32,
+    column: u32,
+    end_column: u32,
+    replacement: String,
+}
+
+/// Parses the output of `shellcheck --format=json1`.
+fn parse_shellcheck(
+    report: &str,
+    is_linted_file: &dyn Fn(Option<&str>) -> bool,
+) -> Result<Vec<LintIssue>> {
+    let report: ShellcheckReport = serde_json::from_str(report)?;
+    Ok(report
+        .comments
+        .into_iter()
+        .filter(|comment| is_linted_file(comment.file.as_deref()))
+        .map(|comment| {
+            let code = format!("SC{}", comment.code);
+            let fixes = comment.fix.map(|fix| LintIssueFix {
+                title: format!("Apply fix for {code}"),
+                edits: fix
+                    .replacements
+                    .into_iter()
+                    .map(|replacement| {
+                        (
+                            LintPosition::one_based(replacement.line, replacement.column)
+                                ..LintPosition::one_based(
+                                    replacement.end_line,
+                                    replacement.end_column,
+                                ),
+                            replacement.replacement,
+                        )
+                    })
+                    .collect(),
+            });
+            LintIssue {
+                range: LintPosition::one_based(comment.line, comment.column)
+                    ..LintPosition::one_based(comment.end_line, comment.end_column),
+                severity: match comment.level.as_str() {
+                    "error" => DiagnosticSeverity::ERROR,
+                    "warning" => DiagnosticSeverity::WARNING,
+                    "info" => DiagnosticSeverity::INFORMATION,
+                    _ => DiagnosticSeverity::HINT,
+                },
+                code_url: Some(format!("https://www.shellcheck.net/wiki/{code}")),
+                code: Some(code),
+                message: comment.message,
+                fixes: fixes.into_iter().collect(),
+            }
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct RuffDiagnostic {
+    code: Option<String>,
+    message: String,
+    location: RuffLocation,
+    end_location: RuffLocation,
+    filename: Option<String>,
+    url: Option<String>,
+    fix: Option<RuffFix>,
+}
+
+#[derive(Deserialize)]
+struct RuffLocation {
+    row: u32,
+    column: u32,
+}
+
+impl RuffLocation {
+    fn position(&self) -> LintPosition {
+        LintPosition::one_based(self.row, self.column)
+    }
+}
+
+#[derive(Deserialize)]
+struct RuffFix {
+    message: Option<String>,
+    applicability: Option<String>,
+    edits: Vec<RuffEdit>,
+}
+
+#[derive(Deserialize)]
+struct RuffEdit {
+    content: Option<String>,
+    location: RuffLocation,
+    end_location: RuffLocation,
+}
+
+/// Parses the output of `ruff check --output-format=json`.
+fn parse_ruff(
+    report: &str,
+    is_linted_file: &dyn Fn(Option<&str>) -> bool,
+) -> Result<Vec<LintIssue>> {
+    let diagnostics: Vec<RuffDiagnostic> = serde_json::from_str(report)?;
+    Ok(diagnostics
+        .into_iter()
+        .filter(|diagnostic| is_linted_file(diagnostic.filename.as_deref()))
+        .map(|diagnostic| {
+            let fixes = diagnostic
+                .fix
+                .filter(|fix| fix.applicability.as_deref() != Some("display-only"))
+                .map(|fix| {
+                    let mut title = fix.message.unwrap_or_else(|| match &diagnostic.code {
+                        Some(code) => format!("Fix {code}"),
+                        None => "Apply suggested fix".to_string(),
+                    });
+                    if fix.applicability.as_deref() == Some("unsafe") {
+                        title.push_str(" (unsafe)");
+                    }
+                    LintIssueFix {
+                        title,
+                        edits: fix
+                            .edits
+                            .into_iter()
+                            .map(|edit| {
+                                (
+                                    edit.location.position()..edit.end_location.position(),
+                                    edit.content.unwrap_or_default(),
+                                )
+                            })
+                            .collect(),
+                    }
+                });
+            LintIssue {
+                range: diagnostic.location.position()..diagnostic.end_location.position(),
+                // Ruff reports syntax errors without a rule code.
+                severity: if diagnostic.code.is_some() {
+                    DiagnosticSeverity::WARNING
+                } else {
+                    DiagnosticSeverity::ERROR
+                },
+                code: diagnostic.code,
+                code_url: diagnostic.url,
+                message: diagnostic.message,
+                fixes: fixes.into_iter().collect(),
+            }
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciLintReport {
+    issues: Option<Vec<GolangciLintIssue>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciLintIssue {
+    from_linter: String,
+    text: String,
+    #[serde(default)]
+    severity: String,
+    pos: GolangciLintPosition,
+    line_range: Option<GolangciLintLineRange>,
+    replacement: Option<GolangciLintReplacement>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciLintPosition {
+    filename: String,
+    line: u32,
+    column: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciLintLineRange {
+    from: u32,
+    to: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciLintReplacement {
+    #[serde(default)]
+    need_only_delete: bool,
+    new_lines: Option<Vec<String>>,
+    inline: Option<GolangciLintInlineFix>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciLintInlineFix {
+    /// The zero-based column where the replaced text starts.
+    start_col: u32,
+    length: u32,
+    new_string: String,
+}
+
+/// Parses the output of `golangci-lint run --output.json.path=stdout`.
+fn parse_golangci_lint(
+    report: &str,
+    is_linted_file: &dyn Fn(Option<&str>) -> bool,
+) -> Result<Vec<LintIssue>> {
+    let report: GolangciLintReport = serde_json::from_str(report)?;
+    Ok(report
+        .issues
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|issue| is_linted_file(Some(&issue.pos.filename)))
+        .map(|issue| {
+            let (first_line, last_line) = issue
+                .line_range
+                .as_ref()
+                .map_or((issue.pos.line, issue.pos.line), |range| {
+                    (range.from, range.to)
+                });
+            let fixes = issue.replacement.map(|replacement| {
+                let edit = if let Some(inline) = replacement.inline {
+                    let start = LintPosition {
+                        row: issue.pos.line.saturating_sub(1),
+                        column: inline.start_col,
+                    };
+                    let end = LintPosition {
+                        column: inline.start_col + inline.length,
+                        ..start
+                    };
+                    (start..end, inline.new_string)
+                } else {
+                    let new_text = if replacement.need_only_delete {
+                        String::new()
+                    } else {
+                        replacement
+                            .new_lines
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|line| line + "\n")
+                            .collect()
+                    };
+                    (
+                        LintPosition::line_start(first_line)
+                            ..LintPosition::line_start(last_line + 1),
+                        new_text,
+                    )
+                };
+                LintIssueFix {
+                    title: format!("Apply fix from {}", issue.from_linter),
+                    edits: vec![edit],
+                }
+            });
+            LintIssue {
+                range: LintPosition::one_based(issue.pos.line, issue.pos.column)
+                    ..LintPosition::line_end(last_line),
+                severity: match issue.severity.to_ascii_lowercase().as_str() {
+                    "error" => DiagnosticSeverity::ERROR,
+                    "info" => DiagnosticSeverity::INFORMATION,
+                    "hint" => DiagnosticSeverity::HINT,
+                    _ => DiagnosticSeverity::WARNING,
+                },
+                code: Some(issue.from_linter),
+                code_url: None,
+                message: issue.text,
+                fixes: fixes.into_iter().collect(),
+            }
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarkdownlintIssue {
+    file_name: Option<String>,
+    line_number: u32,
+    rule_names: Vec<String>,
+    rule_description: String,
+    rule_information: Option<String>,
+    error_detail: Option<String>,
+    error_range: Option<(u32, u32)>,
+    fix_info: Option<MarkdownlintFixInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarkdownlintFixInfo {
+    line_number: Option<u32>,
+    edit_column: Option<u32>,
+    delete_count: Option<i64>,
+    insert_text: Option<String>,
+}
+
+/// Parses the output of `markdownlint --json`.
+fn parse_markdownlint(
+    report: &str,
+    is_linted_file: &dyn Fn(Option<&str>) -> bool,
+) -> Result<Vec<LintIssue>> {
+    let issues: Vec<MarkdownlintIssue> = serde_json::from_str(report)?;
+    Ok(issues
+        .into_iter()
+        .filter(|issue| is_linted_file(issue.file_name.as_deref()))
+        .map(|issue| {
+            let code = issue.rule_names.first().cloned();
+            let fixes = issue.fix_info.map(|fix_info| {
+                let line = fix_info.line_number.unwrap_or(issue.line_number);
+                let edit = match fix_info.delete_count {
+                    // A negative count deletes the whole line.
+                    Some(delete_count) if delete_count < 0 => (
+                        LintPosition::line_start(line)..LintPosition::line_start(line + 1),
+                        String::new(),
+                    ),
+                    delete_count => {
+                        let start =
+                            LintPosition::one_based(line, fix_info.edit_column.unwrap_or(1));
+                        let end = LintPosition {
+                            column: start.column + delete_count.unwrap_or(0) as u32,
+                            ..start
+                        };
+                        (start..end, fix_info.insert_text.unwrap_or_default())
+                    }
+                };
+                LintIssueFix {
+                    title: match &code {
+                        Some(code) => format!("Fix {code}"),
+                        None => "Apply suggested fix".to_string(),
+                    },
+                    edits: vec![edit],
+                }
+            });
+            let range = match issue.error_range {
+                Some((column, length)) => {
+                    let start = LintPosition::one_based(issue.line_number, column);
+                    start..LintPosition {
+                        column: start.column + length,
+                        ..start
+                    }
+                }
+                None => {
+                    LintPosition::line_start(issue.line_number)
+                        ..LintPosition::line_end(issue.line_number)
+                }
+            };
+            let message = match issue.error_detail {
+                Some(detail) => format!("{} [{detail}]", issue.rule_description),
+                None => issue.rule_description,
+            };
+            LintIssue {
+                range,
+                severity: DiagnosticSeverity::WARNING,
+                code,
+                code_url: issue.rule_information,
+                message,
+                fixes: fixes.into_iter().collect(),
+            }
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct SarifLog {
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Deserialize)]
+struct SarifRun {
+    tool: Option<SarifTool>,
+    #[serde(default)]
+    results: Vec<SarifResult>,
+}
+
+#[derive(Deserialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Deserialize)]
+struct SarifDriver {
+    #[serde(default)]
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: String,
+    help_uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: Option<String>,
+    level: Option<String>,
+    message: SarifMessage,
+    #[serde(default)]
+    locations: Vec<SarifLocation>,
+    #[serde(default)]
+    fixes: Vec<SarifFix>,
+}
+
+#[derive(Deserialize)]
+struct SarifMessage {
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: Option<SarifPhysicalLocation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: Option<SarifArtifactLocation>,
+    region: Option<SarifRegion>,
+}
+
+#[derive(Deserialize)]
+struct SarifArtifactLocation {
+    uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: Option<u32>,
+    start_column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+}
+
+impl SarifRegion {
+    fn range(&self) -> Range<LintPosition> {
+        let start_line = self.start_line.unwrap_or(1);
+        let start = LintPosition::one_based(start_line, self.start_column.unwrap_or(1));
+        let end_line = self.end_line.unwrap_or(start_line);
+        let end = match self.end_column {
+            Some(end_column) => LintPosition::one_based(end_line, end_column),
+            None => LintPosition::line_end(end_line),
+        };
+        start..end
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifFix {
+    description: Option<SarifMessage>,
+    #[serde(default)]
+    artifact_changes: Vec<SarifArtifactChange>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactChange {
+    artifact_location: Option<SarifArtifactLocation>,
+    #[serde(default)]
+    replacements: Vec<SarifReplacement>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifReplacement {
+    deleted_region: SarifRegion,
+    inserted_content: Option<SarifContent>,
+}
+
+#[derive(Deserialize)]
+struct SarifContent {
+    text: Option<String>,
+}
+
+/// Parses a SARIF log, in which columns count UTF-16 code units unless specified otherwise.
+fn parse_sarif(
+    report: &str,
+    is_linted_file: &dyn Fn(Option<&str>) -> bool,
+) -> Result<Vec<LintIssue>> {
+    let log: SarifLog = serde_json::from_str(report)?;
+    let mut issues = Vec::new();
+    for run in log.runs {
+        let rules = run.tool.map(|tool| tool.driver.rules).unwrap_or_default();
+        for result in run.results {
+            let location = result
+                .locations
+                .into_iter()
+                .find_map(|location| location.physical_location);
+            let uri = location
+                .as_ref()
+                .and_then(|location| location.artifact_location.as_ref())
+                .and_then(|artifact| artifact.uri.as_deref());
+            if !is_linted_file(uri) {
+                continue;
+            }
+            let range = location
+                .as_ref()
+                .and_then(|location| location.region.as_ref())
+                .map_or_else(
+                    || LintPosition::line_start(1)..LintPosition::line_start(1),
+                    SarifRegion::range,
+                );
+            let fixes = result
+                .fixes
+                .into_iter()
+                .filter_map(|fix| {
+                    let edits = fix
+                        .artifact_changes
+                        .into_iter()
+                        .filter(|change| {
+                            is_linted_file(
+                                change
+                                    .artifact_location
+                                    .as_ref()
+                                    .and_then(|artifact| artifact.uri.as_deref()),
+                            )
+                        })
+                        .flat_map(|change| change.replacements)
+                        .map(|replacement| {
+                            (
+                                replacement.deleted_region.range(),
+                                replacement
+                                    .inserted_content
+                                    .and_then(|content| content.text)
+                                    .unwrap_or_default(),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    let title = fix
+                        .description
+                        .and_then(|description| description.text)
+                        .or_else(|| {
+                            result
+                                .rule_id
+                                .as_ref()
+                                .map(|rule_id| format!("Apply fix for {rule_id}"))
+                        })
+                        .unwrap_or_else(|| "Apply suggested fix".to_string());
+                    (!edits.is_empty()).then_some(LintIssueFix { title, edits })
+                })
+                .collect();
+            let code_url = result.rule_id.as_ref().and_then(|rule_id| {
+                rules
+                    .iter()
+                    .find(|rule| &rule.id == rule_id)
+                    .and_then(|rule| rule.help_uri.clone())
+            });
+            issues.push(LintIssue {
+                range,
+                severity: match result.level.as_deref() {
+                    Some("error") => DiagnosticSeverity::ERROR,
+                    Some("note") => DiagnosticSeverity::INFORMATION,
+                    Some("none") => DiagnosticSeverity::HINT,
+                    _ => DiagnosticSeverity::WARNING,
+                },
+                code: result.rule_id,
+                code_url,
+                message: result.message.text.unwrap_or_default(),
+                fixes,
+            });
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use pretty_assertions::assert_eq;
+    use util::path;
+
+    fn position(row: u32, column: u32) -> LintPosition {
+        LintPosition { row, column }
+    }
+
+    fn any_file(_: Option<&str>) -> bool {
+        true
+    }
+
+    #[test]
+    fn test_parse_shellcheck() {
+        let report = r#"{"comments":[
+            {"file":"-","line":3,"endLine":3,"column":6,"endColumn":10,"level":"info","code":2086,
+             "message":"Double quote to prevent globbing and word splitting.",
+             "fix":{"replacements":[
+                {"line":3,"endLine":3,"column":6,"endColumn":6,"precedence":7,"insertionPoint":"afterEnd","replacement":"\""},
+                {"line":3,"endLine":3,"column":10,"endColumn":10,"precedence":7,"insertionPoint":"beforeStart","replacement":"\""}
+             ]}},
+            {"file":"-","line":1,"endLine":1,"column":1,"endColumn":1,"level":"error","code":2148,
+             "message":"Tips depend on target shell and yours is unknown.","fix":null}
+        ]}"#;
+        assert_eq!(
+            parse_report(LinterOutputFormat::Shellcheck, report, &any_file).unwrap(),
+            [
+                LintIssue {
+                    range: position(2, 5)..position(2, 9),
+                    severity: DiagnosticSeverity::INFORMATION,
+                    code: Some("SC2086".to_string()),
+                    code_url: Some("https://www.shellcheck.net/wiki/SC2086".to_string()),
+                    message: "Double quote to prevent globbing and word splitting.".to_string(),
+                    fixes: vec![LintIssueFix {
+                        title: "Apply fix for SC2086".to_string(),
+                        edits: vec![
+                            (position(2, 5)..position(2, 5), "\"".to_string()),
+                            (position(2, 9)..position(2, 9), "\"".to_string()),
+                        ],
+                    }],
+                },
+                LintIssue {
+                    range: position(0, 0)..position(0, 0),
+                    severity: DiagnosticSeverity::ERROR,
+                    code: Some("SC2148".to_string()),
+                    code_url: Some("https://www.shellcheck.net/wiki/SC2148".to_string()),
+                    message: "Tips depend on target shell and yours is unknown.".to_string(),
+                    fixes: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ruff() {
+        let report = r#"[
+            {"cell":null,"code":"F401","end_location":{"column":10,"row":1},
+             "filename":"/project/main.py",
+             "fix":{"applicability":"safe","edits":[{"content":"","end_location":{"column":1,"row":2},"location":{"column":1,"row":1}}],"message":"Remove unused import: `os`"},
+             "location":{"column":8,"row":1},"message":"`os` imported but unused","noqa_row":1,
+             "url":"https://docs.astral.sh/ruff/rules/unused-import"},
+            {"cell":null,"code":null,"end_location":{"column":5,"row":4},"filename":"/project/main.py",
+             "fix":null,"location":{"column":1,"row":4},"message":"SyntaxError: Expected an expression","noqa_row":null,"url":null},
+            {"cell":null,"code":"E501","end_location":{"column":2,"row":1},"filename":"/project/other.py",
+             "fix":null,"location":{"column":1,"row":1},"message":"Line too long","noqa_row":1,"url":null}
+        ]"#;
+        let is_main = |path: Option<&str>| path == Some("/project/main.py");
+        assert_eq!(
+            parse_report(LinterOutputFormat::Ruff, report, &is_main).unwrap(),
+            [
+                LintIssue {
+                    range: position(0, 7)..position(0, 9),
+                    severity: DiagnosticSeverity::WARNING,
+                    code: Some("F401".to_string()),
+                    code_url: Some("https://docs.astral.sh/ruff/rules/unused-import".to_string()),
+                    message: "`os` imported but unused".to_string(),
+                    fixes: vec![LintIssueFix {
+                        title: "Remove unused import: `os`".to_string(),
+                        edits: vec![(position(0, 0)..position(1, 0), String::new())],
+                    }],
+                },
+                LintIssue {
+                    range: position(3, 0)..position(3, 4),
+                    severity: DiagnosticSeverity::ERROR,
+                    code: None,
+                    code_url: None,
+                    message: "SyntaxError: Expected an expression".to_string(),
+                    fixes: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_golangci_lint() {
+        let report = r#"{"Issues":[
+            {"FromLinter":"errcheck","Text":"Error return value of `f.Close` is not checked","Severity":"",
+             "SourceLines":["\tf.Close()"],"Pos":{"Filename":"cmd/main.go","Offset":120,"Line":12,"Column":9}},
+            {"FromLinter":"gofmt","Text":"File is not properly formatted","Severity":"error",
+             "SourceLines":["x :=  1"],"Replacement":{"NeedOnlyDelete":false,"NewLines":["x := 1"],"Inline":null},
+             "LineRange":{"From":4,"To":4},"Pos":{"Filename":"cmd/main.go","Offset":0,"Line":4,"Column":0}},
+            {"FromLinter":"misspell","Text":"`recieve` is a misspelling of `receive`","Severity":"",
+             "Replacement":{"NeedOnlyDelete":false,"NewLines":null,"Inline":{"StartCol":3,"Length":7,"NewString":"receive"}},
+             "Pos":{"Filename":"cmd/util.go","Offset":0,"Line":2,"Column":4}}
+        ],"Report":{}}"#;
+        let is_main = |path: Option<&str>| path == Some("cmd/main.go");
+        assert_eq!(
+            parse_report(LinterOutputFormat::GolangciLint, report, &is_main).unwrap(),
+            [
+                LintIssue {
+                    range: position(11, 8)..position(11, u32::MAX),
+                    severity: DiagnosticSeverity::WARNING,
+                    code: Some("errcheck".to_string()),
+                    code_url: None,
+                    message: "Error return value of `f.Close` is not checked".to_string(),
+                    fixes: Vec::new(),
+                },
+                LintIssue {
+                    range: position(3, 0)..position(3, u32::MAX),
+                    severity: DiagnosticSeverity::ERROR,
+                    code: Some("gofmt".to_string()),
+                    code_url: None,
+                    message: "File is not properly formatted".to_string(),
+                    fixes: vec![LintIssueFix {
+                        title: "Apply fix from gofmt".to_string(),
+                        edits: vec![(position(3, 0)..position(4, 0), "x := 1\n".to_string())],
+                    }],
+                },
+            ]
+        );
+
+        let is_util = |path: Option<&str>| path == Some("cmd/util.go");
+        assert_eq!(
+            parse_report(LinterOutputFormat::GolangciLint, report, &is_util).unwrap()[0].fixes,
+            [LintIssueFix {
+                title: "Apply fix from misspell".to_string(),
+                edits: vec![(position(1, 3)..position(1, 10), "receive".to_string())],
+            }]
+        );
+        assert!(
+            parse_report(
+                LinterOutputFormat::GolangciLint,
+                r#"{"Issues":null}"#,
+                &any_file
+            )
+            .unwrap()
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parse_markdownlint() {
+        let report = r#"[
+            {"fileName":"stdin","lineNumber":2,"ruleNames":["MD009","no-trailing-spaces"],
+             "ruleDescription":"Trailing spaces","ruleInformation":"https://github.com/DavidAnson/markdownlint/blob/v0.37.4/doc/md009.md",
+             "errorDetail":"Expected: 0 or 2; Actual: 1","errorContext":null,"errorRange":[6,1],
+             "fixInfo":{"editColumn":6,"deleteCount":1}},
+            {"fileName":"stdin","lineNumber":4,"ruleNames":["MD012","no-multiple-blanks"],
+             "ruleDescription":"Multiple consecutive blank lines","ruleInformation":null,
+             "errorDetail":null,"errorContext":null,"errorRange":null,"fixInfo":{"deleteCount":-1}}
+        ]"#;
+        assert_eq!(
+            parse_report(LinterOutputFormat::Markdownlint, report, &any_file).unwrap(),
+            [
+                LintIssue {
+                    range: position(1, 5)..position(1, 6),
+                    severity: DiagnosticSeverity::WARNING,
+                    code: Some("MD009".to_string()),
+                    code_url: Some(
+                        "https://github.com/DavidAnson/markdownlint/blob/v0.37.4/doc/md009.md"
+                            .to_string()
+                    ),
+                    message: "Trailing spaces [Expected: 0 or 2; Actual: 1]".to_string(),
+                    fixes: vec![LintIssueFix {
+                        title: "Fix MD009".to_string(),
+                        edits: vec![(position(1, 5)..position(1, 6), String::new())],
+                    }],
+                },
+                LintIssue {
+                    range: position(3, 0)..position(3, u32::MAX),
+                    severity: DiagnosticSeverity::WARNING,
+                    code: Some("MD012".to_string()),
+                    code_url: None,
+                    message: "Multiple consecutive blank lines".to_string(),
+                    fixes: vec![LintIssueFix {
+                        title: "Fix MD012".to_string(),
+                        edits: vec![(position(3, 0)..position(4, 0), String::new())],
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sarif() {
+        let report = r#"{"version":"2.1.0","runs":[{
+            "tool":{"driver":{"name":"Hadolint","rules":[
+                {"id":"DL3008","helpUri":"https://github.com/hadolint/hadolint/wiki/DL3008"}]}},
+            "results":[
+                {"ruleId":"DL3008","level":"warning",
+                 "message":{"text":"Pin versions in apt get install."},
+                 "locations":[{"physicalLocation":{"artifactLocation":{"uri":"-"},
+                    "region":{"startLine":3,"startColumn":1,"endLine":3,"endColumn":34}}}]},
+                {"ruleId":"DL3020","level":"error",
+                 "message":{"text":"Use COPY instead of ADD for files and folders"},
+                 "locations":[{"physicalLocation":{"artifactLocation":{"uri":"-"},
+                    "region":{"startLine":5}}}],
+                 "fixes":[{"artifactChanges":[{"artifactLocation":{"uri":"-"},"replacements":[
+                    {"deletedRegion":{"startLine":5,"startColumn":1,"endLine":5,"endColumn":4},
+                     "insertedContent":{"text":"COPY"}}]}]}]}
+            ]}]}"#;
+        assert_eq!(
+            parse_report(LinterOutputFormat::Sarif, report, &any_file).unwrap(),
+            [
+                LintIssue {
+                    range: position(2, 0)..position(2, 33),
+                    severity: DiagnosticSeverity::WARNING,
+                    code: Some("DL3008".to_string()),
+                    code_url: Some("https://github.com/hadolint/hadolint/wiki/DL3008".to_string()),
+                    message: "Pin versions in apt get install.".to_string(),
+                    fixes: Vec::new(),
+                },
+                LintIssue {
+                    range: position(4, 0)..position(4, u32::MAX),
+                    severity: DiagnosticSeverity::ERROR,
+                    code: Some("DL3020".to_string()),
+                    code_url: None,
+                    message: "Use COPY instead of ADD for files and folders".to_string(),
+                    fixes: vec![LintIssueFix {
+                        title: "Apply fix for DL3020".to_string(),
+                        edits: vec![(position(4, 0)..position(4, 3), "COPY".to_string())],
+                    }],
+                },
+            ]
+        );
+        assert!(
+            parse_report(
+                LinterOutputFormat::Sarif,
+                "hadolint: command failed",
+                &any_file
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_is_linted_file() {
+        let buffer_path = Path::new(path!("/project/src/main.sh"));
+        let working_dir = Path::new(path!("/project"));
+        for reported_path in [None, Some("-"), Some("<stdin>"), Some("")] {
+            assert!(is_linted_file(reported_path, buffer_path, working_dir));
+        }
+        assert!(is_linted_file(
+            Some(path!("/project/src/main.sh")),
+            buffer_path,
+            working_dir
+        ));
+        let relative_path = Path::new("src").join("main.sh");
+        assert!(is_linted_file(
+            relative_path.to_str(),
+            buffer_path,
+            working_dir
+        ));
+        assert!(!is_linted_file(Some("main.sh"), buffer_path, working_dir));
+        assert!(!is_linted_file(
+            Some(path!("/project/src/other.sh")),
+            buffer_path,
+            working_dir
+        ));
+        let uri = url::Url::from_file_path(buffer_path).unwrap();
+        assert!(is_linted_file(Some(uri.as_str()), buffer_path, working_dir));
+    }
+
+    #[gpui::test]
+    fn test_point_utf16(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a\n\té😀x = 1\n", cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let point = |column, unit| point_utf16(position(1, column), unit, &snapshot);
+
+        assert_eq!(point(3, ColumnUnit::Chars), PointUtf16::new(1, 4));
+        assert_eq!(point(7, ColumnUnit::Bytes), PointUtf16::new(1, 4));
+        assert_eq!(point(4, ColumnUnit::Utf16), PointUtf16::new(1, 4));
+        // Columns within a character are rounded down to its start.
+        assert_eq!(point(5, ColumnUnit::Bytes), PointUtf16::new(1, 2));
+        assert_eq!(point(u32::MAX, ColumnUnit::Chars), PointUtf16::new(1, 9));
+        assert_eq!(
+            point_utf16(position(7, 0), ColumnUnit::Chars, &snapshot),
+            PointUtf16::new(2, 0)
+        );
+    }
+
+    #[gpui::test]
+    fn test_diagnostic_entries(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("import os\nx =  1\n", cx));
+        let linted = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "# comment\n")], None, cx)
+        });
+        let current = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+
+        let issues = vec![LintIssue {
+            range: position(1, 3)..position(1, 5),
+            severity: DiagnosticSeverity::WARNING,
+            code: Some("E222".to_string()),
+            code_url: Some(
+                "https://docs.astral.sh/ruff/rules/multiple-spaces-after-operator".to_string(),
+            ),
+            message: "Multiple spaces after operator".to_string(),
+            fixes: vec![LintIssueFix {
+                title: "Remove whitespace".to_string(),
+                edits: vec![(position(1, 3)..position(1, 5), " ".to_string())],
+            }],
+        }];
+        let mut next_group_id = 7;
+        let entries = diagnostic_entries(
+            "ruff",
+            issues,
+            ColumnUnit::Chars,
+            false,
+            &linted,
+            &current,
+            &mut next_group_id,
+        );
+
+        assert_eq!(next_group_id, 8);
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        // The issue follows the line that was inserted above it after linting.
+        assert_eq!(
+            entry.range,
+            Unclipped(PointUtf16::new(2, 3))..Unclipped(PointUtf16::new(2, 5))
+        );
+        assert_eq!(entry.diagnostic.source.as_deref(), Some("ruff"));
+        assert_eq!(entry.diagnostic.group_id, 7);
+        assert_eq!(
+            entry.diagnostic.code,
+            Some(NumberOrString::String("E222".to_string()))
+        );
+        assert!(!entry.diagnostic.is_disk_based);
+
+        let fix_data =
+            serde_json::from_value::<LintFixData>(entry.diagnostic.data.clone().unwrap()).unwrap();
+        assert_eq!(
+            fix_data,
+            LintFixData {
+                lint_fixes: vec![LintFix {
+                    title: "Remove whitespace".to_string(),
+                    edits: vec![LintFixEdit {
+                        start: RelativePoint {
+                            row_delta: 0,
+                            column: 3
+                        },
+                        end: RelativePoint {
+                            row_delta: 0,
+                            column: 5
+                        },
+                        new_text: " ".to_string(),
+                    }],
+                }],
+            }
+        );
+        assert_eq!(
+            fix_data.lint_fixes[0].edits[0]
+                .start
+                .resolve(PointUtf16::new(4, 0)),
+            PointUtf16::new(4, 3)
+        );
+    }
+}
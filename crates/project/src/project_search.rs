@@ -320,6 +320,7 @@ impl Search {
                             grab_buffer_snapshot_rx,
                             find_all_matches_tx,
                             sorted_matches_tx,
+                            query.needs_syntax_tree(),
                             cx.clone(),
                         )
                         .boxed_local(),
@@ -331,8 +332,12 @@ impl Search {
                 };
                 let ensure_matches_are_reported_in_order = if should_find_all_matches {
                     Some(
-                        Self::ensure_matched_ranges_are_reported_in_order(sorted_matches_rx, tx)
-                            .boxed_local(),
+                        Self::ensure_matched_ranges_are_reported_in_order(
+                            sorted_matches_rx,
+                            tx,
+                            query.is_structural(),
+                        )
+                        .boxed_local(),
                     )
                 } else {
                     drop(tx);
@@ -486,10 +491,18 @@ impl Search {
             oneshot::Sender<(Entity<Buffer>, Vec<Range<language::Anchor>>)>,
         )>,
         results: Sender<oneshot::Receiver<(Entity<Buffer>, Vec<Range<language::Anchor>>)>>,
+        wait_for_parsing: bool,
         mut cx: AsyncApp,
     ) {
         _ = maybe!(async move {
             while let Ok(buffer) = rx.recv().await {
+                if wait_for_parsing {
+                    // Some queries match against syntax trees, which freshly opened
+                    // buffers don't have yet.
+                    buffer
+                        .read_with(&mut cx, |this, _| this.parsing_idle())?
+                        .await;
+                }
                 let snapshot = buffer.read_with(&mut cx, |this, _| this.snapshot())?;
                 let (tx, rx) = oneshot::channel();
                 find_all_matches_tx.send((buffer, snapshot, tx)).await?;
@@ -504,6 +517,7 @@ impl Search {
     async fn ensure_matched_ranges_are_reported_in_order(
         rx: Receiver<oneshot::Receiver<(Entity<Buffer>, Vec<Range<language::Anchor>>)>>,
         tx: Sender<SearchResult>,
+        skip_unmatched_buffers: bool,
     ) {
         use postage::stream::Stream;
         _ = maybe!(async move {
@@ -513,6 +527,11 @@ impl Search {
                 let Some((buffer, ranges)) = next_buffer_matches.recv().await else {
                     continue;
                 };
+                // Structural queries can only approximate whether a file matches before
+                // searching it, so don't report the files that turned out not to.
+                if skip_unmatched_buffers && ranges.is_empty() {
+                    continue;
+                }
 
                 if matched_buffers > Search::MAX_SEARCH_RESULT_FILES
                     || matches > Search::MAX_SEARCH_RESULT_RANGES
@@ -3,6 +3,7 @@
 use crate::{
     Event,
     git_store::{GitStoreEvent, RepositoryEvent, StatusEntry, pending_op},
+    search::SyntaxScope,
     task_inventory::TaskContexts,
     task_store::TaskSettingsLocation,
     *,
@@ -6381,6 +6382,85 @@ async fn test_search_in_gitignored_dirs(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "fn one() { foo(1, 2); foo(3); }",
+            "two.rs": "fn two() {\n    foo(\n        4, // four\n        5,\n    );\n}",
+            "three.rs": "fn three() { bar(1, 2); }",
+            "notes.txt": "foo(1, 2)",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    let query = SearchQuery::structural(
+        "foo($A, $B)",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query, cx).await.unwrap(),
+        HashMap::from_iter([
+            (path!("dir/one.rs").to_string(), vec![11..20]),
+            (path!("dir/two.rs").to_string(), vec![15..55]),
+        ]),
+        "only calls with two arguments match, regardless of formatting, and files without a grammar are skipped"
+    );
+}
+
+#[gpui::test]
+async fn test_search_syntax_scope(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "// foo\nfn foo() { let s = \"foo\"; }",
+            "two.rs": "fn two() {}",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    for (syntax_scope, expected_ranges) in [
+        (SyntaxScope::Any, vec![3..6, 10..13, 27..30]),
+        (SyntaxScope::Code, vec![10..13]),
+        (SyntaxScope::Comments, vec![3..6]),
+        (SyntaxScope::Strings, vec![27..30]),
+    ] {
+        let query = SearchQuery::text(
+            "foo",
+            false,
+            false,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+        .with_syntax_scope(syntax_scope);
+        assert_eq!(
+            search(&project, query, cx).await.unwrap(),
+            HashMap::from_iter([(path!("dir/one.rs").to_string(), expected_ranges)]),
+            "unexpected matches for {syntax_scope:?}"
+        );
+    }
+}
+
 #[gpui::test]
 async fn test_search_with_unicode(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -18,6 +18,10 @@ use util::{
     rel_path::RelPath,
 };
 
+mod structural;
+
+pub use structural::StructuralPattern;
+
 #[derive(Debug)]
 pub enum SearchResult {
     Buffer {
@@ -34,6 +38,91 @@ pub enum SearchInputKind {
     Exclude,
 }
 
+/// The syntactic context that search matches are restricted to, based on
+/// the syntax trees of the searched buffers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SyntaxScope {
+    /// Keep matches wherever they are.
+    #[default]
+    Any,
+    /// Keep matches outside of comments and strings.
+    Code,
+    /// Keep matches inside comments, including doc comments.
+    Comments,
+    /// Keep matches inside string literals.
+    Strings,
+}
+
+impl SyntaxScope {
+    pub const ALL: [Self; 4] = [Self::Any, Self::Code, Self::Comments, Self::Strings];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Any => "Match Anywhere",
+            Self::Code => "Match in Code Only",
+            Self::Comments => "Match in Comments Only",
+            Self::Strings => "Match in Strings Only",
+        }
+    }
+
+    /// The scope after this one, wrapping around to [`SyntaxScope::Any`].
+    pub fn next(self) -> Self {
+        let ix = Self::ALL
+            .iter()
+            .position(|scope| *scope == self)
+            .unwrap_or(0);
+        Self::ALL[(ix + 1) % Self::ALL.len()]
+    }
+
+    /// Classifies the given range of the buffer as code, a comment or a string.
+    ///
+    /// Languages declare their comments and strings in `overrides.scm`. For the
+    /// ones that don't, this falls back to the names of the enclosing syntax nodes.
+    pub fn of_range(buffer: &BufferSnapshot, range: Range<usize>) -> Self {
+        if let Some(scope) = buffer.language_scope_at(range.start)
+            && let Some(name) = scope.override_name()
+        {
+            match name {
+                "comment" => return Self::Comments,
+                "string" => return Self::Strings,
+                _ => {}
+            }
+        }
+
+        let mut node = buffer.syntax_ancestor(range);
+        while let Some(current) = node {
+            let kind = current.kind();
+            if kind.contains("interpolation") || kind.contains("substitution") {
+                break;
+            } else if kind.contains("comment") {
+                return Self::Comments;
+            } else if kind.contains("string") || kind.contains("char_literal") {
+                return Self::Strings;
+            }
+            node = current.parent();
+        }
+        Self::Code
+    }
+
+    fn to_proto(self) -> proto::SearchSyntaxScope {
+        match self {
+            Self::Any => proto::SearchSyntaxScope::Any,
+            Self::Code => proto::SearchSyntaxScope::Code,
+            Self::Comments => proto::SearchSyntaxScope::Comments,
+            Self::Strings => proto::SearchSyntaxScope::Strings,
+        }
+    }
+
+    fn from_proto(scope: proto::SearchSyntaxScope) -> Self {
+        match scope {
+            proto::SearchSyntaxScope::Any => Self::Any,
+            proto::SearchSyntaxScope::Code => Self::Code,
+            proto::SearchSyntaxScope::Comments => Self::Comments,
+            proto::SearchSyntaxScope::Strings => Self::Strings,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
@@ -41,6 +130,7 @@ pub struct SearchInputs {
     files_to_exclude: PathMatcher,
     match_full_paths: bool,
     buffers: Option<Vec<Entity<Buffer>>>,
+    syntax_scope: SyntaxScope,
 }
 
 impl SearchInputs {
@@ -77,6 +167,13 @@ pub enum SearchQuery {
         one_match_per_line: bool,
         inner: SearchInputs,
     },
+    /// Matches code by its syntax tree rather than its text, see [`StructuralPattern`].
+    Structural {
+        pattern: StructuralPattern,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -126,6 +223,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            syntax_scope: SyntaxScope::Any,
         };
         Ok(Self::Text {
             search,
@@ -189,6 +287,7 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            syntax_scope: SyntaxScope::Any,
         };
         Ok(Self::Regex {
             regex,
@@ -202,6 +301,36 @@ impl SearchQuery {
         })
     }
 
+    /// Create a structural query, which matches a code pattern with metavariables
+    /// against the syntax trees of the searched files.
+    ///
+    /// Include/exclude patterns are matched the same way as for [`Self::regex`].
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            match_full_paths,
+            buffers,
+            syntax_scope: SyntaxScope::Any,
+        };
+        Ok(Self::Structural {
+            pattern,
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     /// Extracts case sensitivity settings from pattern items in the provided
     /// query and returns the same query, with the pattern items removed.
     ///
@@ -266,7 +395,17 @@ impl SearchQuery {
             message.files_to_exclude
         };
 
-        if message.regex {
+        let syntax_scope = SyntaxScope::from_proto(message.syntax_scope());
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                PathMatcher::new(files_to_include, path_style)?,
+                PathMatcher::new(files_to_exclude, path_style)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -289,7 +428,8 @@ impl SearchQuery {
                 false,
                 None, // search opened only don't need search remote
             )
-        }
+        };
+        Ok(query?.with_syntax_scope(syntax_scope))
     }
 
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
@@ -301,6 +441,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -308,12 +452,26 @@ impl SearchQuery {
         }
     }
 
+    /// Restricts matches to code, comments or strings.
+    pub fn with_syntax_scope(mut self, syntax_scope: SyntaxScope) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Structural { ref mut inner, .. } => {
+                inner.syntax_scope = syntax_scope;
+                self
+            }
+        }
+    }
+
     pub fn to_proto(&self) -> proto::SearchQuery {
         let mut files_to_include = self.files_to_include().sources();
         let mut files_to_exclude = self.files_to_exclude().sources();
         proto::SearchQuery {
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
+            syntax_scope: self.syntax_scope().to_proto() as i32,
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -363,14 +521,19 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let mut text = String::new();
+                reader.read_to_string(&mut text)?;
+                Ok(pattern.may_match(&text))
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
@@ -397,6 +560,24 @@ impl SearchQuery {
                     None
                 }
             }
+            // Structural replacements need the match's syntax tree, see `structural_replacement_for`.
+            SearchQuery::Structural { .. } => None,
+        }
+    }
+
+    /// Replaces a structural search hit, given as a range of `buffer` that this query matched.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        match self {
+            SearchQuery::Structural {
+                pattern,
+                replacement,
+                ..
+            } => pattern.replacement_for(buffer, range, replacement.as_deref()?),
+            SearchQuery::Text { .. } | SearchQuery::Regex { .. } => None,
         }
     }
 
@@ -495,6 +676,18 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { pattern, .. } => {
+                matches = pattern.search(buffer, Some(range_offset..range_offset + rope.len()));
+            }
+        }
+
+        let syntax_scope = self.syntax_scope();
+        if syntax_scope != SyntaxScope::Any {
+            matches.retain(|range| {
+                let range = range.start + range_offset..range.end + range_offset;
+                SyntaxScope::of_range(buffer, range) == syntax_scope
+            });
         }
 
         matches
@@ -512,6 +705,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -519,6 +713,8 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            // Identifiers are compared exactly, but that's not an option the user chose.
+            Self::Structural { .. } => false,
         }
     }
 
@@ -530,6 +726,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -537,6 +736,20 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
+    pub fn syntax_scope(&self) -> SyntaxScope {
+        self.as_inner().syntax_scope
+    }
+
+    /// Whether matching depends on the syntax trees of the searched buffers,
+    /// which need to be parsed before searching them.
+    pub fn needs_syntax_tree(&self) -> bool {
+        self.is_structural() || self.syntax_scope() != SyntaxScope::Any
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -580,7 +793,9 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 
@@ -593,7 +808,7 @@ impl SearchQuery {
             Self::Regex {
                 one_match_per_line, ..
             } => Some(*one_match_per_line),
-            Self::Text { .. } => None,
+            Self::Text { .. } | Self::Structural { .. } => None,
         }
     }
 }
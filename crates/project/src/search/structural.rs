@@ -0,0 +1,614 @@
+//! Syntax-aware search patterns, in the spirit of ast-grep.
+//!
+//! A pattern is a snippet of code in the language being searched, where
+//! metavariables stand in for arbitrary syntax nodes:
+//!
+//! - `$NAME` matches a single node and captures its text.
+//! - `$$$NAME` matches any number of sibling nodes, such as the arguments of a call.
+//! - `$_` and `$$$` match without capturing anything.
+//!
+//! Using the same metavariable twice requires both nodes to have the same text,
+//! so `$A == $A` finds comparisons of an expression with itself. Replacement
+//! templates refer to captures with the same syntax, e.g. `bar($B, $A)`.
+//!
+//! The pattern is parsed with the tree-sitter grammar of every language it is
+//! matched against, and matches are found by comparing the pattern's syntax tree
+//! with the buffer's, so whitespace, comments and formatting are ignored.
+
+use collections::{HashMap, HashSet};
+use language::{BufferSnapshot, Language, LanguageName, Node, Tree};
+use parking_lot::Mutex;
+use std::{fmt, ops::Range, sync::Arc};
+
+const PLACEHOLDER_PREFIX: &str = "zed_meta_";
+const MULTI_PLACEHOLDER_PREFIX: &str = "zed_metas_";
+
+#[derive(Clone)]
+pub struct StructuralPattern {
+    source: Arc<str>,
+    /// The pattern with metavariables replaced by identifiers that every grammar can parse.
+    parseable: Arc<str>,
+    metavariables: Arc<HashMap<String, Metavariable>>,
+    /// Words that must appear verbatim in any matching text.
+    literals: Arc<[String]>,
+    compiled: Arc<Mutex<HashMap<LanguageName, Option<Arc<CompiledPattern>>>>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Metavariable {
+    name: String,
+    multi: bool,
+}
+
+impl Metavariable {
+    fn captures(&self) -> bool {
+        !self.name.is_empty() && !self.name.starts_with('_')
+    }
+}
+
+struct CompiledPattern {
+    tree: Tree,
+    root_range: Range<usize>,
+}
+
+impl CompiledPattern {
+    fn root(&self) -> Node<'_> {
+        self.tree
+            .root_node()
+            .named_descendant_for_byte_range(self.root_range.start, self.root_range.end)
+            .unwrap_or_else(|| self.tree.root_node())
+    }
+}
+
+type Captures = HashMap<String, Range<usize>>;
+
+/// Sequences that are known not to match, identified by the number of pattern
+/// and candidate nodes left to match and the captures made so far.
+type Failures = HashSet<(usize, usize, Vec<(String, Range<usize>)>)>;
+
+enum Token<'a> {
+    Text(&'a str),
+    Metavariable(Metavariable),
+}
+
+/// Splits a pattern or replacement template into literal text and metavariables.
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut ix = 0;
+    let bytes = source.as_bytes();
+    while ix < bytes.len() {
+        if bytes[ix] != b'$' {
+            ix += 1;
+            continue;
+        }
+
+        let multi = source[ix..].starts_with("$$$");
+        let name_start = if multi { ix + 3 } else { ix + 1 };
+        let name_len = source[name_start..]
+            .bytes()
+            .take_while(|byte| matches!(byte, b'A'..=b'Z' | b'0'..=b'9' | b'_'))
+            .count();
+        let name_end = name_start + name_len;
+        let is_metavariable = if multi {
+            true
+        } else {
+            name_len > 0 && !bytes[name_start].is_ascii_digit()
+        };
+        if !is_metavariable {
+            ix += 1;
+            continue;
+        }
+
+        if text_start < ix {
+            tokens.push(Token::Text(&source[text_start..ix]));
+        }
+        tokens.push(Token::Metavariable(Metavariable {
+            name: source[name_start..name_end].to_string(),
+            multi,
+        }));
+        ix = name_end;
+        text_start = ix;
+    }
+    if text_start < source.len() {
+        tokens.push(Token::Text(&source[text_start..]));
+    }
+    tokens
+}
+
+impl StructuralPattern {
+    pub fn new(source: &str) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            !source.trim().is_empty(),
+            "structural search pattern is empty"
+        );
+
+        let mut parseable = String::new();
+        let mut metavariables = HashMap::default();
+        let mut literals = Vec::new();
+        for token in tokenize(source) {
+            match token {
+                Token::Text(text) => {
+                    parseable.push_str(text);
+                    literals.extend(
+                        text.split(|c: char| !c.is_alphanumeric() && c != '_')
+                            .filter(|word| !word.is_empty())
+                            .map(ToString::to_string),
+                    );
+                }
+                Token::Metavariable(metavariable) => {
+                    let placeholder = if metavariable.multi {
+                        format!("{MULTI_PLACEHOLDER_PREFIX}{}", metavariable.name)
+                    } else {
+                        format!("{PLACEHOLDER_PREFIX}{}", metavariable.name)
+                    };
+                    parseable.push_str(&placeholder);
+                    if metavariable.captures()
+                        && metavariables.values().any(|existing: &Metavariable| {
+                            existing.name == metavariable.name
+                                && existing.multi != metavariable.multi
+                        })
+                    {
+                        let name = &metavariable.name;
+                        anyhow::bail!("${name} can't match both a single node and a sequence");
+                    }
+                    metavariables.insert(placeholder, metavariable);
+                }
+            }
+        }
+
+        Ok(Self {
+            source: source.into(),
+            parseable: parseable.into(),
+            metavariables: Arc::new(metavariables),
+            literals: literals.into(),
+            compiled: Default::default(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns false if the text can't possibly contain a match, without parsing it.
+    pub(crate) fn may_match(&self, text: &str) -> bool {
+        self.literals
+            .iter()
+            .all(|literal| text.contains(literal.as_str()))
+    }
+
+    fn compile(&self, language: &Arc<Language>) -> Option<Arc<CompiledPattern>> {
+        self.compiled
+            .lock()
+            .entry(language.name())
+            .or_insert_with(|| {
+                let grammar = language.grammar()?;
+                let tree = language::with_parser(|parser| {
+                    parser.set_language(&grammar.ts_language).ok()?;
+                    parser.parse(self.parseable.as_bytes(), None)
+                })?;
+                let start = self.parseable.len() - self.parseable.trim_start().len();
+                let end = self.parseable.trim_end().len();
+                let root = tree
+                    .root_node()
+                    .named_descendant_for_byte_range(start, end)?;
+                if root.has_error() || root.is_missing() {
+                    log::debug!(
+                        "structural search pattern {:?} is not valid {}",
+                        self.source,
+                        language.name()
+                    );
+                    return None;
+                }
+                let root_range = root.byte_range();
+                Some(Arc::new(CompiledPattern { tree, root_range }))
+            })
+            .clone()
+    }
+
+    /// Finds the outermost nodes matching the pattern in every syntax layer of the buffer.
+    pub(crate) fn search(
+        &self,
+        buffer: &BufferSnapshot,
+        subrange: Option<Range<usize>>,
+    ) -> Vec<Range<usize>> {
+        let range = subrange.unwrap_or(0..buffer.len());
+        let matcher = Matcher {
+            pattern: self,
+            pattern_text: &self.parseable,
+            buffer,
+        };
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let Some(pattern) = self.compile(layer.language) else {
+                continue;
+            };
+            matcher.find_all(pattern.root(), layer.node(), &range, &mut matches);
+        }
+
+        // Injected layers can overlap their parents, so keep the outermost match.
+        matches.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+        let mut end = 0;
+        matches.retain(|mat| {
+            if mat.start < end {
+                return false;
+            }
+            end = mat.end;
+            true
+        });
+        matches
+            .into_iter()
+            .map(|mat| mat.start - range.start..mat.end - range.start)
+            .collect()
+    }
+
+    /// Expands the replacement template for a match that [`search`](Self::search) found
+    /// in the buffer.
+    ///
+    /// The captures are recovered by matching the pattern against the buffer's syntax
+    /// tree again, so the match is parsed in its context and with the buffer's languages.
+    pub(crate) fn replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+        template: &str,
+    ) -> Option<String> {
+        let matcher = Matcher {
+            pattern: self,
+            pattern_text: &self.parseable,
+            buffer,
+        };
+        let captures = buffer
+            .syntax_layers_for_range(range.clone(), true)
+            .find_map(|layer| {
+                let pattern = self.compile(layer.language)?;
+                let mut node = layer
+                    .node()
+                    .descendant_for_byte_range(range.start, range.end)?;
+                // The pattern's root may be an ancestor of the smallest node spanning
+                // the match, e.g. an expression statement around a call.
+                loop {
+                    if node.byte_range() != range {
+                        return None;
+                    }
+                    let mut captures = Captures::default();
+                    if matcher.match_node(pattern.root(), node, &mut captures) {
+                        return Some(captures);
+                    }
+                    node = node.parent()?;
+                }
+            })?;
+
+        let mut replacement = String::new();
+        for token in tokenize(template) {
+            match token {
+                Token::Text(text) => replacement.push_str(text),
+                Token::Metavariable(metavariable) => match captures.get(&metavariable.name) {
+                    Some(range) => replacement.extend(buffer.text_for_range(range.clone())),
+                    None => {
+                        replacement.push_str(if metavariable.multi { "$$$" } else { "$" });
+                        replacement.push_str(&metavariable.name);
+                    }
+                },
+            }
+        }
+        Some(replacement)
+    }
+}
+
+impl fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StructuralPattern")
+            .field(&self.source)
+            .finish()
+    }
+}
+
+struct Matcher<'a> {
+    pattern: &'a StructuralPattern,
+    pattern_text: &'a str,
+    buffer: &'a BufferSnapshot,
+}
+
+impl Matcher<'_> {
+    /// Compares the buffer's text in the range with the given text, reading
+    /// only that range of the buffer.
+    fn text_eq(&self, range: Range<usize>, text: &str) -> bool {
+        range.len() == text.len()
+            && self
+                .buffer
+                .text_for_range(range)
+                .flat_map(str::bytes)
+                .eq(text.bytes())
+    }
+
+    fn ranges_eq(&self, a: Range<usize>, b: Range<usize>) -> bool {
+        a.len() == b.len()
+            && self
+                .buffer
+                .text_for_range(a)
+                .flat_map(str::bytes)
+                .eq(self.buffer.text_for_range(b).flat_map(str::bytes))
+    }
+
+    fn find_all(
+        &self,
+        pattern: Node,
+        root: Node,
+        range: &Range<usize>,
+        matches: &mut Vec<Range<usize>>,
+    ) {
+        let root_is_metavariable = self.metavariable(pattern).is_some();
+        let mut cursor = root.walk();
+        loop {
+            let node = cursor.node();
+            let node_range = node.byte_range();
+            let mut descend = node_range.start < range.end && node_range.end > range.start;
+            if !node_range.is_empty()
+                && node_range.start >= range.start
+                && node_range.end <= range.end
+                && (root_is_metavariable || node.kind_id() == pattern.kind_id())
+                && self.match_node(pattern, node, &mut Captures::default())
+            {
+                matches.push(node_range);
+                descend = false;
+            }
+
+            if descend && cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn metavariable(&self, pattern: Node) -> Option<&Metavariable> {
+        let text = &self.pattern_text[pattern.byte_range()];
+        let metavariable = self.pattern.metavariables.get(text).or_else(|| {
+            // Sequence metavariables are often wrapped in a statement.
+            let text = text.trim_end().trim_end_matches([';', ',']).trim_end();
+            self.pattern
+                .metavariables
+                .get(text)
+                .filter(|metavariable| metavariable.multi)
+        })?;
+        Some(metavariable)
+    }
+
+    fn bind(
+        &self,
+        metavariable: &Metavariable,
+        range: Range<usize>,
+        captures: &mut Captures,
+    ) -> bool {
+        if !metavariable.captures() {
+            return true;
+        }
+        match captures.get(&metavariable.name) {
+            Some(existing) => self.ranges_eq(existing.clone(), range),
+            None => {
+                captures.insert(metavariable.name.clone(), range);
+                true
+            }
+        }
+    }
+
+    fn match_node(&self, pattern: Node, candidate: Node, captures: &mut Captures) -> bool {
+        if let Some(metavariable) = self.metavariable(pattern) {
+            return self.bind(metavariable, candidate.byte_range(), captures);
+        }
+        if pattern.kind_id() != candidate.kind_id() {
+            return false;
+        }
+        if pattern.child_count() == 0 || candidate.child_count() == 0 {
+            return self.text_eq(
+                candidate.byte_range(),
+                &self.pattern_text[pattern.byte_range()],
+            );
+        }
+
+        let pattern_children = significant_children(pattern);
+        let candidate_children = significant_children(candidate);
+        self.match_sequence(
+            &pattern_children,
+            &candidate_children,
+            candidate.end_byte(),
+            captures,
+            &mut Failures::default(),
+        )
+    }
+
+    /// Matches the pattern nodes against the candidate nodes, which are always
+    /// suffixes of the same children. Failures are remembered, since sequence
+    /// metavariables would otherwise retry the same suffixes exponentially often.
+    fn match_sequence(
+        &self,
+        pattern: &[Node],
+        candidates: &[Node],
+        end: usize,
+        captures: &mut Captures,
+        failures: &mut Failures,
+    ) -> bool {
+        let mut captured = captures
+            .iter()
+            .map(|(name, range)| (name.clone(), range.clone()))
+            .collect::<Vec<_>>();
+        captured.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let key = (pattern.len(), candidates.len(), captured);
+        if failures.contains(&key) {
+            return false;
+        }
+        // Captures are only updated when the sequence matches.
+        let matched = self.match_sequence_uncached(pattern, candidates, end, captures, failures);
+        if !matched {
+            failures.insert(key);
+        }
+        matched
+    }
+
+    fn match_sequence_uncached(
+        &self,
+        pattern: &[Node],
+        candidates: &[Node],
+        end: usize,
+        captures: &mut Captures,
+        failures: &mut Failures,
+    ) -> bool {
+        let Some((first, rest)) = pattern.split_first() else {
+            return candidates.is_empty();
+        };
+
+        if let Some(metavariable) = self.metavariable(*first).filter(|m| m.multi) {
+            let start = candidates.first().map_or(end, |node| node.start_byte());
+            for count in 0..=candidates.len() {
+                let range = if count == 0 {
+                    start..start
+                } else {
+                    start..candidates[count - 1].end_byte()
+                };
+                let mut attempt = captures.clone();
+                if self.bind(metavariable, range, &mut attempt)
+                    && self.match_sequence(rest, &candidates[count..], end, &mut attempt, failures)
+                {
+                    *captures = attempt;
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        // Allow `foo($A, $$$REST)` to match `foo(a)`, by letting the separator
+        // before an empty sequence go unmatched.
+        if !first.is_named()
+            && let Some(next) = rest.first()
+            && self.metavariable(*next).is_some_and(|m| m.multi)
+        {
+            let mut attempt = captures.clone();
+            if self.match_sequence(&rest[1..], candidates, end, &mut attempt, failures) {
+                let start = candidates.first().map_or(end, |node| node.start_byte());
+                let metavariable = self.metavariable(*next).unwrap();
+                if self.bind(metavariable, start..start, &mut attempt) {
+                    *captures = attempt;
+                    return true;
+                }
+            }
+        }
+
+        let Some((candidate, candidate_rest)) = candidates.split_first() else {
+            return false;
+        };
+        let mut attempt = captures.clone();
+        if self.match_node(*first, *candidate, &mut attempt)
+            && self.match_sequence(rest, candidate_rest, end, &mut attempt, failures)
+        {
+            *captures = attempt;
+            return true;
+        }
+        false
+    }
+}
+
+/// The children of a node, without comments and other extras, which shouldn't
+/// affect whether code matches.
+fn significant_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra() && !child.is_missing())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Buffer, rust_lang};
+
+    async fn rust_buffer(text: &str, cx: &mut TestAppContext) -> BufferSnapshot {
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+        cx.executor().run_until_parked();
+        buffer.read_with(cx, |buffer, _| buffer.snapshot())
+    }
+
+    async fn search(pattern: &str, text: &str, cx: &mut TestAppContext) -> Vec<String> {
+        let pattern = StructuralPattern::new(pattern).unwrap();
+        let snapshot = rust_buffer(text, cx).await;
+        pattern
+            .search(&snapshot, None)
+            .into_iter()
+            .map(|range| text[range].to_string())
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_structural_search(cx: &mut TestAppContext) {
+        let text = "fn main() {\n    foo(1, bar(2));\n    foo(x);\n    baz(1, 2);\n    foo(\n        a, // comment\n        b,\n    );\n}\n";
+        assert_eq!(
+            search("foo($A, $B)", text, cx).await,
+            [
+                "foo(1, bar(2))",
+                "foo(\n        a, // comment\n        b,\n    )"
+            ]
+        );
+        assert_eq!(search("foo($$$ARGS)", text, cx).await.len(), 3);
+        assert_eq!(
+            search("$F(1, $_)", text, cx).await,
+            ["foo(1, bar(2))", "baz(1, 2)"]
+        );
+        assert_eq!(search("bar($A)", text, cx).await, ["bar(2)"]);
+
+        let text = "fn f() { let a = x == x; let b = x == y; }";
+        assert_eq!(search("$A == $A", text, cx).await, ["x == x"]);
+
+        // Sequence metavariables don't try every way of splitting the arguments
+        // between them again for each failed attempt.
+        let args = (0..100).map(|ix| ix.to_string()).collect::<Vec<_>>();
+        let text = format!("fn f() {{ foo({}); }}", args.join(", "));
+        assert!(
+            search("foo($$$, $$$, $$$, $$$, $$$, $$$, missing)", &text, cx)
+                .await
+                .is_empty()
+        );
+        assert_eq!(
+            search("foo($$$, 50, $$$, 99)", &text, cx).await,
+            [&text[9..text.len() - 3]]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_structural_replacement(cx: &mut TestAppContext) {
+        let pattern = StructuralPattern::new("foo($A, $$$REST)").unwrap();
+        let text = "fn main() { foo(1, 2, 3); foo(4); }";
+        let snapshot = rust_buffer(text, cx).await;
+        let matches = pattern.search(&snapshot, None);
+        assert_eq!(matches.len(), 2);
+
+        let template = "bar($$$REST).baz($A)";
+        assert_eq!(
+            pattern.replacement_for(&snapshot, matches[0].clone(), template),
+            Some("bar(2, 3).baz(1)".to_string())
+        );
+        assert_eq!(
+            pattern.replacement_for(&snapshot, matches[1].clone(), template),
+            Some("bar().baz(4)".to_string())
+        );
+
+        // A pattern that never searched this buffer, e.g. on a remote client, still
+        // resolves its grammar from the buffer's language.
+        let pattern = StructuralPattern::new("foo($A, $$$REST)").unwrap();
+        assert_eq!(
+            pattern.replacement_for(&snapshot, matches[1].clone(), "$A"),
+            Some("4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pattern_literals() {
+        let pattern = StructuralPattern::new("foo.bar($A, $$$)").unwrap();
+        assert!(pattern.may_match("x.foo.bar(1)"));
+        assert!(!pattern.may_match("x.foo(1)"));
+        assert!(StructuralPattern::new("  ").is_err());
+    }
+}
@@ -4,7 +4,7 @@ use gpui::{App, AppContext as _, Context, Entity, Task, WeakEntity};
 
 use futures::{FutureExt, future::Shared};
 use itertools::Itertools as _;
-use language::LanguageName;
+use language::{LanguageName, LanguageRegistry, Toolchain};
 use remote::RemoteClient;
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
@@ -18,7 +18,7 @@ use terminal::{
 };
 use util::{command::new_std_command, get_default_system_shell, maybe, rel_path::RelPath};
 
-use crate::{Project, ProjectPath};
+use crate::{Project, ProjectPath, toolchain_store::activate_toolchain_in_env};
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
@@ -108,14 +108,18 @@ impl Project {
                         worktree_id,
                         path: Arc::from(RelPath::empty()),
                     }),
-            );
+            )
+            .collect::<Vec<_>>();
+        let env_toolchains = self.environment_toolchains(&project_path_contexts, cx);
         let toolchains = project_path_contexts
+            .into_iter()
             .filter(|_| detect_venv)
             .map(|p| self.active_toolchain(p, LanguageName::new_static("Python"), cx))
             .collect::<Vec<_>>();
         let lang_registry = self.languages.clone();
         cx.spawn(async move |project, cx| {
             let mut env = env_task.await.unwrap_or_default();
+            activate_toolchains_in_env(env_toolchains, &lang_registry, &mut env).await;
             env.extend(settings.env);
 
             let activation_script = maybe!(async {
@@ -308,8 +312,11 @@ impl Project {
                         worktree_id,
                         path: RelPath::empty().into(),
                     }),
-            );
+            )
+            .collect::<Vec<_>>();
+        let env_toolchains = self.environment_toolchains(&project_path_contexts, cx);
         let toolchains = project_path_contexts
+            .into_iter()
             .filter(|_| detect_venv)
             .map(|p| self.active_toolchain(p, LanguageName::new_static("Python"), cx))
             .collect::<Vec<_>>();
@@ -332,6 +339,7 @@ impl Project {
         cx.spawn(async move |project, cx| {
             let shell_kind = ShellKind::new(&shell, is_windows);
             let mut env = env_task.await.unwrap_or_default();
+            activate_toolchains_in_env(env_toolchains, &lang_registry, &mut env).await;
             env.extend(settings.env);
 
             let activation_script = maybe!(async {
@@ -559,6 +567,52 @@ impl Project {
             Task::ready(None).shared()
         }
     }
+
+    /// Looks up the active toolchain of each of [`ENVIRONMENT_TOOLCHAIN_LANGUAGES`], trying
+    /// `project_paths` in order.
+    fn environment_toolchains(
+        &self,
+        project_paths: &[ProjectPath],
+        cx: &App,
+    ) -> Vec<Vec<Task<Option<Toolchain>>>> {
+        ENVIRONMENT_TOOLCHAIN_LANGUAGES
+            .iter()
+            .map(|&language_name| {
+                project_paths
+                    .iter()
+                    .map(|path| {
+                        self.active_toolchain(
+                            path.clone(),
+                            LanguageName::new_static(language_name),
+                            cx,
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Languages whose active toolchains are activated through the environment of terminals and tasks,
+/// rather than through an activation script. Later languages are activated last, so that TypeScript's
+/// Node.js version comes first on `PATH` in a project that also selected one for JavaScript.
+const ENVIRONMENT_TOOLCHAIN_LANGUAGES: [&str; 5] =
+    ["Go", "Rust", "JavaScript", "TSX", "TypeScript"];
+
+async fn activate_toolchains_in_env(
+    toolchains: Vec<Vec<Task<Option<Toolchain>>>>,
+    languages: &LanguageRegistry,
+    env: &mut HashMap<String, String>,
+) {
+    for candidates in toolchains {
+        for toolchain in candidates {
+            let Some(toolchain) = toolchain.await else {
+                continue;
+            };
+            activate_toolchain_in_env(languages, &toolchain, env).await;
+            break;
+        }
+    }
 }
 
 fn create_remote_shell(
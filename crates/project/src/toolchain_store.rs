@@ -3,7 +3,7 @@ use std::{path::PathBuf, str::FromStr, sync::Arc};
 use anyhow::{Context as _, Result, bail};
 
 use async_trait::async_trait;
-use collections::{BTreeMap, IndexSet};
+use collections::{BTreeMap, HashMap, IndexSet};
 use fs::Fs;
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
@@ -438,6 +438,22 @@ impl language::LanguageToolchainStore for RemoteStore {
     }
 }
 
+/// Updates `env` so that processes spawned with it use `toolchain`, as decided by its language's
+/// toolchain lister.
+pub(crate) async fn activate_toolchain_in_env(
+    languages: &LanguageRegistry,
+    toolchain: &Toolchain,
+    env: &mut HashMap<String, String>,
+) {
+    if let Ok(language) = languages
+        .language_for_name(&toolchain.language_name.0)
+        .await
+        && let Some(lister) = language.toolchain_lister()
+    {
+        lister.activate_in_env(toolchain, env);
+    }
+}
+
 pub struct EmptyToolchainStore;
 impl language::LocalLanguageToolchainStore for EmptyToolchainStore {
     fn active_toolchain(
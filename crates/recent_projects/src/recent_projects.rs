@@ -6,7 +6,10 @@ mod ssh_config;
 #[cfg(target_os = "windows")]
 mod wsl_picker;
 
-use remote::RemoteConnectionOptions;
+use anyhow::Context as _;
+use remote::{
+    ContainerRuntime, DevContainerConfig, DockerConnectionOptions, RemoteConnectionOptions,
+};
 pub use remote_connections::{RemoteConnectionModal, connect, open_remote_project};
 
 use disconnected_overlay::DisconnectedOverlay;
@@ -23,7 +26,10 @@ use picker::{
 pub use remote_connections::SshSettings;
 pub use remote_servers::RemoteServerProjects;
 use settings::Settings;
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use ui::{KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*, tooltip_container};
 use util::{ResultExt, paths::PathExt};
 use workspace::{
@@ -31,7 +37,7 @@ use workspace::{
     WORKSPACE_DB, Workspace, WorkspaceId, notifications::DetachAndPromptErr,
     with_active_or_new_workspace,
 };
-use zed_actions::{OpenRecent, OpenRemote};
+use zed_actions::{OpenRecent, OpenRemote, ReopenInContainer};
 
 pub fn init(cx: &mut App) {
     #[cfg(target_os = "windows")]
@@ -160,9 +166,79 @@ pub fn init(cx: &mut App) {
         });
     });
 
+    cx.on_action(|_: &ReopenInContainer, cx| {
+        with_active_or_new_workspace(cx, reopen_in_container);
+    });
+
     cx.observe_new(DisconnectedOverlay::register).detach();
 }
 
+/// Returns the dev container configuration of `local_folder`, if it has one.
+async fn devcontainer_config_path(fs: &dyn project::Fs, local_folder: &Path) -> Option<PathBuf> {
+    for relative_path in remote::DEVCONTAINER_CONFIG_PATHS {
+        let path = local_folder.join(relative_path);
+        if fs.is_file(&path).await {
+            return Some(path);
+        }
+    }
+    None
+}
+
+fn reopen_in_container(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    if !project.is_local() {
+        return;
+    }
+    let Some(local_folder) = project
+        .visible_worktrees(cx)
+        .next()
+        .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+    else {
+        return;
+    };
+    let fs = workspace.app_state().fs.clone();
+    let app_state = workspace.app_state().clone();
+    let open_options = OpenOptions {
+        replace_window: window.window_handle().downcast::<Workspace>(),
+        ..Default::default()
+    };
+
+    cx.spawn_in(window, async move |_, cx| {
+        let config_path = devcontainer_config_path(fs.as_ref(), &local_folder)
+            .await
+            .with_context(|| {
+                format!(
+                    "No dev container configuration found in {}",
+                    local_folder.display()
+                )
+            })?;
+        let config = DevContainerConfig::parse(&fs.load(&config_path).await?)?;
+
+        let runtime = cx
+            .background_spawn(async move { ContainerRuntime::detect() })
+            .await;
+        let paths = vec![PathBuf::from(
+            config.container_workspace_folder(&local_folder),
+        )];
+        open_remote_project(
+            RemoteConnectionOptions::Docker(DockerConnectionOptions::for_devcontainer(
+                local_folder,
+                runtime,
+            )),
+            paths,
+            app_state,
+            open_options,
+            cx,
+        )
+        .await
+    })
+    .detach_and_prompt_err("Failed to reopen in container", window, cx, |_, _, _| None);
+}
+
 #[cfg(target_os = "windows")]
 pub fn add_wsl_distro(
     fs: Arc<dyn project::Fs>,
@@ -219,6 +295,21 @@ impl RecentProjects {
             }
         });
         let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        let local_folder = picker
+            .read(cx)
+            .delegate
+            .workspace
+            .upgrade()
+            .and_then(|workspace| {
+                let workspace = workspace.read(cx);
+                let project = workspace.project().read(cx);
+                if !project.is_local() {
+                    return None;
+                }
+                let worktree = project.visible_worktrees(cx).next()?;
+                let local_folder = worktree.read(cx).abs_path().to_path_buf();
+                Some((workspace.app_state().fs.clone(), local_folder))
+            });
         // We do not want to block the UI on a potentially lengthy call to DB, so we're gonna swap
         // out workspace locations once the future runs to completion.
         cx.spawn_in(window, async move |this, cx| {
@@ -227,9 +318,16 @@ impl RecentProjects {
                 .await
                 .log_err()
                 .unwrap_or_default();
+            let has_devcontainer = match local_folder {
+                Some((fs, local_folder)) => devcontainer_config_path(fs.as_ref(), &local_folder)
+                    .await
+                    .is_some(),
+                None => false,
+            };
             this.update_in(cx, move |this, window, cx| {
                 this.picker.update(cx, move |picker, cx| {
                     picker.delegate.set_workspaces(workspaces);
+                    picker.delegate.has_devcontainer = has_devcontainer;
                     picker.update_matches(picker.query(cx), window, cx)
                 })
             })
@@ -291,6 +389,8 @@ pub struct RecentProjectsDelegate {
     // Flag to reset index when there is a new query vs not reset index when user delete an item
     reset_selected_match_index: bool,
     has_any_non_local_projects: bool,
+    // Whether the current local folder can be reopened in a dev container.
+    has_devcontainer: bool,
     focus_handle: FocusHandle,
 }
 
@@ -310,6 +410,7 @@ impl RecentProjectsDelegate {
             render_paths,
             reset_selected_match_index: true,
             has_any_non_local_projects: false,
+            has_devcontainer: false,
             focus_handle,
         }
     }
@@ -609,6 +710,10 @@ impl PickerDelegate for RecentProjectsDelegate {
                                     Icon::new(match options {
                                         RemoteConnectionOptions::Ssh { .. } => IconName::Server,
                                         RemoteConnectionOptions::Wsl { .. } => IconName::Linux,
+                                        RemoteConnectionOptions::Docker { .. }
+                                        | RemoteConnectionOptions::Command { .. } => {
+                                            IconName::Server
+                                        }
                                     })
                                     .color(Color::Muted)
                                     .into_any_element()
@@ -649,6 +754,15 @@ impl PickerDelegate for RecentProjectsDelegate {
                 .justify_end()
                 .border_t_1()
                 .border_color(cx.theme().colors().border_variant)
+                .when(self.has_devcontainer, |this| {
+                    this.child(
+                        Button::new("container", "Reopen in Container")
+                            .key_binding(KeyBinding::for_action(&ReopenInContainer, cx))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(ReopenInContainer.boxed_clone(), cx)
+                            }),
+                    )
+                })
                 .child(
                     Button::new("remote", "Open Remote Folder")
                         .key_binding(KeyBinding::for_action(
@@ -292,6 +292,12 @@ impl RemoteConnectionModal {
                 (options.connection_string(), options.nickname.clone(), false)
             }
             RemoteConnectionOptions::Wsl(options) => (options.distro_name.clone(), None, true),
+            RemoteConnectionOptions::Docker(options) => {
+                (options.container_name.clone(), None, false)
+            }
+            RemoteConnectionOptions::Command(options) => {
+                (options.command.clone(), options.nickname.clone(), false)
+            }
         };
         Self {
             prompt: cx.new(|cx| {
@@ -671,6 +677,12 @@ pub async fn open_remote_project(
                                 match connection_options {
                                     RemoteConnectionOptions::Ssh(_) => "Failed to connect over SSH",
                                     RemoteConnectionOptions::Wsl(_) => "Failed to connect to WSL",
+                                    RemoteConnectionOptions::Docker(_) => {
+                                        "Failed to connect to container"
+                                    }
+                                    RemoteConnectionOptions::Command(_) => {
+                                        "Failed to connect to remote host"
+                                    }
                                 },
                                 Some(&format!("{e:#}")),
                                 &["Retry", "Cancel"],
@@ -727,6 +739,12 @@ pub async fn open_remote_project(
                             match connection_options {
                                 RemoteConnectionOptions::Ssh(_) => "Failed to connect over SSH",
                                 RemoteConnectionOptions::Wsl(_) => "Failed to connect to WSL",
+                                RemoteConnectionOptions::Docker(_) => {
+                                    "Failed to connect to container"
+                                }
+                                RemoteConnectionOptions::Command(_) => {
+                                    "Failed to connect to remote host"
+                                }
                             },
                             Some(&format!("{e:#}")),
                             &["Retry", "Cancel"],
@@ -134,6 +134,9 @@ enum ProjectPickerData {
     Wsl {
         distro_name: SharedString,
     },
+    Other {
+        display_name: SharedString,
+    },
 }
 
 struct ProjectPicker {
@@ -207,6 +210,11 @@ impl ProjectPicker {
             RemoteConnectionOptions::Wsl(connection) => ProjectPickerData::Wsl {
                 distro_name: connection.distro_name.clone().into(),
             },
+            RemoteConnectionOptions::Docker(_) | RemoteConnectionOptions::Command(_) => {
+                ProjectPickerData::Other {
+                    display_name: connection.display_name().into(),
+                }
+            }
         };
         let _path_task = cx
             .spawn_in(window, {
@@ -358,6 +366,13 @@ impl gpui::Render for ProjectPicker {
                     is_wsl: true,
                 }
                 .render(window, cx),
+                ProjectPickerData::Other { display_name } => SshConnectionHeader {
+                    connection_string: display_name.clone(),
+                    paths: Default::default(),
+                    nickname: None,
+                    is_wsl: false,
+                }
+                .render(window, cx),
             })
             .child(
                 div()
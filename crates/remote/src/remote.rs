@@ -10,5 +10,9 @@ pub use remote_client::{
     ConnectionIdentifier, ConnectionState, RemoteClient, RemoteClientDelegate, RemoteClientEvent,
     RemoteConnection, RemoteConnectionOptions, RemotePlatform, connect,
 };
+pub use transport::command::CommandConnectionOptions;
+pub use transport::docker::{
+    ContainerRuntime, DEVCONTAINER_CONFIG_PATHS, DevContainerConfig, DockerConnectionOptions,
+};
 pub use transport::ssh::{SshConnectionOptions, SshPortForwardOption};
 pub use transport::wsl::WslConnectionOptions;
@@ -3,6 +3,8 @@ use crate::{
     protocol::MessageId,
     proxy::ProxyLaunchError,
     transport::{
+        command::{CommandConnectionOptions, CommandRemoteConnection},
+        docker::{DockerConnectionOptions, DockerRemoteConnection},
         ssh::SshRemoteConnection,
         wsl::{WslConnectionOptions, WslRemoteConnection},
     },
@@ -1042,6 +1044,16 @@ impl ConnectionPool {
                                 .await
                                 .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
                         }
+                        RemoteConnectionOptions::Docker(opts) => {
+                            DockerRemoteConnection::new(opts, delegate, cx)
+                                .await
+                                .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                        }
+                        RemoteConnectionOptions::Command(opts) => {
+                            CommandRemoteConnection::new(opts, delegate, cx)
+                                .await
+                                .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                        }
                     };
 
                     cx.update_global(|pool: &mut Self, _| {
@@ -1077,6 +1089,8 @@ impl ConnectionPool {
 pub enum RemoteConnectionOptions {
     Ssh(SshConnectionOptions),
     Wsl(WslConnectionOptions),
+    Docker(DockerConnectionOptions),
+    Command(CommandConnectionOptions),
 }
 
 impl RemoteConnectionOptions {
@@ -1084,6 +1098,11 @@ impl RemoteConnectionOptions {
         match self {
             RemoteConnectionOptions::Ssh(opts) => opts.host.clone(),
             RemoteConnectionOptions::Wsl(opts) => opts.distro_name.clone(),
+            RemoteConnectionOptions::Docker(opts) => opts.container_name.clone(),
+            RemoteConnectionOptions::Command(opts) => opts
+                .nickname
+                .clone()
+                .unwrap_or_else(|| opts.command.clone()),
         }
     }
 }
@@ -1100,6 +1119,18 @@ impl From<WslConnectionOptions> for RemoteConnectionOptions {
     }
 }
 
+impl From<DockerConnectionOptions> for RemoteConnectionOptions {
+    fn from(opts: DockerConnectionOptions) -> Self {
+        RemoteConnectionOptions::Docker(opts)
+    }
+}
+
+impl From<CommandConnectionOptions> for RemoteConnectionOptions {
+    fn from(opts: CommandConnectionOptions) -> Self {
+        RemoteConnectionOptions::Command(opts)
+    }
+}
+
 #[cfg(target_os = "windows")]
 /// Open a wsl path (\\wsl.localhost\<distro>\path)
 #[derive(Debug, Clone, PartialEq, Eq, gpui::Action)]
@@ -12,6 +12,8 @@ use gpui::{AppContext as _, AsyncApp, Task};
 use rpc::proto::Envelope;
 use smol::process::Child;
 
+pub mod command;
+pub mod docker;
 pub mod ssh;
 pub mod wsl;
 
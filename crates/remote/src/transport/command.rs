@@ -0,0 +1,592 @@
+use crate::{
+    RemoteClientDelegate, RemotePlatform,
+    remote_client::{CommandTemplate, RemoteConnection, RemoteConnectionOptions},
+    transport::{parse_platform, parse_shell},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{
+    AsyncWriteExt as _,
+    channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender},
+    io::BufReader,
+};
+use gpui::{App, AppContext as _, AsyncApp, Task};
+use release_channel::{AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use semver::Version;
+use smol::{fs, process};
+use std::{
+    ffi::OsStr,
+    fmt::Write as _,
+    iter,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Instant,
+};
+use util::{
+    paths::{PathStyle, RemotePathBuf},
+    shell::ShellKind,
+};
+
+/// The placeholder that is replaced with the command to run on the remote host.
+const COMMAND_PLACEHOLDER: &str = "{cmd}";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, schemars::JsonSchema)]
+pub struct CommandConnectionOptions {
+    /// A command that runs `{cmd}` on the remote host with its stdio bridged
+    /// to the local process, e.g. `kubectl exec -i my-pod -- {cmd}`.
+    pub command: String,
+    /// The command used for terminals, when it differs from `command`,
+    /// e.g. `kubectl exec -it my-pod -- {cmd}` to allocate a tty.
+    pub interactive_command: Option<String>,
+    pub nickname: Option<String>,
+}
+
+impl CommandConnectionOptions {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            interactive_command: None,
+            nickname: None,
+        }
+    }
+
+    /// Substitutes the program and its arguments into the command template.
+    ///
+    /// A `{cmd}` argument is replaced by the program and its arguments, while
+    /// `{cmd}` inside of a larger argument (e.g. `bash -lc "cd /src && {cmd}"`)
+    /// is replaced by the quoted command line.
+    fn expand(
+        template: &str,
+        program: &str,
+        args: &[impl AsRef<str>],
+    ) -> Result<(String, Vec<String>)> {
+        let tokens = shlex::split(template)
+            .with_context(|| format!("invalid command template: {template:?}"))?;
+        let command = iter::once(program).chain(args.iter().map(|arg| arg.as_ref()));
+
+        let mut argv = Vec::new();
+        let mut found_placeholder = false;
+        for token in tokens {
+            if token == COMMAND_PLACEHOLDER {
+                argv.extend(command.clone().map(ToOwned::to_owned));
+                found_placeholder = true;
+            } else if token.contains(COMMAND_PLACEHOLDER) {
+                let command_line =
+                    shlex::try_join(command.clone()).context("failed to quote command")?;
+                argv.push(token.replace(COMMAND_PLACEHOLDER, &command_line));
+                found_placeholder = true;
+            } else {
+                argv.push(token);
+            }
+        }
+        anyhow::ensure!(
+            found_placeholder,
+            "command template {template:?} does not contain {COMMAND_PLACEHOLDER}"
+        );
+
+        let mut argv = argv.into_iter();
+        let program = argv.next().context("command template is empty")?;
+        Ok((program, argv.collect()))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CommandRemoteConnection {
+    remote_binary_path: Option<String>,
+    platform: RemotePlatform,
+    shell: String,
+    shell_kind: ShellKind,
+    home_dir: String,
+    connection_options: CommandConnectionOptions,
+}
+
+impl CommandRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: CommandConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        log::info!("Connecting through {:?}", connection_options.command);
+        let (release_channel, version) =
+            cx.update(|cx| (ReleaseChannel::global(cx), AppVersion::global(cx)))?;
+
+        let mut this = Self {
+            connection_options,
+            remote_binary_path: None,
+            platform: RemotePlatform { os: "", arch: "" },
+            shell: String::new(),
+            shell_kind: ShellKind::Posix,
+            home_dir: String::new(),
+        };
+        delegate.set_status(Some("Detecting remote environment"), cx);
+        this.shell = match run_script(&this.connection_options, "echo $SHELL").await {
+            Ok(output) => parse_shell(&output, "sh"),
+            Err(e) => {
+                log::error!("Failed to detect remote shell: {e}");
+                "sh".to_owned()
+            }
+        };
+        log::info!("Remote shell discovered: {}", this.shell);
+        this.shell_kind = ShellKind::new(&this.shell, false);
+        this.home_dir = run_script(&this.connection_options, "echo $HOME")
+            .await
+            .context("failed detecting home directory")?;
+        this.platform = parse_platform(&run_script(&this.connection_options, "uname -sm").await?)
+            .context("failed detecting platform")?;
+        log::info!("Remote platform discovered: {:?}", this.platform);
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, cx)
+                .await
+                .context("failed ensuring server binary")?,
+        );
+        log::debug!("Detected remote environment: {this:#?}");
+
+        Ok(this)
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: Version,
+        cx: &mut AsyncApp,
+    ) -> Result<String> {
+        let version_str = match release_channel {
+            ReleaseChannel::Dev => "build".to_string(),
+            _ => version.to_string(),
+        };
+
+        let binary_name = format!(
+            "zed-remote-server-{}-{}",
+            release_channel.dev_name(),
+            version_str
+        );
+        let dst_path = format!(
+            "{}/{}/{}",
+            self.home_dir,
+            paths::remote_server_dir_relative().display(PathStyle::Posix),
+            binary_name
+        );
+
+        #[cfg(debug_assertions)]
+        if let Some(remote_server_path) =
+            super::build_remote_server_from_source(&self.platform, delegate.as_ref(), cx).await?
+        {
+            self.upload_and_install(&remote_server_path, &dst_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        let version_check = format!("{} version", quote(&dst_path)?);
+        if run_script(&self.connection_options, &version_check)
+            .await
+            .is_ok()
+        {
+            return Ok(dst_path);
+        }
+
+        let wanted_version = match release_channel {
+            ReleaseChannel::Nightly | ReleaseChannel::Dev => None,
+            _ => Some(cx.update(|cx| AppVersion::global(cx))?),
+        };
+
+        let src_path = delegate
+            .download_server_binary_locally(self.platform, release_channel, wanted_version, cx)
+            .await?;
+        self.upload_and_install(&src_path, &dst_path, delegate, cx)
+            .await?;
+
+        Ok(dst_path)
+    }
+
+    /// Streams the (possibly gzipped) binary through the command's stdin and
+    /// moves it into place.
+    async fn upload_and_install(
+        &self,
+        src_path: &Path,
+        dst_path: &str,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Uploading remote server"), cx);
+
+        let t0 = Instant::now();
+        let src_stat = fs::metadata(&src_path)
+            .await
+            .with_context(|| format!("source path does not exist: {}", src_path.display()))?;
+        log::info!(
+            "uploading remote server to {} ({}kb)",
+            dst_path,
+            src_stat.len() / 1024
+        );
+
+        let dst_dir = dst_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let staged = format!("{dst_path}.{}", std::process::id());
+        let decompress = if src_path.extension() == Some(OsStr::new("gz")) {
+            "gunzip -c"
+        } else {
+            "cat"
+        };
+        let script = format!(
+            "set -e; mkdir -p {dir}; {decompress} > {staged}; chmod 755 {staged}; mv -f {staged} {dst}",
+            dir = quote(dst_dir)?,
+            staged = quote(&staged)?,
+            dst = quote(dst_path)?,
+        );
+        upload_file(&self.connection_options, src_path, &script)
+            .await
+            .map_err(|e| anyhow!("Failed to install server binary: {}", e))?;
+
+        log::info!("uploaded remote server in {:?}", t0.elapsed());
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for CommandRemoteConnection {
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = &self.remote_binary_path else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut proxy_args = vec![];
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Ok(value) = std::env::var(env_var) {
+                proxy_args.push(format!("{}={}", env_var, value));
+            }
+        }
+
+        proxy_args.push(remote_binary_path.clone());
+        proxy_args.push("proxy".to_owned());
+        proxy_args.push("--identifier".to_owned());
+        proxy_args.push(unique_identifier);
+
+        if reconnect {
+            proxy_args.push("--reconnect".to_owned());
+        }
+
+        let proxy_process = match command(&self.connection_options.command, "env", &proxy_args)
+            .and_then(|mut command| Ok(command.kill_on_drop(true).spawn()?))
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+            }
+        };
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let options = self.connection_options.clone();
+        cx.background_spawn(async move {
+            let dest = quote(&dest_path.to_string())?;
+            let script = format!("set -e; mkdir -p {dest}; tar -xf - -C {dest}");
+
+            let mut tar = util::command::new_smol_command("tar")
+                .arg("-cf")
+                .arg("-")
+                .arg("-C")
+                .arg(&src_path)
+                .arg(".")
+                .stdout(Stdio::piped())
+                .spawn()
+                .context("failed to spawn tar")?;
+            let mut archive = tar.stdout.take().context("tar has no stdout")?;
+            let result = pipe_into_script(&options, &mut archive, &script).await;
+            tar.status().await?;
+            result.map_err(|e| {
+                anyhow!(
+                    "failed to upload directory {} -> {}: {}",
+                    src_path.display(),
+                    dest_path,
+                    e
+                )
+            })
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        false
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        port_forward: Option<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        if port_forward.is_some() {
+            bail!("Port forwarding is not supported by command connections");
+        }
+
+        let shell_kind = self.shell_kind;
+        let mut exec = String::new();
+        if let Some(working_dir) = working_dir {
+            let working_dir = RemotePathBuf::new(working_dir, PathStyle::Posix).to_string();
+            write!(
+                exec,
+                "cd {} && ",
+                shell_kind
+                    .try_quote(&working_dir)
+                    .context("shell quoting")?
+            )?;
+        }
+
+        exec.push_str("exec env ");
+        for (k, v) in env.iter() {
+            write!(
+                exec,
+                "{}={} ",
+                k,
+                shell_kind.try_quote(v).context("shell quoting")?
+            )?;
+        }
+
+        if let Some(program) = program {
+            write!(
+                exec,
+                "{}",
+                shell_kind
+                    .try_quote_prefix_aware(&program)
+                    .context("shell quoting")?
+            )?;
+            for arg in args {
+                let arg = shell_kind.try_quote(&arg).context("shell quoting")?;
+                write!(exec, " {}", &arg)?;
+            }
+        } else {
+            write!(&mut exec, "{} -l", self.shell)?;
+        }
+
+        let template = self
+            .connection_options
+            .interactive_command
+            .as_ref()
+            .unwrap_or(&self.connection_options.command);
+        let (program, args) =
+            CommandConnectionOptions::expand(template, &self.shell, &["-c", exec.as_str()])?;
+        Ok(CommandTemplate {
+            program,
+            args,
+            env: HashMap::default(),
+        })
+    }
+
+    fn build_forward_ports_command(
+        &self,
+        _: Vec<(u16, String, u16)>,
+    ) -> anyhow::Result<CommandTemplate> {
+        Err(anyhow!(
+            "Port forwarding is not supported by command connections"
+        ))
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Command(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        self.shell.clone()
+    }
+
+    fn default_system_shell(&self) -> String {
+        String::from("/bin/sh")
+    }
+
+    fn has_wsl_interop(&self) -> bool {
+        false
+    }
+}
+
+fn quote(value: &str) -> Result<String> {
+    Ok(ShellKind::Posix
+        .try_quote(value)
+        .context("shell quoting")?
+        .into_owned())
+}
+
+/// Creates a command running `program` through the command template.
+fn command(template: &str, program: &str, args: &[impl AsRef<str>]) -> Result<process::Command> {
+    let (program, args) = CommandConnectionOptions::expand(template, program, args)?;
+    let mut command = util::command::new_smol_command(program);
+    command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    log::debug!("remote command {:?}", command);
+    Ok(command)
+}
+
+/// Runs a POSIX shell script on the remote host, returning its trimmed stdout.
+async fn run_script(options: &CommandConnectionOptions, script: &str) -> Result<String> {
+    let mut command = command(&options.command, "sh", &["-c", script])?;
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .with_context(|| format!("Failed to run command '{:?}'", command))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Command '{:?}' failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Runs a script on the remote host that reads the given local file from stdin.
+async fn upload_file(
+    options: &CommandConnectionOptions,
+    src_path: &Path,
+    script: &str,
+) -> Result<()> {
+    let file = fs::File::open(src_path)
+        .await
+        .with_context(|| format!("failed to open {}", src_path.display()))?;
+    pipe_into_script(options, &mut BufReader::new(file), script).await
+}
+
+async fn pipe_into_script(
+    options: &CommandConnectionOptions,
+    input: &mut (impl futures::AsyncRead + Unpin),
+    script: &str,
+) -> Result<()> {
+    let mut child = command(&options.command, "sh", &["-c", script])?
+        .stdout(Stdio::null())
+        .spawn()
+        .context("failed to spawn remote command")?;
+    let mut stdin = child.stdin.take().context("remote command has no stdin")?;
+
+    // Read stderr while writing the input, so that a command that reports
+    // errors before reading all of its input doesn't block on a full pipe.
+    let write_input = async move {
+        futures::io::copy(input, &mut stdin).await?;
+        stdin.close().await?;
+        anyhow::Ok(())
+    };
+    let (write_result, output) = futures::join!(write_input, child.output());
+    let output = output?;
+    anyhow::ensure!(
+        output.status.success(),
+        "remote command failed: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    write_result.context("failed to write to remote command")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_command_template() {
+        assert_eq!(
+            CommandConnectionOptions::expand(
+                "kubectl exec -i my-pod -- {cmd}",
+                "sh",
+                &["-c", "echo $HOME"]
+            )
+            .unwrap(),
+            (
+                "kubectl".to_owned(),
+                vec![
+                    "exec".to_owned(),
+                    "-i".to_owned(),
+                    "my-pod".to_owned(),
+                    "--".to_owned(),
+                    "sh".to_owned(),
+                    "-c".to_owned(),
+                    "echo $HOME".to_owned(),
+                ]
+            )
+        );
+
+        assert_eq!(
+            CommandConnectionOptions::expand(
+                r#"nix develop --command bash -lc "cd /src && {cmd}""#,
+                "sh",
+                &["-c", "uname -sm"]
+            )
+            .unwrap()
+            .1,
+            vec![
+                "develop",
+                "--command",
+                "bash",
+                "-lc",
+                "cd /src && sh -c 'uname -sm'",
+            ]
+        );
+
+        assert!(CommandConnectionOptions::expand("kubectl exec -i pod", "sh", &["-c"]).is_err());
+        assert!(CommandConnectionOptions::expand("{cmd} 'unterminated", "sh", &["-c"]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_connection_through_local_shell() {
+        let options = CommandConnectionOptions::new("env ZED_COMMAND_TRANSPORT=1 {cmd}");
+        smol::block_on(async {
+            let output = run_script(&options, "echo $ZED_COMMAND_TRANSPORT")
+                .await
+                .unwrap();
+            assert_eq!(output, "1");
+
+            assert!(parse_platform(&run_script(&options, "uname -sm").await.unwrap()).is_ok());
+            assert!(run_script(&options, "exit 3").await.is_err());
+
+            let dir = tempfile::tempdir().unwrap();
+            let src_path = dir.path().join("src");
+            std::fs::write(&src_path, "remote_server").unwrap();
+            let dst_path = dir.path().join("nested/dst");
+            let script = format!(
+                "mkdir -p {}; cat > {}",
+                quote(&dst_path.parent().unwrap().to_string_lossy()).unwrap(),
+                quote(&dst_path.to_string_lossy()).unwrap()
+            );
+            upload_file(&options, &src_path, &script).await.unwrap();
+            assert_eq!(std::fs::read_to_string(&dst_path).unwrap(), "remote_server");
+        });
+    }
+}
@@ -0,0 +1,1070 @@
+use crate::{
+    RemoteClientDelegate, RemotePlatform,
+    remote_client::{CommandTemplate, RemoteConnection, RemoteConnectionOptions},
+    transport::{parse_platform, parse_shell},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use collections::{BTreeMap, HashMap};
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{App, AppContext as _, AsyncApp, Task};
+use release_channel::{AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use semver::Version;
+use serde::Deserialize;
+use sha2::{Digest as _, Sha256};
+use smol::{fs, process};
+use std::{
+    ffi::OsStr,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Instant,
+};
+use util::{
+    paths::{PathStyle, RemotePathBuf},
+    shell::ShellKind,
+};
+
+/// Locations of the dev container configuration, relative to the project folder.
+pub const DEVCONTAINER_CONFIG_PATHS: &[&str] =
+    &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// The command line tool used to manage containers.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn program(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+
+    /// Prefers `docker`, falling back to `podman` when only the latter is installed.
+    pub fn detect() -> Self {
+        if which::which("docker").is_err() && which::which("podman").is_ok() {
+            ContainerRuntime::Podman
+        } else {
+            ContainerRuntime::Docker
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, schemars::JsonSchema)]
+pub struct DockerConnectionOptions {
+    /// The container to attach to. It is created from the dev container
+    /// configuration in `local_folder` when it does not exist yet.
+    pub container_name: String,
+    pub local_folder: Option<PathBuf>,
+    #[serde(default)]
+    pub runtime: ContainerRuntime,
+    pub user: Option<String>,
+}
+
+impl DockerConnectionOptions {
+    /// Options for the dev container of the given local project folder.
+    pub fn for_devcontainer(local_folder: PathBuf, runtime: ContainerRuntime) -> Self {
+        Self {
+            container_name: devcontainer_name(&local_folder),
+            local_folder: Some(local_folder),
+            runtime,
+            user: None,
+        }
+    }
+}
+
+/// Derives a stable container name from the project folder, so that reopening
+/// the same folder attaches to the container created the first time.
+///
+/// The readable part of the name is lossy, so it is followed by a hash of the
+/// full path to keep folders like `/a-b` and `/a_b` apart.
+fn devcontainer_name(local_folder: &Path) -> String {
+    let path = local_folder.to_string_lossy();
+    let path = path.trim_end_matches(['/', '\\']);
+    let mut name = String::from("zed");
+    for c in path.chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_lowercase());
+        } else if !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    let mut name = format!("{}-", name.trim_end_matches('-'));
+    for byte in &Sha256::digest(path.as_bytes())[..4] {
+        write!(name, "{byte:02x}").ok();
+    }
+    name
+}
+
+/// The subset of `devcontainer.json` that Zed understands.
+///
+/// See https://containers.dev/implementors/json_reference/
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainerConfig {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<DevContainerBuild>,
+    /// Legacy spelling of `build.dockerfile`.
+    pub docker_file: Option<String>,
+    #[serde(default)]
+    pub mounts: Vec<DevContainerMount>,
+    #[serde(default)]
+    pub forward_ports: Vec<ForwardPort>,
+    pub post_create_command: Option<LifecycleCommand>,
+    pub workspace_folder: Option<String>,
+    pub workspace_mount: Option<String>,
+    pub remote_user: Option<String>,
+    pub container_user: Option<String>,
+    #[serde(default)]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainerBuild {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    pub target: Option<String>,
+    #[serde(default)]
+    pub args: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum DevContainerMount {
+    /// A `--mount` specification, e.g. `source=cache,target=/cache,type=volume`.
+    Spec(String),
+    Object {
+        source: Option<String>,
+        target: String,
+        #[serde(rename = "type")]
+        kind: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ForwardPort {
+    Port(u16),
+    /// A `host:port` pair, e.g. `localhost:3000`.
+    Address(String),
+}
+
+impl ForwardPort {
+    fn port(&self) -> Option<u16> {
+        match self {
+            ForwardPort::Port(port) => Some(*port),
+            ForwardPort::Address(address) => address.rsplit(':').next()?.parse().ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum LifecycleCommand {
+    /// Run through `/bin/sh -c`.
+    Shell(String),
+    /// Run without a shell.
+    Exec(Vec<String>),
+    /// Named commands, which Zed runs one after another.
+    Parallel(BTreeMap<String, LifecycleCommand>),
+}
+
+impl LifecycleCommand {
+    /// Flattens the command into the argument vectors to execute, in order.
+    fn argvs(&self) -> Vec<Vec<String>> {
+        match self {
+            LifecycleCommand::Shell(command) => {
+                vec![vec!["/bin/sh".into(), "-c".into(), command.clone()]]
+            }
+            LifecycleCommand::Exec(args) if args.is_empty() => Vec::new(),
+            LifecycleCommand::Exec(args) => vec![args.clone()],
+            LifecycleCommand::Parallel(commands) => commands
+                .values()
+                .flat_map(|command| command.argvs())
+                .collect(),
+        }
+    }
+}
+
+impl DevContainerConfig {
+    pub fn parse(content: &str) -> Result<Self> {
+        serde_json_lenient::from_str(content).context("failed to parse devcontainer.json")
+    }
+
+    /// Where the project folder is mounted inside of the container.
+    pub fn container_workspace_folder(&self, local_folder: &Path) -> String {
+        match &self.workspace_folder {
+            Some(folder) => self.substitute(folder, local_folder, ""),
+            None => format!("/workspaces/{}", basename(local_folder)),
+        }
+    }
+
+    /// The user that Zed runs the remote server and terminals as.
+    pub fn user(&self) -> Option<&str> {
+        self.remote_user
+            .as_deref()
+            .or(self.container_user.as_deref())
+    }
+
+    fn dockerfile(&self) -> Option<&str> {
+        self.build
+            .as_ref()
+            .and_then(|build| build.dockerfile.as_deref())
+            .or(self.docker_file.as_deref())
+    }
+
+    fn image_tag(container_name: &str) -> String {
+        format!("{container_name}-image")
+    }
+
+    /// Arguments to build the image, when the configuration uses a Dockerfile.
+    ///
+    /// Paths in the configuration are relative to the directory holding `devcontainer.json`.
+    fn build_args(&self, container_name: &str, config_dir: &Path) -> Option<Vec<String>> {
+        let dockerfile = self.dockerfile()?;
+        let build = self.build.clone().unwrap_or_default();
+        let context = config_dir.join(build.context.as_deref().unwrap_or("."));
+
+        let mut args = vec![
+            "build".to_owned(),
+            "--tag".to_owned(),
+            Self::image_tag(container_name),
+            "--file".to_owned(),
+            config_dir.join(dockerfile).to_string_lossy().into_owned(),
+        ];
+        for (key, value) in &build.args {
+            args.push("--build-arg".to_owned());
+            args.push(format!("{key}={value}"));
+        }
+        if let Some(target) = build.target {
+            args.push("--target".to_owned());
+            args.push(target);
+        }
+        args.push(context.to_string_lossy().into_owned());
+        Some(args)
+    }
+
+    /// Arguments to create the container. It is kept alive with a shell loop, so that
+    /// it keeps running between connections.
+    fn run_args(&self, container_name: &str, image: &str, local_folder: &Path) -> Vec<String> {
+        let workspace_folder = self.container_workspace_folder(local_folder);
+        let substitute = |value: &str| self.substitute(value, local_folder, &workspace_folder);
+
+        let mut args = vec![
+            "run".to_owned(),
+            "--detach".to_owned(),
+            "--name".to_owned(),
+            container_name.to_owned(),
+            "--mount".to_owned(),
+        ];
+        args.push(match &self.workspace_mount {
+            Some(mount) => substitute(mount),
+            None => format!(
+                "type=bind,source={},target={}",
+                local_folder.display(),
+                workspace_folder
+            ),
+        });
+
+        for mount in &self.mounts {
+            args.push("--mount".to_owned());
+            args.push(match mount {
+                DevContainerMount::Spec(spec) => substitute(spec),
+                DevContainerMount::Object {
+                    source,
+                    target,
+                    kind,
+                } => {
+                    let mut spec = format!("type={}", kind.as_deref().unwrap_or("bind"));
+                    if let Some(source) = source {
+                        write!(spec, ",source={}", substitute(source)).ok();
+                    }
+                    write!(spec, ",target={}", substitute(target)).ok();
+                    spec
+                }
+            });
+        }
+
+        for port in self.forward_ports.iter().filter_map(ForwardPort::port) {
+            args.push("--publish".to_owned());
+            args.push(format!("127.0.0.1:{port}:{port}"));
+        }
+
+        for (key, value) in &self.container_env {
+            args.push("--env".to_owned());
+            args.push(format!("{key}={}", substitute(value)));
+        }
+
+        if let Some(user) = &self.container_user {
+            args.push("--user".to_owned());
+            args.push(user.clone());
+        }
+
+        args.extend(self.run_args.iter().map(|arg| substitute(arg)));
+        args.extend([
+            "--entrypoint".to_owned(),
+            "/bin/sh".to_owned(),
+            image.to_owned(),
+            "-c".to_owned(),
+            "while sleep 1000; do :; done".to_owned(),
+        ]);
+        args
+    }
+
+    /// Expands the `${...}` variables supported in `devcontainer.json`.
+    fn substitute(&self, value: &str, local_folder: &Path, container_folder: &str) -> String {
+        let mut result = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                rest = &rest[start..];
+                break;
+            };
+            let variable = &rest[start + 2..start + len];
+            match variable {
+                "localWorkspaceFolder" => result.push_str(&local_folder.to_string_lossy()),
+                "localWorkspaceFolderBasename" => result.push_str(&basename(local_folder)),
+                "containerWorkspaceFolder" => result.push_str(container_folder),
+                "containerWorkspaceFolderBasename" => {
+                    result.push_str(basename(Path::new(container_folder)).as_str())
+                }
+                _ => {
+                    if let Some(env) = variable.strip_prefix("localEnv:") {
+                        let (name, default) = env.split_once(':').unwrap_or((env, ""));
+                        result.push_str(&std::env::var(name).unwrap_or_else(|_| default.into()));
+                    } else {
+                        result.push_str(&rest[start..=start + len]);
+                    }
+                }
+            }
+            rest = &rest[start + len + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+fn basename(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+async fn find_devcontainer_config(local_folder: &Path) -> Result<PathBuf> {
+    for relative_path in DEVCONTAINER_CONFIG_PATHS {
+        let path = local_folder.join(relative_path);
+        if fs::metadata(&path).await.is_ok() {
+            return Ok(path);
+        }
+    }
+    bail!(
+        "no dev container configuration found in {}",
+        local_folder.display()
+    )
+}
+
+#[derive(Debug)]
+pub(crate) struct DockerRemoteConnection {
+    remote_binary_path: Option<String>,
+    platform: RemotePlatform,
+    shell: String,
+    shell_kind: ShellKind,
+    home_dir: String,
+    user: Option<String>,
+    connection_options: DockerConnectionOptions,
+}
+
+impl DockerRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: DockerConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "Connecting to container {} with {}",
+            connection_options.container_name,
+            connection_options.runtime.program()
+        );
+        let (release_channel, version) =
+            cx.update(|cx| (ReleaseChannel::global(cx), AppVersion::global(cx)))?;
+
+        let mut this = Self {
+            user: connection_options.user.clone(),
+            connection_options,
+            remote_binary_path: None,
+            platform: RemotePlatform { os: "", arch: "" },
+            shell: String::new(),
+            shell_kind: ShellKind::Posix,
+            home_dir: String::new(),
+        };
+        this.ensure_container_running(&delegate, cx)
+            .await
+            .context("failed starting container")?;
+
+        delegate.set_status(Some("Detecting container environment"), cx);
+        this.shell = this
+            .detect_shell()
+            .await
+            .context("failed detecting shell")?;
+        log::info!("Remote shell discovered: {}", this.shell);
+        this.shell_kind = ShellKind::new(&this.shell, false);
+        this.home_dir = this
+            .run_container_command("sh", &["-c", "echo $HOME"])
+            .await
+            .context("failed detecting home directory")?;
+        this.platform = this
+            .detect_platform()
+            .await
+            .context("failed detecting platform")?;
+        log::info!("Remote platform discovered: {:?}", this.platform);
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, cx)
+                .await
+                .context("failed ensuring server binary")?,
+        );
+        log::debug!("Detected container environment: {this:#?}");
+
+        Ok(this)
+    }
+
+    async fn ensure_container_running(
+        &mut self,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let runtime = self.connection_options.runtime;
+        let container_name = self.connection_options.container_name.clone();
+
+        delegate.set_status(Some("Looking for container"), cx);
+        let config = match &self.connection_options.local_folder {
+            Some(local_folder) => {
+                let config_path = find_devcontainer_config(local_folder).await?;
+                let content = fs::read_to_string(&config_path)
+                    .await
+                    .with_context(|| format!("failed to read {}", config_path.display()))?;
+                Some((
+                    DevContainerConfig::parse(&content)?,
+                    config_path,
+                    local_folder.clone(),
+                ))
+            }
+            None => None,
+        };
+        if self.user.is_none() {
+            self.user = config
+                .as_ref()
+                .and_then(|(config, _, _)| config.user().map(ToOwned::to_owned));
+        }
+
+        let inspect = [
+            "inspect",
+            "--format",
+            "{{.State.Running}}",
+            container_name.as_str(),
+        ];
+        if let Ok(running) = run_runtime_command(runtime, &inspect).await {
+            if running != "true" {
+                delegate.set_status(Some("Starting container"), cx);
+                run_runtime_command(runtime, &["start", container_name.as_str()]).await?;
+            }
+            if let Some((config, _, local_folder)) = &config {
+                self.run_post_create_command(config, local_folder, delegate, cx)
+                    .await?;
+            }
+            return Ok(());
+        }
+
+        let Some((config, config_path, local_folder)) = config else {
+            bail!("container {container_name} does not exist");
+        };
+        let config_dir = config_path.parent().unwrap_or(local_folder.as_path());
+
+        let image = if let Some(build_args) = config.build_args(&container_name, config_dir) {
+            delegate.set_status(Some("Building container image"), cx);
+            run_runtime_command(runtime, &build_args).await?;
+            DevContainerConfig::image_tag(&container_name)
+        } else {
+            config
+                .image
+                .clone()
+                .context("devcontainer.json specifies neither an image nor a Dockerfile")?
+        };
+
+        delegate.set_status(Some("Creating container"), cx);
+        run_runtime_command(
+            runtime,
+            &config.run_args(&container_name, &image, &local_folder),
+        )
+        .await?;
+
+        self.run_post_create_command(&config, &local_folder, delegate, cx)
+            .await
+    }
+
+    /// Runs `postCreateCommand` unless it already succeeded in this container.
+    ///
+    /// Completion is recorded with a marker file inside the container, so a
+    /// command that failed is retried on the next connection.
+    async fn run_post_create_command(
+        &self,
+        config: &DevContainerConfig,
+        local_folder: &Path,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        const MARKER: &str = "$HOME/.devcontainer/.zedPostCreateCommandMarker";
+
+        let Some(post_create_command) = &config.post_create_command else {
+            return Ok(());
+        };
+        let check_marker = format!("test -e \"{MARKER}\"");
+        if self
+            .run_container_command("sh", &["-c", &check_marker])
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+
+        delegate.set_status(Some("Running postCreateCommand"), cx);
+        let runtime = self.connection_options.runtime;
+        let workspace_folder = config.container_workspace_folder(local_folder);
+        for argv in post_create_command.argvs() {
+            let mut args = self.exec_args(false, Some(workspace_folder.as_str()));
+            args.extend(argv);
+            let output = run_runtime_command(runtime, &args)
+                .await
+                .context("postCreateCommand failed")?;
+            log::info!("postCreateCommand output: {output}");
+        }
+
+        let write_marker = format!("mkdir -p \"$(dirname \"{MARKER}\")\" && touch \"{MARKER}\"");
+        self.run_container_command("sh", &["-c", &write_marker])
+            .await
+            .context("failed to record that postCreateCommand ran")?;
+        Ok(())
+    }
+
+    async fn detect_platform(&self) -> Result<RemotePlatform> {
+        let program = self.shell_kind.prepend_command_prefix("uname");
+        let output = self.run_container_command(&program, &["-sm"]).await?;
+        parse_platform(&output)
+    }
+
+    async fn detect_shell(&self) -> Result<String> {
+        const DEFAULT_SHELL: &str = "sh";
+        match self
+            .run_container_command("sh", &["-c", "echo $SHELL"])
+            .await
+        {
+            Ok(output) => Ok(parse_shell(&output, DEFAULT_SHELL)),
+            Err(e) => {
+                log::error!("Failed to detect remote shell: {e}");
+                Ok(DEFAULT_SHELL.to_owned())
+            }
+        }
+    }
+
+    /// Arguments for `docker exec`, up to and including the container name.
+    fn exec_args(&self, tty: bool, working_dir: Option<&str>) -> Vec<String> {
+        let mut args = vec!["exec".to_owned(), if tty { "-it" } else { "-i" }.to_owned()];
+        if let Some(user) = &self.user {
+            args.push("--user".to_owned());
+            args.push(user.clone());
+        }
+        if let Some(working_dir) = working_dir {
+            args.push("--workdir".to_owned());
+            args.push(working_dir.to_owned());
+        }
+        args.push(self.connection_options.container_name.clone());
+        args
+    }
+
+    async fn run_container_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        let mut exec_args = self.exec_args(false, None);
+        exec_args.push(program.to_owned());
+        exec_args.extend(args.iter().map(|arg| arg.to_string()));
+        run_runtime_command(self.connection_options.runtime, &exec_args).await
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: Version,
+        cx: &mut AsyncApp,
+    ) -> Result<String> {
+        let version_str = match release_channel {
+            ReleaseChannel::Dev => "build".to_string(),
+            _ => version.to_string(),
+        };
+
+        let binary_name = format!(
+            "zed-remote-server-{}-{}",
+            release_channel.dev_name(),
+            version_str
+        );
+        let dst_path = format!(
+            "{}/{}/{}",
+            self.home_dir,
+            paths::remote_server_dir_relative().display(PathStyle::Posix),
+            binary_name
+        );
+
+        #[cfg(debug_assertions)]
+        if let Some(remote_server_path) =
+            super::build_remote_server_from_source(&self.platform, delegate.as_ref(), cx).await?
+        {
+            self.upload_and_install(&remote_server_path, &dst_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        if self
+            .run_container_command(&dst_path, &["version"])
+            .await
+            .is_ok()
+        {
+            return Ok(dst_path);
+        }
+
+        let wanted_version = match release_channel {
+            ReleaseChannel::Nightly | ReleaseChannel::Dev => None,
+            _ => Some(cx.update(|cx| AppVersion::global(cx))?),
+        };
+
+        let src_path = delegate
+            .download_server_binary_locally(self.platform, release_channel, wanted_version, cx)
+            .await?;
+        self.upload_and_install(&src_path, &dst_path, delegate, cx)
+            .await?;
+
+        Ok(dst_path)
+    }
+
+    /// Copies the (possibly gzipped) binary into the container's `/tmp`, then
+    /// moves it into place as the connecting user.
+    async fn upload_and_install(
+        &self,
+        src_path: &Path,
+        dst_path: &str,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Uploading remote server"), cx);
+
+        let t0 = Instant::now();
+        let src_stat = fs::metadata(&src_path)
+            .await
+            .with_context(|| format!("source path does not exist: {}", src_path.display()))?;
+        log::info!(
+            "uploading remote server to container {} ({}kb)",
+            dst_path,
+            src_stat.len() / 1024
+        );
+
+        let compressed = src_path.extension() == Some(OsStr::new("gz"));
+        let tmp_path = format!(
+            "/tmp/zed-remote-server-{}{}",
+            std::process::id(),
+            if compressed { ".gz" } else { "" }
+        );
+        run_runtime_command(
+            self.connection_options.runtime,
+            &[
+                "cp".to_owned(),
+                src_path.to_string_lossy().into_owned(),
+                format!("{}:{}", self.connection_options.container_name, tmp_path),
+            ],
+        )
+        .await
+        .with_context(|| format!("failed to copy {} into container", src_path.display()))?;
+        log::info!("uploaded remote server in {:?}", t0.elapsed());
+
+        delegate.set_status(Some("Extracting remote server"), cx);
+        let quote = |path: &str| -> Result<String> {
+            Ok(ShellKind::Posix
+                .try_quote(path)
+                .context("shell quoting")?
+                .into_owned())
+        };
+        let dst_dir = dst_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let staged = format!("{dst_path}.{}", std::process::id());
+        let script = format!(
+            "set -e; mkdir -p {dir}; {copy} {tmp} > {staged}; chmod 755 {staged}; mv -f {staged} {dst}; rm -f {tmp} || true",
+            dir = quote(dst_dir)?,
+            copy = if compressed { "gunzip -c" } else { "cat" },
+            tmp = quote(&tmp_path)?,
+            staged = quote(&staged)?,
+            dst = quote(dst_path)?,
+        );
+        self.run_container_command("sh", &["-c", &script])
+            .await
+            .map_err(|e| anyhow!("Failed to extract server binary: {}", e))?;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for DockerRemoteConnection {
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = &self.remote_binary_path else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut proxy_args = self.exec_args(false, None);
+        proxy_args.push("env".to_owned());
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Ok(value) = std::env::var(env_var) {
+                proxy_args.push(format!("{}={}", env_var, value));
+            }
+        }
+
+        proxy_args.push(remote_binary_path.clone());
+        proxy_args.push("proxy".to_owned());
+        proxy_args.push("--identifier".to_owned());
+        proxy_args.push(unique_identifier);
+
+        if reconnect {
+            proxy_args.push("--reconnect".to_owned());
+        }
+
+        let proxy_process = match runtime_command(self.connection_options.runtime, &proxy_args)
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+            }
+        };
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let runtime = self.connection_options.runtime;
+        let destination = format!("{}:{}", self.connection_options.container_name, dest_path);
+        cx.background_spawn(async move {
+            run_runtime_command(
+                runtime,
+                &[
+                    "cp".to_owned(),
+                    src_path.to_string_lossy().into_owned(),
+                    destination,
+                ],
+            )
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "failed to upload directory {} -> {}: {}",
+                    src_path.display(),
+                    dest_path,
+                    e
+                )
+            })?;
+            Ok(())
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        false
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        port_forward: Option<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        if port_forward.is_some() {
+            bail!("Container ports are published through forwardPorts in devcontainer.json");
+        }
+
+        let shell_kind = self.shell_kind;
+        let working_dir = working_dir
+            .map(|working_dir| RemotePathBuf::new(working_dir, PathStyle::Posix).to_string());
+
+        let mut docker_args = self.exec_args(program.is_none(), working_dir.as_deref());
+        // The container name must come last, so insert the environment before it.
+        let container_name = docker_args.pop().unwrap_or_default();
+        for (k, v) in env.iter() {
+            docker_args.push("--env".to_owned());
+            docker_args.push(format!("{k}={v}"));
+        }
+        docker_args.push(container_name);
+
+        let mut exec = String::from("exec ");
+        if let Some(program) = program {
+            write!(
+                exec,
+                "{}",
+                shell_kind
+                    .try_quote_prefix_aware(&program)
+                    .context("shell quoting")?
+            )?;
+            for arg in args {
+                let arg = shell_kind.try_quote(&arg).context("shell quoting")?;
+                write!(exec, " {}", &arg)?;
+            }
+        } else {
+            write!(&mut exec, "{} -l", self.shell)?;
+        }
+        docker_args.extend([self.shell.clone(), "-c".to_owned(), exec]);
+
+        Ok(CommandTemplate {
+            program: self.connection_options.runtime.program().to_owned(),
+            args: docker_args,
+            env: HashMap::default(),
+        })
+    }
+
+    fn build_forward_ports_command(
+        &self,
+        _: Vec<(u16, String, u16)>,
+    ) -> anyhow::Result<CommandTemplate> {
+        Err(anyhow!(
+            "Container ports are published through forwardPorts in devcontainer.json"
+        ))
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Docker(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        self.shell.clone()
+    }
+
+    fn default_system_shell(&self) -> String {
+        String::from("/bin/sh")
+    }
+
+    fn has_wsl_interop(&self) -> bool {
+        false
+    }
+}
+
+async fn run_runtime_command(
+    runtime: ContainerRuntime,
+    args: &[impl AsRef<OsStr>],
+) -> Result<String> {
+    let mut command = runtime_command(runtime, args);
+    let output = command
+        .output()
+        .await
+        .with_context(|| format!("Failed to run command '{:?}'", command))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Command '{:?}' failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+fn runtime_command(runtime: ContainerRuntime, args: &[impl AsRef<OsStr>]) -> process::Command {
+    let mut command = util::command::new_smol_command(runtime.program());
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .args(args);
+    log::debug!("{} {:?}", runtime.program(), command);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        // Comments and trailing commas are allowed.
+        {
+            "name": "Rust",
+            "build": {
+                "dockerfile": "Dockerfile",
+                "context": "..",
+                "args": { "VARIANT": "bookworm" },
+            },
+            "mounts": [
+                "source=cargo-cache,target=/usr/local/cargo/registry,type=volume",
+                { "source": "${localEnv:ZED_DEVCONTAINER_TEST_UNSET:/tmp}", "target": "/host-tmp" },
+            ],
+            "forwardPorts": [3000, "localhost:8080"],
+            "postCreateCommand": "cargo fetch",
+            "remoteUser": "vscode",
+            "containerEnv": { "PROJECT": "${containerWorkspaceFolderBasename}" },
+        }
+    "#;
+
+    #[test]
+    fn test_parse_devcontainer_config() {
+        let config = DevContainerConfig::parse(CONFIG).unwrap();
+        assert_eq!(config.name.as_deref(), Some("Rust"));
+        assert_eq!(config.dockerfile(), Some("Dockerfile"));
+        assert_eq!(config.user(), Some("vscode"));
+        assert_eq!(
+            config
+                .forward_ports
+                .iter()
+                .filter_map(ForwardPort::port)
+                .collect::<Vec<_>>(),
+            vec![3000, 8080]
+        );
+        assert_eq!(
+            config.post_create_command.unwrap().argvs(),
+            vec![vec!["/bin/sh", "-c", "cargo fetch"]]
+        );
+
+        let config =
+            DevContainerConfig::parse(r#"{ "image": "alpine", "dockerFile": "x" }"#).unwrap();
+        assert_eq!(config.image.as_deref(), Some("alpine"));
+        assert_eq!(config.dockerfile(), Some("x"));
+
+        let command = LifecycleCommand::Parallel(BTreeMap::from_iter([
+            ("a".to_owned(), LifecycleCommand::Exec(vec!["make".into()])),
+            ("b".to_owned(), LifecycleCommand::Exec(Vec::new())),
+        ]));
+        assert_eq!(command.argvs(), vec![vec!["make"]]);
+    }
+
+    #[test]
+    fn test_devcontainer_container_args() {
+        let config = DevContainerConfig::parse(CONFIG).unwrap();
+        let local_folder = Path::new("/home/me/my-project");
+        assert_eq!(
+            config.container_workspace_folder(local_folder),
+            "/workspaces/my-project"
+        );
+
+        let build_args = config
+            .build_args(
+                "zed-project",
+                Path::new("/home/me/my-project/.devcontainer"),
+            )
+            .unwrap();
+        assert_eq!(
+            build_args,
+            vec![
+                "build",
+                "--tag",
+                "zed-project-image",
+                "--file",
+                "/home/me/my-project/.devcontainer/Dockerfile",
+                "--build-arg",
+                "VARIANT=bookworm",
+                "/home/me/my-project/.devcontainer/..",
+            ]
+        );
+
+        let run_args = config.run_args("zed-project", "zed-project-image", local_folder);
+        assert_eq!(
+            run_args,
+            vec![
+                "run",
+                "--detach",
+                "--name",
+                "zed-project",
+                "--mount",
+                "type=bind,source=/home/me/my-project,target=/workspaces/my-project",
+                "--mount",
+                "source=cargo-cache,target=/usr/local/cargo/registry,type=volume",
+                "--mount",
+                "type=bind,source=/tmp,target=/host-tmp",
+                "--publish",
+                "127.0.0.1:3000:3000",
+                "--publish",
+                "127.0.0.1:8080:8080",
+                "--env",
+                "PROJECT=my-project",
+                "--entrypoint",
+                "/bin/sh",
+                "zed-project-image",
+                "-c",
+                "while sleep 1000; do :; done",
+            ]
+        );
+
+        let config = DevContainerConfig::parse(
+            r#"{ "image": "alpine", "workspaceFolder": "/src/${localWorkspaceFolderBasename}" }"#,
+        )
+        .unwrap();
+        assert_eq!(config.build_args("c", Path::new("/")), None);
+        assert_eq!(
+            config.container_workspace_folder(local_folder),
+            "/src/my-project"
+        );
+    }
+
+    #[test]
+    fn test_devcontainer_name() {
+        let name = devcontainer_name(Path::new("/home/me/My Project"));
+        assert!(name.starts_with("zed-home-me-my-project-"), "{name}");
+        assert_eq!(name.len(), "zed-home-me-my-project-".len() + 8);
+        assert_eq!(name, devcontainer_name(Path::new("/home/me/My Project")));
+
+        assert_eq!(
+            DockerConnectionOptions::for_devcontainer(
+                PathBuf::from("/src/app/"),
+                ContainerRuntime::Podman
+            )
+            .container_name,
+            devcontainer_name(Path::new("/src/app"))
+        );
+        assert_ne!(
+            devcontainer_name(Path::new("/a-b")),
+            devcontainer_name(Path::new("/a_b"))
+        );
+    }
+}
@@ -1,10 +1,14 @@
 mod registrar;
 
 use crate::{
-    FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOption,
-    SearchOptions, SearchSource, SelectAllMatches, SelectNextMatch, SelectPreviousMatch,
-    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
-    search_bar::{ActionButtonState, input_base_styles, render_action_button, render_text_input},
+    CycleSyntaxScope, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
+    SearchOption, SearchOptions, SearchSource, SelectAllMatches, SelectNextMatch,
+    SelectPreviousMatch, ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection,
+    ToggleWholeWord,
+    search_bar::{
+        ActionButtonState, input_base_styles, render_action_button, render_syntax_scope_button,
+        render_text_input,
+    },
 };
 use any_vec::AnyVec;
 use anyhow::Context as _;
@@ -21,7 +25,7 @@ use gpui::{
 };
 use language::{Language, LanguageRegistry};
 use project::{
-    search::SearchQuery,
+    search::{SearchQuery, SyntaxScope},
     search_history::{SearchHistory, SearchHistoryCursor},
 };
 use schemars::JsonSchema;
@@ -114,6 +118,7 @@ pub struct BufferSearchBar {
     search_options: SearchOptions,
     default_options: SearchOptions,
     configured_options: SearchOptions,
+    syntax_scope: SyntaxScope,
     query_error: Option<String>,
     dismissed: bool,
     search_history: SearchHistory,
@@ -148,6 +153,7 @@ impl Render for BufferSearchBar {
             replacement,
             selection,
             find_in_results,
+            syntax_scope,
         } = self.supported_options(cx);
 
         self.query_editor.update(cx, |query_editor, cx| {
@@ -226,6 +232,13 @@ impl Render for BufferSearchBar {
                                 SearchSource::Buffer,
                                 focus_handle.clone(),
                             ))
+                        })
+                        .when(syntax_scope, |div| {
+                            div.child(render_syntax_scope_button(
+                                "buffer-search-bar-syntax-scope",
+                                self.syntax_scope,
+                                focus_handle.clone(),
+                            ))
                         }),
                 )
             });
@@ -444,6 +457,9 @@ impl Render for BufferSearchBar {
             .when(regex, |this| {
                 this.on_action(cx.listener(Self::toggle_regex))
             })
+            .when(syntax_scope, |this| {
+                this.on_action(cx.listener(Self::cycle_syntax_scope))
+            })
             .when(selection, |this| {
                 this.on_action(cx.listener(Self::toggle_selection))
             })
@@ -538,6 +554,15 @@ impl BufferSearchBar {
                 this.toggle_regex(action, window, cx);
             }
         }));
+        registrar.register_handler(ForDeployed(
+            |this, action: &CycleSyntaxScope, window, cx| {
+                if this.supported_options(cx).syntax_scope {
+                    this.cycle_syntax_scope(action, window, cx);
+                } else {
+                    cx.propagate();
+                }
+            },
+        ));
         registrar.register_handler(ForDeployed(|this, action: &ToggleSelection, window, cx| {
             if this.supported_options(cx).selection {
                 this.toggle_selection(action, window, cx);
@@ -666,6 +691,7 @@ impl BufferSearchBar {
             default_options: search_options,
             configured_options: search_options,
             search_options,
+            syntax_scope: SyntaxScope::Any,
             pending_search: None,
             query_error: None,
             dismissed: true,
@@ -1171,6 +1197,30 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, window, cx)
     }
 
+    pub fn syntax_scope(&self) -> SyntaxScope {
+        self.syntax_scope
+    }
+
+    pub fn set_syntax_scope(
+        &mut self,
+        syntax_scope: SyntaxScope,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.syntax_scope = syntax_scope;
+        drop(self.update_matches(false, false, window, cx));
+        cx.notify();
+    }
+
+    fn cycle_syntax_scope(
+        &mut self,
+        _: &CycleSyntaxScope,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_syntax_scope(self.syntax_scope.next(), window, cx);
+    }
+
     fn clear_active_searchable_item_matches(&mut self, window: &mut Window, cx: &mut App) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -1211,6 +1261,11 @@ impl BufferSearchBar {
     ) -> oneshot::Receiver<()> {
         let (done_tx, done_rx) = oneshot::channel();
         let query = self.query(cx);
+        let syntax_scope = if self.supported_options(cx).syntax_scope {
+            self.syntax_scope
+        } else {
+            SyntaxScope::Any
+        };
         self.pending_search.take();
 
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
@@ -1240,7 +1295,9 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
-                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_scope(syntax_scope),
                             Err(e) => {
                                 self.query_error = Some(e.to_string());
                                 self.clear_active_searchable_item_matches(window, cx);
@@ -1259,7 +1316,9 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
-                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_scope(syntax_scope),
                             Err(e) => {
                                 self.query_error = Some(e.to_string());
                                 self.clear_active_searchable_item_matches(window, cx);
@@ -2915,6 +2974,64 @@ mod tests {
         });
     }
 
+    #[perf]
+    #[gpui::test]
+    async fn test_search_syntax_scope(cx: &mut TestAppContext) {
+        init_globals(cx);
+        let buffer = cx.new(|cx| {
+            Buffer::local("// foo\nfn foo() { let s = \"foo\"; }", cx)
+                .with_language(language::rust_lang(), cx)
+        });
+        let window = cx.add_window(|_, _| gpui::Empty);
+        let editor = window.build_entity(cx, |window, cx| {
+            Editor::for_buffer(buffer.clone(), None, window, cx)
+        });
+        let search_bar = window.build_entity(cx, |window, cx| {
+            let mut search_bar = BufferSearchBar::new(None, window, cx);
+            search_bar.set_active_pane_item(Some(&editor), window, cx);
+            search_bar.show(window, cx);
+            search_bar
+        });
+        let cx = VisualTestContext::from_window(*window, cx).into_mut();
+        cx.run_until_parked();
+
+        search_bar
+            .update_in(cx, |search_bar, window, cx| {
+                search_bar.search("foo", None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        editor.update_in(cx, |editor, window, cx| {
+            assert_eq!(
+                display_points_of(editor.all_text_background_highlights(window, cx)),
+                &[
+                    DisplayPoint::new(DisplayRow(0), 3)..DisplayPoint::new(DisplayRow(0), 6),
+                    DisplayPoint::new(DisplayRow(1), 3)..DisplayPoint::new(DisplayRow(1), 6),
+                    DisplayPoint::new(DisplayRow(1), 20)..DisplayPoint::new(DisplayRow(1), 23),
+                ]
+            );
+        });
+
+        for (syntax_scope, expected_row, expected_column) in [
+            (SyntaxScope::Code, 1, 3),
+            (SyntaxScope::Comments, 0, 3),
+            (SyntaxScope::Strings, 1, 20),
+        ] {
+            search_bar.update_in(cx, |search_bar, window, cx| {
+                search_bar.set_syntax_scope(syntax_scope, window, cx);
+            });
+            cx.run_until_parked();
+            editor.update_in(cx, |editor, window, cx| {
+                assert_eq!(
+                    display_points_of(editor.all_text_background_highlights(window, cx)),
+                    &[DisplayPoint::new(DisplayRow(expected_row), expected_column)
+                        ..DisplayPoint::new(DisplayRow(expected_row), expected_column + 3)],
+                    "unexpected matches for {syntax_scope:?}"
+                );
+            });
+        }
+    }
+
     #[perf]
     #[gpui::test]
     async fn test_search_options_changes(cx: &mut TestAppContext) {
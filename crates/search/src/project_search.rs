@@ -1,9 +1,13 @@
 use crate::{
-    BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
-    SearchOption, SearchOptions, SearchSource, SelectNextMatch, SelectPreviousMatch,
-    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleWholeWord,
+    BufferSearchBar, CycleSyntaxScope, FocusSearch, NextHistoryQuery, PreviousHistoryQuery,
+    ReplaceAll, ReplaceNext, SearchOption, SearchOptions, SearchSource, SelectNextMatch,
+    SelectPreviousMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace,
+    ToggleStructural, ToggleWholeWord,
     buffer_search::Deploy,
-    search_bar::{ActionButtonState, input_base_styles, render_action_button, render_text_input},
+    search_bar::{
+        ActionButtonState, input_base_styles, render_action_button, render_syntax_scope_button,
+        render_text_input,
+    },
 };
 use anyhow::Context as _;
 use collections::HashMap;
@@ -26,7 +30,7 @@ use language::{Buffer, Language};
 use menu::Confirm;
 use project::{
     Project, ProjectPath,
-    search::{SearchInputKind, SearchQuery},
+    search::{SearchInputKind, SearchQuery, SyntaxScope},
     search_history::SearchHistoryCursor,
 };
 use settings::Settings;
@@ -45,6 +49,7 @@ use workspace::{
     item::{BreadcrumbText, Item, ItemEvent, ItemHandle, SaveOptions},
     searchable::{Direction, SearchableItem, SearchableItemHandle},
 };
+use zed_actions::git::SearchHistory;
 
 actions!(
     project_search,
@@ -97,6 +102,18 @@ pub fn init(cx: &mut App) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, window, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, window, cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleStructural, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, action: &CycleSyntaxScope, window, cx| {
+                search_bar.cycle_syntax_scope(action, window, cx);
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &ToggleReplace, window, cx| {
@@ -225,6 +242,7 @@ pub struct ProjectSearchView {
     replacement_editor: Entity<Editor>,
     results_editor: Entity<Editor>,
     search_options: SearchOptions,
+    syntax_scope: SyntaxScope,
     panels_with_errors: HashMap<InputPanel, String>,
     active_match_index: Option<usize>,
     search_id: usize,
@@ -734,6 +752,14 @@ impl ProjectSearchView {
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut Context<Self>) {
         self.search_options.toggle(option);
+        // A query is either a regex or a syntax pattern, never both.
+        if self.search_options.contains(option) {
+            if option.contains(SearchOptions::REGEX) {
+                self.search_options.remove(SearchOptions::STRUCTURAL);
+            } else if option.contains(SearchOptions::STRUCTURAL) {
+                self.search_options.remove(SearchOptions::REGEX);
+            }
+        }
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.entity.read(cx).project.downgrade(),
@@ -743,6 +769,10 @@ impl ProjectSearchView {
         self.adjust_query_regex_language(cx);
     }
 
+    fn cycle_syntax_scope(&mut self) {
+        self.syntax_scope = self.syntax_scope.next();
+    }
+
     fn toggle_opened_only(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
         self.included_opened_only = !self.included_opened_only;
     }
@@ -988,6 +1018,7 @@ impl ProjectSearchView {
             query_editor,
             results_editor,
             search_options: options,
+            syntax_scope: SyntaxScope::Any,
             panels_with_errors: HashMap::default(),
             active_match_index: None,
             included_files_editor,
@@ -1067,6 +1098,7 @@ impl ProjectSearchView {
                         editor.set_text(old_query.as_str(), window, cx);
                     });
                     search_view.search_options = SearchOptions::from_query(&old_query);
+                    search_view.syntax_scope = old_query.syntax_scope();
                     search_view.adjust_query_regex_language(cx);
                 }
                 new_query
@@ -1251,6 +1283,69 @@ impl ProjectSearchView {
         self.query_editor.read(cx).text(cx)
     }
 
+    /// The current query as a search through the git history of the project, for
+    /// commits adding or removing the query in the included files.
+    fn history_search(&self, cx: &App) -> SearchHistory {
+        let paths = if self.filters_enabled {
+            self.included_paths_in_repository(cx)
+        } else {
+            Vec::new()
+        };
+        SearchHistory {
+            query: self.search_query_text(cx),
+            regex: self.search_options.contains(SearchOptions::REGEX),
+            case_sensitive: self.search_options.contains(SearchOptions::CASE_SENSITIVE),
+            paths,
+        }
+    }
+
+    /// Translates the included files, which are relative to the worktrees and may start
+    /// with a worktree's name, into paths relative to the active repository.
+    fn included_paths_in_repository(&self, cx: &App) -> Vec<String> {
+        let project = self.entity.read(cx).project.read(cx);
+        let Some(repository) = project.active_repository(cx) else {
+            return Vec::new();
+        };
+        let repository_path = repository.read(cx).work_directory_abs_path.clone();
+        let worktrees = project.visible_worktrees(cx).collect::<Vec<_>>();
+        let included_files = self.included_files_editor.read(cx).text(cx);
+
+        let mut paths = Vec::new();
+        for entry in included_files
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            for worktree in &worktrees {
+                let worktree = worktree.read(cx);
+                let relative_path = entry
+                    .strip_prefix(worktree.root_name().as_unix_str())
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .unwrap_or(entry);
+                let Ok(path) = worktree
+                    .abs_path()
+                    .join(relative_path)
+                    .strip_prefix(&repository_path)
+                    .map(|path| {
+                        path.components()
+                            .map(|component| component.as_os_str().to_string_lossy())
+                            .join("/")
+                    })
+                else {
+                    continue;
+                };
+                // The whole repository is included.
+                if path.is_empty() {
+                    return Vec::new();
+                }
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+
     fn build_search_query(
         &mut self,
         cx: &mut Context<Self>,
@@ -1322,7 +1417,35 @@ impl ProjectSearchView {
             .count()
             > 1;
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+                match_full_paths,
+                open_buffers,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error.is_some() {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(e) => {
+                    let should_mark_error = self
+                        .panels_with_errors
+                        .insert(InputPanel::Query, e.to_string());
+                    if should_mark_error.is_none() {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1391,7 +1514,7 @@ impl ProjectSearchView {
         if query.as_ref().is_some_and(|query| query.is_empty()) {
             return None;
         }
-        query
+        query.map(|query| query.with_syntax_scope(self.syntax_scope))
     }
 
     fn open_buffers(&self, cx: &App, workspace: &Workspace) -> Vec<Entity<Buffer>> {
@@ -1822,6 +1945,40 @@ impl ProjectSearchBar {
         true
     }
 
+    fn cycle_syntax_scope(
+        &mut self,
+        _: &CycleSyntaxScope,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.active_project_search.is_none() {
+            return;
+        }
+
+        cx.spawn_in(window, async move |this, cx| {
+            let task = this.update_in(cx, |this, window, cx| {
+                let search_view = this.active_project_search.as_ref()?;
+                search_view.update(cx, |search_view, cx| {
+                    search_view.cycle_syntax_scope();
+                    search_view
+                        .entity
+                        .read(cx)
+                        .active_query
+                        .is_some()
+                        .then(|| search_view.prompt_to_save_if_dirty_then_search(window, cx))
+                })
+            })?;
+            if let Some(task) = task {
+                task.await?;
+            }
+            this.update(cx, |_, cx| {
+                cx.notify();
+            })?;
+            anyhow::Ok(())
+        })
+        .detach();
+    }
+
     fn toggle_replace(&mut self, _: &ToggleReplace, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(search) = &self.active_project_search {
             search.update(cx, |this, cx| {
@@ -2101,6 +2258,16 @@ impl Render for ProjectSearchBar {
                         search.search_options,
                         SearchSource::Project(cx),
                         focus_handle.clone(),
+                    ))
+                    .child(SearchOption::Structural.as_button(
+                        search.search_options,
+                        SearchSource::Project(cx),
+                        focus_handle.clone(),
+                    ))
+                    .child(render_syntax_scope_button(
+                        "project-search-syntax-scope",
+                        search.syntax_scope,
+                        focus_handle.clone(),
                     )),
             );
 
@@ -2191,6 +2358,18 @@ impl Render for ProjectSearchBar {
                 &ToggleReplace,
                 focus_handle.clone(),
             ))
+            .child(
+                IconButton::new("project-search-history-button", IconName::HistoryRerun)
+                    .shape(IconButtonShape::Square)
+                    .disabled(search.search_query_text(cx).is_empty())
+                    .tooltip(Tooltip::text("Search Git History"))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(search) = this.active_project_search.as_ref() {
+                            let action = search.read(cx).history_search(cx);
+                            window.dispatch_action(Box::new(action), cx);
+                        }
+                    })),
+            )
             .child(matches_column);
 
         let search_line = h_flex()
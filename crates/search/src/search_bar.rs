@@ -1,5 +1,7 @@
+use crate::CycleSyntaxScope;
 use editor::{Editor, EditorElement, EditorStyle};
 use gpui::{Action, Entity, FocusHandle, Hsla, IntoElement, TextStyle};
+use project::search::SyntaxScope;
 use settings::Settings;
 use theme::ThemeSettings;
 use ui::{IconButton, IconButtonShape};
@@ -39,6 +41,21 @@ pub(super) fn render_action_button(
     })
 }
 
+pub(super) fn render_syntax_scope_button(
+    id_prefix: &'static str,
+    syntax_scope: SyntaxScope,
+    focus_handle: FocusHandle,
+) -> impl IntoElement {
+    render_action_button(
+        id_prefix,
+        ui::IconName::ListFilter,
+        (syntax_scope != SyntaxScope::Any).then_some(ActionButtonState::Toggled),
+        syntax_scope.label(),
+        &CycleSyntaxScope,
+        focus_handle,
+    )
+}
+
 pub(crate) fn input_base_styles(border_color: Hsla, map: impl FnOnce(Div) -> Div) -> Div {
     h_flex()
         .map(map)
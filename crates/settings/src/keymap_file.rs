@@ -10,7 +10,7 @@ use schemars::{JsonSchema, json_schema};
 use serde::Deserialize;
 use serde_json::{Value, json};
 use std::borrow::Cow;
-use std::{any::TypeId, fmt::Write, rc::Rc, sync::Arc, sync::LazyLock};
+use std::{any::TypeId, fmt::Write, ops::Range, rc::Rc, sync::Arc, sync::LazyLock};
 use util::ResultExt as _;
 use util::{
     asset_str,
@@ -21,7 +21,7 @@ use util::{
 use crate::SettingsAssets;
 use settings_json::{
     append_top_level_array_value_in_json_text, parse_json_with_comments,
-    replace_top_level_array_value_in_json_text,
+    replace_top_level_array_value_in_json_text, top_level_array_value_key_range,
 };
 
 pub trait KeyBindingValidator: Send + Sync {
@@ -627,6 +627,20 @@ impl KeymapFile {
         self.0.iter()
     }
 
+    /// Returns the byte range of the keystrokes of the binding described by `target` within the
+    /// given keymap file contents, if the binding is defined there.
+    pub fn binding_keystrokes_range(
+        keymap_contents: &str,
+        target: &KeybindUpdateTarget<'_>,
+        keyboard_mapper: &dyn gpui::PlatformKeyboardMapper,
+    ) -> Option<Range<usize>> {
+        let keymap = Self::parse(keymap_contents).ok()?;
+        let target_action_value = target.action_value().ok()?;
+        let (index, keystrokes_str) =
+            find_binding(&keymap, target, &target_action_value, keyboard_mapper)?;
+        top_level_array_value_key_range(keymap_contents, index, &["bindings", keystrokes_str])
+    }
+
     pub async fn load_keymap_file(fs: &Arc<dyn Fs>) -> Result<String> {
         match fs.load(paths::keymap_file()).await {
             result @ Ok(_) => result,
@@ -832,57 +846,56 @@ impl KeymapFile {
             );
             keymap_contents.replace_range(replace_range, &replace_value);
         }
-        return Ok(keymap_contents);
-
-        fn find_binding<'a, 'b>(
-            keymap: &'b KeymapFile,
-            target: &KeybindUpdateTarget<'a>,
-            target_action_value: &Value,
-            keyboard_mapper: &dyn gpui::PlatformKeyboardMapper,
-        ) -> Option<(usize, &'b str)> {
-            let target_context_parsed =
-                KeyBindingContextPredicate::parse(target.context.unwrap_or("")).ok();
-            for (index, section) in keymap.sections().enumerate() {
-                let section_context_parsed =
-                    KeyBindingContextPredicate::parse(&section.context).ok();
-                if section_context_parsed != target_context_parsed {
-                    continue;
-                }
-                let Some(bindings) = &section.bindings else {
-                    continue;
-                };
-                for (keystrokes_str, action) in bindings {
-                    let Ok(keystrokes) = keystrokes_str
-                        .split_whitespace()
-                        .map(|source| {
-                            let keystroke = Keystroke::parse(source)?;
-                            Ok(KeybindingKeystroke::new_with_mapper(
-                                keystroke,
-                                false,
-                                keyboard_mapper,
-                            ))
-                        })
-                        .collect::<Result<Vec<_>, InvalidKeystrokeError>>()
-                    else {
-                        continue;
-                    };
-                    if keystrokes.len() != target.keystrokes.len()
-                        || !keystrokes
-                            .iter()
-                            .zip(target.keystrokes)
-                            .all(|(a, b)| a.inner().should_match(b))
-                    {
-                        continue;
-                    }
-                    if &action.0 != target_action_value {
-                        continue;
-                    }
-                    return Some((index, keystrokes_str));
-                }
+        Ok(keymap_contents)
+    }
+}
+
+fn find_binding<'a, 'b>(
+    keymap: &'b KeymapFile,
+    target: &KeybindUpdateTarget<'a>,
+    target_action_value: &Value,
+    keyboard_mapper: &dyn gpui::PlatformKeyboardMapper,
+) -> Option<(usize, &'b str)> {
+    let target_context_parsed =
+        KeyBindingContextPredicate::parse(target.context.unwrap_or("")).ok();
+    for (index, section) in keymap.sections().enumerate() {
+        let section_context_parsed = KeyBindingContextPredicate::parse(&section.context).ok();
+        if section_context_parsed != target_context_parsed {
+            continue;
+        }
+        let Some(bindings) = &section.bindings else {
+            continue;
+        };
+        for (keystrokes_str, action) in bindings {
+            let Ok(keystrokes) = keystrokes_str
+                .split_whitespace()
+                .map(|source| {
+                    let keystroke = Keystroke::parse(source)?;
+                    Ok(KeybindingKeystroke::new_with_mapper(
+                        keystroke,
+                        false,
+                        keyboard_mapper,
+                    ))
+                })
+                .collect::<Result<Vec<_>, InvalidKeystrokeError>>()
+            else {
+                continue;
+            };
+            if keystrokes.len() != target.keystrokes.len()
+                || !keystrokes
+                    .iter()
+                    .zip(target.keystrokes)
+                    .all(|(a, b)| a.inner().should_match(b))
+            {
+                continue;
+            }
+            if &action.0 != target_action_value {
+                continue;
             }
-            None
+            return Some((index, keystrokes_str));
         }
     }
+    None
 }
 
 #[derive(Clone, Debug)]
@@ -1174,7 +1187,7 @@ impl Action for ActionSequence {
 
 #[cfg(test)]
 mod tests {
-    use gpui::{DummyKeyboardMapper, KeybindingKeystroke, Keystroke};
+    use gpui::{Action as _, DummyKeyboardMapper, KeybindingKeystroke, Keystroke};
     use unindent::Unindent;
 
     use crate::{
@@ -1968,4 +1981,73 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn binding_keystrokes_range() {
+        let keymap = r#"
+            [
+              {
+                "bindings": {
+                  "ctrl-a": "zed::SomeAction",
+                }
+              },
+              {
+                "context": "Editor",
+                "bindings": {
+                  "ctrl-a": ["zed::SomeAction", { "foo": true }],
+                  "ctrl-k ctrl-b": null,
+                }
+              },
+            ]
+            "#
+        .unindent();
+
+        #[track_caller]
+        fn check(keymap: &str, target: KeybindUpdateTarget, expected_line: Option<usize>) {
+            let line = KeymapFile::binding_keystrokes_range(keymap, &target, &DummyKeyboardMapper)
+                .map(|range| keymap[..range.start].matches('\n').count());
+            assert_eq!(line, expected_line);
+        }
+
+        check(
+            &keymap,
+            KeybindUpdateTarget {
+                context: None,
+                keystrokes: &parse_keystrokes("ctrl-a"),
+                action_name: "zed::SomeAction",
+                action_arguments: None,
+            },
+            Some(3),
+        );
+        check(
+            &keymap,
+            KeybindUpdateTarget {
+                context: Some("Editor"),
+                keystrokes: &parse_keystrokes("ctrl-a"),
+                action_name: "zed::SomeAction",
+                action_arguments: Some(r#"{"foo": true}"#),
+            },
+            Some(9),
+        );
+        check(
+            &keymap,
+            KeybindUpdateTarget {
+                context: Some("Editor"),
+                keystrokes: &parse_keystrokes("ctrl-k ctrl-b"),
+                action_name: gpui::NoAction.name(),
+                action_arguments: None,
+            },
+            Some(10),
+        );
+        check(
+            &keymap,
+            KeybindUpdateTarget {
+                context: Some("Workspace"),
+                keystrokes: &parse_keystrokes("ctrl-a"),
+                action_name: "zed::SomeAction",
+                action_arguments: None,
+            },
+            None,
+        );
+    }
 }
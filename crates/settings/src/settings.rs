@@ -6,8 +6,10 @@ pub mod merge_from;
 mod serde_helper;
 mod settings_content;
 mod settings_file;
+mod settings_profile;
 mod settings_store;
 mod vscode_import;
+mod vscode_keymap_import;
 
 pub use settings_content::*;
 pub use settings_macros::RegisterSetting;
@@ -32,12 +34,17 @@ pub use keymap_file::{
 pub use serde_helper::*;
 pub use settings_file::*;
 pub use settings_json::*;
+pub use settings_profile::SettingsProfileContext;
 pub use settings_store::{
     InvalidSettingsError, LocalSettingsKind, MigrationStatus, ParseStatus, Settings, SettingsFile,
-    SettingsJsonSchemaParams, SettingsKey, SettingsLocation, SettingsParseResult, SettingsStore,
+    SettingsJsonSchemaParams, SettingsKey, SettingsLocation, SettingsParseResult, SettingsSource,
+    SettingsStore,
 };
 
 pub use vscode_import::{VsCodeSettings, VsCodeSettingsSource};
+pub use vscode_keymap_import::{
+    ImportedKeybinding, SkippedKeybinding, VsCodeKeybinding, VsCodeKeymap,
+};
 
 pub use keymap_file::ActionSequence;
 
@@ -90,6 +97,7 @@ pub fn init(cx: &mut App) {
     let settings = SettingsStore::new(cx, &default_settings());
     cx.set_global(settings);
     SettingsStore::observe_active_settings_profile_name(cx).detach();
+    SettingsStore::observe_settings_profile_context(cx).detach();
 }
 
 pub fn default_settings() -> Cow<'static, str> {
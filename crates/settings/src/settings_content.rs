@@ -26,7 +26,7 @@ use serde::{Deserialize, Serialize};
 use settings_macros::{MergeFrom, with_fallible_options};
 use std::collections::BTreeSet;
 use std::env;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 pub use util::serde::default_true;
 
 use crate::{ActiveSettingsProfileName, merge_from};
@@ -137,6 +137,9 @@ pub struct SettingsContent {
 
     /// Configuration for session-related features
     pub session: Option<SessionSettingsContent>,
+
+    /// Configuration for syncing user settings between machines.
+    pub settings_sync: Option<SettingsSyncSettingsContent>,
     /// Control what info is collected by Zed.
     pub telemetry: Option<TelemetrySettingsContent>,
 
@@ -185,6 +188,67 @@ pub struct UserSettingsContent {
 
     #[serde(default)]
     pub profiles: IndexMap<String, SettingsContent>,
+
+    /// Conditions under which settings profiles are applied automatically,
+    /// keyed by profile name.
+    #[serde(default)]
+    pub profile_triggers: IndexMap<String, SettingsProfileTriggerContent>,
+
+    /// Settings that only apply on a specific machine, keyed by the
+    /// machine's name.
+    ///
+    /// This lets a single settings file be shared between machines, for
+    /// example with settings sync.
+    #[serde(default)]
+    pub machines: IndexMap<String, SettingsContent>,
+}
+
+/// The conditions under which a settings profile is applied automatically.
+///
+/// Every condition that's set must hold for the profile to be applied.
+#[with_fallible_options]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct SettingsProfileTriggerContent {
+    /// Apply the profile when one of the open worktrees matches one of these
+    /// globs, e.g. `~/work/**`.
+    pub worktrees: Option<Vec<String>>,
+    /// Apply the profile when the active buffer is written in one of these
+    /// languages.
+    pub languages: Option<Vec<String>>,
+    /// Apply the profile while you're sharing your screen in a call.
+    pub screen_sharing: Option<bool>,
+    /// Apply the profile when the system appearance is light or dark.
+    pub appearance: Option<ProfileAppearanceContent>,
+    /// Apply the profile during a time of day.
+    pub time: Option<TimeOfDayRangeContent>,
+}
+
+/// The system appearance a settings profile is applied for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileAppearanceContent {
+    Light,
+    Dark,
+}
+
+/// A range of local time, as `HH:MM` strings. The range wraps past midnight
+/// when `to` is earlier than `from`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct TimeOfDayRangeContent {
+    pub from: String,
+    pub to: String,
+}
+
+/// Returns the name of this machine, used to select its section of the
+/// `machines` user settings.
+///
+/// This is the hostname, unless overridden with `ZED_MACHINE_NAME`.
+pub fn machine_name() -> Option<String> {
+    static HOSTNAME: LazyLock<Option<String>> = LazyLock::new(util::hostname);
+    env::var("ZED_MACHINE_NAME")
+        .ok()
+        .filter(|name| !name.is_empty())
+        .or_else(|| HOSTNAME.clone())
 }
 
 pub struct ExtensionsSettingsContent {
@@ -210,6 +274,10 @@ impl UserSettingsContent {
         }
     }
 
+    pub fn for_machine(&self, machine_name: Option<&str>) -> Option<&SettingsContent> {
+        self.machines.get(machine_name?)
+    }
+
     pub fn for_profile(&self, cx: &App) -> Option<&SettingsContent> {
         let Some(active_profile) = cx.try_global::<ActiveSettingsProfileName>() else {
             return None;
@@ -731,6 +799,22 @@ pub struct JournalSettingsContent {
     pub hour_format: Option<HourFormat>,
 }
 
+/// Settings for syncing user settings, keymap, snippets, themes and
+/// extensions through a folder.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct SettingsSyncSettingsContent {
+    /// The folder to sync with. When it's a git repository, changes are
+    /// committed, and pulled from and pushed to its upstream.
+    ///
+    /// Default: null
+    pub path: Option<String>,
+    /// A git repository URL to clone into `path` if it doesn't exist yet.
+    ///
+    /// Default: null
+    pub repository: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum HourFormat {
@@ -25,11 +25,23 @@ pub struct AllLanguageSettingsContent {
     /// Settings for associating file extensions and filenames
     /// with languages.
     pub file_types: Option<HashMap<Arc<str>, ExtendingVec<String>>>,
+    /// Command-line formatters that can be referenced by name from the `formatter` setting.
+    ///
+    /// Zed ships definitions for common formatters, which can be tweaked
+    /// or extended by defining formatters with the same or a new name.
+    pub formatters: Option<HashMap<Arc<str>, FormatterDefinitionContent>>,
+    /// Command-line linters that can be enabled by name in the `lint` setting.
+    ///
+    /// Zed ships definitions for common linters, which can be tweaked
+    /// or extended by defining linters with the same or a new name.
+    pub linters: Option<HashMap<Arc<str>, LinterDefinitionContent>>,
 }
 
 impl merge_from::MergeFrom for AllLanguageSettingsContent {
     fn merge_from(&mut self, other: &Self) {
         self.file_types.merge_from(&other.file_types);
+        self.formatters.merge_from(&other.formatters);
+        self.linters.merge_from(&other.linters);
         self.features.merge_from(&other.features);
         self.edit_predictions.merge_from(&other.edit_predictions);
 
@@ -321,6 +333,8 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: off
     pub prettier: Option<PrettierSettingsContent>,
+    /// Which command-line linters to run on files of this language, and when.
+    pub lint: Option<LintSettingsContent>,
     /// Whether to automatically close JSX tags.
     pub jsx_tag_auto_close: Option<JsxTagAutoCloseSettingsContent>,
     /// Whether to use language servers to provide code intelligence.
@@ -700,6 +714,124 @@ pub struct PrettierSettingsContent {
     pub options: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// Controls which command-line linters run on files of a language, and when.
+#[with_fallible_options]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct LintSettingsContent {
+    /// The names of the linters from the `linters` setting to run.
+    ///
+    /// Default: []
+    pub linters: Option<Vec<String>>,
+    /// When to run the linters.
+    ///
+    /// Default: on_save
+    pub run: Option<LintTrigger>,
+    /// How long to wait after the last edit before linting, in milliseconds,
+    /// when `run` is `on_idle`.
+    ///
+    /// Default: 1000
+    pub idle_delay_ms: Option<u64>,
+}
+
+/// When to run command-line linters.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LintTrigger {
+    /// Lint files after they are saved.
+    #[default]
+    OnSave,
+    /// Lint files after they are saved, and once no edits were made to them for `idle_delay_ms`.
+    /// Linters that read the file from disk rather than from stdin still only run on save.
+    OnIdle,
+}
+
+/// A command-line linter that can be enabled in the `lint` setting.
+#[with_fallible_options]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct LinterDefinitionContent {
+    /// The program to run.
+    pub command: Option<Arc<str>>,
+    /// The arguments to pass to the program.
+    ///
+    /// The following variables are substituted:
+    /// - `{buffer_path}`: the absolute path of the file being linted.
+    /// - `{buffer_dir}`: the directory containing the file being linted.
+    /// - `{worktree_root}`: the root of the worktree containing the file.
+    pub arguments: Option<Vec<String>>,
+    /// How the linter reads the file to lint.
+    ///
+    /// Default: stdin
+    pub input: Option<LinterInput>,
+    /// The format of the linter's output.
+    pub output_format: Option<LinterOutputFormat>,
+}
+
+/// How a command-line linter reads the file to lint.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LinterInput {
+    /// The buffer's text is written to the linter's standard input, so unsaved changes can be linted.
+    #[default]
+    Stdin,
+    /// The linter reads the file from disk, so it only runs when the file is saved.
+    File,
+}
+
+/// The output formats of command-line linters that Zed can read diagnostics from.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LinterOutputFormat {
+    /// SARIF 2.1.0, supported by many linters.
+    Sarif,
+    /// The output of `shellcheck --format=json1`.
+    Shellcheck,
+    /// The output of `ruff check --output-format=json`.
+    Ruff,
+    /// The output of `golangci-lint run --output.json.path=stdout`.
+    GolangciLint,
+    /// The output of `markdownlint --json`.
+    Markdownlint,
+}
+
 /// TODO: this should just be a bool
 /// Controls the behavior of formatting files when they are saved.
 #[derive(
@@ -765,11 +897,76 @@ pub enum Formatter {
     },
     /// Files should be formatted using a code action executed by language servers.
     CodeAction(String),
+    /// Format code using a command-line formatter defined in the `formatters` setting.
+    Named(String),
     /// Format code using a language server.
     #[serde(untagged)]
     LanguageServer(LanguageServerFormatterSpecifier),
 }
 
+/// A command-line formatter that can be referenced from the `formatter` setting.
+#[with_fallible_options]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct FormatterDefinitionContent {
+    /// The program to run.
+    pub command: Option<Arc<str>>,
+    /// The arguments to pass to the program.
+    ///
+    /// The following variables are substituted:
+    /// - `{buffer_path}`: the absolute path of the buffer being formatted.
+    /// - `{input_path}`: the file the formatter should rewrite, when `mode` is `in_place`.
+    ///   The same as `{buffer_path}` otherwise.
+    /// - `{worktree_root}`: the root of the worktree containing the buffer.
+    pub arguments: Option<Vec<String>>,
+    /// How the buffer's text is passed to the formatter.
+    ///
+    /// Default: stdio
+    pub mode: Option<FormatterMode>,
+    /// Arguments appended to `arguments` when formatting a selection, once per selected range.
+    /// Besides the variables supported by `arguments`, these can use:
+    /// - `{range_start_line}` and `{range_end_line}`: the 1-based, inclusive lines of the range.
+    /// - `{range_start_offset}` and `{range_end_offset}`: the byte offsets of the range.
+    /// - `{range_length}`: the length of the range in bytes.
+    ///
+    /// When not set, selections are formatted by formatting the whole buffer
+    /// and only keeping the changes that touch the selected lines.
+    pub range_arguments: Option<Vec<String>>,
+    /// Exit codes that mean the formatter succeeded.
+    ///
+    /// Default: [0]
+    pub success_exit_codes: Option<Vec<i32>>,
+    /// Exit codes that mean the text was already formatted, in which case the output is ignored.
+    ///
+    /// Default: []
+    pub unchanged_exit_codes: Option<Vec<i32>>,
+}
+
+/// How a command-line formatter receives the text to format.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatterMode {
+    /// The text is written to the formatter's standard input,
+    /// and the formatted text is read from its standard output.
+    #[default]
+    Stdio,
+    /// The text is written to a temporary file that the formatter rewrites in place.
+    /// Its path is available as `{input_path}`.
+    InPlace,
+}
+
 #[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
 #[serde(
     rename_all = "snake_case",
@@ -935,7 +1132,7 @@ pub enum IndentGuideBackgroundColoring {
 #[cfg(test)]
 mod test {
 
-    use crate::{ParseStatus, fallible_options};
+    use crate::{ParseStatus, fallible_options, merge_from::MergeFrom as _};
 
     use super::*;
 
@@ -991,6 +1188,52 @@ mod test {
             serde_json::to_string(&LanguageServerFormatterSpecifier::Current).unwrap(),
             "\"language_server\"",
         );
+
+        let raw = "{\"formatter\": [{\"named\": \"ruff\"}, \"language_server\"]}";
+        let settings: LanguageSettingsContent = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            settings.formatter,
+            Some(FormatterList::Vec(vec![
+                Formatter::Named("ruff".to_string()),
+                Formatter::LanguageServer(LanguageServerFormatterSpecifier::Current),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_formatter_definitions_merge() {
+        let mut settings: AllLanguageSettingsContent = serde_json::from_str(
+            r#"{
+                "formatters": {
+                    "black": {
+                        "command": "black",
+                        "arguments": ["--quiet", "-"],
+                        "range_arguments": ["--line-ranges", "{range_start_line}-{range_end_line}"]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        let user_settings: AllLanguageSettingsContent = serde_json::from_str(
+            r#"{
+                "formatters": {
+                    "black": { "command": "/opt/black/bin/black" },
+                    "nixfmt": { "command": "nixfmt", "arguments": ["{input_path}"], "mode": "in_place" }
+                }
+            }"#,
+        )
+        .unwrap();
+        settings.merge_from(&user_settings);
+
+        let formatters = settings.formatters.unwrap();
+        let black = &formatters["black"];
+        assert_eq!(black.command.as_deref(), Some("/opt/black/bin/black"));
+        assert_eq!(
+            black.arguments.as_deref(),
+            Some(&["--quiet".to_string(), "-".to_string()][..])
+        );
+        assert!(black.range_arguments.is_some());
+        assert_eq!(formatters["nixfmt"].mode, Some(FormatterMode::InPlace));
     }
 
     #[test]
@@ -0,0 +1,192 @@
+use std::path::PathBuf;
+
+use globset::{Glob, GlobSetBuilder};
+use gpui::{Global, SharedString};
+
+use crate::{ProfileAppearanceContent, SettingsProfileTriggerContent, TimeOfDayRangeContent};
+
+/// The state of the app that settings profile triggers are matched against.
+///
+/// The [`crate::SettingsStore`] re-evaluates the `profile_triggers` whenever
+/// this global changes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SettingsProfileContext {
+    /// The absolute paths of the worktrees in the active workspace.
+    pub worktree_paths: Vec<PathBuf>,
+    /// The language of the active buffer.
+    pub language: Option<SharedString>,
+    /// Whether the screen is being shared in a call.
+    pub screen_sharing: bool,
+    /// The appearance of the system.
+    pub appearance: Option<ProfileAppearanceContent>,
+    /// The local time of day, in minutes since midnight.
+    pub minutes_since_midnight: Option<u32>,
+}
+
+impl Global for SettingsProfileContext {}
+
+impl SettingsProfileTriggerContent {
+    /// Returns whether every condition of this trigger holds in the given
+    /// context. A trigger without any condition never matches.
+    pub fn matches(&self, context: &SettingsProfileContext) -> bool {
+        let SettingsProfileTriggerContent {
+            worktrees,
+            languages,
+            screen_sharing,
+            appearance,
+            time,
+        } = self;
+        if worktrees.is_none()
+            && languages.is_none()
+            && screen_sharing.is_none()
+            && appearance.is_none()
+            && time.is_none()
+        {
+            return false;
+        }
+
+        worktrees
+            .as_ref()
+            .is_none_or(|globs| worktrees_match(globs, &context.worktree_paths))
+            && languages.as_ref().is_none_or(|languages| {
+                context.language.as_ref().is_some_and(|language| {
+                    languages
+                        .iter()
+                        .any(|candidate| candidate.eq_ignore_ascii_case(language))
+                })
+            })
+            && screen_sharing.is_none_or(|screen_sharing| screen_sharing == context.screen_sharing)
+            && appearance.is_none_or(|appearance| context.appearance == Some(appearance))
+            && time.as_ref().is_none_or(|time| {
+                context
+                    .minutes_since_midnight
+                    .is_some_and(|minutes| time.contains(minutes))
+            })
+    }
+}
+
+impl TimeOfDayRangeContent {
+    /// Returns whether the given number of minutes since midnight falls in
+    /// this range. Ranges with invalid times never contain anything.
+    pub fn contains(&self, minutes_since_midnight: u32) -> bool {
+        let (Some(from), Some(to)) = (parse_time_of_day(&self.from), parse_time_of_day(&self.to))
+        else {
+            return false;
+        };
+        if from <= to {
+            (from..to).contains(&minutes_since_midnight)
+        } else {
+            minutes_since_midnight >= from || minutes_since_midnight < to
+        }
+    }
+}
+
+/// Parses a `HH:MM` time into minutes since midnight.
+fn parse_time_of_day(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let hours = hours.parse::<u32>().ok().filter(|hours| *hours < 24)?;
+    let minutes = minutes
+        .parse::<u32>()
+        .ok()
+        .filter(|minutes| *minutes < 60)?;
+    Some(hours * 60 + minutes)
+}
+
+fn worktrees_match(globs: &[String], worktree_paths: &[PathBuf]) -> bool {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        let glob = match glob.strip_prefix("~/") {
+            Some(rest) => util::paths::home_dir()
+                .join(rest)
+                .to_string_lossy()
+                .into_owned(),
+            None => glob.clone(),
+        };
+        match Glob::new(&glob) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(error) => log::warn!("invalid worktree glob in settings profile trigger: {error}"),
+        }
+    }
+    let Ok(glob_set) = builder.build() else {
+        return false;
+    };
+    worktree_paths.iter().any(|path| glob_set.is_match(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_of_day_range() {
+        let evening = TimeOfDayRangeContent {
+            from: "18:30".into(),
+            to: "23:00".into(),
+        };
+        assert!(!evening.contains(18 * 60 + 29));
+        assert!(evening.contains(18 * 60 + 30));
+        assert!(evening.contains(22 * 60 + 59));
+        assert!(!evening.contains(23 * 60));
+
+        let night = TimeOfDayRangeContent {
+            from: "22:00".into(),
+            to: "07:00".into(),
+        };
+        assert!(night.contains(23 * 60));
+        assert!(night.contains(3 * 60));
+        assert!(!night.contains(7 * 60));
+        assert!(!night.contains(12 * 60));
+
+        let invalid = TimeOfDayRangeContent {
+            from: "25:00".into(),
+            to: "07:00".into(),
+        };
+        assert!(!invalid.contains(3 * 60));
+    }
+
+    #[test]
+    fn test_trigger_conditions() {
+        let context = SettingsProfileContext {
+            worktree_paths: vec![PathBuf::from("/code/work/api")],
+            language: Some("Rust".into()),
+            screen_sharing: true,
+            appearance: Some(ProfileAppearanceContent::Dark),
+            minutes_since_midnight: Some(10 * 60),
+        };
+
+        assert!(!SettingsProfileTriggerContent::default().matches(&context));
+
+        let pairing = SettingsProfileTriggerContent {
+            screen_sharing: Some(true),
+            ..Default::default()
+        };
+        assert!(pairing.matches(&context));
+        assert!(!pairing.matches(&SettingsProfileContext {
+            screen_sharing: false,
+            ..context.clone()
+        }));
+
+        let work_rust = SettingsProfileTriggerContent {
+            worktrees: Some(vec!["/code/work/**".into()]),
+            languages: Some(vec!["rust".into()]),
+            ..Default::default()
+        };
+        assert!(work_rust.matches(&context));
+        assert!(!work_rust.matches(&SettingsProfileContext {
+            worktree_paths: vec![PathBuf::from("/code/personal/blog")],
+            ..context.clone()
+        }));
+        assert!(!work_rust.matches(&SettingsProfileContext {
+            language: None,
+            ..context.clone()
+        }));
+
+        let light = SettingsProfileTriggerContent {
+            appearance: Some(ProfileAppearanceContent::Light),
+            ..Default::default()
+        };
+        assert!(!light.matches(&context));
+    }
+}
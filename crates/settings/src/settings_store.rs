@@ -32,7 +32,8 @@ pub type EditorconfigProperties = ec4rs::Properties;
 
 use crate::{
     ActiveSettingsProfileName, FontFamilyName, IconThemeName, LanguageSettingsContent,
-    LanguageToSettingsMap, ThemeName, VsCodeSettings, WorktreeId, fallible_options,
+    LanguageToSettingsMap, SettingsProfileContext, ThemeName, VsCodeSettings, WorktreeId,
+    fallible_options,
     merge_from::MergeFrom,
     settings_content::{
         ExtensionsSettingsContent, ProjectSettingsContent, SettingsContent, UserSettingsContent,
@@ -145,9 +146,17 @@ pub struct SettingsStore {
     global_settings: Option<Box<SettingsContent>>,
 
     extension_settings: Option<Box<SettingsContent>>,
+    /// Settings translated from the options in vim mode's `vimrc` or set with `:set`.
+    vimrc_settings: Option<Box<SettingsContent>>,
     server_settings: Option<Box<SettingsContent>>,
 
     merged_settings: Rc<SettingsContent>,
+    /// The profiles applied because their `profile_triggers` match the
+    /// [`SettingsProfileContext`], in the order they're defined in.
+    triggered_settings_profiles: Vec<String>,
+    /// The name of this machine, which selects its section of the `machines`
+    /// user settings.
+    machine_name: Option<String>,
 
     local_settings: BTreeMap<(WorktreeId, Arc<RelPath>), SettingsContent>,
     raw_editorconfig_settings: BTreeMap<(WorktreeId, Arc<RelPath>), (String, Option<Editorconfig>)>,
@@ -198,6 +207,46 @@ impl Ord for SettingsFile {
     }
 }
 
+/// A layer of settings that the value of a setting can come from.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SettingsSource {
+    Default,
+    Extension,
+    Global,
+    User,
+    /// The section of the user settings for the current release channel.
+    ReleaseChannel,
+    /// The section of the user settings for the current operating system.
+    Os,
+    /// The section of the user settings for the current machine.
+    Machine,
+    /// A settings profile, either triggered or selected by the user.
+    Profile(String),
+    /// The options of vim mode's `vimrc` or set with `:set`.
+    Vimrc,
+    Server,
+    Project((WorktreeId, Arc<RelPath>)),
+}
+
+impl SettingsSource {
+    /// Returns the settings file this layer is stored in.
+    pub fn file(&self) -> SettingsFile {
+        match self {
+            SettingsSource::Default => SettingsFile::Default,
+            SettingsSource::Extension | SettingsSource::Global | SettingsSource::Vimrc => {
+                SettingsFile::Global
+            }
+            SettingsSource::User
+            | SettingsSource::ReleaseChannel
+            | SettingsSource::Os
+            | SettingsSource::Machine
+            | SettingsSource::Profile(_) => SettingsFile::User,
+            SettingsSource::Server => SettingsFile::Server,
+            SettingsSource::Project(location) => SettingsFile::Project(location.clone()),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Editorconfig {
     pub is_root: bool,
@@ -266,11 +315,14 @@ impl SettingsStore {
             setting_values: Default::default(),
             default_settings: default_settings.clone(),
             global_settings: None,
+            vimrc_settings: None,
             server_settings: None,
             user_settings: None,
             extension_settings: None,
 
             merged_settings: default_settings,
+            triggered_settings_profiles: Vec::new(),
+            machine_name: crate::machine_name(),
             local_settings: BTreeMap::default(),
             raw_editorconfig_settings: BTreeMap::default(),
             setting_file_updates_tx,
@@ -295,6 +347,16 @@ impl SettingsStore {
         })
     }
 
+    pub fn observe_settings_profile_context(cx: &mut App) -> gpui::Subscription {
+        cx.observe_global::<SettingsProfileContext>(|cx| {
+            Self::update_global(cx, |store, cx| {
+                if store.matching_settings_profiles(cx) != store.triggered_settings_profiles {
+                    store.recompute_values(None, cx);
+                }
+            });
+        })
+    }
+
     pub fn update<C, R>(cx: &mut C, f: impl FnOnce(&mut Self, &mut C) -> R) -> R
     where
         C: BorrowAppContext,
@@ -405,11 +467,62 @@ impl SettingsStore {
             .flat_map(|settings| settings.profiles.keys().map(|k| k.as_str()))
     }
 
+    /// Get the names of the settings profiles that are currently applied,
+    /// from lowest to highest precedence.
+    ///
+    /// Profiles whose `profile_triggers` match are applied in the order
+    /// they're defined in, followed by the profile selected by the user.
+    pub fn active_settings_profiles<'a>(&'a self, cx: &'a App) -> Vec<&'a str> {
+        let mut profiles = self
+            .triggered_settings_profiles
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        if let Some(selected) = cx.try_global::<ActiveSettingsProfileName>()
+            && self
+                .user_settings
+                .as_ref()
+                .is_some_and(|settings| settings.profiles.contains_key(&selected.0))
+        {
+            profiles.retain(|name| *name != selected.0);
+            profiles.push(&selected.0);
+        }
+        profiles
+    }
+
+    fn matching_settings_profiles(&self, cx: &App) -> Vec<String> {
+        let (Some(user_settings), Some(context)) = (
+            self.user_settings.as_ref(),
+            cx.try_global::<SettingsProfileContext>(),
+        ) else {
+            return Vec::new();
+        };
+        user_settings
+            .profiles
+            .keys()
+            .filter(|name| {
+                user_settings
+                    .profile_triggers
+                    .get(*name)
+                    .is_some_and(|trigger| trigger.matches(context))
+            })
+            .cloned()
+            .collect()
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut App) -> Self {
         Self::new(cx, &crate::test_settings())
     }
 
+    /// Overrides the name of this machine, which selects its section of the
+    /// `machines` user settings.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn set_machine_name(&mut self, machine_name: Option<String>, cx: &mut App) {
+        self.machine_name = machine_name;
+        self.recompute_values(None, cx);
+    }
+
     /// Updates the value of a setting in the user's global configuration.
     ///
     /// This is only for tests. Normally, settings are only loaded from
@@ -659,6 +772,82 @@ impl SettingsStore {
         (SettingsFile::Default, None)
     }
 
+    /// Returns the layers of settings that apply to the given file, from
+    /// highest to lowest precedence.
+    ///
+    /// For project files, these are the project files of its parent
+    /// directories, followed by everything that applies app-wide.
+    pub fn settings_layers(
+        &self,
+        file: &SettingsFile,
+        cx: &App,
+    ) -> Vec<(SettingsSource, &SettingsContent)> {
+        let mut layers = Vec::new();
+        if let SettingsFile::Project((worktree_id, path)) = file {
+            layers.extend(
+                self.local_settings
+                    .iter()
+                    .rev()
+                    .filter(|((local_worktree_id, local_path), _)| {
+                        local_worktree_id == worktree_id && path.starts_with(local_path)
+                    })
+                    .map(|(location, content)| {
+                        (SettingsSource::Project(location.clone()), content)
+                    }),
+            );
+        }
+        if let Some(server_settings) = self.server_settings.as_deref() {
+            layers.push((SettingsSource::Server, server_settings));
+        }
+        if let Some(vimrc_settings) = self.vimrc_settings.as_deref() {
+            layers.push((SettingsSource::Vimrc, vimrc_settings));
+        }
+        if let Some(user_settings) = self.user_settings.as_ref() {
+            if let Some(selected) = cx.try_global::<ActiveSettingsProfileName>()
+                && let Some(profile) = user_settings.profiles.get(&selected.0)
+            {
+                layers.push((SettingsSource::Profile(selected.0.clone()), profile));
+            }
+            for profile_name in self.triggered_settings_profiles.iter().rev() {
+                if let Some(profile) = user_settings.profiles.get(profile_name) {
+                    layers.push((SettingsSource::Profile(profile_name.clone()), profile));
+                }
+            }
+            if let Some(machine_settings) = user_settings.for_machine(self.machine_name.as_deref())
+            {
+                layers.push((SettingsSource::Machine, machine_settings));
+            }
+            if let Some(os_settings) = user_settings.for_os() {
+                layers.push((SettingsSource::Os, os_settings));
+            }
+            if let Some(release_channel_settings) = user_settings.for_release_channel() {
+                layers.push((SettingsSource::ReleaseChannel, release_channel_settings));
+            }
+            layers.push((SettingsSource::User, &user_settings.content));
+        }
+        if let Some(global_settings) = self.global_settings.as_deref() {
+            layers.push((SettingsSource::Global, global_settings));
+        }
+        if let Some(extension_settings) = self.extension_settings.as_deref() {
+            layers.push((SettingsSource::Extension, extension_settings));
+        }
+        layers.push((SettingsSource::Default, self.default_settings.as_ref()));
+        layers
+    }
+
+    /// Returns the layer that the effective value of a setting comes from for
+    /// the given file, or `None` if no layer sets it.
+    pub fn value_source<T: ?Sized>(
+        &self,
+        file: &SettingsFile,
+        pick: fn(&SettingsContent) -> Option<&T>,
+        cx: &App,
+    ) -> Option<SettingsSource> {
+        self.settings_layers(file, cx)
+            .into_iter()
+            .find_map(|(source, content)| pick(content).is_some().then_some(source))
+    }
+
     #[inline(always)]
     fn parse_and_migrate_zed_settings<SettingsContentType: serde::de::DeserializeOwned>(
         &mut self,
@@ -951,6 +1140,13 @@ impl SettingsStore {
         Ok(())
     }
 
+    /// Sets the settings that vim mode translates its options into, which take
+    /// precedence over the user's settings.
+    pub fn set_vimrc_settings(&mut self, content: Option<SettingsContent>, cx: &mut App) {
+        self.vimrc_settings = content.map(Box::new);
+        self.recompute_values(None, cx);
+    }
+
     pub fn set_extension_settings(
         &mut self,
         content: ExtensionsSettingsContent,
@@ -1072,6 +1268,7 @@ impl SettingsStore {
         let mut paths_stack = Vec::<Option<(WorktreeId, &RelPath)>>::new();
 
         if changed_local_path.is_none() {
+            self.triggered_settings_profiles = self.matching_settings_profiles(cx);
             let mut merged = self.default_settings.as_ref().clone();
             merged.merge_from_option(self.extension_settings.as_deref());
             merged.merge_from_option(self.global_settings.as_deref());
@@ -1079,8 +1276,13 @@ impl SettingsStore {
                 merged.merge_from(&user_settings.content);
                 merged.merge_from_option(user_settings.for_release_channel());
                 merged.merge_from_option(user_settings.for_os());
+                merged.merge_from_option(user_settings.for_machine(self.machine_name.as_deref()));
+                for profile_name in &self.triggered_settings_profiles {
+                    merged.merge_from_option(user_settings.profiles.get(profile_name));
+                }
                 merged.merge_from_option(user_settings.for_profile(cx));
             }
+            merged.merge_from_option(self.vimrc_settings.as_deref());
             merged.merge_from_option(self.server_settings.as_deref());
             self.merged_settings = Rc::new(merged);
 
@@ -1156,6 +1358,43 @@ impl SettingsStore {
         properties.use_fallbacks();
         Some(properties)
     }
+
+    /// Returns the directory of the `.editorconfig` file that sets any of the
+    /// given properties for the file at `for_path`.
+    pub fn editorconfig_source(
+        &self,
+        for_worktree: WorktreeId,
+        for_path: &RelPath,
+        keys: &[&str],
+    ) -> Option<Arc<RelPath>> {
+        let mut source = None;
+        for (directory_with_config, _, parsed_editorconfig) in
+            self.local_editorconfig_settings(for_worktree)
+        {
+            if !for_path.starts_with(&directory_with_config) {
+                continue;
+            }
+            let Some(parsed_editorconfig) = parsed_editorconfig else {
+                continue;
+            };
+            if parsed_editorconfig.is_root {
+                source = None;
+            }
+            let mut properties = EditorconfigProperties::new();
+            for section in parsed_editorconfig.sections {
+                section
+                    .apply_to(&mut properties, for_path.as_std_path())
+                    .log_err()?;
+            }
+            if keys
+                .iter()
+                .any(|key| !properties.get_raw_for_key(key).is_unset())
+            {
+                source = Some(directory_with_config);
+            }
+        }
+        source
+    }
 }
 
 /// The result of parsing settings, including any migration attempts
@@ -1932,6 +2171,89 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_machine_settings(cx: &mut App) {
+        let machine_name = "test-machine";
+        let mut store = SettingsStore::new(cx, &test_settings());
+        store.register_setting::<ItemSettings>();
+        store.set_machine_name(Some(machine_name.to_string()), cx);
+
+        let user_settings = serde_json::json!({
+            "tabs": { "close_position": "left", "git_status": false },
+            "machines": {
+                machine_name: { "tabs": { "git_status": true } },
+                format!("not-{machine_name}"): { "tabs": { "close_position": "right" } }
+            }
+        });
+        store
+            .set_user_settings(&user_settings.to_string(), cx)
+            .unwrap();
+        assert_eq!(
+            store.get::<ItemSettings>(None),
+            &ItemSettings {
+                close_position: ClosePosition::Left,
+                git_status: true,
+            }
+        );
+    }
+
+    #[gpui::test]
+    fn test_triggered_settings_profiles(cx: &mut App) {
+        let mut store = SettingsStore::new(cx, &test_settings());
+        store.register_setting::<ItemSettings>();
+
+        let user_settings = r#"{
+            "tabs": { "close_position": "left" },
+            "profiles": {
+                "Pairing": { "tabs": { "git_status": true } },
+                "Rust": { "tabs": { "close_position": "right", "git_status": false } }
+            },
+            "profile_triggers": {
+                "Pairing": { "screen_sharing": true },
+                "Rust": { "languages": ["Rust"] }
+            }
+        }"#;
+
+        store.set_user_settings(user_settings, cx).unwrap();
+        assert_eq!(store.active_settings_profiles(cx), Vec::<&str>::new());
+        assert_eq!(
+            store.get::<ItemSettings>(None),
+            &ItemSettings {
+                close_position: ClosePosition::Left,
+                git_status: false,
+            }
+        );
+
+        // Profiles are applied in the order they're defined in, so the
+        // "Rust" profile wins over the "Pairing" one.
+        cx.set_global(SettingsProfileContext {
+            language: Some("Rust".into()),
+            screen_sharing: true,
+            ..Default::default()
+        });
+        store.set_user_settings(user_settings, cx).unwrap();
+        assert_eq!(store.active_settings_profiles(cx), vec!["Pairing", "Rust"]);
+        assert_eq!(
+            store.get::<ItemSettings>(None),
+            &ItemSettings {
+                close_position: ClosePosition::Right,
+                git_status: false,
+            }
+        );
+
+        // A profile selected by hand takes precedence over triggered ones.
+        cx.set_global(ActiveSettingsProfileName("Pairing".into()));
+        store.set_user_settings(user_settings, cx).unwrap();
+        assert_eq!(store.active_settings_profiles(cx), vec!["Rust", "Pairing"]);
+        assert_eq!(
+            store.get::<ItemSettings>(None),
+            &ItemSettings {
+                close_position: ClosePosition::Right,
+                git_status: true,
+            }
+        );
+    }
+
     #[gpui::test]
     fn test_get_value_for_field_basic(cx: &mut App) {
         let mut store = SettingsStore::new(cx, &test_settings());
@@ -1994,6 +2316,120 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_value_source(cx: &mut App) {
+        let mut store = SettingsStore::new(cx, &test_settings());
+        store.register_setting::<DefaultLanguageSettings>();
+
+        fn get(content: &SettingsContent) -> Option<&u32> {
+            content
+                .project
+                .all_languages
+                .defaults
+                .preferred_line_length
+                .as_ref()
+        }
+
+        let root = (WorktreeId::from_usize(0), RelPath::empty().into_arc());
+        let subdirectory = (WorktreeId::from_usize(0), rel_path("docs").into_arc());
+        let root_file = SettingsFile::Project(root.clone());
+        let subdirectory_file = SettingsFile::Project(subdirectory.clone());
+        store
+            .set_local_settings(
+                root.0,
+                root.1.clone(),
+                LocalSettingsKind::Settings,
+                Some(r#"{}"#),
+                cx,
+            )
+            .unwrap();
+
+        assert_eq!(
+            store.value_source(&root_file, get, cx),
+            Some(SettingsSource::Default)
+        );
+
+        store
+            .set_user_settings(
+                r#"{
+                    "preferred_line_length": 100,
+                    "profiles": { "Writing": { "preferred_line_length": 72 } }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            store.value_source(&SettingsFile::User, get, cx),
+            Some(SettingsSource::User)
+        );
+
+        cx.set_global(ActiveSettingsProfileName("Writing".into()));
+        assert_eq!(
+            store.value_source(&SettingsFile::User, get, cx),
+            Some(SettingsSource::Profile("Writing".into()))
+        );
+
+        store
+            .set_local_settings(
+                subdirectory.0,
+                subdirectory.1.clone(),
+                LocalSettingsKind::Settings,
+                Some(r#"{"preferred_line_length": 80}"#),
+                cx,
+            )
+            .unwrap();
+        assert_eq!(
+            store.value_source(&subdirectory_file, get, cx),
+            Some(SettingsSource::Project(subdirectory))
+        );
+        // Settings of subdirectories don't apply to their parents.
+        assert_eq!(
+            store.value_source(&root_file, get, cx),
+            Some(SettingsSource::Profile("Writing".into()))
+        );
+    }
+
+    #[gpui::test]
+    fn test_editorconfig_source(cx: &mut App) {
+        let mut store = SettingsStore::new(cx, &test_settings());
+        let worktree_id = WorktreeId::from_usize(0);
+        store
+            .set_local_settings(
+                worktree_id,
+                RelPath::empty().into_arc(),
+                LocalSettingsKind::Editorconfig,
+                Some("root = true\n[*]\nindent_style = space\n[*.rs]\nindent_size = 4\n"),
+                cx,
+            )
+            .unwrap();
+        store
+            .set_local_settings(
+                worktree_id,
+                rel_path("docs").into_arc(),
+                LocalSettingsKind::Editorconfig,
+                Some("[*.md]\nmax_line_length = 80\n"),
+                cx,
+            )
+            .unwrap();
+
+        assert_eq!(
+            store.editorconfig_source(worktree_id, rel_path("src/main.rs"), &["indent_size"]),
+            Some(RelPath::empty().into_arc())
+        );
+        assert_eq!(
+            store.editorconfig_source(worktree_id, rel_path("README.md"), &["indent_size"]),
+            None
+        );
+        assert_eq!(
+            store.editorconfig_source(worktree_id, rel_path("docs/guide.md"), &["max_line_length"]),
+            Some(rel_path("docs").into_arc())
+        );
+        assert_eq!(
+            store.editorconfig_source(worktree_id, rel_path("docs/guide.md"), &["indent_style"]),
+            Some(RelPath::empty().into_arc())
+        );
+    }
+
     #[gpui::test]
     fn test_get_value_for_field_local_worktrees_dont_interfere(cx: &mut App) {
         let mut store = SettingsStore::new(cx, &test_settings());
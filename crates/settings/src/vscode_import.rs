@@ -395,6 +395,8 @@ impl VsCodeSettings {
                 defaults: self.default_language_settings_content(),
                 languages: Default::default(),
                 file_types: self.file_types(),
+                formatters: None,
+                linters: None,
             },
             worktree: self.worktree_settings_content(),
             lsp: Default::default(),
@@ -446,6 +448,7 @@ impl VsCodeSettings {
             jsx_tag_auto_close: None,
             language_servers: None,
             linked_edits: self.read_bool("editor.linkedEditing"),
+            lint: None,
             preferred_line_length: self.read_u32("editor.wordWrapColumn"),
             prettier: None,
             remove_trailing_whitespace_on_save: self.read_bool("editor.trimAutoWhitespace"),
@@ -0,0 +1,1260 @@
+use crate::{KeymapFile, VsCodeSettingsSource};
+use anyhow::{Context as _, Result, anyhow};
+use collections::IndexMap;
+use fs::Fs;
+use gpui::{KeyBindingContextPredicate, Keystroke};
+use paths::{cursor_keybindings_file_paths, vscode_keybindings_file_paths};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use settings_json::{append_top_level_array_value_in_json_text, infer_json_indent_size};
+use std::{path::Path, sync::Arc};
+
+/// An entry of a VS Code `keybindings.json` file.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct VsCodeKeybinding {
+    pub key: String,
+    pub command: String,
+    #[serde(default)]
+    pub when: Option<String>,
+    #[serde(default)]
+    pub args: Option<Value>,
+}
+
+/// A VS Code keybinding translated to a Zed keymap binding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedKeybinding {
+    pub keystrokes: String,
+    pub context: Option<String>,
+    /// The action as written in `keymap.json`: either its name, or its name and
+    /// arguments.
+    pub action: Value,
+    pub original: VsCodeKeybinding,
+}
+
+impl ImportedKeybinding {
+    pub fn action_name(&self) -> &str {
+        match &self.action {
+            Value::Array(values) => values.first().and_then(Value::as_str).unwrap_or_default(),
+            value => value.as_str().unwrap_or_default(),
+        }
+    }
+}
+
+/// A VS Code keybinding that has no equivalent in Zed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkippedKeybinding {
+    pub original: VsCodeKeybinding,
+    pub reason: String,
+}
+
+/// The keybindings from a VS Code `keybindings.json` file, translated to Zed
+/// where possible, so that they can be reviewed before being added to the
+/// user's keymap.
+pub struct VsCodeKeymap {
+    pub source: VsCodeSettingsSource,
+    pub path: Arc<Path>,
+    pub imported: Vec<ImportedKeybinding>,
+    pub skipped: Vec<SkippedKeybinding>,
+}
+
+impl VsCodeKeymap {
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn from_str(content: &str, source: VsCodeSettingsSource) -> Result<Self> {
+        Self::parse(
+            content,
+            source,
+            Path::new("/example-path/Code/User/keybindings.json").into(),
+        )
+    }
+
+    pub async fn load_user_keymap(source: VsCodeSettingsSource, fs: Arc<dyn Fs>) -> Result<Self> {
+        let candidate_paths = match source {
+            VsCodeSettingsSource::VsCode => vscode_keybindings_file_paths(),
+            VsCodeSettingsSource::Cursor => cursor_keybindings_file_paths(),
+        };
+        let mut path = None;
+        for candidate_path in candidate_paths.iter() {
+            if fs.is_file(candidate_path).await {
+                path = Some(candidate_path.clone());
+            }
+        }
+        let Some(path) = path else {
+            return Err(anyhow!(
+                "No keybindings file found, expected to find it in one of the following paths:\n{}",
+                candidate_paths
+                    .into_iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        };
+        let content = fs.load(&path).await.with_context(|| {
+            format!(
+                "Error loading {} keybindings file from {}",
+                source,
+                path.display()
+            )
+        })?;
+        Self::parse(&content, source, path.into())
+    }
+
+    fn parse(content: &str, source: VsCodeSettingsSource, path: Arc<Path>) -> Result<Self> {
+        let entries: Vec<Value> = serde_json_lenient::from_str(content).with_context(|| {
+            format!(
+                "Error parsing {} keybindings file from {}",
+                source,
+                path.display()
+            )
+        })?;
+
+        let mut imported = Vec::new();
+        let mut skipped = Vec::new();
+        for entry in entries {
+            let original = match serde_json::from_value::<VsCodeKeybinding>(entry.clone()) {
+                Ok(original) => original,
+                Err(error) => {
+                    log::warn!("Skipping invalid {source} keybinding {entry}: {error}");
+                    continue;
+                }
+            };
+            match translate_keybinding(&original) {
+                Ok((keystrokes, context, action)) => imported.push(ImportedKeybinding {
+                    keystrokes,
+                    context,
+                    action,
+                    original,
+                }),
+                Err(reason) => skipped.push(SkippedKeybinding { original, reason }),
+            }
+        }
+
+        Ok(Self {
+            source,
+            path,
+            imported,
+            skipped,
+        })
+    }
+
+    /// The actions that VS Code commands are translated to, with their
+    /// arguments.
+    pub fn mapped_actions() -> impl Iterator<Item = (&'static str, Option<Value>)> {
+        COMMANDS.iter().map(|(_, action, arguments)| {
+            let arguments = arguments.map(|arguments| {
+                serde_json::from_str(arguments).expect("invalid arguments in command table")
+            });
+            (*action, arguments)
+        })
+    }
+
+    /// Appends the imported bindings to the given `keymap.json` contents, in one
+    /// section per context. Bindings that VS Code lists later take precedence,
+    /// like they do in Zed.
+    pub fn append_to_keymap(&self, mut keymap_contents: String, tab_size: usize) -> Result<String> {
+        KeymapFile::parse(&keymap_contents).context("Failed to parse keymap")?;
+
+        let mut sections = IndexMap::<Option<&str>, IndexMap<&str, &Value>>::default();
+        for binding in &self.imported {
+            let bindings = sections.entry(binding.context.as_deref()).or_default();
+            // Re-inserting moves the binding to the end, where it takes precedence.
+            bindings.shift_remove(binding.keystrokes.as_str());
+            bindings.insert(&binding.keystrokes, &binding.action);
+        }
+
+        for (context, bindings) in sections {
+            let mut section = Map::new();
+            if let Some(context) = context {
+                section.insert("context".into(), context.into());
+            }
+            section.insert(
+                "bindings".into(),
+                bindings
+                    .into_iter()
+                    .map(|(keystrokes, action)| (keystrokes.to_string(), action.clone()))
+                    .collect::<Map<_, _>>()
+                    .into(),
+            );
+            let (replace_range, replace_value) = append_top_level_array_value_in_json_text(
+                &keymap_contents,
+                &section.into(),
+                tab_size,
+            );
+            keymap_contents.replace_range(replace_range, &replace_value);
+        }
+        Ok(keymap_contents)
+    }
+
+    /// Adds the imported bindings to the user's keymap file.
+    pub async fn write_to_user_keymap(&self, fs: &Arc<dyn Fs>) -> Result<()> {
+        let keymap_contents = KeymapFile::load_keymap_file(fs)
+            .await
+            .context("Failed to load keymap file")?;
+        let tab_size = infer_json_indent_size(&keymap_contents);
+        let keymap_contents = self.append_to_keymap(keymap_contents, tab_size)?;
+        fs.write(paths::keymap_file(), keymap_contents.as_bytes())
+            .await
+            .context("Failed to write keymap file")
+    }
+}
+
+/// Translates a keybinding to keystrokes, a context and an action, or explains
+/// why it can't be.
+fn translate_keybinding(
+    binding: &VsCodeKeybinding,
+) -> Result<(String, Option<String>, Value), String> {
+    if binding.command.starts_with('-') {
+        return Err("Removes one of VS Code's default keybindings".into());
+    }
+    let keystrokes = translate_keystrokes(&binding.key)?;
+    let action = translate_command(&binding.command, binding.args.as_ref())?;
+    let context = match &binding.when {
+        Some(when) => {
+            let clause = WhenClause::parse(when)?;
+            translate_when_clause(&clause)?.map(|context| context.to_string())
+        }
+        None => None,
+    };
+    if let Some(context) = &context {
+        KeyBindingContextPredicate::parse(context)
+            .map_err(|error| format!("Produced an invalid context `{context}`: {error}"))?;
+    }
+    Ok((keystrokes, context, action))
+}
+
+/// Translates keys like `ctrl+shift+k ctrl+c` to `ctrl-shift-k ctrl-c`.
+fn translate_keystrokes(key: &str) -> Result<String, String> {
+    let mut keystrokes = Vec::new();
+    for chord in key.split_whitespace() {
+        let chord = chord.to_lowercase();
+        let (modifiers, key) = if let Some(modifiers) = chord.strip_suffix("++") {
+            (modifiers, "+")
+        } else {
+            chord.rsplit_once('+').unwrap_or(("", chord.as_str()))
+        };
+
+        let mut keystroke = String::new();
+        for modifier in modifiers.split('+').filter(|modifier| !modifier.is_empty()) {
+            let modifier = match modifier {
+                "ctrl" | "shift" | "alt" | "cmd" => modifier,
+                "meta" => "super",
+                "win" => "win",
+                _ => return Err(format!("Unknown modifier `{modifier}`")),
+            };
+            keystroke.push_str(modifier);
+            keystroke.push('-');
+        }
+        keystroke.push_str(translate_key(key).ok_or_else(|| format!("Unsupported key `{key}`"))?);
+        Keystroke::parse(&keystroke).map_err(|error| format!("Invalid keystroke: {error}"))?;
+        keystrokes.push(keystroke);
+    }
+    if keystrokes.is_empty() {
+        return Err("Has no key".into());
+    }
+    Ok(keystrokes.join(" "))
+}
+
+fn translate_key(key: &str) -> Option<&str> {
+    match key {
+        "escape" | "enter" | "tab" | "space" | "backspace" | "delete" | "insert" | "home"
+        | "end" | "pageup" | "pagedown" | "up" | "down" | "left" | "right" => Some(key),
+        _ if key.strip_prefix('f').is_some_and(|number| {
+            number
+                .parse::<u8>()
+                .is_ok_and(|number| (1..=24).contains(&number))
+        }) =>
+        {
+            Some(key)
+        }
+        _ if key.chars().count() == 1 && key.chars().all(|c| c.is_ascii_graphic()) => Some(key),
+        _ => None,
+    }
+}
+
+fn translate_command(command: &str, args: Option<&Value>) -> Result<Value, String> {
+    match (command, args) {
+        ("workbench.action.tasks.runTask", Some(Value::String(task_name))) => {
+            return Ok(json!(["task::Spawn", { "task_name": task_name }]));
+        }
+        ("workbench.action.terminal.sendSequence", Some(args)) => {
+            let text = args
+                .get("text")
+                .and_then(Value::as_str)
+                .ok_or("Expected a `text` argument")?;
+            return Ok(json!(["terminal::SendText", text]));
+        }
+        ("runCommands", Some(args)) => {
+            let commands = args
+                .get("commands")
+                .and_then(Value::as_array)
+                .ok_or("Expected a `commands` argument")?;
+            let actions = commands
+                .iter()
+                .map(|command| match command {
+                    Value::String(command) => translate_command(command, None),
+                    command => translate_command(
+                        command
+                            .get("command")
+                            .and_then(Value::as_str)
+                            .ok_or("Expected a command name")?,
+                        command.get("args"),
+                    ),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(json!(["action::Sequence", actions]));
+        }
+        _ => {}
+    }
+
+    let (_, action, arguments) = COMMANDS
+        .iter()
+        .find(|(vscode_command, ..)| *vscode_command == command)
+        .ok_or_else(|| format!("No equivalent for `{command}`"))?;
+    if args.is_some() {
+        return Err(format!("Arguments to `{command}` aren't supported"));
+    }
+    Ok(match arguments {
+        Some(arguments) => json!([
+            action,
+            serde_json::from_str::<Value>(arguments).map_err(|error| error.to_string())?
+        ]),
+        None => json!(action),
+    })
+}
+
+/// A parsed VS Code `when` clause.
+#[derive(Debug, PartialEq)]
+enum WhenClause {
+    Bool(bool),
+    Key(String),
+    Equal(String, String),
+    NotEqual(String, String),
+    Not(Box<WhenClause>),
+    And(Vec<WhenClause>),
+    Or(Vec<WhenClause>),
+}
+
+impl WhenClause {
+    fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize_when_clause(source)?;
+        let mut parser = WhenClauseParser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let clause = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.position) {
+            return Err(format!("Unexpected `{token}` in `when` clause"));
+        }
+        Ok(clause)
+    }
+}
+
+fn tokenize_when_clause(source: &str) -> Result<Vec<String>, String> {
+    // Longer operators come first, so that `<=` isn't read as `<`.
+    const OPERATORS: [&str; 12] = [
+        "&&", "||", "==", "!=", "=~", "<=", ">=", "!", "(", ")", "<", ">",
+    ];
+
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+    while !rest.is_empty() {
+        if let Some(operator) = OPERATORS
+            .iter()
+            .find(|operator| rest.starts_with(**operator))
+        {
+            tokens.push(operator.to_string());
+            rest = &rest[operator.len()..];
+        } else if let Some(quoted) = rest.strip_prefix('\'') {
+            let end = quoted
+                .find('\'')
+                .ok_or("Unterminated string in `when` clause")?;
+            tokens.push(format!("'{}'", &quoted[..end]));
+            rest = &quoted[end + 1..];
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || "&|=!<>()'".contains(c))
+                .unwrap_or(rest.len());
+            tokens.push(rest[..end].to_string());
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+struct WhenClauseParser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl<'a> WhenClauseParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn or(&mut self) -> Result<WhenClause, String> {
+        let mut clauses = vec![self.and()?];
+        while self.peek() == Some("||") {
+            self.position += 1;
+            clauses.push(self.and()?);
+        }
+        Ok(if clauses.len() == 1 {
+            clauses.remove(0)
+        } else {
+            WhenClause::Or(clauses)
+        })
+    }
+
+    fn and(&mut self) -> Result<WhenClause, String> {
+        let mut clauses = vec![self.unary()?];
+        while self.peek() == Some("&&") {
+            self.position += 1;
+            clauses.push(self.unary()?);
+        }
+        Ok(if clauses.len() == 1 {
+            clauses.remove(0)
+        } else {
+            WhenClause::And(clauses)
+        })
+    }
+
+    fn unary(&mut self) -> Result<WhenClause, String> {
+        match self.next() {
+            Some("!") => Ok(WhenClause::Not(Box::new(self.unary()?))),
+            Some("(") => {
+                let clause = self.or()?;
+                match self.next() {
+                    Some(")") => Ok(clause),
+                    _ => Err("Expected `)` in `when` clause".into()),
+                }
+            }
+            Some("true") => Ok(WhenClause::Bool(true)),
+            Some("false") => Ok(WhenClause::Bool(false)),
+            Some(token) if !token.starts_with('\'') && !is_when_operator(token) => {
+                let key = token.to_string();
+                match self.peek() {
+                    Some(operator @ ("==" | "!=")) => {
+                        let equal = operator == "==";
+                        self.position += 1;
+                        let value = self
+                            .next()
+                            .filter(|value| !is_when_operator(value))
+                            .ok_or("Expected a value in `when` clause")?
+                            .trim_matches('\'')
+                            .to_string();
+                        Ok(if equal {
+                            WhenClause::Equal(key, value)
+                        } else {
+                            WhenClause::NotEqual(key, value)
+                        })
+                    }
+                    Some(operator @ ("=~" | "<" | "<=" | ">" | ">=" | "in" | "not")) => Err(
+                        format!("The `{operator}` operator in `when` clauses isn't supported"),
+                    ),
+                    _ => Ok(WhenClause::Key(key)),
+                }
+            }
+            Some(token) => Err(format!("Unexpected `{token}` in `when` clause")),
+            None => Err("Unexpected end of `when` clause".into()),
+        }
+    }
+}
+
+fn is_when_operator(token: &str) -> bool {
+    matches!(
+        token,
+        "&&" | "||" | "==" | "!=" | "=~" | "<=" | ">=" | "!" | "(" | ")" | "<" | ">"
+    )
+}
+
+/// A Zed keymap context predicate, kept as a tree so that it's printed with
+/// only the parentheses it needs.
+#[derive(Debug, PartialEq)]
+enum Context {
+    Identifier(&'static str),
+    Equal(&'static str, String),
+    Not(Box<Context>),
+    And(Vec<Context>),
+    Or(Vec<Context>),
+}
+
+impl Context {
+    fn precedence(&self) -> u8 {
+        match self {
+            Context::Or(_) => 1,
+            Context::And(_) => 2,
+            Context::Equal(..) => 3,
+            Context::Not(_) | Context::Identifier(_) => 4,
+        }
+    }
+
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, min_precedence: u8) -> std::fmt::Result {
+        if self.precedence() < min_precedence {
+            write!(f, "(")?;
+            self.write(f, 0)?;
+            return write!(f, ")");
+        }
+        match self {
+            Context::Identifier(identifier) => write!(f, "{identifier}"),
+            Context::Equal(key, value) => write!(f, "{key} == {value}"),
+            Context::Not(context) => {
+                write!(f, "!")?;
+                context.write(f, 4)
+            }
+            Context::And(contexts) | Context::Or(contexts) => {
+                let separator = if matches!(self, Context::And(_)) {
+                    " && "
+                } else {
+                    " || "
+                };
+                for (index, context) in contexts.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, "{separator}")?;
+                    }
+                    context.write(f, self.precedence() + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Translates a `when` clause to a keymap context, where `None` means that the
+/// binding applies everywhere.
+fn translate_when_clause(clause: &WhenClause) -> Result<Option<Context>, String> {
+    match clause {
+        WhenClause::Bool(true) => Ok(None),
+        WhenClause::Bool(false) => Err("Is never enabled".into()),
+        WhenClause::Key(key) => {
+            let os = match key.as_str() {
+                "isMac" => Some("macos"),
+                "isLinux" => Some("linux"),
+                "isWindows" => Some("windows"),
+                _ => None,
+            };
+            if let Some(os) = os {
+                return Ok(Some(Context::Equal("os", os.to_string())));
+            }
+            match WHEN_KEYS
+                .iter()
+                .find(|(vscode_key, _)| *vscode_key == key.as_str())
+            {
+                Some((_, Some(identifier))) => Ok(Some(Context::Identifier(identifier))),
+                Some((_, None)) => Ok(None),
+                None => Err(format!("No equivalent for the `{key}` condition")),
+            }
+        }
+        WhenClause::Equal(key, value) => translate_comparison(key, value).map(Some),
+        WhenClause::NotEqual(key, value) => {
+            translate_comparison(key, value).map(|context| Some(Context::Not(Box::new(context))))
+        }
+        WhenClause::Not(clause) => match translate_when_clause(clause)? {
+            Some(context) => Ok(Some(Context::Not(Box::new(context)))),
+            None => Err("Is never enabled".into()),
+        },
+        WhenClause::And(clauses) => {
+            let mut contexts = Vec::new();
+            for clause in clauses {
+                if let Some(context) = translate_when_clause(clause)?
+                    && !contexts.contains(&context)
+                {
+                    contexts.push(context);
+                }
+            }
+            Ok(match contexts.len() {
+                0 => None,
+                1 => contexts.pop(),
+                _ => Some(Context::And(contexts)),
+            })
+        }
+        WhenClause::Or(clauses) => {
+            let mut contexts = Vec::new();
+            for clause in clauses {
+                let Some(context) = translate_when_clause(clause)? else {
+                    return Ok(None);
+                };
+                if !contexts.contains(&context) {
+                    contexts.push(context);
+                }
+            }
+            Ok(if contexts.len() == 1 {
+                contexts.pop()
+            } else {
+                Some(Context::Or(contexts))
+            })
+        }
+    }
+}
+
+fn translate_comparison(key: &str, value: &str) -> Result<Context, String> {
+    match key {
+        "resourceExtname" => Ok(Context::Equal(
+            "extension",
+            value.trim_start_matches('.').to_string(),
+        )),
+        "vim.mode" => {
+            let mode = match value {
+                "Normal" => "normal",
+                "Insert" => "insert",
+                "Visual" | "VisualLine" | "VisualBlock" => "visual",
+                _ => return Err(format!("No equivalent for the `{value}` Vim mode")),
+            };
+            Ok(Context::Equal("vim_mode", mode.to_string()))
+        }
+        _ => Err(format!("No equivalent for the `{key}` condition")),
+    }
+}
+
+/// VS Code context keys, and the keymap contexts they correspond to. Keys
+/// without a context hold wherever bindings from the other keys apply.
+const WHEN_KEYS: &[(&str, Option<&str>)] = &[
+    ("editorTextFocus", Some("Editor")),
+    ("editorFocus", Some("Editor")),
+    ("textInputFocus", Some("Editor")),
+    ("editorIsOpen", None),
+    ("suggestWidgetVisible", Some("showing_completions")),
+    ("parameterHintsVisible", Some("showing_signature_help")),
+    ("inSnippetMode", Some("in_snippet")),
+    ("renameInputVisible", Some("renaming")),
+    ("inlineSuggestionVisible", Some("edit_prediction")),
+    ("terminalFocus", Some("Terminal")),
+    ("filesExplorerFocus", Some("ProjectPanel")),
+    ("explorerViewletFocus", Some("ProjectPanel")),
+    ("inQuickOpen", Some("Picker")),
+];
+
+/// VS Code commands, and the actions and action arguments they correspond to.
+/// `VsCodeKeymap::mapped_actions` exposes these so that tests can check that
+/// every action exists.
+const COMMANDS: &[(&str, &str, Option<&str>)] = &[
+    // Workbench
+    (
+        "workbench.action.showCommands",
+        "command_palette::Toggle",
+        None,
+    ),
+    ("workbench.action.quickOpen", "file_finder::Toggle", None),
+    ("workbench.action.gotoLine", "go_to_line::Toggle", None),
+    ("workbench.action.gotoSymbol", "outline::Toggle", None),
+    (
+        "workbench.action.showAllSymbols",
+        "project_symbols::Toggle",
+        None,
+    ),
+    ("workbench.action.files.save", "workspace::Save", None),
+    ("workbench.action.files.saveAll", "workspace::SaveAll", None),
+    ("workbench.action.files.saveAs", "workspace::SaveAs", None),
+    (
+        "workbench.action.files.newUntitledFile",
+        "workspace::NewFile",
+        None,
+    ),
+    ("workbench.action.files.openFile", "workspace::Open", None),
+    ("workbench.action.files.openFolder", "workspace::Open", None),
+    (
+        "workbench.action.closeActiveEditor",
+        "pane::CloseActiveItem",
+        None,
+    ),
+    (
+        "workbench.action.closeAllEditors",
+        "pane::CloseAllItems",
+        None,
+    ),
+    (
+        "workbench.action.closeOtherEditors",
+        "pane::CloseOtherItems",
+        None,
+    ),
+    (
+        "workbench.action.reopenClosedEditor",
+        "pane::ReopenClosedItem",
+        None,
+    ),
+    (
+        "workbench.action.nextEditor",
+        "pane::ActivateNextItem",
+        None,
+    ),
+    (
+        "workbench.action.previousEditor",
+        "pane::ActivatePreviousItem",
+        None,
+    ),
+    ("workbench.action.splitEditor", "pane::SplitRight", None),
+    (
+        "workbench.action.splitEditorRight",
+        "pane::SplitRight",
+        None,
+    ),
+    ("workbench.action.splitEditorDown", "pane::SplitDown", None),
+    ("workbench.action.navigateBack", "pane::GoBack", None),
+    ("workbench.action.navigateForward", "pane::GoForward", None),
+    (
+        "workbench.action.toggleSidebarVisibility",
+        "workspace::ToggleLeftDock",
+        None,
+    ),
+    (
+        "workbench.action.toggleAuxiliaryBar",
+        "workspace::ToggleRightDock",
+        None,
+    ),
+    (
+        "workbench.action.togglePanel",
+        "workspace::ToggleBottomDock",
+        None,
+    ),
+    (
+        "workbench.action.toggleMaximizedPanel",
+        "workspace::ToggleZoom",
+        None,
+    ),
+    (
+        "workbench.action.toggleFullScreen",
+        "zed::ToggleFullScreen",
+        None,
+    ),
+    ("workbench.action.newWindow", "workspace::NewWindow", None),
+    (
+        "workbench.action.closeWindow",
+        "workspace::CloseWindow",
+        None,
+    ),
+    ("workbench.action.openRecent", "projects::OpenRecent", None),
+    ("workbench.action.openSettings", "zed::OpenSettings", None),
+    (
+        "workbench.action.openGlobalKeybindings",
+        "zed::OpenKeymap",
+        None,
+    ),
+    (
+        "workbench.action.selectTheme",
+        "theme_selector::Toggle",
+        None,
+    ),
+    (
+        "workbench.action.zoomIn",
+        "zed::IncreaseBufferFontSize",
+        None,
+    ),
+    (
+        "workbench.action.zoomOut",
+        "zed::DecreaseBufferFontSize",
+        None,
+    ),
+    (
+        "workbench.action.zoomReset",
+        "zed::ResetBufferFontSize",
+        None,
+    ),
+    ("workbench.action.findInFiles", "pane::DeploySearch", None),
+    ("workbench.action.tasks.runTask", "task::Spawn", None),
+    ("workbench.action.debug.start", "debugger::Start", None),
+    ("workbench.action.quit", "zed::Quit", None),
+    ("workbench.action.chat.open", "agent::ToggleFocus", None),
+    (
+        "workbench.actions.view.problems",
+        "diagnostics::Deploy",
+        None,
+    ),
+    (
+        "workbench.view.explorer",
+        "project_panel::ToggleFocus",
+        None,
+    ),
+    ("workbench.view.search", "pane::DeploySearch", None),
+    ("workbench.view.scm", "git_panel::ToggleFocus", None),
+    ("workbench.view.extensions", "zed::Extensions", None),
+    (
+        "workbench.files.action.showActiveFileInExplorer",
+        "pane::RevealInProjectPanel",
+        None,
+    ),
+    (
+        "workbench.action.editor.nextChange",
+        "editor::GoToHunk",
+        None,
+    ),
+    (
+        "workbench.action.editor.previousChange",
+        "editor::GoToPreviousHunk",
+        None,
+    ),
+    (
+        "workbench.action.terminal.toggleTerminal",
+        "terminal_panel::Toggle",
+        None,
+    ),
+    (
+        "workbench.action.terminal.new",
+        "workspace::NewTerminal",
+        None,
+    ),
+    ("workbench.action.terminal.clear", "terminal::Clear", None),
+    (
+        "workbench.action.terminal.copySelection",
+        "terminal::Copy",
+        None,
+    ),
+    ("workbench.action.terminal.paste", "terminal::Paste", None),
+    ("git.commit", "git::Commit", None),
+    ("markdown.showPreview", "markdown::OpenPreview", None),
+    // Editing
+    ("undo", "editor::Undo", None),
+    ("redo", "editor::Redo", None),
+    ("cursorUndo", "editor::UndoSelection", None),
+    ("editor.action.clipboardCopyAction", "editor::Copy", None),
+    ("editor.action.clipboardCutAction", "editor::Cut", None),
+    ("editor.action.clipboardPasteAction", "editor::Paste", None),
+    ("editor.action.selectAll", "editor::SelectAll", None),
+    ("editor.action.commentLine", "editor::ToggleComments", None),
+    ("editor.action.formatDocument", "editor::Format", None),
+    ("editor.action.rename", "editor::Rename", None),
+    (
+        "editor.action.revealDefinition",
+        "editor::GoToDefinition",
+        None,
+    ),
+    (
+        "editor.action.revealDefinitionAside",
+        "editor::GoToDefinitionSplit",
+        None,
+    ),
+    (
+        "editor.action.goToDeclaration",
+        "editor::GoToDeclaration",
+        None,
+    ),
+    (
+        "editor.action.goToTypeDefinition",
+        "editor::GoToTypeDefinition",
+        None,
+    ),
+    (
+        "editor.action.goToImplementation",
+        "editor::GoToImplementation",
+        None,
+    ),
+    (
+        "editor.action.goToReferences",
+        "editor::FindAllReferences",
+        None,
+    ),
+    (
+        "references-view.findReferences",
+        "editor::FindAllReferences",
+        None,
+    ),
+    ("editor.action.showHover", "editor::Hover", None),
+    ("editor.action.quickFix", "editor::ToggleCodeActions", None),
+    (
+        "editor.action.triggerSuggest",
+        "editor::ShowCompletions",
+        None,
+    ),
+    (
+        "editor.action.triggerParameterHints",
+        "editor::ShowSignatureHelp",
+        None,
+    ),
+    (
+        "acceptSelectedSuggestion",
+        "editor::ConfirmCompletion",
+        None,
+    ),
+    (
+        "editor.action.inlineSuggest.trigger",
+        "editor::ShowEditPrediction",
+        None,
+    ),
+    (
+        "editor.action.inlineSuggest.commit",
+        "editor::AcceptEditPrediction",
+        None,
+    ),
+    (
+        "editor.action.addSelectionToNextFindMatch",
+        "editor::SelectNext",
+        Some(r#"{ "replace_newest": false }"#),
+    ),
+    (
+        "editor.action.moveSelectionToNextFindMatch",
+        "editor::SelectNext",
+        Some(r#"{ "replace_newest": true }"#),
+    ),
+    (
+        "editor.action.selectHighlights",
+        "editor::SelectAllMatches",
+        None,
+    ),
+    (
+        "editor.action.insertCursorAbove",
+        "editor::AddSelectionAbove",
+        None,
+    ),
+    (
+        "editor.action.insertCursorBelow",
+        "editor::AddSelectionBelow",
+        None,
+    ),
+    (
+        "editor.action.insertCursorAtEndOfEachLineSelected",
+        "editor::SplitSelectionIntoLines",
+        None,
+    ),
+    (
+        "editor.action.copyLinesDownAction",
+        "editor::DuplicateLineDown",
+        None,
+    ),
+    (
+        "editor.action.copyLinesUpAction",
+        "editor::DuplicateLineUp",
+        None,
+    ),
+    (
+        "editor.action.moveLinesDownAction",
+        "editor::MoveLineDown",
+        None,
+    ),
+    (
+        "editor.action.moveLinesUpAction",
+        "editor::MoveLineUp",
+        None,
+    ),
+    ("editor.action.deleteLines", "editor::DeleteLine", None),
+    (
+        "editor.action.insertLineAfter",
+        "editor::NewlineBelow",
+        None,
+    ),
+    (
+        "editor.action.insertLineBefore",
+        "editor::NewlineAbove",
+        None,
+    ),
+    ("editor.action.indentLines", "editor::Indent", None),
+    ("editor.action.outdentLines", "editor::Outdent", None),
+    ("editor.action.joinLines", "editor::JoinLines", None),
+    (
+        "editor.action.transformToUppercase",
+        "editor::ConvertToUpperCase",
+        None,
+    ),
+    (
+        "editor.action.transformToLowercase",
+        "editor::ConvertToLowerCase",
+        None,
+    ),
+    (
+        "editor.action.sortLinesAscending",
+        "editor::SortLinesCaseSensitive",
+        None,
+    ),
+    (
+        "editor.action.smartSelect.expand",
+        "editor::SelectLargerSyntaxNode",
+        None,
+    ),
+    (
+        "editor.action.smartSelect.shrink",
+        "editor::SelectSmallerSyntaxNode",
+        None,
+    ),
+    (
+        "editor.action.jumpToBracket",
+        "editor::MoveToEnclosingBracket",
+        None,
+    ),
+    ("editor.action.marker.next", "editor::GoToDiagnostic", None),
+    (
+        "editor.action.marker.prev",
+        "editor::GoToPreviousDiagnostic",
+        None,
+    ),
+    (
+        "editor.action.toggleWordWrap",
+        "editor::ToggleSoftWrap",
+        None,
+    ),
+    ("editor.fold", "editor::Fold", None),
+    ("editor.unfold", "editor::UnfoldLines", None),
+    ("editor.toggleFold", "editor::ToggleFold", None),
+    ("editor.foldRecursively", "editor::FoldRecursive", None),
+    ("editor.foldAll", "editor::FoldAll", None),
+    ("editor.unfoldAll", "editor::UnfoldAll", None),
+    ("expandLineSelection", "editor::SelectLine", None),
+    ("cursorHome", "editor::MoveToBeginningOfLine", None),
+    ("cursorLineStart", "editor::MoveToBeginningOfLine", None),
+    ("cursorEnd", "editor::MoveToEndOfLine", None),
+    ("cursorLineEnd", "editor::MoveToEndOfLine", None),
+    ("cursorHomeSelect", "editor::SelectToBeginningOfLine", None),
+    ("cursorEndSelect", "editor::SelectToEndOfLine", None),
+    ("cursorTop", "editor::MoveToBeginning", None),
+    ("cursorBottom", "editor::MoveToEnd", None),
+    ("cursorWordLeft", "editor::MoveToPreviousWordStart", None),
+    ("cursorWordRight", "editor::MoveToNextWordEnd", None),
+    ("deleteLeft", "editor::Backspace", None),
+    ("deleteRight", "editor::Delete", None),
+    ("deleteWordLeft", "editor::DeleteToPreviousWordStart", None),
+    ("deleteWordRight", "editor::DeleteToNextWordEnd", None),
+    ("deleteAllLeft", "editor::DeleteToBeginningOfLine", None),
+    ("scrollLineUp", "editor::LineUp", None),
+    ("scrollLineDown", "editor::LineDown", None),
+    // Search
+    ("actions.find", "buffer_search::Deploy", None),
+    (
+        "editor.action.startFindReplaceAction",
+        "buffer_search::DeployReplace",
+        None,
+    ),
+    (
+        "editor.action.nextMatchFindAction",
+        "search::SelectNextMatch",
+        None,
+    ),
+    (
+        "editor.action.previousMatchFindAction",
+        "search::SelectPreviousMatch",
+        None,
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn translate_when(source: &str) -> Result<Option<String>, String> {
+        let clause = WhenClause::parse(source)?;
+        Ok(translate_when_clause(&clause)?.map(|context| context.to_string()))
+    }
+
+    #[test]
+    fn test_translate_keystrokes() {
+        assert_eq!(
+            translate_keystrokes("ctrl+shift+p").as_deref(),
+            Ok("ctrl-shift-p")
+        );
+        assert_eq!(
+            translate_keystrokes("cmd+k cmd+c").as_deref(),
+            Ok("cmd-k cmd-c")
+        );
+        assert_eq!(translate_keystrokes("ctrl+-").as_deref(), Ok("ctrl--"));
+        assert_eq!(translate_keystrokes("ctrl++").as_deref(), Ok("ctrl-+"));
+        assert_eq!(translate_keystrokes("Meta+F12").as_deref(), Ok("super-f12"));
+        assert_eq!(
+            translate_keystrokes("alt+PageDown").as_deref(),
+            Ok("alt-pagedown")
+        );
+        assert_eq!(
+            translate_keystrokes("ctrl+[KeyA]"),
+            Err("Unsupported key `[keya]`".to_string())
+        );
+        assert_eq!(
+            translate_keystrokes("ctrl+numpad_add"),
+            Err("Unsupported key `numpad_add`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_translate_when_clauses() {
+        assert_eq!(translate_when("editorTextFocus"), Ok(Some("Editor".into())));
+        assert_eq!(
+            translate_when("editorTextFocus && editorFocus && suggestWidgetVisible"),
+            Ok(Some("Editor && showing_completions".into()))
+        );
+        assert_eq!(
+            translate_when("terminalFocus || (editorTextFocus && !inSnippetMode)"),
+            Ok(Some("Terminal || Editor && !in_snippet".into()))
+        );
+        assert_eq!(
+            translate_when("!(terminalFocus || editorFocus)"),
+            Ok(Some("!(Terminal || Editor)".into()))
+        );
+        assert_eq!(
+            translate_when("editorTextFocus && resourceExtname == '.rs'"),
+            Ok(Some("Editor && extension == rs".into()))
+        );
+        assert_eq!(
+            translate_when("vim.mode != 'Insert' && isMac"),
+            Ok(Some("!(vim_mode == insert) && os == macos".into()))
+        );
+        assert_eq!(translate_when("editorIsOpen || terminalFocus"), Ok(None));
+        assert_eq!(
+            translate_when("editorTextFocus && !editorReadonly"),
+            Err("No equivalent for the `editorReadonly` condition".into())
+        );
+        assert_eq!(
+            translate_when("resourceFilename =~ /docker/"),
+            Err("The `=~` operator in `when` clauses isn't supported".into())
+        );
+        assert_eq!(
+            translate_when("editorTextFocus &&"),
+            Err("Unexpected end of `when` clause".into())
+        );
+    }
+
+    #[test]
+    fn test_vscode_keymap_import() {
+        let keymap = VsCodeKeymap::from_str(
+            r#"
+            // Place your key bindings in this file to override the defaults
+            [
+                {
+                    "key": "ctrl+shift+d",
+                    "command": "editor.action.copyLinesDownAction",
+                    "when": "editorTextFocus && !editorReadonly"
+                },
+                { "key": "ctrl+d", "command": "editor.action.copyLinesDownAction", "when": "editorTextFocus" },
+                { "key": "ctrl+k ctrl+n", "command": "editor.action.addSelectionToNextFindMatch" },
+                { "key": "ctrl+shift+b", "command": "workbench.action.tasks.runTask", "args": "build" },
+                { "key": "ctrl+e", "command": "workbench.action.terminal.sendSequence", "args": { "text": "exit\n" }, "when": "terminalFocus" },
+                { "key": "ctrl+p", "command": "-workbench.action.quickOpen" },
+                { "key": "ctrl+alt+g", "command": "gitlens.showGraph" },
+                { "key": "ctrl+r", "command": "workbench.action.files.save", "args": { "force": true } },
+                {
+                    "key": "ctrl+s",
+                    "command": "runCommands",
+                    "args": { "commands": ["editor.action.formatDocument", { "command": "workbench.action.files.save" }] },
+                    "when": "editorFocus",
+                },
+                { "command": "missing.key" },
+            ]
+            "#,
+            VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+
+        let imported = keymap
+            .imported
+            .iter()
+            .map(|binding| {
+                (
+                    binding.keystrokes.as_str(),
+                    binding.context.as_deref(),
+                    binding.action.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            imported,
+            [
+                ("ctrl-d", Some("Editor"), json!("editor::DuplicateLineDown")),
+                (
+                    "ctrl-k ctrl-n",
+                    None,
+                    json!(["editor::SelectNext", { "replace_newest": false }])
+                ),
+                (
+                    "ctrl-shift-b",
+                    None,
+                    json!(["task::Spawn", { "task_name": "build" }])
+                ),
+                (
+                    "ctrl-e",
+                    Some("Terminal"),
+                    json!(["terminal::SendText", "exit\n"])
+                ),
+                (
+                    "ctrl-s",
+                    Some("Editor"),
+                    json!(["action::Sequence", ["editor::Format", "workspace::Save"]])
+                ),
+            ]
+        );
+        assert_eq!(keymap.imported[1].action_name(), "editor::SelectNext");
+
+        let skipped = keymap
+            .skipped
+            .iter()
+            .map(|binding| (binding.original.key.as_str(), binding.reason.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            [
+                (
+                    "ctrl+shift+d",
+                    "No equivalent for the `editorReadonly` condition"
+                ),
+                ("ctrl+p", "Removes one of VS Code's default keybindings"),
+                ("ctrl+alt+g", "No equivalent for `gitlens.showGraph`"),
+                (
+                    "ctrl+r",
+                    "Arguments to `workbench.action.files.save` aren't supported"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_append_to_keymap() {
+        let keymap = VsCodeKeymap::from_str(
+            r#"[
+                { "key": "ctrl+d", "command": "editor.action.copyLinesDownAction", "when": "editorTextFocus" },
+                { "key": "ctrl+shift+p", "command": "workbench.action.showCommands" },
+                { "key": "ctrl+d", "command": "editor.action.deleteLines", "when": "editorTextFocus" },
+                { "key": "ctrl+j", "command": "editor.action.joinLines", "when": "editorTextFocus" },
+            ]"#,
+            VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+
+        let existing = r#"[
+  {
+    "context": "Workspace",
+    "bindings": {
+      "ctrl-q": "zed::Quit"
+    }
+  }
+]"#;
+        let contents = keymap.append_to_keymap(existing.to_string(), 2).unwrap();
+        let sections = KeymapFile::parse(&contents)
+            .unwrap()
+            .sections()
+            .map(|section| {
+                (
+                    section.context.clone(),
+                    section
+                        .bindings()
+                        .map(|(keystrokes, action)| format!("{keystrokes}: {action}"))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sections,
+            [
+                (
+                    "Workspace".to_string(),
+                    vec!["ctrl-q: zed::Quit".to_string()]
+                ),
+                (
+                    "Editor".to_string(),
+                    vec![
+                        "ctrl-d: editor::DeleteLine".to_string(),
+                        "ctrl-j: editor::JoinLines".to_string(),
+                    ]
+                ),
+                (
+                    String::new(),
+                    vec!["ctrl-shift-p: command_palette::Toggle".to_string()]
+                ),
+            ]
+        );
+
+        assert!(keymap.append_to_keymap("[{".to_string(), 2).is_err());
+    }
+}
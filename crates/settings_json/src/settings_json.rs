@@ -497,6 +497,46 @@ pub fn replace_top_level_array_value_in_json_text(
     }
 }
 
+/// Returns the byte range of the key at `key_path` within the object at `array_index` of the
+/// top-level array, if it exists.
+pub fn top_level_array_value_key_range(
+    text: &str,
+    array_index: usize,
+    key_path: &[impl AsRef<str>],
+) -> Option<Range<usize>> {
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&tree_sitter_json::LANGUAGE.into())
+        .unwrap();
+    let syntax_tree = parser.parse(text, None)?;
+
+    let mut cursor = syntax_tree.walk();
+    let array = syntax_tree
+        .root_node()
+        .named_children(&mut cursor)
+        .find(|node| node.kind() == TS_ARRAY_KIND)?;
+    let mut node = array
+        .named_children(&mut cursor)
+        .filter(|node| node.kind() != TS_COMMENT_KIND && !node.is_extra() && !node.is_missing())
+        .nth(array_index)?;
+
+    let mut key_range = None;
+    for key in key_path {
+        let key = serde_json::to_string(key.as_ref()).ok()?;
+        let pair = node
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "pair")
+            .find(|pair| {
+                pair.child_by_field_name("key")
+                    .and_then(|key_node| text.get(key_node.byte_range()))
+                    == Some(key.as_str())
+            })?;
+        key_range = Some(pair.child_by_field_name("key")?.byte_range());
+        node = pair.child_by_field_name("value")?;
+    }
+    key_range
+}
+
 pub fn append_top_level_array_value_in_json_text(
     text: &str,
     new_value: &Value,
@@ -2632,4 +2672,37 @@ mod tests {
 }"#;
         assert_eq!(infer_json_indent_size(json_mixed), 2);
     }
+
+    #[test]
+    fn test_top_level_array_value_key_range() {
+        let text = r#"[
+            // Comments are skipped
+            { "bindings": { "ctrl-a": "a" } },
+            {
+                "context": "Editor",
+                "bindings": {
+                    "ctrl-b": "b",
+                    "ctrl-k ctrl-c": "c"
+                }
+            }
+        ]"#
+        .unindent();
+
+        let range = top_level_array_value_key_range(&text, 1, &["bindings", "ctrl-k ctrl-c"])
+            .expect("key exists");
+        assert_eq!(&text[range], r#""ctrl-k ctrl-c""#);
+
+        let range =
+            top_level_array_value_key_range(&text, 0, &["bindings", "ctrl-a"]).expect("key exists");
+        assert_eq!(&text[range], r#""ctrl-a""#);
+
+        assert_eq!(
+            top_level_array_value_key_range(&text, 0, &["bindings", "ctrl-b"]),
+            None
+        );
+        assert_eq!(
+            top_level_array_value_key_range(&text, 2, &["bindings", "ctrl-a"]),
+            None
+        );
+    }
 }
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use call::ActiveCall;
+use chrono::Timelike as _;
+use editor::Editor;
+use gpui::{
+    Context, Entity, IntoElement, ParentElement, Render, SharedString, Subscription, Task,
+    WeakEntity, Window, WindowAppearance, div,
+};
+use project::Project;
+use settings::{ProfileAppearanceContent, SettingsProfileContext, SettingsStore};
+use ui::{Button, ButtonCommon, Clickable, LabelSize, Tooltip, prelude::*};
+use workspace::{StatusItemView, Workspace, item::ItemHandle};
+
+use crate::toggle_settings_profile_selector;
+
+/// Shows the settings profiles that are applied in the status bar.
+///
+/// While its window is active, it also keeps the [`SettingsProfileContext`]
+/// that `profile_triggers` are matched against up to date.
+pub struct ActiveSettingsProfile {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    active_language: Option<SharedString>,
+    _observe_active_editor: Option<Subscription>,
+    _observe_room: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+    _update_time_of_day: Task<()>,
+}
+
+impl ActiveSettingsProfile {
+    pub fn new(workspace: &Workspace, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut subscriptions =
+            vec![
+                cx.observe_window_activation(window, |this, window, cx| {
+                    this.update_context(window, cx);
+                }),
+                cx.observe_window_appearance(window, |this, window, cx| {
+                    this.update_context(window, cx);
+                }),
+                cx.subscribe_in(workspace.project(), window, |this, _, event, window, cx| {
+                    match event {
+                        project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_) => {
+                            this.update_context(window, cx);
+                        }
+                        _ => {}
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+            ];
+        if let Some(active_call) = ActiveCall::try_global(cx) {
+            subscriptions.push(cx.observe_in(
+                &active_call,
+                window,
+                |this, active_call, window, cx| {
+                    this._observe_room = active_call.read(cx).room().map(|room| {
+                        cx.observe_in(room, window, |this, _, window, cx| {
+                            this.update_context(window, cx);
+                        })
+                    });
+                    this.update_context(window, cx);
+                },
+            ));
+        }
+
+        let update_time_of_day = cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(Duration::from_secs(60))
+                    .await;
+                if this
+                    .update_in(cx, |this, window, cx| this.update_context(window, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            workspace: workspace.weak_handle(),
+            project: workspace.project().clone(),
+            active_language: None,
+            _observe_active_editor: None,
+            _observe_room: None,
+            _subscriptions: subscriptions,
+            _update_time_of_day: update_time_of_day,
+        }
+    }
+
+    fn update_language(
+        &mut self,
+        editor: Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let language = editor
+            .read(cx)
+            .active_excerpt(cx)
+            .and_then(|(_, buffer, _)| Some(buffer.read(cx).language()?.name().0));
+        if self.active_language != language {
+            self.active_language = language;
+            self.update_context(window, cx);
+        }
+    }
+
+    fn update_context(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !window.is_window_active() {
+            return;
+        }
+        let worktree_paths = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .collect();
+        let screen_sharing = ActiveCall::try_global(cx)
+            .and_then(|active_call| active_call.read(cx).room().cloned())
+            .is_some_and(|room| room.read(cx).is_sharing_screen());
+        let appearance = match window.appearance() {
+            WindowAppearance::Light | WindowAppearance::VibrantLight => {
+                ProfileAppearanceContent::Light
+            }
+            WindowAppearance::Dark | WindowAppearance::VibrantDark => {
+                ProfileAppearanceContent::Dark
+            }
+        };
+        let now = chrono::Local::now();
+
+        let context = SettingsProfileContext {
+            worktree_paths,
+            language: self.active_language.clone(),
+            screen_sharing,
+            appearance: Some(appearance),
+            minutes_since_midnight: Some(now.hour() * 60 + now.minute()),
+        };
+        if cx.try_global::<SettingsProfileContext>() != Some(&context) {
+            cx.set_global(context);
+        }
+    }
+}
+
+impl Render for ActiveSettingsProfile {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let active_profiles = cx
+            .global::<SettingsStore>()
+            .active_settings_profiles(cx)
+            .join(", ");
+
+        div().when(!active_profiles.is_empty(), |el| {
+            el.child(
+                Button::new("active-settings-profile", active_profiles)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(workspace) = this.workspace.upgrade() {
+                            workspace.update(cx, |workspace, cx| {
+                                toggle_settings_profile_selector(workspace, window, cx)
+                            });
+                        }
+                    }))
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action(
+                            "Active Settings Profiles",
+                            &zed_actions::settings_profile_selector::Toggle,
+                            cx,
+                        )
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveSettingsProfile {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor =
+                Some(cx.observe_in(&editor, window, Self::update_language));
+            self.update_language(editor, window, cx);
+        } else {
+            self.active_language = None;
+            self._observe_active_editor = None;
+        }
+
+        self.update_context(window, cx);
+        cx.notify();
+    }
+}
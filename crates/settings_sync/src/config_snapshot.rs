@@ -0,0 +1,297 @@
+use anyhow::{Context as _, Result};
+use collections::{BTreeMap, BTreeSet};
+use fs::Fs;
+use futures::StreamExt as _;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The file in the sync folder that lists the installed extensions.
+pub const EXTENSIONS_FILE: &str = "extensions.json";
+
+const SYNCED_FILES: &[&str] = &["settings.json", "keymap.json"];
+const SYNCED_DIRECTORIES: &[&str] = &["snippets", "themes"];
+
+/// The contents of the synced configuration files, keyed by their path
+/// relative to the config directory or the sync folder.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConfigSnapshot(pub BTreeMap<String, String>);
+
+impl ConfigSnapshot {
+    /// Loads the settings, keymap, snippets and themes stored in `dir`.
+    pub async fn load(fs: &dyn Fs, dir: &Path) -> Result<Self> {
+        let mut files = BTreeMap::default();
+        for name in SYNCED_FILES {
+            if let Some(contents) = load_file(fs, &dir.join(name)).await? {
+                files.insert(name.to_string(), contents);
+            }
+        }
+
+        let mut pending_directories = SYNCED_DIRECTORIES
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        while let Some(relative_dir) = pending_directories.pop() {
+            let abs_dir = dir.join(&relative_dir);
+            if !fs.is_dir(&abs_dir).await {
+                continue;
+            }
+            let mut entries = fs.read_dir(&abs_dir).await?;
+            while let Some(entry) = entries.next().await {
+                let entry = entry?;
+                let Some(file_name) = entry.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                if file_name.starts_with('.') {
+                    continue;
+                }
+                let relative_path = format!("{relative_dir}/{file_name}");
+                if fs.is_dir(&entry).await {
+                    pending_directories.push(relative_path);
+                } else if let Some(contents) = load_file(fs, &entry).await? {
+                    files.insert(relative_path, contents);
+                }
+            }
+        }
+
+        Ok(Self(files))
+    }
+
+    /// Records the ids of the installed extensions.
+    pub fn set_extensions(&mut self, extension_ids: impl IntoIterator<Item = impl Into<String>>) {
+        let extension_ids = extension_ids
+            .into_iter()
+            .map(Into::into)
+            .collect::<BTreeSet<String>>();
+        let mut contents = serde_json::to_string_pretty(&extension_ids).unwrap_or_default();
+        contents.push('\n');
+        self.0.insert(EXTENSIONS_FILE.to_string(), contents);
+    }
+
+    /// Returns the ids of the extensions recorded in this snapshot, if any.
+    pub fn extensions(&self) -> Option<BTreeSet<String>> {
+        let contents = self.0.get(EXTENSIONS_FILE)?;
+        serde_json::from_str(contents).ok()
+    }
+
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.0.get(path).map(String::as_str)
+    }
+
+    /// Sets the contents of a file, or removes it if `contents` is `None`.
+    pub fn set(&mut self, path: &str, contents: Option<&str>) {
+        match contents {
+            Some(contents) => {
+                self.0.insert(path.to_string(), contents.to_string());
+            }
+            None => {
+                self.0.remove(path);
+            }
+        }
+    }
+}
+
+async fn load_file(fs: &dyn Fs, path: &Path) -> Result<Option<String>> {
+    if !fs.is_file(path).await {
+        return Ok(None);
+    }
+    fs.load(path)
+        .await
+        .with_context(|| format!("loading {path:?}"))
+        .map(Some)
+}
+
+/// How a synced file differs between this machine and the sync folder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileStatus {
+    /// Changed on this machine since the last sync.
+    Local,
+    /// Changed in the sync folder since the last sync.
+    Incoming,
+    /// Changed both on this machine and in the sync folder.
+    Conflict,
+}
+
+/// Compares this machine's files with the sync folder, using the files as
+/// of the last sync to tell which side changed.
+///
+/// Files that are the same on both sides are omitted.
+pub fn compare(
+    local: &ConfigSnapshot,
+    remote: &ConfigSnapshot,
+    baseline: &ConfigSnapshot,
+) -> BTreeMap<String, FileStatus> {
+    let paths = local
+        .0
+        .keys()
+        .chain(remote.0.keys())
+        .collect::<BTreeSet<_>>();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let local = local.get(path);
+            let remote = remote.get(path);
+            let baseline = baseline.get(path);
+            let status = if local == remote {
+                return None;
+            } else if remote == baseline {
+                FileStatus::Local
+            } else if local == baseline {
+                FileStatus::Incoming
+            } else {
+                FileStatus::Conflict
+            };
+            Some((path.clone(), status))
+        })
+        .collect()
+}
+
+/// The files as they were after the last sync with a folder.
+///
+/// This is stored on each machine rather than in the sync folder, as each
+/// machine syncs at different times.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncBaseline {
+    pub folder: PathBuf,
+    pub files: ConfigSnapshot,
+}
+
+impl SyncBaseline {
+    /// Loads the baseline for `folder`, which is empty if this machine
+    /// hasn't synced with it yet.
+    pub async fn load(fs: &dyn Fs, path: &Path, folder: &Path) -> Self {
+        let baseline = match fs.load(path).await {
+            Ok(contents) => serde_json::from_str::<Self>(&contents)
+                .inspect_err(|error| log::error!("invalid settings sync state: {error}"))
+                .ok(),
+            Err(_) => None,
+        };
+        baseline
+            .filter(|baseline| baseline.folder == folder)
+            .unwrap_or_else(|| Self {
+                folder: folder.to_path_buf(),
+                files: ConfigSnapshot::default(),
+            })
+    }
+
+    pub async fn save(&self, fs: &dyn Fs, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs.create_dir(parent).await?;
+        }
+        fs.atomic_write(path.to_path_buf(), serde_json::to_string_pretty(self)?)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use util::path;
+
+    fn snapshot(files: &[(&str, &str)]) -> ConfigSnapshot {
+        ConfigSnapshot(
+            files
+                .iter()
+                .map(|(path, contents)| (path.to_string(), contents.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = snapshot(&[
+            ("settings.json", "a"),
+            ("keymap.json", "a"),
+            ("snippets/rust.json", "a"),
+            ("themes/dark.json", "a"),
+        ]);
+        let local = snapshot(&[
+            ("settings.json", "b"),
+            ("keymap.json", "a"),
+            ("snippets/rust.json", "b"),
+            ("snippets/go.json", "a"),
+        ]);
+        let remote = snapshot(&[
+            ("settings.json", "a"),
+            ("keymap.json", "c"),
+            ("snippets/rust.json", "c"),
+            ("themes/dark.json", "a"),
+        ]);
+
+        assert_eq!(
+            compare(&local, &remote, &baseline),
+            BTreeMap::from_iter([
+                ("keymap.json".to_string(), FileStatus::Incoming),
+                ("settings.json".to_string(), FileStatus::Local),
+                ("snippets/go.json".to_string(), FileStatus::Local),
+                ("snippets/rust.json".to_string(), FileStatus::Conflict),
+                ("themes/dark.json".to_string(), FileStatus::Local),
+            ])
+        );
+
+        // Without a baseline, every difference is a conflict, unless one side
+        // doesn't have the file yet.
+        let baseline = ConfigSnapshot::default();
+        let local = snapshot(&[("settings.json", "a"), ("keymap.json", "a")]);
+        let remote = snapshot(&[("settings.json", "b"), ("snippets/rust.json", "a")]);
+        assert_eq!(
+            compare(&local, &remote, &baseline),
+            BTreeMap::from_iter([
+                ("keymap.json".to_string(), FileStatus::Local),
+                ("settings.json".to_string(), FileStatus::Conflict),
+                ("snippets/rust.json".to_string(), FileStatus::Incoming),
+            ])
+        );
+    }
+
+    #[gpui::test]
+    async fn test_load_snapshot(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/config"),
+            json!({
+                "settings.json": "{}",
+                "keymap.json": "[]",
+                "tasks.json": "[]",
+                "snippets": {
+                    "rust.json": "{}",
+                    ".DS_Store": "",
+                    "web": { "html.json": "{}" }
+                },
+                "themes": { "dark.json": "{}" },
+                "prompts": { "prompt.md": "" }
+            }),
+        )
+        .await;
+
+        let mut snapshot = ConfigSnapshot::load(fs.as_ref(), Path::new(path!("/config")))
+            .await
+            .unwrap();
+        assert_eq!(
+            snapshot.0.keys().collect::<Vec<_>>(),
+            [
+                "keymap.json",
+                "settings.json",
+                "snippets/rust.json",
+                "snippets/web/html.json",
+                "themes/dark.json",
+            ]
+        );
+
+        snapshot.set_extensions(["toml", "html"]);
+        assert_eq!(
+            snapshot.get(EXTENSIONS_FILE),
+            Some("[\n  \"html\",\n  \"toml\"\n]\n")
+        );
+        assert_eq!(
+            snapshot.extensions(),
+            Some(BTreeSet::from_iter([
+                "html".to_string(),
+                "toml".to_string()
+            ]))
+        );
+    }
+}
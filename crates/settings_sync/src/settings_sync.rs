@@ -0,0 +1,89 @@
+//! Syncs the user's settings, keymap, snippets, themes and installed
+//! extensions between machines, through a folder that's either a git
+//! repository or shared some other way.
+
+mod config_snapshot;
+mod sync_folder;
+
+pub use config_snapshot::{ConfigSnapshot, EXTENSIONS_FILE, FileStatus, compare};
+pub use sync_folder::{SyncFolder, SyncStatus};
+
+use gpui::actions;
+use settings::{RegisterSetting, Settings};
+use std::path::PathBuf;
+
+actions!(
+    settings_sync,
+    [
+        /// Copies the settings, keymap, snippets, themes and extensions
+        /// changed on this machine into the sync folder.
+        Push,
+        /// Reviews and applies the changes made in the sync folder by other
+        /// machines.
+        Pull,
+    ]
+);
+
+/// Settings for syncing user configuration between machines.
+#[derive(Clone, Debug, RegisterSetting)]
+pub struct SettingsSyncSettings {
+    /// The folder to sync with.
+    ///
+    /// Default: null
+    pub path: Option<PathBuf>,
+    /// A git repository URL to clone into `path` if it doesn't exist yet.
+    ///
+    /// Default: null
+    pub repository: Option<String>,
+}
+
+impl Settings for SettingsSyncSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let settings_sync = content.settings_sync.clone().unwrap();
+        Self {
+            path: settings_sync
+                .path
+                .map(|path| PathBuf::from(shellexpand::tilde(&path).as_ref())),
+            repository: settings_sync.repository,
+        }
+    }
+}
+
+/// Describes the incoming and conflicting changes, one per line.
+pub fn describe_changes(status: &SyncStatus, incoming: &[String], conflicts: &[String]) -> String {
+    let mut lines = Vec::new();
+    for path in incoming.iter().chain(conflicts) {
+        if path == EXTENSIONS_FILE {
+            continue;
+        }
+        let change = match (status.local.get(path), status.remote.get(path)) {
+            (None, _) => "added",
+            (_, None) => "removed",
+            _ => "changed",
+        };
+        if conflicts.contains(path) {
+            lines.push(format!("{path} ({change}, also changed on this machine)"));
+        } else {
+            lines.push(format!("{path} ({change})"));
+        }
+    }
+
+    if incoming
+        .iter()
+        .chain(conflicts)
+        .any(|path| path == EXTENSIONS_FILE)
+    {
+        let local = status.local.extensions().unwrap_or_default();
+        let remote = status.remote.extensions().unwrap_or_default();
+        let installed = remote.difference(&local).cloned().collect::<Vec<_>>();
+        let uninstalled = local.difference(&remote).cloned().collect::<Vec<_>>();
+        if !installed.is_empty() {
+            lines.push(format!("Install extensions: {}", installed.join(", ")));
+        }
+        if !uninstalled.is_empty() {
+            lines.push(format!("Uninstall extensions: {}", uninstalled.join(", ")));
+        }
+    }
+
+    lines.join("\n")
+}
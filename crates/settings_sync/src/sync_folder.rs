@@ -0,0 +1,417 @@
+use crate::config_snapshot::{ConfigSnapshot, EXTENSIONS_FILE, FileStatus, SyncBaseline, compare};
+use anyhow::{Context as _, Result};
+use collections::{BTreeMap, HashMap};
+use fs::{Fs, RemoveOptions, RenameOptions};
+use futures::StreamExt as _;
+use git::repository::{AskPassDelegate, CommitOptions, GitRepository, RepoPath};
+use gpui::AsyncApp;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// A folder that user configuration is synced through, which may be a git
+/// repository.
+pub struct SyncFolder {
+    fs: Arc<dyn Fs>,
+    path: PathBuf,
+    config_dir: PathBuf,
+    baseline_path: PathBuf,
+    repository: Option<Arc<dyn GitRepository>>,
+}
+
+/// The differences between this machine and the sync folder.
+pub struct SyncStatus {
+    pub local: ConfigSnapshot,
+    pub remote: ConfigSnapshot,
+    pub changes: BTreeMap<String, FileStatus>,
+    baseline: SyncBaseline,
+}
+
+impl SyncStatus {
+    pub fn paths(&self, status: FileStatus) -> impl Iterator<Item = &str> {
+        self.changes
+            .iter()
+            .filter(move |(_, file_status)| **file_status == status)
+            .map(|(path, _)| path.as_str())
+    }
+}
+
+impl SyncFolder {
+    /// Opens the sync folder at `path`, cloning `repository_url` into it or
+    /// creating it if it doesn't exist yet.
+    pub async fn open(
+        fs: Arc<dyn Fs>,
+        path: PathBuf,
+        repository_url: Option<&str>,
+        config_dir: PathBuf,
+        baseline_path: PathBuf,
+    ) -> Result<Self> {
+        if !fs.is_dir(&path).await {
+            match repository_url {
+                Some(url) => clone_repository(fs.as_ref(), url, &path).await?,
+                None => fs
+                    .create_dir(&path)
+                    .await
+                    .with_context(|| format!("creating sync folder {path:?}"))?,
+            }
+        }
+
+        let dot_git = path.join(".git");
+        let repository = if fs.is_dir(&dot_git).await {
+            // Git is found on the `PATH` when it's run.
+            Some(
+                fs.open_repo(&dot_git, Some(Path::new("git")))
+                    .with_context(|| format!("opening repository at {dot_git:?}"))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            fs,
+            path,
+            config_dir,
+            baseline_path,
+            repository,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_repository(&self) -> bool {
+        self.repository.is_some()
+    }
+
+    /// Pulls the changes pushed from other machines, if the sync folder is a
+    /// git repository with a remote.
+    pub async fn pull_repository(&self, cx: &mut AsyncApp) -> Result<()> {
+        let Some((repository, branch, remote)) = self.upstream().await? else {
+            return Ok(());
+        };
+        let askpass = AskPassDelegate::new(cx, |_, _, _| {});
+        repository
+            .pull(Some(branch), remote, true, askpass, git_env(), cx.clone())
+            .await
+            .context("pulling the settings sync repository")?;
+        Ok(())
+    }
+
+    /// Compares this machine's configuration with the sync folder.
+    ///
+    /// `installed_extensions` is only compared when it's given.
+    pub async fn status(&self, installed_extensions: Option<Vec<String>>) -> Result<SyncStatus> {
+        let fs = self.fs.as_ref();
+        let mut local = ConfigSnapshot::load(fs, &self.config_dir).await?;
+        let mut remote = ConfigSnapshot::load(fs, &self.path).await?;
+        let extensions_path = self.path.join(EXTENSIONS_FILE);
+        if let Some(installed_extensions) = installed_extensions {
+            local.set_extensions(installed_extensions);
+            if fs.is_file(&extensions_path).await {
+                let contents = fs.load(&extensions_path).await?;
+                remote.set(EXTENSIONS_FILE, Some(&contents));
+            }
+        }
+
+        let mut baseline = SyncBaseline::load(fs, &self.baseline_path, &self.path).await;
+        let changes = compare(&local, &remote, &baseline.files);
+        // Files that are the same on both sides are in sync, even if they
+        // were changed in the same way on each side.
+        for (path, contents) in &local.0 {
+            if remote.get(path) == Some(contents.as_str()) {
+                baseline.files.set(path, Some(contents));
+            }
+        }
+
+        Ok(SyncStatus {
+            local,
+            remote,
+            changes,
+            baseline,
+        })
+    }
+
+    /// Copies the files changed on this machine into the sync folder, and
+    /// commits and pushes them if it's a git repository.
+    ///
+    /// Returns the paths of the files that were pushed.
+    pub async fn push(&self, status: SyncStatus, cx: &mut AsyncApp) -> Result<Vec<String>> {
+        let SyncStatus {
+            local,
+            changes,
+            mut baseline,
+            ..
+        } = status;
+        let fs = self.fs.as_ref();
+
+        let mut pushed = Vec::new();
+        for (path, _) in changes
+            .iter()
+            .filter(|(_, status)| **status == FileStatus::Local)
+        {
+            let contents = local.get(path);
+            write_file(fs, &self.path.join(path), contents).await?;
+            baseline.files.set(path, contents);
+            pushed.push(path.clone());
+        }
+
+        if let Some(repository) = &self.repository
+            && !pushed.is_empty()
+        {
+            let paths = pushed
+                .iter()
+                .map(RepoPath::new)
+                .collect::<Result<Vec<_>>>()?;
+            repository.stage_paths(paths, git_env()).await?;
+            let message = match settings::machine_name() {
+                Some(machine_name) => format!("Sync settings from {machine_name}"),
+                None => "Sync settings".to_string(),
+            };
+            let askpass = AskPassDelegate::new(cx, |_, _, _| {});
+            repository
+                .commit(
+                    message.into(),
+                    None,
+                    CommitOptions::default(),
+                    askpass,
+                    git_env(),
+                )
+                .await
+                .context("committing to the settings sync repository")?;
+        }
+        baseline.save(fs, &self.baseline_path).await?;
+
+        // Push even when nothing was committed, in case an earlier push failed.
+        if let Some((repository, branch, remote)) = self.upstream().await? {
+            let askpass = AskPassDelegate::new(cx, |_, _, _| {});
+            repository
+                .push(branch, remote, None, askpass, git_env(), cx.clone())
+                .await
+                .context("pushing the settings sync repository")?;
+        }
+        Ok(pushed)
+    }
+
+    /// Copies the given files from the sync folder onto this machine.
+    ///
+    /// The extensions list isn't written anywhere, as extensions are
+    /// installed through the extension store instead.
+    pub async fn apply(&self, status: SyncStatus, paths: &[String]) -> Result<()> {
+        let SyncStatus {
+            remote,
+            mut baseline,
+            ..
+        } = status;
+        let fs = self.fs.as_ref();
+        for path in paths {
+            let contents = remote.get(path);
+            if path != EXTENSIONS_FILE {
+                write_file(fs, &self.config_dir.join(path), contents).await?;
+            }
+            baseline.files.set(path, contents);
+        }
+        baseline.save(fs, &self.baseline_path).await
+    }
+
+    async fn upstream(&self) -> Result<Option<(&Arc<dyn GitRepository>, String, String)>> {
+        let Some(repository) = &self.repository else {
+            return Ok(None);
+        };
+        let Some(branch) = repository
+            .branches()
+            .await?
+            .into_iter()
+            .find(|branch| branch.is_head)
+        else {
+            return Ok(None);
+        };
+        let branch = branch.name().to_string();
+        let remote = match repository.get_branch_remote(branch.clone()).await? {
+            Some(remote) => Some(remote),
+            None => repository.get_all_remotes().await?.into_iter().next(),
+        };
+        Ok(remote.map(|remote| (repository, branch, remote.name.to_string())))
+    }
+}
+
+fn git_env() -> Arc<HashMap<String, String>> {
+    Arc::new(HashMap::default())
+}
+
+async fn write_file(fs: &dyn Fs, path: &Path, contents: Option<&str>) -> Result<()> {
+    match contents {
+        Some(contents) => {
+            if let Some(parent) = path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            fs.atomic_write(path.to_path_buf(), contents.to_string())
+                .await
+                .with_context(|| format!("writing {path:?}"))
+        }
+        None => fs
+            .remove_file(
+                path,
+                RemoveOptions {
+                    ignore_if_not_exists: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .with_context(|| format!("removing {path:?}")),
+    }
+}
+
+/// Clones `url` into `path`.
+///
+/// `git clone` names the checkout after the repository, so it's cloned
+/// into an empty directory first and then moved into place.
+async fn clone_repository(fs: &dyn Fs, url: &str, path: &Path) -> Result<()> {
+    let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+        anyhow::bail!("invalid sync folder {path:?}");
+    };
+    let mut staging_name = file_name.to_os_string();
+    staging_name.push(".clone");
+    let staging_dir = parent.join(staging_name);
+    fs.create_dir(&staging_dir).await?;
+
+    let result = async {
+        fs.git_clone(url, &staging_dir)
+            .await
+            .with_context(|| format!("cloning {url}"))?;
+        let checkout = fs
+            .read_dir(&staging_dir)
+            .await?
+            .next()
+            .await
+            .context("git clone didn't create a checkout")??;
+        fs.rename(&checkout, path, RenameOptions::default()).await
+    }
+    .await;
+
+    fs.remove_dir(
+        &staging_dir,
+        RemoveOptions {
+            recursive: true,
+            ignore_if_not_exists: true,
+        },
+    )
+    .await
+    .ok();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use util::path;
+
+    async fn open_folder(fs: &Arc<FakeFs>) -> SyncFolder {
+        SyncFolder::open(
+            fs.clone(),
+            PathBuf::from(path!("/sync")),
+            None,
+            PathBuf::from(path!("/config")),
+            PathBuf::from(path!("/data/settings_sync/baseline.json")),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[gpui::test]
+    async fn test_push_and_apply(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/config"),
+            json!({
+                "settings.json": "{ \"vim_mode\": true }",
+                "keymap.json": "[]",
+                "snippets": { "rust.json": "{}" },
+            }),
+        )
+        .await;
+
+        let folder = open_folder(&fs).await;
+        assert!(!folder.is_repository());
+        let status = folder.status(Some(vec!["html".into()])).await.unwrap();
+        assert_eq!(
+            status.paths(FileStatus::Local).collect::<Vec<_>>(),
+            [
+                "extensions.json",
+                "keymap.json",
+                "settings.json",
+                "snippets/rust.json"
+            ]
+        );
+        let pushed = folder.push(status, &mut cx.to_async()).await.unwrap();
+        assert_eq!(pushed.len(), 4);
+        assert_eq!(
+            fs.load(path!("/sync/settings.json").as_ref())
+                .await
+                .unwrap(),
+            "{ \"vim_mode\": true }"
+        );
+
+        // Another machine changes the keymap and removes a snippet, while
+        // this one changes its settings.
+        fs.write(path!("/sync/keymap.json").as_ref(), b"[{}]")
+            .await
+            .unwrap();
+        fs.remove_file(
+            path!("/sync/snippets/rust.json").as_ref(),
+            RemoveOptions::default(),
+        )
+        .await
+        .unwrap();
+        fs.write(path!("/config/settings.json").as_ref(), b"{}")
+            .await
+            .unwrap();
+
+        let folder = open_folder(&fs).await;
+        let status = folder.status(Some(vec!["html".into()])).await.unwrap();
+        assert_eq!(
+            status.changes,
+            BTreeMap::from_iter([
+                ("keymap.json".to_string(), FileStatus::Incoming),
+                ("settings.json".to_string(), FileStatus::Local),
+                ("snippets/rust.json".to_string(), FileStatus::Incoming),
+            ])
+        );
+        let incoming = status
+            .paths(FileStatus::Incoming)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        folder.apply(status, &incoming).await.unwrap();
+        assert_eq!(
+            fs.load(path!("/config/keymap.json").as_ref())
+                .await
+                .unwrap(),
+            "[{}]"
+        );
+        assert!(
+            !fs.is_file(path!("/config/snippets/rust.json").as_ref())
+                .await
+        );
+
+        // The local settings change is still pending, and both sides changing
+        // the keymap is a conflict.
+        fs.write(path!("/sync/keymap.json").as_ref(), b"[1]")
+            .await
+            .unwrap();
+        fs.write(path!("/config/keymap.json").as_ref(), b"[2]")
+            .await
+            .unwrap();
+        let status = folder.status(Some(vec!["html".into()])).await.unwrap();
+        assert_eq!(
+            status.changes,
+            BTreeMap::from_iter([
+                ("keymap.json".to_string(), FileStatus::Conflict),
+                ("settings.json".to_string(), FileStatus::Local),
+            ])
+        );
+    }
+}
@@ -6097,7 +6097,28 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
 
 const LANGUAGES_SECTION_HEADER: &'static str = "Languages";
 
-fn current_language() -> Option<SharedString> {
+/// The prefix of the `json_path` of settings that can be overridden per language.
+pub(crate) const LANGUAGE_JSON_PATH_PREFIX: &'static str = "languages.$(language).";
+
+/// Returns the settings `content` sets for `language`, without the ones for
+/// all languages that language settings fields would otherwise fall back to.
+pub(crate) fn language_overrides(
+    content: &SettingsContent,
+    language: &SharedString,
+) -> SettingsContent {
+    let mut overrides = SettingsContent::default();
+    if let Some(language_content) = content.project.all_languages.languages.0.get(language) {
+        overrides
+            .project
+            .all_languages
+            .languages
+            .0
+            .insert(language.clone(), language_content.clone());
+    }
+    overrides
+}
+
+pub(crate) fn current_language() -> Option<SharedString> {
     sub_page_stack().iter().find_map(|page| {
         (page.section_header == LANGUAGES_SECTION_HEADER).then(|| page.link.title.clone())
     })
@@ -15,7 +15,9 @@ use project::{Project, WorktreeId};
 use release_channel::ReleaseChannel;
 use schemars::JsonSchema;
 use serde::Deserialize;
-use settings::{Settings, SettingsContent, SettingsStore, initial_project_settings_content};
+use settings::{
+    Settings, SettingsContent, SettingsSource, SettingsStore, initial_project_settings_content,
+};
 use std::{
     any::{Any, TypeId, type_name},
     cell::RefCell,
@@ -40,6 +42,7 @@ use zed_actions::{OpenProjectSettings, OpenSettings, OpenSettingsAt};
 use crate::components::{
     EnumVariantDropdown, SettingsInputField, font_picker, icon_theme_picker, theme_picker,
 };
+use crate::page_data::{LANGUAGE_JSON_PATH_PREFIX, current_language, language_overrides};
 
 const NAVBAR_CONTAINER_TAB_INDEX: isize = 0;
 const NAVBAR_GROUP_TAB_INDEX: isize = 1;
@@ -155,8 +158,37 @@ trait AnySettingField {
         file_set_in: &settings::SettingsFile,
         cx: &App,
     ) -> Option<Box<dyn Fn(&mut App)>>;
+    /// Returns the layer of settings the effective value comes from, for the given file.
+    fn value_source(&self, file: &SettingsUiFile, cx: &App) -> Option<SettingValueSource>;
+    /// Returns a function that removes the value from the current file, so it
+    /// inherits the value of the layers below it.
+    fn reset_to_inherited_fn(
+        &self,
+        current_file: &SettingsUiFile,
+        cx: &App,
+    ) -> Option<Box<dyn Fn(&mut App)>>;
 
     fn json_path(&self) -> Option<&'static str>;
+
+    /// Returns the language whose overrides this field shows, when it's
+    /// rendered on a language's page.
+    fn current_language(&self) -> Option<SharedString> {
+        self.json_path()
+            .is_some_and(|path| path.starts_with(LANGUAGE_JSON_PATH_PREFIX))
+            .then(current_language)
+            .flatten()
+    }
+}
+
+/// Where the effective value of a setting comes from.
+#[derive(Clone, Debug, PartialEq)]
+struct SettingValueSource {
+    source: SettingsSource,
+    /// The language whose page the setting is shown on.
+    language: Option<SharedString>,
+    /// Whether the value is set specifically for the language, rather than
+    /// for all languages.
+    is_language_override: bool,
 }
 
 impl<T: PartialEq + Clone + Send + Sync + 'static> AnySettingField for SettingField<T> {
@@ -223,6 +255,66 @@ impl<T: PartialEq + Clone + Send + Sync + 'static> AnySettingField for SettingFi
         }));
     }
 
+    fn value_source(&self, file: &SettingsUiFile, cx: &App) -> Option<SettingValueSource> {
+        let store = SettingsStore::global(cx);
+        let file = file.to_settings();
+        // Values set for a language win over the ones set for all languages,
+        // whatever layer the latter come from.
+        let language = self.current_language();
+        if let Some(current_language) = &language
+            && let Some((source, _)) =
+                store
+                    .settings_layers(&file, cx)
+                    .into_iter()
+                    .find(|(_, content)| {
+                        (self.pick)(&language_overrides(content, current_language)).is_some()
+                    })
+        {
+            return Some(SettingValueSource {
+                source,
+                language,
+                is_language_override: true,
+            });
+        }
+        store
+            .value_source(&file, self.pick, cx)
+            .map(|source| SettingValueSource {
+                source,
+                language,
+                is_language_override: false,
+            })
+    }
+
+    fn reset_to_inherited_fn(
+        &self,
+        current_file: &SettingsUiFile,
+        cx: &App,
+    ) -> Option<Box<dyn Fn(&mut App)>> {
+        let store = SettingsStore::global(cx);
+        let content = store.get_content_for_file(current_file.to_settings())?;
+        if let Some(language) = self.current_language() {
+            // Only the value set for the language can be removed here.
+            (self.pick)(&language_overrides(content, &language))?;
+        } else {
+            (self.pick)(content)?;
+            // Without an inherited value, this is the same as resetting to the default.
+            let (inherited_from, _) =
+                store.get_value_up_to_file(current_file.to_settings(), self.pick);
+            if inherited_from == settings::SettingsFile::Default {
+                return None;
+            }
+        }
+
+        let this = *self;
+        let current_file = current_file.clone();
+        Some(Box::new(move |cx| {
+            update_settings_file(current_file.clone(), None, cx, move |settings, _| {
+                (this.write)(settings, None);
+            })
+            .log_err();
+        }))
+    }
+
     fn json_path(&self) -> Option<&'static str> {
         self.json_path
     }
@@ -981,7 +1073,12 @@ fn render_settings_item(
     cx: &mut Context<'_, SettingsWindow>,
 ) -> Stateful<Div> {
     let (found_in_file, _) = setting_item.field.file_set_in(file.clone(), cx);
-    let file_set_in = SettingsUiFile::from_settings(found_in_file.clone());
+    let source_label = settings_window
+        .editorconfig_source_label(setting_item, &file, cx)
+        .or_else(|| {
+            let value_source = setting_item.field.value_source(&file, cx)?;
+            settings_window.value_source_label(&file, &value_source)
+        });
 
     let clipboard_has_link = cx
         .read_from_clipboard()
@@ -1033,20 +1130,28 @@ fn render_settings_item(
                             },
                         )
                         .when_some(
-                            file_set_in.filter(|file_set_in| file_set_in != &file),
-                            |this, file_set_in| {
+                            if sub_field {
+                                None
+                            } else {
+                                setting_item.field.reset_to_inherited_fn(&file, cx)
+                            },
+                            |this, reset_to_inherited| {
                                 this.child(
-                                    Label::new(format!(
-                                        "—  Modified in {}",
-                                        settings_window
-                                            .display_name(&file_set_in)
-                                            .expect("File name should exist")
-                                    ))
-                                    .color(Color::Muted)
-                                    .size(LabelSize::Small),
+                                    IconButton::new("reset-to-inherited-btn", IconName::RotateCcw)
+                                        .icon_color(Color::Muted)
+                                        .icon_size(IconSize::Small)
+                                        .tooltip(Tooltip::text("Reset to Inherited Value"))
+                                        .on_click({
+                                            move |_, _, cx| {
+                                                reset_to_inherited(cx);
+                                            }
+                                        }),
                                 )
                             },
-                        ),
+                        )
+                        .when_some(source_label, |this, (label, color)| {
+                            this.child(Label::new(label).color(color).size(LabelSize::Small))
+                        }),
                 )
                 .child(
                     Label::new(SharedString::new_static(setting_item.description))
@@ -1190,6 +1295,19 @@ impl PartialEq for SubPageLink {
     }
 }
 
+/// Returns the `.editorconfig` properties that override the language setting
+/// with the given name.
+fn editorconfig_keys(setting_name: &str) -> &'static [&'static str] {
+    match setting_name {
+        "tab_size" => &["indent_size", "tab_width"],
+        "hard_tabs" => &["indent_style"],
+        "preferred_line_length" => &["max_line_length"],
+        "ensure_final_newline_on_save" => &["insert_final_newline"],
+        "remove_trailing_whitespace_on_save" => &["trim_trailing_whitespace"],
+        _ => &[],
+    }
+}
+
 fn all_language_names(cx: &App) -> Vec<SharedString> {
     workspace::AppState::global(cx)
         .upgrade()
@@ -2211,6 +2329,122 @@ impl SettingsWindow {
         }
     }
 
+    fn source_display_name(&self, source: &SettingsSource) -> Option<String> {
+        Some(match source {
+            SettingsSource::Default => "Default".to_string(),
+            SettingsSource::Extension => "Extensions".to_string(),
+            SettingsSource::Global => "Global Settings".to_string(),
+            SettingsSource::User => "User".to_string(),
+            SettingsSource::ReleaseChannel => {
+                format!("User ({})", release_channel::RELEASE_CHANNEL.display_name())
+            }
+            SettingsSource::Os => format!("User ({})", std::env::consts::OS),
+            SettingsSource::Machine => {
+                format!(
+                    "User ({})",
+                    settings::machine_name().as_deref().unwrap_or("machine")
+                )
+            }
+            SettingsSource::Profile(name) => format!("Profile \"{name}\""),
+            SettingsSource::Vimrc => "vimrc".to_string(),
+            SettingsSource::Server => "Server".to_string(),
+            SettingsSource::Project(location) => {
+                self.display_name(&SettingsUiFile::Project(location.clone()))?
+            }
+        })
+    }
+
+    /// Describes where the value of a setting comes from, unless it's set
+    /// directly in the file being edited.
+    fn value_source_label(
+        &self,
+        file: &SettingsUiFile,
+        value_source: &SettingValueSource,
+    ) -> Option<(String, Color)> {
+        let SettingValueSource {
+            source,
+            language,
+            is_language_override,
+        } = value_source;
+        let mut source_name = self.source_display_name(source)?;
+        if *is_language_override && let Some(language) = language {
+            source_name = format!("{language} in {source_name}");
+        }
+
+        let file = file.to_settings();
+        if source.file() == file
+            && matches!(source, SettingsSource::User | SettingsSource::Project(_))
+        {
+            return (language.is_some() && !is_language_override)
+                .then(|| ("—  Set for all languages".to_string(), Color::Muted));
+        }
+
+        let is_overridden = file == settings::SettingsFile::User
+            && matches!(
+                source,
+                SettingsSource::ReleaseChannel
+                    | SettingsSource::Os
+                    | SettingsSource::Machine
+                    | SettingsSource::Profile(_)
+                    | SettingsSource::Vimrc
+                    | SettingsSource::Server
+            );
+        if is_overridden {
+            Some((format!("—  Overridden by {source_name}"), Color::Warning))
+        } else if source == &SettingsSource::Default && !is_language_override {
+            None
+        } else {
+            Some((format!("—  Inherited from {source_name}"), Color::Muted))
+        }
+    }
+
+    /// Describes the `.editorconfig` file that overrides a language setting
+    /// in the project file being edited, if any.
+    fn editorconfig_source_label(
+        &self,
+        setting_item: &SettingItem,
+        file: &SettingsUiFile,
+        cx: &App,
+    ) -> Option<(String, Color)> {
+        let SettingsUiFile::Project((worktree_id, directory)) = file else {
+            return None;
+        };
+        let keys = setting_item
+            .field
+            .json_path()?
+            .strip_prefix(LANGUAGE_JSON_PATH_PREFIX)
+            .map(editorconfig_keys)?;
+        if keys.is_empty() {
+            return None;
+        }
+
+        // Check the properties .editorconfig sets for a file of the current language.
+        let file_name = current_language()
+            .and_then(|language| {
+                let languages = AppState::global(cx).upgrade()?.languages.clone();
+                let suffix = languages
+                    .available_language_for_name(&language)?
+                    .matcher()
+                    .path_suffixes
+                    .first()?
+                    .clone();
+                Some(format!("file.{suffix}"))
+            })
+            .unwrap_or_else(|| "file".to_string());
+        let file_path = directory.join(RelPath::unix(&file_name).ok()?);
+        let editorconfig_directory =
+            SettingsStore::global(cx).editorconfig_source(*worktree_id, &file_path, keys)?;
+        let editorconfig_directory = self.display_name(&SettingsUiFile::Project((
+            *worktree_id,
+            editorconfig_directory,
+        )))?;
+
+        Some((
+            format!("—  Overridden by .editorconfig in {editorconfig_directory}"),
+            Color::Warning,
+        ))
+    }
+
     // TODO:
     //  Reconsider this after preview launch
     // fn file_location_str(&self) -> String {
@@ -179,11 +179,44 @@ pub fn toggle_modal(
 }
 
 pub fn spawn_tasks_filtered<F>(
-    mut predicate: F,
+    predicate: F,
     overrides: Option<TaskOverrides>,
     window: &mut Window,
     cx: &mut Context<Workspace>,
 ) -> Task<anyhow::Result<()>>
+where
+    F: FnMut((&TaskSourceKind, &TaskTemplate)) -> bool + 'static,
+{
+    let schedule_tasks = schedule_tasks_filtered(predicate, overrides.clone(), window, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let did_spawn = schedule_tasks.await?;
+        if !did_spawn {
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    spawn_task_or_modal(
+                        workspace,
+                        &Spawn::ViaModal {
+                            reveal_target: overrides.and_then(|overrides| overrides.reveal_target),
+                        },
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+        }
+
+        Ok(())
+    })
+}
+
+/// Schedules all tasks matching `predicate`, resolving them in the context of the
+/// active item. Resolves to whether any task was scheduled.
+pub fn schedule_tasks_filtered<F>(
+    mut predicate: F,
+    overrides: Option<TaskOverrides>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<anyhow::Result<bool>>
 where
     F: FnMut((&TaskSourceKind, &TaskTemplate)) -> bool + 'static,
 {
@@ -249,22 +282,7 @@ where
                 if tasks.is_empty() { None } else { Some(()) }
             })?
             .is_some();
-        if !did_spawn {
-            workspace
-                .update_in(cx, |workspace, window, cx| {
-                    spawn_task_or_modal(
-                        workspace,
-                        &Spawn::ViaModal {
-                            reveal_target: overrides.and_then(|overrides| overrides.reveal_target),
-                        },
-                        window,
-                        cx,
-                    );
-                })
-                .ok();
-        }
-
-        Ok(())
+        Ok(did_spawn)
     })
 }
 
@@ -1430,6 +1430,7 @@ impl SearchableItem for TerminalView {
             replacement: false,
             selection: false,
             find_in_results: false,
+            syntax_scope: false,
         }
     }
 
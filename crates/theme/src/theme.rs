@@ -47,8 +47,8 @@ pub use crate::schema::*;
 pub use crate::settings::*;
 pub use crate::styles::*;
 pub use ::settings::{
-    FontStyleContent, HighlightStyleContent, StatusColorsContent, ThemeColorsContent,
-    ThemeStyleContent,
+    FontStyleContent, HighlightStyleContent, PlayerColorContent, StatusColorsContent,
+    ThemeColorsContent, ThemeStyleContent,
 };
 
 /// Defines window border radius for platforms that use client side decorations.
@@ -0,0 +1,406 @@
+use anyhow::{Context as _, Result};
+use collections::IndexMap;
+use gpui::Rgba;
+use strum::IntoEnumIterator;
+use theme::{
+    FontStyleContent, FontWeightContent, HighlightStyleContent, PlayerColorContent,
+    StatusColorsContent, ThemeColorsContent, ThemeContent, ThemeFamilyContent, ThemeStyleContent,
+    WindowBackgroundContent,
+};
+
+use crate::ThemeAppearanceJson;
+use crate::color::{appearance_for_background, mix, rgba_to_hex, with_alpha};
+use crate::vscode::ZedSyntaxToken;
+
+/// Parses a base16 or base24 scheme and converts it following the base16
+/// styling guidelines.
+///
+/// Both the original format, with a `scheme` name and top-level `baseXX` keys,
+/// and the newer one, with a `name`, a `variant` and a nested `palette`, are
+/// supported.
+pub(crate) fn import_base16_scheme(
+    content: &str,
+    fallback_name: &str,
+) -> Result<ThemeFamilyContent> {
+    let entries = parse_scheme_entries(content);
+
+    let mut palette = Vec::with_capacity(24);
+    for ix in 0..24 {
+        let key = format!("base{ix:02x}");
+        let Some(value) = entries.get(&key) else {
+            // base24 schemes define eight more colors than base16 ones.
+            anyhow::ensure!(ix >= 16, "scheme doesn't define {key}");
+            break;
+        };
+        let hex = if value.starts_with('#') {
+            value.clone()
+        } else {
+            format!("#{value}")
+        };
+        let color = Rgba::try_from(hex.as_str()).with_context(|| format!("invalid {key}"))?;
+        palette.push(color);
+    }
+    let base = |ix: usize| palette[ix];
+
+    let name = entries
+        .get("name")
+        .or_else(|| entries.get("scheme"))
+        .cloned()
+        .unwrap_or_else(|| fallback_name.to_string());
+    let appearance = match entries.get("variant").map(String::as_str) {
+        Some("light") => ThemeAppearanceJson::Light,
+        Some("dark") => ThemeAppearanceJson::Dark,
+        _ => appearance_for_background(base(0x00)),
+    };
+
+    let hex = |ix: usize| Some(rgba_to_hex(base(ix)));
+    let alpha = |ix: usize, alpha: f32| Some(rgba_to_hex(with_alpha(base(ix), alpha)));
+    // base24's bright colors, which base16 schemes don't distinguish.
+    let bright = |ix: usize, fallback: usize| {
+        Some(rgba_to_hex(
+            palette.get(ix).copied().unwrap_or(base(fallback)),
+        ))
+    };
+    let transparent = Some("#00000000".to_string());
+
+    let colors = ThemeColorsContent {
+        border: hex(0x02),
+        border_variant: hex(0x01),
+        border_focused: hex(0x0D),
+        border_selected: hex(0x0D),
+        border_transparent: transparent.clone(),
+        border_disabled: hex(0x01),
+        elevated_surface_background: hex(0x01),
+        surface_background: hex(0x01),
+        background: hex(0x01),
+        element_background: hex(0x01),
+        element_hover: hex(0x02),
+        element_active: hex(0x02),
+        element_selected: hex(0x02),
+        element_disabled: hex(0x01),
+        drop_target_background: alpha(0x0D, 0.2),
+        ghost_element_background: transparent.clone(),
+        ghost_element_hover: hex(0x02),
+        ghost_element_active: hex(0x02),
+        ghost_element_selected: hex(0x02),
+        ghost_element_disabled: hex(0x01),
+        text: hex(0x05),
+        text_muted: hex(0x04),
+        text_placeholder: hex(0x03),
+        text_disabled: hex(0x03),
+        text_accent: hex(0x0D),
+        icon: hex(0x05),
+        icon_muted: hex(0x04),
+        icon_disabled: hex(0x03),
+        icon_placeholder: hex(0x03),
+        icon_accent: hex(0x0D),
+        status_bar_background: hex(0x01),
+        title_bar_background: hex(0x01),
+        title_bar_inactive_background: hex(0x01),
+        toolbar_background: hex(0x00),
+        tab_bar_background: hex(0x01),
+        tab_inactive_background: hex(0x01),
+        tab_active_background: hex(0x00),
+        search_match_background: alpha(0x0A, 0.4),
+        panel_background: hex(0x01),
+        panel_focused_border: hex(0x0D),
+        pane_focused_border: hex(0x0D),
+        pane_group_border: hex(0x02),
+        scrollbar_thumb_background: alpha(0x04, 0.3),
+        scrollbar_thumb_hover_background: alpha(0x04, 0.5),
+        scrollbar_thumb_border: transparent.clone(),
+        scrollbar_track_background: transparent,
+        scrollbar_track_border: hex(0x01),
+        editor_foreground: hex(0x05),
+        editor_background: hex(0x00),
+        editor_gutter_background: hex(0x00),
+        editor_subheader_background: hex(0x01),
+        editor_active_line_background: Some(rgba_to_hex(mix(base(0x00), base(0x01), 0.6))),
+        editor_highlighted_line_background: hex(0x01),
+        editor_line_number: hex(0x03),
+        editor_active_line_number: hex(0x04),
+        editor_invisible: hex(0x03),
+        editor_wrap_guide: hex(0x01),
+        editor_active_wrap_guide: hex(0x02),
+        editor_indent_guide: hex(0x01),
+        editor_indent_guide_active: hex(0x02),
+        editor_document_highlight_read_background: alpha(0x0D, 0.1),
+        editor_document_highlight_write_background: alpha(0x0D, 0.2),
+        terminal_background: hex(0x00),
+        terminal_foreground: hex(0x05),
+        terminal_bright_foreground: hex(0x07),
+        terminal_dim_foreground: hex(0x04),
+        terminal_ansi_black: hex(0x00),
+        terminal_ansi_bright_black: hex(0x03),
+        terminal_ansi_red: hex(0x08),
+        terminal_ansi_bright_red: bright(0x12, 0x08),
+        terminal_ansi_green: hex(0x0B),
+        terminal_ansi_bright_green: bright(0x14, 0x0B),
+        terminal_ansi_yellow: hex(0x0A),
+        terminal_ansi_bright_yellow: bright(0x13, 0x0A),
+        terminal_ansi_blue: hex(0x0D),
+        terminal_ansi_bright_blue: bright(0x16, 0x0D),
+        terminal_ansi_magenta: hex(0x0E),
+        terminal_ansi_bright_magenta: bright(0x17, 0x0E),
+        terminal_ansi_cyan: hex(0x0C),
+        terminal_ansi_bright_cyan: bright(0x15, 0x0C),
+        terminal_ansi_white: hex(0x05),
+        terminal_ansi_bright_white: hex(0x07),
+        link_text_hover: hex(0x0D),
+        version_control_added: hex(0x0B),
+        version_control_deleted: hex(0x08),
+        version_control_modified: hex(0x0E),
+        version_control_renamed: hex(0x0C),
+        version_control_conflict: hex(0x09),
+        version_control_ignored: hex(0x03),
+        ..Default::default()
+    };
+
+    let status = StatusColorsContent {
+        conflict: hex(0x09),
+        created: hex(0x0B),
+        deleted: hex(0x08),
+        error: hex(0x08),
+        hidden: hex(0x03),
+        hint: hex(0x03),
+        ignored: hex(0x03),
+        info: hex(0x0D),
+        modified: hex(0x0E),
+        predictive: hex(0x03),
+        renamed: hex(0x0C),
+        success: hex(0x0B),
+        unreachable: hex(0x03),
+        warning: hex(0x09),
+        ..Default::default()
+    };
+
+    let players = [0x0D, 0x08, 0x0B, 0x0E, 0x09, 0x0C, 0x0A, 0x0F]
+        .into_iter()
+        .map(|ix| PlayerColorContent {
+            cursor: hex(ix),
+            background: hex(ix),
+            selection: alpha(ix, 0.25),
+        })
+        .collect();
+
+    let syntax = ZedSyntaxToken::iter()
+        .map(|token| {
+            let (font_style, font_weight) = match token {
+                ZedSyntaxToken::Emphasis => (Some(FontStyleContent::Italic), None),
+                ZedSyntaxToken::EmphasisStrong | ZedSyntaxToken::Title => {
+                    (None, Some(FontWeightContent::Bold))
+                }
+                _ => (None, None),
+            };
+            let style = HighlightStyleContent {
+                color: hex(syntax_palette_index(token)),
+                background_color: None,
+                font_style,
+                font_weight,
+            };
+            (token.to_string(), style)
+        })
+        .collect();
+
+    Ok(ThemeFamilyContent {
+        name: name.clone(),
+        author: entries.get("author").cloned().unwrap_or_default(),
+        themes: vec![ThemeContent {
+            name,
+            appearance: appearance.into(),
+            style: ThemeStyleContent {
+                window_background_appearance: Some(WindowBackgroundContent::Opaque),
+                accents: Vec::new(),
+                colors,
+                status,
+                players,
+                syntax,
+            },
+        }],
+    })
+}
+
+/// Returns the palette color for a syntax highlight, following the base16
+/// styling guidelines.
+fn syntax_palette_index(token: ZedSyntaxToken) -> usize {
+    match token {
+        ZedSyntaxToken::Comment
+        | ZedSyntaxToken::CommentDoc
+        | ZedSyntaxToken::Hint
+        | ZedSyntaxToken::Predictive => 0x03,
+        ZedSyntaxToken::Operator
+        | ZedSyntaxToken::Primary
+        | ZedSyntaxToken::Punctuation
+        | ZedSyntaxToken::PunctuationBracket
+        | ZedSyntaxToken::PunctuationDelimiter
+        | ZedSyntaxToken::Variable => 0x05,
+        ZedSyntaxToken::LinkText
+        | ZedSyntaxToken::Property
+        | ZedSyntaxToken::PunctuationListMarker
+        | ZedSyntaxToken::Tag
+        | ZedSyntaxToken::VariableSpecial => 0x08,
+        ZedSyntaxToken::Boolean | ZedSyntaxToken::Constant | ZedSyntaxToken::Number => 0x09,
+        ZedSyntaxToken::Attribute
+        | ZedSyntaxToken::Constructor
+        | ZedSyntaxToken::EmphasisStrong
+        | ZedSyntaxToken::Enum
+        | ZedSyntaxToken::Label
+        | ZedSyntaxToken::Preproc
+        | ZedSyntaxToken::Type
+        | ZedSyntaxToken::Variant => 0x0A,
+        ZedSyntaxToken::String
+        | ZedSyntaxToken::StringSpecialSymbol
+        | ZedSyntaxToken::TextLiteral => 0x0B,
+        ZedSyntaxToken::LinkUri
+        | ZedSyntaxToken::StringEscape
+        | ZedSyntaxToken::StringRegex
+        | ZedSyntaxToken::StringSpecial => 0x0C,
+        ZedSyntaxToken::Function | ZedSyntaxToken::Title => 0x0D,
+        ZedSyntaxToken::Emphasis | ZedSyntaxToken::Keyword => 0x0E,
+        ZedSyntaxToken::Embedded | ZedSyntaxToken::PunctuationSpecial => 0x0F,
+    }
+}
+
+/// Reads the `key: value` pairs of a scheme file, with keys lowercased.
+///
+/// Scheme files only use a small subset of YAML, so this doesn't implement the
+/// rest of it. The entries of nested maps, like the `palette` of newer schemes,
+/// are read as if they were at the top level.
+fn parse_scheme_entries(content: &str) -> IndexMap<String, String> {
+    let mut entries = IndexMap::default();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+        let value = value.trim();
+        let value = if let Some(quote @ ('"' | '\'')) = value.chars().next() {
+            let value = &value[1..];
+            &value[..value.find(quote).unwrap_or(value.len())]
+        } else {
+            // Unquoted values end at a comment.
+            value.split(" #").next().unwrap_or_default().trim()
+        };
+        if !value.is_empty() {
+            entries.insert(key.to_ascii_lowercase(), value.to_string());
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::AppearanceContent;
+
+    const BASE16_SCHEME: &str = r#"
+scheme: "Tomorrow Night"
+author: "Chris Kempson (http://chriskempson.com)"
+base00: "1d1f21" # background
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666"
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"#;
+
+    const BASE24_SCHEME: &str = r##"
+system: "base24"
+name: 'One Light'
+author: "Someone"
+variant: "light"
+palette:
+  base00: "#fafafa"
+  base01: "#f0f0f1"
+  base02: "#e5e5e6"
+  base03: "#a0a1a7"
+  base04: "#696c77"
+  base05: "#383a42"
+  base06: "#202227"
+  base07: "#090a0b"
+  base08: "#ca1243"
+  base09: "#d75f00"
+  base0A: "#c18401"
+  base0B: "#50a14f"
+  base0C: "#0184bc"
+  base0D: "#4078f2"
+  base0E: "#a626a4"
+  base0F: "#986801"
+  base10: "#ffffff"
+  base11: "#ffffff"
+  base12: "#ec2258"
+  base13: "#f4a701"
+  base14: "#6db76c"
+  base15: "#01a7ef"
+  base16: "#709af5"
+  base17: "#d02fcd"
+"##;
+
+    #[test]
+    fn test_import_base16_scheme() {
+        let family = import_base16_scheme(BASE16_SCHEME, "tomorrow-night").unwrap();
+        assert_eq!(family.name, "Tomorrow Night");
+        assert_eq!(family.author, "Chris Kempson (http://chriskempson.com)");
+
+        let theme = &family.themes[0];
+        assert_eq!(theme.appearance, AppearanceContent::Dark);
+        let colors = &theme.style.colors;
+        assert_eq!(colors.editor_background.as_deref(), Some("#1d1f21ff"));
+        assert_eq!(colors.editor_foreground.as_deref(), Some("#c5c8c6ff"));
+        assert_eq!(colors.terminal_ansi_red.as_deref(), Some("#cc6666ff"));
+        assert_eq!(
+            colors.terminal_ansi_bright_red.as_deref(),
+            Some("#cc6666ff")
+        );
+        assert_eq!(theme.style.players.len(), 8);
+
+        let syntax = &theme.style.syntax;
+        assert_eq!(syntax.len(), ZedSyntaxToken::iter().count());
+        assert_eq!(syntax["comment"].color.as_deref(), Some("#969896ff"));
+        assert_eq!(syntax["keyword"].color.as_deref(), Some("#b294bbff"));
+        assert_eq!(syntax["string"].color.as_deref(), Some("#b5bd68ff"));
+        assert_eq!(syntax["function"].color.as_deref(), Some("#81a2beff"));
+    }
+
+    #[test]
+    fn test_import_base24_scheme() {
+        let family = import_base16_scheme(BASE24_SCHEME, "one-light").unwrap();
+        assert_eq!(family.name, "One Light");
+
+        let theme = &family.themes[0];
+        assert_eq!(theme.appearance, AppearanceContent::Light);
+        let colors = &theme.style.colors;
+        assert_eq!(colors.editor_background.as_deref(), Some("#fafafaff"));
+        assert_eq!(colors.terminal_ansi_red.as_deref(), Some("#ca1243ff"));
+        assert_eq!(
+            colors.terminal_ansi_bright_red.as_deref(),
+            Some("#ec2258ff")
+        );
+        assert_eq!(
+            colors.terminal_ansi_bright_magenta.as_deref(),
+            Some("#d02fcdff")
+        );
+    }
+
+    #[test]
+    fn test_import_incomplete_base16_scheme() {
+        let error = import_base16_scheme("scheme: Broken\nbase00: \"000000\"\n", "broken")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "scheme doesn't define base01");
+    }
+}
@@ -1,7 +1,9 @@
 use anyhow::Result;
-use gpui::Hsla;
+use gpui::{Hsla, Rgba};
 use palette::FromColor;
 
+use crate::ThemeAppearanceJson;
+
 #[allow(unused)]
 pub(crate) fn try_parse_color(color: &str) -> Result<Hsla> {
     let rgba = gpui::Rgba::try_from(color)?;
@@ -27,6 +29,43 @@ pub(crate) fn pack_color(color: Hsla) -> u32 {
     u32::from(rgba)
 }
 
+/// Formats a color as a `#rrggbbaa` hex string.
+pub(crate) fn rgba_to_hex(color: Rgba) -> String {
+    let channel = |value: f32| (value.clamp(0., 1.) * 255.).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b),
+        channel(color.a)
+    )
+}
+
+/// Mixes `amount` (between 0 and 1) of `other` into `color`.
+pub(crate) fn mix(color: Rgba, other: Rgba, amount: f32) -> Rgba {
+    let lerp = |from: f32, to: f32| from + (to - from) * amount;
+    Rgba {
+        r: lerp(color.r, other.r),
+        g: lerp(color.g, other.g),
+        b: lerp(color.b, other.b),
+        a: lerp(color.a, other.a),
+    }
+}
+
+pub(crate) fn with_alpha(color: Rgba, alpha: f32) -> Rgba {
+    Rgba { a: alpha, ..color }
+}
+
+/// Guesses whether a theme is light or dark from its background color.
+pub(crate) fn appearance_for_background(background: Rgba) -> ThemeAppearanceJson {
+    let luminance = 0.2126 * background.r + 0.7152 * background.g + 0.0722 * background.b;
+    if luminance < 0.5 {
+        ThemeAppearanceJson::Dark
+    } else {
+        ThemeAppearanceJson::Light
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,4 +92,22 @@ mod tests {
 
         assert_eq!(format!("#{:x}", rgba), color);
     }
+
+    #[test]
+    pub fn test_mix_colors() {
+        let black = Rgba::try_from("#000000").unwrap();
+        let white = Rgba::try_from("#ffffff").unwrap();
+
+        assert_eq!(rgba_to_hex(mix(black, white, 0.)), "#000000ff");
+        assert_eq!(rgba_to_hex(mix(black, white, 0.5)), "#808080ff");
+        assert_eq!(rgba_to_hex(with_alpha(white, 0.25)), "#ffffff40");
+        assert!(matches!(
+            appearance_for_background(black),
+            ThemeAppearanceJson::Dark
+        ));
+        assert!(matches!(
+            appearance_for_background(white),
+            ThemeAppearanceJson::Light
+        ));
+    }
 }
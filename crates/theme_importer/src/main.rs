@@ -1,62 +1,43 @@
-mod color;
-mod vscode;
-
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::PathBuf;
 
 use anyhow::{Context as _, Result};
-use clap::Parser;
-use collections::IndexMap;
+use clap::{Parser, ValueEnum};
 use log::LevelFilter;
-use serde::Deserialize;
 use simplelog::ColorChoice;
 use simplelog::{TermLogger, TerminalMode};
-use theme::{Appearance, AppearanceContent};
-
-use crate::vscode::VsCodeTheme;
-use crate::vscode::VsCodeThemeConverter;
-
-const ZED_THEME_SCHEMA_URL: &str = "https://zed.dev/schema/themes/v0.2.0.json";
-
-#[derive(Debug, Clone, Copy, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum ThemeAppearanceJson {
-    Light,
-    Dark,
-}
-
-impl From<ThemeAppearanceJson> for AppearanceContent {
-    fn from(value: ThemeAppearanceJson) -> Self {
-        match value {
-            ThemeAppearanceJson::Light => Self::Light,
-            ThemeAppearanceJson::Dark => Self::Dark,
-        }
-    }
+use theme_importer::{ThemeFormat, import_theme, theme_family_to_json};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FormatArg {
+    Vscode,
+    TmTheme,
+    SublimeColorScheme,
+    Base16,
 }
 
-impl From<ThemeAppearanceJson> for Appearance {
-    fn from(value: ThemeAppearanceJson) -> Self {
+impl From<FormatArg> for ThemeFormat {
+    fn from(value: FormatArg) -> Self {
         match value {
-            ThemeAppearanceJson::Light => Self::Light,
-            ThemeAppearanceJson::Dark => Self::Dark,
+            FormatArg::Vscode => Self::VsCode,
+            FormatArg::TmTheme => Self::TmTheme,
+            FormatArg::SublimeColorScheme => Self::SublimeColorScheme,
+            FormatArg::Base16 => Self::Base16,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ThemeMetadata {
-    pub name: String,
-    pub file_name: String,
-    pub appearance: ThemeAppearanceJson,
-}
-
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// The path to the theme to import.
     theme_path: PathBuf,
 
+    /// The format of the theme. Inferred from the file extension by default.
+    #[arg(long, value_enum)]
+    format: Option<FormatArg>,
+
     /// Whether to warn when values are missing from the theme.
     #[arg(long)]
     warn_on_missing: bool,
@@ -89,6 +70,13 @@ fn main() -> Result<()> {
 
     let theme_file_path = args.theme_path;
 
+    let format = match args.format {
+        Some(format) => format.into(),
+        None => ThemeFormat::from_path(&theme_file_path).with_context(|| {
+            format!("can't infer the format of {theme_file_path:?}, pass it with --format")
+        })?,
+    };
+
     let mut buffer = Vec::new();
     match File::open(&theme_file_path).and_then(|mut file| file.read_to_end(&mut buffer)) {
         Ok(_) => {}
@@ -98,24 +86,13 @@ fn main() -> Result<()> {
         }
     };
 
-    let vscode_theme: VsCodeTheme = serde_json_lenient::from_slice(&buffer)
+    let fallback_name = theme_file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let theme_family = import_theme(&buffer, format, &fallback_name)
         .context(format!("failed to parse theme {theme_file_path:?}"))?;
-
-    let theme_metadata = ThemeMetadata {
-        name: vscode_theme.name.clone().unwrap_or("".to_string()),
-        appearance: ThemeAppearanceJson::Dark,
-        file_name: "".to_string(),
-    };
-
-    let converter = VsCodeThemeConverter::new(vscode_theme, theme_metadata, IndexMap::default());
-
-    let theme = converter.convert()?;
-    let mut theme = serde_json::to_value(theme).unwrap();
-    theme.as_object_mut().unwrap().insert(
-        "$schema".to_string(),
-        serde_json::Value::String(ZED_THEME_SCHEMA_URL.to_string()),
-    );
-    let theme_json = serde_json::to_string_pretty(&theme).unwrap();
+    let theme_json = theme_family_to_json(&theme_family)?;
 
     if let Some(output) = args.output {
         let mut file = File::create(output)?;
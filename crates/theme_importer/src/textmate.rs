@@ -0,0 +1,221 @@
+mod sublime;
+mod tm_theme;
+
+use anyhow::{Context as _, Result};
+use collections::IndexMap;
+use gpui::Rgba;
+use theme::{
+    PlayerColorContent, StatusColorsContent, ThemeColorsContent, ThemeContent, ThemeFamilyContent,
+    ThemeStyleContent, WindowBackgroundContent,
+};
+
+use crate::color::{appearance_for_background, mix, rgba_to_hex, with_alpha};
+use crate::vscode::{VsCodeTokenColor, VsCodeTokenScope, convert_syntax_theme};
+
+pub(crate) use sublime::parse_sublime_color_scheme;
+pub(crate) use tm_theme::parse_tm_theme;
+
+/// A color scheme made of TextMate scope rules, as used by `.tmTheme` and
+/// `.sublime-color-scheme` files.
+pub(crate) struct TextMateTheme {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub globals: TextMateGlobals,
+    /// The scope rules, in the same shape as VS Code's `tokenColors`, which
+    /// are TextMate rules too.
+    pub rules: Vec<VsCodeTokenColor>,
+}
+
+/// The colors of the editor itself, rather than of the text in it.
+#[derive(Debug, Default)]
+pub(crate) struct TextMateGlobals {
+    pub background: Option<Rgba>,
+    pub foreground: Option<Rgba>,
+    pub caret: Option<Rgba>,
+    pub accent: Option<Rgba>,
+    pub line_highlight: Option<Rgba>,
+    pub selection: Option<Rgba>,
+    pub find_highlight: Option<Rgba>,
+    pub gutter: Option<Rgba>,
+    pub gutter_foreground: Option<Rgba>,
+    pub invisibles: Option<Rgba>,
+    pub guide: Option<Rgba>,
+    pub active_guide: Option<Rgba>,
+    pub line_diff_added: Option<Rgba>,
+    pub line_diff_modified: Option<Rgba>,
+    pub line_diff_deleted: Option<Rgba>,
+}
+
+impl TextMateGlobals {
+    /// Returns the global with the given name, which is camel case in
+    /// `.tmTheme` files and snake case in `.sublime-color-scheme` files.
+    fn get_mut(&mut self, name: &str) -> Option<&mut Option<Rgba>> {
+        Some(match name {
+            "background" => &mut self.background,
+            "foreground" => &mut self.foreground,
+            "caret" => &mut self.caret,
+            "accent" => &mut self.accent,
+            "lineHighlight" | "line_highlight" => &mut self.line_highlight,
+            "selection" => &mut self.selection,
+            "findHighlight" | "find_highlight" => &mut self.find_highlight,
+            "gutter" => &mut self.gutter,
+            "gutterForeground" | "gutter_foreground" => &mut self.gutter_foreground,
+            "invisibles" => &mut self.invisibles,
+            "guide" => &mut self.guide,
+            "activeGuide" | "active_guide" => &mut self.active_guide,
+            "line_diff_added" => &mut self.line_diff_added,
+            "line_diff_modified" => &mut self.line_diff_modified,
+            "line_diff_deleted" => &mut self.line_diff_deleted,
+            _ => return None,
+        })
+    }
+}
+
+impl TextMateTheme {
+    pub fn convert(self, fallback_name: &str) -> Result<ThemeFamilyContent> {
+        let globals = &self.globals;
+        let background = globals
+            .background
+            .context("color scheme doesn't define a background color")?;
+        let foreground = globals
+            .foreground
+            .context("color scheme doesn't define a foreground color")?;
+        let accent = globals.accent.or(globals.caret).unwrap_or(foreground);
+
+        // Everything that isn't part of the scheme is a shade between its
+        // background and foreground colors.
+        let shade = |amount: f32| Some(rgba_to_hex(mix(background, foreground, amount)));
+        let muted = |amount: f32| Some(rgba_to_hex(mix(foreground, background, amount)));
+        let hex = |color: Rgba| Some(rgba_to_hex(color));
+        let transparent = Some("#00000000".to_string());
+
+        let colors = ThemeColorsContent {
+            border: shade(0.16),
+            border_variant: shade(0.1),
+            border_focused: hex(accent),
+            border_selected: hex(accent),
+            border_transparent: transparent.clone(),
+            border_disabled: shade(0.1),
+            elevated_surface_background: shade(0.06),
+            surface_background: shade(0.04),
+            background: shade(0.04),
+            element_background: shade(0.08),
+            element_hover: shade(0.12),
+            element_active: shade(0.16),
+            element_selected: shade(0.16),
+            element_disabled: shade(0.06),
+            drop_target_background: hex(with_alpha(accent, 0.2)),
+            ghost_element_background: transparent.clone(),
+            ghost_element_hover: shade(0.1),
+            ghost_element_active: shade(0.14),
+            ghost_element_selected: shade(0.14),
+            ghost_element_disabled: shade(0.06),
+            text: hex(foreground),
+            text_muted: muted(0.35),
+            text_placeholder: muted(0.55),
+            text_disabled: muted(0.55),
+            text_accent: hex(accent),
+            icon: hex(foreground),
+            icon_muted: muted(0.35),
+            icon_disabled: muted(0.55),
+            icon_placeholder: muted(0.55),
+            icon_accent: hex(accent),
+            status_bar_background: shade(0.04),
+            title_bar_background: shade(0.04),
+            title_bar_inactive_background: shade(0.02),
+            toolbar_background: hex(background),
+            tab_bar_background: shade(0.04),
+            tab_inactive_background: shade(0.04),
+            tab_active_background: hex(background),
+            search_match_background: hex(with_alpha(globals.find_highlight.unwrap_or(accent), 0.4)),
+            panel_background: shade(0.04),
+            panel_focused_border: hex(accent),
+            pane_focused_border: hex(accent),
+            pane_group_border: shade(0.16),
+            scrollbar_thumb_background: hex(with_alpha(foreground, 0.2)),
+            scrollbar_thumb_hover_background: hex(with_alpha(foreground, 0.3)),
+            scrollbar_thumb_border: transparent.clone(),
+            scrollbar_track_background: transparent,
+            scrollbar_track_border: shade(0.08),
+            editor_foreground: hex(foreground),
+            editor_background: hex(background),
+            editor_gutter_background: hex(globals.gutter.unwrap_or(background)),
+            editor_subheader_background: shade(0.04),
+            editor_active_line_background: globals.line_highlight.map_or(shade(0.05), hex),
+            editor_highlighted_line_background: shade(0.08),
+            editor_line_number: globals.gutter_foreground.map_or(muted(0.55), hex),
+            editor_active_line_number: hex(foreground),
+            editor_invisible: globals.invisibles.map_or(muted(0.7), hex),
+            editor_wrap_guide: globals.guide.map_or(shade(0.08), hex),
+            editor_active_wrap_guide: globals.active_guide.map_or(shade(0.16), hex),
+            editor_indent_guide: globals.guide.map_or(shade(0.08), hex),
+            editor_indent_guide_active: globals.active_guide.map_or(shade(0.16), hex),
+            editor_document_highlight_read_background: hex(with_alpha(accent, 0.1)),
+            editor_document_highlight_write_background: hex(with_alpha(accent, 0.2)),
+            terminal_background: hex(background),
+            terminal_foreground: hex(foreground),
+            link_text_hover: hex(accent),
+            ..Default::default()
+        };
+
+        let status = StatusColorsContent {
+            created: globals
+                .line_diff_added
+                .map(rgba_to_hex)
+                .or_else(|| self.scope_foreground("markup.inserted")),
+            deleted: globals
+                .line_diff_deleted
+                .map(rgba_to_hex)
+                .or_else(|| self.scope_foreground("markup.deleted")),
+            modified: globals
+                .line_diff_modified
+                .map(rgba_to_hex)
+                .or_else(|| self.scope_foreground("markup.changed")),
+            hidden: muted(0.55),
+            ignored: muted(0.55),
+            ..Default::default()
+        };
+
+        let caret = globals.caret.unwrap_or(accent);
+        let players = vec![PlayerColorContent {
+            cursor: hex(caret),
+            background: hex(caret),
+            selection: hex(globals.selection.unwrap_or(with_alpha(accent, 0.25))),
+        }];
+
+        let syntax = convert_syntax_theme(&self.rules, &IndexMap::default());
+        let name = self.name.unwrap_or_else(|| fallback_name.to_string());
+
+        Ok(ThemeFamilyContent {
+            name: name.clone(),
+            author: self.author.unwrap_or_default(),
+            themes: vec![ThemeContent {
+                name,
+                appearance: appearance_for_background(background).into(),
+                style: ThemeStyleContent {
+                    window_background_appearance: Some(WindowBackgroundContent::Opaque),
+                    accents: Vec::new(),
+                    colors,
+                    status,
+                    players,
+                    syntax,
+                },
+            }],
+        })
+    }
+
+    /// Returns the foreground color of the first rule that targets exactly the
+    /// given scope.
+    fn scope_foreground(&self, scope: &str) -> Option<String> {
+        self.rules
+            .iter()
+            .filter(|rule| match &rule.scope {
+                Some(VsCodeTokenScope::One(scopes)) => {
+                    scopes.split(',').any(|candidate| candidate.trim() == scope)
+                }
+                Some(VsCodeTokenScope::Many(scopes)) => scopes.iter().any(|s| s == scope),
+                None => false,
+            })
+            .find_map(|rule| rule.settings.foreground.clone())
+    }
+}
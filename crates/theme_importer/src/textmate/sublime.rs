@@ -0,0 +1,417 @@
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::IndexMap;
+use gpui::{Hsla, Rgba};
+use serde::Deserialize;
+
+use crate::color::{mix, rgba_to_hex};
+use crate::textmate::{TextMateGlobals, TextMateTheme};
+use crate::vscode::{VsCodeTokenColor, VsCodeTokenColorSettings, VsCodeTokenScope};
+
+/// How many `var()` references may be followed to resolve a single color.
+const MAX_VARIABLE_DEPTH: usize = 16;
+
+#[derive(Debug, Deserialize)]
+struct SublimeColorScheme {
+    name: Option<String>,
+    author: Option<String>,
+    #[serde(default)]
+    variables: IndexMap<String, String>,
+    #[serde(default)]
+    globals: IndexMap<String, String>,
+    #[serde(default)]
+    rules: Vec<SublimeRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SublimeRule {
+    name: Option<String>,
+    scope: Option<String>,
+    foreground: Option<SublimeForeground>,
+    background: Option<String>,
+    font_style: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SublimeForeground {
+    Color(String),
+    /// Used by hashed syntax highlighting, which we don't support, so only the
+    /// first color is used.
+    Gradient(Vec<String>),
+}
+
+/// Parses a Sublime Text `.sublime-color-scheme` file, resolving its variables
+/// and color functions.
+pub(crate) fn parse_sublime_color_scheme(content: &[u8]) -> Result<TextMateTheme> {
+    let scheme: SublimeColorScheme = serde_json_lenient::from_slice(content)
+        .context("failed to parse .sublime-color-scheme file")?;
+    let variables = &scheme.variables;
+
+    let mut globals = TextMateGlobals::default();
+    for (name, value) in &scheme.globals {
+        let Some(global) = globals.get_mut(name) else {
+            continue;
+        };
+        match resolve_color(value, variables, 0) {
+            Ok(color) => *global = Some(color),
+            Err(err) => log::warn!("Ignoring global '{name}': {err}"),
+        }
+    }
+
+    let mut rules = Vec::new();
+    for rule in scheme.rules {
+        let Some(scope) = rule.scope else {
+            continue;
+        };
+        let color = |value: Option<&String>| {
+            let value = value?;
+            match resolve_color(value, variables, 0) {
+                Ok(color) => Some(rgba_to_hex(color)),
+                Err(err) => {
+                    log::warn!("Ignoring color for '{scope}': {err}");
+                    None
+                }
+            }
+        };
+        let foreground = match &rule.foreground {
+            Some(SublimeForeground::Color(color)) => Some(color),
+            Some(SublimeForeground::Gradient(colors)) => colors.first(),
+            None => None,
+        };
+        let settings = VsCodeTokenColorSettings {
+            foreground: color(foreground),
+            background: color(rule.background.as_ref()),
+            font_style: rule.font_style,
+        };
+        rules.push(VsCodeTokenColor {
+            name: rule.name,
+            scope: Some(VsCodeTokenScope::One(scope)),
+            settings,
+        });
+    }
+
+    Ok(TextMateTheme {
+        name: scheme.name,
+        author: scheme.author,
+        globals,
+        rules,
+    })
+}
+
+/// Resolves a color in any of the syntaxes supported by Sublime Text: hex
+/// colors, CSS color functions, a subset of the named colors, `var()` and
+/// `color()` with its adjusters.
+fn resolve_color(value: &str, variables: &IndexMap<String, String>, depth: usize) -> Result<Rgba> {
+    let value = value.trim();
+    if depth > MAX_VARIABLE_DEPTH {
+        bail!("variables are nested too deeply in {value:?}");
+    }
+    if value.starts_with('#') {
+        return Rgba::try_from(value);
+    }
+    let Some((function, arguments)) = split_function(value) else {
+        return named_color(value).with_context(|| format!("unknown color {value:?}"));
+    };
+    let arguments = split_arguments(arguments);
+
+    match function {
+        "var" => {
+            let [name] = arguments.as_slice() else {
+                bail!("invalid variable reference {value:?}");
+            };
+            let variable = variables
+                .get(*name)
+                .with_context(|| format!("undefined variable {name:?}"))?;
+            resolve_color(variable, variables, depth + 1)
+        }
+        "rgb" | "rgba" => {
+            let [r, g, b, rest @ ..] = arguments.as_slice() else {
+                bail!("invalid color {value:?}");
+            };
+            let channel = |value: &str| -> Result<f32> {
+                match value.strip_suffix('%') {
+                    Some(percent) => Ok(percent.parse::<f32>()? / 100.),
+                    None => Ok(value.parse::<f32>()? / 255.),
+                }
+            };
+            Ok(Rgba {
+                r: channel(r)?,
+                g: channel(g)?,
+                b: channel(b)?,
+                a: rest.first().map_or(Ok(1.), |alpha| parse_fraction(alpha))?,
+            })
+        }
+        "hsl" | "hsla" => {
+            let [h, s, l, rest @ ..] = arguments.as_slice() else {
+                bail!("invalid color {value:?}");
+            };
+            let hue = h.trim_end_matches("deg").parse::<f32>()?.rem_euclid(360.);
+            Ok(Rgba::from(Hsla {
+                h: hue / 360.,
+                s: parse_fraction(s)?,
+                l: parse_fraction(l)?,
+                a: rest.first().map_or(Ok(1.), |alpha| parse_fraction(alpha))?,
+            }))
+        }
+        "color" => {
+            let (base, adjusters) = arguments
+                .split_first()
+                .with_context(|| format!("invalid color {value:?}"))?;
+            let mut color = resolve_color(base, variables, depth + 1)?;
+            for adjuster in adjusters {
+                color = adjust_color(color, adjuster, variables, depth + 1)
+                    .with_context(|| format!("invalid adjuster in {value:?}"))?;
+            }
+            Ok(color)
+        }
+        _ => Err(anyhow!("unsupported color function {function:?}")),
+    }
+}
+
+/// Applies one of the adjusters of a `color()` function, like `alpha(0.5)`,
+/// `l(+ 10%)` or `blend(#fff 80%)`.
+fn adjust_color(
+    color: Rgba,
+    adjuster: &str,
+    variables: &IndexMap<String, String>,
+    depth: usize,
+) -> Result<Rgba> {
+    let (function, arguments) =
+        split_function(adjuster).with_context(|| format!("expected an adjuster: {adjuster:?}"))?;
+    match function {
+        "alpha" | "a" => Ok(Rgba {
+            a: adjust_component(color.a, arguments)?,
+            ..color
+        }),
+        "lightness" | "l" => {
+            let mut hsla = Hsla::from(color);
+            hsla.l = adjust_component(hsla.l, arguments)?;
+            Ok(Rgba::from(hsla))
+        }
+        "saturation" | "s" => {
+            let mut hsla = Hsla::from(color);
+            hsla.s = adjust_component(hsla.s, arguments)?;
+            Ok(Rgba::from(hsla))
+        }
+        "blend" | "blenda" => {
+            let arguments = split_arguments(arguments);
+            let [other, percentage, ..] = arguments.as_slice() else {
+                bail!("invalid blend {adjuster:?}");
+            };
+            let other = resolve_color(other, variables, depth)?;
+            // The percentage is how much of the base color is kept.
+            let blended = mix(color, other, 1. - parse_fraction(percentage)?);
+            Ok(if function == "blend" {
+                Rgba {
+                    a: color.a,
+                    ..blended
+                }
+            } else {
+                blended
+            })
+        }
+        "min-contrast" => {
+            log::warn!("Ignoring unsupported adjuster {adjuster:?}");
+            Ok(color)
+        }
+        _ => Err(anyhow!("unsupported adjuster {function:?}")),
+    }
+}
+
+/// Applies an absolute (`0.5`, `50%`) or relative (`+ 0.1`, `- 10%`, `* 2`)
+/// change to a component in the range 0 to 1.
+fn adjust_component(value: f32, change: &str) -> Result<f32> {
+    let change: String = change.chars().filter(|c| !c.is_whitespace()).collect();
+    let adjusted = if let Some(amount) = change.strip_prefix('+') {
+        value + parse_fraction(amount)?
+    } else if let Some(amount) = change.strip_prefix('-') {
+        value - parse_fraction(amount)?
+    } else if let Some(factor) = change.strip_prefix('*') {
+        value * factor.parse::<f32>()?
+    } else {
+        parse_fraction(&change)?
+    };
+    Ok(adjusted.clamp(0., 1.))
+}
+
+/// Parses a number between 0 and 1, or a percentage.
+fn parse_fraction(value: &str) -> Result<f32> {
+    let fraction = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f32>()? / 100.,
+        None => value.trim().parse::<f32>()?,
+    };
+    Ok(fraction)
+}
+
+/// Splits `name(arguments)` into its name and arguments.
+fn split_function(value: &str) -> Option<(&str, &str)> {
+    let (name, rest) = value.split_once('(')?;
+    let arguments = rest.trim_end().strip_suffix(')')?;
+    Some((name.trim(), arguments))
+}
+
+/// Splits function arguments separated by commas, whitespace or slashes, while
+/// keeping nested function calls intact.
+fn split_arguments(arguments: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (ix, c) in arguments.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' | '/' | ' ' | '\t' | '\n' if depth == 0 => {
+                result.push(&arguments[start..ix]);
+                start = ix + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(&arguments[start..]);
+    result.retain(|argument| !argument.is_empty());
+    result
+}
+
+fn named_color(name: &str) -> Option<Rgba> {
+    let hex = match name.to_ascii_lowercase().as_str() {
+        "transparent" => "#00000000",
+        "black" => "#000000",
+        "white" => "#ffffff",
+        "red" => "#ff0000",
+        "lime" => "#00ff00",
+        "green" => "#008000",
+        "blue" => "#0000ff",
+        "yellow" => "#ffff00",
+        "cyan" | "aqua" => "#00ffff",
+        "magenta" | "fuchsia" => "#ff00ff",
+        "gray" | "grey" => "#808080",
+        "silver" => "#c0c0c0",
+        "maroon" => "#800000",
+        "olive" => "#808000",
+        "navy" => "#000080",
+        "purple" => "#800080",
+        "teal" => "#008080",
+        "orange" => "#ffa500",
+        "pink" => "#ffc0cb",
+        "brown" => "#a52a2a",
+        _ => return None,
+    };
+    Rgba::try_from(hex).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::AppearanceContent;
+
+    fn resolve(value: &str) -> String {
+        let variables = IndexMap::from_iter([
+            ("white".to_string(), "#ffffff".to_string()),
+            ("blue".to_string(), "hsl(210, 50%, 60%)".to_string()),
+            ("foreground".to_string(), "var(white)".to_string()),
+            ("loop".to_string(), "var(loop)".to_string()),
+        ]);
+        match resolve_color(value, &variables, 0) {
+            Ok(color) => rgba_to_hex(color),
+            Err(err) => format!("error: {err}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_colors() {
+        assert_eq!(resolve("#fff"), "#ffffffff");
+        assert_eq!(resolve("#11223344"), "#11223344");
+        assert_eq!(resolve("rgb(255, 0, 0)"), "#ff0000ff");
+        assert_eq!(resolve("rgba(0, 0, 0, 0.5)"), "#00000080");
+        assert_eq!(resolve("rgb(0 0 0 / 50%)"), "#00000080");
+        assert_eq!(resolve("hsl(0, 100%, 50%)"), "#ff0000ff");
+        assert_eq!(resolve("hsla(120, 100%, 50%, 0.5)"), "#00ff0080");
+        assert_eq!(resolve("var(blue)"), "#6699ccff");
+        assert_eq!(resolve("var(foreground)"), "#ffffffff");
+        assert_eq!(resolve("white"), "#ffffffff");
+        assert_eq!(resolve("color(var(white) alpha(0.25))"), "#ffffff40");
+        assert_eq!(resolve("color(var(white) a(- 50%))"), "#ffffff80");
+        assert_eq!(resolve("color(#000 blend(#fff 75%))"), "#404040ff");
+        assert_eq!(resolve("color(hsl(0, 100%, 50%) l(+ 10%))"), "#ff3333ff");
+        assert_eq!(resolve("color(hsl(0, 100%, 50%) s(0%))"), "#808080ff");
+
+        assert_eq!(
+            resolve("var(missing)"),
+            "error: undefined variable \"missing\""
+        );
+        assert_eq!(
+            resolve("var(loop)"),
+            "error: variables are nested too deeply in \"var(loop)\""
+        );
+        assert_eq!(resolve("chartreuse"), "error: unknown color \"chartreuse\"");
+    }
+
+    #[test]
+    fn test_import_sublime_color_scheme() {
+        let scheme = r##"{
+            // Comments and trailing commas are allowed.
+            "name": "Breakers",
+            "author": "Someone",
+            "variables": {
+                "white": "#fafafa",
+                "grey": "hsl(0, 0%, 40%)",
+                "orange": "#f99157",
+            },
+            "globals": {
+                "background": "var(white)",
+                "foreground": "color(var(grey) l(- 20%))",
+                "caret": "var(orange)",
+                "selection": "color(var(orange) alpha(0.25))",
+                "shadow": "not a color",
+            },
+            "rules": [
+                {
+                    "name": "Comment",
+                    "scope": "comment, punctuation.definition.comment",
+                    "foreground": "var(grey)",
+                    "font_style": "italic",
+                },
+                {
+                    "name": "Keyword",
+                    "scope": "keyword",
+                    "foreground": ["var(orange)", "#ff0000"],
+                    "font_style": "bold",
+                },
+                {
+                    "name": "Broken",
+                    "scope": "string",
+                    "foreground": "var(undefined)",
+                },
+            ],
+        }"##;
+
+        let family = parse_sublime_color_scheme(scheme.as_bytes())
+            .unwrap()
+            .convert("breakers")
+            .unwrap();
+        assert_eq!(family.name, "Breakers");
+        assert_eq!(family.author, "Someone");
+
+        let theme = &family.themes[0];
+        assert_eq!(theme.appearance, AppearanceContent::Light);
+        assert_eq!(
+            theme.style.colors.editor_background.as_deref(),
+            Some("#fafafaff")
+        );
+        assert_eq!(
+            theme.style.colors.editor_foreground.as_deref(),
+            Some("#333333ff")
+        );
+        assert_eq!(theme.style.players[0].cursor.as_deref(), Some("#f99157ff"));
+        assert_eq!(
+            theme.style.players[0].selection.as_deref(),
+            Some("#f9915740")
+        );
+
+        let syntax = &theme.style.syntax;
+        assert_eq!(syntax["comment"].color.as_deref(), Some("#666666ff"));
+        assert_eq!(syntax["keyword"].color.as_deref(), Some("#f99157ff"));
+        assert!(syntax["keyword"].font_weight.is_some());
+        assert!(!syntax.contains_key("string"));
+    }
+}
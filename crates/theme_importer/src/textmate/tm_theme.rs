@@ -0,0 +1,178 @@
+use anyhow::{Context as _, Result};
+use collections::IndexMap;
+use gpui::Rgba;
+use serde::Deserialize;
+
+use crate::color::rgba_to_hex;
+use crate::textmate::{TextMateGlobals, TextMateTheme};
+use crate::vscode::{VsCodeTokenColor, VsCodeTokenColorSettings, VsCodeTokenScope};
+
+#[derive(Debug, Deserialize)]
+struct TmTheme {
+    name: Option<String>,
+    author: Option<String>,
+    settings: Vec<TmThemeRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmThemeRule {
+    name: Option<String>,
+    scope: Option<String>,
+    #[serde(default)]
+    settings: IndexMap<String, String>,
+}
+
+/// Parses a TextMate `.tmTheme` property list.
+///
+/// The rule without a scope holds the colors of the editor itself.
+pub(crate) fn parse_tm_theme(content: &[u8]) -> Result<TextMateTheme> {
+    let tm_theme: TmTheme =
+        plist::from_bytes(content).context("failed to parse .tmTheme property list")?;
+
+    let mut globals = TextMateGlobals::default();
+    let mut rules = Vec::new();
+    for rule in tm_theme.settings {
+        let Some(scope) = rule.scope else {
+            for (name, value) in &rule.settings {
+                let Some(global) = globals.get_mut(name) else {
+                    continue;
+                };
+                match Rgba::try_from(value.as_str()) {
+                    Ok(color) => *global = Some(color),
+                    Err(_) => log::warn!("Ignoring invalid color for '{name}': {value:?}"),
+                }
+            }
+            continue;
+        };
+
+        let color = |key: &str| {
+            let value = rule.settings.get(key)?;
+            match Rgba::try_from(value.as_str()) {
+                Ok(color) => Some(rgba_to_hex(color)),
+                Err(_) => {
+                    log::warn!("Ignoring invalid {key} for '{scope}': {value:?}");
+                    None
+                }
+            }
+        };
+        let settings = VsCodeTokenColorSettings {
+            foreground: color("foreground"),
+            background: color("background"),
+            font_style: rule.settings.get("fontStyle").cloned(),
+        };
+        rules.push(VsCodeTokenColor {
+            name: rule.name,
+            scope: Some(VsCodeTokenScope::One(scope)),
+            settings,
+        });
+    }
+
+    Ok(TextMateTheme {
+        name: tm_theme.name,
+        author: tm_theme.author,
+        globals,
+        rules,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::AppearanceContent;
+
+    const MONOKAI: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple Computer//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>Monokai</string>
+    <key>settings</key>
+    <array>
+        <dict>
+            <key>settings</key>
+            <dict>
+                <key>background</key>
+                <string>#272822</string>
+                <key>caret</key>
+                <string>#F8F8F0</string>
+                <key>foreground</key>
+                <string>#F8F8F2</string>
+                <key>lineHighlight</key>
+                <string>#3E3D32</string>
+                <key>selection</key>
+                <string>#49483E</string>
+                <key>bracketsOptions</key>
+                <string>underline</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>name</key>
+            <string>Comment</string>
+            <key>scope</key>
+            <string>comment</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#75715E</string>
+                <key>fontStyle</key>
+                <string>italic</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>name</key>
+            <string>String</string>
+            <key>scope</key>
+            <string>string</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#E6DB74</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>name</key>
+            <string>Function name</string>
+            <key>scope</key>
+            <string>entity.name.function, support.function</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#A6E22E</string>
+            </dict>
+        </dict>
+    </array>
+</dict>
+</plist>"#;
+
+    #[test]
+    fn test_import_tm_theme() {
+        let family = parse_tm_theme(MONOKAI.as_bytes())
+            .unwrap()
+            .convert("monokai")
+            .unwrap();
+        assert_eq!(family.name, "Monokai");
+
+        let theme = &family.themes[0];
+        assert_eq!(theme.appearance, AppearanceContent::Dark);
+
+        let colors = &theme.style.colors;
+        assert_eq!(colors.editor_background.as_deref(), Some("#272822ff"));
+        assert_eq!(colors.editor_foreground.as_deref(), Some("#f8f8f2ff"));
+        assert_eq!(
+            colors.editor_active_line_background.as_deref(),
+            Some("#3e3d32ff")
+        );
+        assert_eq!(
+            theme.style.players[0].selection.as_deref(),
+            Some("#49483eff")
+        );
+
+        let syntax = &theme.style.syntax;
+        assert_eq!(syntax["comment"].color.as_deref(), Some("#75715eff"));
+        assert!(syntax["comment"].font_style.is_some());
+        assert_eq!(syntax["comment.doc"].color.as_deref(), Some("#75715eff"));
+        assert_eq!(syntax["string"].color.as_deref(), Some("#e6db74ff"));
+        assert_eq!(syntax["function"].color.as_deref(), Some("#a6e22eff"));
+        assert!(!syntax.contains_key("keyword"));
+    }
+}
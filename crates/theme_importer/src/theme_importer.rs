@@ -0,0 +1,144 @@
+mod base16;
+mod color;
+mod textmate;
+mod vscode;
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use collections::IndexMap;
+use gpui::Rgba;
+use serde::Deserialize;
+use theme::{Appearance, AppearanceContent, ThemeFamilyContent};
+
+use crate::vscode::{VsCodeTheme, VsCodeThemeConverter};
+
+const ZED_THEME_SCHEMA_URL: &str = "https://zed.dev/schema/themes/v0.2.0.json";
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeAppearanceJson {
+    Light,
+    Dark,
+}
+
+impl From<ThemeAppearanceJson> for AppearanceContent {
+    fn from(value: ThemeAppearanceJson) -> Self {
+        match value {
+            ThemeAppearanceJson::Light => Self::Light,
+            ThemeAppearanceJson::Dark => Self::Dark,
+        }
+    }
+}
+
+impl From<ThemeAppearanceJson> for Appearance {
+    fn from(value: ThemeAppearanceJson) -> Self {
+        match value {
+            ThemeAppearanceJson::Light => Self::Light,
+            ThemeAppearanceJson::Dark => Self::Dark,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ThemeMetadata {
+    pub name: String,
+    pub file_name: String,
+    pub appearance: ThemeAppearanceJson,
+}
+
+/// A theme format that can be converted into a Zed theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeFormat {
+    /// A VS Code color theme (`.json`).
+    VsCode,
+    /// A TextMate theme (`.tmTheme`), also used by older Sublime Text versions.
+    TmTheme,
+    /// A Sublime Text color scheme (`.sublime-color-scheme`).
+    SublimeColorScheme,
+    /// A base16 or base24 scheme (`.yaml`).
+    Base16,
+}
+
+impl ThemeFormat {
+    /// Infers the format of a theme file from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" | "jsonc" => Some(Self::VsCode),
+            "tmtheme" => Some(Self::TmTheme),
+            "sublime-color-scheme" => Some(Self::SublimeColorScheme),
+            "yaml" | "yml" => Some(Self::Base16),
+            _ => None,
+        }
+    }
+}
+
+/// Converts the contents of a theme file into a Zed theme family.
+///
+/// `fallback_name` is used when the theme doesn't name itself, and is usually
+/// the name of the file it came from.
+pub fn import_theme(
+    content: &[u8],
+    format: ThemeFormat,
+    fallback_name: &str,
+) -> Result<ThemeFamilyContent> {
+    match format {
+        ThemeFormat::VsCode => import_vscode_theme(content, fallback_name),
+        ThemeFormat::TmTheme => textmate::parse_tm_theme(content)?.convert(fallback_name),
+        ThemeFormat::SublimeColorScheme => {
+            textmate::parse_sublime_color_scheme(content)?.convert(fallback_name)
+        }
+        ThemeFormat::Base16 => {
+            let content = std::str::from_utf8(content).context("scheme isn't valid UTF-8")?;
+            base16::import_base16_scheme(content, fallback_name)
+        }
+    }
+}
+
+fn import_vscode_theme(content: &[u8], fallback_name: &str) -> Result<ThemeFamilyContent> {
+    let vscode_theme: VsCodeTheme =
+        serde_json_lenient::from_slice(content).context("failed to parse VS Code theme")?;
+
+    let appearance = match vscode_theme.theme_type.as_deref() {
+        Some("light" | "hcLight") => ThemeAppearanceJson::Light,
+        Some("dark" | "hc") => ThemeAppearanceJson::Dark,
+        _ => vscode_theme
+            .colors
+            .editor
+            .background
+            .as_deref()
+            .and_then(|background| Rgba::try_from(background).ok())
+            .map_or(ThemeAppearanceJson::Dark, color::appearance_for_background),
+    };
+    let name = vscode_theme
+        .name
+        .clone()
+        .unwrap_or_else(|| fallback_name.to_string());
+    let author = vscode_theme.author.clone().unwrap_or_default();
+    let theme_metadata = ThemeMetadata {
+        name: name.clone(),
+        appearance,
+        file_name: "".to_string(),
+    };
+
+    let converter = VsCodeThemeConverter::new(vscode_theme, theme_metadata, IndexMap::default());
+
+    Ok(ThemeFamilyContent {
+        name,
+        author,
+        themes: vec![converter.convert()?],
+    })
+}
+
+/// Serializes a theme family into the JSON expected in the themes directory.
+pub fn theme_family_to_json(theme_family: &ThemeFamilyContent) -> Result<String> {
+    let mut theme_family = serde_json::to_value(theme_family)?;
+    if let Some(theme_family) = theme_family.as_object_mut() {
+        theme_family.insert(
+            "$schema".to_string(),
+            serde_json::Value::String(ZED_THEME_SCHEMA_URL.to_string()),
+        );
+    }
+    Ok(serde_json::to_string_pretty(&theme_family)?)
+}
@@ -1,30 +1,12 @@
 use anyhow::Result;
 use collections::IndexMap;
-use strum::IntoEnumIterator;
 use theme::{
-    FontStyleContent, FontWeightContent, HighlightStyleContent, StatusColorsContent,
-    ThemeColorsContent, ThemeContent, ThemeStyleContent, WindowBackgroundContent,
+    HighlightStyleContent, StatusColorsContent, ThemeColorsContent, ThemeContent,
+    ThemeStyleContent, WindowBackgroundContent,
 };
 
 use crate::ThemeMetadata;
-use crate::vscode::{VsCodeTheme, VsCodeTokenScope};
-
-use super::ZedSyntaxToken;
-
-pub(crate) fn try_parse_font_weight(font_style: &str) -> Option<FontWeightContent> {
-    match font_style {
-        style if style.contains("bold") => Some(FontWeightContent::Bold),
-        _ => None,
-    }
-}
-
-pub(crate) fn try_parse_font_style(font_style: &str) -> Option<FontStyleContent> {
-    match font_style {
-        style if style.contains("italic") => Some(FontStyleContent::Italic),
-        style if style.contains("oblique") => Some(FontStyleContent::Oblique),
-        _ => None,
-    }
-}
+use crate::vscode::{VsCodeTheme, convert_syntax_theme};
 
 pub struct VsCodeThemeConverter {
     theme: VsCodeTheme,
@@ -212,65 +194,9 @@ impl VsCodeThemeConverter {
     }
 
     fn convert_syntax_theme(&self) -> Result<IndexMap<String, HighlightStyleContent>> {
-        let mut highlight_styles = IndexMap::default();
-
-        for syntax_token in ZedSyntaxToken::iter() {
-            let override_match = self
-                .syntax_overrides
-                .get(&syntax_token.to_string())
-                .and_then(|scope| {
-                    self.theme.token_colors.iter().find(|token_color| {
-                        token_color.scope == Some(VsCodeTokenScope::Many(scope.clone()))
-                    })
-                });
-
-            let best_match = override_match
-                .or_else(|| syntax_token.find_best_token_color_match(&self.theme.token_colors))
-                .or_else(|| {
-                    syntax_token.fallbacks().iter().find_map(|fallback| {
-                        fallback.find_best_token_color_match(&self.theme.token_colors)
-                    })
-                });
-
-            let Some(token_color) = best_match else {
-                log::warn!("No matching token color found for '{syntax_token}'");
-                continue;
-            };
-
-            log::info!(
-                "Matched '{syntax_token}' to '{}'",
-                token_color
-                    .name
-                    .clone()
-                    .or_else(|| token_color
-                        .scope
-                        .as_ref()
-                        .map(|scope| format!("{:?}", scope)))
-                    .unwrap_or_else(|| "no identifier".to_string())
-            );
-
-            let highlight_style = HighlightStyleContent {
-                color: token_color.settings.foreground.clone(),
-                background_color: token_color.settings.background.clone(),
-                font_style: token_color
-                    .settings
-                    .font_style
-                    .as_ref()
-                    .and_then(|style| try_parse_font_style(style)),
-                font_weight: token_color
-                    .settings
-                    .font_style
-                    .as_ref()
-                    .and_then(|style| try_parse_font_weight(style)),
-            };
-
-            if highlight_style.is_empty() {
-                continue;
-            }
-
-            highlight_styles.insert(syntax_token.to_string(), highlight_style);
-        }
-
-        Ok(highlight_styles)
+        Ok(convert_syntax_theme(
+            &self.theme.token_colors,
+            &self.syntax_overrides,
+        ))
     }
 }
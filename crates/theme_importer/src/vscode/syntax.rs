@@ -1,6 +1,7 @@
-use indexmap::IndexMap;
+use collections::IndexMap;
 use serde::Deserialize;
-use strum::EnumIter;
+use strum::{EnumIter, IntoEnumIterator};
+use theme::{FontStyleContent, FontWeightContent, HighlightStyleContent};
 
 #[derive(Debug, PartialEq, Eq, Deserialize)]
 #[serde(untagged)]
@@ -122,7 +123,7 @@ impl ZedSyntaxToken {
         &self,
         token_colors: &'a [VsCodeTokenColor],
     ) -> Option<&'a VsCodeTokenColor> {
-        let mut ranked_matches = IndexMap::new();
+        let mut ranked_matches = IndexMap::default();
 
         for (ix, token_color) in token_colors.iter().enumerate() {
             if token_color.settings.foreground.is_none() {
@@ -308,3 +309,88 @@ impl ZedSyntaxToken {
         }
     }
 }
+
+pub(crate) fn try_parse_font_weight(font_style: &str) -> Option<FontWeightContent> {
+    match font_style {
+        style if style.contains("bold") => Some(FontWeightContent::Bold),
+        _ => None,
+    }
+}
+
+pub(crate) fn try_parse_font_style(font_style: &str) -> Option<FontStyleContent> {
+    match font_style {
+        style if style.contains("italic") => Some(FontStyleContent::Italic),
+        style if style.contains("oblique") => Some(FontStyleContent::Oblique),
+        _ => None,
+    }
+}
+
+/// Picks the best matching token color for each of Zed's syntax highlights.
+///
+/// `syntax_overrides` maps highlight names to the exact scopes of the token
+/// color that should be used for them.
+pub(crate) fn convert_syntax_theme(
+    token_colors: &[VsCodeTokenColor],
+    syntax_overrides: &IndexMap<String, Vec<String>>,
+) -> IndexMap<String, HighlightStyleContent> {
+    let mut highlight_styles = IndexMap::default();
+
+    for syntax_token in ZedSyntaxToken::iter() {
+        let override_match = syntax_overrides
+            .get(&syntax_token.to_string())
+            .and_then(|scope| {
+                token_colors.iter().find(|token_color| {
+                    token_color.scope == Some(VsCodeTokenScope::Many(scope.clone()))
+                })
+            });
+
+        let best_match = override_match
+            .or_else(|| syntax_token.find_best_token_color_match(token_colors))
+            .or_else(|| {
+                syntax_token
+                    .fallbacks()
+                    .iter()
+                    .find_map(|fallback| fallback.find_best_token_color_match(token_colors))
+            });
+
+        let Some(token_color) = best_match else {
+            log::warn!("No matching token color found for '{syntax_token}'");
+            continue;
+        };
+
+        log::info!(
+            "Matched '{syntax_token}' to '{}'",
+            token_color
+                .name
+                .clone()
+                .or_else(|| token_color
+                    .scope
+                    .as_ref()
+                    .map(|scope| format!("{:?}", scope)))
+                .unwrap_or_else(|| "no identifier".to_string())
+        );
+
+        let highlight_style = HighlightStyleContent {
+            color: token_color.settings.foreground.clone(),
+            background_color: token_color.settings.background.clone(),
+            font_style: token_color
+                .settings
+                .font_style
+                .as_ref()
+                .and_then(|style| try_parse_font_style(style)),
+            font_weight: token_color
+                .settings
+                .font_style
+                .as_ref()
+                .and_then(|style| try_parse_font_weight(style)),
+        };
+
+        if highlight_style.is_empty() {
+            continue;
+        }
+
+        highlight_styles.insert(syntax_token.to_string(), highlight_style);
+    }
+
+    highlight_styles
+}
@@ -12,11 +12,10 @@ pub struct VsCodeTheme {
     )]
     pub schema: Option<String>,
     pub name: Option<String>,
-    #[expect(
-        unused,
-        reason = "This field was found to be unused with serde library bump; it's left as is due to insufficient context on PO's side, but it *may* be fine to remove"
-    )]
     pub author: Option<String>,
+    /// Either `dark`, `light`, `hc` or `hcLight`.
+    #[serde(rename = "type")]
+    pub theme_type: Option<String>,
     #[expect(
         unused,
         reason = "This field was found to be unused with serde library bump; it's left as is due to insufficient context on PO's side, but it *may* be fine to remove"
@@ -0,0 +1,100 @@
+use anyhow::Context as _;
+use gpui::{Context, PathPromptOptions, PromptLevel, Window};
+use theme::ThemeRegistry;
+use theme_importer::{ThemeFormat, import_theme, theme_family_to_json};
+use workspace::{Workspace, notifications::DetachAndPromptErr as _};
+
+use crate::toggle_theme_selector;
+
+/// Prompts for a VS Code, TextMate, Sublime Text or base16 theme, converts it
+/// into the themes directory and shows the imported themes in the theme
+/// selector.
+pub(crate) fn import_theme_from_file(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+        prompt: Some("Import Theme".into()),
+    });
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(path) = paths.await??.and_then(|mut paths| paths.pop()) else {
+            return Ok(());
+        };
+        let format = ThemeFormat::from_path(&path).with_context(|| {
+            format!("{path:?} isn't a VS Code, TextMate, Sublime Text or base16 theme")
+        })?;
+        let content = fs.load_bytes(&path).await?;
+        let fallback_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let theme_family = import_theme(&content, format, &fallback_name)
+            .with_context(|| format!("failed to convert {path:?}"))?;
+
+        let themes_dir = paths::themes_dir();
+        fs.create_dir(themes_dir).await?;
+        let file_name = format!("{}.json", theme_file_stem(&theme_family.name));
+        let destination = themes_dir.join(file_name);
+        if fs.is_file(&destination).await {
+            let answer = workspace.update_in(cx, |_, window, cx| {
+                window.prompt(
+                    PromptLevel::Warning,
+                    &format!(
+                        "A theme named \"{}\" is already installed",
+                        theme_family.name
+                    ),
+                    Some("Do you want to replace it?"),
+                    &["Replace", "Cancel"],
+                    cx,
+                )
+            })?;
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+        }
+        fs.atomic_write(destination.clone(), theme_family_to_json(&theme_family)?)
+            .await?;
+
+        let theme_registry = cx.update(|_, cx| ThemeRegistry::global(cx))?;
+        theme_registry.load_user_theme(&destination, fs).await?;
+
+        let themes_filter = theme_family
+            .themes
+            .into_iter()
+            .map(|theme| theme.name)
+            .collect();
+        workspace.update_in(cx, |workspace, window, cx| {
+            toggle_theme_selector(
+                workspace,
+                &zed_actions::theme_selector::Toggle {
+                    themes_filter: Some(themes_filter),
+                },
+                window,
+                cx,
+            );
+        })?;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to import theme", window, cx, |_, _, _| None);
+}
+
+/// Turns a theme family name into a file name that's valid on every platform.
+fn theme_file_stem(name: &str) -> String {
+    let stem = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-");
+    if stem.is_empty() {
+        "imported-theme".to_string()
+    } else {
+        stem
+    }
+}
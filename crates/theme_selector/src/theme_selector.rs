@@ -1,4 +1,5 @@
 mod icon_theme_selector;
+mod theme_import;
 
 use fs::Fs;
 use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
@@ -24,7 +25,9 @@ actions!(
     theme_selector,
     [
         /// Reloads all themes from disk.
-        Reload
+        Reload,
+        /// Imports a VS Code, TextMate, Sublime Text or base16 theme from a file.
+        ImportFromFile
     ]
 );
 
@@ -41,6 +44,11 @@ pub fn init(cx: &mut App) {
             toggle_icon_theme_selector(workspace, &action, window, cx);
         });
     });
+    cx.on_action(|_: &ImportFromFile, cx| {
+        with_active_or_new_workspace(cx, |workspace, window, cx| {
+            theme_import::import_theme_from_file(workspace, window, cx);
+        });
+    });
 }
 
 fn toggle_theme_selector(
@@ -506,17 +514,29 @@ impl PickerDelegate for ThemeSelectorDelegate {
                         })),
                 )
                 .child(
-                    Button::new("more-themes", "Install Themes").on_click(cx.listener({
-                        move |_, _, window, cx| {
-                            window.dispatch_action(
-                                Box::new(Extensions {
-                                    category_filter: Some(ExtensionCategoryFilter::Themes),
-                                    id: None,
-                                }),
-                                cx,
-                            );
-                        }
-                    })),
+                    h_flex()
+                        .gap_1()
+                        .child(Button::new("import-theme", "Import From File…").on_click(
+                            cx.listener(|this, _, window, cx| {
+                                // Close the selector first, so it can be reopened
+                                // with the imported themes.
+                                this.delegate.dismissed(window, cx);
+                                window.dispatch_action(Box::new(ImportFromFile), cx);
+                            }),
+                        ))
+                        .child(
+                            Button::new("more-themes", "Install Themes").on_click(cx.listener({
+                                move |_, _, window, cx| {
+                                    window.dispatch_action(
+                                        Box::new(Extensions {
+                                            category_filter: Some(ExtensionCategoryFilter::Themes),
+                                            id: None,
+                                        }),
+                                        cx,
+                                    );
+                                }
+                            })),
+                        ),
                 )
                 .into_any_element(),
         )
@@ -328,6 +328,10 @@ impl TitleBar {
                 (options.nickname.map(|nick| nick.into()), IconName::Server)
             }
             RemoteConnectionOptions::Wsl(_) => (None, IconName::Linux),
+            RemoteConnectionOptions::Docker(_) => (None, IconName::Server),
+            RemoteConnectionOptions::Command(options) => {
+                (options.nickname.map(|nick| nick.into()), IconName::Server)
+            }
         };
         let nickname = nickname.unwrap_or_else(|| host.clone());
 
@@ -195,8 +195,10 @@ impl ActiveToolchain {
                             toolchains.first()
                         }
                     })
-                    .or_else(|| toolchains.toolchains.first())
-                    .cloned();
+                    .cloned()
+                    // Otherwise only pick the lister's default, e.g. a version pinned by the
+                    // project, and leave the toolchain found on `PATH` in use if there's none.
+                    .or_else(|| toolchains.default_toolchain());
                 if let Some(toolchain) = &default_choice {
                     workspace::WORKSPACE_DB
                         .set_toolchain(
@@ -301,6 +301,35 @@ fn load_shell_from_passwd() -> Result<()> {
     Ok(())
 }
 
+/// Returns the name of this machine, as reported by the operating system.
+pub fn hostname() -> Option<String> {
+    #[cfg(unix)]
+    {
+        let mut buffer = [0u8; 256];
+        let status =
+            unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) };
+        if status != 0 {
+            return None;
+        }
+        let len = buffer.iter().position(|byte| *byte == 0)?;
+        String::from_utf8(buffer[..len].to_vec())
+            .ok()
+            .filter(|name| !name.is_empty())
+    }
+
+    #[cfg(windows)]
+    {
+        env::var("COMPUTERNAME")
+            .ok()
+            .filter(|name| !name.is_empty())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        None
+    }
+}
+
 /// Returns a shell escaped path for the current zed executable
 pub fn get_shell_safe_zed_path(shell_kind: shell::ShellKind) -> anyhow::Result<String> {
     let zed_path =
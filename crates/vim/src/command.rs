@@ -2,7 +2,7 @@ use anyhow::{Result, anyhow};
 use collections::{HashMap, HashSet};
 use command_palette_hooks::{CommandInterceptItem, CommandInterceptResult};
 use editor::{
-    Bias, Editor, EditorSettings, SelectionEffects, ToPoint,
+    Bias, Editor, SelectionEffects, ToPoint,
     actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive},
     display_map::ToDisplayPoint,
 };
@@ -18,9 +18,10 @@ use regex::Regex;
 use schemars::JsonSchema;
 use search::{BufferSearchBar, SearchOptions};
 use serde::Deserialize;
-use settings::{Settings, SettingsStore};
+use settings::{CurrentLineHighlight, SettingsContent, UseSystemClipboard};
 use std::{
     iter::Peekable,
+    num::NonZeroU32,
     ops::{Deref, Range},
     path::{Path, PathBuf},
     process::Stdio,
@@ -48,6 +49,7 @@ use crate::{
     },
     object::Object,
     state::{Mark, Mode},
+    vimrc::{self, Vimrc},
     visual::VisualDeleteLine,
 };
 
@@ -82,12 +84,19 @@ pub struct WithCount {
     action: WrappedAction,
 }
 
-#[derive(Clone, Deserialize, JsonSchema, PartialEq)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq)]
 pub enum VimOption {
     Wrap(bool),
     Number(bool),
     RelativeNumber(bool),
     IgnoreCase(bool),
+    SmartCase(bool),
+    CursorLine(bool),
+    ExpandTab(bool),
+    TabStop(u32),
+    ScrollOff(u32),
+    TextWidth(u32),
+    Clipboard(UseSystemClipboard),
 }
 
 impl VimOption {
@@ -103,16 +112,18 @@ impl VimOption {
             }
         }
 
-        Self::possibilities(prefix)
+        let possibilities = Self::possibilities(prefix).chain(
+            // options that take a value can't be enumerated, so only offer them once typed out
+            Self::from(prefix).filter(|option| Self::possibilities(prefix).all(|p| p != *option)),
+        );
+
+        possibilities
             .map(|possible| {
                 let mut options = prefix_of_options.clone();
                 options.push(possible);
 
                 CommandInterceptItem {
-                    string: format!(
-                        ":set {}",
-                        options.iter().map(|opt| opt.to_string()).join(" ")
-                    ),
+                    string: format!(":set {}", options.iter().join(" ")),
                     action: VimSet { options }.boxed_clone(),
                     positions: vec![],
                 }
@@ -134,13 +145,34 @@ impl VimOption {
             (None, VimOption::IgnoreCase(false)),
             (Some("ic"), VimOption::IgnoreCase(true)),
             (Some("noic"), VimOption::IgnoreCase(false)),
+            (None, VimOption::SmartCase(true)),
+            (None, VimOption::SmartCase(false)),
+            (Some("scs"), VimOption::SmartCase(true)),
+            (Some("noscs"), VimOption::SmartCase(false)),
+            (None, VimOption::CursorLine(true)),
+            (None, VimOption::CursorLine(false)),
+            (Some("cul"), VimOption::CursorLine(true)),
+            (Some("nocul"), VimOption::CursorLine(false)),
+            (None, VimOption::ExpandTab(true)),
+            (None, VimOption::ExpandTab(false)),
+            (Some("et"), VimOption::ExpandTab(true)),
+            (Some("noet"), VimOption::ExpandTab(false)),
         ]
         .into_iter()
-        .filter(move |(prefix, option)| prefix.unwrap_or(option.to_string()).starts_with(query))
+        .filter(move |(prefix, option)| {
+            prefix
+                .map(ToString::to_string)
+                .unwrap_or_else(|| option.to_string())
+                .starts_with(query)
+        })
         .map(|(_, option)| option)
     }
 
-    fn from(option: &str) -> Option<Self> {
+    pub(crate) fn from(option: &str) -> Option<Self> {
+        if let Some((name, value)) = option.split_once('=') {
+            return Self::from_assignment(name, value);
+        }
+
         match option {
             "wrap" => Some(Self::Wrap(true)),
             "nowrap" => Some(Self::Wrap(false)),
@@ -160,20 +192,155 @@ impl VimOption {
             "noignorecase" => Some(Self::IgnoreCase(false)),
             "noic" => Some(Self::IgnoreCase(false)),
 
+            "smartcase" => Some(Self::SmartCase(true)),
+            "scs" => Some(Self::SmartCase(true)),
+            "nosmartcase" => Some(Self::SmartCase(false)),
+            "noscs" => Some(Self::SmartCase(false)),
+
+            "cursorline" => Some(Self::CursorLine(true)),
+            "cul" => Some(Self::CursorLine(true)),
+            "nocursorline" => Some(Self::CursorLine(false)),
+            "nocul" => Some(Self::CursorLine(false)),
+
+            "expandtab" => Some(Self::ExpandTab(true)),
+            "et" => Some(Self::ExpandTab(true)),
+            "noexpandtab" => Some(Self::ExpandTab(false)),
+            "noet" => Some(Self::ExpandTab(false)),
+
             _ => None,
         }
     }
 
-    fn to_string(&self) -> &'static str {
+    fn from_assignment(name: &str, value: &str) -> Option<Self> {
+        match name {
+            // Zed has a single indentation width, so `shiftwidth` and `softtabstop`
+            // are treated as aliases of `tabstop`.
+            "tabstop" | "ts" | "shiftwidth" | "sw" | "softtabstop" | "sts" => {
+                value.parse().ok().filter(|n| *n > 0).map(Self::TabStop)
+            }
+            "scrolloff" | "so" => value.parse().ok().map(Self::ScrollOff),
+            "textwidth" | "tw" => value.parse().ok().map(Self::TextWidth),
+            "clipboard" | "cb" => match value {
+                "" => Some(Self::Clipboard(UseSystemClipboard::Never)),
+                "unnamed" | "unnamedplus" | "unnamed,unnamedplus" | "unnamedplus,unnamed" => {
+                    Some(Self::Clipboard(UseSystemClipboard::Always))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether the option applies to every editor (by overriding settings)
+    /// rather than only to the editor it was set in.
+    pub(crate) fn is_global(&self) -> bool {
+        match self {
+            VimOption::Wrap(_)
+            | VimOption::Number(_)
+            | VimOption::RelativeNumber(_)
+            | VimOption::CursorLine(_)
+            | VimOption::TextWidth(_) => false,
+            VimOption::IgnoreCase(_)
+            | VimOption::SmartCase(_)
+            | VimOption::ExpandTab(_)
+            | VimOption::TabStop(_)
+            | VimOption::ScrollOff(_)
+            | VimOption::Clipboard(_) => true,
+        }
+    }
+
+    /// Writes an option that [`is_global`](Self::is_global) into the settings that vim
+    /// mode layers on top of the user's settings.
+    pub(crate) fn apply_to_settings(&self, settings: &mut SettingsContent) {
+        match self {
+            VimOption::IgnoreCase(enabled) => {
+                settings
+                    .editor
+                    .search
+                    .get_or_insert_default()
+                    .case_sensitive = Some(!*enabled);
+            }
+            VimOption::SmartCase(enabled) => {
+                settings.vim.get_or_insert_default().use_smartcase_find = Some(*enabled);
+            }
+            VimOption::Clipboard(clipboard) => {
+                settings.vim.get_or_insert_default().use_system_clipboard = Some(*clipboard);
+            }
+            VimOption::ScrollOff(lines) => {
+                settings.editor.vertical_scroll_margin = Some(*lines as f32);
+            }
+            VimOption::ExpandTab(enabled) => {
+                settings.project.all_languages.defaults.hard_tabs = Some(!*enabled);
+            }
+            VimOption::TabStop(width) => {
+                settings.project.all_languages.defaults.tab_size = NonZeroU32::new(*width);
+            }
+            VimOption::Wrap(_)
+            | VimOption::Number(_)
+            | VimOption::RelativeNumber(_)
+            | VimOption::CursorLine(_)
+            | VimOption::TextWidth(_) => {}
+        }
+    }
+
+    /// Applies an option that is local to the given editor.
+    pub(crate) fn apply_local(&self, editor: &mut Editor, cx: &mut Context<Editor>) {
+        match self {
+            VimOption::Wrap(true) => {
+                editor.set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
+            }
+            VimOption::Wrap(false) => {
+                editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
+            }
+            VimOption::Number(enabled) => {
+                editor.set_show_line_numbers(*enabled, cx);
+            }
+            VimOption::RelativeNumber(enabled) => {
+                editor.set_relative_line_number(Some(*enabled), cx);
+            }
+            VimOption::CursorLine(enabled) => {
+                editor.set_current_line_highlight(Some(if *enabled {
+                    CurrentLineHighlight::All
+                } else {
+                    CurrentLineHighlight::None
+                }));
+                cx.notify();
+            }
+            VimOption::TextWidth(width) => {
+                editor.set_hard_wrap((*width > 0).then_some(*width as usize), cx);
+            }
+            VimOption::IgnoreCase(_)
+            | VimOption::SmartCase(_)
+            | VimOption::ExpandTab(_)
+            | VimOption::TabStop(_)
+            | VimOption::ScrollOff(_)
+            | VimOption::Clipboard(_) => {}
+        }
+    }
+}
+
+impl std::fmt::Display for VimOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            VimOption::Wrap(true) => "wrap",
-            VimOption::Wrap(false) => "nowrap",
-            VimOption::Number(true) => "number",
-            VimOption::Number(false) => "nonumber",
-            VimOption::RelativeNumber(true) => "relativenumber",
-            VimOption::RelativeNumber(false) => "norelativenumber",
-            VimOption::IgnoreCase(true) => "ignorecase",
-            VimOption::IgnoreCase(false) => "noignorecase",
+            VimOption::Wrap(true) => write!(f, "wrap"),
+            VimOption::Wrap(false) => write!(f, "nowrap"),
+            VimOption::Number(true) => write!(f, "number"),
+            VimOption::Number(false) => write!(f, "nonumber"),
+            VimOption::RelativeNumber(true) => write!(f, "relativenumber"),
+            VimOption::RelativeNumber(false) => write!(f, "norelativenumber"),
+            VimOption::IgnoreCase(true) => write!(f, "ignorecase"),
+            VimOption::IgnoreCase(false) => write!(f, "noignorecase"),
+            VimOption::SmartCase(true) => write!(f, "smartcase"),
+            VimOption::SmartCase(false) => write!(f, "nosmartcase"),
+            VimOption::CursorLine(true) => write!(f, "cursorline"),
+            VimOption::CursorLine(false) => write!(f, "nocursorline"),
+            VimOption::ExpandTab(true) => write!(f, "expandtab"),
+            VimOption::ExpandTab(false) => write!(f, "noexpandtab"),
+            VimOption::TabStop(width) => write!(f, "tabstop={width}"),
+            VimOption::ScrollOff(lines) => write!(f, "scrolloff={lines}"),
+            VimOption::TextWidth(width) => write!(f, "textwidth={width}"),
+            VimOption::Clipboard(UseSystemClipboard::Never) => write!(f, "clipboard="),
+            VimOption::Clipboard(_) => write!(f, "clipboard=unnamedplus"),
         }
     }
 }
@@ -237,6 +404,27 @@ struct VimNorm {
     pub command: String,
 }
 
+/// Evaluates a line of vimscript, such as a mapping or a `:command` definition.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct VimrcLine {
+    pub line: String,
+}
+
+/// Loads a file of vimscript on top of the vimrc.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct VimSource {
+    pub filename: String,
+}
+
+/// Runs the ex commands that a user-defined `:command` expands to.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct RunUserCommand {
+    actions: Vec<WrappedAction>,
+}
+
 #[derive(Debug)]
 struct WrappedAction(Box<dyn Action>);
 
@@ -262,31 +450,83 @@ impl Deref for WrappedAction {
 pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     // Vim::action(editor, cx, |vim, action: &StartOfLine, window, cx| {
     Vim::action(editor, cx, |vim, action: &VimSet, _, cx| {
+        let mut vimrc = cx.try_global::<Vimrc>().cloned().unwrap_or_default();
         for option in action.options.iter() {
-            vim.update_editor(cx, |_, editor, cx| match option {
-                VimOption::Wrap(true) => {
-                    editor
-                        .set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
-                }
-                VimOption::Wrap(false) => {
-                    editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
-                }
-                VimOption::Number(enabled) => {
-                    editor.set_show_line_numbers(*enabled, cx);
-                }
-                VimOption::RelativeNumber(enabled) => {
-                    editor.set_relative_line_number(Some(*enabled), cx);
-                }
-                VimOption::IgnoreCase(enabled) => {
-                    let mut settings = EditorSettings::get_global(cx).clone();
-                    settings.search.case_sensitive = !*enabled;
-                    SettingsStore::update(cx, |store, _| {
-                        store.override_global(settings);
-                    });
-                }
+            if option.is_global() {
+                vimrc.set_option(option.clone());
+            } else {
+                vim.update_editor(cx, |_, editor, cx| option.apply_local(editor, cx));
+            }
+        }
+        vimrc.activate(cx);
+    });
+    Vim::action(editor, cx, |vim, action: &VimrcLine, window, cx| {
+        let mut vimrc = cx.try_global::<Vimrc>().cloned().unwrap_or_default();
+        let result = vimrc.eval_line(&action.line);
+        if result.is_ok() {
+            vimrc.activate(cx);
+        } else if let Some(workspace) = vim.workspace(window) {
+            workspace.update(cx, |workspace, cx| {
+                result.notify_err(workspace, cx);
             });
         }
     });
+
+    Vim::action(editor, cx, |vim, action: &VimSource, window, cx| {
+        let Some(workspace) = vim.workspace(window) else {
+            return;
+        };
+        let fs = workspace.read(cx).app_state().fs.clone();
+        let path = if let Some(path) = action.filename.strip_prefix("~/") {
+            util::paths::home_dir().join(path)
+        } else {
+            let path = PathBuf::from(&action.filename);
+            match workspace
+                .read(cx)
+                .project()
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+            {
+                Some(worktree) if path.is_relative() => worktree.read(cx).abs_path().join(path),
+                _ => path,
+            }
+        };
+
+        cx.spawn_in(window, async move |vim, cx| {
+            let content = fs.load(&path).await;
+            vim.update_in(cx, |vim, window, cx| {
+                let content = match content {
+                    Ok(content) => content,
+                    Err(error) => {
+                        if let Some(workspace) = vim.workspace(window) {
+                            workspace.update(cx, |workspace, cx| {
+                                Err::<(), _>(error.context(format!("sourcing {path:?}")))
+                                    .notify_err(workspace, cx);
+                            });
+                        }
+                        return;
+                    }
+                };
+                let mut vimrc = cx.try_global::<Vimrc>().cloned().unwrap_or_default();
+                let errors = vimrc.extend(&content);
+                for option in vimrc.options().iter().filter(|option| !option.is_global()) {
+                    vim.update_editor(cx, |_, editor, cx| option.apply_local(editor, cx));
+                }
+                vimrc.activate(cx);
+                vimrc::report_errors(errors, cx);
+            })
+            .ok();
+        })
+        .detach();
+    });
+
+    Vim::action(editor, cx, |_, action: &RunUserCommand, window, cx| {
+        for action in &action.actions {
+            window.dispatch_action(action.boxed_clone(), cx);
+        }
+    });
+
     Vim::action(editor, cx, |vim, _: &VisualCommand, window, cx| {
         let Some(workspace) = vim.workspace(window) else {
             return;
@@ -1602,11 +1842,49 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("ex", ""), editor::actions::ReloadFile).bang(editor::actions::ReloadFile),
         VimCommand::new(("cpp", "link"), editor::actions::CopyPermalinkToLine).range(act_on_range),
         VimCommand::str(("opt", "ions"), "zed::OpenDefaultSettings"),
-        VimCommand::str(("map", ""), "vim::OpenDefaultKeymap"),
+        VimCommand::str(("map", ""), "vim::OpenDefaultKeymap").args(|_, args| {
+            Some(
+                VimrcLine {
+                    line: format!("map {args}"),
+                }
+                .boxed_clone(),
+            )
+        }),
+        vimrc_command(("no", "remap"), "noremap"),
+        vimrc_command(("nm", "ap"), "nmap"),
+        vimrc_command(("nn", "oremap"), "nnoremap"),
+        vimrc_command(("vm", "ap"), "vmap"),
+        vimrc_command(("vn", "oremap"), "vnoremap"),
+        vimrc_command(("xm", "ap"), "xmap"),
+        vimrc_command(("xn", "oremap"), "xnoremap"),
+        vimrc_command(("om", "ap"), "omap"),
+        vimrc_command(("ono", "remap"), "onoremap"),
+        vimrc_command(("im", "ap"), "imap"),
+        vimrc_command(("ino", "remap"), "inoremap"),
+        vimrc_command(("unm", "ap"), "unmap"),
+        vimrc_command(("nun", "map"), "nunmap"),
+        vimrc_command(("vu", "nmap"), "vunmap"),
+        vimrc_command(("iu", "nmap"), "iunmap"),
+        vimrc_command(("com", "mand"), "command!"),
+        vimrc_command(("delc", "ommand"), "delcommand"),
+        VimCommand::new(("so", "urce"), ArgumentRequired)
+            .filename(|_, filename| Some(VimSource { filename }.boxed_clone())),
         VimCommand::new(("h", "elp"), OpenDocs),
     ]
 }
 
+/// A command that evaluates its arguments as a line of vimscript.
+fn vimrc_command(pattern: (&'static str, &'static str), command: &'static str) -> VimCommand {
+    VimCommand::new(pattern, ArgumentRequired).args(move |_, args| {
+        Some(
+            VimrcLine {
+                line: format!("{command} {args}"),
+            }
+            .boxed_clone(),
+        )
+    })
+}
+
 struct VimCommands(Vec<VimCommand>);
 // safety: we only ever access this from the main thread (as ensured by the cx argument)
 // actions are not Sync so we can't otherwise use a OnceLock.
@@ -1666,31 +1944,7 @@ pub fn command_interceptor(
     let has_trailing_space = query.ends_with(" ");
     let mut query = query.as_str().trim();
 
-    let on_matching_lines = (query.starts_with('g') || query.starts_with('v'))
-        .then(|| {
-            let (pattern, range, search, invert) = OnMatchingLines::parse(query, &range)?;
-            let start_idx = query.len() - pattern.len();
-            query = query[start_idx..].trim();
-            Some((range, search, invert))
-        })
-        .flatten();
-
-    let mut action = if range.is_some() && query.is_empty() {
-        Some(
-            GoToLine {
-                range: range.clone().unwrap(),
-            }
-            .boxed_clone(),
-        )
-    } else if query.starts_with('/') || query.starts_with('?') {
-        Some(
-            FindCommand {
-                query: query[1..].to_string(),
-                backwards: query.starts_with('?'),
-            }
-            .boxed_clone(),
-        )
-    } else if query.starts_with("se ") || query.starts_with("set ") {
+    if query.starts_with("se ") || query.starts_with("set ") {
         let (prefix, option) = query.split_once(' ').unwrap();
         let mut commands = VimOption::possible_commands(option);
         if !commands.is_empty() {
@@ -1703,45 +1957,10 @@ pub fn command_interceptor(
             results: commands,
             exclusive: false,
         });
-    } else if query.starts_with('s') {
-        let mut substitute = "substitute".chars().peekable();
-        let mut query = query.chars().peekable();
-        while substitute
-            .peek()
-            .is_some_and(|char| Some(char) == query.peek())
-        {
-            substitute.next();
-            query.next();
-        }
-        if let Some(replacement) = Replacement::parse(query) {
-            let range = range.clone().unwrap_or(CommandRange {
-                start: Position::CurrentLine { offset: 0 },
-                end: None,
-            });
-            Some(ReplaceCommand { replacement, range }.boxed_clone())
-        } else {
-            None
-        }
-    } else if query.contains('!') {
-        ShellExec::parse(query, range.clone())
-    } else if on_matching_lines.is_some() {
-        commands(cx)
-            .iter()
-            .find_map(|command| command.parse(query, &range, cx))
-    } else {
-        None
-    };
+    }
 
-    if let Some((range, search, invert)) = on_matching_lines
-        && let Some(ref inner) = action
-    {
-        action = Some(Box::new(OnMatchingLines {
-            range,
-            search,
-            action: WrappedAction(inner.boxed_clone()),
-            invert,
-        }));
-    };
+    let action =
+        user_command_action(query, cx).or_else(|| parse_special_command(&mut query, &range, cx));
 
     if let Some(action) = action {
         let string = input.to_string();
@@ -1856,6 +2075,122 @@ pub fn command_interceptor(
     }
 }
 
+/// Parses the commands that don't come from the [`commands`] list, such as
+/// `:s`, `:g`, searches and bare ranges. `query` is advanced past any `:g` prefix.
+fn parse_special_command(
+    query: &mut &str,
+    range: &Option<CommandRange>,
+    cx: &App,
+) -> Option<Box<dyn Action>> {
+    let mut current = *query;
+    let on_matching_lines = (current.starts_with('g') || current.starts_with('v'))
+        .then(|| {
+            let (pattern, range, search, invert) = OnMatchingLines::parse(current, range)?;
+            let start_idx = current.len() - pattern.len();
+            current = current[start_idx..].trim();
+            Some((range, search, invert))
+        })
+        .flatten();
+    *query = current;
+    let query = current;
+
+    let mut action = if range.is_some() && query.is_empty() {
+        Some(
+            GoToLine {
+                range: range.clone().unwrap(),
+            }
+            .boxed_clone(),
+        )
+    } else if query.starts_with('/') || query.starts_with('?') {
+        Some(
+            FindCommand {
+                query: query[1..].to_string(),
+                backwards: query.starts_with('?'),
+            }
+            .boxed_clone(),
+        )
+    } else if query.starts_with('s') {
+        let mut substitute = "substitute".chars().peekable();
+        let mut query = query.chars().peekable();
+        while substitute
+            .peek()
+            .is_some_and(|char| Some(char) == query.peek())
+        {
+            substitute.next();
+            query.next();
+        }
+        if let Some(replacement) = Replacement::parse(query) {
+            let range = range.clone().unwrap_or(CommandRange {
+                start: Position::CurrentLine { offset: 0 },
+                end: None,
+            });
+            Some(ReplaceCommand { replacement, range }.boxed_clone())
+        } else {
+            None
+        }
+    } else if query.contains('!') {
+        ShellExec::parse(query, range.clone())
+    } else if on_matching_lines.is_some() {
+        commands(cx)
+            .iter()
+            .find_map(|command| command.parse(query, range, cx))
+    } else {
+        None
+    };
+
+    if let Some((range, search, invert)) = on_matching_lines
+        && let Some(ref inner) = action
+    {
+        action = Some(Box::new(OnMatchingLines {
+            range,
+            search,
+            action: WrappedAction(inner.boxed_clone()),
+            invert,
+        }));
+    };
+
+    action
+}
+
+/// Parses a complete ex command, without offering completions.
+fn ex_command_action(input: &str, cx: &App) -> Option<Box<dyn Action>> {
+    let (range, query) = VimCommand::parse_range(input.trim_start_matches(':'));
+    let mut query = query.trim();
+
+    if let Some(options) = query
+        .strip_prefix("set ")
+        .or_else(|| query.strip_prefix("se "))
+    {
+        let options = options
+            .split_whitespace()
+            .map(VimOption::from)
+            .collect::<Option<Vec<_>>>()?;
+        return Some(VimSet { options }.boxed_clone());
+    }
+
+    parse_special_command(&mut query, &range, cx).or_else(|| {
+        commands(cx)
+            .iter()
+            .find_map(|command| command.parse(query, &range, cx))
+    })
+}
+
+/// Expands a command defined with `:command` into the actions it runs.
+fn user_command_action(query: &str, cx: &App) -> Option<Box<dyn Action>> {
+    if !query.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let (name, args) = query.split_once(' ').unwrap_or((query, ""));
+    let commands = cx
+        .try_global::<Vimrc>()?
+        .expand_user_command(name, args.trim())?;
+    let actions = commands
+        .iter()
+        .map(|command| ex_command_action(command, cx).map(WrappedAction))
+        .collect::<Option<Vec<_>>>()?;
+    Some(RunUserCommand { actions }.boxed_clone())
+}
+
 fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     let mut positions = Vec::new();
     let mut chars = query.chars();
@@ -2447,11 +2782,13 @@ mod test {
         VimAddon,
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
+        vimrc::Vimrc,
     };
     use editor::{Editor, EditorSettings};
     use gpui::{Context, TestAppContext};
     use indoc::indoc;
-    use settings::Settings;
+    use language::language_settings::AllLanguageSettings;
+    use settings::{Settings, SettingsStore};
     use util::path;
     use workspace::{OpenOptions, Workspace};
 
@@ -3053,6 +3390,103 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_set_value_options(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.simulate_keystrokes(": set space t s = 3");
+        cx.simulate_keystrokes("enter");
+        cx.read(|cx| {
+            assert_eq!(
+                AllLanguageSettings::get_global(cx).defaults.tab_size.get(),
+                3
+            );
+        });
+        cx.simulate_keystrokes(": set space n o e t");
+        cx.simulate_keystrokes("enter");
+        cx.read(|cx| {
+            assert!(AllLanguageSettings::get_global(cx).defaults.hard_tabs);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_options(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let tab_size = |cx: &mut VimTestContext| {
+            cx.read(|cx| AllLanguageSettings::get_global(cx).defaults.tab_size.get())
+        };
+        let default_tab_size = tab_size(&mut cx);
+
+        cx.update(|_, cx| Vimrc::parse("set ts=3").0.activate(cx));
+        assert_eq!(tab_size(&mut cx), 3);
+
+        // The options outlive changes to the settings file.
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.editor.vertical_scroll_margin = Some(5.);
+            });
+        });
+        assert_eq!(tab_size(&mut cx), 3);
+
+        // The options only apply while vim mode is enabled.
+        let set_vim_mode = |enabled, cx: &mut VimTestContext| {
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings(cx, |settings| settings.vim_mode = Some(enabled));
+            });
+        };
+        set_vim_mode(false, &mut cx);
+        assert_eq!(tab_size(&mut cx), default_tab_size);
+        set_vim_mode(true, &mut cx);
+        assert_eq!(tab_size(&mut cx), 3);
+
+        // Options that are removed from the vimrc are reverted.
+        cx.update(|_, cx| Vimrc::parse("").0.activate(cx));
+        assert_eq!(tab_size(&mut cx), default_tab_size);
+    }
+
+    #[gpui::test]
+    async fn test_user_command(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.simulate_keystrokes(": c o m m a n d space T n space t a b n e w enter");
+        cx.simulate_keystrokes(": T n enter");
+        cx.workspace(|workspace, _, cx| assert_eq!(workspace.items(cx).count(), 2));
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_mappings(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update(|_, cx| {
+            let (vimrc, errors) = Vimrc::parse("inoremap jk <Esc>\nnnoremap Q dd");
+            assert!(errors.is_empty());
+            cx.bind_keys(vimrc.key_bindings(cx));
+        });
+
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes("Q");
+        cx.assert_state("ˇtwo", Mode::Normal);
+        cx.simulate_keystrokes("i j k");
+        cx.assert_state("ˇtwo", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_noremap_swap(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update(|_, cx| {
+            let (vimrc, errors) = Vimrc::parse("nnoremap j k\nnnoremap k j\nnmap J k");
+            assert!(errors.is_empty());
+            cx.bind_keys(vimrc.key_bindings(cx));
+        });
+
+        cx.set_state("one\nˇtwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes("j");
+        cx.assert_state("ˇone\ntwo\nthree", Mode::Normal);
+        // Recursive mappings go through the swapped keys.
+        cx.simulate_keystrokes("J");
+        cx.assert_state("one\nˇtwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes("k");
+        cx.assert_state("one\ntwo\nˇthree", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_ignorecase_command(cx: &mut TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
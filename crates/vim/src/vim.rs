@@ -17,6 +17,7 @@ mod replace;
 mod rewrap;
 mod state;
 mod surrounds;
+mod vimrc;
 mod visual;
 
 use crate::normal::paste::Paste as VimPaste;
@@ -52,6 +53,7 @@ use theme::ThemeSettings;
 use ui::{IntoElement, SharedString, px};
 use vim_mode_setting::HelixModeSetting;
 use vim_mode_setting::VimModeSetting;
+pub use vimrc::{Vimrc, handle_vimrc_file_changes};
 use workspace::{self, Pane, Workspace};
 
 use crate::{
@@ -269,6 +271,7 @@ actions!(
 /// Initializes the `vim` crate.
 pub fn init(cx: &mut App) {
     VimGlobals::register(cx);
+    vimrc::init(cx);
 
     cx.observe_new(Vim::register).detach();
 
@@ -634,6 +637,14 @@ impl Vim {
             entity: vim.clone(),
         });
 
+        let vimrc_options = cx
+            .try_global::<Vimrc>()
+            .map(|vimrc| vimrc.options().to_vec())
+            .unwrap_or_default();
+        for option in vimrc_options.iter().filter(|option| !option.is_global()) {
+            option.apply_local(editor, cx);
+        }
+
         vim.update(cx, |_, cx| {
             Vim::action(editor, cx, |vim, _: &SwitchToNormalMode, window, cx| {
                 vim.switch_mode(Mode::Normal, false, window, cx)
@@ -1953,7 +1964,7 @@ impl Vim {
     }
 }
 
-#[derive(RegisterSetting)]
+#[derive(Clone, RegisterSetting)]
 struct VimSettings {
     pub default_mode: Mode,
     pub toggle_relative_line_numbers: bool,
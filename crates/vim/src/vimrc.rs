@@ -0,0 +1,689 @@
+//! Support for a subset of vimscript, loaded from the user's `vimrc` file or with `:source`.
+//!
+//! Only the commands that have a direct equivalent in Zed are understood:
+//! the `:map` family (translated into key bindings that send keystrokes, or
+//! that run the keys' default actions for `:noremap`),
+//! `:set` (see [`VimOption`]), `:command` (user commands that expand to ex
+//! commands) and `let mapleader`. Anything else is reported as an error and skipped.
+
+use std::rc::Rc;
+
+use anyhow::{Result, anyhow, bail};
+use collections::HashMap;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{
+    Action, App, AppContext as _, Global, KeyBinding, KeyBindingContextPredicate, KeyContext,
+    Keymap, Keystroke, NoAction,
+};
+use settings::{Settings as _, SettingsContent, SettingsStore};
+use vim_mode_setting::VimModeSetting;
+use workspace::{
+    SendKeystrokes,
+    notifications::{
+        NotificationId, dismiss_app_notification, show_app_notification,
+        simple_message_notification::MessageNotification,
+    },
+};
+
+use crate::command::VimOption;
+
+/// The mappings, options and user commands loaded from vimscript.
+#[derive(Clone, Debug, Default)]
+pub struct Vimrc {
+    leader: Vec<String>,
+    mappings: Vec<Mapping>,
+    options: Vec<VimOption>,
+    user_commands: HashMap<String, String>,
+}
+
+impl Global for Vimrc {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MapMode {
+    Normal,
+    Visual,
+    OperatorPending,
+    Insert,
+}
+
+impl MapMode {
+    fn context(&self) -> &'static str {
+        match self {
+            MapMode::Normal => "vim_mode == normal && !menu",
+            MapMode::Visual => "vim_mode == visual && !menu",
+            MapMode::OperatorPending => "vim_mode == operator",
+            MapMode::Insert => "vim_mode == insert",
+        }
+    }
+
+    /// The context of an editor in this mode, used to find what keys do there
+    /// without the vimrc.
+    fn context_stack(&self) -> [KeyContext; 3] {
+        let mut editor = KeyContext::default();
+        editor.add("Editor");
+        editor.set("mode", "full");
+        let vim_mode = match self {
+            MapMode::Normal => "normal",
+            MapMode::Visual => "visual",
+            MapMode::OperatorPending => "operator",
+            MapMode::Insert => "insert",
+        };
+        if *self != MapMode::Insert {
+            editor.add("VimControl");
+        }
+        editor.set("vim_mode", vim_mode);
+        editor.set("vim_operator", "none");
+        let mut workspace = KeyContext::default();
+        workspace.add("Workspace");
+        let mut pane = KeyContext::default();
+        pane.add("Pane");
+        [workspace, pane, editor]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Mapping {
+    modes: Vec<MapMode>,
+    /// The keystrokes that trigger the mapping, in Zed's keystroke syntax.
+    lhs: Vec<String>,
+    /// The keystrokes to send, or `None` to disable the `lhs` (`<Nop>` or `:unmap`).
+    rhs: Option<Vec<String>>,
+    /// Whether the `rhs` ignores the other mappings, as with `:noremap`.
+    noremap: bool,
+}
+
+impl Vimrc {
+    /// Parses vimscript, returning everything that could be understood along
+    /// with an error message for each line that couldn't.
+    pub(crate) fn parse(source: &str) -> (Self, Vec<String>) {
+        let mut vimrc = Self::default();
+        let errors = vimrc.extend(source);
+        (vimrc, errors)
+    }
+
+    /// Evaluates the given vimscript on top of the current state.
+    pub(crate) fn extend(&mut self, source: &str) -> Vec<String> {
+        let mut lines: Vec<(usize, String)> = Vec::new();
+        for (ix, line) in source.lines().enumerate() {
+            let trimmed = line.trim_start();
+            // A line starting with a backslash continues the previous one.
+            if let Some(continuation) = trimmed.strip_prefix('\\')
+                && let Some((_, previous)) = lines.last_mut()
+            {
+                previous.push_str(continuation);
+            } else {
+                lines.push((ix + 1, trimmed.to_string()));
+            }
+        }
+
+        lines
+            .into_iter()
+            .filter_map(|(row, line)| {
+                self.eval_line(&line)
+                    .err()
+                    .map(|error| format!("line {row}: {error}"))
+            })
+            .collect()
+    }
+
+    /// Evaluates a single line of vimscript.
+    pub(crate) fn eval_line(&mut self, line: &str) -> Result<()> {
+        let line = line.trim().trim_start_matches(':');
+        if line.is_empty() || line.starts_with('"') {
+            return Ok(());
+        }
+
+        let (command, args) = line
+            .split_once(char::is_whitespace)
+            .map(|(command, args)| (command, args.trim()))
+            .unwrap_or((line, ""));
+        let (command, bang) = match command.strip_suffix('!') {
+            Some(command) => (command, true),
+            None => (command, false),
+        };
+
+        match command {
+            "let" => self.eval_let(args),
+            "se" | "set" | "setl" | "setlocal" | "setg" | "setglobal" => {
+                for option in args.split_whitespace() {
+                    let option = VimOption::from(option)
+                        .ok_or_else(|| anyhow!("unsupported option {option:?}"))?;
+                    self.set_option(option);
+                }
+                Ok(())
+            }
+            "com" | "command" => self.eval_command(args, bang),
+            "delc" | "delcommand" => {
+                self.user_commands.remove(args);
+                Ok(())
+            }
+            _ => {
+                if let Some(modes) = map_command(command, bang) {
+                    self.eval_map(modes, is_noremap(command), args)
+                } else if let Some(modes) = unmap_command(command, bang) {
+                    self.eval_unmap(modes, args)
+                } else {
+                    bail!("unsupported command {command:?}")
+                }
+            }
+        }
+    }
+
+    fn eval_let(&mut self, args: &str) -> Result<()> {
+        let (name, value) = args
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected `let name = value`"))?;
+        match name.trim() {
+            "mapleader" | "g:mapleader" => {
+                let value = parse_string(value.trim())?;
+                self.leader = parse_keys(&value, &[])?;
+                Ok(())
+            }
+            name => bail!("unsupported variable {name:?}"),
+        }
+    }
+
+    fn eval_command(&mut self, args: &str, bang: bool) -> Result<()> {
+        let mut rest = args;
+        // Attributes such as `-nargs=*` or `-bar` don't change how the command expands.
+        while let Some(attribute) = rest.strip_prefix('-') {
+            rest = attribute
+                .split_once(char::is_whitespace)
+                .map(|(_, rest)| rest.trim_start())
+                .unwrap_or("");
+        }
+        let (name, replacement) = rest
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| anyhow!("expected `command Name {{replacement}}`"))?;
+        if !name.starts_with(|c: char| c.is_ascii_uppercase())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            bail!("user command names must start with an uppercase letter: {name:?}");
+        }
+        if !bang && self.user_commands.contains_key(name) {
+            bail!("command {name:?} already exists, add ! to replace it");
+        }
+        self.user_commands
+            .insert(name.to_string(), replacement.trim().to_string());
+        Ok(())
+    }
+
+    fn eval_map(&mut self, modes: Vec<MapMode>, noremap: bool, args: &str) -> Result<()> {
+        let args = skip_map_arguments(args)?;
+        let (lhs, rhs) = args
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| anyhow!("expected `map {{lhs}} {{rhs}}`"))?;
+        let lhs = parse_keys(lhs, &self.leader)?;
+        let rhs = rhs.trim();
+        let rhs = if rhs.eq_ignore_ascii_case("<nop>") {
+            None
+        } else {
+            Some(parse_keys(rhs, &self.leader)?)
+        };
+        self.push_mapping(Mapping {
+            modes,
+            lhs,
+            rhs,
+            noremap,
+        });
+        Ok(())
+    }
+
+    fn eval_unmap(&mut self, modes: Vec<MapMode>, args: &str) -> Result<()> {
+        let lhs = parse_keys(skip_map_arguments(args)?, &self.leader)?;
+        self.push_mapping(Mapping {
+            modes,
+            lhs,
+            rhs: None,
+            noremap: false,
+        });
+        Ok(())
+    }
+
+    fn push_mapping(&mut self, mapping: Mapping) {
+        // A later mapping of the same keys replaces an earlier one in each of its modes.
+        for existing in &mut self.mappings {
+            if existing.lhs == mapping.lhs {
+                existing.modes.retain(|mode| !mapping.modes.contains(mode));
+            }
+        }
+        self.mappings.retain(|existing| !existing.modes.is_empty());
+        self.mappings.push(mapping);
+    }
+
+    /// The options set with `:set`, in the order they were set.
+    pub(crate) fn options(&self) -> &[VimOption] {
+        &self.options
+    }
+
+    /// Sets an option, replacing any earlier value of it.
+    pub(crate) fn set_option(&mut self, option: VimOption) {
+        self.options
+            .retain(|existing| !same_option(existing, &option));
+        self.options.push(option);
+    }
+
+    /// The settings that the global options translate into, or `None` if there are none.
+    fn settings(&self) -> Option<SettingsContent> {
+        let mut settings = SettingsContent::default();
+        let mut has_settings = false;
+        for option in self.options.iter().filter(|option| option.is_global()) {
+            option.apply_to_settings(&mut settings);
+            has_settings = true;
+        }
+        has_settings.then_some(settings)
+    }
+
+    /// Makes this the active vimrc, replacing the settings layer built from the
+    /// previous one, so that options it no longer sets are reverted.
+    pub(crate) fn activate(self, cx: &mut App) {
+        cx.set_global(self);
+        Self::apply_settings(cx);
+    }
+
+    /// Sets the settings layer of the active vimrc, which only applies while
+    /// vim mode is enabled.
+    fn apply_settings(cx: &mut App) {
+        let settings = if VimModeSetting::get_global(cx).0 {
+            cx.global::<Self>().settings()
+        } else {
+            None
+        };
+        SettingsStore::update_global(cx, |store, cx| store.set_vimrc_settings(settings, cx));
+    }
+
+    /// Returns the ex commands a user command expands to, with `<args>` replaced.
+    pub(crate) fn expand_user_command(&self, name: &str, args: &str) -> Option<Vec<String>> {
+        let replacement = self.user_commands.get(name)?;
+        Some(
+            replacement
+                .replace("<args>", args)
+                .replace("<q-args>", &quote_args(args))
+                .split('|')
+                .map(|command| command.trim().to_string())
+                .filter(|command| !command.is_empty())
+                .collect(),
+        )
+    }
+
+    /// Builds the key bindings for the mappings, in definition order.
+    ///
+    /// `noremap` mappings run the actions their keys are bound to in the keymap
+    /// loaded so far, so this has to be called after loading the default keymap
+    /// and before binding the vimrc's keys.
+    pub fn key_bindings(&self, cx: &App) -> Vec<KeyBinding> {
+        let keymap = cx.key_bindings();
+        let keymap = keymap.borrow();
+        let mut bindings = Vec::new();
+        for mapping in &self.mappings {
+            for mode in &mapping.modes {
+                let action = match &mapping.rhs {
+                    Some(keystrokes) if mapping.noremap => {
+                        match self.noremap_action(keystrokes, *mode, &keymap) {
+                            Ok(action) => action,
+                            Err(error) => {
+                                log::error!("invalid vimrc mapping: {error}");
+                                continue;
+                            }
+                        }
+                    }
+                    Some(keystrokes) => SendKeystrokes(keystrokes.join(" ")).boxed_clone(),
+                    None => NoAction.boxed_clone(),
+                };
+                let context = KeyBindingContextPredicate::parse(mode.context())
+                    .expect("mapping contexts are valid");
+                match KeyBinding::load(
+                    &mapping.lhs.join(" "),
+                    action,
+                    Some(Rc::new(context)),
+                    false,
+                    None,
+                    cx.keyboard_mapper().as_ref(),
+                ) {
+                    Ok(binding) => bindings.push(binding),
+                    Err(error) => log::error!("invalid vimrc mapping: {error}"),
+                }
+            }
+        }
+        bindings
+    }
+
+    /// Returns the action of a `noremap` mapping: the one its keys are bound to
+    /// without the vimrc, or sending the keys if none of them are mapped in it.
+    fn noremap_action(
+        &self,
+        rhs: &[String],
+        mode: MapMode,
+        keymap: &Keymap,
+    ) -> Result<Box<dyn Action>> {
+        let input = rhs
+            .iter()
+            .map(|keystroke| Keystroke::parse(keystroke))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (bindings, _) = keymap.bindings_for_input(&input, &mode.context_stack());
+        if let Some(binding) = bindings.first() {
+            return Ok(binding.action().boxed_clone());
+        }
+        // Sent keys are dispatched through the vimrc's bindings too.
+        if self.mappings.iter().any(|mapping| {
+            mapping.modes.contains(&mode) && rhs.iter().any(|key| mapping.lhs[0] == *key)
+        }) {
+            bail!(
+                "noremap to several keys is only supported when none of them are mapped: {}",
+                rhs.join(" ")
+            );
+        }
+        Ok(SendKeystrokes(rhs.join(" ")).boxed_clone())
+    }
+}
+
+/// Quotes the arguments of a user command as a vimscript string, like `<q-args>`.
+fn quote_args(args: &str) -> String {
+    format!("\"{}\"", args.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn same_option(a: &VimOption, b: &VimOption) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+/// Returns the modes of a `:map` family command.
+fn map_command(command: &str, bang: bool) -> Option<Vec<MapMode>> {
+    use MapMode::*;
+    Some(match command {
+        "map" | "no" | "noremap" if bang => vec![Insert],
+        "map" | "no" | "noremap" => vec![Normal, Visual, OperatorPending],
+        "nm" | "nmap" | "nn" | "nnoremap" => vec![Normal],
+        "vm" | "vmap" | "xm" | "xmap" | "vn" | "vnoremap" | "xn" | "xnoremap" => vec![Visual],
+        "om" | "omap" | "ono" | "onoremap" => vec![OperatorPending],
+        "im" | "imap" | "ino" | "inoremap" => vec![Insert],
+        _ => return None,
+    })
+}
+
+/// Whether a `:map` family command ignores the other mappings.
+fn is_noremap(command: &str) -> bool {
+    matches!(
+        command,
+        "no" | "noremap"
+            | "nn"
+            | "nnoremap"
+            | "vn"
+            | "vnoremap"
+            | "xn"
+            | "xnoremap"
+            | "ono"
+            | "onoremap"
+            | "ino"
+            | "inoremap"
+    )
+}
+
+fn unmap_command(command: &str, bang: bool) -> Option<Vec<MapMode>> {
+    use MapMode::*;
+    Some(match command {
+        "unm" | "unmap" if bang => vec![Insert],
+        "unm" | "unmap" => vec![Normal, Visual, OperatorPending],
+        "nun" | "nunmap" => vec![Normal],
+        "vu" | "vunmap" | "xu" | "xunmap" => vec![Visual],
+        "ou" | "ounmap" => vec![OperatorPending],
+        "iu" | "iunmap" => vec![Insert],
+        _ => return None,
+    })
+}
+
+/// Skips `<silent>`, `<nowait>` and friends, which have no effect in Zed.
+fn skip_map_arguments(mut args: &str) -> Result<&str> {
+    loop {
+        args = args.trim_start();
+        let Some(end) = args.find('>') else {
+            return Ok(args);
+        };
+        match args[..=end].to_ascii_lowercase().as_str() {
+            "<silent>" | "<nowait>" | "<buffer>" | "<unique>" | "<special>" => {
+                args = &args[end + 1..];
+            }
+            "<expr>" | "<script>" => bail!("{} mappings are not supported", &args[..=end]),
+            _ => return Ok(args),
+        }
+    }
+}
+
+fn parse_string(value: &str) -> Result<String> {
+    if let Some(value) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        return Ok(value.replace("''", "'"));
+    }
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(|| anyhow!("expected a string, got {value:?}"))?;
+    // Double-quoted strings may contain escaped keys such as "\<Space>".
+    Ok(value.replace("\\<", "<").replace("\\\\", "\\"))
+}
+
+/// Converts vim key notation (e.g. `<C-w>h` or `<leader>ff`) into Zed keystrokes.
+pub(crate) fn parse_keys(notation: &str, leader: &[String]) -> Result<Vec<String>> {
+    let mut keystrokes = Vec::new();
+    let mut rest = notation;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some(end) = rest.find('>')
+            && let Some(keys) = parse_special_key(&rest[1..end], leader)?
+        {
+            keystrokes.extend(keys);
+            rest = &rest[end + 1..];
+            continue;
+        }
+        keystrokes.push(match c {
+            ' ' => "space".to_string(),
+            c => c.to_string(),
+        });
+        rest = &rest[c.len_utf8()..];
+    }
+    if keystrokes.is_empty() {
+        bail!("empty key sequence");
+    }
+    Ok(keystrokes)
+}
+
+/// Parses the inside of a `<...>` key. Returns `None` if it isn't a key name,
+/// in which case the `<` is taken literally.
+fn parse_special_key(name: &str, leader: &[String]) -> Result<Option<Vec<String>>> {
+    let lowercase = name.to_ascii_lowercase();
+    if lowercase == "leader" {
+        if leader.is_empty() {
+            return Ok(Some(vec!["\\".to_string()]));
+        }
+        return Ok(Some(leader.to_vec()));
+    }
+
+    let mut modifiers = String::new();
+    let mut key = lowercase.as_str();
+    while key.len() > 2 && key.as_bytes()[1] == b'-' {
+        modifiers.push_str(match key.as_bytes()[0] {
+            b'c' => "ctrl-",
+            b's' => "shift-",
+            b'a' | b'm' => "alt-",
+            b'd' => "cmd-",
+            _ => return Ok(None),
+        });
+        key = &key[2..];
+    }
+
+    let key = match key {
+        "cr" | "enter" | "return" => "enter",
+        "esc" => "escape",
+        "space" => "space",
+        "tab" => "tab",
+        "bs" => "backspace",
+        "del" => "delete",
+        "up" => "up",
+        "down" => "down",
+        "left" => "left",
+        "right" => "right",
+        "home" => "home",
+        "end" => "end",
+        "pageup" => "pageup",
+        "pagedown" => "pagedown",
+        "insert" => "insert",
+        "lt" => "<",
+        "bar" => "|",
+        "bslash" => "\\",
+        key if key.len() == 1 => key,
+        key if key.starts_with('f') && key[1..].parse::<u8>().is_ok() => key,
+        _ if modifiers.is_empty() => return Ok(None),
+        _ => bail!("unknown key <{name}>"),
+    };
+    Ok(Some(vec![format!("{modifiers}{key}")]))
+}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.set_global(Vimrc::default());
+
+    let mut was_enabled = VimModeSetting::get_global(cx).0;
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let is_enabled = VimModeSetting::get_global(cx).0;
+        if is_enabled != was_enabled {
+            was_enabled = is_enabled;
+            Vimrc::apply_settings(cx);
+        }
+    })
+    .detach();
+}
+
+/// Loads the vimrc whenever its contents change.
+pub fn handle_vimrc_file_changes(mut vimrc_file_rx: mpsc::UnboundedReceiver<String>, cx: &mut App) {
+    cx.spawn(async move |cx| {
+        while let Some(content) = vimrc_file_rx.next().await {
+            let (vimrc, errors) = Vimrc::parse(&content);
+            cx.update(|cx| {
+                vimrc.activate(cx);
+                report_errors(errors, cx);
+            })
+            .ok();
+        }
+    })
+    .detach();
+}
+
+pub(crate) fn report_errors(errors: Vec<String>, cx: &mut App) {
+    struct VimrcErrorNotification;
+    let id = NotificationId::unique::<VimrcErrorNotification>();
+    if errors.is_empty() {
+        dismiss_app_notification(&id, cx);
+        return;
+    }
+    let message = format!(
+        "Some vimrc commands could not be loaded:\n\n{}",
+        errors.join("\n")
+    );
+    show_app_notification(id, cx, move |cx| {
+        cx.new(|cx| MessageNotification::new(message.clone(), cx))
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        let leader = vec!["space".to_string()];
+        assert_eq!(parse_keys("jk", &leader).unwrap(), vec!["j", "k"]);
+        assert_eq!(
+            parse_keys(":w<CR>", &leader).unwrap(),
+            vec![":", "w", "enter"]
+        );
+        assert_eq!(
+            parse_keys("<leader>ff", &leader).unwrap(),
+            vec!["space", "f", "f"]
+        );
+        assert_eq!(parse_keys("<C-w>H", &leader).unwrap(), vec!["ctrl-w", "H"]);
+        assert_eq!(
+            parse_keys("<S-Tab><lt>a", &leader).unwrap(),
+            vec!["shift-tab", "<", "a"]
+        );
+        assert_eq!(parse_keys("<foo>", &leader).unwrap().len(), 5);
+        assert!(parse_keys("<C-foo>", &leader).is_err());
+    }
+
+    #[test]
+    fn test_parse_vimrc() {
+        let (vimrc, errors) = Vimrc::parse(
+            r#"
+            " comment
+            let mapleader = " "
+            set nowrap tabstop=2 ignorecase
+            nnoremap <silent> <leader>w :w<CR>
+            inoremap jk <Esc>
+            map Q <Nop>
+            command! -nargs=* W w | echo <args>
+            syntax on
+            "#,
+        );
+
+        assert_eq!(errors, vec!["line 9: unsupported command \"syntax\""]);
+        assert_eq!(
+            vimrc.options(),
+            &[
+                VimOption::Wrap(false),
+                VimOption::TabStop(2),
+                VimOption::IgnoreCase(true)
+            ]
+        );
+        assert_eq!(
+            vimrc.mappings,
+            vec![
+                Mapping {
+                    modes: vec![MapMode::Normal],
+                    lhs: vec!["space".into(), "w".into()],
+                    rhs: Some(vec![":".into(), "w".into(), "enter".into()]),
+                    noremap: true,
+                },
+                Mapping {
+                    modes: vec![MapMode::Insert],
+                    lhs: vec!["j".into(), "k".into()],
+                    rhs: Some(vec!["escape".into()]),
+                    noremap: true,
+                },
+                Mapping {
+                    modes: vec![MapMode::Normal, MapMode::Visual, MapMode::OperatorPending],
+                    lhs: vec!["Q".into()],
+                    rhs: None,
+                    noremap: false,
+                },
+            ]
+        );
+        assert_eq!(
+            vimrc.expand_user_command("W", "hi"),
+            Some(vec!["w".to_string(), "echo hi".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_quote_args() {
+        assert_eq!(quote_args(""), r#""""#);
+        assert_eq!(quote_args("a b"), r#""a b""#);
+        assert_eq!(quote_args(r#"say "hi" C:\dir"#), r#""say \"hi\" C:\\dir""#);
+        assert_eq!(quote_args("é\t"), "\"é\t\"");
+    }
+
+    #[test]
+    fn test_remapping_replaces_modes() {
+        let (vimrc, errors) = Vimrc::parse(
+            "
+            noremap H ^
+            vnoremap H 0
+            ",
+        );
+        assert!(errors.is_empty());
+        assert_eq!(vimrc.mappings.len(), 2);
+        assert_eq!(
+            vimrc.mappings[0].modes,
+            vec![MapMode::Normal, MapMode::OperatorPending]
+        );
+        assert_eq!(vimrc.mappings[1].modes, vec![MapMode::Visual]);
+    }
+}
@@ -20,7 +20,10 @@ use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
 
 use language::{LanguageName, Toolchain, ToolchainScope};
 use project::WorktreeId;
-use remote::{RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions};
+use remote::{
+    CommandConnectionOptions, ContainerRuntime, DockerConnectionOptions, RemoteConnectionOptions,
+    SshConnectionOptions, WslConnectionOptions,
+};
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
@@ -702,6 +705,13 @@ impl Domain for WorkspaceDb {
         sql!(
             DROP TABLE ssh_connections;
         ),
+        sql!(
+            ALTER TABLE remote_connections ADD COLUMN interactive_command TEXT;
+            ALTER TABLE remote_connections ADD COLUMN nickname TEXT;
+        ),
+        sql!(
+            ALTER TABLE remote_connections ADD COLUMN local_folder TEXT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1114,6 +1124,9 @@ impl WorkspaceDb {
         let mut host = None;
         let mut port = None;
         let mut distro = None;
+        let mut interactive_command = None;
+        let mut nickname = None;
+        let mut local_folder = None;
         match options {
             RemoteConnectionOptions::Ssh(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1126,8 +1139,37 @@ impl WorkspaceDb {
                 distro = Some(options.distro_name);
                 user = options.user;
             }
+            RemoteConnectionOptions::Docker(options) => {
+                kind = match options.runtime {
+                    ContainerRuntime::Docker => RemoteConnectionKind::Docker,
+                    ContainerRuntime::Podman => RemoteConnectionKind::Podman,
+                };
+                host = Some(options.container_name);
+                local_folder = options
+                    .local_folder
+                    .map(|folder| folder.to_string_lossy().into_owned());
+                user = options.user;
+            }
+            // The command template is stored as the host.
+            RemoteConnectionOptions::Command(options) => {
+                kind = RemoteConnectionKind::Command;
+                host = Some(options.command);
+                user = None;
+                interactive_command = options.interactive_command;
+                nickname = options.nickname;
+            }
         }
-        Self::get_or_create_remote_connection_query(this, kind, host, port, user, distro)
+        Self::get_or_create_remote_connection_query(
+            this,
+            kind,
+            host,
+            port,
+            user,
+            distro,
+            interactive_command,
+            nickname,
+            local_folder,
+        )
     }
 
     fn get_or_create_remote_connection_query(
@@ -1137,6 +1179,9 @@ impl WorkspaceDb {
         port: Option<u16>,
         user: Option<String>,
         distro: Option<String>,
+        interactive_command: Option<String>,
+        nickname: Option<String>,
+        local_folder: Option<String>,
     ) -> Result<RemoteConnectionId> {
         if let Some(id) = this.select_row_bound(sql!(
             SELECT id
@@ -1146,7 +1191,10 @@ impl WorkspaceDb {
                 host IS ? AND
                 port IS ? AND
                 user IS ? AND
-                distro IS ?
+                distro IS ? AND
+                interactive_command IS ? AND
+                nickname IS ? AND
+                local_folder IS ?
             LIMIT 1
         ))?((
             kind.serialize(),
@@ -1154,6 +1202,9 @@ impl WorkspaceDb {
             port,
             user.clone(),
             distro.clone(),
+            interactive_command.clone(),
+            nickname.clone(),
+            local_folder.clone(),
         ))? {
             Ok(RemoteConnectionId(id))
         } else {
@@ -1163,10 +1214,22 @@ impl WorkspaceDb {
                     host,
                     port,
                     user,
-                    distro
-                ) VALUES (?1, ?2, ?3, ?4, ?5)
+                    distro,
+                    interactive_command,
+                    nickname,
+                    local_folder
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
                 RETURNING id
-            ))?((kind.serialize(), host, port, user, distro))?
+            ))?((
+                kind.serialize(),
+                host,
+                port,
+                user,
+                distro,
+                interactive_command,
+                nickname,
+                local_folder,
+            ))?
             .context("failed to insert remote project")?;
             Ok(RemoteConnectionId(id))
         }
@@ -1249,17 +1312,28 @@ impl WorkspaceDb {
     fn remote_connections(&self) -> Result<HashMap<RemoteConnectionId, RemoteConnectionOptions>> {
         Ok(self.select(sql!(
             SELECT
-                id, kind, host, port, user, distro
+                id, kind, host, port, user, distro, interactive_command, nickname, local_folder
             FROM
                 remote_connections
         ))?()?
         .into_iter()
-        .filter_map(|(id, kind, host, port, user, distro)| {
-            Some((
-                RemoteConnectionId(id),
-                Self::remote_connection_from_row(kind, host, port, user, distro)?,
-            ))
-        })
+        .filter_map(
+            |(id, kind, host, port, user, distro, interactive_command, nickname, local_folder)| {
+                Some((
+                    RemoteConnectionId(id),
+                    Self::remote_connection_from_row(
+                        kind,
+                        host,
+                        port,
+                        user,
+                        distro,
+                        interactive_command,
+                        nickname,
+                        local_folder,
+                    )?,
+                ))
+            },
+        )
         .collect())
     }
 
@@ -1267,14 +1341,24 @@ impl WorkspaceDb {
         &self,
         id: RemoteConnectionId,
     ) -> Result<RemoteConnectionOptions> {
-        let (kind, host, port, user, distro) = self.select_row_bound(sql!(
-            SELECT kind, host, port, user, distro
-            FROM remote_connections
-            WHERE id = ?
-        ))?(id.0)?
-        .context("no such remote connection")?;
-        Self::remote_connection_from_row(kind, host, port, user, distro)
-            .context("invalid remote_connection row")
+        let (kind, host, port, user, distro, interactive_command, nickname, local_folder) =
+            self.select_row_bound(sql!(
+                SELECT kind, host, port, user, distro, interactive_command, nickname, local_folder
+                FROM remote_connections
+                WHERE id = ?
+            ))?(id.0)?
+            .context("no such remote connection")?;
+        Self::remote_connection_from_row(
+            kind,
+            host,
+            port,
+            user,
+            distro,
+            interactive_command,
+            nickname,
+            local_folder,
+        )
+        .context("invalid remote_connection row")
     }
 
     fn remote_connection_from_row(
@@ -1283,6 +1367,9 @@ impl WorkspaceDb {
         port: Option<u16>,
         user: Option<String>,
         distro: Option<String>,
+        interactive_command: Option<String>,
+        nickname: Option<String>,
+        local_folder: Option<String>,
     ) -> Option<RemoteConnectionOptions> {
         match RemoteConnectionKind::deserialize(&kind)? {
             RemoteConnectionKind::Wsl => Some(RemoteConnectionOptions::Wsl(WslConnectionOptions {
@@ -1295,6 +1382,25 @@ impl WorkspaceDb {
                 username: user,
                 ..Default::default()
             })),
+            kind @ (RemoteConnectionKind::Docker | RemoteConnectionKind::Podman) => {
+                Some(RemoteConnectionOptions::Docker(DockerConnectionOptions {
+                    container_name: host?,
+                    local_folder: local_folder.map(PathBuf::from),
+                    runtime: if kind == RemoteConnectionKind::Podman {
+                        ContainerRuntime::Podman
+                    } else {
+                        ContainerRuntime::Docker
+                    },
+                    user,
+                }))
+            }
+            RemoteConnectionKind::Command => {
+                Some(RemoteConnectionOptions::Command(CommandConnectionOptions {
+                    command: host?,
+                    interactive_command,
+                    nickname,
+                }))
+            }
         }
     }
 
@@ -2756,6 +2862,64 @@ mod tests {
         assert_ne!(connection_id, different_connection);
     }
 
+    #[gpui::test]
+    async fn test_get_or_create_container_connection() {
+        let db = WorkspaceDb::open_test_db("test_get_or_create_container_connection").await;
+
+        let options = RemoteConnectionOptions::Docker(DockerConnectionOptions {
+            container_name: "zed-src-app".to_string(),
+            local_folder: Some(PathBuf::from("/src/app")),
+            runtime: ContainerRuntime::Podman,
+            user: Some("vscode".to_string()),
+        });
+        let connection_id = db
+            .get_or_create_remote_connection(options.clone())
+            .await
+            .unwrap();
+        assert_eq!(db.remote_connection(connection_id).unwrap(), options);
+
+        let docker_connection_id = db
+            .get_or_create_remote_connection(RemoteConnectionOptions::Docker(
+                DockerConnectionOptions {
+                    container_name: "zed-src-app".to_string(),
+                    local_folder: Some(PathBuf::from("/src/app")),
+                    runtime: ContainerRuntime::Docker,
+                    user: Some("vscode".to_string()),
+                },
+            ))
+            .await
+            .unwrap();
+        assert_ne!(connection_id, docker_connection_id);
+    }
+
+    #[gpui::test]
+    async fn test_get_or_create_command_connection() {
+        let db = WorkspaceDb::open_test_db("test_get_or_create_command_connection").await;
+
+        let options = RemoteConnectionOptions::Command(CommandConnectionOptions {
+            command: "kubectl exec -i my-pod -- {cmd}".to_string(),
+            interactive_command: Some("kubectl exec -it my-pod -- {cmd}".to_string()),
+            nickname: Some("my-pod".to_string()),
+        });
+        let connection_id = db
+            .get_or_create_remote_connection(options.clone())
+            .await
+            .unwrap();
+        assert_eq!(db.remote_connection(connection_id).unwrap(), options);
+
+        let renamed_connection_id = db
+            .get_or_create_remote_connection(RemoteConnectionOptions::Command(
+                CommandConnectionOptions {
+                    command: "kubectl exec -i my-pod -- {cmd}".to_string(),
+                    interactive_command: Some("kubectl exec -it my-pod -- {cmd}".to_string()),
+                    nickname: Some("pod".to_string()),
+                },
+            ))
+            .await
+            .unwrap();
+        assert_ne!(connection_id, renamed_connection_id);
+    }
+
     #[gpui::test]
     async fn test_get_or_create_ssh_project_with_null_user() {
         let db = WorkspaceDb::open_test_db("test_get_or_create_ssh_project_with_null_user").await;
@@ -32,6 +32,9 @@ pub(crate) struct RemoteConnectionId(pub u64);
 pub(crate) enum RemoteConnectionKind {
     Ssh,
     Wsl,
+    Docker,
+    Podman,
+    Command,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -75,6 +78,9 @@ impl RemoteConnectionKind {
         match self {
             RemoteConnectionKind::Ssh => "ssh",
             RemoteConnectionKind::Wsl => "wsl",
+            RemoteConnectionKind::Docker => "docker",
+            RemoteConnectionKind::Podman => "podman",
+            RemoteConnectionKind::Command => "command",
         }
     }
 
@@ -82,6 +88,9 @@ impl RemoteConnectionKind {
         match text {
             "ssh" => Some(Self::Ssh),
             "wsl" => Some(Self::Wsl),
+            "docker" => Some(Self::Docker),
+            "podman" => Some(Self::Podman),
+            "command" => Some(Self::Command),
             _ => None,
         }
     }
@@ -8,11 +8,20 @@ use project::Project;
 use proto::{CrashReport, GetCrashFilesResponse};
 use reqwest::multipart::{Form, Part};
 use smol::stream::StreamExt;
-use std::{ffi::OsStr, fs, sync::Arc, thread::ThreadId, time::Duration};
+use std::{
+    ffi::OsStr,
+    fs,
+    sync::Arc,
+    thread::ThreadId,
+    time::{Duration, SystemTime},
+};
 use util::ResultExt;
 
 use crate::STARTUP_TIME;
 
+/// How long uploaded crash reports are kept, so that they can still be viewed locally.
+const UPLOADED_CRASH_REPORT_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
 pub fn init(client: Arc<Client>, cx: &mut App) {
     monitor_hangs(cx);
 
@@ -166,6 +175,20 @@ pub async fn upload_previous_minidumps(client: Arc<Client>) -> anyhow::Result<()
         }
         let mut json_path = child_path.clone();
         json_path.set_extension("json");
+        let uploaded_path = child_path.with_extension("uploaded");
+        if let Ok(uploaded) = fs::metadata(&uploaded_path) {
+            let is_expired = uploaded
+                .modified()
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .is_some_and(|age| age > UPLOADED_CRASH_REPORT_RETENTION);
+            if is_expired {
+                fs::remove_file(child_path).ok();
+                fs::remove_file(json_path).ok();
+                fs::remove_file(uploaded_path).ok();
+            }
+            continue;
+        }
         if let Ok(metadata) = serde_json::from_slice(&smol::fs::read(&json_path).await?)
             && upload_minidump(
                 client.clone(),
@@ -179,8 +202,9 @@ pub async fn upload_previous_minidumps(client: Arc<Client>) -> anyhow::Result<()
             .log_err()
             .is_some()
         {
-            fs::remove_file(child_path).ok();
-            fs::remove_file(json_path).ok();
+            fs::write(&uploaded_path, b"")
+                .context("marking minidump as uploaded")
+                .log_err();
         }
     }
     Ok(())
@@ -1,4 +1,5 @@
 mod app_menus;
+mod cli_control;
 pub mod component_preview;
 pub mod edit_prediction_registry;
 #[cfg(target_os = "macos")]
@@ -6,6 +7,7 @@ pub(crate) mod mac_only_instance;
 mod migrate;
 mod open_listener;
 mod quick_action_bar;
+pub mod sync_settings;
 #[cfg(target_os = "windows")]
 pub(crate) mod windows_only_instance;
 
@@ -429,6 +431,8 @@ pub fn initialize_workspace(
             cx.new(|_| language_selector::ActiveBufferLanguage::new(workspace));
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
+        let active_settings_profile = cx
+            .new(|cx| settings_profile_selector::ActiveSettingsProfile::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
 
@@ -451,6 +455,7 @@ pub fn initialize_workspace(
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
             status_bar.add_right_item(edit_prediction_ui, window, cx);
+            status_bar.add_right_item(active_settings_profile, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(line_ending_indicator, window, cx);
@@ -1529,6 +1534,14 @@ pub fn handle_keymap_file_changes(
     let mut old_vim_enabled = VimModeSetting::get_global(cx).0;
     let mut old_helix_enabled = vim_mode_setting::HelixModeSetting::get_global(cx).0;
 
+    cx.observe_global::<vim::Vimrc>({
+        let base_keymap_tx = base_keymap_tx.clone();
+        move |_| {
+            base_keymap_tx.unbounded_send(()).ok();
+        }
+    })
+    .detach();
+
     cx.observe_global::<SettingsStore>(move |cx| {
         let new_base_keymap = *BaseKeymap::get_global(cx);
         let new_vim_enabled = VimModeSetting::get_global(cx).0;
@@ -1724,6 +1737,16 @@ fn reload_keymaps(cx: &mut App, mut user_key_bindings: Vec<KeyBinding>) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
 
+    if VimModeSetting::get_global(cx).0
+        && let Some(vimrc) = cx.try_global::<vim::Vimrc>()
+    {
+        let mut vimrc_key_bindings = vimrc.key_bindings(cx);
+        for key_binding in &mut vimrc_key_bindings {
+            key_binding.set_meta(KeybindSource::User.meta());
+        }
+        cx.bind_keys(vimrc_key_bindings);
+    }
+
     for key_binding in &mut user_key_bindings {
         key_binding.set_meta(KeybindSource::User.meta());
     }
@@ -4674,6 +4697,18 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_vscode_keymap_import_actions(cx: &mut gpui::TestAppContext) {
+        init_keymap_test(cx);
+        cx.update(|cx| {
+            for (name, arguments) in settings::VsCodeKeymap::mapped_actions() {
+                if let Err(error) = cx.build_action(name, arguments) {
+                    panic!("VS Code command maps to an invalid action {name}: {error}");
+                }
+            }
+        });
+    }
+
     /// Checks that action namespaces are the expected set. The purpose of this is to prevent typos
     /// and let you know when introducing a new namespace.
     #[gpui::test]
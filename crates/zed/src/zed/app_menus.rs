@@ -299,6 +299,7 @@ pub fn app_menus(cx: &mut App) -> Vec<Menu> {
                     auto_update_ui::ViewReleaseNotesLocally,
                 ),
                 MenuItem::action("View Telemetry", zed_actions::OpenTelemetryLog),
+                MenuItem::action("View Crash Reports", zed_actions::OpenCrashReports),
                 MenuItem::action("View Dependency Licenses", zed_actions::OpenLicenses),
                 MenuItem::action("Show Welcome", onboarding::ShowWelcome),
                 MenuItem::separator(),
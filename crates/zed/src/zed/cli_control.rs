@@ -0,0 +1,416 @@
+//! Handles the `zed` CLI requests that control an already running instance,
+//! such as `zed --action` and `zed --query`, as opposed to opening paths.
+
+use anyhow::{Context as _, Result};
+use cli::{CliPosition, CliQuery, CliResponse, ipc::IpcSender};
+use editor::Editor;
+use gpui::{App, AsyncApp, WindowHandle};
+use language::{Bias, Buffer, DiagnosticSeverity, Point};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use util::ResultExt as _;
+use workspace::{OpenOptions, OpenVisible, Workspace};
+
+#[derive(Debug, Serialize)]
+struct WorkspaceInfo {
+    window_id: u64,
+    focused: bool,
+    paths: Vec<PathBuf>,
+    active_path: Option<PathBuf>,
+    buffers: Vec<BufferInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct BufferInfo {
+    path: PathBuf,
+    dirty: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SelectionInfo {
+    path: Option<PathBuf>,
+    selections: Vec<RangeInfo>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct RangeInfo {
+    start: CliPosition,
+    end: CliPosition,
+}
+
+#[derive(Debug, Serialize)]
+struct DiagnosticInfo {
+    path: PathBuf,
+    start: CliPosition,
+    end: CliPosition,
+    severity: &'static str,
+    message: String,
+    source: Option<String>,
+}
+
+/// Reports the outcome of a control request to the CLI, printing its output on
+/// success, and tells the CLI to exit.
+pub(crate) fn respond(result: Result<Option<String>>, responses: &IpcSender<CliResponse>) {
+    let status = match result {
+        Ok(output) => {
+            if let Some(message) = output {
+                responses.send(CliResponse::Stdout { message }).log_err();
+            }
+            0
+        }
+        Err(error) => {
+            responses
+                .send(CliResponse::Stderr {
+                    message: format!("{error:#}"),
+                })
+                .log_err();
+            1
+        }
+    };
+    responses.send(CliResponse::Exit { status }).log_err();
+}
+
+pub(crate) async fn dispatch_action(
+    name: String,
+    arguments: Option<String>,
+    cx: &mut AsyncApp,
+) -> Result<Option<String>> {
+    let arguments = arguments
+        .map(|arguments| serde_json::from_str(&arguments))
+        .transpose()
+        .with_context(|| format!("invalid JSON arguments for {name}"))?;
+    cx.update(|cx| {
+        let action = cx.build_action(&name, arguments)?;
+        let workspace = focused_workspace(cx)?;
+        workspace.update(cx, |_, window, cx| window.dispatch_action(action, cx))?;
+        anyhow::Ok(None)
+    })?
+}
+
+pub(crate) async fn query(query: CliQuery, cx: &mut AsyncApp) -> Result<Option<String>> {
+    let output = match query {
+        CliQuery::Workspaces => cx.update(|cx| serde_json::to_string_pretty(&workspaces(cx)))??,
+        CliQuery::Selection => {
+            cx.update(|cx| anyhow::Ok(serde_json::to_string_pretty(&active_selection(cx)?)?))??
+        }
+        CliQuery::Diagnostics => serde_json::to_string_pretty(&diagnostics(cx).await?)?,
+    };
+    Ok(Some(output))
+}
+
+pub(crate) async fn set_selection(
+    path: String,
+    start: CliPosition,
+    end: Option<CliPosition>,
+    cx: &mut AsyncApp,
+) -> Result<Option<String>> {
+    let path = PathBuf::from(path);
+    let workspace = cx.update(|cx| workspace_for_path(&path, cx))??;
+    let item = workspace
+        .update(cx, |workspace, window, cx| {
+            window.activate_window();
+            workspace.open_abs_path(
+                path.clone(),
+                OpenOptions {
+                    visible: Some(OpenVisible::None),
+                    ..Default::default()
+                },
+                window,
+                cx,
+            )
+        })?
+        .await?;
+    let editor = item
+        .downcast::<Editor>()
+        .with_context(|| format!("{path:?} was not opened in an editor"))?;
+    workspace.update(cx, |_, window, cx| {
+        editor.update(cx, |editor, cx| {
+            let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+                return;
+            };
+            let snapshot = buffer.read(cx).snapshot();
+            let start = to_point(start);
+            let end = end.map_or(start, to_point);
+            let range =
+                snapshot.clip_point(start, Bias::Left)..snapshot.clip_point(end, Bias::Left);
+            editor.go_to_singleton_buffer_range(range, window, cx);
+        })
+    })?;
+    Ok(None)
+}
+
+pub(crate) async fn run_task(label: String, cx: &mut AsyncApp) -> Result<Option<String>> {
+    let workspace = cx.update(|cx| focused_workspace(cx))??;
+    let did_spawn = workspace
+        .update(cx, |_, window, cx| {
+            let label = label.clone();
+            tasks_ui::schedule_tasks_filtered(
+                move |(_, task)| task.label == label,
+                None,
+                window,
+                cx,
+            )
+        })?
+        .await?;
+    anyhow::ensure!(did_spawn, "no task labeled {label:?}");
+    Ok(None)
+}
+
+/// The workspace window that control requests apply to: the active one, or
+/// else the frontmost workspace window.
+fn focused_workspace(cx: &App) -> Result<WindowHandle<Workspace>> {
+    cx.active_window()
+        .and_then(|window| window.downcast::<Workspace>())
+        .or_else(|| {
+            cx.window_stack()?
+                .into_iter()
+                .find_map(|window| window.downcast::<Workspace>())
+        })
+        .or_else(|| {
+            cx.windows()
+                .into_iter()
+                .find_map(|window| window.downcast::<Workspace>())
+        })
+        .context("no Zed window is open")
+}
+
+/// Prefers a workspace that already contains `path`, so that jumping to a
+/// location from an external tool reuses the window of the right project.
+fn workspace_for_path(path: &Path, cx: &App) -> Result<WindowHandle<Workspace>> {
+    let focused = focused_workspace(cx)?;
+    let contains_path = |workspace: &WindowHandle<Workspace>| {
+        workspace.read(cx).is_ok_and(|workspace| {
+            workspace
+                .project()
+                .read(cx)
+                .find_worktree(path, cx)
+                .is_some()
+        })
+    };
+    if contains_path(&focused) {
+        return Ok(focused);
+    }
+    Ok(cx
+        .windows()
+        .into_iter()
+        .filter_map(|window| window.downcast::<Workspace>())
+        .find(|workspace| contains_path(workspace))
+        .unwrap_or(focused))
+}
+
+fn workspaces(cx: &App) -> Vec<WorkspaceInfo> {
+    let focused = focused_workspace(cx).ok();
+    cx.windows()
+        .into_iter()
+        .filter_map(|window| {
+            let window = window.downcast::<Workspace>()?;
+            let workspace = window.read(cx).ok()?;
+            let project = workspace.project().read(cx);
+            Some(WorkspaceInfo {
+                window_id: window.window_id().as_u64(),
+                focused: focused == Some(window),
+                paths: workspace
+                    .root_paths(cx)
+                    .into_iter()
+                    .map(|path| path.to_path_buf())
+                    .collect(),
+                active_path: workspace
+                    .active_item(cx)
+                    .and_then(|item| project.absolute_path(&item.project_path(cx)?, cx)),
+                buffers: project
+                    .buffer_store()
+                    .read(cx)
+                    .buffers()
+                    .filter_map(|buffer| {
+                        let buffer = buffer.read(cx);
+                        Some(BufferInfo {
+                            path: buffer_abs_path(buffer, cx)?,
+                            dirty: buffer.is_dirty(),
+                        })
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn active_selection(cx: &mut App) -> Result<SelectionInfo> {
+    let workspace = focused_workspace(cx)?;
+    let editor = workspace
+        .read(cx)?
+        .active_item_as::<Editor>(cx)
+        .context("the active item is not an editor")?;
+    let buffer = editor
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+        .context("the active editor does not edit a single file")?;
+    let path = buffer_abs_path(buffer.read(cx), cx);
+    let selections = editor.update(cx, |editor, cx| {
+        let snapshot = editor.display_snapshot(cx);
+        editor.selections.all::<Point>(&snapshot)
+    });
+    Ok(SelectionInfo {
+        path,
+        selections: selections
+            .into_iter()
+            .map(|selection| RangeInfo {
+                start: to_position(selection.start),
+                end: to_position(selection.end),
+            })
+            .collect(),
+    })
+}
+
+async fn diagnostics(cx: &mut AsyncApp) -> Result<Vec<DiagnosticInfo>> {
+    let workspace = cx.update(|cx| focused_workspace(cx))??;
+    let project = workspace.read_with(cx, |workspace, _| workspace.project().clone())?;
+    let project_paths = project.read_with(cx, |project, cx| {
+        let mut project_paths = Vec::new();
+        for (project_path, _, _) in project.diagnostic_summaries(false, cx) {
+            if !project_paths.contains(&project_path) {
+                project_paths.push(project_path);
+            }
+        }
+        project_paths
+    })?;
+
+    let mut diagnostics = Vec::new();
+    for project_path in project_paths {
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+            .await?;
+        buffer.read_with(cx, |buffer, cx| {
+            let Some(path) = buffer_abs_path(buffer, cx) else {
+                return;
+            };
+            let snapshot = buffer.snapshot();
+            for entry in snapshot.diagnostics_in_range::<_, Point>(0..snapshot.len(), false) {
+                if !entry.diagnostic.is_primary {
+                    continue;
+                }
+                diagnostics.push(DiagnosticInfo {
+                    path: path.clone(),
+                    start: to_position(entry.range.start),
+                    end: to_position(entry.range.end),
+                    severity: severity_name(entry.diagnostic.severity),
+                    message: entry.diagnostic.message.clone(),
+                    source: entry.diagnostic.source.clone(),
+                });
+            }
+        })?;
+    }
+    Ok(diagnostics)
+}
+
+fn buffer_abs_path(buffer: &Buffer, cx: &App) -> Option<PathBuf> {
+    Some(buffer.file()?.as_local()?.abs_path(cx))
+}
+
+fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "error",
+        DiagnosticSeverity::WARNING => "warning",
+        DiagnosticSeverity::INFORMATION => "info",
+        DiagnosticSeverity::HINT => "hint",
+        _ => "unknown",
+    }
+}
+
+fn to_point(position: CliPosition) -> Point {
+    Point::new(
+        position.row.saturating_sub(1),
+        position.column.saturating_sub(1),
+    )
+}
+
+fn to_position(point: Point) -> CliPosition {
+    CliPosition {
+        row: point.row + 1,
+        column: point.column + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zed::tests::init_test;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_control_requests(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                path!("/root"),
+                json!({
+                    "file.txt": "one\ntwo\nthree\n",
+                }),
+            )
+            .await;
+        cx.update(|cx| {
+            workspace::open_paths(
+                &[PathBuf::from(path!("/root"))],
+                app_state.clone(),
+                OpenOptions::default(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+
+        let position = |row, column| CliPosition { row, column };
+        cx.spawn(|mut cx| async move {
+            set_selection(
+                path!("/root/file.txt").into(),
+                position(2, 2),
+                Some(position(3, 10)),
+                &mut cx,
+            )
+            .await
+        })
+        .await
+        .unwrap();
+
+        let selection = cx.update(|cx| active_selection(cx)).unwrap();
+        assert_eq!(selection.path, Some(PathBuf::from(path!("/root/file.txt"))));
+        // Positions past the end of a line are clipped to it.
+        assert_eq!(
+            selection.selections,
+            vec![RangeInfo {
+                start: position(2, 2),
+                end: position(3, 6),
+            }]
+        );
+
+        let workspaces = cx.update(|cx| workspaces(cx));
+        assert_eq!(workspaces.len(), 1);
+        assert!(workspaces[0].focused);
+        assert_eq!(workspaces[0].paths, vec![PathBuf::from(path!("/root"))]);
+        assert_eq!(
+            workspaces[0].active_path,
+            Some(PathBuf::from(path!("/root/file.txt")))
+        );
+        assert_eq!(
+            workspaces[0]
+                .buffers
+                .iter()
+                .map(|buffer| (buffer.path.clone(), buffer.dirty))
+                .collect::<Vec<_>>(),
+            vec![(PathBuf::from(path!("/root/file.txt")), false)]
+        );
+
+        let error = cx
+            .spawn(|mut cx| async move {
+                dispatch_action("no_such::Action".into(), None, &mut cx).await
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("no_such::Action"), "{error}");
+    }
+}
@@ -1,5 +1,6 @@
 use crate::handle_open_request;
 use crate::restorable_workspace_locations;
+use crate::zed::cli_control;
 use anyhow::{Context as _, Result, anyhow};
 use cli::{CliRequest, CliResponse, ipc::IpcSender};
 use cli::{IpcHandshake, ipc};
@@ -12,13 +13,13 @@ use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::channel::{mpsc, oneshot};
 use futures::future::join_all;
 use futures::{FutureExt, SinkExt, StreamExt};
-use git_ui::file_diff_view::FileDiffView;
+use git_ui::{file_diff_view::FileDiffView, merge_view::MergeView};
 use gpui::{App, AsyncApp, Global, WindowHandle};
 use language::Point;
 use onboarding::FIRST_OPEN;
 use onboarding::show_onboarding_view;
 use recent_projects::{SshSettings, open_remote_project};
-use remote::{RemoteConnectionOptions, WslConnectionOptions};
+use remote::{CommandConnectionOptions, RemoteConnectionOptions, WslConnectionOptions};
 use settings::Settings;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -79,6 +80,12 @@ impl OpenRequest {
             }));
         }
 
+        if let Some(command) = request.remote_command {
+            this.remote_connection = Some(RemoteConnectionOptions::Command(
+                CommandConnectionOptions::new(command),
+            ));
+        }
+
         for url in request.urls {
             if let Some(server_name) = url.strip_prefix("zed-cli://") {
                 this.kind = Some(OpenRequestKind::CliConnection(connect_to_cli(server_name)?));
@@ -191,6 +198,7 @@ pub struct RawOpenRequest {
     pub urls: Vec<String>,
     pub diff_paths: Vec<[String; 2]>,
     pub wsl: Option<String>,
+    pub remote_command: Option<String>,
 }
 
 impl Global for OpenListener {}
@@ -266,6 +274,7 @@ fn connect_to_cli(
 pub async fn open_paths_with_positions(
     path_positions: &[PathWithPosition],
     diff_paths: &[[String; 2]],
+    merge_paths: Option<&[String; 4]>,
     app_state: Arc<AppState>,
     open_options: workspace::OpenOptions,
     cx: &mut AsyncApp,
@@ -274,6 +283,7 @@ pub async fn open_paths_with_positions(
     Vec<Option<Result<Box<dyn ItemHandle>>>>,
 )> {
     let mut caret_positions = HashMap::default();
+    let fs = app_state.fs.clone();
 
     let paths = path_positions
         .iter()
@@ -305,6 +315,12 @@ pub async fn open_paths_with_positions(
         }
     }
 
+    if let Some(merge_paths) = merge_paths {
+        items.push(Some(
+            open_merge_view(merge_paths, fs.as_ref(), workspace, cx).await,
+        ));
+    }
+
     for (item, path) in items.iter_mut().zip(&paths) {
         if let Some(Err(error)) = item {
             *error = anyhow!("error opening {path:?}: {error}");
@@ -330,6 +346,43 @@ pub async fn open_paths_with_positions(
     Ok((workspace, items))
 }
 
+async fn open_merge_view(
+    merge_paths: &[String; 4],
+    fs: &dyn Fs,
+    workspace: WindowHandle<Workspace>,
+    cx: &mut AsyncApp,
+) -> Result<Box<dyn ItemHandle>> {
+    let canonicalize = async |path: &String| {
+        fs.canonicalize(Path::new(path))
+            .await
+            .with_context(|| format!("error opening {path:?}"))
+    };
+    let [base_path, local_path, remote_path, merged_path] = merge_paths;
+    let base_path = canonicalize(base_path).await?;
+    let local_path = canonicalize(local_path).await?;
+    let remote_path = canonicalize(remote_path).await?;
+    let merged_path = canonicalize(merged_path).await?;
+    let merge_view = workspace
+        .update(cx, |workspace, window, cx| {
+            MergeView::open(
+                base_path,
+                local_path,
+                remote_path,
+                merged_path,
+                workspace,
+                window,
+                cx,
+            )
+        })?
+        .await?;
+    Ok(Box::new(merge_view))
+}
+
+/// Whether a file resolved through `zed --merge` still has unresolved conflicts.
+fn has_conflict_markers(text: &str) -> bool {
+    text.lines().any(|line| line.starts_with("<<<<<<< "))
+}
+
 pub async fn handle_cli_connection(
     (mut requests, responses): (mpsc::Receiver<CliRequest>, IpcSender<CliResponse>),
     app_state: Arc<AppState>,
@@ -341,8 +394,10 @@ pub async fn handle_cli_connection(
                 urls,
                 paths,
                 diff_paths,
+                merge_paths,
                 wait,
                 wsl,
+                remote_command,
                 open_new_workspace,
                 reuse,
                 env,
@@ -355,6 +410,7 @@ pub async fn handle_cli_connection(
                                 urls,
                                 diff_paths,
                                 wsl,
+                                remote_command,
                             },
                             cx,
                         ) {
@@ -379,6 +435,7 @@ pub async fn handle_cli_connection(
                 let open_workspace_result = open_workspaces(
                     paths,
                     diff_paths,
+                    merge_paths,
                     open_new_workspace,
                     reuse,
                     &responses,
@@ -392,6 +449,22 @@ pub async fn handle_cli_connection(
                 let status = if open_workspace_result.is_err() { 1 } else { 0 };
                 responses.send(CliResponse::Exit { status }).log_err();
             }
+            CliRequest::DispatchAction { name, arguments } => {
+                let result = cli_control::dispatch_action(name, arguments, cx).await;
+                cli_control::respond(result, &responses);
+            }
+            CliRequest::Query { query } => {
+                let result = cli_control::query(query, cx).await;
+                cli_control::respond(result, &responses);
+            }
+            CliRequest::SetSelection { path, start, end } => {
+                let result = cli_control::set_selection(path, start, end, cx).await;
+                cli_control::respond(result, &responses);
+            }
+            CliRequest::RunTask { label } => {
+                let result = cli_control::run_task(label, cx).await;
+                cli_control::respond(result, &responses);
+            }
         }
     }
 }
@@ -399,6 +472,7 @@ pub async fn handle_cli_connection(
 async fn open_workspaces(
     paths: Vec<String>,
     diff_paths: Vec<[String; 2]>,
+    merge_paths: Option<[String; 4]>,
     open_new_workspace: Option<bool>,
     reuse: bool,
     responses: &IpcSender<CliResponse>,
@@ -407,7 +481,7 @@ async fn open_workspaces(
     env: Option<collections::HashMap<String, String>>,
     cx: &mut AsyncApp,
 ) -> Result<()> {
-    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() {
+    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() && merge_paths.is_none() {
         // If no paths are provided, restore from previous workspaces unless a new workspace is requested with -n
         if open_new_workspace == Some(true) {
             Vec::new()
@@ -459,6 +533,7 @@ async fn open_workspaces(
                     let workspace_failed_to_open = open_local_workspace(
                         workspace_paths,
                         diff_paths.clone(),
+                        merge_paths.clone(),
                         open_new_workspace,
                         reuse,
                         wait,
@@ -506,6 +581,7 @@ async fn open_workspaces(
 async fn open_local_workspace(
     workspace_paths: Vec<String>,
     diff_paths: Vec<[String; 2]>,
+    merge_paths: Option<[String; 4]>,
     open_new_workspace: Option<bool>,
     reuse: bool,
     wait: bool,
@@ -538,6 +614,7 @@ async fn open_local_workspace(
     match open_paths_with_positions(
         &paths_with_position,
         &diff_paths,
+        merge_paths.as_ref(),
         app_state.clone(),
         workspace::OpenOptions {
             open_new_workspace: effective_open_new_workspace,
@@ -583,8 +660,9 @@ async fn open_local_workspace(
 
             if wait {
                 let background = cx.background_executor().clone();
+                let merging = merge_paths.is_some();
                 let wait = async move {
-                    if paths_with_position.is_empty() && diff_paths.is_empty() {
+                    if paths_with_position.is_empty() && diff_paths.is_empty() && !merging {
                         let (done_tx, done_rx) = oneshot::channel();
                         let _subscription = workspace.update(cx, |_, _, cx| {
                             cx.on_release(move |_, _| {
@@ -613,6 +691,20 @@ async fn open_local_workspace(
                         }
                     }
                 }
+
+                // Let git know when the merge was abandoned, so that it keeps the file
+                // marked as conflicted instead of accepting the markers as the resolution.
+                if let Some([.., merged_path]) = &merge_paths
+                    && let Ok(text) = app_state.fs.load(Path::new(merged_path)).await
+                    && has_conflict_markers(&text)
+                {
+                    responses
+                        .send(CliResponse::Stderr {
+                            message: format!("{merged_path} still contains conflict markers"),
+                        })
+                        .log_err();
+                    errored = true;
+                }
             }
         }
         Err(error) => {
@@ -691,6 +783,29 @@ mod tests {
         assert_eq!(request.open_paths, vec!["/"]);
     }
 
+    #[gpui::test]
+    fn test_parse_remote_command(cx: &mut TestAppContext) {
+        let _app_state = init_test(cx);
+        let request = cx.update(|cx| {
+            OpenRequest::parse(
+                RawOpenRequest {
+                    urls: vec!["file:///src/app".into()],
+                    remote_command: Some("kubectl exec -i my-pod -- {cmd}".into()),
+                    ..Default::default()
+                },
+                cx,
+            )
+            .unwrap()
+        });
+        assert_eq!(
+            request.remote_connection.unwrap(),
+            RemoteConnectionOptions::Command(CommandConnectionOptions::new(
+                "kubectl exec -i my-pod -- {cmd}"
+            ))
+        );
+        assert_eq!(request.open_paths, vec!["/src/app"]);
+    }
+
     #[gpui::test]
     async fn test_open_workspace_with_directory(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -753,6 +868,70 @@ mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_open_workspace_with_merge(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                path!("/root"),
+                json!({
+                    "file_BASE.txt": "one\n",
+                    "file_LOCAL.txt": "one (local)\n",
+                    "file_REMOTE.txt": "one (remote)\n",
+                    "file.txt": "<<<<<<< HEAD\none (local)\n=======\none (remote)\n>>>>>>> feature\n",
+                }),
+            )
+            .await;
+
+        let (response_tx, _) = ipc::channel::<CliResponse>().unwrap();
+        let merge_paths = [
+            path!("/root/file_BASE.txt").to_owned(),
+            path!("/root/file_LOCAL.txt").to_owned(),
+            path!("/root/file_REMOTE.txt").to_owned(),
+            path!("/root/file.txt").to_owned(),
+        ];
+        let errored = cx
+            .spawn({
+                let app_state = app_state.clone();
+                |mut cx| async move {
+                    open_local_workspace(
+                        vec![],
+                        vec![],
+                        Some(merge_paths),
+                        None,
+                        false,
+                        false,
+                        &response_tx,
+                        None,
+                        &app_state,
+                        &mut cx,
+                    )
+                    .await
+                }
+            })
+            .await;
+        assert!(!errored);
+
+        assert_eq!(cx.windows().len(), 1);
+        let workspace = cx.windows()[0].downcast::<Workspace>().unwrap();
+        workspace
+            .update(cx, |workspace, _, cx| {
+                assert!(workspace.active_item_as::<MergeView>(cx).is_some());
+            })
+            .unwrap();
+
+        let merged = app_state
+            .fs
+            .load(Path::new(path!("/root/file.txt")))
+            .await
+            .unwrap();
+        assert!(has_conflict_markers(&merged));
+        assert!(!has_conflict_markers("one (local)\none (remote)\n"));
+    }
+
     #[gpui::test]
     async fn test_open_workspace_with_nonexistent_files(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -817,6 +996,7 @@ mod tests {
                 open_local_workspace(
                     workspace_paths,
                     vec![],
+                    None,
                     open_new_workspace,
                     false,
                     false,
@@ -891,6 +1071,7 @@ mod tests {
                         workspace_paths,
                         vec![],
                         None,
+                        None,
                         false,
                         false,
                         &response_tx,
@@ -914,6 +1095,7 @@ mod tests {
                     open_local_workspace(
                         workspace_paths_reuse,
                         vec![],
+                        None,
                         None, // open_new_workspace will be overridden by reuse logic
                         true, // reuse = true
                         false,
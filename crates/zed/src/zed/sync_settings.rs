@@ -0,0 +1,243 @@
+//! The commands for syncing user configuration through the folder set in
+//! the `settings_sync` settings.
+
+use anyhow::Result;
+use collections::BTreeSet;
+use extension_host::ExtensionStore;
+use git_ui::file_diff_view::FileDiffView;
+use gpui::{App, Context, PromptLevel, Task, Window};
+use settings::Settings as _;
+use settings_sync::{
+    EXTENSIONS_FILE, FileStatus, Pull, Push, SettingsSyncSettings, SyncFolder, describe_changes,
+};
+use std::sync::Arc;
+use util::ResultExt as _;
+use workspace::{
+    Toast, Workspace,
+    notifications::{DetachAndPromptErr as _, NotificationId},
+};
+
+struct SettingsSyncNotification;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &Push, window, cx| {
+            push(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &Pull, window, cx| {
+            pull(workspace, window, cx);
+        });
+    })
+    .detach();
+}
+
+fn push(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(open_folder) = open_sync_folder(workspace, cx) else {
+        return;
+    };
+    let installed_extensions = installed_extensions(cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let folder = open_folder.await?;
+        folder.pull_repository(cx).await?;
+        let status = folder.status(installed_extensions).await?;
+
+        let conflicts = status.paths(FileStatus::Conflict).collect::<Vec<_>>();
+        anyhow::ensure!(
+            conflicts.is_empty(),
+            "{} changed both on this machine and in {}. Pull to review the changes first.",
+            conflicts.join(", "),
+            folder.path().display()
+        );
+        let has_incoming_changes = status.paths(FileStatus::Incoming).next().is_some();
+
+        let pushed = folder.push(status, cx).await?;
+        let mut message = match pushed.len() {
+            0 => "There are no settings changes to push.".to_string(),
+            1 => format!("Pushed {}.", pushed[0]),
+            count => format!("Pushed {count} files."),
+        };
+        if has_incoming_changes {
+            message.push_str(" There are changes from other machines to pull.");
+        }
+        workspace.update(cx, |workspace, cx| show_toast(workspace, message, cx))
+    })
+    .detach_and_prompt_err("Failed to push settings", window, cx, |_, _, _| None);
+}
+
+fn pull(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(open_folder) = open_sync_folder(workspace, cx) else {
+        return;
+    };
+    let installed_extensions = installed_extensions(cx);
+    let config_dir = paths::config_dir().clone();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let folder = open_folder.await?;
+        folder.pull_repository(cx).await?;
+        let status = folder.status(installed_extensions).await?;
+
+        let incoming = status
+            .paths(FileStatus::Incoming)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let conflicts = status
+            .paths(FileStatus::Conflict)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if incoming.is_empty() && conflicts.is_empty() {
+            return workspace.update(cx, |workspace, cx| {
+                show_toast(workspace, "Settings are up to date.".to_string(), cx)
+            });
+        }
+
+        // Show how each file that exists on both sides would change, before
+        // asking whether to apply the changes.
+        for path in incoming.iter().chain(&conflicts) {
+            if path == EXTENSIONS_FILE
+                || status.local.get(path).is_none()
+                || status.remote.get(path).is_none()
+            {
+                continue;
+            }
+            let open_diff = workspace.update_in(cx, |workspace, window, cx| {
+                FileDiffView::open(
+                    config_dir.join(path),
+                    folder.path().join(path),
+                    workspace,
+                    window,
+                    cx,
+                )
+            })?;
+            open_diff.await.log_err();
+        }
+
+        let mut options = Vec::new();
+        if !incoming.is_empty() {
+            let label = if conflicts.is_empty() {
+                "Apply"
+            } else {
+                "Apply Non-Conflicting"
+            };
+            options.push((label, incoming.clone()));
+        }
+        if !conflicts.is_empty() {
+            let all_paths = incoming.iter().chain(&conflicts).cloned().collect();
+            options.push(("Apply All, Overwriting Local Changes", all_paths));
+        }
+        let answers = options
+            .iter()
+            .map(|(label, _)| *label)
+            .chain(["Cancel"])
+            .collect::<Vec<_>>();
+
+        let message = format!("Apply changes from {}?", folder.path().display());
+        let detail = describe_changes(&status, &incoming, &conflicts);
+        let answer = cx
+            .update(|window, cx| {
+                window.prompt(PromptLevel::Info, &message, Some(&detail), &answers, cx)
+            })?
+            .await?;
+        let Some((_, paths)) = options.into_iter().nth(answer) else {
+            return Ok(());
+        };
+
+        let extensions = paths
+            .iter()
+            .any(|path| path == EXTENSIONS_FILE)
+            .then(|| status.remote.extensions())
+            .flatten();
+        folder.apply(status, &paths).await?;
+        if let Some(extensions) = extensions {
+            cx.update(|_, cx| sync_extensions(&extensions, cx))?;
+        }
+
+        let message = match paths.len() {
+            1 => format!("Applied {}.", paths[0]),
+            count => format!("Applied {count} files."),
+        };
+        workspace.update(cx, |workspace, cx| show_toast(workspace, message, cx))
+    })
+    .detach_and_prompt_err("Failed to pull settings", window, cx, |_, _, _| None);
+}
+
+fn open_sync_folder(
+    workspace: &mut Workspace,
+    cx: &mut Context<Workspace>,
+) -> Option<Task<Result<SyncFolder>>> {
+    let settings = SettingsSyncSettings::get_global(cx);
+    let Some(path) = settings.path.clone() else {
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<SettingsSyncNotification>(),
+                "Set `settings_sync.path` to choose the folder to sync settings with.",
+            )
+            .on_click("Open Settings", |window, cx| {
+                window.dispatch_action(Box::new(zed_actions::OpenSettingsFile), cx);
+            }),
+            cx,
+        );
+        return None;
+    };
+    let repository = settings.repository.clone();
+    let fs = workspace.app_state().fs.clone();
+    Some(cx.background_spawn(async move {
+        SyncFolder::open(
+            fs,
+            path,
+            repository.as_deref(),
+            paths::config_dir().clone(),
+            paths::settings_sync_dir().join("baseline.json"),
+        )
+        .await
+    }))
+}
+
+fn show_toast(workspace: &mut Workspace, message: String, cx: &mut Context<Workspace>) {
+    workspace.show_toast(
+        Toast::new(
+            NotificationId::unique::<SettingsSyncNotification>(),
+            message,
+        )
+        .autohide(),
+        cx,
+    );
+}
+
+/// Returns the ids of the installed extensions, excluding dev extensions.
+fn installed_extensions(cx: &App) -> Option<Vec<String>> {
+    let store = ExtensionStore::try_global(cx)?;
+    Some(
+        store
+            .read(cx)
+            .installed_extensions()
+            .iter()
+            .filter(|(_, entry)| !entry.dev)
+            .map(|(id, _)| id.to_string())
+            .collect(),
+    )
+}
+
+/// Installs and uninstalls extensions so that the installed ones match
+/// `extension_ids`.
+fn sync_extensions(extension_ids: &BTreeSet<String>, cx: &mut App) {
+    let Some(store) = ExtensionStore::try_global(cx) else {
+        return;
+    };
+    store.update(cx, |store, cx| {
+        let installed = store
+            .installed_extensions()
+            .iter()
+            .filter(|(_, entry)| !entry.dev)
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<Arc<str>>>();
+        for id in extension_ids {
+            if !installed.iter().any(|installed_id| **installed_id == **id) {
+                store.install_latest_extension(id.as_str().into(), cx);
+            }
+        }
+        for id in installed {
+            if !extension_ids.contains(id.as_ref()) {
+                store.uninstall_extension(id, cx).detach_and_log_err(cx);
+            }
+        }
+    });
+}
@@ -155,8 +155,10 @@ fn send_args_to_instance(args: &Args) -> anyhow::Result<()> {
             paths,
             urls,
             diff_paths,
+            merge_paths: None,
             wait: false,
             wsl: args.wsl.clone(),
+            remote_command: None,
             open_new_workspace: None,
             reuse: false,
             env: None,
@@ -70,6 +70,10 @@ actions!(
         OpenTelemetryLog,
         /// Opens the performance profiler.
         OpenPerformanceProfiler,
+        /// Opens the list of past crash reports.
+        OpenCrashReports,
+        /// Opens the log viewer, which tails the log with level, scope and regex filters.
+        OpenLogViewer,
     ]
 );
 
@@ -204,7 +208,9 @@ pub mod workspace {
 }
 
 pub mod git {
-    use gpui::actions;
+    use gpui::{Action, actions};
+    use schemars::JsonSchema;
+    use serde::Deserialize;
 
     actions!(
         git,
@@ -228,6 +234,25 @@ pub mod git {
             Worktree
         ]
     );
+
+    /// Searches the history of the active repository for commits whose changes
+    /// add or remove the given text.
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+    #[action(namespace = git)]
+    #[serde(deny_unknown_fields)]
+    pub struct SearchHistory {
+        /// The text to search for. When empty, prompts for it instead.
+        #[serde(default)]
+        pub query: String,
+        /// Whether the query is a regular expression.
+        #[serde(default)]
+        pub regex: bool,
+        #[serde(default)]
+        pub case_sensitive: bool,
+        /// Paths relative to the repository root to limit the search to.
+        #[serde(default)]
+        pub paths: Vec<String>,
+    }
 }
 
 pub mod toast {
@@ -428,6 +453,15 @@ pub struct OpenRemote {
     pub create_new_window: bool,
 }
 
+actions!(
+    projects,
+    [
+        /// Reopens the current local folder inside its dev container, as configured
+        /// by `.devcontainer/devcontainer.json`.
+        ReopenInContainer
+    ]
+);
+
 /// Where to spawn the task in the UI.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
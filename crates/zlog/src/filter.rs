@@ -111,6 +111,42 @@ pub fn refresh_from_settings(settings: &HashMap<String, String>) {
     log::trace!("Log configuration updated");
 }
 
+/// Filters records that have already been logged, such as those read back
+/// from the JSON sink, using the same syntax as `ZED_LOG`, e.g.
+/// `warn,project=debug,editor::display_map=trace`.
+#[derive(Debug)]
+pub struct RecordFilter {
+    level: log::LevelFilter,
+    scope_map: ScopeMap,
+}
+
+impl RecordFilter {
+    pub fn parse(filter: &str) -> anyhow::Result<Self> {
+        let env_filter = env_config::parse(filter)?;
+        Ok(Self {
+            level: env_filter.level_global.unwrap_or(log::LevelFilter::max()),
+            scope_map: ScopeMap::new_from_settings_and_env(
+                &HashMap::default(),
+                Some(&env_filter),
+                &[],
+            ),
+        })
+    }
+
+    pub fn is_enabled(
+        &self,
+        scope: &ScopeRef<'_>,
+        module_path: Option<&str>,
+        level: log::Level,
+    ) -> bool {
+        match self.scope_map.is_enabled(scope, module_path, level) {
+            EnabledStatus::Enabled => true,
+            EnabledStatus::Disabled => false,
+            EnabledStatus::NotConfigured => level <= self.level,
+        }
+    }
+}
+
 fn level_filter_from_str(level_str: &str) -> Option<log::LevelFilter> {
     use log::LevelFilter::*;
     let level = match level_str.to_ascii_lowercase().as_str() {
@@ -840,4 +876,29 @@ mod tests {
             "crate::submodule should be disabled by disabling `crate` filter"
         );
     }
+
+    #[test]
+    fn test_record_filter() {
+        use log::Level;
+        let filter = RecordFilter::parse("warn,project=debug,editor::display_map=trace").unwrap();
+        assert!(filter.is_enabled(&scope_new(&["workspace"]), None, Level::Warn));
+        assert!(!filter.is_enabled(&scope_new(&["workspace"]), None, Level::Info));
+        assert!(filter.is_enabled(&scope_new(&["project", "git"]), None, Level::Debug));
+        assert!(!filter.is_enabled(&scope_new(&["project"]), None, Level::Trace));
+        assert!(filter.is_enabled(
+            &scope_new(&["editor"]),
+            Some("editor::display_map"),
+            Level::Trace
+        ));
+        assert!(!filter.is_enabled(
+            &scope_new(&["editor"]),
+            Some("editor::element"),
+            Level::Info
+        ));
+
+        let filter = RecordFilter::parse("").unwrap();
+        assert!(filter.is_enabled(&scope_new(&["workspace"]), None, Level::Trace));
+
+        assert!(RecordFilter::parse("info,warn").is_err());
+    }
 }
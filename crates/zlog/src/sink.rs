@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::{
     fs,
     io::{self, Write},
@@ -8,7 +9,7 @@ use std::{
     },
 };
 
-use crate::{SCOPE_STRING_SEP_CHAR, ScopeRef};
+use crate::{SCOPE_DEPTH_MAX, SCOPE_STRING_SEP_CHAR, ScopeRef, private};
 
 // ANSI color escape codes for log levels
 const ANSI_RESET: &str = "\x1b[0m";
@@ -19,18 +20,16 @@ const ANSI_GREEN: &str = "\x1b[32m";
 const ANSI_BLUE: &str = "\x1b[34m";
 const ANSI_MAGENTA: &str = "\x1b[35m";
 
-/// Is Some(file) if file output is enabled.
-static ENABLED_SINKS_FILE: Mutex<Option<std::fs::File>> = Mutex::new(None);
-static SINK_FILE_PATH: OnceLock<&'static PathBuf> = OnceLock::new();
-static SINK_FILE_PATH_ROTATE: OnceLock<&'static PathBuf> = OnceLock::new();
+/// Plain text file output.
+static SINK_FILE: FileSink = FileSink::new();
+/// JSON lines file output, one [`JsonRecord`] per line.
+static SINK_JSON_FILE: FileSink = FileSink::new();
 
 // NB: Since this can be accessed in tests, we probably should stick to atomics here.
 /// Whether stdout output is enabled.
 static ENABLED_SINKS_STDOUT: AtomicBool = AtomicBool::new(false);
 /// Whether stderr output is enabled.
 static ENABLED_SINKS_STDERR: AtomicBool = AtomicBool::new(false);
-/// Atomic counter for the size of the log file in bytes.
-static SINK_FILE_SIZE_BYTES: AtomicU64 = AtomicU64::new(0);
 /// Maximum size of the log file before it will be rotated, in bytes.
 const SINK_FILE_SIZE_BYTES_MAX: u64 = 1024 * 1024; // 1 MB
 
@@ -42,6 +41,62 @@ pub struct Record<'a> {
     pub line: Option<u32>,
 }
 
+/// A record as written by the JSON sink.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonRecord {
+    /// RFC 3339 timestamp in local time, with millisecond precision.
+    pub timestamp: String,
+    pub level: log::Level,
+    /// The scope names, starting with the crate name.
+    pub scope: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// The thread's name, or its id if it's unnamed.
+    pub thread: String,
+    pub message: String,
+}
+
+impl JsonRecord {
+    fn new(record: &Record) -> Self {
+        let mut scope = record
+            .scope
+            .iter()
+            .take_while(|scope| !scope.is_empty())
+            .map(|scope| scope.to_string())
+            .collect::<Vec<_>>();
+        // Records from the `log` crate are scoped by their module path.
+        if let Some(first) = scope.first_mut()
+            && first.contains("::")
+        {
+            *first = private::extract_crate_name_from_module_path(first).to_string();
+        }
+        let thread = std::thread::current();
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            level: record.level,
+            scope,
+            module_path: record.module_path.map(ToString::to_string),
+            line: record.line,
+            thread: match thread.name() {
+                Some(name) => name.to_string(),
+                None => format!("{:?}", thread.id()),
+            },
+            message: record.message.to_string(),
+        }
+    }
+
+    /// Returns the scope in the form expected by [`crate::filter::RecordFilter`].
+    pub fn scope_ref(&self) -> ScopeRef<'_> {
+        let mut scope = [""; SCOPE_DEPTH_MAX];
+        for (slot, name) in scope.iter_mut().zip(&self.scope) {
+            *slot = name.as_str();
+        }
+        scope
+    }
+}
+
 pub fn init_output_stdout() {
     // Use atomics here instead of just a `static mut`, since in the context
     // of tests these accesses can be multi-threaded.
@@ -56,34 +111,22 @@ pub fn init_output_file(
     path: &'static PathBuf,
     path_rotate: Option<&'static PathBuf>,
 ) -> io::Result<()> {
-    let mut file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)?;
-
-    SINK_FILE_PATH
-        .set(path)
-        .expect("Init file output should only be called once");
-    if let Some(path_rotate) = path_rotate {
-        SINK_FILE_PATH_ROTATE
-            .set(path_rotate)
-            .expect("Init file output should only be called once");
-    }
-
-    let mut enabled_sinks_file = ENABLED_SINKS_FILE
-        .try_lock()
-        .expect("Log file lock is available during init");
-
-    let size_bytes = file.metadata().map_or(0, |metadata| metadata.len());
-    if size_bytes >= SINK_FILE_SIZE_BYTES_MAX {
-        rotate_log_file(&mut file, Some(path), path_rotate, &SINK_FILE_SIZE_BYTES);
-    } else {
-        SINK_FILE_SIZE_BYTES.store(size_bytes, Ordering::Release);
-    }
+    SINK_FILE.init(path, path_rotate)
+}
 
-    *enabled_sinks_file = Some(file);
+/// Enables writing records to `path` as JSON lines, for tools that want to
+/// filter them by scope or level. Can be called again after
+/// [`close_output_json_file`], with the same paths.
+pub fn init_output_json_file(
+    path: &'static PathBuf,
+    path_rotate: Option<&'static PathBuf>,
+) -> io::Result<()> {
+    SINK_JSON_FILE.init(path, path_rotate)
+}
 
-    Ok(())
+/// Stops writing records as JSON lines.
+pub fn close_output_json_file() {
+    SINK_JSON_FILE.close();
 }
 
 const LEVEL_OUTPUT_STRINGS: [&str; 6] = [
@@ -144,11 +187,102 @@ pub fn submit(mut record: Record) {
             record.message
         );
     }
-    let mut file = ENABLED_SINKS_FILE.lock().unwrap_or_else(|handle| {
-        ENABLED_SINKS_FILE.clear_poison();
-        handle.into_inner()
+    SINK_FILE.write(|writer| {
+        writeln!(
+            writer,
+            "{} {} {} {}",
+            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
+            LEVEL_OUTPUT_STRINGS[record.level as usize],
+            SourceFmt {
+                scope: record.scope,
+                module_path: record.module_path,
+                line: record.line,
+                ansi: false,
+            },
+            record.message
+        )
+    });
+    SINK_JSON_FILE.write(|writer| {
+        let mut line = serde_json::to_vec(&JsonRecord::new(&record))?;
+        line.push(b'\n');
+        writer.write_all(&line)
     });
-    if let Some(file) = file.as_mut() {
+}
+
+pub fn flush() {
+    if ENABLED_SINKS_STDOUT.load(Ordering::Acquire) {
+        _ = std::io::stdout().lock().flush();
+    }
+    SINK_FILE.flush();
+    SINK_JSON_FILE.flush();
+}
+
+/// A log file that is rotated once it grows past [`SINK_FILE_SIZE_BYTES_MAX`].
+struct FileSink {
+    /// Is Some(file) if this output is enabled.
+    file: Mutex<Option<fs::File>>,
+    path: OnceLock<&'static PathBuf>,
+    path_rotate: OnceLock<&'static PathBuf>,
+    /// Atomic counter for the size of the file in bytes.
+    size_bytes: AtomicU64,
+}
+
+impl FileSink {
+    const fn new() -> Self {
+        Self {
+            file: Mutex::new(None),
+            path: OnceLock::new(),
+            path_rotate: OnceLock::new(),
+            size_bytes: AtomicU64::new(0),
+        }
+    }
+
+    fn init(
+        &self,
+        path: &'static PathBuf,
+        path_rotate: Option<&'static PathBuf>,
+    ) -> io::Result<()> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        let initialized_path = *self.path.get_or_init(|| path);
+        debug_assert_eq!(initialized_path, path, "log file paths can't change");
+        if let Some(path_rotate) = path_rotate {
+            let initialized_path_rotate = *self.path_rotate.get_or_init(|| path_rotate);
+            debug_assert_eq!(
+                initialized_path_rotate, path_rotate,
+                "log file paths can't change"
+            );
+        }
+
+        let mut enabled_file = self.lock();
+
+        let size_bytes = file.metadata().map_or(0, |metadata| metadata.len());
+        if size_bytes >= SINK_FILE_SIZE_BYTES_MAX {
+            rotate_log_file(&mut file, Some(path), path_rotate, &self.size_bytes);
+        } else {
+            self.size_bytes.store(size_bytes, Ordering::Release);
+        }
+
+        *enabled_file = Some(file);
+
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<fs::File>> {
+        self.file.lock().unwrap_or_else(|handle| {
+            self.file.clear_poison();
+            handle.into_inner()
+        })
+    }
+
+    fn write(&self, write: impl FnOnce(&mut dyn io::Write) -> io::Result<()>) {
+        let mut file = self.lock();
+        let Some(file) = file.as_mut() else {
+            return;
+        };
         struct SizedWriter<'a> {
             file: &'a mut std::fs::File,
             written: u64,
@@ -166,44 +300,34 @@ pub fn submit(mut record: Record) {
         }
         let file_size_bytes = {
             let mut writer = SizedWriter { file, written: 0 };
-            _ = writeln!(
-                &mut writer,
-                "{} {} {} {}",
-                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
-                LEVEL_OUTPUT_STRINGS[record.level as usize],
-                SourceFmt {
-                    scope: record.scope,
-                    module_path: record.module_path,
-                    line: record.line,
-                    ansi: false,
-                },
-                record.message
-            );
-            SINK_FILE_SIZE_BYTES.fetch_add(writer.written, Ordering::AcqRel) + writer.written
+            _ = write(&mut writer);
+            self.size_bytes.fetch_add(writer.written, Ordering::AcqRel) + writer.written
         };
         if file_size_bytes > SINK_FILE_SIZE_BYTES_MAX {
             rotate_log_file(
                 file,
-                SINK_FILE_PATH.get(),
-                SINK_FILE_PATH_ROTATE.get(),
-                &SINK_FILE_SIZE_BYTES,
+                self.path.get(),
+                self.path_rotate.get(),
+                &self.size_bytes,
             );
         }
     }
-}
 
-pub fn flush() {
-    if ENABLED_SINKS_STDOUT.load(Ordering::Acquire) {
-        _ = std::io::stdout().lock().flush();
+    fn flush(&self) {
+        if let Some(file) = self.lock().as_mut()
+            && let Err(err) = file.flush()
+        {
+            eprintln!("Failed to flush log file: {}", err);
+        }
     }
-    let mut file = ENABLED_SINKS_FILE.lock().unwrap_or_else(|handle| {
-        ENABLED_SINKS_FILE.clear_poison();
-        handle.into_inner()
-    });
-    if let Some(file) = file.as_mut()
-        && let Err(err) = file.flush()
-    {
-        eprintln!("Failed to flush log file: {}", err);
+
+    /// Disables this output until it is initialized again.
+    fn close(&self) {
+        if let Some(mut file) = self.lock().take()
+            && let Err(err) = file.flush()
+        {
+            eprintln!("Failed to flush log file: {}", err);
+        }
     }
 }
 
@@ -312,6 +436,35 @@ mod tests {
         assert_eq!(size.load(Ordering::Acquire), 0);
     }
 
+    #[test]
+    fn test_json_record() {
+        let record = JsonRecord::new(&Record {
+            scope: crate::private::scope_new(&["project::git_store"]),
+            level: log::Level::Warn,
+            message: &format_args!("failed to load {}", "HEAD"),
+            module_path: Some("project::git_store"),
+            line: None,
+        });
+        assert_eq!(record.scope, ["project"]);
+        assert_eq!(record.scope_ref(), ["project", "", "", ""]);
+        assert_eq!(record.module_path.as_deref(), Some("project::git_store"));
+        assert_eq!(record.message, "failed to load HEAD");
+        assert!(chrono::DateTime::parse_from_rfc3339(&record.timestamp).is_ok());
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.contains(r#""level":"WARN""#), "{json}");
+        assert_eq!(serde_json::from_str::<JsonRecord>(&json).unwrap(), record);
+
+        let record = JsonRecord::new(&Record {
+            scope: crate::private::scope_new(&["project", "git"]),
+            level: log::Level::Info,
+            message: &format_args!("done"),
+            module_path: Some("project::git_store"),
+            line: Some(12),
+        });
+        assert_eq!(record.scope, ["project", "git"]);
+    }
+
     /// Regression test, ensuring that if log level values change we are made aware
     #[test]
     fn test_log_level_names() {
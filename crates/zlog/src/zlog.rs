@@ -5,7 +5,10 @@ mod env_config;
 pub mod filter;
 pub mod sink;
 
-pub use sink::{flush, init_output_file, init_output_stderr, init_output_stdout};
+pub use sink::{
+    JsonRecord, close_output_json_file, flush, init_output_file, init_output_json_file,
+    init_output_stderr, init_output_stdout,
+};
 
 pub const SCOPE_DEPTH_MAX: usize = 4;
 
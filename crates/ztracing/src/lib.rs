@@ -1,3 +1,5 @@
+pub mod recording;
+
 #[cfg(ztracing)]
 pub use tracing::instrument;
 #[cfg(not(ztracing))]
@@ -6,10 +8,10 @@ pub use ztracing_macro::instrument;
 #[cfg(ztracing)]
 pub fn init() {
     use tracing_subscriber::prelude::*;
-    tracing::subscriber::set_global_default(
-        tracing_subscriber::registry().with(tracing_tracy::TracyLayer::default()),
-    )
-    .expect("setup tracy layer");
+    let registry = tracing_subscriber::registry().with(recording::layer::RecordingLayer);
+    #[cfg(feature = "tracy")]
+    let registry = registry.with(tracing_tracy::TracyLayer::default());
+    tracing::subscriber::set_global_default(registry).expect("setup tracing layers");
 }
 
 #[cfg(not(ztracing))]
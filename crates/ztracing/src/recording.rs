@@ -0,0 +1,307 @@
+//! Records events to a trace that can be opened in Perfetto
+//! (https://ui.perfetto.dev) or `chrome://tracing`, using the Chrome Trace
+//! Event JSON format.
+
+use std::{
+    borrow::Cow,
+    io,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::ThreadId,
+    time::Instant,
+};
+
+use serde_json::json;
+
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+/// Lets spans skip locking [`RECORDING`] when nothing is being recorded.
+static IS_RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// Starts recording spans, returning false if a recording was already in
+/// progress.
+pub fn start_recording() -> bool {
+    let mut recording = RECORDING.lock().unwrap_or_else(|error| error.into_inner());
+    if recording.is_some() {
+        return false;
+    }
+    *recording = Some(Recording::new(Instant::now()));
+    IS_RECORDING.store(true, Ordering::Release);
+    true
+}
+
+/// Stops recording, returning the spans recorded since [`start_recording`].
+pub fn stop_recording() -> Option<Recording> {
+    let mut recording = RECORDING.lock().unwrap_or_else(|error| error.into_inner());
+    IS_RECORDING.store(false, Ordering::Release);
+    recording.take()
+}
+
+pub fn is_recording() -> bool {
+    IS_RECORDING.load(Ordering::Acquire)
+}
+
+/// Runs `f` on the recording in progress, if any.
+pub fn with_recording(f: impl FnOnce(&mut Recording)) {
+    if !is_recording() {
+        return;
+    }
+    let mut recording = RECORDING.lock().unwrap_or_else(|error| error.into_inner());
+    if let Some(recording) = recording.as_mut() {
+        f(recording);
+    }
+}
+
+/// Events recorded over a period of time, grouped into one track per thread.
+pub struct Recording {
+    start: Instant,
+    threads: Vec<Track>,
+    events: Vec<Event>,
+}
+
+struct Track {
+    thread_id: ThreadId,
+    name: Option<String>,
+}
+
+struct Event {
+    name: Cow<'static, str>,
+    category: &'static str,
+    track: usize,
+    start: Instant,
+    end: Instant,
+}
+
+impl Recording {
+    pub fn new(start: Instant) -> Self {
+        Self {
+            start,
+            threads: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn start(&self) -> Instant {
+        self.start
+    }
+
+    /// Records something that happened on `thread` between `start` and `end`.
+    /// Events that ended before the recording started are ignored.
+    pub fn add_event(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        category: &'static str,
+        thread_id: ThreadId,
+        start: Instant,
+        end: Instant,
+    ) {
+        if end < self.start {
+            return;
+        }
+        let track = self.track(thread_id);
+        self.events.push(Event {
+            name: name.into(),
+            category,
+            track,
+            start: start.max(self.start),
+            end,
+        });
+    }
+
+    /// Names the track showing a thread's events, if it hasn't been named yet.
+    pub fn set_thread_name(&mut self, thread_id: ThreadId, name: &str) {
+        let track = self.track(thread_id);
+        self.threads[track]
+            .name
+            .get_or_insert_with(|| name.to_string());
+    }
+
+    fn track(&mut self, thread_id: ThreadId) -> usize {
+        match self
+            .threads
+            .iter()
+            .position(|track| track.thread_id == thread_id)
+        {
+            Some(track) => track,
+            None => {
+                self.threads.push(Track {
+                    thread_id,
+                    name: None,
+                });
+                self.threads.len() - 1
+            }
+        }
+    }
+
+    /// Writes the recording as a Chrome Trace Event JSON object.
+    pub fn write_chrome_trace(&self, writer: impl io::Write) -> io::Result<()> {
+        let micros = |instant: Instant| instant.duration_since(self.start).as_secs_f64() * 1e6;
+
+        let mut events = self.events.iter().collect::<Vec<_>>();
+        // Sorting by start, and then by longest first, keeps nested events
+        // after the events containing them.
+        events.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+        let thread_names = self.threads.iter().enumerate().map(|(track, thread)| {
+            let name = thread
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{:?}", thread.thread_id));
+            json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": 1,
+                "tid": track + 1,
+                "args": { "name": name },
+            })
+        });
+        let events = events.into_iter().map(|event| {
+            json!({
+                "name": event.name,
+                "cat": event.category,
+                "ph": "X",
+                "ts": micros(event.start),
+                "dur": micros(event.end) - micros(event.start),
+                "pid": 1,
+                "tid": event.track + 1,
+            })
+        });
+
+        let trace = json!({
+            "traceEvents": thread_names.chain(events).collect::<Vec<_>>(),
+            "displayTimeUnit": "ms",
+        });
+        serde_json::to_writer(writer, &trace)?;
+        Ok(())
+    }
+}
+
+#[cfg(ztracing)]
+pub(crate) mod layer {
+    use std::{thread, time::Instant};
+    use tracing::{Subscriber, span};
+    use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+    /// Adds entered spans to the current recording.
+    pub struct RecordingLayer;
+
+    struct EnteredAt(Instant);
+
+    impl<S> Layer<S> for RecordingLayer
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+            if !super::is_recording() {
+                return;
+            }
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().replace(EnteredAt(Instant::now()));
+            }
+        }
+
+        fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+            let Some(span) = ctx.span(id) else {
+                return;
+            };
+            let Some(EnteredAt(start)) = span.extensions_mut().remove::<EnteredAt>() else {
+                return;
+            };
+            let end = Instant::now();
+            let thread = thread::current();
+            super::with_recording(|recording| {
+                if let Some(name) = thread.name() {
+                    recording.set_thread_name(thread.id(), name);
+                }
+                recording.add_event(span.name(), "span", thread.id(), start, end);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_write_chrome_trace() {
+        let start = Instant::now();
+        let thread_id = std::thread::current().id();
+        let mut recording = Recording::new(start);
+        recording.set_thread_name(thread_id, "main");
+        recording.add_event(
+            "layout",
+            "span",
+            thread_id,
+            start + Duration::from_micros(150),
+            start + Duration::from_micros(200),
+        );
+        recording.add_event(
+            "frame",
+            "frame",
+            thread_id,
+            start + Duration::from_micros(100),
+            start + Duration::from_micros(300),
+        );
+        // Clamped to the start of the recording.
+        recording.add_event(
+            "startup",
+            "span",
+            thread_id,
+            start - Duration::from_micros(10),
+            start + Duration::from_micros(50),
+        );
+        // Ended before the recording started.
+        recording.add_event(
+            "init",
+            "span",
+            thread_id,
+            start - Duration::from_micros(10),
+            start - Duration::from_micros(5),
+        );
+
+        let mut output = Vec::new();
+        recording.write_chrome_trace(&mut output).unwrap();
+        let trace: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let summary = events
+            .iter()
+            .map(|event| {
+                (
+                    event["ph"].as_str().unwrap(),
+                    event["name"].as_str().unwrap(),
+                    event["ts"].as_f64().map(f64::round),
+                    event["dur"].as_f64().map(f64::round),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                ("M", "thread_name", None, None),
+                ("X", "startup", Some(0.), Some(50.)),
+                ("X", "frame", Some(100.), Some(200.)),
+                ("X", "layout", Some(150.), Some(50.)),
+            ]
+        );
+        assert_eq!(events[0]["args"]["name"], "main");
+        assert!(events.iter().all(|event| event["tid"] == 1));
+    }
+
+    #[test]
+    fn test_start_and_stop_recording() {
+        assert!(start_recording());
+        assert!(!start_recording());
+        assert!(is_recording());
+        with_recording(|recording| {
+            let now = Instant::now();
+            recording.add_event("poll", "task", std::thread::current().id(), now, now);
+        });
+        let recording = stop_recording().unwrap();
+        assert_eq!(recording.events.len(), 1);
+        assert!(!is_recording());
+        assert!(stop_recording().is_none());
+    }
+}